@@ -1,6 +1,6 @@
 //! CLI config for compactor-related commands
 
-use std::num::NonZeroUsize;
+use std::{num::NonZeroUsize, time::Duration};
 
 use crate::{gossip::GossipConfig, memory_size::MemorySize};
 
@@ -269,4 +269,34 @@ pub struct CompactorConfig {
         action
     )]
     pub max_partition_fetch_queries_per_second: Option<usize>,
+
+    /// Interval between passes materialising the namespace rollup policies.
+    ///
+    /// Each pass rolls up the partitions of the source tables of all rollup
+    /// policies that aged past their age threshold, and whose rollup is
+    /// missing or out of date. If not provided, rollups are not
+    /// materialised. Enable this on a single compactor only.
+    ///
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long = "compaction-rollup-poll-interval",
+        env = "INFLUXDB_IOX_COMPACTION_ROLLUP_POLL_INTERVAL",
+        value_parser = humantime::parse_duration,
+    )]
+    pub rollup_poll_interval: Option<Duration>,
+
+    /// Interval between passes rewriting partitions with a sort key ordered
+    /// by tag cardinality.
+    ///
+    /// Each pass considers the fully compacted partitions that received new
+    /// files since the previous pass. If not provided, partitions are only
+    /// rewritten when requested through the compaction gRPC service.
+    ///
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long = "compaction-sort-key-rewrite-poll-interval",
+        env = "INFLUXDB_IOX_COMPACTION_SORT_KEY_REWRITE_POLL_INTERVAL",
+        value_parser = humantime::parse_duration,
+    )]
+    pub sort_key_rewrite_poll_interval: Option<Duration>,
 }
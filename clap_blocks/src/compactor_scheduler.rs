@@ -1,5 +1,7 @@
 //! Compactor-Scheduler-related configs.
 
+use std::time::Duration;
+
 /// Compaction Scheduler type.
 #[derive(Debug, Default, Clone, Copy, PartialEq, clap::ValueEnum)]
 pub enum CompactorSchedulerType {
@@ -90,6 +92,33 @@ pub struct PartitionSourceConfigForLocalScheduler {
     pub ignore_partition_skip_marker: bool,
 }
 
+/// CLI config for the compactor's connection to a remote scheduler.
+#[derive(Debug, Clone, Default, clap::Parser)]
+pub struct ConfigForRemoteScheduler {
+    /// gRPC address of the compactor scheduler service.
+    ///
+    /// Required when using the remote scheduler.
+    #[clap(
+        long = "compactor-scheduler-address",
+        env = "INFLUXDB_IOX_COMPACTOR_SCHEDULER_ADDRESS",
+        required_if_eq("compactor_scheduler_type", "remote"),
+        action
+    )]
+    pub scheduler_address: Option<String>,
+
+    /// Identifier this compactor registers with the compactor scheduler
+    /// service.
+    ///
+    /// Must be unique amongst the compactors using the same scheduler. If not
+    /// provided, the host name is used.
+    #[clap(
+        long = "compactor-scheduler-compactor-id",
+        env = "INFLUXDB_IOX_COMPACTOR_SCHEDULER_COMPACTOR_ID",
+        action
+    )]
+    pub compactor_id: Option<String>,
+}
+
 /// CLI config for compactor scheduler.
 #[derive(Debug, Clone, Default, clap::Parser)]
 pub struct CompactorSchedulerConfig {
@@ -110,6 +139,64 @@ pub struct CompactorSchedulerConfig {
     /// Shard config used by the local scheduler.
     #[clap(flatten)]
     pub shard_config: ShardConfigForLocalScheduler,
+
+    /// Connection config used by the remote scheduler.
+    #[clap(flatten)]
+    pub remote_config: ConfigForRemoteScheduler,
+
+    /// Prioritise compacting the partitions that are most expensive to
+    /// query, as reported by the queriers over gossip, with the read costs
+    /// decaying with this half-life.
+    ///
+    /// Requires gossip to be enabled on both the compactors and queriers.
+    /// Only supported by the local scheduler: setting it with the remote
+    /// scheduler is a configuration error. If not provided, partitions are
+    /// not prioritised.
+    ///
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long = "compaction-query-stats-half-life",
+        env = "INFLUXDB_IOX_COMPACTION_QUERY_STATS_HALF_LIFE",
+        value_parser = humantime::parse_duration,
+    )]
+    pub query_stats_half_life: Option<Duration>,
+
+    /// Share the compactors fairly between namespaces, proportionally to
+    /// their compaction weight, rather than compacting partitions in the
+    /// order they are found.
+    ///
+    /// Prevents a namespace with a large compaction backlog from starving the
+    /// others. Only used by the local scheduler.
+    #[clap(
+        long = "compaction-fair-share",
+        env = "INFLUXDB_IOX_COMPACTION_FAIR_SHARE",
+        action
+    )]
+    pub fair_share: bool,
+}
+
+/// CLI config for the standalone compactor scheduler service.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct CompactorSchedulerServerConfig {
+    /// Partition source config used to find compaction jobs.
+    #[clap(flatten)]
+    pub partition_source_config: PartitionSourceConfigForLocalScheduler,
+
+    /// Duration for which a compaction job is leased to a compactor.
+    ///
+    /// Compactors renew the leases of the jobs they are working on with
+    /// periodic heartbeats. A job whose lease is not renewed within this
+    /// duration is reassigned to another compactor, and a compactor that does
+    /// not contact the scheduler within this duration is deregistered.
+    ///
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long = "compactor-scheduler-lease-duration",
+        env = "INFLUXDB_IOX_COMPACTOR_SCHEDULER_LEASE_DURATION",
+        default_value = "60s",
+        value_parser = humantime::parse_duration,
+    )]
+    pub lease_duration: Duration,
 }
 
 #[cfg(test)]
@@ -141,6 +228,35 @@ mod tests {
         );
     }
 
+    #[test]
+    fn remote_requires_scheduler_address() {
+        let error = CompactorSchedulerConfig::try_parse_from([
+            "my_binary",
+            "--compactor-scheduler",
+            "remote",
+        ])
+        .unwrap_err()
+        .to_string();
+        assert_contains!(&error, "--compactor-scheduler-address <SCHEDULER_ADDRESS>");
+
+        let config = CompactorSchedulerConfig::try_parse_from([
+            "my_binary",
+            "--compactor-scheduler",
+            "remote",
+            "--compactor-scheduler-address",
+            "http://scheduler:8082",
+        ])
+        .unwrap();
+        assert_eq!(
+            config.compactor_scheduler_type,
+            CompactorSchedulerType::Remote
+        );
+        assert_eq!(
+            config.remote_config.scheduler_address.as_deref(),
+            Some("http://scheduler:8082")
+        );
+    }
+
     #[test]
     fn any_other_scheduler_type_string_is_invalid() {
         let error = CompactorSchedulerConfig::try_parse_from([
@@ -82,4 +82,15 @@ pub struct GarbageCollectorConfig {
         env = "INFLUXDB_IOX_GC_RETENTION_SLEEP_INTERVAL_MINUTES"
     )]
     pub retention_sleep_interval_minutes: u64,
+
+    /// Number of minutes to sleep between iterations of the cold tier mover, which moves parquet
+    /// files older than the cold tier age of their namespace to the cold object store.
+    /// Only runs if a cold object store is configured.
+    /// Defaults to 60 minutes.
+    #[clap(
+        long,
+        default_value_t = 60,
+        env = "INFLUXDB_IOX_GC_TIERING_SLEEP_INTERVAL_MINUTES"
+    )]
+    pub tiering_sleep_interval_minutes: u64,
 }
@@ -2,7 +2,7 @@
 
 use std::{num::NonZeroUsize, path::PathBuf};
 
-use crate::gossip::GossipConfig;
+use crate::{gossip::GossipConfig, wal_encryption::WalEncryptionConfig};
 
 /// CLI config for the ingester using the RPC write path
 #[derive(Debug, Clone, clap::Parser)]
@@ -35,6 +35,84 @@ pub struct IngesterConfig {
     )]
     pub wal_rotation_period_seconds: u64,
 
+    /// The durability guarantee provided by the WAL before a write is
+    /// acknowledged.
+    ///
+    /// "per-write" fsyncs each write individually as soon as possible.
+    ///
+    /// "group-commit" commits the writes received within a window of time (see
+    /// `--wal-group-commit-max-latency-ms` and
+    /// `--wal-group-commit-max-batch-bytes`) with a single fsync, trading write
+    /// latency for throughput.
+    ///
+    /// "unsafe" acknowledges writes without an fsync, and may lose
+    /// acknowledged writes if the host crashes. Only suitable for test
+    /// clusters.
+    #[clap(
+        long = "wal-durability-mode",
+        env = "INFLUXDB_IOX_WAL_DURABILITY_MODE",
+        default_value = "group-commit",
+        value_enum
+    )]
+    pub wal_durability_mode: WalDurabilityMode,
+
+    /// The maximum number of milliseconds a write is buffered for before it is
+    /// committed to the WAL when using the "group-commit" durability mode.
+    #[clap(
+        long = "wal-group-commit-max-latency-ms",
+        env = "INFLUXDB_IOX_WAL_GROUP_COMMIT_MAX_LATENCY_MS",
+        default_value = "10",
+        value_parser = clap::value_parser!(u64).range(1..),
+    )]
+    pub wal_group_commit_max_latency_ms: u64,
+
+    /// The estimated number of bytes of buffered writes that causes them to
+    /// be committed to the WAL before `--wal-group-commit-max-latency-ms`
+    /// elapses, when using the "group-commit" durability mode.
+    #[clap(
+        long = "wal-group-commit-max-batch-bytes",
+        env = "INFLUXDB_IOX_WAL_GROUP_COMMIT_MAX_BATCH_BYTES",
+        default_value = "4194304", // 4MiB
+        action
+    )]
+    pub wal_group_commit_max_batch_bytes: usize,
+
+    /// WAL encryption config.
+    #[clap(flatten)]
+    pub wal_encryption_config: WalEncryptionConfig,
+
+    /// The object store path prefix to archive closed WAL segment files under
+    /// before they are deleted from local disk. Each ingester instance must
+    /// use its own prefix.
+    ///
+    /// Archived segments can be replayed with `influxdb_iox debug wal
+    /// replay-archive`.
+    ///
+    /// Disabled by default.
+    #[clap(long = "wal-archive-prefix", env = "INFLUXDB_IOX_WAL_ARCHIVE_PREFIX")]
+    pub wal_archive_prefix: Option<String>,
+
+    /// The number of hours archived WAL segment files are retained for after
+    /// the segment was closed, when `--wal-archive-prefix` is set.
+    #[clap(
+        long = "wal-archive-retention-hours",
+        env = "INFLUXDB_IOX_WAL_ARCHIVE_RETENTION_HOURS",
+        default_value = "168", // 7 days
+        action
+    )]
+    pub wal_archive_retention_hours: u64,
+
+    /// The number of tasks applying replayed WAL operations to the buffer
+    /// during startup.
+    ///
+    /// If not specified, defaults to the available parallelism of the system.
+    #[clap(
+        long = "wal-replay-concurrency",
+        env = "INFLUXDB_IOX_WAL_REPLAY_CONCURRENCY",
+        action
+    )]
+    pub wal_replay_concurrency: Option<NonZeroUsize>,
+
     /// Sets how many queries the ingester will handle simultaneously before
     /// rejecting further incoming requests.
     #[clap(
@@ -76,6 +154,40 @@ pub struct IngesterConfig {
     )]
     pub persist_hot_partition_cost: usize,
 
+    /// The maximum number of bytes of memory used by buffered and persisting
+    /// data before the coldest of it is spilled to local disk.
+    ///
+    /// Spilled data remains queryable, and is read back from disk when
+    /// queried or persisted. Persist jobs release their persist queue capacity
+    /// once their data is spilled.
+    ///
+    /// Requires `--spill-directory`. Disabled by default.
+    #[clap(
+        long = "spill-threshold-bytes",
+        env = "INFLUXDB_IOX_SPILL_THRESHOLD_BYTES",
+        requires = "spill_directory"
+    )]
+    pub spill_threshold_bytes: Option<usize>,
+
+    /// The directory spilled data is written to.
+    ///
+    /// This must not be within the WAL directory, and any existing spill files
+    /// within it are removed at startup.
+    #[clap(long = "spill-directory", env = "INFLUXDB_IOX_SPILL_DIRECTORY")]
+    pub spill_directory: Option<PathBuf>,
+
+    /// The maximum number of bytes of spilled data that may be written to the
+    /// spill directory.
+    ///
+    /// Once reached, no further data is spilled until existing spill files
+    /// are removed after persistence.
+    #[clap(
+        long = "spill-max-disk-bytes",
+        env = "INFLUXDB_IOX_SPILL_MAX_DISK_BYTES",
+        default_value = "10737418240"
+    )]
+    pub spill_max_disk_bytes: u64,
+
     /// Limit the number of partitions that may be buffered in a single
     /// namespace (across all tables) at any one time.
     ///
@@ -85,4 +197,73 @@ pub struct IngesterConfig {
         env = "INFLUXDB_IOX_MAX_PARTITIONS_PER_NAMESPACE"
     )]
     pub max_partitions_per_namespace: Option<NonZeroUsize>,
+
+    /// Limit the approximate number of distinct series (tag sets) that may be
+    /// written to a single table.
+    ///
+    /// Writes that would create new series beyond this limit are rejected.
+    ///
+    /// This limit is disabled by default.
+    #[clap(
+        long = "max-series-per-table",
+        env = "INFLUXDB_IOX_MAX_SERIES_PER_TABLE"
+    )]
+    pub max_series_per_table: Option<NonZeroUsize>,
+
+    /// The gRPC address of a peer ingester to hand off all buffered data to
+    /// during graceful shutdown, instead of persisting it, in the form of
+    /// "http://ingester-1:8083".
+    ///
+    /// The peer persists the handed-off data (merged with its own buffered
+    /// data for the same partitions) before acknowledging it, and this
+    /// ingester continues to serve queries until then.
+    ///
+    /// If the handoff fails or does not complete within
+    /// `--handoff-timeout-seconds`, the buffered data is persisted.
+    ///
+    /// Disabled by default.
+    #[clap(
+        long = "handoff-peer-address",
+        env = "INFLUXDB_IOX_HANDOFF_PEER_ADDRESS"
+    )]
+    pub handoff_peer_address: Option<String>,
+
+    /// The address of this ingester as configured in the routers, announced
+    /// to the routers over gossip when this ingester starts or begins handing
+    /// off its buffered data.
+    ///
+    /// This MUST exactly match the address given in the router
+    /// `--ingester-addresses` list, and requires gossip to be enabled. Only
+    /// used when `--handoff-peer-address` is set.
+    #[clap(
+        long = "handoff-advertise-address",
+        env = "INFLUXDB_IOX_HANDOFF_ADVERTISE_ADDRESS"
+    )]
+    pub handoff_advertise_address: Option<String>,
+
+    /// The maximum duration of time a shutdown handoff may take before falling
+    /// back to persisting the buffered data.
+    #[clap(
+        long = "handoff-timeout-seconds",
+        env = "INFLUXDB_IOX_HANDOFF_TIMEOUT_SECONDS",
+        default_value = "120",
+        action
+    )]
+    pub handoff_timeout_seconds: u64,
+}
+
+/// The durability guarantee provided by the WAL before a write is
+/// acknowledged.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum WalDurabilityMode {
+    /// fsync each write individually.
+    PerWrite,
+
+    /// Commit the writes received within a window of time with a single
+    /// fsync.
+    #[default]
+    GroupCommit,
+
+    /// Acknowledge writes without an fsync.
+    Unsafe,
 }
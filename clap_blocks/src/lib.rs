@@ -32,3 +32,4 @@ pub mod router;
 pub mod run_config;
 pub mod single_tenant;
 pub mod socket_addr;
+pub mod wal_encryption;
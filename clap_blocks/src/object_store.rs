@@ -203,6 +203,32 @@ pub struct ObjectStoreConfig {
         action
     )]
     pub object_store_connection_limit: NonZeroUsize,
+
+    /// Which object storage to use for the cold storage tier. If not specified,
+    /// parquet files are never moved out of the object store configured by
+    /// `--object-store`.
+    ///
+    /// Accepts the same values as `--object-store`, and shares its credential
+    /// configuration (`--aws-access-key-id`, `--google-service-account`, etc).
+    /// Must also set `--cold-bucket` for cloud object stores, or
+    /// `--cold-data-dir` for `file`.
+    #[clap(
+        value_enum,
+        long = "cold-object-store",
+        env = "INFLUXDB_IOX_COLD_OBJECT_STORE",
+        ignore_case = true,
+        action
+    )]
+    pub cold_object_store: Option<ObjectStoreType>,
+
+    /// Name of the bucket to use for the cold storage tier. Must also set
+    /// `--cold-object-store` to a cloud object storage to have any effect.
+    #[clap(long = "cold-bucket", env = "INFLUXDB_IOX_COLD_BUCKET", action)]
+    pub cold_bucket: Option<String>,
+
+    /// The location InfluxDB IOx will use to store cold tier files locally.
+    #[clap(long = "cold-data-dir", env = "INFLUXDB_IOX_COLD_DB_DIR", action)]
+    pub cold_database_directory: Option<PathBuf>,
 }
 
 impl ObjectStoreConfig {
@@ -225,6 +251,9 @@ impl ObjectStoreConfig {
             azure_storage_access_key: Default::default(),
             azure_storage_account: Default::default(),
             bucket: Default::default(),
+            cold_bucket: Default::default(),
+            cold_database_directory: Default::default(),
+            cold_object_store: Default::default(),
             database_directory,
             google_service_account: Default::default(),
             object_store,
@@ -409,6 +438,30 @@ pub fn make_object_store(config: &ObjectStoreConfig) -> Result<Arc<DynObjectStor
     }
 }
 
+/// Create the config-dependant object store of the cold storage tier, if one
+/// is configured.
+///
+/// The cold object store is configured through the `--cold-*` options and
+/// shares all other options (credentials, connection limit, etc) with the
+/// primary object store.
+pub fn make_cold_object_store(
+    config: &ObjectStoreConfig,
+) -> Result<Option<Arc<DynObjectStore>>, ParseError> {
+    let Some(cold_object_store) = config.cold_object_store else {
+        return Ok(None);
+    };
+
+    info!("Cold tier object store");
+    let cold_config = ObjectStoreConfig {
+        object_store: Some(cold_object_store),
+        bucket: config.cold_bucket.clone(),
+        database_directory: config.cold_database_directory.clone(),
+        ..config.clone()
+    };
+
+    make_object_store(&cold_config).map(Some)
+}
+
 #[derive(Debug, Snafu)]
 #[allow(missing_docs)]
 pub enum CheckError {
@@ -597,6 +650,47 @@ mod tests {
         )
     }
 
+    #[test]
+    fn no_cold_object_store_by_default() {
+        let config = ObjectStoreConfig::try_parse_from(["server"]).unwrap();
+
+        assert!(make_cold_object_store(&config).unwrap().is_none());
+    }
+
+    #[test]
+    fn valid_cold_file_config() {
+        let root = TempDir::new().unwrap();
+        let root_path = root.path().to_str().unwrap();
+        let cold_root = TempDir::new().unwrap();
+        let cold_root_path = cold_root.path().to_str().unwrap();
+
+        let config = ObjectStoreConfig::try_parse_from([
+            "server",
+            "--object-store",
+            "file",
+            "--data-dir",
+            root_path,
+            "--cold-object-store",
+            "file",
+            "--cold-data-dir",
+            cold_root_path,
+        ])
+        .unwrap();
+
+        let object_store = make_object_store(&config).unwrap().to_string();
+
+        let cold_object_store = make_cold_object_store(&config)
+            .unwrap()
+            .expect("cold object store configured")
+            .to_string();
+        assert!(
+            cold_object_store.starts_with("LocalFileSystem"),
+            "{}",
+            cold_object_store
+        );
+        assert_ne!(cold_object_store, object_store);
+    }
+
     #[test]
     fn file_config_missing_params() {
         // this test tests for failure to configure the object store because of data-dir configuration missing
@@ -9,6 +9,7 @@ use crate::{
 };
 use std::{
     num::{NonZeroUsize, ParseIntError},
+    path::PathBuf,
     time::Duration,
 };
 
@@ -131,6 +132,24 @@ pub struct RouterConfig {
     )]
     pub rpc_write_replicas: NonZeroUsize,
 
+    /// The strategy used to distribute RPC writes across the ingesters.
+    ///
+    /// "round-robin" spreads writes uniformly across all healthy ingesters,
+    /// causing every ingester to buffer data for every partition.
+    ///
+    /// "consistent-hash" routes all writes for a given namespace, table and
+    /// partition key to the same ingester(s), failing over to the next
+    /// ingester on the hash ring when it is unavailable. All routers MUST be
+    /// configured with the same ingester addresses, in the same order, to
+    /// route consistently.
+    #[clap(
+        long = "rpc-write-balancer",
+        env = "INFLUXDB_IOX_RPC_WRITE_BALANCER",
+        default_value = "round-robin",
+        value_enum
+    )]
+    pub rpc_write_balancer: RpcWriteBalancerType,
+
     /// Specify the maximum number of probe requests to be sent per second.
     ///
     /// At least 20% of these requests must succeed within a second for the
@@ -141,6 +160,45 @@ pub struct RouterConfig {
         default_value = "10"
     )]
     pub rpc_write_health_num_probes: u64,
+
+    /// Enable the on-disk write spool, storing spooled writes in this
+    /// directory.
+    ///
+    /// When no ingester is available to accept a write, the write is durably
+    /// spooled to this directory instead of being rejected, and replayed in
+    /// order once an ingester becomes available again.
+    ///
+    /// The spool is disabled when this is not set.
+    #[clap(
+        long = "rpc-write-spool-directory",
+        env = "INFLUXDB_IOX_RPC_WRITE_SPOOL_DIRECTORY",
+        action
+    )]
+    pub rpc_write_spool_directory: Option<PathBuf>,
+
+    /// The approximate maximum size in bytes of the on-disk write spool.
+    ///
+    /// Once this size is reached, writes are rejected until spooled writes
+    /// have been replayed.
+    #[clap(
+        long = "rpc-write-spool-max-bytes",
+        env = "INFLUXDB_IOX_RPC_WRITE_SPOOL_MAX_BYTES",
+        default_value = "1073741824", // 1GiB
+        action
+    )]
+    pub rpc_write_spool_max_bytes: u64,
+}
+
+/// The strategy used to distribute RPC writes across ingesters.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum RpcWriteBalancerType {
+    /// Distribute writes approximately uniformly across healthy ingesters.
+    #[default]
+    RoundRobin,
+
+    /// Consistently hash writes to ingesters by namespace, table and
+    /// partition key.
+    ConsistentHash,
 }
 
 /// Map a string containing an integer number of seconds into a [`Duration`].
@@ -0,0 +1,46 @@
+//! CLI config for encryption of WAL segments at rest.
+
+use std::path::PathBuf;
+
+/// Configuration of the keys used to encrypt and decrypt WAL segments.
+///
+/// Keys are specified as a set of "<key id>:<64 hex characters>" entries,
+/// separated by commas or newlines. The first key is used to encrypt new
+/// segments, and the remaining keys are used only to read segments that were
+/// encrypted with them, allowing the active key to be rotated.
+///
+/// If no keys are specified, new segments are not encrypted.
+#[derive(Debug, Clone, clap::Parser)]
+#[allow(missing_copy_implementations)]
+pub struct WalEncryptionConfig {
+    /// The set of keys used to encrypt and decrypt WAL segments.
+    ///
+    /// Example: "2:<64 hex chars>,1:<64 hex chars>"
+    #[clap(
+        long = "wal-encryption-keys",
+        env = "INFLUXDB_IOX_WAL_ENCRYPTION_KEYS",
+        conflicts_with = "wal_encryption_key_file", // Field name, not flag
+        hide_env_values = true,
+        action
+    )]
+    pub wal_encryption_keys: Option<String>,
+
+    /// The path to a file containing the set of keys used to encrypt and
+    /// decrypt WAL segments, one per line.
+    #[clap(
+        long = "wal-encryption-key-file",
+        env = "INFLUXDB_IOX_WAL_ENCRYPTION_KEY_FILE",
+        action
+    )]
+    pub wal_encryption_key_file: Option<PathBuf>,
+}
+
+impl WalEncryptionConfig {
+    /// Initialise the WAL encryption config to be disabled.
+    pub fn disabled() -> Self {
+        Self {
+            wal_encryption_keys: None,
+            wal_encryption_key_file: None,
+        }
+    }
+}
@@ -1,12 +1,14 @@
 //! Main compactor entry point.
 use std::sync::Arc;
 
+use compactor_scheduler::SchedulerConfig;
 use futures::{
     future::{BoxFuture, Shared},
     FutureExt, TryFutureExt,
 };
 use generated_types::influxdata::iox::gossip::{v1::CompactionEvent, Topic};
 use gossip::{NopDispatcher, TopicInterests};
+use gossip_query_stats::rx::QueryStatsRx;
 use observability_deps::tracing::{info, warn};
 use tokio::task::{JoinError, JoinHandle};
 use tokio_util::sync::CancellationToken;
@@ -19,6 +21,9 @@ use crate::{
     },
     config::Config,
     driver::compact,
+    query_stats::QueryStatsRecorder,
+    rollup::Rollup,
+    sort_key_rewrite::{SortKeyRewrite, SortKeyRewriteRequests},
 };
 
 /// A [`JoinHandle`] that can be cloned
@@ -34,6 +39,7 @@ fn shared_handle(handle: JoinHandle<()>) -> SharedJoinHandle {
 pub struct Compactor {
     shutdown: CancellationToken,
     worker: SharedJoinHandle,
+    sort_key_rewrite_requests: SortKeyRewriteRequests,
 }
 
 impl Compactor {
@@ -54,21 +60,67 @@ impl Compactor {
         ));
         let df_semaphore = Arc::new(semaphore_metrics.new_semaphore(config.df_concurrency.get()));
 
+        // Materialise the namespace rollup policies, if configured.
+        let rollup = config.rollup_poll_interval.map(|poll_interval| {
+            let rollup = Rollup::new(
+                Arc::clone(&config.catalog),
+                config.parquet_store_real.clone(),
+                Arc::clone(&config.exec),
+                config.exec.pool(),
+                Arc::clone(&config.time_provider),
+            );
+            (rollup, poll_interval)
+        });
+
+        // Rewrite partition sort keys on request, and periodically if
+        // configured.
+        let sort_key_rewrite = SortKeyRewrite::new(
+            Arc::clone(&config.catalog),
+            config.parquet_store_real.clone(),
+            Arc::clone(&config.exec),
+            config.exec.pool(),
+            Arc::clone(&config.time_provider),
+            config.max_desired_file_size_bytes,
+        );
+        let (sort_key_rewrite_requests, sort_key_rewrite_rx) = SortKeyRewriteRequests::new();
+        let sort_key_rewrite_poll_interval = config.sort_key_rewrite_poll_interval;
+
         // Initialise the gossip subsystem, if configured.
         let gossip = match config.gossip_bind_address {
             Some(bind) => {
+                let read_amplification_scores = match &config.scheduler_config {
+                    SchedulerConfig::Local(c) => c.read_amplification_scores.clone(),
+                    SchedulerConfig::Remote(_) => None,
+                };
+
                 // Initialise the gossip subsystem.
-                let handle = gossip::Builder::<_, Topic>::new(
-                    config.gossip_seeds,
-                    NopDispatcher,
-                    Arc::clone(&config.metric_registry),
-                )
-                // Configure the compactor to subscribe to no topics - it
-                // currently only sends events.
-                .with_topic_filter(TopicInterests::default())
-                .bind(bind)
-                .await
-                .expect("failed to start gossip reactor");
+                //
+                // The compactor only subscribes to the query stats broadcast
+                // by the queriers when they are used to prioritise partitions.
+                let builder = match read_amplification_scores {
+                    Some(scores) => {
+                        gossip::Builder::<_, Topic>::new(
+                            config.gossip_seeds,
+                            QueryStatsRx::new(QueryStatsRecorder::new(scores), 100),
+                            Arc::clone(&config.metric_registry),
+                        )
+                        .with_topic_filter(TopicInterests::default().with_topic(Topic::QueryStats))
+                        .bind(bind)
+                        .await
+                    }
+                    None => {
+                        gossip::Builder::<_, Topic>::new(
+                            config.gossip_seeds,
+                            NopDispatcher,
+                            Arc::clone(&config.metric_registry),
+                        )
+                        // Otherwise subscribe to no topics - it only sends events.
+                        .with_topic_filter(TopicInterests::default())
+                        .bind(bind)
+                        .await
+                    }
+                };
+                let handle = builder.expect("failed to start gossip reactor");
 
                 let event_tx =
                     gossip_compaction::tx::CompactionEventTx::<CompactionEvent>::new(handle);
@@ -93,11 +145,27 @@ impl Compactor {
 
                     info!("compactor done");
                 } => {}
+                _ = async {
+                    match rollup {
+                        Some((rollup, poll_interval)) => rollup.run(poll_interval).await,
+                        None => futures::future::pending().await,
+                    }
+                } => {}
+                _ = sort_key_rewrite.run(sort_key_rewrite_poll_interval, sort_key_rewrite_rx) => {}
             }
         });
         let worker = shared_handle(worker);
 
-        Self { shutdown, worker }
+        Self {
+            shutdown,
+            worker,
+            sort_key_rewrite_requests,
+        }
+    }
+
+    /// Handle requesting sort key rewrites of tables.
+    pub fn sort_key_rewrite_requests(&self) -> SortKeyRewriteRequests {
+        self.sort_key_rewrite_requests.clone()
     }
 
     /// Trigger shutdown. You should [join](Self::join) afterwards.
@@ -9,6 +9,8 @@ use datafusion::{error::DataFusionError, physical_plan::ExecutionPlan};
 pub mod panic;
 pub mod planner_v1;
 mod query_chunk;
+pub mod rollup;
+pub mod sort_key;
 
 use crate::{partition_info::PartitionInfo, plan_ir::PlanIR};
 
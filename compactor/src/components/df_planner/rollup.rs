@@ -0,0 +1,138 @@
+use std::{fmt::Display, sync::Arc};
+
+use data_types::{rollup::RollupAggregate, RollupPolicy};
+use datafusion::{
+    common::ScalarValue,
+    error::DataFusionError,
+    logical_expr::{col, date_bin, lit, Expr, LogicalPlanBuilder},
+    physical_plan::ExecutionPlan,
+    prelude::{avg, count, max, min, sum},
+};
+use iox_query::{
+    exec::{Executor, ExecutorType},
+    frontend::reorg::ReorgPlanner,
+    util::logical_sort_key_exprs,
+};
+use parquet_file::storage::ParquetStorage;
+use schema::{sort::SortKey, TIME_COLUMN_NAME};
+
+use crate::{
+    components::df_planner::query_chunk::{to_query_chunks, QueryableParquetChunk},
+    partition_info::PartitionInfo,
+    plan_ir::FileIR,
+};
+
+/// Builder for rollup plans, downsampling the files of a source partition
+/// into the rows of a rollup table as described by a [`RollupPolicy`].
+///
+/// The input files are deduplicated exactly as they would be by a compaction
+/// plan, before the rows are aggregated into time buckets of the policy
+/// interval, grouped by all tags of the source partition.
+#[derive(Debug)]
+pub struct RollupPlanner {
+    store: ParquetStorage,
+    exec: Arc<Executor>,
+}
+
+impl RollupPlanner {
+    /// Create a new rollup plan builder.
+    pub fn new(store: ParquetStorage, exec: Arc<Executor>) -> Self {
+        Self { store, exec }
+    }
+
+    /// Plan the rollup of `files` of the `source` partition, returning the
+    /// plan and the [`SortKey`] of the rows it produces: the partition sort
+    /// key, restricted to the tags present in `files`.
+    ///
+    /// Aggregates of fields that do not appear in any of the files are
+    /// omitted from the output.
+    pub async fn plan(
+        &self,
+        files: &[FileIR],
+        source: &PartitionInfo,
+        policy: &RollupPolicy,
+    ) -> Result<(Arc<dyn ExecutionPlan>, SortKey), DataFusionError> {
+        let ctx = self.exec.new_context(ExecutorType::Reorg);
+
+        let query_chunks = to_query_chunks(files, source, self.store.clone());
+        let merged_schema = QueryableParquetChunk::merge_schemas(&query_chunks);
+        let primary_key = merged_schema.primary_key();
+        let sort_key = source
+            .sort_key
+            .as_ref()
+            .expect("no partition sort key in catalog")
+            .filter_to(&primary_key, source.partition_id.get());
+
+        let deduplicated = ReorgPlanner::new()
+            .compact_plan(
+                Arc::from(source.table.name.clone()),
+                &merged_schema,
+                query_chunks,
+                sort_key.clone(),
+            )
+            .map_err(|e| {
+                DataFusionError::Context(
+                    String::from("rollup planner"),
+                    Box::new(DataFusionError::External(Box::new(e))),
+                )
+            })?;
+
+        // Group by all tags and the start of the time bucket of each row.
+        let stride = lit(ScalarValue::new_interval_mdn(
+            0,
+            0,
+            policy.interval.as_nanos() as i64,
+        ));
+        let group_exprs = primary_key
+            .iter()
+            .filter(|c| **c != TIME_COLUMN_NAME)
+            .map(|c| col(*c))
+            .chain(std::iter::once(
+                date_bin(
+                    stride,
+                    col(TIME_COLUMN_NAME),
+                    lit(ScalarValue::TimestampNanosecond(Some(0), None)),
+                )
+                .alias(TIME_COLUMN_NAME),
+            ))
+            .collect::<Vec<_>>();
+
+        let aggr_exprs = policy
+            .aggregates
+            .iter()
+            .filter(|a| merged_schema.find_index_of(&a.field).is_some())
+            .map(|a| aggregate_expr(a.aggregate, col(a.field.as_str())).alias(a.output_column()))
+            .collect::<Vec<_>>();
+
+        let plan = LogicalPlanBuilder::from(deduplicated)
+            .aggregate(group_exprs, aggr_exprs)?
+            .sort(logical_sort_key_exprs(&sort_key))?
+            .build()?;
+
+        let plan = ctx.create_physical_plan(&plan).await.map_err(|e| {
+            DataFusionError::Context(
+                String::from("rollup planner"),
+                Box::new(DataFusionError::External(Box::new(e))),
+            )
+        })?;
+
+        Ok((plan, sort_key))
+    }
+}
+
+impl Display for RollupPlanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rollup")
+    }
+}
+
+/// The DataFusion aggregate expression computing `aggregate` over `expr`.
+fn aggregate_expr(aggregate: RollupAggregate, expr: Expr) -> Expr {
+    match aggregate {
+        RollupAggregate::Min => min(expr),
+        RollupAggregate::Max => max(expr),
+        RollupAggregate::Sum => sum(expr),
+        RollupAggregate::Count => count(expr),
+        RollupAggregate::Mean => avg(expr),
+    }
+}
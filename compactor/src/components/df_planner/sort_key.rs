@@ -0,0 +1,128 @@
+use std::{fmt::Display, sync::Arc};
+
+use datafusion::{
+    error::DataFusionError,
+    logical_expr::{col, Expr, LogicalPlan, LogicalPlanBuilder},
+    physical_plan::ExecutionPlan,
+    prelude::approx_distinct,
+};
+use iox_query::{
+    exec::{Executor, ExecutorType},
+    frontend::reorg::ReorgPlanner,
+};
+use parquet_file::storage::ParquetStorage;
+
+use crate::{
+    components::df_planner::query_chunk::{to_query_chunks, QueryableParquetChunk},
+    partition_info::PartitionInfo,
+    plan_ir::FileIR,
+};
+
+/// Builder for the plans of a sort key rewrite of a partition.
+///
+/// A rewrite first estimates the cardinality of every tag of the partition
+/// files, from which a new sort key is derived, and then rewrites the files
+/// sorted on the new key.
+#[derive(Debug)]
+pub struct SortKeyRewritePlanner {
+    store: ParquetStorage,
+    exec: Arc<Executor>,
+}
+
+impl SortKeyRewritePlanner {
+    /// Create a new sort key rewrite plan builder.
+    pub fn new(store: ParquetStorage, exec: Arc<Executor>) -> Self {
+        Self { store, exec }
+    }
+
+    /// Plan the estimation of the number of distinct values of every tag in
+    /// `files`, returning the plan and the tags in the order of the columns
+    /// of the single row it produces.
+    pub async fn cardinality_plan(
+        &self,
+        files: &[FileIR],
+        partition: &PartitionInfo,
+    ) -> Result<(Arc<dyn ExecutionPlan>, Vec<String>), DataFusionError> {
+        let query_chunks = to_query_chunks(files, partition, self.store.clone());
+        let merged_schema = QueryableParquetChunk::merge_schemas(&query_chunks);
+        let tags = merged_schema
+            .tags_iter()
+            .map(|f| f.name().clone())
+            .collect::<Vec<_>>();
+        let sort_key = partition
+            .sort_key
+            .as_ref()
+            .expect("no partition sort key in catalog")
+            .filter_to(&merged_schema.primary_key(), partition.partition_id.get());
+
+        let deduplicated = ReorgPlanner::new()
+            .compact_plan(
+                Arc::from(partition.table.name.clone()),
+                &merged_schema,
+                query_chunks,
+                sort_key,
+            )
+            .map_err(context)?;
+
+        let aggr_exprs = tags
+            .iter()
+            .map(|t| approx_distinct(col(t.as_str())).alias(t.as_str()))
+            .collect::<Vec<_>>();
+        let plan = LogicalPlanBuilder::from(deduplicated)
+            .aggregate(Vec::<Expr>::new(), aggr_exprs)?
+            .build()?;
+
+        Ok((self.physical_plan(&plan).await?, tags))
+    }
+
+    /// Plan the rewrite of `files` sorted on the (new) sort key of
+    /// `partition`, split into one output file per time range delimited by
+    /// `split_times`.
+    pub async fn rewrite_plan(
+        &self,
+        files: &[FileIR],
+        partition: &PartitionInfo,
+        split_times: Vec<i64>,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let query_chunks = to_query_chunks(files, partition, self.store.clone());
+        let merged_schema = QueryableParquetChunk::merge_schemas(&query_chunks);
+        let sort_key = partition
+            .sort_key
+            .as_ref()
+            .expect("no partition sort key in catalog")
+            .filter_to(&merged_schema.primary_key(), partition.partition_id.get());
+
+        let plan = ReorgPlanner::new()
+            .split_plan(
+                Arc::from(partition.table.name.clone()),
+                &merged_schema,
+                query_chunks,
+                sort_key,
+                split_times,
+            )
+            .map_err(context)?;
+
+        self.physical_plan(&plan).await
+    }
+
+    async fn physical_plan(
+        &self,
+        plan: &LogicalPlan,
+    ) -> Result<Arc<dyn ExecutionPlan>, DataFusionError> {
+        let ctx = self.exec.new_context(ExecutorType::Reorg);
+        ctx.create_physical_plan(plan).await.map_err(context)
+    }
+}
+
+impl Display for SortKeyRewritePlanner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "sort_key_rewrite")
+    }
+}
+
+fn context(e: impl std::error::Error + Send + Sync + 'static) -> DataFusionError {
+    DataFusionError::Context(
+        String::from("sort key rewrite planner"),
+        Box::new(DataFusionError::External(Box::new(e))),
+    )
+}
@@ -59,7 +59,8 @@ use super::{
         greater_size_matching_files::GreaterSizeMatchingFilesPartitionFilter,
         has_files::HasFilesPartitionFilter, has_matching_file::HasMatchingFilePartitionFilter,
         logging::LoggingPartitionFilterWrapper, max_num_columns::MaxNumColumnsPartitionFilter,
-        metrics::MetricsPartitionFilterWrapper, or::OrPartitionFilter, PartitionFilter,
+        metrics::MetricsPartitionFilterWrapper, or::OrPartitionFilter,
+        retention::RetentionPartitionFilter, PartitionFilter,
     },
     partition_info_source::{sub_sources::SubSourcePartitionInfoSource, PartitionInfoSource},
     partition_source::{
@@ -123,7 +124,18 @@ fn make_jobs_source_commit_jobs_sink(
     Arc<CommitToScheduler>,
     Arc<dyn CompactionJobDoneSink>,
 ) {
-    let compaction_jobs_source = ScheduledCompactionJobsSource::new(Arc::clone(&scheduler));
+    let compaction_jobs_source: Arc<dyn CompactionJobsSource> =
+        Arc::new(ScheduledCompactionJobsSource::new(Arc::clone(&scheduler)));
+    // Shuffle the jobs, unless the scheduler already put them in the order
+    // they should be compacted in.
+    let compaction_jobs_source = if config.scheduler_config.orders_jobs() {
+        compaction_jobs_source
+    } else {
+        Arc::new(RandomizeOrderCompactionJobsSourcesWrapper::new(
+            compaction_jobs_source,
+            1234,
+        ))
+    };
 
     let commit = CommitToScheduler::new(Arc::clone(&scheduler));
 
@@ -156,11 +168,9 @@ fn make_jobs_source_commit_jobs_sink(
 
     // Note: Place "not empty" wrapper at the very last so that the logging and metric wrapper work
     // even when there is not data.
-    let compaction_jobs_source =
-        LoggingCompactionJobsWrapper::new(MetricsCompactionJobsSourceWrapper::new(
-            RandomizeOrderCompactionJobsSourcesWrapper::new(compaction_jobs_source, 1234),
-            &config.metric_registry,
-        ));
+    let compaction_jobs_source = LoggingCompactionJobsWrapper::new(
+        MetricsCompactionJobsSourceWrapper::new(compaction_jobs_source, &config.metric_registry),
+    );
     let compaction_jobs_source: Arc<dyn CompactionJobsSource> = if config.process_once {
         // do not wrap into the "not empty" filter because we do NOT wanna throttle in this case
         // but just exit early
@@ -251,6 +261,10 @@ fn exceptional_cases_partition_filters(config: &Config) -> Vec<Arc<dyn Partition
         config.max_num_columns_per_table,
     )));
 
+    partition_filters.push(Arc::new(RetentionPartitionFilter::new(Arc::clone(
+        &config.time_provider,
+    ))));
+
     partition_filters
 }
 
@@ -134,6 +134,8 @@ mod tests {
                     TableSchema {
                         id: TableId::new(1),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
+                        partition_template_version: 0,
                         columns: ColumnsByName::new([
                             Column {
                                 name: "col1".to_string(),
@@ -155,6 +157,8 @@ mod tests {
                     TableSchema {
                         id: TableId::new(2),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
+                        partition_template_version: 0,
                         columns: ColumnsByName::new([
                             Column {
                                 name: "col1".to_string(),
@@ -190,6 +194,13 @@ mod tests {
                         retention_period_ns: None,
                         deleted_at: None,
                         partition_template: Default::default(),
+                        schema_mode: Default::default(),
+                        field_coercion: Default::default(),
+                        write_rules: Default::default(),
+                        rollup_policies: Default::default(),
+                        cold_tier_age_ns: None,
+                        compaction_weight: 1,
+                        properties_version: 0,
                     },
                     schema: NamespaceSchema {
                         id,
@@ -198,6 +209,10 @@ mod tests {
                         max_columns_per_table: MaxColumnsPerTable::try_from(10).unwrap(),
                         retention_period_ns: None,
                         partition_template: Default::default(),
+                        schema_mode: Default::default(),
+                        field_coercion: Default::default(),
+                        write_rules: Default::default(),
+                        properties_version: 0,
                     },
                 },
             }
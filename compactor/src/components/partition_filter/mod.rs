@@ -14,6 +14,7 @@ pub mod logging;
 pub mod max_num_columns;
 pub mod metrics;
 pub mod or;
+pub mod retention;
 
 /// Filters partition based on ID and Parquet files.
 ///
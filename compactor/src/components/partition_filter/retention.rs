@@ -0,0 +1,87 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::ParquetFile;
+use iox_time::TimeProvider;
+
+use crate::{error::DynError, PartitionInfo};
+
+use super::PartitionFilter;
+
+/// Skips partitions whose files all lie outside the retention period that
+/// applies to the partition's table.
+///
+/// Those files will be flagged for deletion by the garbage collector, so
+/// compacting them is wasted work.
+#[derive(Debug)]
+pub struct RetentionPartitionFilter {
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl RetentionPartitionFilter {
+    pub fn new(time_provider: Arc<dyn TimeProvider>) -> Self {
+        Self { time_provider }
+    }
+}
+
+impl Display for RetentionPartitionFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "retention")
+    }
+}
+
+#[async_trait]
+impl PartitionFilter for RetentionPartitionFilter {
+    async fn apply(
+        &self,
+        partition_info: &PartitionInfo,
+        files: &[ParquetFile],
+    ) -> Result<bool, DynError> {
+        let Some(retention_period_ns) = partition_info.retention_period_ns else {
+            return Ok(true);
+        };
+
+        let cutoff = self.time_provider.now().timestamp_nanos() - retention_period_ns;
+        Ok(files.iter().any(|f| f.max_time.get() >= cutoff))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+    use iox_time::{MockProvider, Time};
+
+    use crate::test_utils::PartitionInfoBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let filter = RetentionPartitionFilter::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(0),
+        )));
+        assert_eq!(filter.to_string(), "retention");
+    }
+
+    #[tokio::test]
+    async fn test_apply() {
+        let filter = RetentionPartitionFilter::new(Arc::new(MockProvider::new(
+            Time::from_timestamp_nanos(100),
+        )));
+        let expired = ParquetFileBuilder::new(1).with_time_range(0, 10).build();
+        let live = ParquetFileBuilder::new(2).with_time_range(0, 60).build();
+
+        // infinite retention never skips
+        let p_info = Arc::new(PartitionInfoBuilder::new().build());
+        assert!(filter.apply(&p_info, &[expired.clone()]).await.unwrap());
+
+        // cutoff is at 50
+        let p_info = Arc::new(
+            PartitionInfoBuilder::new()
+                .with_retention_period_ns(Some(50))
+                .build(),
+        );
+        assert!(!filter.apply(&p_info, &[expired.clone()]).await.unwrap());
+        assert!(filter.apply(&p_info, &[expired, live]).await.unwrap());
+    }
+}
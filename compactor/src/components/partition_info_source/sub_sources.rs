@@ -145,6 +145,9 @@ where
         // This wil be removed once sort_key is removed from partition
         assert_eq!(sort_key, p_sort_key);
 
+        let retention_period_ns =
+            table.effective_retention_period_ns(namespace.retention_period_ns);
+
         Ok(Arc::new(PartitionInfo {
             partition_id,
             partition_hash_id: partition.hash_id().cloned(),
@@ -154,6 +157,7 @@ where
             table_schema: Arc::new(table_schema.clone()),
             sort_key,
             partition_key: partition.partition_key,
+            retention_period_ns,
         }))
     }
 }
@@ -38,6 +38,8 @@ pub fn log_config(config: &Config) {
         max_num_columns_per_table,
         max_num_files_per_plan,
         max_partition_fetch_queries_per_second,
+        rollup_poll_interval,
+        sort_key_rewrite_poll_interval,
         gossip_bind_address,
         gossip_seeds,
     } = &config;
@@ -72,6 +74,8 @@ pub fn log_config(config: &Config) {
         max_num_columns_per_table,
         max_num_files_per_plan,
         max_partition_fetch_queries_per_second,
+        ?rollup_poll_interval,
+        ?sort_key_rewrite_poll_interval,
         ?gossip_bind_address,
         ?gossip_seeds,
         "config",
@@ -136,6 +136,17 @@ pub struct Config {
     /// Queries are smoothed over the full second.
     pub max_partition_fetch_queries_per_second: Option<usize>,
 
+    /// Interval between passes materialising the namespace rollup policies.
+    ///
+    /// Rollups are not materialised if `None`.
+    pub rollup_poll_interval: Option<Duration>,
+
+    /// Interval between passes rewriting the partitions that received new
+    /// files with a cardinality-ordered sort key.
+    ///
+    /// Partitions are only rewritten on request if `None`.
+    pub sort_key_rewrite_poll_interval: Option<Duration>,
+
     /// The optional bind address for node to use for gossip communication.
     pub gossip_bind_address: Option<SocketAddr>,
 
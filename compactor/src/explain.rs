@@ -0,0 +1,332 @@
+//! Dry-run of the compaction of a single partition.
+//!
+//! [`explain_partition`] runs the decision making components of the
+//! [driver](crate::driver) against the catalog and reports what the compactor
+//! would do with a partition, without executing any plan or writing to the
+//! object store or catalog.
+//!
+//! Only the first round is explained: the decisions of any later round depend
+//! on the files produced by the plans of the previous one.
+use std::sync::Arc;
+
+use compactor_scheduler::SchedulerConfig;
+use data_types::{ParquetFile, PartitionId};
+use serde::Serialize;
+
+use crate::{
+    components::hardcoded::hardcoded_components,
+    config::Config,
+    error::DynError,
+    file_classification::{FileClassification, FilesForProgress, FilesToSplitOrCompact},
+    round_info::{CompactRange, CompactType},
+    Components, PartitionInfo, PlanIR,
+};
+
+/// What the compactor would do with a partition.
+#[derive(Debug, Serialize)]
+pub struct PartitionExplanation {
+    /// The partition.
+    pub partition_id: i64,
+    /// The files of the partition that are not marked for deletion.
+    pub files: Vec<FileExplanation>,
+    /// False if the partition filter decided there is nothing to compact.
+    pub needs_compaction: bool,
+    /// True if the round info source decided the partition is fully
+    /// compacted.
+    pub done: bool,
+    /// The ranges of the first round.
+    pub ranges: Vec<RangeExplanation>,
+    /// L2 files held back until the L0s of all ranges are compacted.
+    pub l2_files_for_later: Vec<i64>,
+}
+
+/// A parquet file, as seen by the compactor.
+#[derive(Debug, Serialize)]
+pub struct FileExplanation {
+    /// Catalog ID.
+    pub id: i64,
+    /// Compaction level.
+    pub level: i16,
+    /// Minimum timestamp.
+    pub min_time: i64,
+    /// Maximum timestamp.
+    pub max_time: i64,
+    /// Size in bytes.
+    pub file_size_bytes: i64,
+    /// Number of rows.
+    pub row_count: i64,
+    /// Creation time of the newest L0 file the data originates from.
+    pub max_l0_created_at: i64,
+}
+
+impl From<&ParquetFile> for FileExplanation {
+    fn from(f: &ParquetFile) -> Self {
+        Self {
+            id: f.id.get(),
+            level: f.compaction_level as i16,
+            min_time: f.min_time.get(),
+            max_time: f.max_time.get(),
+            file_size_bytes: f.file_size_bytes,
+            row_count: f.row_count,
+            max_l0_created_at: f.max_l0_created_at.get(),
+        }
+    }
+}
+
+/// A [`CompactRange`] of the round.
+#[derive(Debug, Serialize)]
+pub struct RangeExplanation {
+    /// The operation chosen for the range.
+    pub op: Option<String>,
+    /// Minimum time of any file in the range.
+    pub min: i64,
+    /// Maximum time of any file in the range.
+    pub max: i64,
+    /// Sum of the sizes of all files in the range.
+    pub cap: usize,
+    /// True if the range contains L0 files.
+    pub has_l0s: bool,
+    /// Files of the range left for a later round.
+    pub files_for_later: Vec<i64>,
+    /// The branches compacted concurrently within the range.
+    pub branches: Vec<BranchExplanation>,
+}
+
+/// The decisions taken for a single branch of a range.
+#[derive(Debug, Serialize)]
+pub struct BranchExplanation {
+    /// The files of the branch.
+    pub files: Vec<i64>,
+    /// Level of the files produced by the branch.
+    pub target_level: i16,
+    /// Files upgraded to the target level without being rewritten.
+    pub files_to_upgrade: Vec<i64>,
+    /// Files left untouched.
+    pub files_to_keep: Vec<i64>,
+    /// Whether the files to rewrite are split or compacted, and why.
+    pub split_or_compact: String,
+    /// Files to split or compact.
+    pub files_to_split_or_compact: Vec<i64>,
+    /// Why no progress can be made on the branch, if so.
+    pub skipped: Option<String>,
+    /// The plans the branch would execute.
+    pub plans: Vec<PlanExplanation>,
+}
+
+/// A [`PlanIR`].
+#[derive(Debug, Serialize)]
+pub struct PlanExplanation {
+    /// Kind of plan and the reason it was chosen.
+    pub reason: String,
+    /// Level of the output files.
+    pub target_level: Option<i16>,
+    /// Input files.
+    pub files: Vec<i64>,
+    /// Total size of the input files.
+    pub input_bytes: i64,
+    /// Times the output is split at.
+    pub split_times: Vec<i64>,
+    /// Number of output files.
+    pub n_output_files: usize,
+}
+
+impl From<&PlanIR> for PlanExplanation {
+    fn from(plan: &PlanIR) -> Self {
+        let (target_level, split_times) = match plan {
+            PlanIR::Compact { target_level, .. } => (Some(*target_level as i16), vec![]),
+            PlanIR::Split {
+                target_level,
+                split_times,
+                ..
+            } => (Some(*target_level as i16), split_times.clone()),
+            PlanIR::None { .. } => (None, vec![]),
+        };
+
+        Self {
+            reason: plan.reason(),
+            target_level,
+            files: plan.input_files().iter().map(|f| f.file.id.get()).collect(),
+            input_bytes: plan.input_bytes(),
+            split_times,
+            n_output_files: plan.n_output_files(),
+        }
+    }
+}
+
+/// Explain the first compaction round of `partition_id` using the components
+/// built from `config`.
+///
+/// The components are built in shadow mode and never execute a plan, so
+/// neither the catalog nor the object store are modified.
+pub async fn explain_partition(
+    config: &Config,
+    partition_id: PartitionId,
+) -> Result<PartitionExplanation, DynError> {
+    let config = Config {
+        // Jobs are never fetched, don't bother a remote scheduler.
+        scheduler_config: SchedulerConfig::default(),
+        shadow_mode: true,
+        simulate_without_object_store: true,
+        process_once: true,
+        ..config.clone()
+    };
+    let components = hardcoded_components(&config);
+
+    let files = components.partition_files_source.fetch(partition_id).await;
+    let partition_info = components.partition_info_source.fetch(partition_id).await?;
+
+    let mut explanation = PartitionExplanation {
+        partition_id: partition_id.get(),
+        files: files.iter().map(FileExplanation::from).collect(),
+        needs_compaction: false,
+        done: false,
+        ranges: vec![],
+        l2_files_for_later: vec![],
+    };
+
+    if files.is_empty()
+        || !components
+            .partition_filter
+            .apply(&partition_info, &files)
+            .await?
+    {
+        return Ok(explanation);
+    }
+    explanation.needs_compaction = true;
+
+    let (round_info, done) = components
+        .round_info_source
+        .calculate(
+            Arc::clone(&components),
+            None,
+            &partition_info,
+            config.df_concurrency.get(),
+            files,
+        )
+        .await?;
+    explanation.done = done;
+    explanation.l2_files_for_later = ids(round_info
+        .l2_files_for_later
+        .lock()
+        .unwrap()
+        .as_deref()
+        .unwrap_or_default());
+
+    for range in &round_info.ranges {
+        explanation
+            .ranges
+            .push(explain_range(&components, &partition_info, range).await?);
+    }
+
+    Ok(explanation)
+}
+
+async fn explain_range(
+    components: &Components,
+    partition_info: &Arc<PartitionInfo>,
+    range: &CompactRange,
+) -> Result<RangeExplanation, DynError> {
+    let mut branches = vec![];
+    let range_branches = range.branches.lock().unwrap().clone().unwrap_or_default();
+    if let Some(op) = &range.op {
+        for branch in range_branches {
+            branches.push(explain_branch(components, partition_info, op, branch).await?);
+        }
+    }
+
+    let files_for_later = ids(range
+        .files_for_later
+        .lock()
+        .unwrap()
+        .as_deref()
+        .unwrap_or_default());
+
+    Ok(RangeExplanation {
+        op: range.op.as_ref().map(ToString::to_string),
+        min: range.min,
+        max: range.max,
+        cap: range.cap,
+        has_l0s: range.has_l0s,
+        files_for_later,
+        branches,
+    })
+}
+
+async fn explain_branch(
+    components: &Components,
+    partition_info: &Arc<PartitionInfo>,
+    op: &CompactType,
+    branch: Vec<ParquetFile>,
+) -> Result<BranchExplanation, DynError> {
+    let files = ids(&branch);
+    let FileClassification {
+        target_level,
+        files_to_make_progress_on,
+        files_to_keep,
+    } = components
+        .file_classifier
+        .classify(partition_info, op, branch);
+
+    // The driver gives up on the partition if this filter errors; report the
+    // error on the branch instead so the rest of the round is still explained.
+    let skipped = match components
+        .post_classification_partition_filter
+        .apply(partition_info, &files_to_make_progress_on, &files_to_keep)
+        .await
+    {
+        Ok(true) => None,
+        Ok(false) => Some(String::from("no progress possible")),
+        Err(e) => Some(e.to_string()),
+    };
+
+    let FilesForProgress {
+        upgrade,
+        split_or_compact,
+    } = files_to_make_progress_on;
+
+    let plans = if skipped.is_none() {
+        let paths = split_or_compact.file_input_paths();
+        let object_store_ids = paths.iter().map(|p| p.objest_store_id()).collect();
+        components
+            .ir_planner
+            .create_plans(
+                Arc::clone(partition_info),
+                target_level,
+                split_or_compact.clone(),
+                object_store_ids,
+                paths,
+            )
+            .iter()
+            .map(PlanExplanation::from)
+            .collect()
+    } else {
+        vec![]
+    };
+
+    Ok(BranchExplanation {
+        files,
+        target_level: target_level as i16,
+        files_to_upgrade: ids(&upgrade),
+        files_to_keep: ids(&files_to_keep),
+        split_or_compact: describe(&split_or_compact),
+        files_to_split_or_compact: split_or_compact
+            .files()
+            .into_iter()
+            .map(|f| f.id.get())
+            .collect(),
+        skipped,
+        plans,
+    })
+}
+
+fn describe(split_or_compact: &FilesToSplitOrCompact) -> String {
+    match split_or_compact {
+        FilesToSplitOrCompact::None(reason) => format!("none({reason:?})"),
+        FilesToSplitOrCompact::Split(_, reason) => format!("split({reason:?})"),
+        FilesToSplitOrCompact::Compact(_, reason) => format!("compact({reason:?})"),
+    }
+}
+
+fn ids(files: &[ParquetFile]) -> Vec<i64> {
+    files.iter().map(|f| f.id.get()).collect()
+}
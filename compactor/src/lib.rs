@@ -213,11 +213,15 @@ mod components;
 pub mod config;
 mod driver;
 mod error;
+pub mod explain;
 mod file_classification;
 pub mod object_store;
 mod partition_info;
 mod plan_ir;
+mod query_stats;
+mod rollup;
 mod round_info;
+pub mod sort_key_rewrite;
 
 // publically expose items needed for testing
 pub use components::{
@@ -34,6 +34,10 @@ pub struct PartitionInfo {
 
     /// partition_key
     pub partition_key: PartitionKey,
+
+    /// Retention period in ns of the table, or of the namespace if the table
+    /// does not override it. None represents infinite retention.
+    pub retention_period_ns: Option<i64>,
 }
 
 impl PartitionInfo {
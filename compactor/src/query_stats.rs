@@ -0,0 +1,75 @@
+//! Ingestion of the per-partition read costs broadcast by the queriers.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use compactor_scheduler::ReadAmplificationScores;
+use data_types::PartitionId;
+use generated_types::influxdata::iox::gossip::v1::QueryStats;
+use gossip_query_stats::rx::QueryStatsEventHandler;
+
+/// A [`QueryStatsEventHandler`] recording the received read costs into the
+/// [`ReadAmplificationScores`] used to prioritise compaction.
+#[derive(Debug)]
+pub(crate) struct QueryStatsRecorder(Arc<ReadAmplificationScores>);
+
+impl QueryStatsRecorder {
+    pub(crate) fn new(scores: Arc<ReadAmplificationScores>) -> Self {
+        Self(scores)
+    }
+}
+
+#[async_trait]
+impl QueryStatsEventHandler for QueryStatsRecorder {
+    async fn handle(&self, event: QueryStats) {
+        for p in event.partitions {
+            self.0.record(
+                PartitionId::new(p.partition_id),
+                p.queries,
+                p.files_scanned,
+                p.dedup_rows,
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use generated_types::influxdata::iox::gossip::v1::PartitionReadCost;
+    use iox_time::{MockProvider, Time};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_records_scores() {
+        let scores = Arc::new(ReadAmplificationScores::new(
+            Duration::from_secs(60),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+        ));
+        let recorder = QueryStatsRecorder::new(Arc::clone(&scores));
+
+        recorder
+            .handle(QueryStats {
+                partitions: vec![
+                    PartitionReadCost {
+                        partition_id: 1,
+                        queries: 1,
+                        files_scanned: 4,
+                        dedup_rows: 0,
+                    },
+                    PartitionReadCost {
+                        partition_id: 2,
+                        queries: 2,
+                        files_scanned: 2,
+                        dedup_rows: 0,
+                    },
+                ],
+            })
+            .await;
+
+        assert_eq!(scores.score(PartitionId::new(1)), 3.0);
+        assert_eq!(scores.score(PartitionId::new(2)), 0.0);
+    }
+}
@@ -0,0 +1,507 @@
+//! Materialisation of the namespace [`RollupPolicies`] into rollup tables.
+//!
+//! Rollups are not driven by the compaction jobs: a partition that aged past
+//! the age threshold of a policy usually receives no new files, and is never
+//! revisited by the compaction loop. Instead, the rollup loop periodically
+//! scans the source tables of all policies, and (re-)materialises the rollup
+//! of every aged partition whose rollup is missing or out of date.
+//!
+//! [`RollupPolicies`]: data_types::RollupPolicies
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use data_types::{
+    ChunkOrder, CompactionLevel, Namespace, ParquetFile, Partition, PartitionKey, RollupPolicy,
+    Table, TableSchema,
+};
+use datafusion::execution::memory_pool::MemoryPool;
+use iox_catalog::interface::{get_table_columns_by_id, Catalog, SoftDeletedRows};
+use iox_query::exec::Executor;
+use iox_time::{Time, TimeProvider};
+use observability_deps::tracing::{debug, info, warn};
+use parquet_file::{storage::ParquetStorage, ParquetFilePath};
+use schema::sort::SortKey;
+
+use crate::{
+    components::{
+        df_plan_exec::{dedicated::DedicatedDataFusionPlanExec, DataFusionPlanExec},
+        df_planner::rollup::RollupPlanner,
+        parquet_file_sink::{object_store::ObjectStoreParquetFileSink, ParquetFileSink},
+    },
+    error::DynError,
+    partition_info::PartitionInfo,
+    plan_ir::FileIR,
+};
+
+/// Materialises the rollup policies of all namespaces.
+#[derive(Debug)]
+pub(crate) struct Rollup {
+    catalog: Arc<dyn Catalog>,
+    planner: RollupPlanner,
+    exec: DedicatedDataFusionPlanExec,
+    sink: ObjectStoreParquetFileSink,
+    time_provider: Arc<dyn TimeProvider>,
+}
+
+impl Rollup {
+    pub(crate) fn new(
+        catalog: Arc<dyn Catalog>,
+        store: ParquetStorage,
+        exec: Arc<Executor>,
+        pool: Arc<dyn MemoryPool>,
+        time_provider: Arc<dyn TimeProvider>,
+    ) -> Self {
+        Self {
+            catalog,
+            planner: RollupPlanner::new(store.clone(), Arc::clone(&exec)),
+            exec: DedicatedDataFusionPlanExec::new(exec),
+            sink: ObjectStoreParquetFileSink::new(pool, store, Arc::clone(&time_provider)),
+            time_provider,
+        }
+    }
+
+    /// Materialise the rollups every `poll_interval`, forever.
+    pub(crate) async fn run(&self, poll_interval: Duration) {
+        loop {
+            if let Err(e) = self.run_once().await {
+                warn!(error = %e, "rollup pass failed");
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+
+    /// Materialise the missing or out of date rollups of all namespaces once.
+    pub(crate) async fn run_once(&self) -> Result<(), DynError> {
+        let namespaces = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .list(SoftDeletedRows::ExcludeDeleted)
+            .await?;
+
+        for namespace in namespaces {
+            for policy in namespace.rollup_policies.iter() {
+                if let Err(e) = self.rollup_table(&namespace, policy).await {
+                    warn!(
+                        error = %e,
+                        namespace = %namespace.name,
+                        source_table = %policy.source_table,
+                        target_table = %policy.target_table(),
+                        "failed to materialise rollup",
+                    );
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Materialise the rollups of all aged partitions of the source table of
+    /// `policy`.
+    async fn rollup_table(
+        &self,
+        namespace: &Namespace,
+        policy: &RollupPolicy,
+    ) -> Result<(), DynError> {
+        let mut repos = self.catalog.repositories().await;
+
+        let Some(source_table) = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &policy.source_table)
+            .await?
+        else {
+            debug!(
+                namespace = %namespace.name,
+                source_table = %policy.source_table,
+                "rollup source table does not exist",
+            );
+            return Ok(());
+        };
+        let source_partitions = repos.partitions().list_by_table_id(source_table.id).await?;
+        let source_files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(source_table.id)
+            .await?;
+        let source_files = files_by_partition_key(source_files, &source_partitions);
+
+        let target_table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &policy.target_table())
+            .await?;
+        let mut target_files = match &target_table {
+            Some(t) => {
+                let partitions = repos.partitions().list_by_table_id(t.id).await?;
+                let files = repos
+                    .parquet_files()
+                    .list_by_table_not_to_delete(t.id)
+                    .await?;
+                files_by_partition_key(files, &partitions)
+            }
+            None => HashMap::new(),
+        };
+        drop(repos);
+
+        let cutoff = self.time_provider.now() - policy.age_threshold;
+        let stale = stale_partitions(source_files, &target_files, cutoff);
+        if stale.is_empty() {
+            return Ok(());
+        }
+
+        let target_table = match target_table {
+            Some(t) => t,
+            None => self.create_target_table(&source_table, policy).await?,
+        };
+
+        let mut source_schema = TableSchema::new_empty_from(&source_table);
+        source_schema.columns =
+            get_table_columns_by_id(source_table.id, self.catalog.repositories().await.as_mut())
+                .await?;
+        let source_schema = Arc::new(source_schema);
+        let source_table = Arc::new(source_table);
+        let mut source_partitions = source_partitions
+            .into_iter()
+            .map(|p| (p.partition_key.clone(), p))
+            .collect::<HashMap<_, _>>();
+
+        for (partition_key, files) in stale {
+            let Some(partition) = source_partitions.remove(&partition_key) else {
+                continue;
+            };
+            // Partitions without a sort key have not been persisted yet.
+            let Some(sort_key) = partition.sort_key() else {
+                continue;
+            };
+
+            let source = Arc::new(PartitionInfo {
+                partition_id: partition.id,
+                partition_hash_id: partition.hash_id().cloned(),
+                namespace_id: namespace.id,
+                namespace_name: namespace.name.clone(),
+                table: Arc::clone(&source_table),
+                table_schema: Arc::clone(&source_schema),
+                sort_key: Some(sort_key),
+                partition_key: partition.partition_key,
+                retention_period_ns: source_table
+                    .effective_retention_period_ns(namespace.retention_period_ns),
+            });
+            let old_target_files = target_files.remove(&partition_key).unwrap_or_default();
+
+            if let Err(e) = self
+                .rollup_partition(
+                    namespace,
+                    &target_table,
+                    policy,
+                    source,
+                    files,
+                    &old_target_files,
+                )
+                .await
+            {
+                warn!(
+                    error = %e,
+                    namespace = %namespace.name,
+                    target_table = %target_table.name,
+                    %partition_key,
+                    "failed to materialise rollup partition",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Create the rollup table of `policy`, partitioned like `source`.
+    async fn create_target_table(
+        &self,
+        source: &Table,
+        policy: &RollupPolicy,
+    ) -> Result<Table, DynError> {
+        let name = policy.target_table();
+        let mut repos = self.catalog.repositories().await;
+        match repos
+            .tables()
+            .create(
+                &name,
+                source.partition_template.clone(),
+                source.namespace_id,
+            )
+            .await
+        {
+            Ok(table) => {
+                info!(
+                    namespace_id = %source.namespace_id,
+                    source_table = %source.name,
+                    target_table = %name,
+                    "created rollup table",
+                );
+                Ok(table)
+            }
+            Err(iox_catalog::interface::Error::TableNameExists { .. }) => repos
+                .tables()
+                .get_by_namespace_and_name(source.namespace_id, &name)
+                .await?
+                .ok_or_else(|| format!("rollup table {name} disappeared").into()),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    /// Replace `old_target_files` of the rollup of a single partition with
+    /// the rollup of the source `files`.
+    async fn rollup_partition(
+        &self,
+        namespace: &Namespace,
+        target_table: &Table,
+        policy: &RollupPolicy,
+        source: Arc<PartitionInfo>,
+        files: Vec<ParquetFile>,
+        old_target_files: &[ParquetFile],
+    ) -> Result<(), DynError> {
+        // Only aggregate the fields the aggregate is defined for.
+        let policy = RollupPolicy {
+            aggregates: policy
+                .aggregates
+                .iter()
+                .filter(|a| {
+                    source
+                        .table_schema
+                        .columns
+                        .get(&a.field)
+                        .and_then(|c| a.aggregate.output_type(c.column_type))
+                        .is_some()
+                })
+                .cloned()
+                .collect(),
+            ..policy.clone()
+        };
+
+        let max_l0_created_at = files
+            .iter()
+            .map(|f| f.max_l0_created_at)
+            .max()
+            .expect("at least one file");
+        let files = files
+            .into_iter()
+            .map(|file| FileIR {
+                path: ParquetFilePath::from(&file),
+                order: ChunkOrder::new(file.max_l0_created_at.get()),
+                file,
+            })
+            .collect::<Vec<_>>();
+
+        let (plan, sort_key) = self.planner.plan(&files, &source, &policy).await?;
+
+        let target = self
+            .target_partition_info(namespace, target_table, &policy, &source, sort_key)
+            .await?;
+
+        let mut created = vec![];
+        for stream in self.exec.exec(plan) {
+            if let Some(params) = self
+                .sink
+                .store(
+                    stream,
+                    Arc::clone(&target),
+                    CompactionLevel::Final,
+                    Time::from_timestamp_nanos(max_l0_created_at.get()),
+                )
+                .await?
+            {
+                created.push(params);
+            }
+        }
+
+        let delete = old_target_files.iter().map(|f| f.id).collect::<Vec<_>>();
+        self.catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create_upgrade_delete(&delete, &[], &created, CompactionLevel::Final)
+            .await?;
+
+        info!(
+            namespace = %namespace.name,
+            source_table = %source.table.name,
+            target_table = %target_table.name,
+            partition_key = %source.partition_key,
+            n_input_files = files.len(),
+            n_output_files = created.len(),
+            n_replaced_files = delete.len(),
+            "materialised rollup partition",
+        );
+
+        Ok(())
+    }
+
+    /// Create the columns and partition of the rollup of `source`, returning
+    /// the [`PartitionInfo`] of the target partition.
+    async fn target_partition_info(
+        &self,
+        namespace: &Namespace,
+        target_table: &Table,
+        policy: &RollupPolicy,
+        source: &PartitionInfo,
+        sort_key: SortKey,
+    ) -> Result<Arc<PartitionInfo>, DynError> {
+        let mut repos = self.catalog.repositories().await;
+
+        for (column, _) in sort_key.iter() {
+            let column_type = source
+                .table_schema
+                .columns
+                .get(column)
+                .expect("sort key column in table schema")
+                .column_type;
+            repos
+                .columns()
+                .create_or_get(column, target_table.id, column_type)
+                .await?;
+        }
+        for aggregate in &policy.aggregates {
+            let column_type = source
+                .table_schema
+                .columns
+                .get(&aggregate.field)
+                .and_then(|c| aggregate.aggregate.output_type(c.column_type))
+                .expect("aggregates filtered to valid output types");
+            repos
+                .columns()
+                .create_or_get(&aggregate.output_column(), target_table.id, column_type)
+                .await?;
+        }
+
+        let columns = get_table_columns_by_id(target_table.id, repos.as_mut()).await?;
+
+        // The rows of the rollup are sorted on the same columns as the source
+        // partition; replace the sort key of the target partition if needed.
+        let mut partition = repos
+            .partitions()
+            .create_or_get(source.partition_key.clone(), target_table.id)
+            .await?;
+        if partition.sort_key() != Some(sort_key.clone()) {
+            let new_sort_key = sort_key.to_columns().collect::<Vec<_>>();
+            partition = repos
+                .partitions()
+                .cas_sort_key(
+                    &partition.transition_partition_id(),
+                    partition.sort_key.clone(),
+                    partition.sort_key_ids_none_if_empty().cloned(),
+                    &new_sort_key,
+                    &columns.ids_for_names(&new_sort_key),
+                )
+                .await
+                .map_err(|e| format!("failed to update rollup partition sort key: {e:?}"))?;
+        }
+
+        let mut table_schema = TableSchema::new_empty_from(target_table);
+        table_schema.columns = columns;
+
+        Ok(Arc::new(PartitionInfo {
+            partition_id: partition.id,
+            partition_hash_id: partition.hash_id().cloned(),
+            namespace_id: namespace.id,
+            namespace_name: namespace.name.clone(),
+            table: Arc::new(target_table.clone()),
+            table_schema: Arc::new(table_schema),
+            sort_key: partition.sort_key(),
+            partition_key: partition.partition_key,
+            retention_period_ns: target_table
+                .effective_retention_period_ns(namespace.retention_period_ns),
+        }))
+    }
+}
+
+/// Group `files` by the key of the partition (of `partitions`) they belong
+/// to.
+fn files_by_partition_key(
+    files: Vec<ParquetFile>,
+    partitions: &[Partition],
+) -> HashMap<PartitionKey, Vec<ParquetFile>> {
+    let keys = partitions
+        .iter()
+        .map(|p| (p.transition_partition_id(), &p.partition_key))
+        .collect::<HashMap<_, _>>();
+
+    let mut by_key: HashMap<PartitionKey, Vec<ParquetFile>> = HashMap::new();
+    for file in files {
+        if let Some(key) = keys.get(&file.partition_id) {
+            by_key.entry((*key).clone()).or_default().push(file);
+        }
+    }
+    by_key
+}
+
+/// Select the source partitions containing only data older than `cutoff`
+/// whose rollup is missing, or older than the newest data in the partition,
+/// returning each of them with its files, ordered by partition key.
+///
+/// The rollup of a partition is up to date if its files were written from
+/// all the data of the source partition, which the `max_l0_created_at` of the
+/// files tracks: compaction retains it, and new data raises it.
+fn stale_partitions(
+    source_files: HashMap<PartitionKey, Vec<ParquetFile>>,
+    target_files: &HashMap<PartitionKey, Vec<ParquetFile>>,
+    cutoff: Time,
+) -> Vec<(PartitionKey, Vec<ParquetFile>)> {
+    let max_l0_created_at = |files: &[ParquetFile]| files.iter().map(|f| f.max_l0_created_at).max();
+
+    let mut stale = source_files
+        .into_iter()
+        .filter(|(_, files)| {
+            files
+                .iter()
+                .all(|f| f.max_time.get() < cutoff.timestamp_nanos())
+        })
+        .filter(|(key, files)| {
+            let rolled_up = target_files
+                .get(key)
+                .and_then(|files| max_l0_created_at(files));
+            rolled_up < max_l0_created_at(files)
+        })
+        .collect::<Vec<_>>();
+    stale.sort_by(|(a, _), (b, _)| a.cmp(b));
+    stale
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+
+    use super::*;
+
+    fn file(id: i64, max_time: i64, max_l0_created_at: i64) -> ParquetFile {
+        ParquetFileBuilder::new(id)
+            .with_time_range(0, max_time)
+            .with_max_l0_created_at(max_l0_created_at)
+            .build()
+    }
+
+    #[test]
+    fn test_stale_partitions() {
+        let key = |k: &str| PartitionKey::from(k);
+        let source_files = [
+            // Aged, never rolled up.
+            (key("a"), vec![file(1, 10, 100), file(2, 20, 200)]),
+            // Aged, rolled up from all its data.
+            (key("b"), vec![file(3, 10, 100)]),
+            // Aged, new data since the rollup.
+            (key("c"), vec![file(4, 10, 100), file(5, 20, 300)]),
+            // Contains data newer than the cutoff.
+            (key("d"), vec![file(6, 10, 100), file(7, 50, 200)]),
+        ]
+        .into_iter()
+        .collect();
+        let target_files = [
+            (key("b"), vec![file(8, 0, 100)]),
+            (key("c"), vec![file(9, 0, 100)]),
+        ]
+        .into_iter()
+        .collect();
+
+        let got = stale_partitions(source_files, &target_files, Time::from_timestamp_nanos(50))
+            .into_iter()
+            .map(|(key, files)| (key, files.into_iter().map(|f| f.id.get()).collect()))
+            .collect::<Vec<(_, Vec<_>)>>();
+        assert_eq!(got, vec![(key("a"), vec![1, 2]), (key("c"), vec![4, 5])]);
+    }
+}
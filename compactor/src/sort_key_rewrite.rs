@@ -0,0 +1,452 @@
+//! Rewrites of partitions with a cardinality-ordered sort key.
+//!
+//! The sort key of a partition only ever grows: tags are appended to it in
+//! the order the ingesters first persist them, which is often far from the
+//! cardinality order that sorts (and compresses) the data best. A sort key
+//! rewrite estimates the cardinality of every tag of a fully compacted
+//! partition, and if ordering the tags from low to high cardinality changes
+//! the sort key, rewrites all files of the partition sorted on the new key,
+//! swapping the files and the catalog sort key atomically.
+//!
+//! Rewrites are triggered per table through [`SortKeyRewriteRequests`], and,
+//! if configured, periodically for all partitions that received new files
+//! since the previous pass.
+
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use data_types::{
+    ChunkOrder, CompactionLevel, ParquetFile, Partition, PartitionId, TableId, TableSchema,
+    TimestampMinMax,
+};
+use datafusion::{arrow::array::UInt64Array, execution::memory_pool::MemoryPool};
+use futures::TryStreamExt;
+use iox_catalog::interface::{get_table_columns_by_id, Catalog, SoftDeletedRows};
+use iox_query::exec::Executor;
+use iox_time::{Time, TimeProvider};
+use observability_deps::tracing::{debug, info, warn};
+use parquet_file::{storage::ParquetStorage, ParquetFilePath};
+use schema::{sort::SortKey, TIME_COLUMN_NAME};
+use tokio::sync::mpsc;
+
+use crate::{
+    components::{
+        df_plan_exec::{dedicated::DedicatedDataFusionPlanExec, DataFusionPlanExec},
+        df_planner::sort_key::SortKeyRewritePlanner,
+        ir_planner::planner_v1::V1IRPlanner,
+        parquet_file_sink::{object_store::ObjectStoreParquetFileSink, ParquetFileSink},
+    },
+    error::DynError,
+    partition_info::PartitionInfo,
+    plan_ir::FileIR,
+};
+
+/// Number of table rewrite requests buffered before further requests are
+/// rejected.
+const REQUEST_QUEUE_SIZE: usize = 100;
+
+/// A handle requesting the sort key rewrite of all partitions of a table.
+#[derive(Debug, Clone)]
+pub struct SortKeyRewriteRequests {
+    tx: mpsc::Sender<TableId>,
+}
+
+impl SortKeyRewriteRequests {
+    /// Create a new handle, and the receiver of its requests.
+    pub(crate) fn new() -> (Self, mpsc::Receiver<TableId>) {
+        let (tx, rx) = mpsc::channel(REQUEST_QUEUE_SIZE);
+        (Self { tx }, rx)
+    }
+
+    /// Request the rewrite of the partitions of `table_id`, returning false
+    /// if too many requests are pending already.
+    pub fn request(&self, table_id: TableId) -> bool {
+        self.tx.try_send(table_id).is_ok()
+    }
+}
+
+/// Rewrites partitions with a cardinality-ordered sort key.
+#[derive(Debug)]
+pub(crate) struct SortKeyRewrite {
+    catalog: Arc<dyn Catalog>,
+    planner: SortKeyRewritePlanner,
+    exec: DedicatedDataFusionPlanExec,
+    sink: ObjectStoreParquetFileSink,
+    time_provider: Arc<dyn TimeProvider>,
+    max_desired_file_size_bytes: u64,
+}
+
+impl SortKeyRewrite {
+    pub(crate) fn new(
+        catalog: Arc<dyn Catalog>,
+        store: ParquetStorage,
+        exec: Arc<Executor>,
+        pool: Arc<dyn MemoryPool>,
+        time_provider: Arc<dyn TimeProvider>,
+        max_desired_file_size_bytes: u64,
+    ) -> Self {
+        Self {
+            catalog,
+            planner: SortKeyRewritePlanner::new(store.clone(), Arc::clone(&exec)),
+            exec: DedicatedDataFusionPlanExec::new(exec),
+            sink: ObjectStoreParquetFileSink::new(pool, store, Arc::clone(&time_provider)),
+            time_provider,
+            max_desired_file_size_bytes,
+        }
+    }
+
+    /// Serve the table rewrite `requests` forever and, if `poll_interval` is
+    /// set, rewrite the partitions that received new files every
+    /// `poll_interval`.
+    pub(crate) async fn run(
+        &self,
+        poll_interval: Option<Duration>,
+        mut requests: mpsc::Receiver<TableId>,
+    ) {
+        let mut last_pass = self.time_provider.now();
+        let mut next_pass = poll_interval.map(|i| tokio::time::Instant::now() + i);
+
+        loop {
+            tokio::select! {
+                _ = async {
+                    match next_pass {
+                        Some(deadline) => tokio::time::sleep_until(deadline).await,
+                        None => futures::future::pending().await,
+                    }
+                } => {
+                    let now = self.time_provider.now();
+                    if let Err(e) = self.run_once(last_pass, now).await {
+                        warn!(error = %e, "sort key rewrite pass failed");
+                    }
+                    last_pass = now;
+                    next_pass = poll_interval.map(|i| tokio::time::Instant::now() + i);
+                }
+                Some(table_id) = requests.recv() => {
+                    if let Err(e) = self.rewrite_table(table_id, None).await {
+                        warn!(error = %e, %table_id, "sort key rewrite of table failed");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Rewrite the partitions that received new files between `since` and
+    /// `until`.
+    pub(crate) async fn run_once(&self, since: Time, until: Time) -> Result<(), DynError> {
+        let mut repos = self.catalog.repositories().await;
+        let ids = repos
+            .partitions()
+            .partitions_new_file_between(since.into(), Some(until.into()))
+            .await?;
+        if ids.is_empty() {
+            return Ok(());
+        }
+        let partitions = repos.partitions().get_by_id_batch(ids).await?;
+        drop(repos);
+
+        let mut by_table: BTreeMap<TableId, HashSet<PartitionId>> = BTreeMap::new();
+        for p in partitions {
+            by_table.entry(p.table_id).or_default().insert(p.id);
+        }
+
+        for (table_id, partitions) in by_table {
+            if let Err(e) = self.rewrite_table(table_id, Some(&partitions)).await {
+                warn!(error = %e, %table_id, "sort key rewrite of table failed");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rewrite the partitions of `table_id`, or only those in `only` if set.
+    async fn rewrite_table(
+        &self,
+        table_id: TableId,
+        only: Option<&HashSet<PartitionId>>,
+    ) -> Result<(), DynError> {
+        let mut repos = self.catalog.repositories().await;
+
+        let Some(table) = repos.tables().get_by_id(table_id).await? else {
+            debug!(%table_id, "sort key rewrite of unknown table");
+            return Ok(());
+        };
+        let Some(namespace) = repos
+            .namespaces()
+            .get_by_id(table.namespace_id, SoftDeletedRows::ExcludeDeleted)
+            .await?
+        else {
+            return Ok(());
+        };
+        let mut table_schema = TableSchema::new_empty_from(&table);
+        table_schema.columns = get_table_columns_by_id(table_id, repos.as_mut()).await?;
+        let partitions = repos.partitions().list_by_table_id(table_id).await?;
+        let files = repos
+            .parquet_files()
+            .list_by_table_not_to_delete(table_id)
+            .await?;
+        drop(repos);
+
+        let mut files_by_partition: HashMap<_, Vec<ParquetFile>> = HashMap::new();
+        for file in files {
+            files_by_partition
+                .entry(file.partition_id.clone())
+                .or_default()
+                .push(file);
+        }
+
+        let table = Arc::new(table);
+        let table_schema = Arc::new(table_schema);
+        for partition in partitions {
+            if only.map_or(false, |only| !only.contains(&partition.id)) {
+                continue;
+            }
+            let Some(files) = files_by_partition.remove(&partition.transition_partition_id())
+            else {
+                continue;
+            };
+            // Partitions still being compacted are rewritten once they are
+            // fully compacted, which creates new files again.
+            if files
+                .iter()
+                .any(|f| f.compaction_level != CompactionLevel::Final)
+            {
+                continue;
+            }
+
+            let partition_key = partition.partition_key.clone();
+            let info = PartitionInfo {
+                partition_id: partition.id,
+                partition_hash_id: partition.hash_id().cloned(),
+                namespace_id: namespace.id,
+                namespace_name: namespace.name.clone(),
+                table: Arc::clone(&table),
+                table_schema: Arc::clone(&table_schema),
+                sort_key: partition.sort_key(),
+                partition_key: partition.partition_key.clone(),
+                retention_period_ns: table
+                    .effective_retention_period_ns(namespace.retention_period_ns),
+            };
+            if let Err(e) = self.rewrite_partition(&partition, info, files).await {
+                warn!(
+                    error = %e,
+                    namespace = %namespace.name,
+                    table = %table.name,
+                    %partition_key,
+                    "sort key rewrite of partition failed",
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Rewrite the `files` of `partition` if ordering its tags by cardinality
+    /// changes its sort key.
+    async fn rewrite_partition(
+        &self,
+        partition: &Partition,
+        mut info: PartitionInfo,
+        files: Vec<ParquetFile>,
+    ) -> Result<(), DynError> {
+        // Partitions without a sort key have not been persisted yet.
+        let Some(current) = info.sort_key.clone() else {
+            return Ok(());
+        };
+
+        let max_l0_created_at = files
+            .iter()
+            .map(|f| f.max_l0_created_at)
+            .max()
+            .expect("at least one file");
+        let files = files
+            .into_iter()
+            .map(|file| FileIR {
+                path: ParquetFilePath::from(&file),
+                order: ChunkOrder::new(file.max_l0_created_at.get()),
+                file,
+            })
+            .collect::<Vec<_>>();
+
+        let cardinalities = self.cardinalities(&files, &info).await?;
+        let sort_key = cardinality_sort_key(&current, &cardinalities);
+        if sort_key == current {
+            debug!(
+                partition_id = %partition.id,
+                %sort_key,
+                "partition sort key already ordered by cardinality",
+            );
+            return Ok(());
+        }
+
+        let chunk_times = files
+            .iter()
+            .map(|f| TimestampMinMax {
+                min: f.file.min_time.get(),
+                max: f.file.max_time.get(),
+            })
+            .collect::<Vec<_>>();
+        let split_times = V1IRPlanner::compute_split_time(
+            chunk_times,
+            files.iter().map(|f| f.file.min_time.get()).min().unwrap(),
+            files.iter().map(|f| f.file.max_time.get()).max().unwrap(),
+            files.iter().map(|f| f.file.file_size_bytes as u64).sum(),
+            self.max_desired_file_size_bytes,
+        );
+
+        info.sort_key = Some(sort_key.clone());
+        let info = Arc::new(info);
+        let plan = self
+            .planner
+            .rewrite_plan(&files, &info, split_times)
+            .await?;
+
+        let mut created = vec![];
+        for stream in self.exec.exec(plan) {
+            if let Some(params) = self
+                .sink
+                .store(
+                    stream,
+                    Arc::clone(&info),
+                    CompactionLevel::Final,
+                    Time::from_timestamp_nanos(max_l0_created_at.get()),
+                )
+                .await?
+            {
+                created.push(params);
+            }
+        }
+
+        let new_sort_key = sort_key.to_columns().collect::<Vec<_>>();
+        let new_sort_key_ids = info.table_schema.columns.ids_for_names(&new_sort_key);
+        let delete = files.iter().map(|f| f.file.id).collect::<Vec<_>>();
+        match self
+            .catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .rewrite_sort_key(
+                &partition.transition_partition_id(),
+                &partition.sort_key_ids,
+                &new_sort_key,
+                &new_sort_key_ids,
+                &delete,
+                &created,
+            )
+            .await
+        {
+            Ok(_) => {}
+            Err(e @ iox_catalog::interface::Error::SortKeyRewriteConflict { .. }) => {
+                // The partition changed while it was rewritten. The written
+                // files are never referenced by the catalog, and are removed
+                // by the garbage collector.
+                info!(error = %e, "sort key rewrite of partition abandoned");
+                return Ok(());
+            }
+            Err(e) => return Err(e.into()),
+        }
+
+        info!(
+            namespace = %info.namespace_name,
+            table = %info.table.name,
+            partition_key = %info.partition_key,
+            old_sort_key = %current,
+            new_sort_key = %sort_key,
+            n_input_files = files.len(),
+            n_output_files = created.len(),
+            "rewrote partition sort key",
+        );
+
+        Ok(())
+    }
+
+    /// Estimate the number of distinct values of every tag in `files`.
+    async fn cardinalities(
+        &self,
+        files: &[FileIR],
+        partition: &PartitionInfo,
+    ) -> Result<HashMap<String, u64>, DynError> {
+        let (plan, tags) = self.planner.cardinality_plan(files, partition).await?;
+
+        let mut cardinalities = HashMap::with_capacity(tags.len());
+        for stream in self.exec.exec(plan) {
+            for batch in stream.try_collect::<Vec<_>>().await? {
+                if batch.num_rows() == 0 {
+                    continue;
+                }
+                for (tag, column) in tags.iter().zip(batch.columns()) {
+                    let column = column
+                        .as_any()
+                        .downcast_ref::<UInt64Array>()
+                        .ok_or("approx_distinct output is not a UInt64 array")?;
+                    cardinalities.insert(tag.clone(), column.value(0));
+                }
+            }
+        }
+
+        Ok(cardinalities)
+    }
+}
+
+/// Order the tags of `current` and of `cardinalities` from low to high
+/// cardinality, ties broken by name, followed by the time column.
+///
+/// Tags of `current` with no estimated cardinality do not appear in any file
+/// of the partition, and sort first.
+fn cardinality_sort_key(current: &SortKey, cardinalities: &HashMap<String, u64>) -> SortKey {
+    let mut tags = current
+        .to_columns()
+        .filter(|c| *c != TIME_COLUMN_NAME)
+        .map(|c| (cardinalities.get(c).copied().unwrap_or_default(), c))
+        .collect::<Vec<_>>();
+    tags.extend(
+        cardinalities
+            .iter()
+            .filter(|(c, _)| !current.contains(c))
+            .map(|(c, n)| (*n, c.as_str())),
+    );
+    tags.sort_unstable();
+
+    SortKey::from_columns(
+        tags.into_iter()
+            .map(|(_, c)| c)
+            .chain(std::iter::once(TIME_COLUMN_NAME)),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cardinality_sort_key() {
+        let current = SortKey::from_columns(["host", "region", "env", "time"]);
+        let cardinalities = [
+            ("host".to_string(), 1000),
+            ("region".to_string(), 10),
+            ("env".to_string(), 10),
+            ("dc".to_string(), 3),
+        ]
+        .into_iter()
+        .collect();
+
+        let got = cardinality_sort_key(&current, &cardinalities);
+        assert_eq!(
+            got,
+            SortKey::from_columns(["dc", "env", "region", "host", "time"])
+        );
+
+        // Already ordered by cardinality.
+        assert_eq!(cardinality_sort_key(&got, &cardinalities), got);
+
+        // Tags no longer present in any file sort first.
+        let cardinalities = [("host".to_string(), 1000)].into_iter().collect();
+        let got = cardinality_sort_key(&current, &cardinalities);
+        assert_eq!(
+            got,
+            SortKey::from_columns(["env", "region", "host", "time"])
+        );
+    }
+}
@@ -23,6 +23,8 @@ impl PartitionInfoBuilder {
             namespace_id,
             name: String::from("table"),
             partition_template: Default::default(),
+            retention_period_ns: None,
+            partition_template_version: 0,
         });
         let table_schema = Arc::new(TableSchema::new_empty_from(&table));
 
@@ -36,6 +38,7 @@ impl PartitionInfoBuilder {
                 table_schema,
                 sort_key: None,
                 partition_key,
+                retention_period_ns: None,
             },
         }
     }
@@ -58,6 +61,8 @@ impl PartitionInfoBuilder {
         let table_schema = Arc::new(TableSchema {
             id: self.inner.table.id,
             partition_template: Default::default(),
+            retention_period_ns: None,
+            partition_template_version: 0,
             columns: ColumnsByName::new(columns),
         });
         self.inner.table_schema = table_schema;
@@ -65,6 +70,11 @@ impl PartitionInfoBuilder {
         self
     }
 
+    pub fn with_retention_period_ns(mut self, retention_period_ns: Option<i64>) -> Self {
+        self.inner.retention_period_ns = retention_period_ns;
+        self
+    }
+
     pub fn build(self) -> PartitionInfo {
         self.inner
     }
@@ -1,4 +1,5 @@
 use arrow_util::assert_batches_sorted_eq;
+use compactor::explain::explain_partition;
 use compactor_test_utils::{format_files, list_object_store, TestSetup};
 use data_types::{CompactionLevel, ParquetFile, PartitionId};
 
@@ -537,6 +538,60 @@ async fn test_shadow_mode_partition_fail() {
     assert_skipped_compactions(&setup, []).await;
 }
 
+#[tokio::test]
+async fn test_explain() {
+    test_helpers::maybe_start_logging();
+
+    // Create a test setup with 6 files
+    let setup = TestSetup::builder()
+        .await
+        .with_files()
+        .await
+        .with_max_num_files_per_plan(4)
+        .build()
+        .await;
+
+    let catalog_files_pre = setup.list_by_table().await;
+    let object_store_files_pre = list_object_store(&setup.catalog.object_store).await;
+
+    let explanation = explain_partition(&setup.config, setup.partition_info.partition_id)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        explanation.partition_id,
+        setup.partition_info.partition_id.get()
+    );
+    assert_eq!(explanation.files.len(), 6);
+    assert!(explanation.needs_compaction);
+    assert!(!explanation.done);
+
+    // Every branch of the first round is classified and planned, and only
+    // files of the partition are used as plan inputs.
+    let file_ids = catalog_files_pre
+        .iter()
+        .map(|f| f.id.get())
+        .collect::<Vec<_>>();
+    let plans = explanation
+        .ranges
+        .iter()
+        .flat_map(|r| &r.branches)
+        .flat_map(|b| &b.plans)
+        .collect::<Vec<_>>();
+    assert!(!plans.is_empty());
+    for plan in plans {
+        assert!(plan.files.iter().all(|id| file_ids.contains(id)));
+    }
+
+    // Nothing was written.
+    assert_eq!(setup.list_by_table().await, catalog_files_pre);
+    assert_eq!(
+        list_object_store(&setup.catalog.object_store).await,
+        object_store_files_pre
+    );
+    assert_skipped_compactions(&setup, []).await;
+}
+
 #[track_caller]
 fn assert_levels<'a>(
     files: impl IntoIterator<Item = &'a ParquetFile>,
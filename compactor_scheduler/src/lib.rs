@@ -38,8 +38,8 @@ mod local_scheduler;
 pub(crate) use local_scheduler::partition_done_sink::mock::MockPartitionDoneSink;
 pub use local_scheduler::{
     combos::throttle_partition::Error as ThrottleError,
-    partitions_source_config::PartitionsSourceConfig, shard_config::ShardConfig,
-    LocalSchedulerConfig,
+    partitions_source_config::PartitionsSourceConfig, read_amplification::ReadAmplificationScores,
+    shard_config::ShardConfig, LocalSchedulerConfig,
 };
 pub(crate) use local_scheduler::{
     combos::unique_partitions::Error as UniquePartitionsError,
@@ -48,6 +48,12 @@ pub(crate) use local_scheduler::{
     LocalScheduler,
 };
 
+mod remote_scheduler;
+pub(crate) use remote_scheduler::RemoteScheduler;
+pub use remote_scheduler::{
+    proto::ProtoError, CompactorId, LeaseError, LeaseManager, RemoteSchedulerConfig,
+};
+
 // partitions_source trait
 mod partitions_source;
 pub(crate) use partitions_source::*;
@@ -80,6 +86,10 @@ pub fn create_scheduler(
             );
             Arc::new(scheduler)
         }
+        SchedulerConfig::Remote(scheduler_config) => Arc::new(RemoteScheduler::new(
+            scheduler_config,
+            BackoffConfig::default(),
+        )),
     }
 }
 
@@ -101,6 +111,8 @@ pub fn create_test_scheduler(
             ),
             shard_config: None,
             ignore_partition_skip_marker: false,
+            read_amplification_scores: None,
+            fair_share: false,
         }),
     };
     create_scheduler(
@@ -6,6 +6,7 @@ pub(crate) mod partition_done_sink;
 pub(crate) mod partitions_source;
 pub(crate) mod partitions_source_config;
 pub(crate) mod partitions_subset_source;
+pub(crate) mod read_amplification;
 pub(crate) mod shard_config;
 
 use std::{sync::Arc, time::Duration};
@@ -20,8 +21,8 @@ use crate::{
     commit::{logging::LoggingCommitWrapper, metrics::MetricsCommitWrapper},
     Commit, CommitUpdate, CommitWrapper, CompactionJob, CompactionJobEnd, CompactionJobEndVariant,
     CompactionJobStatus, CompactionJobStatusResponse, CompactionJobStatusVariant, MockCommit,
-    MockPartitionsSource, PartitionsSource, PartitionsSourceConfig, Scheduler, ShardConfig,
-    SkipReason,
+    MockPartitionsSource, PartitionsSource, PartitionsSourceConfig, ReadAmplificationScores,
+    Scheduler, ShardConfig, SkipReason,
 };
 
 use self::{
@@ -36,7 +37,8 @@ use self::{
     partitions_source::{
         catalog_all::CatalogAllPartitionsSource,
         catalog_to_compact::CatalogToCompactPartitionsSource,
-        filter::FilterPartitionsSourceWrapper, never_skipped::NeverSkippedPartitionsSource,
+        fair_share::FairSharePartitionsSource, filter::FilterPartitionsSourceWrapper,
+        never_skipped::NeverSkippedPartitionsSource, prioritised::PrioritisedPartitionsSource,
     },
     partitions_subset_source::skipped::SkippedPartitionsSource,
 };
@@ -54,6 +56,12 @@ pub struct LocalSchedulerConfig {
     pub shard_config: Option<ShardConfig>,
     /// If skipped partitions should be removed from the partitions_source.
     pub ignore_partition_skip_marker: bool,
+    /// If set, partitions are ordered by their read amplification scores, as
+    /// reported by the queriers, compacting the most expensive to query first.
+    pub read_amplification_scores: Option<Arc<ReadAmplificationScores>>,
+    /// If partitions are interleaved across namespaces, proportionally to
+    /// their compaction weight, so that no namespace starves the others.
+    pub fair_share: bool,
 }
 
 /// Implementation of the scheduler for local (per compactor) scheduling.
@@ -85,7 +93,7 @@ impl LocalScheduler {
             config.clone(),
             backoff_config.clone(),
             Arc::clone(&catalog),
-            metrics,
+            Arc::clone(&metrics),
             shadow_mode,
         );
 
@@ -94,6 +102,7 @@ impl LocalScheduler {
             backoff_config.clone(),
             Arc::clone(&catalog),
             Arc::clone(&time_provider),
+            &metrics,
         );
 
         let (partitions_source, commit, partition_done_sink) = Self::build_partition_done_sink(
@@ -118,6 +127,7 @@ impl LocalScheduler {
         backoff_config: BackoffConfig,
         catalog: Arc<dyn Catalog>,
         time_provider: Arc<dyn TimeProvider>,
+        metrics: &metric::Registry,
     ) -> Arc<dyn PartitionsSource> {
         let shard_config = config.shard_config;
 
@@ -144,7 +154,7 @@ impl LocalScheduler {
         if !config.ignore_partition_skip_marker {
             partitions_source = Arc::new(NeverSkippedPartitionsSource::new(
                 partitions_source,
-                SkippedPartitionsSource::new(backoff_config, Arc::clone(&catalog)),
+                SkippedPartitionsSource::new(backoff_config.clone(), Arc::clone(&catalog)),
             ));
         };
 
@@ -160,10 +170,28 @@ impl LocalScheduler {
                 shard_config.shard_id,
             )));
         }
-        Arc::new(FilterPartitionsSourceWrapper::new(
+        let partitions_source = Arc::new(FilterPartitionsSourceWrapper::new(
             AndIdOnlyPartitionFilter::new(id_only_partition_filters),
             partitions_source,
-        ))
+        ));
+
+        let partitions_source: Arc<dyn PartitionsSource> = match config.read_amplification_scores {
+            Some(scores) => Arc::new(PrioritisedPartitionsSource::new(scores, partitions_source)),
+            None => partitions_source,
+        };
+
+        // Interleave namespaces last, retaining any prioritisation within a
+        // namespace.
+        if config.fair_share {
+            Arc::new(FairSharePartitionsSource::new(
+                backoff_config,
+                catalog,
+                partitions_source,
+                metrics,
+            ))
+        } else {
+            partitions_source
+        }
     }
 
     fn build_partition_done_sink(
@@ -337,6 +365,8 @@ mod tests {
             partitions_source_config: PartitionsSourceConfig::default(),
             shard_config,
             ignore_partition_skip_marker: false,
+            read_amplification_scores: None,
+            fair_share: false,
         };
 
         let scheduler = LocalScheduler::new(
@@ -0,0 +1,320 @@
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Display,
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::{NamespaceId, PartitionId, TableId};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use metric::{Metric, U64Gauge};
+use parking_lot::Mutex;
+
+use crate::PartitionsSource;
+
+/// An implementation of [`PartitionsSource`] that interleaves the results of
+/// another [`PartitionsSource`] across namespaces, proportionally to the
+/// compaction weight of each namespace.
+///
+/// This is weighted fair queuing with every partition costing the same: the
+/// k-th partition of a namespace of weight w is given the virtual time k / w,
+/// and partitions are ordered by ascending virtual time. A namespace with a
+/// large backlog therefore can't starve the others, while a namespace of
+/// weight 2 gets twice the jobs of a namespace of weight 1 for as long as both
+/// have partitions to compact.
+///
+/// The relative order of the partitions of a single namespace is retained.
+/// Partitions whose namespace can't be resolved are treated as a namespace of
+/// their own with the default weight.
+///
+/// The number of partitions waiting to be compacted per namespace is reported
+/// on every fetch.
+#[derive(Debug)]
+pub(crate) struct FairSharePartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    backoff_config: BackoffConfig,
+    catalog: Arc<dyn Catalog>,
+    inner: T,
+
+    /// Tables never change namespace, so their namespace is cached.
+    table_namespaces: Mutex<HashMap<TableId, NamespaceId>>,
+
+    backlog: Metric<U64Gauge>,
+    /// The namespaces with a non-zero backlog at the last fetch, to reset.
+    reported_namespaces: Mutex<HashSet<NamespaceId>>,
+}
+
+impl<T> FairSharePartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    /// Create a new [`FairSharePartitionsSource`].
+    pub(crate) fn new(
+        backoff_config: BackoffConfig,
+        catalog: Arc<dyn Catalog>,
+        inner: T,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let backlog = metrics.register_metric::<U64Gauge>(
+            "compactor_fair_share_backlog_partitions",
+            "the number of partitions of a namespace waiting to be compacted",
+        );
+
+        Self {
+            backoff_config,
+            catalog,
+            inner,
+            table_namespaces: Default::default(),
+            backlog,
+            reported_namespaces: Default::default(),
+        }
+    }
+
+    /// Resolve the namespace of each of `partitions`.
+    async fn namespaces(&self, partitions: &[PartitionId]) -> Vec<Option<NamespaceId>> {
+        let partition_tables = Backoff::new(&self.backoff_config)
+            .retry_all_errors("get_by_id_batch", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .partitions()
+                    .get_by_id_batch(partitions.to_vec())
+                    .await
+            })
+            .await
+            .expect("retry forever")
+            .into_iter()
+            .map(|p| (p.id, p.table_id))
+            .collect::<HashMap<_, _>>();
+
+        let unknown_tables = {
+            let table_namespaces = self.table_namespaces.lock();
+            partition_tables
+                .values()
+                .filter(|table_id| !table_namespaces.contains_key(table_id))
+                .copied()
+                .collect::<HashSet<_>>()
+        };
+        for table_id in unknown_tables {
+            let table = Backoff::new(&self.backoff_config)
+                .retry_all_errors("get_table_by_id", || async {
+                    self.catalog
+                        .repositories()
+                        .await
+                        .tables()
+                        .get_by_id(table_id)
+                        .await
+                })
+                .await
+                .expect("retry forever");
+            if let Some(table) = table {
+                self.table_namespaces
+                    .lock()
+                    .insert(table_id, table.namespace_id);
+            }
+        }
+
+        let table_namespaces = self.table_namespaces.lock();
+        partitions
+            .iter()
+            .map(|p| {
+                partition_tables
+                    .get(p)
+                    .and_then(|table_id| table_namespaces.get(table_id))
+                    .copied()
+            })
+            .collect()
+    }
+
+    /// The compaction weight of every namespace.
+    async fn weights(&self) -> HashMap<NamespaceId, i32> {
+        Backoff::new(&self.backoff_config)
+            .retry_all_errors("list_namespaces", || async {
+                self.catalog
+                    .repositories()
+                    .await
+                    .namespaces()
+                    .list(SoftDeletedRows::ExcludeDeleted)
+                    .await
+            })
+            .await
+            .expect("retry forever")
+            .into_iter()
+            .map(|ns| (ns.id, ns.compaction_weight))
+            .collect()
+    }
+
+    fn report_backlog(&self, backlog: HashMap<NamespaceId, u64>) {
+        let mut reported_namespaces = self.reported_namespaces.lock();
+
+        for namespace_id in reported_namespaces.iter() {
+            if !backlog.contains_key(namespace_id) {
+                self.gauge(*namespace_id).set(0);
+            }
+        }
+        for (namespace_id, n) in &backlog {
+            self.gauge(*namespace_id).set(*n);
+        }
+
+        *reported_namespaces = backlog.into_keys().collect();
+    }
+
+    fn gauge(&self, namespace_id: NamespaceId) -> U64Gauge {
+        self.backlog
+            .recorder([("namespace_id", Cow::Owned(namespace_id.to_string()))])
+    }
+}
+
+impl<T> Display for FairSharePartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "fair_share({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for FairSharePartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let partitions = self.inner.fetch().await;
+        if partitions.is_empty() {
+            self.report_backlog(HashMap::new());
+            return partitions;
+        }
+
+        let namespaces = self.namespaces(&partitions).await;
+        let weights = self.weights().await;
+
+        let mut counts: HashMap<Option<NamespaceId>, u64> = HashMap::new();
+        let mut partitions = partitions
+            .into_iter()
+            .zip(namespaces)
+            .map(|(p, namespace_id)| {
+                let k = counts.entry(namespace_id).or_default();
+                *k += 1;
+
+                let weight = namespace_id
+                    .and_then(|id| weights.get(&id))
+                    .copied()
+                    .unwrap_or(1)
+                    .max(1);
+                let virtual_time = *k as f64 / weight as f64;
+
+                (p, namespace_id, virtual_time)
+            })
+            .collect::<Vec<_>>();
+        // A stable sort, retaining the order of the partitions of a namespace.
+        partitions.sort_by(|(_, ns_a, vt_a), (_, ns_b, vt_b)| {
+            vt_a.total_cmp(vt_b).then_with(|| ns_a.cmp(ns_b))
+        });
+
+        self.report_backlog(
+            counts
+                .into_iter()
+                .filter_map(|(namespace_id, n)| namespace_id.map(|id| (id, n)))
+                .collect(),
+        );
+
+        partitions.into_iter().map(|(p, _, _)| p).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::TestCatalog;
+    use metric::{assert_counter, Attributes};
+
+    use crate::MockPartitionsSource;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let source = FairSharePartitionsSource::new(
+            BackoffConfig::default(),
+            TestCatalog::new().catalog(),
+            MockPartitionsSource::new(vec![]),
+            &metric::Registry::default(),
+        );
+        assert_eq!(source.to_string(), "fair_share(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_orders_by_weight() {
+        let catalog = TestCatalog::new();
+        let metrics = metric::Registry::default();
+
+        let ns_a = catalog.create_namespace_1hr_retention("ns_a").await;
+        let ns_b = catalog.create_namespace_1hr_retention("ns_b").await;
+        ns_b.update_compaction_weight(2).await;
+
+        let table_a = ns_a.create_table("table").await;
+        let table_b = ns_b.create_table("table").await;
+
+        let mut a = vec![];
+        let mut b = vec![];
+        for key in ["1", "2", "3"] {
+            a.push(table_a.create_partition(key).await.partition.id);
+            b.push(table_b.create_partition(key).await.partition.id);
+        }
+
+        let inner = [a.clone(), b.clone()].concat();
+        let source = FairSharePartitionsSource::new(
+            BackoffConfig::default(),
+            catalog.catalog(),
+            MockPartitionsSource::new(inner),
+            &metrics,
+        );
+
+        // ns_b, with twice the weight, gets two partitions for each of ns_a.
+        assert_eq!(
+            source.fetch().await[..],
+            [b[0], a[0], b[1], b[2], a[1], a[2]]
+        );
+
+        let attr_a =
+            Attributes::from([("namespace_id", Cow::Owned(ns_a.namespace.id.to_string()))]);
+        let attr_b =
+            Attributes::from([("namespace_id", Cow::Owned(ns_b.namespace.id.to_string()))]);
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_fair_share_backlog_partitions",
+            labels = attr_a.clone(),
+            value = 3,
+        );
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_fair_share_backlog_partitions",
+            labels = attr_b.clone(),
+            value = 3,
+        );
+
+        // Once a namespace has nothing left to compact, its backlog is reset.
+        source.inner.set(vec![b[0]]);
+        assert_eq!(source.fetch().await[..], [b[0]]);
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_fair_share_backlog_partitions",
+            labels = attr_a,
+            value = 0,
+        );
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_fair_share_backlog_partitions",
+            labels = attr_b,
+            value = 1,
+        );
+    }
+}
@@ -5,5 +5,7 @@
 //! are limited to the PartitionId and metadata only, and not dependent upon any file IO.
 pub(crate) mod catalog_all;
 pub(crate) mod catalog_to_compact;
+pub(crate) mod fair_share;
 pub(crate) mod filter;
 pub(crate) mod never_skipped;
+pub(crate) mod prioritised;
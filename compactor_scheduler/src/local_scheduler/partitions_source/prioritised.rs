@@ -0,0 +1,104 @@
+use std::{fmt::Display, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::PartitionId;
+
+use crate::{PartitionsSource, ReadAmplificationScores};
+
+/// An implementation of [`PartitionsSource`] that orders the results of
+/// another [`PartitionsSource`] by descending [`ReadAmplificationScores`].
+///
+/// Partitions without a score retain their relative order, after all scored
+/// partitions.
+#[derive(Debug)]
+pub(crate) struct PrioritisedPartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    scores: Arc<ReadAmplificationScores>,
+    inner: T,
+}
+
+impl<T> PrioritisedPartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    /// Create a new [`PrioritisedPartitionsSource`].
+    pub(crate) fn new(scores: Arc<ReadAmplificationScores>, inner: T) -> Self {
+        Self { scores, inner }
+    }
+}
+
+impl<T> Display for PrioritisedPartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "prioritised({})", self.inner)
+    }
+}
+
+#[async_trait]
+impl<T> PartitionsSource for PrioritisedPartitionsSource<T>
+where
+    T: PartitionsSource,
+{
+    async fn fetch(&self) -> Vec<PartitionId> {
+        let partitions = self.inner.fetch().await;
+        let scores = self.scores.scores(&partitions);
+
+        let mut partitions = partitions.into_iter().zip(scores).collect::<Vec<_>>();
+        // A stable sort, retaining the order of equally scored partitions.
+        partitions.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+        partitions.into_iter().map(|(p, _)| p).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use iox_time::{MockProvider, Time};
+
+    use crate::MockPartitionsSource;
+
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let source = PrioritisedPartitionsSource::new(
+            Arc::new(ReadAmplificationScores::new(
+                Duration::from_secs(60),
+                Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+            )),
+            MockPartitionsSource::new(vec![]),
+        );
+        assert_eq!(source.to_string(), "prioritised(mock)");
+    }
+
+    #[tokio::test]
+    async fn test_orders_by_score() {
+        let p_1 = PartitionId::new(1);
+        let p_2 = PartitionId::new(2);
+        let p_3 = PartitionId::new(3);
+        let p_4 = PartitionId::new(4);
+        let p_5 = PartitionId::new(5);
+
+        let scores = Arc::new(ReadAmplificationScores::new(
+            Duration::from_secs(60),
+            Arc::new(MockProvider::new(Time::from_timestamp_nanos(0))),
+        ));
+        scores.record(p_2, 1, 2, 0);
+        scores.record(p_4, 1, 11, 0);
+        // Not a candidate for compaction.
+        scores.record(p_5, 1, 100, 0);
+
+        let source = PrioritisedPartitionsSource::new(
+            scores,
+            MockPartitionsSource::new(vec![p_1, p_2, p_3, p_4]),
+        );
+
+        assert_eq!(source.fetch().await[..], [p_4, p_2, p_1, p_3]);
+    }
+}
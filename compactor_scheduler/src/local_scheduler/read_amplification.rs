@@ -0,0 +1,172 @@
+//! Read amplification scores of partitions, as reported by the queriers.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use data_types::PartitionId;
+use iox_time::{Time, TimeProvider};
+use parking_lot::Mutex;
+
+/// Deduplicating this many rows at query time is considered as expensive as
+/// scanning one additional parquet file.
+const DEDUP_ROWS_PER_FILE: f64 = 100_000.0;
+
+/// Scores that have decayed below this value are forgotten.
+const MIN_SCORE: f64 = 0.01;
+
+/// The read amplification score of a partition at `updated_at`.
+#[derive(Debug, Clone, Copy)]
+struct Score {
+    value: f64,
+    updated_at: Time,
+}
+
+impl Score {
+    /// The value of this score at `now`, decayed exponentially with the given
+    /// `half_life`.
+    fn decayed(&self, now: Time, half_life: Duration) -> f64 {
+        let elapsed = now
+            .checked_duration_since(self.updated_at)
+            .unwrap_or_default();
+        self.value * 0.5_f64.powf(elapsed.as_secs_f64() / half_life.as_secs_f64())
+    }
+}
+
+/// Per-partition read amplification scores, used to compact the partitions
+/// that are most expensive to query first.
+///
+/// The score of a partition is the number of parquet files scanned by queries
+/// beyond the single file a fully compacted partition needs, plus the cost of
+/// the rows deduplicated at query time. Scores decay exponentially with the
+/// configured half-life, so partitions that are no longer queried (or that
+/// were compacted) lose their priority.
+#[derive(Debug)]
+pub struct ReadAmplificationScores {
+    half_life: Duration,
+    time_provider: Arc<dyn TimeProvider>,
+    scores: Mutex<HashMap<PartitionId, Score>>,
+}
+
+impl ReadAmplificationScores {
+    /// Create a new, empty set of scores decaying with `half_life`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_life` is zero.
+    pub fn new(half_life: Duration, time_provider: Arc<dyn TimeProvider>) -> Self {
+        assert!(!half_life.is_zero(), "half-life must be non-zero");
+
+        Self {
+            half_life,
+            time_provider,
+            scores: Default::default(),
+        }
+    }
+
+    /// Record the read cost of `partition_id` accumulated over `queries`
+    /// queries, as reported by a querier.
+    pub fn record(
+        &self,
+        partition_id: PartitionId,
+        queries: u64,
+        files_scanned: u64,
+        dedup_rows: u64,
+    ) {
+        let cost =
+            files_scanned.saturating_sub(queries) as f64 + dedup_rows as f64 / DEDUP_ROWS_PER_FILE;
+        if cost <= 0.0 {
+            return;
+        }
+
+        let now = self.time_provider.now();
+        let mut scores = self.scores.lock();
+        let score = scores.entry(partition_id).or_insert(Score {
+            value: 0.0,
+            updated_at: now,
+        });
+        *score = Score {
+            value: score.decayed(now, self.half_life) + cost,
+            updated_at: now,
+        };
+    }
+
+    /// Return the current score of `partition_id`, or 0 if it has none.
+    pub fn score(&self, partition_id: PartitionId) -> f64 {
+        let now = self.time_provider.now();
+        self.scores
+            .lock()
+            .get(&partition_id)
+            .map(|score| score.decayed(now, self.half_life))
+            .unwrap_or_default()
+    }
+
+    /// Return the current scores of `partitions` (0 for partitions without a
+    /// score), forgetting all scores that have fully decayed.
+    pub(crate) fn scores(&self, partitions: &[PartitionId]) -> Vec<f64> {
+        let now = self.time_provider.now();
+        let mut scores = self.scores.lock();
+
+        scores.retain(|_, score| score.decayed(now, self.half_life) >= MIN_SCORE);
+
+        partitions
+            .iter()
+            .map(|p| {
+                scores
+                    .get(p)
+                    .map(|score| score.decayed(now, self.half_life))
+                    .unwrap_or_default()
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_time::MockProvider;
+
+    use super::*;
+
+    const HALF_LIFE: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn test_score() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let scores = ReadAmplificationScores::new(HALF_LIFE, Arc::clone(&time_provider) as _);
+
+        let p1 = PartitionId::new(1);
+        let p2 = PartitionId::new(2);
+        let p3 = PartitionId::new(3);
+
+        // 2 queries scanning 5 files in total, 3 more than needed, and
+        // deduplicating 200k rows.
+        scores.record(p1, 2, 5, 200_000);
+        assert_eq!(scores.score(p1), 5.0);
+
+        // A fully compacted partition has no read amplification.
+        scores.record(p2, 3, 3, 0);
+        assert_eq!(scores.score(p2), 0.0);
+
+        // Scores decay with the half-life and accumulate.
+        time_provider.inc(HALF_LIFE);
+        assert_eq!(scores.score(p1), 2.5);
+        scores.record(p1, 1, 2, 0);
+        assert_eq!(scores.score(p1), 3.5);
+
+        assert_eq!(scores.scores(&[p3, p1, p2]), vec![0.0, 3.5, 0.0]);
+    }
+
+    #[test]
+    fn test_decayed_scores_forgotten() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let scores = ReadAmplificationScores::new(HALF_LIFE, Arc::clone(&time_provider) as _);
+
+        let p1 = PartitionId::new(1);
+        scores.record(p1, 1, 2, 0);
+        assert_eq!(scores.scores(&[p1]), vec![1.0]);
+        assert_eq!(scores.scores.lock().len(), 1);
+
+        // After 7 half-lives the score is below the minimum.
+        time_provider.inc(HALF_LIFE * 7);
+        assert_eq!(scores.scores(&[p1]), vec![0.0]);
+        assert!(scores.scores.lock().is_empty());
+    }
+}
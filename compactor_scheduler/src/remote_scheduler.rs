@@ -0,0 +1,33 @@
+//! Internals used by the remote scheduler.
+//!
+//! The remote scheduler is split into two halves:
+//!
+//! * The [`LeaseManager`], run by the standalone compactor scheduler service,
+//!   which leases the jobs of a (non-sharded) [`LocalScheduler`] to any
+//!   registered compactor.
+//! * The [`RemoteScheduler`], used by each compactor to lease jobs from,
+//!   and commit changes through, the compactor scheduler service.
+//!
+//! [`LocalScheduler`]: crate::LocalScheduler
+pub(crate) mod client;
+pub(crate) mod leases;
+pub(crate) mod proto;
+
+use tonic::transport::Uri;
+
+pub(crate) use self::client::RemoteScheduler;
+pub use self::leases::{CompactorId, LeaseError, LeaseManager};
+
+/// Configuration specific to the remote scheduler.
+#[derive(Debug, Clone)]
+pub struct RemoteSchedulerConfig {
+    /// The gRPC address of the compactor scheduler service.
+    pub scheduler_address: Uri,
+    /// The identifier this compactor registers with the scheduler service.
+    ///
+    /// Must be unique amongst the compactors using the same scheduler.
+    pub compactor_id: CompactorId,
+    /// The maximum number of jobs leased from the scheduler in a single
+    /// request.
+    pub max_jobs_per_lease: u32,
+}
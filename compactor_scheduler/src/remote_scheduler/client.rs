@@ -0,0 +1,286 @@
+use std::{collections::HashSet, sync::Arc, time::Duration};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use data_types::ParquetFileId;
+use generated_types::influxdata::iox::compactor_scheduler::v1::{
+    self as proto, compactor_scheduler_service_client::CompactorSchedulerServiceClient,
+    end_job_request::EndAction, update_job_status_request::Status,
+};
+use observability_deps::tracing::{debug, info, warn};
+use parking_lot::Mutex;
+use tokio::task::JoinHandle;
+use tonic::{
+    transport::{Channel, Endpoint, Uri},
+    Code,
+};
+use uuid::Uuid;
+
+use super::{CompactorId, RemoteSchedulerConfig};
+use crate::{
+    CompactionJob, CompactionJobEnd, CompactionJobEndVariant, CompactionJobStatus,
+    CompactionJobStatusResponse, CompactionJobStatusVariant, Scheduler, SkipReason,
+};
+
+type Client = CompactorSchedulerServiceClient<Channel>;
+
+/// The minimum interval between heartbeats sent to the scheduler service.
+const MIN_HEARTBEAT_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Implementation of the scheduler, leasing jobs from the remote compactor
+/// scheduler service.
+///
+/// The leases of all jobs handed out by [`Scheduler::get_jobs()`] and not yet
+/// ended are renewed by a background heartbeat task.
+#[derive(Debug)]
+pub(crate) struct RemoteScheduler {
+    client: Client,
+    scheduler_address: Uri,
+    compactor_id: CompactorId,
+    max_jobs_per_lease: u32,
+    backoff_config: BackoffConfig,
+
+    /// The UUIDs of the jobs leased to this compactor that have not ended.
+    held_jobs: Arc<Mutex<HashSet<Uuid>>>,
+    heartbeat_task: JoinHandle<()>,
+}
+
+impl RemoteScheduler {
+    /// Create a new [`RemoteScheduler`].
+    ///
+    /// The connection to the scheduler service is established lazily.
+    pub(crate) fn new(config: RemoteSchedulerConfig, backoff_config: BackoffConfig) -> Self {
+        let RemoteSchedulerConfig {
+            scheduler_address,
+            compactor_id,
+            max_jobs_per_lease,
+        } = config;
+
+        let client = Client::new(Endpoint::from(scheduler_address.clone()).connect_lazy());
+        let held_jobs = Arc::new(Mutex::new(HashSet::new()));
+
+        let heartbeat_task = tokio::spawn(heartbeat(
+            client.clone(),
+            compactor_id.clone(),
+            Arc::clone(&held_jobs),
+            backoff_config.clone(),
+        ));
+
+        Self {
+            client,
+            scheduler_address,
+            compactor_id,
+            max_jobs_per_lease,
+            backoff_config,
+            held_jobs,
+            heartbeat_task,
+        }
+    }
+}
+
+impl Drop for RemoteScheduler {
+    fn drop(&mut self) {
+        self.heartbeat_task.abort();
+    }
+}
+
+#[async_trait]
+impl Scheduler for RemoteScheduler {
+    async fn get_jobs(&self) -> Vec<CompactionJob> {
+        let request = proto::LeaseJobsRequest {
+            compactor_id: self.compactor_id.to_string(),
+            max_jobs: self.max_jobs_per_lease,
+        };
+
+        let jobs = Backoff::new(&self.backoff_config)
+            .retry_all_errors("lease compaction jobs", || {
+                let mut client = self.client.clone();
+                let request = request.clone();
+                async move {
+                    match client.lease_jobs(request.clone()).await {
+                        // The scheduler has not seen this compactor (yet), or
+                        // deregistered it after missing heartbeats.
+                        Err(e) if e.code() == Code::FailedPrecondition => {
+                            register(&mut client, &self.compactor_id).await?;
+                            client.lease_jobs(request).await
+                        }
+                        res => res,
+                    }
+                }
+            })
+            .await
+            .expect("retry forever")
+            .into_inner()
+            .jobs;
+
+        let jobs = jobs
+            .into_iter()
+            .filter_map(|job| match CompactionJob::try_from(job) {
+                Ok(job) => Some(job),
+                Err(e) => {
+                    warn!(error=%e, "invalid compaction job leased from scheduler");
+                    None
+                }
+            })
+            .collect::<Vec<_>>();
+
+        self.held_jobs
+            .lock()
+            .extend(jobs.iter().map(|job| job.uuid()));
+
+        jobs
+    }
+
+    async fn update_job_status(
+        &self,
+        job_status: CompactionJobStatus,
+    ) -> Result<CompactionJobStatusResponse, Box<dyn std::error::Error + Send + Sync>> {
+        let CompactionJobStatus { job, status } = job_status;
+
+        let (status, is_commit) = match status {
+            CompactionJobStatusVariant::Update(update) => (Status::Update(update.into()), true),
+            CompactionJobStatusVariant::Error(kind) => (Status::Error(kind.into()), false),
+        };
+
+        let response = self
+            .client
+            .clone()
+            .update_job_status(proto::UpdateJobStatusRequest {
+                compactor_id: self.compactor_id.to_string(),
+                job: Some(job.into()),
+                status: Some(status),
+            })
+            .await?
+            .into_inner();
+
+        Ok(if is_commit {
+            CompactionJobStatusResponse::CreatedParquetFiles(
+                response
+                    .created_file_ids
+                    .into_iter()
+                    .map(ParquetFileId::new)
+                    .collect(),
+            )
+        } else {
+            CompactionJobStatusResponse::Ack
+        })
+    }
+
+    async fn end_job(
+        &self,
+        end: CompactionJobEnd,
+    ) -> Result<(), Box<dyn std::error::Error + Send + Sync>> {
+        let CompactionJobEnd { job, end_action } = end;
+
+        // Stop renewing the lease, even if the request fails - the lease will
+        // expire and the job reassigned.
+        self.held_jobs.lock().remove(&job.uuid());
+
+        let end_action = match end_action {
+            CompactionJobEndVariant::RequestToSkip(SkipReason(reason)) => {
+                EndAction::SkipReason(reason)
+            }
+            CompactionJobEndVariant::Complete => {
+                EndAction::Complete(proto::end_job_request::Complete {})
+            }
+        };
+
+        self.client
+            .clone()
+            .end_job(proto::EndJobRequest {
+                compactor_id: self.compactor_id.to_string(),
+                job: Some(job.into()),
+                end_action: Some(end_action),
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for RemoteScheduler {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "remote_compaction_scheduler({})", self.scheduler_address)
+    }
+}
+
+/// Register the compactor with the scheduler service, returning the lease
+/// duration.
+async fn register(
+    client: &mut Client,
+    compactor_id: &CompactorId,
+) -> Result<Duration, tonic::Status> {
+    let response = client
+        .register_compactor(proto::RegisterCompactorRequest {
+            compactor_id: compactor_id.to_string(),
+        })
+        .await?
+        .into_inner();
+
+    info!(%compactor_id, "registered with compactor scheduler");
+
+    Ok(Duration::from_millis(response.lease_duration_ms))
+}
+
+/// Periodically renew the leases of the jobs in `held_jobs`.
+async fn heartbeat(
+    mut client: Client,
+    compactor_id: CompactorId,
+    held_jobs: Arc<Mutex<HashSet<Uuid>>>,
+    backoff_config: BackoffConfig,
+) {
+    let lease_duration = Backoff::new(&backoff_config)
+        .retry_all_errors("register compactor", || {
+            let mut client = client.clone();
+            let compactor_id = compactor_id.clone();
+            async move { register(&mut client, &compactor_id).await }
+        })
+        .await
+        .expect("retry forever");
+
+    // Renew leases well within the lease duration, tolerating a lost
+    // heartbeat.
+    let mut interval = tokio::time::interval((lease_duration / 3).max(MIN_HEARTBEAT_INTERVAL));
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let job_uuids = held_jobs
+            .lock()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        let response = client
+            .heartbeat(proto::HeartbeatRequest {
+                compactor_id: compactor_id.to_string(),
+                job_uuids,
+            })
+            .await;
+
+        match response {
+            Ok(response) => {
+                let lost = response.into_inner().lost_job_uuids;
+                if !lost.is_empty() {
+                    warn!(%compactor_id, ?lost, "lost compaction job leases");
+
+                    let mut held_jobs = held_jobs.lock();
+                    for uuid in lost.iter().filter_map(|uuid| uuid.parse::<Uuid>().ok()) {
+                        held_jobs.remove(&uuid);
+                    }
+                }
+                debug!(%compactor_id, "sent heartbeat to compactor scheduler");
+            }
+            Err(e) if e.code() == Code::FailedPrecondition => {
+                warn!(%compactor_id, "deregistered by compactor scheduler, registering again");
+                if let Err(e) = register(&mut client, &compactor_id).await {
+                    warn!(%compactor_id, error=%e, "failed to register with compactor scheduler");
+                }
+            }
+            Err(e) => {
+                warn!(%compactor_id, error=%e, "failed to send heartbeat to compactor scheduler");
+            }
+        }
+    }
+}
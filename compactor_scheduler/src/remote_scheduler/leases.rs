@@ -0,0 +1,576 @@
+use std::{
+    collections::{hash_map::Entry, HashMap, VecDeque},
+    sync::Arc,
+    time::Duration,
+};
+
+use data_types::PartitionId;
+use iox_time::{Time, TimeProvider};
+use metric::{U64Counter, U64Gauge};
+use observability_deps::tracing::{info, warn};
+use parking_lot::{Mutex, MutexGuard};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{
+    CompactionJob, CompactionJobEnd, CompactionJobStatus, CompactionJobStatusResponse,
+    CompactionJobStatusVariant, Scheduler,
+};
+
+/// The identifier of a compactor registered with a [`LeaseManager`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompactorId(Arc<str>);
+
+impl CompactorId {
+    /// Create a new [`CompactorId`].
+    pub fn new(id: impl Into<Arc<str>>) -> Self {
+        Self(id.into())
+    }
+}
+
+impl std::fmt::Display for CompactorId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// Errors returned by the [`LeaseManager`].
+#[derive(Debug, Error)]
+pub enum LeaseError {
+    /// The compactor is not registered, or was deregistered after failing to
+    /// contact the scheduler within the lease duration.
+    #[error("compactor {0} is not registered")]
+    UnknownCompactor(CompactorId),
+
+    /// The job is not (or no longer) leased to the compactor.
+    #[error("job {job} is not leased to compactor {compactor}")]
+    LeaseNotHeld {
+        /// The UUID of the job.
+        job: Uuid,
+        /// The compactor that does not hold the lease.
+        compactor: CompactorId,
+    },
+
+    /// A commit was sent for a partition other than the one leased.
+    #[error("commit for partition {commit} does not match leased partition {leased}")]
+    PartitionMismatch {
+        /// The partition of the leased job.
+        leased: PartitionId,
+        /// The partition of the commit.
+        commit: PartitionId,
+    },
+
+    /// The underlying scheduler returned an error.
+    #[error(transparent)]
+    Scheduler(Box<dyn std::error::Error + Send + Sync>),
+}
+
+#[derive(Debug)]
+struct Lease {
+    job: CompactionJob,
+    compactor: CompactorId,
+    expires_at: Time,
+}
+
+#[derive(Debug, Default)]
+struct LeaseState {
+    /// Registered compactors, and the time each last contacted the scheduler.
+    compactors: HashMap<CompactorId, Time>,
+    /// Jobs leased to a compactor, keyed by job UUID.
+    leases: HashMap<Uuid, Lease>,
+    /// Jobs waiting to be leased.
+    pending: VecDeque<CompactionJob>,
+}
+
+/// Leases the jobs of an inner [`Scheduler`] to registered compactors.
+///
+/// A lease is renewed each time the compactor holding it sends a heartbeat or
+/// a status update for the job. Leases that are not renewed within the lease
+/// duration expire, and the job is reassigned with a new UUID so that status
+/// updates (and therefore commits) from the previous holder are rejected.
+///
+/// Expiry is evaluated lazily, whenever the [`LeaseManager`] is called.
+#[derive(Debug)]
+pub struct LeaseManager {
+    inner: Arc<dyn Scheduler>,
+    time_provider: Arc<dyn TimeProvider>,
+    lease_duration: Duration,
+    state: Mutex<LeaseState>,
+
+    registered_compactors: U64Gauge,
+    leased_jobs: U64Gauge,
+    expired_leases: U64Counter,
+}
+
+impl LeaseManager {
+    /// Create a new [`LeaseManager`], leasing the jobs of `inner` for
+    /// `lease_duration`.
+    pub fn new(
+        inner: Arc<dyn Scheduler>,
+        time_provider: Arc<dyn TimeProvider>,
+        lease_duration: Duration,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let registered_compactors = metrics
+            .register_metric::<U64Gauge>(
+                "compactor_scheduler_registered_compactors",
+                "the number of compactors registered with the compactor scheduler",
+            )
+            .recorder(&[]);
+        let leased_jobs = metrics
+            .register_metric::<U64Gauge>(
+                "compactor_scheduler_leased_jobs",
+                "the number of compaction jobs currently leased to a compactor",
+            )
+            .recorder(&[]);
+        let expired_leases = metrics
+            .register_metric::<U64Counter>(
+                "compactor_scheduler_expired_job_leases",
+                "the number of compaction job leases that expired and were reassigned",
+            )
+            .recorder(&[]);
+
+        Self {
+            inner,
+            time_provider,
+            lease_duration,
+            state: Default::default(),
+            registered_compactors,
+            leased_jobs,
+            expired_leases,
+        }
+    }
+
+    /// The duration of a job lease.
+    pub fn lease_duration(&self) -> Duration {
+        self.lease_duration
+    }
+
+    /// Register the compactor `id`, allowing it to lease jobs.
+    ///
+    /// Registering an already registered compactor is a no-op.
+    pub fn register_compactor(&self, id: CompactorId) {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock();
+        self.expire(&mut state, now);
+
+        if state.compactors.insert(id.clone(), now).is_none() {
+            info!(compactor_id=%id, "registered compactor");
+        }
+
+        self.update_gauges(&state);
+    }
+
+    /// Lease up to `max_jobs` jobs to the compactor `id`.
+    pub async fn lease_jobs(
+        &self,
+        id: &CompactorId,
+        max_jobs: usize,
+    ) -> Result<Vec<CompactionJob>, LeaseError> {
+        let needs_jobs = self.touch(id)?.pending.is_empty();
+        if needs_jobs {
+            let jobs = self.inner.get_jobs().await;
+            self.state.lock().pending.extend(jobs);
+        }
+
+        let mut state = self.touch(id)?;
+        let expires_at = self.time_provider.now() + self.lease_duration;
+
+        let n = max_jobs.min(state.pending.len());
+        let jobs = state.pending.drain(..n).collect::<Vec<_>>();
+        for job in &jobs {
+            state.leases.insert(
+                job.uuid(),
+                Lease {
+                    job: job.clone(),
+                    compactor: id.clone(),
+                    expires_at,
+                },
+            );
+        }
+
+        self.update_gauges(&state);
+
+        Ok(jobs)
+    }
+
+    /// Renew the leases held by the compactor `id` for the jobs in
+    /// `job_uuids`.
+    ///
+    /// Returns the subset of `job_uuids` no longer leased to the compactor.
+    pub fn heartbeat(
+        &self,
+        id: &CompactorId,
+        job_uuids: impl IntoIterator<Item = Uuid>,
+    ) -> Result<Vec<Uuid>, LeaseError> {
+        let mut state = self.touch(id)?;
+        let expires_at = self.time_provider.now() + self.lease_duration;
+
+        Ok(job_uuids
+            .into_iter()
+            .filter(|uuid| Self::renew(&mut state, id, *uuid, expires_at).is_err())
+            .collect())
+    }
+
+    /// Pass the status update of a job leased to the compactor `id` to the
+    /// inner [`Scheduler`], renewing the lease.
+    pub async fn update_job_status(
+        &self,
+        id: &CompactorId,
+        job_status: CompactionJobStatus,
+    ) -> Result<CompactionJobStatusResponse, LeaseError> {
+        let job = {
+            let mut state = self.touch(id)?;
+            let expires_at = self.time_provider.now() + self.lease_duration;
+            Self::renew(&mut state, id, job_status.job.uuid(), expires_at)?
+        };
+
+        if let CompactionJobStatusVariant::Update(update) = &job_status.status {
+            if update.partition_id != job.partition_id {
+                return Err(LeaseError::PartitionMismatch {
+                    leased: job.partition_id,
+                    commit: update.partition_id,
+                });
+            }
+        }
+
+        self.inner
+            .update_job_status(CompactionJobStatus {
+                job,
+                status: job_status.status,
+            })
+            .await
+            .map_err(LeaseError::Scheduler)
+    }
+
+    /// End a job leased to the compactor `id`, releasing the lease.
+    pub async fn end_job(&self, id: &CompactorId, end: CompactionJobEnd) -> Result<(), LeaseError> {
+        let job = {
+            let mut state = self.touch(id)?;
+            let job = match state.leases.entry(end.job.uuid()) {
+                Entry::Occupied(lease) if lease.get().compactor == *id => lease.remove().job,
+                _ => {
+                    return Err(LeaseError::LeaseNotHeld {
+                        job: end.job.uuid(),
+                        compactor: id.clone(),
+                    })
+                }
+            };
+            self.update_gauges(&state);
+            job
+        };
+
+        self.inner
+            .end_job(CompactionJobEnd {
+                job,
+                end_action: end.end_action,
+            })
+            .await
+            .map_err(LeaseError::Scheduler)
+    }
+
+    /// Lock the state, expiring leases and marking the compactor `id` as
+    /// seen.
+    fn touch(&self, id: &CompactorId) -> Result<MutexGuard<'_, LeaseState>, LeaseError> {
+        let now = self.time_provider.now();
+        let mut state = self.state.lock();
+        self.expire(&mut state, now);
+
+        match state.compactors.get_mut(id) {
+            Some(last_seen) => *last_seen = now,
+            None => return Err(LeaseError::UnknownCompactor(id.clone())),
+        }
+
+        Ok(state)
+    }
+
+    /// Extend the lease of the job `uuid` held by compactor `id`, returning
+    /// the leased job.
+    fn renew(
+        state: &mut LeaseState,
+        id: &CompactorId,
+        uuid: Uuid,
+        expires_at: Time,
+    ) -> Result<CompactionJob, LeaseError> {
+        match state.leases.get_mut(&uuid) {
+            Some(lease) if lease.compactor == *id => {
+                lease.expires_at = expires_at;
+                Ok(lease.job.clone())
+            }
+            _ => Err(LeaseError::LeaseNotHeld {
+                job: uuid,
+                compactor: id.clone(),
+            }),
+        }
+    }
+
+    /// Deregister compactors that have not been seen within the lease
+    /// duration, and reassign the jobs of expired leases.
+    fn expire(&self, state: &mut LeaseState, now: Time) {
+        let lease_duration = self.lease_duration;
+        state.compactors.retain(|id, last_seen| {
+            let alive = *last_seen + lease_duration > now;
+            if !alive {
+                warn!(compactor_id=%id, "deregistered unresponsive compactor");
+            }
+            alive
+        });
+
+        let expired = state
+            .leases
+            .iter()
+            .filter(|(_, lease)| lease.expires_at <= now)
+            .map(|(uuid, _)| *uuid)
+            .collect::<Vec<_>>();
+
+        for uuid in expired {
+            let lease = state.leases.remove(&uuid).expect("lease exists");
+            warn!(
+                compactor_id=%lease.compactor,
+                job_uuid=%uuid,
+                partition_id=%lease.job.partition_id,
+                "compaction job lease expired, reassigning job"
+            );
+
+            // Reassign the partition ahead of newly sourced jobs, under a new
+            // UUID so the previous holder can no longer update it.
+            state
+                .pending
+                .push_front(CompactionJob::new(lease.job.partition_id));
+            self.expired_leases.inc(1);
+        }
+    }
+
+    fn update_gauges(&self, state: &LeaseState) {
+        self.registered_compactors
+            .set(state.compactors.len() as u64);
+        self.leased_jobs.set(state.leases.len() as u64);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use iox_time::MockProvider;
+    use metric::assert_counter;
+
+    use super::*;
+    use crate::{
+        CommitUpdate, CompactionJobEndVariant, LocalSchedulerConfig, PartitionsSourceConfig,
+        SchedulerConfig,
+    };
+
+    const LEASE_DURATION: Duration = Duration::from_secs(30);
+
+    fn new_manager(
+        partitions: &[i64],
+        time_provider: Arc<MockProvider>,
+        metrics: &metric::Registry,
+    ) -> LeaseManager {
+        let inner = crate::create_scheduler(
+            SchedulerConfig::Local(LocalSchedulerConfig {
+                partitions_source_config: PartitionsSourceConfig::Fixed(
+                    partitions.iter().copied().map(PartitionId::new).collect(),
+                ),
+                ignore_partition_skip_marker: true,
+                ..Default::default()
+            }),
+            iox_tests::TestCatalog::new().catalog(),
+            Arc::clone(&time_provider) as _,
+            Arc::new(metric::Registry::default()),
+            true,
+        );
+
+        LeaseManager::new(inner, time_provider, LEASE_DURATION, metrics)
+    }
+
+    #[tokio::test]
+    async fn test_jobs_leased_once() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = metric::Registry::default();
+        let manager = new_manager(&[1, 2, 3], Arc::clone(&time_provider), &metrics);
+
+        let a = CompactorId::new("a");
+        let b = CompactorId::new("b");
+
+        // Unregistered compactors cannot lease jobs.
+        assert_matches!(
+            manager.lease_jobs(&a, 10).await,
+            Err(LeaseError::UnknownCompactor(id)) if id == a
+        );
+
+        manager.register_compactor(a.clone());
+        manager.register_compactor(b.clone());
+
+        let a_jobs = manager.lease_jobs(&a, 2).await.unwrap();
+        assert_eq!(a_jobs.len(), 2);
+        let b_jobs = manager.lease_jobs(&b, 2).await.unwrap();
+        assert_eq!(b_jobs.len(), 1);
+
+        let mut partitions = a_jobs
+            .iter()
+            .chain(&b_jobs)
+            .map(|j| j.partition_id.get())
+            .collect::<Vec<_>>();
+        partitions.sort();
+        assert_eq!(partitions, [1, 2, 3]);
+
+        // All partitions are leased.
+        assert!(manager.lease_jobs(&b, 2).await.unwrap().is_empty());
+
+        // A compactor cannot end a job leased to another.
+        assert_matches!(
+            manager
+                .end_job(
+                    &b,
+                    CompactionJobEnd {
+                        job: a_jobs[0].clone(),
+                        end_action: CompactionJobEndVariant::Complete,
+                    }
+                )
+                .await,
+            Err(LeaseError::LeaseNotHeld { job, .. }) if job == a_jobs[0].uuid()
+        );
+
+        manager
+            .end_job(
+                &a,
+                CompactionJobEnd {
+                    job: a_jobs[0].clone(),
+                    end_action: CompactionJobEndVariant::Complete,
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_scheduler_leased_jobs",
+            value = 2,
+        );
+        assert_counter!(
+            metrics,
+            U64Gauge,
+            "compactor_scheduler_registered_compactors",
+            value = 2,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expired_lease_reassigned() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = metric::Registry::default();
+        let manager = new_manager(&[1], Arc::clone(&time_provider), &metrics);
+
+        let a = CompactorId::new("a");
+        let b = CompactorId::new("b");
+        manager.register_compactor(a.clone());
+        manager.register_compactor(b.clone());
+
+        let job = manager.lease_jobs(&a, 1).await.unwrap().pop().unwrap();
+
+        // Heartbeats keep the lease alive beyond the lease duration.
+        for _ in 0..3 {
+            time_provider.inc(LEASE_DURATION / 2);
+            assert!(manager.heartbeat(&a, [job.uuid()]).unwrap().is_empty());
+            manager.heartbeat(&b, []).unwrap();
+        }
+        assert!(manager.lease_jobs(&b, 1).await.unwrap().is_empty());
+
+        // Compactor "a" stops sending heartbeats, while "b" remains active.
+        time_provider.inc(LEASE_DURATION / 2);
+        manager.heartbeat(&b, []).unwrap();
+        time_provider.inc(LEASE_DURATION / 2);
+
+        let reassigned = manager.lease_jobs(&b, 1).await.unwrap().pop().unwrap();
+        assert_eq!(reassigned.partition_id, job.partition_id);
+        assert_ne!(reassigned.uuid(), job.uuid());
+
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "compactor_scheduler_expired_job_leases",
+            value = 1,
+        );
+
+        // Compactor "a" was deregistered, and must register again.
+        assert_matches!(
+            manager.heartbeat(&a, [job.uuid()]),
+            Err(LeaseError::UnknownCompactor(_))
+        );
+        manager.register_compactor(a.clone());
+
+        // It no longer holds the lease, and cannot commit changes.
+        assert_eq!(manager.heartbeat(&a, [job.uuid()]).unwrap(), [job.uuid()]);
+        assert_matches!(
+            manager
+                .update_job_status(
+                    &a,
+                    CompactionJobStatus {
+                        job: job.clone(),
+                        status: CompactionJobStatusVariant::Update(CommitUpdate::new(
+                            job.partition_id,
+                            vec![],
+                            vec![],
+                            vec![],
+                            data_types::CompactionLevel::Final,
+                        )),
+                    }
+                )
+                .await,
+            Err(LeaseError::LeaseNotHeld { .. })
+        );
+
+        // While the new holder can.
+        assert_matches!(
+            manager
+                .update_job_status(
+                    &b,
+                    CompactionJobStatus {
+                        job: reassigned.clone(),
+                        status: CompactionJobStatusVariant::Update(CommitUpdate::new(
+                            reassigned.partition_id,
+                            vec![],
+                            vec![],
+                            vec![],
+                            data_types::CompactionLevel::Final,
+                        )),
+                    }
+                )
+                .await,
+            Ok(CompactionJobStatusResponse::CreatedParquetFiles(_))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_commit_partition_mismatch() {
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+        let metrics = metric::Registry::default();
+        let manager = new_manager(&[1], Arc::clone(&time_provider), &metrics);
+
+        let a = CompactorId::new("a");
+        manager.register_compactor(a.clone());
+        let job = manager.lease_jobs(&a, 1).await.unwrap().pop().unwrap();
+
+        assert_matches!(
+            manager
+                .update_job_status(
+                    &a,
+                    CompactionJobStatus {
+                        job,
+                        status: CompactionJobStatusVariant::Update(CommitUpdate::new(
+                            PartitionId::new(2),
+                            vec![],
+                            vec![],
+                            vec![],
+                            data_types::CompactionLevel::Final,
+                        )),
+                    }
+                )
+                .await,
+            Err(LeaseError::PartitionMismatch { leased, commit })
+                if leased == PartitionId::new(1) && commit == PartitionId::new(2)
+        );
+    }
+}
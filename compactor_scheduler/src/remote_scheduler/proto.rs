@@ -0,0 +1,196 @@
+//! Conversions between the scheduler types and their protobuf representation
+//! used by the compactor scheduler service.
+
+use data_types::{
+    CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, ParquetFileProtoError,
+    PartitionId,
+};
+use generated_types::influxdata::iox::{
+    catalog::v1 as catalog_proto, compactor_scheduler::v1 as proto,
+};
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::{CommitUpdate, CompactionJob, ErrorKind};
+
+/// Errors deserialising a protobuf serialised scheduler type.
+#[derive(Debug, Error)]
+pub enum ProtoError {
+    /// A required field was not specified.
+    #[error("missing required field: {0}")]
+    MissingField(&'static str),
+
+    /// The specified job UUID is invalid.
+    #[error("invalid job uuid: {0}")]
+    InvalidJobUuid(#[from] uuid::Error),
+
+    /// A specified parquet file is invalid.
+    #[error("invalid parquet file: {0}")]
+    InvalidParquetFile(#[from] ParquetFileProtoError),
+
+    /// The specified compaction level value is invalid.
+    #[error("invalid target compaction level: {0}")]
+    InvalidCompactionLevel(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl From<CompactionJob> for proto::CompactionJob {
+    fn from(job: CompactionJob) -> Self {
+        Self {
+            uuid: job.uuid().to_string(),
+            partition_id: job.partition_id.get(),
+        }
+    }
+}
+
+impl TryFrom<proto::CompactionJob> for CompactionJob {
+    type Error = ProtoError;
+
+    fn try_from(job: proto::CompactionJob) -> Result<Self, Self::Error> {
+        Ok(Self::new_with_uuid(
+            job.uuid.parse::<Uuid>()?,
+            PartitionId::new(job.partition_id),
+        ))
+    }
+}
+
+impl From<CommitUpdate> for proto::CommitUpdate {
+    fn from(update: CommitUpdate) -> Self {
+        let CommitUpdate {
+            partition_id,
+            delete,
+            upgrade,
+            target_level,
+            create,
+        } = update;
+
+        Self {
+            partition_id: partition_id.get(),
+            delete: delete.into_iter().map(Into::into).collect(),
+            upgrade: upgrade.into_iter().map(Into::into).collect(),
+            target_level: target_level as i32,
+            // Files to be created have no catalog ID yet.
+            create: create
+                .into_iter()
+                .map(|params| ParquetFile::from_params(params, ParquetFileId::new(0)).into())
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<proto::CommitUpdate> for CommitUpdate {
+    type Error = ProtoError;
+
+    fn try_from(update: proto::CommitUpdate) -> Result<Self, Self::Error> {
+        let files = |files: Vec<catalog_proto::ParquetFile>| {
+            files
+                .into_iter()
+                .map(ParquetFile::try_from)
+                .collect::<Result<Vec<_>, _>>()
+        };
+
+        Ok(Self {
+            partition_id: PartitionId::new(update.partition_id),
+            delete: files(update.delete)?,
+            upgrade: files(update.upgrade)?,
+            target_level: CompactionLevel::try_from(update.target_level)
+                .map_err(ProtoError::InvalidCompactionLevel)?,
+            create: files(update.create)?
+                .into_iter()
+                .map(ParquetFileParams::from)
+                .collect(),
+        })
+    }
+}
+
+impl From<ErrorKind> for proto::CompactionError {
+    fn from(e: ErrorKind) -> Self {
+        use proto::compaction_error::Kind;
+
+        let (kind, message) = match e {
+            ErrorKind::ObjectStore => (Kind::ObjectStore, String::new()),
+            ErrorKind::OutOfMemory => (Kind::OutOfMemory, String::new()),
+            ErrorKind::Timeout => (Kind::Timeout, String::new()),
+            ErrorKind::Unknown(message) => (Kind::Unknown, message),
+        };
+
+        Self {
+            kind: kind.into(),
+            message,
+        }
+    }
+}
+
+impl From<proto::CompactionError> for ErrorKind {
+    fn from(e: proto::CompactionError) -> Self {
+        use proto::compaction_error::Kind;
+
+        match e.kind() {
+            Kind::ObjectStore => Self::ObjectStore,
+            Kind::OutOfMemory => Self::OutOfMemory,
+            Kind::Timeout => Self::Timeout,
+            Kind::Unknown | Kind::Unspecified => Self::Unknown(e.message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::TransitionPartitionId;
+    use iox_tests::ParquetFileBuilder;
+
+    use super::*;
+
+    #[test]
+    fn test_compaction_job_round_trip() {
+        let job = CompactionJob::new(PartitionId::new(42));
+
+        let got = CompactionJob::try_from(proto::CompactionJob::from(job.clone())).unwrap();
+        assert_eq!(got, job);
+
+        let err = CompactionJob::try_from(proto::CompactionJob {
+            uuid: "bananas".into(),
+            partition_id: 42,
+        })
+        .unwrap_err();
+        assert!(matches!(err, ProtoError::InvalidJobUuid(_)));
+    }
+
+    #[test]
+    fn test_commit_update_round_trip() {
+        let partition = TransitionPartitionId::Deprecated(PartitionId::new(42));
+        let file = |id| {
+            ParquetFileBuilder::new(id)
+                .with_partition(partition.clone())
+                .build()
+        };
+
+        let update = CommitUpdate::new(
+            PartitionId::new(42),
+            vec![file(1)],
+            vec![file(2), file(3)],
+            vec![file(4).into()],
+            CompactionLevel::Final,
+        );
+
+        let got = CommitUpdate::try_from(proto::CommitUpdate::from(update)).unwrap();
+
+        assert_eq!(got.partition_id, PartitionId::new(42));
+        assert_eq!(got.delete, vec![file(1)]);
+        assert_eq!(got.upgrade, vec![file(2), file(3)]);
+        assert_eq!(got.target_level, CompactionLevel::Final);
+        assert_eq!(got.create, vec![ParquetFileParams::from(file(4))]);
+    }
+
+    #[test]
+    fn test_error_kind_round_trip() {
+        for kind in [
+            ErrorKind::ObjectStore,
+            ErrorKind::OutOfMemory,
+            ErrorKind::Timeout,
+            ErrorKind::Unknown("bananas".into()),
+        ] {
+            let got = ErrorKind::from(proto::CompactionError::from(kind.clone()));
+            assert_eq!(got, kind);
+        }
+    }
+}
@@ -7,13 +7,17 @@ use async_trait::async_trait;
 use data_types::{CompactionLevel, ParquetFile, ParquetFileId, ParquetFileParams, PartitionId};
 use uuid::Uuid;
 
-use crate::{CommitWrapper, ErrorKind, LocalSchedulerConfig, PartitionsSourceConfig};
+use crate::{
+    CommitWrapper, ErrorKind, LocalSchedulerConfig, PartitionsSourceConfig, RemoteSchedulerConfig,
+};
 
 /// Scheduler configuration.
 #[derive(Debug, Clone)]
 pub enum SchedulerConfig {
     /// Configuration specific to the [`LocalScheduler`](crate::LocalScheduler).
     Local(LocalSchedulerConfig),
+    /// Configuration specific to the [`RemoteScheduler`](crate::RemoteScheduler).
+    Remote(RemoteSchedulerConfig),
 }
 
 impl SchedulerConfig {
@@ -26,8 +30,19 @@ impl SchedulerConfig {
             partitions_source_config: PartitionsSourceConfig::default(),
             commit_wrapper: Some(commit_wrapper),
             ignore_partition_skip_marker: false,
+            read_amplification_scores: None,
+            fair_share: false,
         })
     }
+
+    /// Returns true if the scheduler hands out jobs in a meaningful order,
+    /// which the compactor should then retain.
+    pub fn orders_jobs(&self) -> bool {
+        match self {
+            Self::Local(config) => config.read_amplification_scores.is_some() || config.fair_share,
+            Self::Remote(_) => false,
+        }
+    }
 }
 
 impl Default for SchedulerConfig {
@@ -44,6 +59,8 @@ impl std::fmt::Display for SchedulerConfig {
                 shard_config,
                 partitions_source_config: _,
                 ignore_partition_skip_marker: _,
+                read_amplification_scores: _,
+                fair_share: _,
             }) => match (&shard_config, commit_wrapper) {
                 (None, None) => write!(f, "local_compaction_scheduler_cfg"),
                 (Some(shard_config), None) => {
@@ -57,6 +74,14 @@ impl std::fmt::Display for SchedulerConfig {
                     write!(f, "local_compaction_scheduler_cfg(commit_wrapper=Some)",)
                 }
             },
+            SchedulerConfig::Remote(RemoteSchedulerConfig {
+                scheduler_address,
+                compactor_id,
+                max_jobs_per_lease: _,
+            }) => write!(
+                f,
+                "remote_compaction_scheduler_cfg({scheduler_address},compactor_id={compactor_id})"
+            ),
         }
     }
 }
@@ -81,6 +106,13 @@ impl CompactionJob {
         }
     }
 
+    /// Create job with a given uuid.
+    ///
+    /// Used to reconstruct a job handed out by a remote scheduler.
+    pub(crate) fn new_with_uuid(uuid: Uuid, partition_id: PartitionId) -> Self {
+        Self { uuid, partition_id }
+    }
+
     /// Get job uuid.
     pub fn uuid(&self) -> Uuid {
         self.uuid
@@ -227,4 +259,18 @@ mod tests {
             "local_compaction_scheduler_cfg(commit_wrapper=Some)"
         );
     }
+
+    #[test]
+    fn test_cfg_display_remote() {
+        let config = SchedulerConfig::Remote(RemoteSchedulerConfig {
+            scheduler_address: "http://scheduler:8082".parse().unwrap(),
+            compactor_id: crate::CompactorId::new("compactor-1"),
+            max_jobs_per_lease: 10,
+        });
+
+        assert_eq!(
+            config.to_string(),
+            "remote_compaction_scheduler_cfg(http://scheduler:8082/,compactor_id=compactor-1)"
+        );
+    }
 }
@@ -169,6 +169,8 @@ impl TestSetupBuilder<false> {
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
             max_partition_fetch_queries_per_second: None,
+            rollup_poll_interval: None,
+            sort_key_rewrite_poll_interval: None,
             gossip_bind_address: None,
             gossip_seeds: vec![],
         };
@@ -639,6 +641,10 @@ impl<const WITH_FILES: bool> TestSetupBuilder<WITH_FILES> {
             table_schema: Arc::new(self.table.catalog_schema().await),
             sort_key: self.partition.partition.sort_key(),
             partition_key: self.partition.partition.partition_key.clone(),
+            retention_period_ns: self
+                .table
+                .table
+                .effective_retention_period_ns(self.ns.namespace.retention_period_ns),
         });
 
         TestSetup {
@@ -177,6 +177,18 @@ impl Column {
     }
 }
 
+/// Serialise a [`Column`] object into its protobuf representation.
+impl From<Column> for generated_types::influxdata::iox::table::v1::Column {
+    fn from(value: Column) -> Self {
+        Self {
+            id: value.id.get(),
+            name: value.name,
+            table_id: value.table_id.get(),
+            column_type: value.column_type as i32,
+        }
+    }
+}
+
 /// The column id and its type for a column
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
 pub struct ColumnSchema {
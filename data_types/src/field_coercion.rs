@@ -0,0 +1,198 @@
+//! Rules controlling the conversion of written field values to the type of an
+//! existing column.
+
+use generated_types::influxdata::iox::namespace::v1 as namespace_proto;
+
+use crate::ColumnType;
+
+/// The set of field value type coercions a namespace permits.
+///
+/// A column's type is fixed by the first write to it - by default, any
+/// subsequent write of a value with a different type is rejected as a schema
+/// conflict. Each enabled rule instead converts the written values to the type
+/// of the existing column.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct FieldCoercionRules {
+    /// Convert i64 values written to a f64 column.
+    pub int_to_float: bool,
+    /// Convert u64 values written to an i64 column, if all values are within
+    /// the range of an i64.
+    pub uint_to_int: bool,
+    /// Convert bool values written to a string column.
+    pub bool_to_string: bool,
+    /// Convert i64, u64 and f64 values written to a string column.
+    pub number_to_string: bool,
+}
+
+impl FieldCoercionRules {
+    /// No coercion is permitted.
+    pub const NONE: Self = Self {
+        int_to_float: false,
+        uint_to_int: false,
+        bool_to_string: false,
+        number_to_string: false,
+    };
+
+    const INT_TO_FLOAT: i16 = 1 << 0;
+    const UINT_TO_INT: i16 = 1 << 1;
+    const BOOL_TO_STRING: i16 = 1 << 2;
+    const NUMBER_TO_STRING: i16 = 1 << 3;
+
+    /// Returns true if any coercion rule is enabled.
+    pub fn is_enabled(&self) -> bool {
+        *self != Self::NONE
+    }
+
+    /// Returns true if values of type `from` may be converted to be written to
+    /// an existing column of type `to`.
+    pub fn permits(&self, from: ColumnType, to: ColumnType) -> bool {
+        match (from, to) {
+            (ColumnType::I64, ColumnType::F64) => self.int_to_float,
+            (ColumnType::U64, ColumnType::I64) => self.uint_to_int,
+            (ColumnType::Bool, ColumnType::String) => self.bool_to_string,
+            (ColumnType::I64 | ColumnType::U64 | ColumnType::F64, ColumnType::String) => {
+                self.number_to_string
+            }
+            _ => false,
+        }
+    }
+
+    /// The bitmask representation of these rules, used by the catalog.
+    pub fn bits(&self) -> i16 {
+        let mut bits = 0;
+        if self.int_to_float {
+            bits |= Self::INT_TO_FLOAT;
+        }
+        if self.uint_to_int {
+            bits |= Self::UINT_TO_INT;
+        }
+        if self.bool_to_string {
+            bits |= Self::BOOL_TO_STRING;
+        }
+        if self.number_to_string {
+            bits |= Self::NUMBER_TO_STRING;
+        }
+        bits
+    }
+
+    /// Construct the rules from their bitmask representation, ignoring any
+    /// unknown bits.
+    pub fn from_bits(bits: i16) -> Self {
+        Self {
+            int_to_float: bits & Self::INT_TO_FLOAT != 0,
+            uint_to_int: bits & Self::UINT_TO_INT != 0,
+            bool_to_string: bits & Self::BOOL_TO_STRING != 0,
+            number_to_string: bits & Self::NUMBER_TO_STRING != 0,
+        }
+    }
+}
+
+impl From<namespace_proto::FieldCoercionRules> for FieldCoercionRules {
+    fn from(value: namespace_proto::FieldCoercionRules) -> Self {
+        let namespace_proto::FieldCoercionRules {
+            int_to_float,
+            uint_to_int,
+            bool_to_string,
+            number_to_string,
+        } = value;
+
+        Self {
+            int_to_float,
+            uint_to_int,
+            bool_to_string,
+            number_to_string,
+        }
+    }
+}
+
+impl From<FieldCoercionRules> for namespace_proto::FieldCoercionRules {
+    fn from(value: FieldCoercionRules) -> Self {
+        let FieldCoercionRules {
+            int_to_float,
+            uint_to_int,
+            bool_to_string,
+            number_to_string,
+        } = value;
+
+        Self {
+            int_to_float,
+            uint_to_int,
+            bool_to_string,
+            number_to_string,
+        }
+    }
+}
+
+// Tell sqlx this is an i16 bitmask in the database.
+impl<DB> sqlx::Type<DB> for FieldCoercionRules
+where
+    i16: sqlx::Type<DB>,
+    DB: sqlx::Database,
+{
+    fn type_info() -> DB::TypeInfo {
+        <i16 as sqlx::Type<DB>>::type_info()
+    }
+}
+
+impl<'q, DB> sqlx::Encode<'q, DB> for FieldCoercionRules
+where
+    DB: sqlx::Database,
+    i16: sqlx::Encode<'q, DB>,
+{
+    fn encode_by_ref(
+        &self,
+        buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+    ) -> sqlx::encode::IsNull {
+        <i16 as sqlx::Encode<'_, DB>>::encode_by_ref(&self.bits(), buf)
+    }
+}
+
+impl<'r, DB: ::sqlx::Database> ::sqlx::decode::Decode<'r, DB> for FieldCoercionRules
+where
+    i16: sqlx::Decode<'r, DB>,
+{
+    fn decode(
+        value: <DB as ::sqlx::database::HasValueRef<'r>>::ValueRef,
+    ) -> ::std::result::Result<
+        Self,
+        ::std::boxed::Box<
+            dyn ::std::error::Error + 'static + ::std::marker::Send + ::std::marker::Sync,
+        >,
+    > {
+        let bits = <i16 as ::sqlx::decode::Decode<'r, DB>>::decode(value)?;
+        Ok(Self::from_bits(bits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bits_round_trip() {
+        for bits in 0..16 {
+            assert_eq!(FieldCoercionRules::from_bits(bits).bits(), bits);
+        }
+        assert_eq!(FieldCoercionRules::NONE.bits(), 0);
+        assert_eq!(FieldCoercionRules::default(), FieldCoercionRules::NONE);
+    }
+
+    #[test]
+    fn test_permits() {
+        let rules = FieldCoercionRules {
+            int_to_float: true,
+            number_to_string: true,
+            ..Default::default()
+        };
+
+        assert!(rules.permits(ColumnType::I64, ColumnType::F64));
+        assert!(rules.permits(ColumnType::F64, ColumnType::String));
+        assert!(rules.permits(ColumnType::U64, ColumnType::String));
+        assert!(!rules.permits(ColumnType::U64, ColumnType::I64));
+        assert!(!rules.permits(ColumnType::Bool, ColumnType::String));
+        assert!(!rules.permits(ColumnType::F64, ColumnType::I64));
+        assert!(!rules.permits(ColumnType::Tag, ColumnType::String));
+
+        assert!(!FieldCoercionRules::NONE.permits(ColumnType::I64, ColumnType::F64));
+    }
+}
@@ -21,15 +21,21 @@ use workspace_hack as _;
 
 mod columns;
 pub use columns::*;
+pub mod field_coercion;
+pub use field_coercion::*;
 mod namespace_name;
 pub use namespace_name::*;
 pub mod partition_template;
 use partition_template::*;
 pub mod partition;
 pub use partition::*;
+pub mod rollup;
+pub use rollup::{RollupPolicies, RollupPolicy};
 pub mod sequence_number_set;
 pub mod service_limits;
 pub use service_limits::*;
+pub mod write_rules;
+pub use write_rules::{WriteRule, WriteRules};
 
 use observability_deps::tracing::warn;
 use schema::TIME_COLUMN_NAME;
@@ -115,6 +121,51 @@ impl CompactionLevel {
     }
 }
 
+/// The object store tier holding the data of a [`ParquetFile`].
+#[derive(Debug, Default, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum StorageTier {
+    /// The primary object store, where all files are initially written.
+    #[default]
+    Hot = 0,
+    /// The cold object store, to which files older than the namespace cold
+    /// tier age are relocated.
+    Cold = 1,
+}
+
+impl StorageTier {
+    /// Returns all tiers
+    pub fn all() -> &'static [Self] {
+        &[Self::Hot, Self::Cold]
+    }
+
+    /// Static name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Hot => "hot",
+            Self::Cold => "cold",
+        }
+    }
+}
+
+impl Display for StorageTier {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl TryFrom<i32> for StorageTier {
+    type Error = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        match value {
+            x if x == Self::Hot as i32 => Ok(Self::Hot),
+            x if x == Self::Cold as i32 => Ok(Self::Cold),
+            _ => Err("invalid storage tier value".into()),
+        }
+    }
+}
+
 /// Unique ID for a `Namespace`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, sqlx::Type)]
 #[sqlx(transparent)]
@@ -292,6 +343,73 @@ pub struct Namespace {
     /// The partition template to use for new tables in this namespace either created implicitly or
     /// created without specifying a partition template.
     pub partition_template: NamespacePartitionTemplateOverride,
+    /// Whether writes to this namespace may implicitly create tables and columns.
+    pub schema_mode: NamespaceSchemaMode,
+    /// The field value type conversions applied to writes to this namespace.
+    pub field_coercion: FieldCoercionRules,
+    /// The transformation rules applied to writes to this namespace.
+    pub write_rules: WriteRules,
+    /// The rollup policies materialised by the compactor for this namespace.
+    pub rollup_policies: RollupPolicies,
+    /// The age in ns after which parquet files of this namespace are moved to
+    /// the cold storage tier. None represents never moving files.
+    pub cold_tier_age_ns: Option<i64>,
+    /// The weight of the share of the compactors given to this namespace
+    /// relative to other namespaces, when compacting with fair sharing.
+    pub compaction_weight: i32,
+    /// The number of changes made to the namespace properties routers apply
+    /// to writes (the [`Self::schema_mode`], [`Self::field_coercion`],
+    /// [`Self::write_rules`] and [`Self::partition_template`]).
+    pub properties_version: i64,
+}
+
+/// Controls how the schema of a namespace may change in response to writes.
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash, sqlx::Type)]
+#[repr(i16)]
+pub enum NamespaceSchemaMode {
+    /// Writes implicitly create any tables and columns they reference that do
+    /// not already exist, subject to the namespace service protection limits.
+    #[default]
+    Implicit = 1,
+    /// Writes referencing tables or columns that do not already exist are
+    /// rejected - all tables and columns must be created explicitly ahead of
+    /// time.
+    Explicit = 2,
+}
+
+impl NamespaceSchemaMode {
+    /// The short string description of the mode.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Implicit => "implicit",
+            Self::Explicit => "explicit",
+        }
+    }
+}
+
+impl std::fmt::Display for NamespaceSchemaMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<generated_types::influxdata::iox::namespace::v1::SchemaMode> for NamespaceSchemaMode {
+    fn from(value: generated_types::influxdata::iox::namespace::v1::SchemaMode) -> Self {
+        use generated_types::influxdata::iox::namespace::v1::SchemaMode;
+        match value {
+            SchemaMode::Unspecified | SchemaMode::Implicit => Self::Implicit,
+            SchemaMode::Explicit => Self::Explicit,
+        }
+    }
+}
+
+impl From<NamespaceSchemaMode> for generated_types::influxdata::iox::namespace::v1::SchemaMode {
+    fn from(value: NamespaceSchemaMode) -> Self {
+        match value {
+            NamespaceSchemaMode::Implicit => Self::Implicit,
+            NamespaceSchemaMode::Explicit => Self::Explicit,
+        }
+    }
 }
 
 /// Schema collection for a namespace. This is an in-memory object useful for a schema
@@ -312,6 +430,17 @@ pub struct NamespaceSchema {
     /// The partition template to use for new tables in this namespace either created implicitly or
     /// created without specifying a partition template.
     pub partition_template: NamespacePartitionTemplateOverride,
+    /// Whether writes to this namespace may implicitly create tables and columns.
+    pub schema_mode: NamespaceSchemaMode,
+    /// The field value type conversions applied to writes to this namespace.
+    pub field_coercion: FieldCoercionRules,
+    /// The transformation rules applied to writes to this namespace.
+    pub write_rules: WriteRules,
+    /// The version of the [`Self::schema_mode`], [`Self::field_coercion`],
+    /// [`Self::write_rules`] and [`Self::partition_template`], incremented by
+    /// every change to them. A copy of the namespace schema with a lower
+    /// version carries stale values.
+    pub properties_version: i64,
 }
 
 impl NamespaceSchema {
@@ -324,6 +453,10 @@ impl NamespaceSchema {
             max_tables,
             max_columns_per_table,
             ref partition_template,
+            schema_mode,
+            field_coercion,
+            ref write_rules,
+            properties_version,
             ..
         } = namespace;
 
@@ -334,6 +467,10 @@ impl NamespaceSchema {
             max_columns_per_table,
             retention_period_ns,
             partition_template: partition_template.clone(),
+            schema_mode,
+            field_coercion,
+            write_rules: write_rules.clone(),
+            properties_version,
         }
     }
 }
@@ -395,6 +532,22 @@ pub struct Table {
     pub name: String,
     /// The partition template to use for writes in this table.
     pub partition_template: TablePartitionTemplateOverride,
+    /// The retention period in ns, overriding that of the namespace.
+    /// None means the namespace retention period applies.
+    pub retention_period_ns: Option<i64>,
+    /// The number of changes made to the [`Self::partition_template`].
+    pub partition_template_version: i64,
+}
+
+impl Table {
+    /// Returns the retention period that applies to this table, preferring
+    /// the table override over the given namespace retention period.
+    pub fn effective_retention_period_ns(
+        &self,
+        namespace_retention_ns: Option<i64>,
+    ) -> Option<i64> {
+        self.retention_period_ns.or(namespace_retention_ns)
+    }
 }
 
 /// Serialise a [`Table`] object into its protobuf representation.
@@ -405,6 +558,7 @@ impl From<Table> for generated_types::influxdata::iox::table::v1::Table {
             name: value.name,
             namespace_id: value.namespace_id.get(),
             partition_template: value.partition_template.as_proto().cloned(),
+            retention_period_ns: value.retention_period_ns,
         }
     }
 }
@@ -418,6 +572,15 @@ pub struct TableSchema {
     /// The partition template to use for writes in this table.
     pub partition_template: TablePartitionTemplateOverride,
 
+    /// The retention period in ns, overriding that of the namespace.
+    /// None means the namespace retention period applies.
+    pub retention_period_ns: Option<i64>,
+
+    /// The version of the [`Self::partition_template`], incremented by every
+    /// change to it. A copy of the table schema with a lower version carries
+    /// a stale partition template.
+    pub partition_template_version: i64,
+
     /// the table's columns by their name
     pub columns: ColumnsByName,
 }
@@ -428,6 +591,8 @@ impl TableSchema {
         Self {
             id: table.id,
             partition_template: table.partition_template.clone(),
+            retention_period_ns: table.retention_period_ns,
+            partition_template_version: table.partition_template_version,
             columns: ColumnsByName::new([]),
         }
     }
@@ -599,12 +764,15 @@ pub struct ParquetFile {
     pub column_set: ColumnSet,
     /// the max of created_at of all L0 files needed for file/chunk ordering for deduplication
     pub max_l0_created_at: Timestamp,
+    /// the object store tier currently holding the file
+    pub storage_tier: StorageTier,
 }
 
 impl ParquetFile {
     /// Create new file from given parameters and ID.
     ///
-    /// [`to_delete`](Self::to_delete) will be set to `None`.
+    /// [`to_delete`](Self::to_delete) will be set to `None` and the file is
+    /// placed in the [`StorageTier::Hot`] tier.
     pub fn from_params(params: ParquetFileParams, id: ParquetFileId) -> Self {
         Self {
             id,
@@ -621,6 +789,7 @@ impl ParquetFile {
             created_at: params.created_at,
             column_set: params.column_set,
             max_l0_created_at: params.max_l0_created_at,
+            storage_tier: StorageTier::Hot,
         }
     }
 
@@ -680,6 +849,7 @@ impl From<ParquetFile> for generated_types::influxdata::iox::catalog::v1::Parque
             created_at: v.created_at.get(),
             column_set: v.column_set.iter().map(|v| v.get()).collect(),
             max_l0_created_at: v.max_l0_created_at.get(),
+            storage_tier: v.storage_tier as i32,
         }
     }
 }
@@ -702,6 +872,10 @@ pub enum ParquetFileProtoError {
     /// The specified compaction level value is invalid.
     #[error("invalid compaction level: {0}")]
     InvalidCompactionLevel(Box<dyn std::error::Error + Send + Sync + 'static>),
+
+    /// The specified storage tier value is invalid.
+    #[error("invalid storage tier: {0}")]
+    InvalidStorageTier(Box<dyn std::error::Error + Send + Sync + 'static>),
 }
 
 impl TryFrom<generated_types::influxdata::iox::catalog::v1::ParquetFile> for ParquetFile {
@@ -732,6 +906,8 @@ impl TryFrom<generated_types::influxdata::iox::catalog::v1::ParquetFile> for Par
             created_at: Timestamp::new(v.created_at),
             column_set: ColumnSet::new(v.column_set.into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(v.max_l0_created_at),
+            storage_tier: StorageTier::try_from(v.storage_tier)
+                .map_err(ParquetFileProtoError::InvalidStorageTier)?,
         })
     }
 }
@@ -2661,11 +2837,15 @@ mod tests {
         let schema1 = TableSchema {
             id: TableId::new(1),
             partition_template: Default::default(),
+            retention_period_ns: None,
+            partition_template_version: 0,
             columns: ColumnsByName::new([]),
         };
         let schema2 = TableSchema {
             id: TableId::new(2),
             partition_template: Default::default(),
+            retention_period_ns: None,
+            partition_template_version: 0,
             columns: ColumnsByName::new([Column {
                 id: ColumnId::new(1),
                 table_id: TableId::new(2),
@@ -2685,6 +2865,10 @@ mod tests {
             max_columns_per_table: MaxColumnsPerTable::try_from(4).unwrap(),
             retention_period_ns: None,
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         };
         let schema2 = NamespaceSchema {
             id: NamespaceId::new(1),
@@ -2694,12 +2878,18 @@ mod tests {
                     id: TableId::new(1),
                     columns: ColumnsByName::new([]),
                     partition_template: Default::default(),
+                    retention_period_ns: None,
+                    partition_template_version: 0,
                 },
             )]),
             max_tables: MaxTables::try_from(42).unwrap(),
             max_columns_per_table: MaxColumnsPerTable::try_from(4).unwrap(),
             retention_period_ns: None,
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         };
         assert!(schema1.size() < schema2.size());
     }
@@ -2752,6 +2942,10 @@ mod tests {
         ]
     }
 
+    fn arbitrary_storage_tier() -> impl prop::strategy::Strategy<Value = StorageTier> {
+        prop_oneof![Just(StorageTier::Hot), Just(StorageTier::Cold)]
+    }
+
     prop_compose! {
         /// Return an arbitrary [`ParquetFile`] with a randomised values.
         fn arbitrary_parquet_file()(
@@ -2768,6 +2962,7 @@ mod tests {
             created_at in arbitrary_timestamp(),
             column_set in prop::collection::vec(any::<i64>(), 0..10),
             max_l0_created_at in arbitrary_timestamp(),
+            storage_tier in arbitrary_storage_tier(),
         ) -> ParquetFile {
             let column_set = ColumnSet::new(column_set.into_iter().map(ColumnId::new));
 
@@ -2786,6 +2981,7 @@ mod tests {
                 created_at,
                 column_set,
                 max_l0_created_at,
+                storage_tier,
             }
         }
     }
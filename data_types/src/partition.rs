@@ -1,6 +1,6 @@
 //! Types having to do with partitions.
 
-use crate::SortedColumnSet;
+use crate::{partition_template::TablePartitionTemplateOverride, SortedColumnSet};
 
 use super::{TableId, Timestamp};
 
@@ -474,6 +474,14 @@ pub struct Partition {
 
     /// The time at which the newest file of the partition is created
     pub new_file_at: Option<Timestamp>,
+
+    /// The partition template of the table at the time this partition was
+    /// created, and therefore the template that derived its
+    /// [`Self::partition_key`].
+    ///
+    /// The table's partition template may have been changed since, so this
+    /// template MUST be used to interpret the partition key.
+    pub partition_template: TablePartitionTemplateOverride,
 }
 
 impl Partition {
@@ -498,6 +506,7 @@ impl Partition {
             sort_key,
             sort_key_ids,
             new_file_at,
+            partition_template: Default::default(),
         }
     }
 
@@ -515,6 +524,7 @@ impl Partition {
         sort_key: Option<Vec<String>>,
         sort_key_ids: SortedColumnSet,
         new_file_at: Option<Timestamp>,
+        partition_template: TablePartitionTemplateOverride,
     ) -> Self {
         Self {
             id,
@@ -524,6 +534,7 @@ impl Partition {
             sort_key,
             sort_key_ids,
             new_file_at,
+            partition_template,
         }
     }
 
@@ -47,6 +47,18 @@
 //! [`build_column_values()`] function can be used to obtain the set of
 //! [`TemplatePart::TagValue`] the key was constructed from.
 //!
+//! ### Hash Buckets
+//!
+//! A [`TemplatePart::Bucket`] part hashes the value of a tag into one of a
+//! fixed number of buckets using a stable hash function
+//! ([`bucket_for_tag_value()`]), and renders the bucket number as the key part.
+//! This bounds the number of partitions derived from a high cardinality tag. As
+//! with [`TemplatePart::TagValue`], a missing tag renders a single `!`.
+//!
+//! The tag value cannot be recovered from a bucket key part, so
+//! [`build_column_values()`] yields a [`ColumnValue::Bucket`] that can only be
+//! used to test if a given value hashes into the partition's bucket.
+//!
 //! ### Value Truncation
 //!
 //! Partition key parts are limited to, at most, 200 bytes in length
@@ -158,8 +170,9 @@ use generated_types::influxdata::iox::partition_template::v1 as proto;
 use once_cell::sync::Lazy;
 use percent_encoding::{percent_decode_str, AsciiSet, CONTROLS};
 use schema::TIME_COLUMN_NAME;
-use std::{borrow::Cow, sync::Arc};
+use std::{borrow::Cow, hash::Hasher, sync::Arc};
 use thiserror::Error;
+use twox_hash::XxHash64;
 
 /// Reasons a user-specified partition template isn't valid.
 #[derive(Debug, Error)]
@@ -193,6 +206,16 @@ pub enum ValidationError {
     /// [`TagValue`]: [`proto::template_part::Part::TagValue`]
     #[error("invalid tag value in partition template: {0}")]
     InvalidTagValue(String),
+
+    /// The partition template defines a [`Bucket`] part, but the number of
+    /// buckets is outside the permitted range.
+    ///
+    /// [`Bucket`]: [`proto::template_part::Part::Bucket`]
+    #[error(
+        "invalid number of buckets in partition template: {0} \
+        (must be between 1 and {MAXIMUM_NUMBER_OF_BUCKETS})"
+    )]
+    InvalidNumberOfBuckets(u32),
 }
 
 /// The maximum number of template parts a custom partition template may specify, to limit the
@@ -200,6 +223,10 @@ pub enum ValidationError {
 /// created with it.
 pub const MAXIMUM_NUMBER_OF_TEMPLATE_PARTS: usize = 8;
 
+/// The maximum number of buckets a [`TemplatePart::Bucket`] part may hash tag
+/// values into.
+pub const MAXIMUM_NUMBER_OF_BUCKETS: u32 = 100_000;
+
 /// The sentinel character used to delimit partition key parts in the partition
 /// key string.
 pub const PARTITION_KEY_DELIMITER: char = '|';
@@ -253,6 +280,26 @@ pub const ENCODED_PARTITION_KEY_CHARS: AsciiSet = CONTROLS
 pub enum TemplatePart<'a> {
     TagValue(&'a str),
     TimeFormat(&'a str),
+    /// The tag name, and the number of buckets its values are hashed into.
+    Bucket(&'a str, u32),
+}
+
+/// Returns the bucket in the range `[0, num_buckets)` that the tag `value` is
+/// assigned to by a [`TemplatePart::Bucket`] partition template part.
+///
+/// The hash function is stable - the same value always maps to the same
+/// bucket, across releases and platforms. Changing it would change the
+/// partition keys derived for existing data.
+///
+/// # Panics
+///
+/// Panics if `num_buckets` is 0.
+pub fn bucket_for_tag_value(value: &str, num_buckets: u32) -> u32 {
+    assert!(num_buckets > 0, "number of buckets must be non-zero");
+
+    let mut hasher = XxHash64::with_seed(0);
+    hasher.write(value.as_bytes());
+    (hasher.finish() % num_buckets as u64) as u32
 }
 
 /// The default partitioning scheme is by each day according to the "time" column.
@@ -344,9 +391,24 @@ impl TablePartitionTemplateOverride {
             .map(|part| match part {
                 proto::template_part::Part::TagValue(value) => TemplatePart::TagValue(value),
                 proto::template_part::Part::TimeFormat(fmt) => TemplatePart::TimeFormat(fmt),
+                proto::template_part::Part::Bucket(bucket) => {
+                    TemplatePart::Bucket(&bucket.tag_name, bucket.num_buckets)
+                }
             })
     }
 
+    /// Iterate through the names of the tag columns this template derives
+    /// partition key parts from.
+    ///
+    /// Partitioning is only supported for tags, so these columns must be of
+    /// tag type.
+    pub fn tag_columns(&self) -> impl Iterator<Item = &str> {
+        self.parts().filter_map(|part| match part {
+            TemplatePart::TagValue(tag_name) | TemplatePart::Bucket(tag_name, _) => Some(tag_name),
+            TemplatePart::TimeFormat(_) => None,
+        })
+    }
+
     /// Size in bytes, including `self`.
     ///
     /// This accounts for the entire allocation of this object, even when it shared (via an internal [`Arc`]).
@@ -370,6 +432,9 @@ impl TablePartitionTemplateOverride {
                                     .map(|part| match part {
                                         proto::template_part::Part::TagValue(s) => s.capacity(),
                                         proto::template_part::Part::TimeFormat(s) => s.capacity(),
+                                        proto::template_part::Part::Bucket(b) => {
+                                            b.tag_name.capacity()
+                                        }
                                     })
                                     .unwrap_or_default()
                             })
@@ -389,7 +454,10 @@ impl TablePartitionTemplateOverride {
 /// `TablePartitionTemplateOverride` types. It's an internal implementation detail to minimize code
 /// duplication.
 mod serialization {
-    use super::{ValidationError, MAXIMUM_NUMBER_OF_TEMPLATE_PARTS, TAG_VALUE_KEY_TIME};
+    use super::{
+        ValidationError, MAXIMUM_NUMBER_OF_BUCKETS, MAXIMUM_NUMBER_OF_TEMPLATE_PARTS,
+        TAG_VALUE_KEY_TIME,
+    };
     use chrono::{format::StrftimeItems, Utc};
     use generated_types::influxdata::iox::partition_template::v1 as proto;
     use std::{fmt::Write, sync::Arc};
@@ -480,6 +548,26 @@ mod serialization {
                             )));
                         }
                     }
+                    Some(proto::template_part::Part::Bucket(bucket)) => {
+                        // The bucketed tag is subject to the same rules as a
+                        // tag value part.
+                        if bucket.tag_name.is_empty() {
+                            return Err(ValidationError::InvalidTagValue(bucket.tag_name.clone()));
+                        }
+
+                        if bucket.tag_name.contains(TAG_VALUE_KEY_TIME) {
+                            return Err(ValidationError::InvalidTagValue(format!(
+                                "{TAG_VALUE_KEY_TIME} cannot be used"
+                            )));
+                        }
+
+                        if bucket.num_buckets == 0 || bucket.num_buckets > MAXIMUM_NUMBER_OF_BUCKETS
+                        {
+                            return Err(ValidationError::InvalidNumberOfBuckets(
+                                bucket.num_buckets,
+                            ));
+                        }
+                    }
                     None => {}
                 }
             }
@@ -558,6 +646,19 @@ pub enum ColumnValue<'a> {
         /// Exclusive end of the datatime partition range.
         end: DateTime<Utc>,
     },
+
+    /// The hash bucket of the input column value.
+    ///
+    /// Attempting to equality match this variant against a string will always
+    /// be false - use [`ColumnValue::is_bucket_match_of()`] to test if a value
+    /// belongs to this bucket instead.
+    Bucket {
+        /// The bucket the column values were hashed into.
+        bucket: u32,
+
+        /// The total number of buckets column values are hashed into.
+        num_buckets: u32,
+    },
 }
 
 impl<'a> ColumnValue<'a> {
@@ -572,13 +673,26 @@ impl<'a> ColumnValue<'a> {
         let this = match self {
             ColumnValue::Identity(v) => v.as_bytes(),
             ColumnValue::Prefix(v) => v.as_bytes(),
-            ColumnValue::Datetime { .. } => {
+            ColumnValue::Datetime { .. } | ColumnValue::Bucket { .. } => {
                 return false;
             }
         };
 
         other.as_ref().starts_with(this)
     }
+
+    /// Returns true if `other` hashes into the bucket described by `self`.
+    ///
+    /// Always returns false for variants other than [`ColumnValue::Bucket`].
+    pub fn is_bucket_match_of(&self, other: &str) -> bool {
+        match self {
+            ColumnValue::Bucket {
+                bucket,
+                num_buckets,
+            } => bucket_for_tag_value(other, *num_buckets) == *bucket,
+            _ => false,
+        }
+    }
 }
 
 impl<'a, T> PartialEq<T> for ColumnValue<'a>
@@ -590,6 +704,7 @@ where
             ColumnValue::Identity(v) => other.as_ref().eq(v.as_ref()),
             ColumnValue::Prefix(_) => false,
             ColumnValue::Datetime { .. } => false,
+            ColumnValue::Bucket { .. } => false,
         }
     }
 }
@@ -634,9 +749,30 @@ pub fn build_column_values<'a>(
             TemplatePart::TimeFormat(format) => {
                 Some((TIME_COLUMN_NAME, parse_part_time_format(value, format)?))
             }
+            TemplatePart::Bucket(col_name, num_buckets) => {
+                Some((col_name, parse_part_bucket(value, num_buckets)?))
+            }
         })
 }
 
+fn parse_part_bucket(value: &str, num_buckets: u32) -> Option<ColumnValue<'static>> {
+    // Skip null partition key parts, indicated by the presence of a single
+    // "!" character as the part value.
+    if value == PARTITION_KEY_VALUE_NULL_STR {
+        return None;
+    }
+
+    let bucket = value.parse::<u32>().ok()?;
+    if bucket >= num_buckets {
+        return None;
+    }
+
+    Some(ColumnValue::Bucket {
+        bucket,
+        num_buckets,
+    })
+}
+
 fn parse_part_tag_value(value: &str) -> Option<ColumnValue<'_>> {
     // Perform re-mapping of sentinel values.
     let value = match value {
@@ -800,6 +936,12 @@ pub fn test_table_partition_override(
             let part = match part {
                 TemplatePart::TagValue(value) => proto::template_part::Part::TagValue(value.into()),
                 TemplatePart::TimeFormat(fmt) => proto::template_part::Part::TimeFormat(fmt.into()),
+                TemplatePart::Bucket(tag_name, num_buckets) => {
+                    proto::template_part::Part::Bucket(proto::Bucket {
+                        tag_name: tag_name.into(),
+                        num_buckets,
+                    })
+                }
             };
 
             proto::TemplatePart { part: Some(part) }
@@ -947,6 +1089,78 @@ mod tests {
         assert_error!(err, ValidationError::InvalidTagValue(ref value) if value.is_empty());
     }
 
+    #[test]
+    fn bucket_num_buckets_is_validated() {
+        for num_buckets in [0, MAXIMUM_NUMBER_OF_BUCKETS + 1] {
+            let err = serialization::Wrapper::try_from(proto::PartitionTemplate {
+                parts: vec![proto::TemplatePart {
+                    part: Some(proto::template_part::Part::Bucket(proto::Bucket {
+                        tag_name: "host".into(),
+                        num_buckets,
+                    })),
+                }],
+            });
+
+            assert_error!(err, ValidationError::InvalidNumberOfBuckets(n) if n == num_buckets);
+        }
+
+        serialization::Wrapper::try_from(proto::PartitionTemplate {
+            parts: vec![proto::TemplatePart {
+                part: Some(proto::template_part::Part::Bucket(proto::Bucket {
+                    tag_name: "host".into(),
+                    num_buckets: MAXIMUM_NUMBER_OF_BUCKETS,
+                })),
+            }],
+        })
+        .expect("valid bucket template");
+    }
+
+    #[test]
+    fn bucket_tag_name_is_validated() {
+        for tag_name in ["", "time"] {
+            let err = serialization::Wrapper::try_from(proto::PartitionTemplate {
+                parts: vec![proto::TemplatePart {
+                    part: Some(proto::template_part::Part::Bucket(proto::Bucket {
+                        tag_name: tag_name.into(),
+                        num_buckets: 10,
+                    })),
+                }],
+            });
+
+            assert_error!(err, ValidationError::InvalidTagValue(_));
+        }
+    }
+
+    /// The bucket assignment of a value MUST NOT change, as it determines the
+    /// partition key of existing data.
+    #[test]
+    fn test_bucket_for_tag_value_stability() {
+        assert_eq!(bucket_for_tag_value("", 10), 1);
+        assert_eq!(bucket_for_tag_value("host-1", 10), 7);
+        assert_eq!(bucket_for_tag_value("bananas", 10), 9);
+        assert_eq!(bucket_for_tag_value("platanos", 10), 0);
+        assert_eq!(bucket_for_tag_value("bananas", 100_000), 3839);
+        assert_eq!(bucket_for_tag_value(&"a".repeat(40), 100_000), 38915);
+
+        // A single bucket always maps to bucket 0.
+        assert_eq!(bucket_for_tag_value("bananas", 1), 0);
+    }
+
+    #[test]
+    fn test_column_value_bucket_match() {
+        let v = ColumnValue::Bucket {
+            bucket: 9,
+            num_buckets: 10,
+        };
+
+        assert!(v.is_bucket_match_of("bananas"));
+        assert!(!v.is_bucket_match_of("platanos"));
+        assert!(!v.is_prefix_match_of("bananas"));
+        assert_ne!(v, "bananas");
+
+        assert!(!identity("bananas").is_bucket_match_of("bananas"));
+    }
+
     fn identity(s: &str) -> ColumnValue<'_> {
         ColumnValue::Identity(s.into())
     }
@@ -995,6 +1209,27 @@ mod tests {
         };
     }
 
+    test_build_column_values!(
+        bucket,
+        template = [
+            TemplatePart::TimeFormat("%Y"),
+            TemplatePart::Bucket("a", 10),
+            TemplatePart::Bucket("b", 10),
+            TemplatePart::Bucket("c", 10),
+        ],
+        partition_key = "2023|7|!|42",
+        want = [
+            (TIME_COLUMN_NAME, year(2023)),
+            (
+                "a",
+                ColumnValue::Bucket {
+                    bucket: 7,
+                    num_buckets: 10
+                }
+            ),
+        ]
+    );
+
     test_build_column_values!(
         module_doc_example_1,
         template = [
@@ -0,0 +1,606 @@
+//! Per-namespace rollup policies, materialised by the compactor.
+//!
+//! A [`RollupPolicy`] describes a table of time-bucketed aggregates derived
+//! from a source table. As the partitions of the source table age past the
+//! policy's age threshold, the compactor computes the aggregates of the
+//! partition and writes them to the same partition of the rollup table,
+//! replacing any previous rollup of that partition.
+//!
+//! The policies are stored in the catalog as the protobuf representation, and
+//! validated once when constructed (or loaded from the catalog) into a
+//! [`RollupPolicies`] instance.
+
+use std::{sync::Arc, time::Duration};
+
+use generated_types::influxdata::iox::rollup::v1 as proto;
+use schema::TIME_COLUMN_NAME;
+use thiserror::Error;
+
+use crate::ColumnType;
+
+/// The number of seconds in a day - rollup intervals must evenly divide a day
+/// so that no time bucket spans two (daily) partitions.
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// Reasons a set of rollup policies may be rejected.
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ValidationError {
+    /// The policy at `index` does not specify a source table.
+    #[error("rollup policy {index} does not specify a source table")]
+    MissingSourceTable {
+        /// The position of the invalid policy.
+        index: usize,
+    },
+
+    /// The policy at `index` specifies an interval that does not evenly
+    /// divide a day.
+    #[error(
+        "rollup policy {index} interval of {interval_seconds}s must be non-zero and evenly divide a day"
+    )]
+    InvalidInterval {
+        /// The position of the invalid policy.
+        index: usize,
+        /// The invalid interval.
+        interval_seconds: u64,
+    },
+
+    /// The policy at `index` specifies an age threshold of 0.
+    #[error("rollup policy {index} specifies an age threshold of 0")]
+    ZeroAgeThreshold {
+        /// The position of the invalid policy.
+        index: usize,
+    },
+
+    /// The policy at `index` specifies no aggregates.
+    #[error("rollup policy {index} specifies no aggregates")]
+    NoAggregates {
+        /// The position of the invalid policy.
+        index: usize,
+    },
+
+    /// The policy at `index` contains an aggregate of an empty field name, or
+    /// of the timestamp column.
+    #[error("rollup policy {index} contains an aggregate of an invalid field {field:?}")]
+    InvalidField {
+        /// The position of the invalid policy.
+        index: usize,
+        /// The invalid field name.
+        field: String,
+    },
+
+    /// The policy at `index` contains an aggregate without an aggregate
+    /// function.
+    #[error("rollup policy {index} contains an aggregate of {field} without a function")]
+    MissingAggregate {
+        /// The position of the invalid policy.
+        index: usize,
+        /// The field missing the aggregate function.
+        field: String,
+    },
+
+    /// The policy at `index` computes the same aggregate of a field twice.
+    #[error("rollup policy {index} computes {column} more than once")]
+    DuplicateAggregate {
+        /// The position of the invalid policy.
+        index: usize,
+        /// The duplicated output column.
+        column: String,
+    },
+
+    /// The policy at `index` writes to the same rollup table as an earlier
+    /// policy.
+    #[error("rollup policy {index} writes to the rollup table {table} of an earlier policy")]
+    DuplicateTable {
+        /// The position of the invalid policy.
+        index: usize,
+        /// The duplicated rollup table name.
+        table: String,
+    },
+}
+
+/// An aggregate function applied to the values of a field within a time
+/// bucket.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RollupAggregate {
+    /// The minimum value.
+    Min,
+    /// The maximum value.
+    Max,
+    /// The sum of all values.
+    Sum,
+    /// The number of non-null values.
+    Count,
+    /// The arithmetic mean of all values.
+    Mean,
+}
+
+impl RollupAggregate {
+    /// The name of this aggregate, used as the suffix of the output column.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Sum => "sum",
+            Self::Count => "count",
+            Self::Mean => "mean",
+        }
+    }
+
+    /// The type of the output column when applied to a field of type
+    /// `input`, or [`None`] if the aggregate does not support the type.
+    pub fn output_type(&self, input: ColumnType) -> Option<ColumnType> {
+        match (self, input) {
+            (_, ColumnType::Tag | ColumnType::Time) => None,
+            (Self::Count, _) => Some(ColumnType::I64),
+            (Self::Mean, ColumnType::I64 | ColumnType::U64 | ColumnType::F64) => {
+                Some(ColumnType::F64)
+            }
+            (
+                Self::Min | Self::Max | Self::Sum,
+                ColumnType::I64 | ColumnType::U64 | ColumnType::F64,
+            ) => Some(input),
+            (_, ColumnType::Bool | ColumnType::String) => None,
+        }
+    }
+}
+
+/// An aggregate of a single field of the source table.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct FieldAggregate {
+    /// The name of the aggregated field.
+    pub field: String,
+    /// The aggregate function.
+    pub aggregate: RollupAggregate,
+}
+
+impl FieldAggregate {
+    /// The name of the rollup table column holding this aggregate.
+    pub fn output_column(&self) -> String {
+        format!("{}_{}", self.field, self.aggregate.name())
+    }
+}
+
+/// A single, validated rollup policy.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RollupPolicy {
+    /// The name of the table to roll up.
+    pub source_table: String,
+    /// The width of the time buckets.
+    pub interval: Duration,
+    /// The aggregates computed for each time bucket.
+    pub aggregates: Vec<FieldAggregate>,
+    /// The minimum age of the data in a partition before it is rolled up.
+    pub age_threshold: Duration,
+}
+
+impl RollupPolicy {
+    /// The name of the table the rollup is written to.
+    pub fn target_table(&self) -> String {
+        target_table(&self.source_table, self.interval.as_secs())
+    }
+}
+
+fn target_table(source_table: &str, interval_seconds: u64) -> String {
+    format!("{source_table}_rollup_{interval_seconds}s")
+}
+
+/// The set of [`RollupPolicy`] configured for a namespace.
+///
+/// Internally this type is [`None`] when no policies are configured.
+#[derive(Debug, PartialEq, Eq, Clone, Default, sqlx::Type)]
+#[sqlx(transparent, no_pg_array)]
+pub struct RollupPolicies(Option<serialization::Wrapper>);
+
+impl RollupPolicies {
+    /// A const "default" impl with no policies.
+    pub const fn const_default() -> Self {
+        Self(None)
+    }
+
+    /// Returns true if no policies are configured.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Iterate over all the policies.
+    pub fn iter(&self) -> impl Iterator<Item = &RollupPolicy> {
+        self.0.iter().flat_map(|v| v.policies().iter())
+    }
+
+    /// Iterate over the policies rolling up `source_table`.
+    pub fn for_table<'a>(
+        &'a self,
+        source_table: &'a str,
+    ) -> impl Iterator<Item = &'a RollupPolicy> + 'a {
+        self.iter().filter(move |p| p.source_table == source_table)
+    }
+
+    /// Return the policy rolling up `source_table` best suited to answer a
+    /// query grouping by `interval`.
+    ///
+    /// This is the policy with the largest interval that evenly divides
+    /// `interval`, so that each requested time bucket is made up of whole
+    /// rollup time buckets.
+    pub fn route(&self, source_table: &str, interval: Duration) -> Option<&RollupPolicy> {
+        self.for_table(source_table)
+            .filter(|p| interval.as_nanos() % p.interval.as_nanos() == 0)
+            .max_by_key(|p| p.interval)
+    }
+
+    /// Return the protobuf representation of these policies.
+    pub fn as_proto(&self) -> Option<&proto::RollupPolicies> {
+        self.0.as_ref().map(|v| v.inner())
+    }
+}
+
+impl TryFrom<proto::RollupPolicies> for RollupPolicies {
+    type Error = ValidationError;
+
+    fn try_from(policies: proto::RollupPolicies) -> Result<Self, Self::Error> {
+        if policies.policies.is_empty() {
+            return Ok(Self(None));
+        }
+        Ok(Self(Some(serialization::Wrapper::try_from(policies)?)))
+    }
+}
+
+fn validate_policy(
+    index: usize,
+    policy: &proto::RollupPolicy,
+) -> Result<RollupPolicy, ValidationError> {
+    if policy.source_table.is_empty() {
+        return Err(ValidationError::MissingSourceTable { index });
+    }
+    if policy.interval_seconds == 0 || SECONDS_PER_DAY % policy.interval_seconds != 0 {
+        return Err(ValidationError::InvalidInterval {
+            index,
+            interval_seconds: policy.interval_seconds,
+        });
+    }
+    if policy.age_threshold_seconds == 0 {
+        return Err(ValidationError::ZeroAgeThreshold { index });
+    }
+    if policy.aggregates.is_empty() {
+        return Err(ValidationError::NoAggregates { index });
+    }
+
+    let mut aggregates: Vec<FieldAggregate> = Vec::with_capacity(policy.aggregates.len());
+    for v in &policy.aggregates {
+        if v.field.is_empty() || v.field == TIME_COLUMN_NAME {
+            return Err(ValidationError::InvalidField {
+                index,
+                field: v.field.clone(),
+            });
+        }
+
+        let aggregate = match v.aggregate() {
+            proto::Aggregate::Unspecified => {
+                return Err(ValidationError::MissingAggregate {
+                    index,
+                    field: v.field.clone(),
+                })
+            }
+            proto::Aggregate::Min => RollupAggregate::Min,
+            proto::Aggregate::Max => RollupAggregate::Max,
+            proto::Aggregate::Sum => RollupAggregate::Sum,
+            proto::Aggregate::Count => RollupAggregate::Count,
+            proto::Aggregate::Mean => RollupAggregate::Mean,
+        };
+
+        let aggregate = FieldAggregate {
+            field: v.field.clone(),
+            aggregate,
+        };
+        if aggregates.contains(&aggregate) {
+            return Err(ValidationError::DuplicateAggregate {
+                index,
+                column: aggregate.output_column(),
+            });
+        }
+        aggregates.push(aggregate);
+    }
+
+    Ok(RollupPolicy {
+        source_table: policy.source_table.clone(),
+        interval: Duration::from_secs(policy.interval_seconds),
+        aggregates,
+        age_threshold: Duration::from_secs(policy.age_threshold_seconds),
+    })
+}
+
+/// This manages the serialization/deserialization of the
+/// `proto::RollupPolicies` type to and from the database through `sqlx`,
+/// validating the policies when loaded.
+mod serialization {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct Wrapper {
+        proto: Arc<proto::RollupPolicies>,
+        policies: Arc<[RollupPolicy]>,
+    }
+
+    impl Wrapper {
+        /// Read access to the inner proto
+        pub fn inner(&self) -> &proto::RollupPolicies {
+            &self.proto
+        }
+
+        /// The validated policies.
+        pub fn policies(&self) -> &[RollupPolicy] {
+            &self.policies
+        }
+    }
+
+    // The validated policies are derived from the proto, so only the proto
+    // needs to be considered for equality.
+    impl PartialEq for Wrapper {
+        fn eq(&self, other: &Self) -> bool {
+            self.proto == other.proto
+        }
+    }
+
+    // The proto contains no floating point values, so its equality is total.
+    impl Eq for Wrapper {}
+
+    impl TryFrom<proto::RollupPolicies> for Wrapper {
+        type Error = ValidationError;
+
+        fn try_from(policies: proto::RollupPolicies) -> Result<Self, Self::Error> {
+            let mut validated: Vec<RollupPolicy> = Vec::with_capacity(policies.policies.len());
+            for (index, policy) in policies.policies.iter().enumerate() {
+                let policy = validate_policy(index, policy)?;
+
+                let table = policy.target_table();
+                if validated.iter().any(|p| p.target_table() == table) {
+                    return Err(ValidationError::DuplicateTable { index, table });
+                }
+                validated.push(policy);
+            }
+
+            Ok(Self {
+                proto: Arc::new(policies),
+                policies: validated.into(),
+            })
+        }
+    }
+
+    impl<DB> sqlx::Type<DB> for Wrapper
+    where
+        sqlx::types::Json<Self>: sqlx::Type<DB>,
+        DB: sqlx::Database,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <sqlx::types::Json<Self> as sqlx::Type<DB>>::type_info()
+        }
+    }
+
+    impl<'q, DB> sqlx::Encode<'q, DB> for Wrapper
+    where
+        DB: sqlx::Database,
+        for<'b> sqlx::types::Json<&'b proto::RollupPolicies>: sqlx::Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+        ) -> sqlx::encode::IsNull {
+            <sqlx::types::Json<&proto::RollupPolicies> as sqlx::Encode<'_, DB>>::encode_by_ref(
+                &sqlx::types::Json(&self.proto),
+                buf,
+            )
+        }
+    }
+
+    impl<'q, DB> sqlx::Decode<'q, DB> for Wrapper
+    where
+        DB: sqlx::Database,
+        sqlx::types::Json<proto::RollupPolicies>: sqlx::Decode<'q, DB>,
+    {
+        fn decode(
+            value: <DB as sqlx::database::HasValueRef<'q>>::ValueRef,
+        ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+            let policies =
+                <sqlx::types::Json<proto::RollupPolicies> as sqlx::Decode<'_, DB>>::decode(value)?
+                    .0;
+            Ok(Self::try_from(policies)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    fn policy(
+        source_table: &str,
+        interval_seconds: u64,
+        aggregates: impl IntoIterator<Item = (&'static str, proto::Aggregate)>,
+    ) -> proto::RollupPolicy {
+        proto::RollupPolicy {
+            source_table: source_table.to_string(),
+            interval_seconds,
+            aggregates: aggregates
+                .into_iter()
+                .map(|(field, aggregate)| proto::FieldAggregate {
+                    field: field.to_string(),
+                    aggregate: aggregate.into(),
+                })
+                .collect(),
+            age_threshold_seconds: 3_600,
+        }
+    }
+
+    fn policies(policies: impl IntoIterator<Item = proto::RollupPolicy>) -> proto::RollupPolicies {
+        proto::RollupPolicies {
+            policies: policies.into_iter().collect(),
+        }
+    }
+
+    #[test]
+    fn test_empty_policies() {
+        let got = RollupPolicies::try_from(policies([])).unwrap();
+        assert!(got.is_empty());
+        assert_eq!(got, RollupPolicies::default());
+        assert!(got.as_proto().is_none());
+        assert_eq!(got.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_validate() {
+        let proto = policies([
+            policy(
+                "cpu",
+                60,
+                [
+                    ("usage", proto::Aggregate::Mean),
+                    ("usage", proto::Aggregate::Max),
+                ],
+            ),
+            policy("mem", 3_600, [("free", proto::Aggregate::Min)]),
+        ]);
+
+        let got = RollupPolicies::try_from(proto.clone()).unwrap();
+        assert_eq!(got.as_proto(), Some(&proto));
+
+        let cpu = got.for_table("cpu").collect::<Vec<_>>();
+        assert_eq!(
+            cpu,
+            [&RollupPolicy {
+                source_table: "cpu".to_string(),
+                interval: Duration::from_secs(60),
+                aggregates: vec![
+                    FieldAggregate {
+                        field: "usage".to_string(),
+                        aggregate: RollupAggregate::Mean,
+                    },
+                    FieldAggregate {
+                        field: "usage".to_string(),
+                        aggregate: RollupAggregate::Max,
+                    },
+                ],
+                age_threshold: Duration::from_secs(3_600),
+            }]
+        );
+        assert_eq!(cpu[0].target_table(), "cpu_rollup_60s");
+        assert_eq!(cpu[0].aggregates[0].output_column(), "usage_mean");
+
+        assert_eq!(
+            got.iter().map(|p| p.target_table()).collect::<Vec<_>>(),
+            ["cpu_rollup_60s", "mem_rollup_3600s"]
+        );
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        assert_matches!(
+            RollupPolicies::try_from(policies([policy("", 60, [("v", proto::Aggregate::Min)])])),
+            Err(ValidationError::MissingSourceTable { index: 0 })
+        );
+
+        for interval_seconds in [0, 7, 25 * 60 * 60] {
+            assert_matches!(
+                RollupPolicies::try_from(policies([policy(
+                    "cpu",
+                    interval_seconds,
+                    [("v", proto::Aggregate::Min)]
+                )])),
+                Err(ValidationError::InvalidInterval { index: 0, .. })
+            );
+        }
+
+        let mut p = policy("cpu", 60, [("v", proto::Aggregate::Min)]);
+        p.age_threshold_seconds = 0;
+        assert_matches!(
+            RollupPolicies::try_from(policies([p])),
+            Err(ValidationError::ZeroAgeThreshold { index: 0 })
+        );
+
+        assert_matches!(
+            RollupPolicies::try_from(policies([
+                policy("cpu", 60, [("v", proto::Aggregate::Min)]),
+                policy("cpu", 60, []),
+            ])),
+            Err(ValidationError::NoAggregates { index: 1 })
+        );
+
+        assert_matches!(
+            RollupPolicies::try_from(policies([policy(
+                "cpu",
+                60,
+                [(TIME_COLUMN_NAME, proto::Aggregate::Min)]
+            )])),
+            Err(ValidationError::InvalidField { index: 0, .. })
+        );
+
+        assert_matches!(
+            RollupPolicies::try_from(policies([policy(
+                "cpu",
+                60,
+                [("v", proto::Aggregate::Unspecified)]
+            )])),
+            Err(ValidationError::MissingAggregate { index: 0, .. })
+        );
+
+        assert_matches!(
+            RollupPolicies::try_from(policies([policy(
+                "cpu",
+                60,
+                [("v", proto::Aggregate::Sum), ("v", proto::Aggregate::Sum)]
+            )])),
+            Err(ValidationError::DuplicateAggregate { index: 0, column }) => {
+                assert_eq!(column, "v_sum");
+            }
+        );
+
+        assert_matches!(
+            RollupPolicies::try_from(policies([
+                policy("cpu", 60, [("v", proto::Aggregate::Sum)]),
+                policy("cpu", 60, [("v", proto::Aggregate::Min)]),
+            ])),
+            Err(ValidationError::DuplicateTable { index: 1, table }) => {
+                assert_eq!(table, "cpu_rollup_60s");
+            }
+        );
+    }
+
+    #[test]
+    fn test_output_type() {
+        use RollupAggregate::*;
+
+        assert_eq!(Mean.output_type(ColumnType::I64), Some(ColumnType::F64));
+        assert_eq!(Sum.output_type(ColumnType::U64), Some(ColumnType::U64));
+        assert_eq!(Max.output_type(ColumnType::F64), Some(ColumnType::F64));
+        assert_eq!(Count.output_type(ColumnType::String), Some(ColumnType::I64));
+        assert_eq!(Count.output_type(ColumnType::Bool), Some(ColumnType::I64));
+        assert_eq!(Min.output_type(ColumnType::String), None);
+        assert_eq!(Sum.output_type(ColumnType::Bool), None);
+        assert_eq!(Count.output_type(ColumnType::Tag), None);
+        assert_eq!(Count.output_type(ColumnType::Time), None);
+    }
+
+    #[test]
+    fn test_route() {
+        let got = RollupPolicies::try_from(policies([
+            policy("cpu", 60, [("v", proto::Aggregate::Sum)]),
+            policy("cpu", 300, [("v", proto::Aggregate::Sum)]),
+            policy("cpu", 3_600, [("v", proto::Aggregate::Sum)]),
+            policy("mem", 600, [("v", proto::Aggregate::Sum)]),
+        ]))
+        .unwrap();
+
+        let route = |table, secs| {
+            got.route(table, Duration::from_secs(secs))
+                .map(|p| p.target_table())
+        };
+
+        assert_eq!(route("cpu", 60).as_deref(), Some("cpu_rollup_60s"));
+        assert_eq!(route("cpu", 120).as_deref(), Some("cpu_rollup_60s"));
+        assert_eq!(route("cpu", 900).as_deref(), Some("cpu_rollup_300s"));
+        assert_eq!(route("cpu", 86_400).as_deref(), Some("cpu_rollup_3600s"));
+        assert_eq!(route("cpu", 30), None);
+        assert_eq!(route("mem", 60), None);
+        assert_eq!(route("disk", 3_600), None);
+    }
+}
@@ -0,0 +1,455 @@
+//! Per-namespace transformation rules applied to writes before schema
+//! validation.
+//!
+//! A namespace may be configured with an ordered set of [`WriteRule`] that drop,
+//! rename and add measurements, fields and tags in each write, similar to the
+//! relabelling configuration of a Prometheus scrape target. The rules are
+//! stored in the catalog as the protobuf representation, and validated &
+//! compiled once when constructed (or loaded from the catalog) into a
+//! [`WriteRules`] instance.
+//!
+//! All regexes must match the entire name they are applied to - they are
+//! implicitly anchored at the start and end.
+
+use std::{hash::Hash, sync::Arc};
+
+use generated_types::influxdata::iox::write_rules::v1 as proto;
+use regex::Regex;
+use schema::TIME_COLUMN_NAME;
+use thiserror::Error;
+
+/// Reasons a set of write rules may be rejected.
+#[derive(Debug, Error)]
+pub enum ValidationError {
+    /// The rule at `index` does not specify a rule type.
+    #[error("write rule {index} does not specify a rule")]
+    MissingRule {
+        /// The position of the invalid rule.
+        index: usize,
+    },
+
+    /// The rule at `index` contains a regex that cannot be compiled.
+    #[error("write rule {index} contains an invalid regex: {source}")]
+    InvalidRegex {
+        /// The position of the invalid rule.
+        index: usize,
+        /// The regex compilation error.
+        source: regex::Error,
+    },
+
+    /// The rule at `index` specifies an empty tag key.
+    #[error("write rule {index} specifies an empty tag key")]
+    EmptyTagKey {
+        /// The position of the invalid rule.
+        index: usize,
+    },
+
+    /// The rule at `index` references the timestamp column.
+    #[error("write rule {index} cannot reference the `{TIME_COLUMN_NAME}` column")]
+    TimeColumn {
+        /// The position of the invalid rule.
+        index: usize,
+    },
+
+    /// The rule at `index` specifies a maximum tag value length of 0.
+    #[error("write rule {index} specifies a maximum tag value length of 0")]
+    ZeroMaxLength {
+        /// The position of the invalid rule.
+        index: usize,
+    },
+}
+
+/// A single, validated write transformation rule.
+#[derive(Debug, Clone)]
+pub enum WriteRule {
+    /// Drop all writes to a measurement with a name matching `measurement`.
+    DropMeasurement {
+        /// The measurement name matcher.
+        measurement: Regex,
+    },
+
+    /// Drop all fields with a name matching `field`, in measurements matching
+    /// `measurement` (or all measurements if [`None`]).
+    DropField {
+        /// The optional measurement name matcher.
+        measurement: Option<Regex>,
+        /// The field name matcher.
+        field: Regex,
+    },
+
+    /// Rename the tag `from` to `to`, unless a tag named `to` already exists.
+    RenameTag {
+        /// The existing tag key.
+        from: String,
+        /// The new tag key.
+        to: String,
+    },
+
+    /// Add the tag `key` with a static `value` to every row, unless a tag named
+    /// `key` already exists.
+    AddTag {
+        /// The tag key.
+        key: String,
+        /// The tag value.
+        value: String,
+    },
+
+    /// Drop all rows containing a tag value longer than `max_length` bytes.
+    DropLongTagValues {
+        /// The maximum permitted tag value length, in bytes.
+        max_length: usize,
+    },
+}
+
+impl WriteRule {
+    /// A short, static identifier for the type of this rule, suitable for use
+    /// as a metric attribute.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::DropMeasurement { .. } => "drop_measurement",
+            Self::DropField { .. } => "drop_field",
+            Self::RenameTag { .. } => "rename_tag",
+            Self::AddTag { .. } => "add_tag",
+            Self::DropLongTagValues { .. } => "drop_long_tag_values",
+        }
+    }
+}
+
+/// The ordered set of [`WriteRule`] configured for a namespace.
+///
+/// Internally this type is [`None`] when no rules are configured.
+#[derive(Debug, PartialEq, Clone, Default, sqlx::Type, Hash)]
+#[sqlx(transparent, no_pg_array)]
+pub struct WriteRules(Option<serialization::Wrapper>);
+
+impl WriteRules {
+    /// A const "default" impl with no rules.
+    pub const fn const_default() -> Self {
+        Self(None)
+    }
+
+    /// Returns true if no rules are configured.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_none()
+    }
+
+    /// Iterate over the rules, in the order they are to be applied.
+    pub fn iter(&self) -> impl Iterator<Item = &WriteRule> {
+        self.0.iter().flat_map(|v| v.rules().iter())
+    }
+
+    /// Return the protobuf representation of these rules.
+    pub fn as_proto(&self) -> Option<&proto::WriteRules> {
+        self.0.as_ref().map(|v| v.inner())
+    }
+}
+
+impl TryFrom<proto::WriteRules> for WriteRules {
+    type Error = ValidationError;
+
+    fn try_from(rules: proto::WriteRules) -> Result<Self, Self::Error> {
+        if rules.rules.is_empty() {
+            return Ok(Self(None));
+        }
+        Ok(Self(Some(serialization::Wrapper::try_from(rules)?)))
+    }
+}
+
+/// Compile `regex`, requiring it to match the entire input.
+fn anchored_regex(index: usize, regex: &str) -> Result<Regex, ValidationError> {
+    Regex::new(&format!("^(?:{regex})$"))
+        .map_err(|source| ValidationError::InvalidRegex { index, source })
+}
+
+/// Validate a tag key used by the rule at `index`.
+fn validate_tag_key(index: usize, key: &str) -> Result<(), ValidationError> {
+    if key.is_empty() {
+        return Err(ValidationError::EmptyTagKey { index });
+    }
+    if key == TIME_COLUMN_NAME {
+        return Err(ValidationError::TimeColumn { index });
+    }
+    Ok(())
+}
+
+fn compile_rule(index: usize, rule: &proto::WriteRule) -> Result<WriteRule, ValidationError> {
+    use proto::write_rule::Rule;
+
+    let rule = match rule.rule.as_ref() {
+        Some(v) => v,
+        None => return Err(ValidationError::MissingRule { index }),
+    };
+
+    Ok(match rule {
+        Rule::DropMeasurement(v) => WriteRule::DropMeasurement {
+            measurement: anchored_regex(index, &v.measurement_regex)?,
+        },
+        Rule::DropField(v) => {
+            let field = anchored_regex(index, &v.field_regex)?;
+            if field.is_match(TIME_COLUMN_NAME) {
+                return Err(ValidationError::TimeColumn { index });
+            }
+            WriteRule::DropField {
+                measurement: match v.measurement_regex.as_str() {
+                    "" => None,
+                    re => Some(anchored_regex(index, re)?),
+                },
+                field,
+            }
+        }
+        Rule::RenameTag(v) => {
+            validate_tag_key(index, &v.from)?;
+            validate_tag_key(index, &v.to)?;
+            WriteRule::RenameTag {
+                from: v.from.clone(),
+                to: v.to.clone(),
+            }
+        }
+        Rule::AddTag(v) => {
+            validate_tag_key(index, &v.key)?;
+            WriteRule::AddTag {
+                key: v.key.clone(),
+                value: v.value.clone(),
+            }
+        }
+        Rule::DropLongTagValues(v) => {
+            if v.max_length == 0 {
+                return Err(ValidationError::ZeroMaxLength { index });
+            }
+            WriteRule::DropLongTagValues {
+                max_length: v.max_length as usize,
+            }
+        }
+    })
+}
+
+/// This manages the serialization/deserialization of the `proto::WriteRules`
+/// type to and from the database through `sqlx`, compiling the rules when
+/// loaded.
+mod serialization {
+    use super::*;
+
+    #[derive(Debug, Clone)]
+    pub struct Wrapper {
+        proto: Arc<proto::WriteRules>,
+        rules: Arc<[WriteRule]>,
+    }
+
+    impl Wrapper {
+        /// Read access to the inner proto
+        pub fn inner(&self) -> &proto::WriteRules {
+            &self.proto
+        }
+
+        /// The compiled rules, in the order they are to be applied.
+        pub fn rules(&self) -> &[WriteRule] {
+            &self.rules
+        }
+    }
+
+    // The compiled rules are derived from the proto, so only the proto needs to
+    // be considered for equality and hashing.
+    impl PartialEq for Wrapper {
+        fn eq(&self, other: &Self) -> bool {
+            self.proto == other.proto
+        }
+    }
+
+    impl Hash for Wrapper {
+        fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+            self.proto.hash(state)
+        }
+    }
+
+    impl TryFrom<proto::WriteRules> for Wrapper {
+        type Error = ValidationError;
+
+        fn try_from(rules: proto::WriteRules) -> Result<Self, Self::Error> {
+            let compiled = rules
+                .rules
+                .iter()
+                .enumerate()
+                .map(|(index, rule)| compile_rule(index, rule))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            Ok(Self {
+                proto: Arc::new(rules),
+                rules: compiled.into(),
+            })
+        }
+    }
+
+    impl<DB> sqlx::Type<DB> for Wrapper
+    where
+        sqlx::types::Json<Self>: sqlx::Type<DB>,
+        DB: sqlx::Database,
+    {
+        fn type_info() -> DB::TypeInfo {
+            <sqlx::types::Json<Self> as sqlx::Type<DB>>::type_info()
+        }
+    }
+
+    impl<'q, DB> sqlx::Encode<'q, DB> for Wrapper
+    where
+        DB: sqlx::Database,
+        for<'b> sqlx::types::Json<&'b proto::WriteRules>: sqlx::Encode<'q, DB>,
+    {
+        fn encode_by_ref(
+            &self,
+            buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer,
+        ) -> sqlx::encode::IsNull {
+            <sqlx::types::Json<&proto::WriteRules> as sqlx::Encode<'_, DB>>::encode_by_ref(
+                &sqlx::types::Json(&self.proto),
+                buf,
+            )
+        }
+    }
+
+    impl<'q, DB> sqlx::Decode<'q, DB> for Wrapper
+    where
+        DB: sqlx::Database,
+        sqlx::types::Json<proto::WriteRules>: sqlx::Decode<'q, DB>,
+    {
+        fn decode(
+            value: <DB as sqlx::database::HasValueRef<'q>>::ValueRef,
+        ) -> Result<Self, Box<dyn std::error::Error + 'static + Send + Sync>> {
+            let rules =
+                <sqlx::types::Json<proto::WriteRules> as sqlx::Decode<'_, DB>>::decode(value)?.0;
+            Ok(Self::try_from(rules)?)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use proto::write_rule::Rule;
+
+    use super::*;
+
+    fn rules(rules: impl IntoIterator<Item = Rule>) -> proto::WriteRules {
+        proto::WriteRules {
+            rules: rules
+                .into_iter()
+                .map(|rule| proto::WriteRule { rule: Some(rule) })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn test_empty_rules() {
+        let got = WriteRules::try_from(rules([])).unwrap();
+        assert!(got.is_empty());
+        assert_eq!(got, WriteRules::default());
+        assert!(got.as_proto().is_none());
+        assert_eq!(got.iter().count(), 0);
+    }
+
+    #[test]
+    fn test_compile_in_order() {
+        let proto = rules([
+            Rule::DropMeasurement(proto::DropMeasurement {
+                measurement_regex: "debug_.*".to_string(),
+            }),
+            Rule::DropField(proto::DropField {
+                measurement_regex: "".to_string(),
+                field_regex: "tmp".to_string(),
+            }),
+            Rule::RenameTag(proto::RenameTag {
+                from: "host".to_string(),
+                to: "hostname".to_string(),
+            }),
+            Rule::AddTag(proto::AddTag {
+                key: "region".to_string(),
+                value: "eu".to_string(),
+            }),
+            Rule::DropLongTagValues(proto::DropLongTagValues { max_length: 42 }),
+        ]);
+
+        let got = WriteRules::try_from(proto.clone()).unwrap();
+        assert_eq!(got.as_proto(), Some(&proto));
+
+        let kinds = got.iter().map(|v| v.kind()).collect::<Vec<_>>();
+        assert_eq!(
+            kinds,
+            [
+                "drop_measurement",
+                "drop_field",
+                "rename_tag",
+                "add_tag",
+                "drop_long_tag_values"
+            ]
+        );
+
+        assert_matches!(got.iter().nth(1), Some(WriteRule::DropField { measurement: None, .. }));
+        assert_matches!(
+            got.iter().last(),
+            Some(WriteRule::DropLongTagValues { max_length: 42 })
+        );
+    }
+
+    #[test]
+    fn test_regex_anchored() {
+        let got = WriteRules::try_from(rules([Rule::DropMeasurement(proto::DropMeasurement {
+            measurement_regex: "debug|trace".to_string(),
+        })]))
+        .unwrap();
+
+        let re = assert_matches!(got.iter().next(), Some(WriteRule::DropMeasurement { measurement }) => measurement);
+        assert!(re.is_match("debug"));
+        assert!(re.is_match("trace"));
+        assert!(!re.is_match("debug_cpu"));
+        assert!(!re.is_match("cpu_trace"));
+    }
+
+    #[test]
+    fn test_validation() {
+        assert_matches!(
+            WriteRules::try_from(proto::WriteRules {
+                rules: vec![proto::WriteRule { rule: None }],
+            }),
+            Err(ValidationError::MissingRule { index: 0 })
+        );
+
+        assert_matches!(
+            WriteRules::try_from(rules([
+                Rule::DropLongTagValues(proto::DropLongTagValues { max_length: 1 }),
+                Rule::DropMeasurement(proto::DropMeasurement {
+                    measurement_regex: "(".to_string(),
+                }),
+            ])),
+            Err(ValidationError::InvalidRegex { index: 1, .. })
+        );
+
+        assert_matches!(
+            WriteRules::try_from(rules([Rule::DropField(proto::DropField {
+                measurement_regex: "".to_string(),
+                field_regex: ".*".to_string(),
+            })])),
+            Err(ValidationError::TimeColumn { index: 0 })
+        );
+
+        assert_matches!(
+            WriteRules::try_from(rules([Rule::RenameTag(proto::RenameTag {
+                from: "host".to_string(),
+                to: "".to_string(),
+            })])),
+            Err(ValidationError::EmptyTagKey { index: 0 })
+        );
+
+        assert_matches!(
+            WriteRules::try_from(rules([Rule::AddTag(proto::AddTag {
+                key: "time".to_string(),
+                value: "bananas".to_string(),
+            })])),
+            Err(ValidationError::TimeColumn { index: 0 })
+        );
+
+        assert_matches!(
+            WriteRules::try_from(rules([Rule::DropLongTagValues(
+                proto::DropLongTagValues { max_length: 0 }
+            )])),
+            Err(ValidationError::ZeroMaxLength { index: 0 })
+        );
+    }
+}
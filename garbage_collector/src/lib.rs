@@ -20,13 +20,13 @@
 #![allow(clippy::missing_docs_in_private_items)]
 
 // Workaround for "unused crate" lint false positives.
-use clap as _;
 use workspace_hack as _;
 
 use crate::{
     objectstore::{checker as os_checker, deleter as os_deleter, lister as os_lister},
     parquetfile::deleter as pf_deleter,
     retention::flagger as retention_flagger,
+    tiering::mover as tiering_mover,
 };
 
 use clap_blocks::garbage_collector::GarbageCollectorConfig;
@@ -43,8 +43,13 @@ use tokio_util::sync::CancellationToken;
 mod objectstore;
 /// Logic for deleting parquet files from the catalog
 mod parquetfile;
+/// An inventory of the object store, classifying every object the way the garbage collector
+/// would, without deleting or modifying anything
+pub mod report;
 /// Logic for flagging parquet files for deletion based on retention settings
 mod retention;
+/// Logic for moving parquet files between storage tiers
+mod tiering;
 
 const BUFFER_SIZE: usize = 1000;
 
@@ -61,6 +66,36 @@ pub struct GarbageCollector {
     os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
     pf_deleter: tokio::task::JoinHandle<Result<(), pf_deleter::Error>>,
     retention_flagger: tokio::task::JoinHandle<Result<(), retention_flagger::Error>>,
+    cold_tier: Option<ColdTierTasks>,
+}
+
+/// The tasks that maintain the cold storage tier, if one is configured.
+struct ColdTierTasks {
+    os_lister: tokio::task::JoinHandle<Result<(), os_lister::Error>>,
+    os_checker: tokio::task::JoinHandle<Result<(), os_checker::Error>>,
+    os_deleter: tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
+    mover: tokio::task::JoinHandle<Result<(), tiering_mover::Error>>,
+}
+
+impl ColdTierTasks {
+    async fn join(self) -> Result<()> {
+        let Self {
+            os_lister,
+            os_checker,
+            os_deleter,
+            mover,
+        } = self;
+
+        let (os_lister, os_checker, os_deleter, mover) =
+            futures::join!(os_lister, os_checker, os_deleter, mover);
+
+        mover.context(TieringMoverPanicSnafu)??;
+        os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
+        os_checker.context(ObjectStoreCheckerPanicSnafu)??;
+        os_lister.context(ObjectStoreListerPanicSnafu)??;
+
+        Ok(())
+    }
 }
 
 impl Debug for GarbageCollector {
@@ -74,85 +109,68 @@ impl GarbageCollector {
     pub fn start(config: Config) -> Result<Self> {
         let Config {
             object_store,
+            cold_object_store,
             sub_config,
             catalog,
         } = config;
 
-        let dry_run = sub_config.dry_run;
         info!(
             objectstore_cutoff_days = %format_duration(sub_config.objectstore_cutoff).to_string(),
             parquetfile_cutoff_days = %format_duration(sub_config.parquetfile_cutoff).to_string(),
             objectstore_sleep_interval_minutes = %sub_config.objectstore_sleep_interval_minutes,
             parquetfile_sleep_interval_minutes = %sub_config.parquetfile_sleep_interval_minutes,
             retention_sleep_interval_minutes = %sub_config.retention_sleep_interval_minutes,
+            tiering_sleep_interval_minutes = %sub_config.tiering_sleep_interval_minutes,
+            cold_tier = cold_object_store.is_some(),
             "GarbageCollector starting"
         );
 
         // Shutdown handler channel to notify children
         let shutdown = CancellationToken::new();
 
-        // Initialise the object store garbage collector, which works as three communicating threads:
-        // - lister lists objects in the object store and sends them on a channel. the lister will
-        //   run until it has enumerated all matching files, then sleep for the configured
-        //   interval.
-        // - checker receives from that channel and checks the catalog to see if they exist, if not
-        //   it sends them on another channel
-        // - deleter receives object store entries that have been checked and therefore should be
-        //   deleted.
-        let (tx1, rx1) = mpsc::channel(BUFFER_SIZE);
-        let (tx2, rx2) = mpsc::channel(BUFFER_SIZE);
-
-        let sdt = shutdown.clone();
-        let osa = Arc::clone(&object_store);
-
-        let os_lister = tokio::spawn(async move {
-            select! {
-                ret = os_lister::perform(
-                    osa,
-                    tx1,
-                    sub_config.objectstore_sleep_interval_minutes,
-                    sub_config.objectstore_sleep_interval_batch_milliseconds,
-                ) => {
-                    ret
-                },
-                _ = sdt.cancelled() => {
-                    Ok(())
-                },
-            }
-        });
-
-        let cat = Arc::clone(&catalog);
-        let sdt = shutdown.clone();
         let cutoff = chrono::Duration::from_std(sub_config.objectstore_cutoff).map_err(|e| {
             Error::CutoffError {
                 message: e.to_string(),
             }
         })?;
 
-        let os_checker = tokio::spawn(async move {
-            select! {
-                ret = os_checker::perform(
-                    cat,
-                    cutoff,
-                    rx1,
-                    tx2,
-                ) => {
-                    ret
-                },
-                _ = sdt.cancelled() => {
-                    Ok(())
-                },
+        let (os_lister, os_checker, os_deleter) = spawn_object_store_tasks(
+            &shutdown,
+            Arc::clone(&object_store),
+            Arc::clone(&catalog),
+            sub_config,
+            cutoff,
+        );
+
+        // The cold tier, if configured, is garbage collected exactly like the primary object
+        // store, and additionally has parquet files moved into it once they are older than the
+        // cold tier age of their namespace.
+        let cold_tier = cold_object_store.map(|cold_object_store| {
+            let (os_lister, os_checker, os_deleter) = spawn_object_store_tasks(
+                &shutdown,
+                Arc::clone(&cold_object_store),
+                Arc::clone(&catalog),
+                sub_config,
+                cutoff,
+            );
+
+            let mover = tokio::spawn(tiering_mover::perform(
+                shutdown.clone(),
+                Arc::clone(&catalog),
+                Arc::clone(&object_store),
+                cold_object_store,
+                sub_config.tiering_sleep_interval_minutes,
+                sub_config.dry_run,
+            ));
+
+            ColdTierTasks {
+                os_lister,
+                os_checker,
+                os_deleter,
+                mover,
             }
         });
 
-        let os_deleter = tokio::spawn(os_deleter::perform(
-            shutdown.clone(),
-            object_store,
-            dry_run,
-            sub_config.objectstore_concurrent_deletes,
-            rx2,
-        ));
-
         // Initialise the parquet file deleter, which is just one thread that calls delete_old()
         // on the catalog then sleeps.
         let pf_deleter = tokio::spawn(pf_deleter::perform(
@@ -178,6 +196,7 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            cold_tier,
         })
     }
 
@@ -197,17 +216,28 @@ impl GarbageCollector {
             os_deleter,
             pf_deleter,
             retention_flagger,
+            cold_tier,
             shutdown: _,
         } = self;
 
-        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger) = futures::join!(
-            os_lister,
-            os_checker,
-            os_deleter,
-            pf_deleter,
-            retention_flagger
-        );
-
+        let cold_tier = async move {
+            match cold_tier {
+                Some(cold_tier) => cold_tier.join().await,
+                None => Ok(()),
+            }
+        };
+
+        let (os_lister, os_checker, os_deleter, pf_deleter, retention_flagger, cold_tier) =
+            futures::join!(
+                os_lister,
+                os_checker,
+                os_deleter,
+                pf_deleter,
+                retention_flagger,
+                cold_tier
+            );
+
+        cold_tier?;
         retention_flagger.context(ParquetFileDeleterPanicSnafu)??;
         pf_deleter.context(ParquetFileDeleterPanicSnafu)??;
         os_deleter.context(ObjectStoreDeleterPanicSnafu)??;
@@ -218,12 +248,86 @@ impl GarbageCollector {
     }
 }
 
+/// Spawn the tasks that garbage collect `object_store`.
+fn spawn_object_store_tasks(
+    shutdown: &CancellationToken,
+    object_store: Arc<DynObjectStore>,
+    catalog: Arc<dyn Catalog>,
+    sub_config: GarbageCollectorConfig,
+    cutoff: chrono::Duration,
+) -> (
+    tokio::task::JoinHandle<Result<(), os_lister::Error>>,
+    tokio::task::JoinHandle<Result<(), os_checker::Error>>,
+    tokio::task::JoinHandle<Result<(), os_deleter::Error>>,
+) {
+    // Initialise the object store garbage collector, which works as three communicating threads:
+    // - lister lists objects in the object store and sends them on a channel. the lister will
+    //   run until it has enumerated all matching files, then sleep for the configured
+    //   interval.
+    // - checker receives from that channel and checks the catalog to see if they exist, if not
+    //   it sends them on another channel
+    // - deleter receives object store entries that have been checked and therefore should be
+    //   deleted.
+    let (tx1, rx1) = mpsc::channel(BUFFER_SIZE);
+    let (tx2, rx2) = mpsc::channel(BUFFER_SIZE);
+
+    let sdt = shutdown.clone();
+    let osa = Arc::clone(&object_store);
+
+    let os_lister = tokio::spawn(async move {
+        select! {
+            ret = os_lister::perform(
+                osa,
+                tx1,
+                sub_config.objectstore_sleep_interval_minutes,
+                sub_config.objectstore_sleep_interval_batch_milliseconds,
+            ) => {
+                ret
+            },
+            _ = sdt.cancelled() => {
+                Ok(())
+            },
+        }
+    });
+
+    let sdt = shutdown.clone();
+    let os_checker = tokio::spawn(async move {
+        select! {
+            ret = os_checker::perform(
+                catalog,
+                cutoff,
+                rx1,
+                tx2,
+            ) => {
+                ret
+            },
+            _ = sdt.cancelled() => {
+                Ok(())
+            },
+        }
+    });
+
+    let os_deleter = tokio::spawn(os_deleter::perform(
+        shutdown.clone(),
+        object_store,
+        sub_config.dry_run,
+        sub_config.objectstore_concurrent_deletes,
+        rx2,
+    ));
+
+    (os_lister, os_checker, os_deleter)
+}
+
 /// Configuration to run the object store garbage collector
 #[derive(Clone)]
 pub struct Config {
     /// The object store to garbage collect
     pub object_store: Arc<DynObjectStore>,
 
+    /// The object store of the cold storage tier to move old parquet files to and garbage
+    /// collect, if any
+    pub cold_object_store: Option<Arc<DynObjectStore>>,
+
     /// The catalog to check if an object is garbage
     pub catalog: Arc<dyn Catalog>,
 
@@ -274,6 +378,12 @@ pub enum Error {
     ParquetFileRetentionFlagger { source: retention_flagger::Error },
     #[snafu(display("The parquet file retention flagger task panicked"))]
     ParquetFileRetentionFlaggerPanic { source: tokio::task::JoinError },
+
+    #[snafu(display("The cold tier mover task failed"))]
+    #[snafu(context(false))]
+    TieringMover { source: tiering_mover::Error },
+    #[snafu(display("The cold tier mover task panicked"))]
+    TieringMoverPanic { source: tokio::task::JoinError },
 }
 
 #[allow(missing_docs)]
@@ -346,6 +456,7 @@ mod tests {
 
         Config {
             object_store,
+            cold_object_store: None,
             catalog,
             sub_config,
         }
@@ -229,7 +229,8 @@ mod tests {
     use async_trait::async_trait;
     use data_types::{
         ColumnId, ColumnSet, CompactionLevel, NamespaceId, ParquetFile, ParquetFileId,
-        ParquetFileParams, PartitionId, TableId, Timestamp, TransitionPartitionId,
+        ParquetFileParams, PartitionId, SortedColumnSet, StorageTier, TableId, Timestamp,
+        TransitionPartitionId,
     };
     use iox_catalog::{
         interface::Catalog,
@@ -513,6 +514,16 @@ mod tests {
             self.inner.create(parquet_file_params).await
         }
 
+        async fn create_if_sort_key(
+            &mut self,
+            parquet_file_params: ParquetFileParams,
+            sort_key_ids: &SortedColumnSet,
+        ) -> iox_catalog::interface::Result<ParquetFile> {
+            self.inner
+                .create_if_sort_key(parquet_file_params, sort_key_ids)
+                .await
+        }
+
         async fn list_all(&mut self) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
             self.inner.list_all().await
         }
@@ -581,5 +592,47 @@ mod tests {
             self.create_upgrade_delete(delete, upgrade, create, target_level)
                 .await
         }
+
+        async fn rewrite_sort_key(
+            &mut self,
+            partition_id: &TransitionPartitionId,
+            old_sort_key_ids: &SortedColumnSet,
+            new_sort_key: &[&str],
+            new_sort_key_ids: &SortedColumnSet,
+            delete: &[ParquetFileId],
+            create: &[ParquetFileParams],
+        ) -> iox_catalog::interface::Result<Vec<ParquetFileId>> {
+            self.inner
+                .rewrite_sort_key(
+                    partition_id,
+                    old_sort_key_ids,
+                    new_sort_key,
+                    new_sort_key_ids,
+                    delete,
+                    create,
+                )
+                .await
+        }
+
+        async fn list_by_namespace_tier_older_than(
+            &mut self,
+            namespace_id: NamespaceId,
+            storage_tier: StorageTier,
+            older_than: Timestamp,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFile>> {
+            self.inner
+                .list_by_namespace_tier_older_than(namespace_id, storage_tier, older_than)
+                .await
+        }
+
+        async fn update_storage_tier(
+            &mut self,
+            parquet_file_ids: &[ParquetFileId],
+            storage_tier: StorageTier,
+        ) -> iox_catalog::interface::Result<Vec<ParquetFileId>> {
+            self.inner
+                .update_storage_tier(parquet_file_ids, storage_tier)
+                .await
+        }
     }
 }
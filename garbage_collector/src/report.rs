@@ -0,0 +1,635 @@
+use arrow::{
+    array::{ArrayRef, Int64Array, StringArray, TimestampNanosecondArray, UInt64Array},
+    error::ArrowError,
+    record_batch::RecordBatch,
+};
+use chrono::{DateTime, Utc};
+use data_types::{NamespaceId, TableId};
+use futures::prelude::*;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use object_store::{DynObjectStore, ObjectMeta};
+use observability_deps::tracing::*;
+use parquet::arrow::ArrowWriter;
+use snafu::prelude::*;
+use std::{
+    collections::{BTreeMap, HashMap},
+    fmt::Display,
+    fs::File,
+    path::Path,
+    sync::Arc,
+};
+use uuid::Uuid;
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Error converting parsed duration: {message}"))]
+    Cutoff { message: String },
+
+    #[snafu(display("The catalog could not be read: {source}"))]
+    Catalog {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("The object store could not be listed: {source}"))]
+    Listing { source: object_store::Error },
+
+    #[snafu(display("The report could not be built: {source}"))]
+    Arrow { source: ArrowError },
+
+    #[snafu(display("The report could not be written as parquet: {source}"))]
+    Parquet {
+        source: parquet::errors::ParquetError,
+    },
+
+    #[snafu(display("The report file {path} could not be created: {source}"))]
+    CreateFile {
+        source: std::io::Error,
+        path: String,
+    },
+}
+
+#[allow(missing_docs)]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// What the garbage collector makes of an object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Classification {
+    /// A parquet file in the catalog that is not flagged for deletion.
+    Referenced,
+    /// A parquet file in the catalog that is flagged for deletion. Its catalog row is removed
+    /// once older than the parquet file cutoff, after which the object is orphaned.
+    SoftDeleted,
+    /// An object not in the catalog and older than the object store cutoff, that the garbage
+    /// collector deletes.
+    Orphaned,
+    /// An object not in the catalog but newer than the object store cutoff, that may still be
+    /// added to the catalog.
+    TooNew,
+}
+
+impl Display for Classification {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Referenced => write!(f, "referenced"),
+            Self::SoftDeleted => write!(f, "soft_deleted"),
+            Self::Orphaned => write!(f, "orphaned"),
+            Self::TooNew => write!(f, "too_new"),
+        }
+    }
+}
+
+/// The file format of a written [`Report`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ReportFormat {
+    /// Comma separated values, with a header row.
+    Csv,
+    /// Parquet.
+    Parquet,
+}
+
+impl ReportFormat {
+    fn extension(&self) -> &'static str {
+        match self {
+            Self::Csv => "csv",
+            Self::Parquet => "parquet",
+        }
+    }
+}
+
+/// A single object of an object store.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ObjectEntry {
+    /// The object store tier the object was listed in, `hot` or `cold`.
+    pub tier: &'static str,
+    /// The location of the object.
+    pub location: String,
+    /// The size of the object in bytes.
+    pub size: u64,
+    /// When the object was last modified.
+    pub last_modified: DateTime<Utc>,
+    /// The namespace of the object, from the catalog or else its location.
+    pub namespace_id: Option<NamespaceId>,
+    /// The table of the object, from the catalog or else its location.
+    pub table_id: Option<TableId>,
+    /// What the garbage collector makes of the object.
+    pub classification: Classification,
+}
+
+/// The objects of a table, of a given classification, in an object store tier.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SummaryEntry {
+    /// The object store tier.
+    pub tier: &'static str,
+    /// The namespace, if known.
+    pub namespace_id: Option<NamespaceId>,
+    /// The name of the namespace, if it is in the catalog.
+    pub namespace_name: Option<String>,
+    /// The table, if known.
+    pub table_id: Option<TableId>,
+    /// The name of the table, if it is in the catalog.
+    pub table_name: Option<String>,
+    /// The classification of the objects.
+    pub classification: Classification,
+    /// The number of objects.
+    pub objects: u64,
+    /// The total size of the objects in bytes.
+    pub bytes: u64,
+}
+
+/// An inventory of the object stores.
+#[derive(Debug, Default)]
+pub struct Report {
+    /// Every object listed.
+    pub objects: Vec<ObjectEntry>,
+    /// The objects and bytes per tier, namespace, table and classification.
+    pub summary: Vec<SummaryEntry>,
+}
+
+/// What the catalog knows about an object store ID.
+#[derive(Debug, Clone, Copy)]
+struct CatalogFile {
+    namespace_id: NamespaceId,
+    table_id: TableId,
+    soft_deleted: bool,
+}
+
+/// Build a [`Report`] of every object of the `stores`, named by tier, classifying unreferenced
+/// objects modified within `cutoff` as too new to judge.
+///
+/// Only reads the catalog and lists the object stores.
+pub async fn build_report(
+    catalog: Arc<dyn Catalog>,
+    stores: &[(&'static str, Arc<DynObjectStore>)],
+    cutoff: std::time::Duration,
+) -> Result<Report> {
+    let cutoff = chrono::Duration::from_std(cutoff).map_err(|e| Error::Cutoff {
+        message: e.to_string(),
+    })?;
+    let older_than = Utc::now() - cutoff;
+
+    let mut repos = catalog.repositories().await;
+
+    let files = repos
+        .parquet_files()
+        .list_all()
+        .await
+        .context(CatalogSnafu)?
+        .into_iter()
+        .map(|f| {
+            (
+                f.object_store_id,
+                CatalogFile {
+                    namespace_id: f.namespace_id,
+                    table_id: f.table_id,
+                    soft_deleted: f.to_delete.is_some(),
+                },
+            )
+        })
+        .collect::<HashMap<_, _>>();
+    let namespace_names = repos
+        .namespaces()
+        .list(SoftDeletedRows::AllRows)
+        .await
+        .context(CatalogSnafu)?
+        .into_iter()
+        .map(|ns| (ns.id, ns.name))
+        .collect::<HashMap<_, _>>();
+    let table_names = repos
+        .tables()
+        .list()
+        .await
+        .context(CatalogSnafu)?
+        .into_iter()
+        .map(|t| (t.id, t.name))
+        .collect::<HashMap<_, _>>();
+    drop(repos);
+
+    let mut objects = vec![];
+    for &(tier, ref store) in stores {
+        info!(tier, "listing object store");
+        let mut items = store.list(None).await.context(ListingSnafu)?;
+        while let Some(item) = items.try_next().await.context(ListingSnafu)? {
+            objects.push(classify(tier, item, &files, older_than));
+        }
+    }
+
+    let mut totals: BTreeMap<_, (u64, u64)> = BTreeMap::new();
+    for object in &objects {
+        let total = totals
+            .entry((
+                object.tier,
+                object.namespace_id,
+                object.table_id,
+                object.classification,
+            ))
+            .or_default();
+        total.0 += 1;
+        total.1 += object.size;
+    }
+    let summary = totals
+        .into_iter()
+        .map(
+            |((tier, namespace_id, table_id, classification), (objects, bytes))| SummaryEntry {
+                tier,
+                namespace_id,
+                namespace_name: namespace_id.and_then(|id| namespace_names.get(&id).cloned()),
+                table_id,
+                table_name: table_id.and_then(|id| table_names.get(&id).cloned()),
+                classification,
+                objects,
+                bytes,
+            },
+        )
+        .collect();
+
+    Ok(Report { objects, summary })
+}
+
+/// Classify `item` like the object store checker does, additionally telling apart soft-deleted
+/// and too new files.
+fn classify(
+    tier: &'static str,
+    item: ObjectMeta,
+    files: &HashMap<Uuid, CatalogFile>,
+    older_than: DateTime<Utc>,
+) -> ObjectEntry {
+    let parts = item.location.parts().collect::<Vec<_>>();
+    let catalog_file = parts
+        .last()
+        .and_then(|name| name.as_ref().strip_suffix(".parquet"))
+        .and_then(|uuid| uuid.parse::<Uuid>().ok())
+        .and_then(|uuid| files.get(&uuid));
+
+    let (namespace_id, table_id, classification) = match catalog_file {
+        Some(f) if f.soft_deleted => (
+            Some(f.namespace_id),
+            Some(f.table_id),
+            Classification::SoftDeleted,
+        ),
+        Some(f) => (
+            Some(f.namespace_id),
+            Some(f.table_id),
+            Classification::Referenced,
+        ),
+        None => {
+            // Parquet files are stored at `<namespace>/<table>/<partition>/<uuid>.parquet`.
+            let id = |i: usize| {
+                parts
+                    .get(i)
+                    .filter(|_| parts.len() == 4)
+                    .and_then(|part| part.as_ref().parse::<i64>().ok())
+            };
+            let classification = if older_than < item.last_modified {
+                Classification::TooNew
+            } else {
+                Classification::Orphaned
+            };
+            (
+                id(0).map(NamespaceId::new),
+                id(1).map(TableId::new),
+                classification,
+            )
+        }
+    };
+
+    ObjectEntry {
+        tier,
+        location: item.location.to_string(),
+        size: item.size as u64,
+        last_modified: item.last_modified,
+        namespace_id,
+        table_id,
+        classification,
+    }
+}
+
+impl Report {
+    /// The [`ObjectEntry`]s as a [`RecordBatch`].
+    pub fn objects_batch(&self) -> Result<RecordBatch> {
+        let objects = &self.objects;
+        RecordBatch::try_from_iter([
+            (
+                "tier",
+                Arc::new(StringArray::from_iter_values(
+                    objects.iter().map(|o| o.tier),
+                )) as ArrayRef,
+            ),
+            (
+                "location",
+                Arc::new(StringArray::from_iter_values(
+                    objects.iter().map(|o| o.location.as_str()),
+                )) as ArrayRef,
+            ),
+            (
+                "size",
+                Arc::new(UInt64Array::from_iter_values(
+                    objects.iter().map(|o| o.size),
+                )) as ArrayRef,
+            ),
+            (
+                "last_modified",
+                Arc::new(TimestampNanosecondArray::from_iter_values(
+                    objects
+                        .iter()
+                        .map(|o| o.last_modified.timestamp_nanos_opt().unwrap_or_default()),
+                )) as ArrayRef,
+            ),
+            (
+                "namespace_id",
+                Arc::new(Int64Array::from_iter(
+                    objects.iter().map(|o| o.namespace_id.map(|id| id.get())),
+                )) as ArrayRef,
+            ),
+            (
+                "table_id",
+                Arc::new(Int64Array::from_iter(
+                    objects.iter().map(|o| o.table_id.map(|id| id.get())),
+                )) as ArrayRef,
+            ),
+            (
+                "classification",
+                Arc::new(StringArray::from_iter_values(
+                    objects.iter().map(|o| o.classification.to_string()),
+                )) as ArrayRef,
+            ),
+        ])
+        .context(ArrowSnafu)
+    }
+
+    /// The [`SummaryEntry`]s as a [`RecordBatch`].
+    pub fn summary_batch(&self) -> Result<RecordBatch> {
+        let summary = &self.summary;
+        RecordBatch::try_from_iter([
+            (
+                "tier",
+                Arc::new(StringArray::from_iter_values(
+                    summary.iter().map(|s| s.tier),
+                )) as ArrayRef,
+            ),
+            (
+                "namespace_id",
+                Arc::new(Int64Array::from_iter(
+                    summary.iter().map(|s| s.namespace_id.map(|id| id.get())),
+                )) as ArrayRef,
+            ),
+            (
+                "namespace_name",
+                Arc::new(StringArray::from_iter(
+                    summary.iter().map(|s| s.namespace_name.as_deref()),
+                )) as ArrayRef,
+            ),
+            (
+                "table_id",
+                Arc::new(Int64Array::from_iter(
+                    summary.iter().map(|s| s.table_id.map(|id| id.get())),
+                )) as ArrayRef,
+            ),
+            (
+                "table_name",
+                Arc::new(StringArray::from_iter(
+                    summary.iter().map(|s| s.table_name.as_deref()),
+                )) as ArrayRef,
+            ),
+            (
+                "classification",
+                Arc::new(StringArray::from_iter_values(
+                    summary.iter().map(|s| s.classification.to_string()),
+                )) as ArrayRef,
+            ),
+            (
+                "objects",
+                Arc::new(UInt64Array::from_iter_values(
+                    summary.iter().map(|s| s.objects),
+                )) as ArrayRef,
+            ),
+            (
+                "bytes",
+                Arc::new(UInt64Array::from_iter_values(
+                    summary.iter().map(|s| s.bytes),
+                )) as ArrayRef,
+            ),
+        ])
+        .context(ArrowSnafu)
+    }
+
+    /// Write the report to `objects.<ext>` and `summary.<ext>` in `dir`, in `format`.
+    pub fn write(&self, dir: &Path, format: ReportFormat) -> Result<()> {
+        for (name, batch) in [
+            ("objects", self.objects_batch()?),
+            ("summary", self.summary_batch()?),
+        ] {
+            let path = dir.join(format!("{name}.{}", format.extension()));
+            let file = File::create(&path).context(CreateFileSnafu {
+                path: path.display().to_string(),
+            })?;
+
+            match format {
+                ReportFormat::Csv => {
+                    let mut writer = arrow::csv::WriterBuilder::new()
+                        .has_headers(true)
+                        .build(file);
+                    writer.write(&batch).context(ArrowSnafu)?;
+                }
+                ReportFormat::Parquet => {
+                    let mut writer =
+                        ArrowWriter::try_new(file, batch.schema(), None).context(ParquetSnafu)?;
+                    writer.write(&batch).context(ParquetSnafu)?;
+                    writer.close().context(ParquetSnafu)?;
+                }
+            }
+            info!(path = %path.display(), rows = batch.num_rows(), "wrote report");
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use data_types::{
+        ColumnId, ColumnSet, CompactionLevel, ParquetFile, ParquetFileParams, Timestamp,
+    };
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use object_store::{memory::InMemory, path::Path as ObjectPath};
+    use parquet_file::ParquetFilePath;
+    use std::time::Duration;
+
+    async fn create_file(catalog: &Arc<dyn Catalog>, table_name: &str) -> ParquetFile {
+        let mut repos = catalog.repositories().await;
+        let namespace = match repos
+            .namespaces()
+            .get_by_name("ns", SoftDeletedRows::AllRows)
+            .await
+        {
+            Ok(Some(namespace)) => namespace,
+            _ => arbitrary_namespace(&mut *repos, "ns").await,
+        };
+        let table = arbitrary_table(&mut *repos, table_name, &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+
+        repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                namespace_id: namespace.id,
+                table_id: table.id,
+                partition_id: partition.transition_partition_id(),
+                object_store_id: Uuid::new_v4(),
+                min_time: Timestamp::new(1),
+                max_time: Timestamp::new(10),
+                file_size_bytes: 3,
+                row_count: 0,
+                compaction_level: CompactionLevel::Initial,
+                created_at: Timestamp::new(1),
+                column_set: ColumnSet::new([ColumnId::new(1)]),
+                max_l0_created_at: Timestamp::new(1),
+            })
+            .await
+            .unwrap()
+    }
+
+    async fn put(store: &Arc<DynObjectStore>, location: ObjectPath, data: &'static [u8]) {
+        store.put(&location, Bytes::from(data)).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn classifies_and_sums_objects() {
+        let metric_registry = Arc::new(metric::Registry::new());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metric_registry));
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let referenced = create_file(&catalog, "referenced").await;
+        let soft_deleted = create_file(&catalog, "soft_deleted").await;
+        catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .create_upgrade_delete(&[soft_deleted.id], &[], &[], CompactionLevel::Initial)
+            .await
+            .unwrap();
+
+        put(
+            &store,
+            ParquetFilePath::from(&referenced).object_store_path(),
+            b"abc",
+        )
+        .await;
+        put(
+            &store,
+            ParquetFilePath::from(&soft_deleted).object_store_path(),
+            b"abc",
+        )
+        .await;
+        // Two unknown files of the referenced table, and an object that isn't a parquet file.
+        let mut unknown = referenced.clone();
+        for data in [b"ab".as_slice(), b"abcd".as_slice()] {
+            unknown.object_store_id = Uuid::new_v4();
+            put(
+                &store,
+                ParquetFilePath::from(&unknown).object_store_path(),
+                data,
+            )
+            .await;
+        }
+        put(&store, ObjectPath::from("not_a_parquet_file.txt"), b"a").await;
+
+        let summarise = |report: &Report| {
+            report
+                .summary
+                .iter()
+                .map(|s| (s.table_name.clone(), s.classification, s.objects, s.bytes))
+                .collect::<Vec<_>>()
+        };
+
+        // Every object is older than no cutoff at all.
+        let report = build_report(
+            Arc::clone(&catalog),
+            &[("hot", Arc::clone(&store))],
+            Duration::ZERO,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.objects.len(), 5);
+        assert_eq!(
+            summarise(&report),
+            [
+                (None, Classification::Orphaned, 1, 1),
+                (
+                    Some("referenced".to_string()),
+                    Classification::Referenced,
+                    1,
+                    3
+                ),
+                (
+                    Some("referenced".to_string()),
+                    Classification::Orphaned,
+                    2,
+                    6
+                ),
+                (
+                    Some("soft_deleted".to_string()),
+                    Classification::SoftDeleted,
+                    1,
+                    3
+                ),
+            ]
+        );
+
+        // Unreferenced objects are too new to judge with a cutoff of an hour.
+        let report = build_report(
+            Arc::clone(&catalog),
+            &[("hot", Arc::clone(&store))],
+            Duration::from_secs(60 * 60),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            summarise(&report),
+            [
+                (None, Classification::TooNew, 1, 1),
+                (
+                    Some("referenced".to_string()),
+                    Classification::Referenced,
+                    1,
+                    3
+                ),
+                (Some("referenced".to_string()), Classification::TooNew, 2, 6),
+                (
+                    Some("soft_deleted".to_string()),
+                    Classification::SoftDeleted,
+                    1,
+                    3
+                ),
+            ]
+        );
+
+        // Nothing was deleted.
+        let listed = store
+            .list(None)
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await;
+        assert_eq!(listed.unwrap().len(), 5);
+
+        // The report can be written in both formats.
+        let dir = tempfile::tempdir().unwrap();
+        report.write(dir.path(), ReportFormat::Csv).unwrap();
+        report.write(dir.path(), ReportFormat::Parquet).unwrap();
+        let csv = std::fs::read_to_string(dir.path().join("summary.csv")).unwrap();
+        assert!(csv.starts_with(
+            "tier,namespace_id,namespace_name,table_id,table_name,classification,objects,bytes\n"
+        ));
+        assert_eq!(csv.lines().count(), 5);
+        assert!(dir.path().join("objects.parquet").exists());
+    }
+}
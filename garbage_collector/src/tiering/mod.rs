@@ -0,0 +1,2 @@
+/// Logic for moving parquet files to the cold storage tier based on namespace settings
+pub(crate) mod mover;
@@ -0,0 +1,359 @@
+use chrono::Utc;
+use data_types::{ParquetFile, ParquetFileId, StorageTier, Timestamp};
+use futures::TryStreamExt;
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use object_store::{path::Path, DynObjectStore};
+use observability_deps::tracing::*;
+use parquet_file::ParquetFilePath;
+use snafu::prelude::*;
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio::{io::AsyncWriteExt, select, time::sleep};
+use tokio_util::sync::CancellationToken;
+
+/// The number of parquet files copied to the cold tier before their move is committed to the
+/// catalog.
+const MOVE_BATCH_SIZE: usize = 100;
+
+pub(crate) async fn perform(
+    shutdown: CancellationToken,
+    catalog: Arc<dyn Catalog>,
+    hot_store: Arc<DynObjectStore>,
+    cold_store: Arc<DynObjectStore>,
+    sleep_interval_minutes: u64,
+    dry_run: bool,
+) -> Result<()> {
+    loop {
+        let now = Utc::now()
+            .timestamp_nanos_opt()
+            .expect("current time out of range");
+        let moved = move_cold_files(&catalog, &hot_store, &cold_store, now, dry_run).await?;
+        info!(moved_count = %moved, "moved parquet files to the cold tier");
+
+        select! {
+            _ = shutdown.cancelled() => {
+                break
+            },
+            _ = sleep(Duration::from_secs(60 * sleep_interval_minutes)) => (),
+        }
+    }
+    Ok(())
+}
+
+/// Move the objects of all hot parquet files whose data is older than the cold tier age of their
+/// namespace at `now` from `hot_store` to `cold_store`, returning the number of files moved.
+///
+/// Each object is copied to the cold tier before the move is committed to the catalog, and only
+/// then deleted from the hot tier, so that queries can always read the file from the tier the
+/// catalog points at. Objects that fail to copy, or whose move fails to commit to the catalog, are
+/// left in the hot tier and retried on the next run.
+async fn move_cold_files(
+    catalog: &Arc<dyn Catalog>,
+    hot_store: &Arc<DynObjectStore>,
+    cold_store: &Arc<DynObjectStore>,
+    now: i64,
+    dry_run: bool,
+) -> Result<usize> {
+    let namespaces = catalog
+        .repositories()
+        .await
+        .namespaces()
+        .list(SoftDeletedRows::ExcludeDeleted)
+        .await
+        .context(ListNamespacesSnafu)?;
+
+    let mut moved = 0;
+    for namespace in namespaces {
+        let Some(cold_tier_age_ns) = namespace.cold_tier_age_ns else {
+            continue;
+        };
+        let cutoff = Timestamp::new(now.saturating_sub(cold_tier_age_ns));
+
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_namespace_tier_older_than(namespace.id, StorageTier::Hot, cutoff)
+            .await
+            .context(ListParquetFilesSnafu)?;
+
+        if dry_run {
+            info!(
+                namespace = %namespace.name,
+                file_count = %files.len(),
+                "Not moving parquet files to the cold tier due to dry run",
+            );
+            continue;
+        }
+
+        for batch in files.chunks(MOVE_BATCH_SIZE) {
+            moved += move_batch(catalog, hot_store, cold_store, batch).await?;
+        }
+    }
+
+    Ok(moved)
+}
+
+async fn move_batch(
+    catalog: &Arc<dyn Catalog>,
+    hot_store: &Arc<DynObjectStore>,
+    cold_store: &Arc<DynObjectStore>,
+    files: &[ParquetFile],
+) -> Result<usize> {
+    let mut copied: Vec<(ParquetFileId, Path)> = Vec::with_capacity(files.len());
+    for file in files {
+        let path = ParquetFilePath::from(file).object_store_path();
+        match copy_object(hot_store, cold_store, &path).await {
+            Ok(()) => copied.push((file.id, path)),
+            Err(e) => warn!(%path, error = %e, "failed to copy parquet file to the cold tier"),
+        }
+    }
+
+    let ids = copied.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+    let updated = catalog
+        .repositories()
+        .await
+        .parquet_files()
+        .update_storage_tier(&ids, StorageTier::Cold)
+        .await
+        .context(UpdateStorageTierSnafu);
+
+    commit_batch(hot_store, cold_store, &copied, updated).await
+}
+
+/// Delete the objects of the `copied` files from the tier the catalog does not point at, once the
+/// catalog `updated` the storage tier of the moved files.
+///
+/// If the catalog update failed, the catalog still points at the hot tier for all of the files,
+/// and their copies are removed from the cold tier before the error is returned.
+async fn commit_batch(
+    hot_store: &Arc<DynObjectStore>,
+    cold_store: &Arc<DynObjectStore>,
+    copied: &[(ParquetFileId, Path)],
+    updated: Result<Vec<ParquetFileId>>,
+) -> Result<usize> {
+    let updated = match updated {
+        Ok(v) => v.into_iter().collect::<HashSet<_>>(),
+        Err(e) => {
+            for (_, path) in copied {
+                if let Err(e) = cold_store.delete(path).await {
+                    warn!(%path, error = %e, "failed to delete cold tier copy of parquet file");
+                }
+            }
+            return Err(e);
+        }
+    };
+
+    for (id, path) in copied {
+        // Files flagged for deletion while being copied stay in the hot tier, where they are
+        // garbage collected as usual. Their copy in the cold tier is never referenced.
+        let store = if updated.contains(id) {
+            hot_store
+        } else {
+            cold_store
+        };
+        if let Err(e) = store.delete(path).await {
+            warn!(%path, error = %e, "failed to delete moved parquet file");
+        }
+    }
+
+    Ok(updated.len())
+}
+
+/// Stream the object at `path` from one store to the other as a multipart upload, without
+/// buffering the whole object in memory.
+///
+/// The upload is aborted if it fails.
+async fn copy_object(
+    from: &Arc<DynObjectStore>,
+    to: &Arc<DynObjectStore>,
+    path: &Path,
+) -> Result<()> {
+    let mut stream = from.get(path).await.context(ReadObjectSnafu)?.into_stream();
+    let (multipart_id, mut writer) = to.put_multipart(path).await.context(StartUploadSnafu)?;
+
+    let res = async {
+        while let Some(chunk) = stream.try_next().await.context(ReadObjectSnafu)? {
+            writer.write_all(&chunk).await.context(UploadSnafu)?;
+        }
+        writer.shutdown().await.context(UploadSnafu)
+    }
+    .await;
+
+    if res.is_err() {
+        if let Err(e) = to.abort_multipart(path, &multipart_id).await {
+            warn!(%path, error = %e, "failed to abort parquet file upload to the cold tier");
+        }
+    }
+
+    res
+}
+
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum Error {
+    #[snafu(display("Failed to list namespaces"))]
+    ListNamespaces {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to list parquet files of a namespace"))]
+    ListParquetFiles {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to move parquet files to the cold tier"))]
+    UpdateStorageTier {
+        source: iox_catalog::interface::Error,
+    },
+
+    #[snafu(display("Failed to read parquet file from the hot tier"))]
+    ReadObject { source: object_store::Error },
+
+    #[snafu(display("Failed to start parquet file upload to the cold tier"))]
+    StartUpload { source: object_store::Error },
+
+    #[snafu(display("Failed to upload parquet file to the cold tier"))]
+    Upload { source: std::io::Error },
+}
+
+pub(crate) type Result<T, E = Error> = std::result::Result<T, E>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytes::Bytes;
+    use data_types::{ColumnId, ColumnSet, CompactionLevel, ParquetFileParams, Timestamp};
+    use iox_catalog::{
+        mem::MemCatalog,
+        test_helpers::{arbitrary_namespace, arbitrary_table},
+    };
+    use object_store::memory::InMemory;
+    use uuid::Uuid;
+
+    const DAY_NS: i64 = 24 * 60 * 60 * 1_000_000_000;
+
+    #[tokio::test]
+    async fn moves_only_files_older_than_the_cold_tier_age() {
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Default::default()));
+        let hot_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let cold_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "ns").await;
+        repos
+            .namespaces()
+            .update_cold_tier_age("ns", Some(30 * DAY_NS))
+            .await
+            .unwrap();
+        let table = arbitrary_table(&mut *repos, "table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("one".into(), table.id)
+            .await
+            .unwrap();
+
+        let now = 100 * DAY_NS;
+        let mut files = vec![];
+        for max_time in [now - 60 * DAY_NS, now - DAY_NS] {
+            let file = repos
+                .parquet_files()
+                .create(ParquetFileParams {
+                    namespace_id: namespace.id,
+                    table_id: table.id,
+                    partition_id: partition.transition_partition_id(),
+                    object_store_id: Uuid::new_v4(),
+                    min_time: Timestamp::new(max_time - DAY_NS),
+                    max_time: Timestamp::new(max_time),
+                    file_size_bytes: 1337,
+                    row_count: 1,
+                    compaction_level: CompactionLevel::Final,
+                    created_at: Timestamp::new(max_time),
+                    column_set: ColumnSet::new([ColumnId::new(1)]),
+                    max_l0_created_at: Timestamp::new(max_time),
+                })
+                .await
+                .unwrap();
+            let path = ParquetFilePath::from(&file).object_store_path();
+            hot_store
+                .put(&path, Bytes::from_static(b"parquet"))
+                .await
+                .unwrap();
+            files.push((file, path));
+        }
+        drop(repos);
+
+        // Nothing is moved in dry run mode
+        let moved = move_cold_files(&catalog, &hot_store, &cold_store, now, true)
+            .await
+            .unwrap();
+        assert_eq!(moved, 0);
+
+        let moved = move_cold_files(&catalog, &hot_store, &cold_store, now, false)
+            .await
+            .unwrap();
+        assert_eq!(moved, 1);
+
+        let [(old_file, old_path), (new_file, new_path)] = &files[..] else {
+            unreachable!()
+        };
+        assert!(hot_store.head(old_path).await.is_err());
+        assert!(cold_store.head(old_path).await.is_ok());
+        assert!(hot_store.head(new_path).await.is_ok());
+        assert!(cold_store.head(new_path).await.is_err());
+
+        let files_in_catalog = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_namespace_not_to_delete(namespace.id)
+            .await
+            .unwrap();
+        let tier_of = |id: ParquetFileId| {
+            files_in_catalog
+                .iter()
+                .find(|f| f.id == id)
+                .unwrap()
+                .storage_tier
+        };
+        assert_eq!(tier_of(old_file.id), StorageTier::Cold);
+        assert_eq!(tier_of(new_file.id), StorageTier::Hot);
+
+        // Moved files are not moved again
+        let moved = move_cold_files(&catalog, &hot_store, &cold_store, now, false)
+            .await
+            .unwrap();
+        assert_eq!(moved, 0);
+    }
+
+    /// The cold tier copies of the files are deleted if the move fails to commit to the catalog,
+    /// leaving the files in the hot tier, where they are moved again on the next run.
+    #[tokio::test]
+    async fn deletes_cold_copies_on_catalog_error() {
+        let hot_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let cold_store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+
+        let path = Path::from("1/2/3/4.parquet");
+        hot_store
+            .put(&path, Bytes::from_static(b"parquet"))
+            .await
+            .unwrap();
+
+        copy_object(&hot_store, &cold_store, &path).await.unwrap();
+        let copy = cold_store.get(&path).await.unwrap().bytes().await.unwrap();
+        assert_eq!(copy, Bytes::from_static(b"parquet"));
+
+        let catalog_error = Error::UpdateStorageTier {
+            source: iox_catalog::interface::Error::SqlxError {
+                source: sqlx::Error::PoolTimedOut,
+            },
+        };
+        let copied = [(ParquetFileId::new(1), path.clone())];
+        let err = commit_batch(&hot_store, &cold_store, &copied, Err(catalog_error))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::UpdateStorageTier { .. }));
+
+        assert!(hot_store.head(&path).await.is_ok());
+        assert!(cold_store.head(&path).await.is_err());
+    }
+}
@@ -21,21 +21,25 @@ fn main() -> Result<()> {
 /// - `influxdata.iox.authz.v1.rs`
 /// - `influxdata.iox.catalog.v1.rs`
 /// - `influxdata.iox.compactor.v1.rs`
+/// - `influxdata.iox.compactor_scheduler.v1.rs`
 /// - `influxdata.iox.delete.v1.rs`
 /// - `influxdata.iox.ingester.v1.rs`
 /// - `influxdata.iox.namespace.v1.rs`
 /// - `influxdata.iox.object_store.v1.rs`
 /// - `influxdata.iox.predicate.v1.rs`
 /// - `influxdata.iox.querier.v1.rs`
+/// - `influxdata.iox.rollup.v1.rs`
 /// - `influxdata.iox.schema.v1.rs`
 /// - `influxdata.iox.table.v1.rs`
 /// - `influxdata.iox.wal.v1.rs`
 /// - `influxdata.iox.write.v1.rs`
+/// - `influxdata.iox.write_rules.v1.rs`
 /// - `influxdata.platform.storage.rs`
 fn generate_grpc_types(root: &Path) -> Result<()> {
     let authz_path = root.join("influxdata/iox/authz/v1");
     let catalog_path = root.join("influxdata/iox/catalog/v1");
     let compactor_path = root.join("influxdata/iox/compactor/v1");
+    let compactor_scheduler_path = root.join("influxdata/iox/compactor_scheduler/v1");
     let delete_path = root.join("influxdata/iox/delete/v1");
     let gossip_path = root.join("influxdata/iox/gossip/v1");
     let ingester_path = root.join("influxdata/iox/ingester/v1");
@@ -44,11 +48,13 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
     let partition_template_path = root.join("influxdata/iox/partition_template/v1");
     let predicate_path = root.join("influxdata/iox/predicate/v1");
     let querier_path = root.join("influxdata/iox/querier/v1");
+    let rollup_path = root.join("influxdata/iox/rollup/v1");
     let schema_path = root.join("influxdata/iox/schema/v1");
     let storage_errors_path = root.join("influxdata/platform/errors");
     let storage_path = root.join("influxdata/platform/storage");
     let table_path = root.join("influxdata/iox/table/v1");
     let wal_path = root.join("influxdata/iox/wal/v1");
+    let write_rules_path = root.join("influxdata/iox/write_rules/v1");
 
     let proto_files = vec![
         authz_path.join("authz.proto"),
@@ -56,11 +62,16 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         catalog_path.join("partition_identifier.proto"),
         catalog_path.join("service.proto"),
         compactor_path.join("service.proto"),
+        compactor_scheduler_path.join("service.proto"),
         delete_path.join("service.proto"),
         gossip_path.join("compaction.proto"),
+        gossip_path.join("ingester_state.proto"),
         gossip_path.join("parquet_file.proto"),
+        gossip_path.join("query_stats.proto"),
         gossip_path.join("schema.proto"),
         gossip_path.join("schema_sync.proto"),
+        ingester_path.join("buffer.proto"),
+        ingester_path.join("handoff.proto"),
         ingester_path.join("parquet_metadata.proto"),
         ingester_path.join("persist.proto"),
         ingester_path.join("write.proto"),
@@ -69,6 +80,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         partition_template_path.join("template.proto"),
         predicate_path.join("predicate.proto"),
         querier_path.join("flight.proto"),
+        rollup_path.join("rollup.proto"),
         root.join("google/longrunning/operations.proto"),
         root.join("google/rpc/error_details.proto"),
         root.join("google/rpc/status.proto"),
@@ -83,6 +95,7 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         storage_path.join("test.proto"),
         table_path.join("service.proto"),
         wal_path.join("wal.proto"),
+        write_rules_path.join("rules.proto"),
     ];
 
     // Tell cargo to recompile if any of these proto files are changed
@@ -99,7 +112,8 @@ fn generate_grpc_types(root: &Path) -> Result<()> {
         .btree_map([
             ".influxdata.iox.ingester.v1.IngesterQueryResponseMetadata.unpersisted_partitions",
         ])
-        .type_attribute(".influxdata.iox.partition_template", "#[derive(Hash)]");
+        .type_attribute(".influxdata.iox.partition_template", "#[derive(Hash)]")
+        .type_attribute(".influxdata.iox.write_rules", "#[derive(Hash)]");
 
     let descriptor_path = PathBuf::from(env::var("OUT_DIR").unwrap()).join("proto_descriptor.bin");
     tonic_build::configure()
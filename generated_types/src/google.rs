@@ -590,21 +590,59 @@ where
     }
 }
 
-#[derive(Debug, Default, Clone)]
+/// IOx returns [`QuotaFailure`] when a request is rejected because it would
+/// exceed a configured limit
+#[derive(Debug, Default, Clone, PartialEq)]
 pub struct QuotaFailure {
     pub subject: String,
     pub description: String,
 }
 
+fn encode_quota_failure(quota_failure: QuotaFailure) -> Result<Any, EncodeError> {
+    let mut buffer = BytesMut::new();
+
+    rpc::QuotaFailure {
+        violations: vec![rpc::quota_failure::Violation {
+            subject: quota_failure.subject,
+            description: quota_failure.description,
+        }],
+    }
+    .encode(&mut buffer)?;
+
+    Ok(Any {
+        type_url: "type.googleapis.com/google.rpc.QuotaFailure".to_string(),
+        value: buffer.freeze(),
+    })
+}
+
 impl From<QuotaFailure> for tonic::Status {
     fn from(quota_failure: QuotaFailure) -> Self {
-        tonic::Status::new(
-            tonic::Code::ResourceExhausted,
-            format!("{}: {}", quota_failure.subject, quota_failure.description),
-        )
+        let message = format!("{}: {}", quota_failure.subject, quota_failure.description);
+        match encode_quota_failure(quota_failure) {
+            Ok(details) => encode_status(tonic::Code::ResourceExhausted, message, details),
+            Err(e) => e.into(),
+        }
+    }
+}
+
+impl From<rpc::quota_failure::Violation> for QuotaFailure {
+    fn from(v: rpc::quota_failure::Violation) -> Self {
+        Self {
+            subject: v.subject,
+            description: v.description,
+        }
     }
 }
 
+/// Returns an iterator over the [`QuotaFailure`] in the provided [`tonic::Status`]
+pub fn decode_quota_failure(status: &tonic::Status) -> impl Iterator<Item = QuotaFailure> {
+    get_details(status)
+        .filter(|details| details.type_url == "type.googleapis.com/google.rpc.QuotaFailure")
+        .flat_map(|details| rpc::QuotaFailure::decode(details.value).ok())
+        .flat_map(|failure| failure.violations)
+        .map(Into::into)
+}
+
 /// An extension trait that adds the method `field` to any type implementing
 /// `TryInto<U, Error = FieldViolation>`
 ///
@@ -756,6 +794,15 @@ mod tests {
         let status = tonic::Status::from(precondition.clone());
         let collected: Vec<_> = decode_precondition_violation(&status).collect();
         assert_eq!(collected, vec![precondition]);
+
+        let quota_failure = QuotaFailure {
+            subject: "influxdata.com/iox/series".to_string(),
+            description: "too many series".to_string(),
+        };
+        let status = tonic::Status::from(quota_failure.clone());
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+        let collected: Vec<_> = decode_quota_failure(&status).collect();
+        assert_eq!(collected, vec![quota_failure]);
     }
 
     #[test]
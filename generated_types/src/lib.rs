@@ -82,6 +82,19 @@ pub mod influxdata {
             }
         }
 
+        pub mod compactor_scheduler {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.compactor_scheduler.v1.rs"
+                ));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.compactor_scheduler.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod delete {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.delete.v1.rs"));
@@ -116,6 +129,13 @@ pub mod influxdata {
                 /// Schema cache consistency check / sync / convergence
                 /// messages.
                 SchemaCacheConsistency = 4,
+
+                /// Ingester lifecycle state changes, such as an ingester
+                /// draining before shutdown.
+                IngesterState = 5,
+
+                /// Per-partition read costs observed by the queriers.
+                QueryStats = 6,
             }
 
             impl TryFrom<u64> for Topic {
@@ -129,6 +149,8 @@ pub mod influxdata {
                         v if v == Self::SchemaCacheConsistency as u64 => {
                             Self::SchemaCacheConsistency
                         }
+                        v if v == Self::IngesterState as u64 => Self::IngesterState,
+                        v if v == Self::QueryStats as u64 => Self::QueryStats,
                         _ => return Err(format!("unknown topic id {}", v).into()),
                     })
                 }
@@ -148,6 +170,13 @@ pub mod influxdata {
                     env!("OUT_DIR"),
                     "/influxdata.iox.ingester.v1.serde.rs"
                 ));
+
+                /// The subject of the [`QuotaFailure`] returned by an ingester
+                /// when rejecting a write that would exceed the configured
+                /// per-table series limit.
+                ///
+                /// [`QuotaFailure`]: crate::google::QuotaFailure
+                pub const SERIES_LIMIT_QUOTA_SUBJECT: &str = "influxdata.com/iox/ingester/series";
             }
         }
 
@@ -207,6 +236,16 @@ pub mod influxdata {
             }
         }
 
+        pub mod rollup {
+            pub mod v1 {
+                include!(concat!(env!("OUT_DIR"), "/influxdata.iox.rollup.v1.rs"));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.rollup.v1.serde.rs"
+                ));
+            }
+        }
+
         pub mod schema {
             pub mod v1 {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.schema.v1.rs"));
@@ -233,6 +272,19 @@ pub mod influxdata {
                 include!(concat!(env!("OUT_DIR"), "/influxdata.iox.wal.v1.serde.rs"));
             }
         }
+
+        pub mod write_rules {
+            pub mod v1 {
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.write_rules.v1.rs"
+                ));
+                include!(concat!(
+                    env!("OUT_DIR"),
+                    "/influxdata.iox.write_rules.v1.serde.rs"
+                ));
+            }
+        }
     }
 
     pub mod pbdata {
@@ -330,6 +382,8 @@ mod tests {
             Topic::NewParquetFiles,
             Topic::CompactionEvents,
             Topic::SchemaCacheConsistency,
+            Topic::IngesterState,
+            Topic::QueryStats,
         ];
 
         for topic in topics {
@@ -346,6 +400,8 @@ mod tests {
             Topic::NewParquetFiles => {}
             Topic::CompactionEvents => {}
             Topic::SchemaCacheConsistency => {}
+            Topic::IngesterState => {}
+            Topic::QueryStats => {}
         }
     }
 }
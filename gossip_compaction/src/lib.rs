@@ -230,6 +230,7 @@ mod tests {
             created_at: 12344321,
             column_set: vec![1, 2, 3, 4, 5],
             max_l0_created_at: 123455555,
+            storage_tier: 1,
         };
 
         let new_file_b = ParquetFile {
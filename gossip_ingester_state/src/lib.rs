@@ -0,0 +1,146 @@
+//! Ingester lifecycle state notifications over [gossip].
+//!
+//! This sub-system is composed of the following primary components:
+//!
+//! * [`gossip`] crate: provides the gossip transport, the [`GossipHandle`], and
+//!   the [`Dispatcher`]. This crate operates on raw bytes.
+//!
+//! * The outgoing [`IngesterStateTx`]: an ingester state focussed wrapper over
+//!   the underlying [`GossipHandle`]. This type translates the protobuf
+//!   [`IngesterStateChange`] from the application layer into raw serialised
+//!   bytes, sending them over the underlying [`gossip`] impl.
+//!
+//! * The incoming [`IngesterStateRx`]: deserialises the incoming bytes from the
+//!   gossip [`Dispatcher`] into [`IngesterStateChange`] and passes them off to
+//!   the [`IngesterStateEventHandler`] implementation for processing.
+//!
+//! Ingesters broadcast their state changes (such as starting to drain before
+//! shutdown) with the [`IngesterStateTx`], and routers implement the
+//! [`IngesterStateEventHandler`] to stop sending writes to draining ingesters.
+//!
+//! # Best Effort
+//!
+//! This underlying gossip subsystem is designed to provide best effort delivery
+//! of messages, and therefore best-effort delivery of ingester state changes,
+//! without any ordering or delivery guarantees. Receivers MUST tolerate missed
+//! state changes.
+//!
+//! [`IngesterStateTx`]: tx::IngesterStateTx
+//! [`IngesterStateRx`]: rx::IngesterStateRx
+//! [`IngesterStateEventHandler`]: rx::IngesterStateEventHandler
+//! [`GossipHandle`]: gossip::GossipHandle
+//! [`Dispatcher`]: gossip::Dispatcher
+//! [`IngesterStateChange`]: generated_types::influxdata::iox::gossip::v1::IngesterStateChange
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    unused_crate_dependencies,
+    missing_docs
+)]
+#![allow(clippy::default_constructed_unit_structs)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+pub mod rx;
+pub mod tx;
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use generated_types::influxdata::iox::gossip::v1::{IngesterState, IngesterStateChange};
+    use gossip::Builder;
+    use test_helpers::{maybe_start_logging, timeout::FutureTimeout};
+    use tokio::{net::UdpSocket, sync::mpsc};
+
+    use crate::{
+        rx::{IngesterStateEventHandler, IngesterStateRx},
+        tx::IngesterStateTx,
+    };
+
+    /// Bind a UDP socket on a random port and return it alongside the socket
+    /// address.
+    async fn random_udp() -> (UdpSocket, SocketAddr) {
+        // Bind a UDP socket to a random port
+        let socket = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind UDP socket");
+        let addr = socket.local_addr().expect("failed to read local addr");
+
+        (socket, addr)
+    }
+
+    #[derive(Debug)]
+    struct MockEventHandler(mpsc::Sender<IngesterStateChange>);
+
+    #[async_trait]
+    impl IngesterStateEventHandler for Arc<MockEventHandler> {
+        async fn handle(&self, event: IngesterStateChange) {
+            self.0.send(event).await.unwrap();
+        }
+    }
+
+    /// Ensure an IngesterStateChange can be round-tripped through the gossip
+    /// layer.
+    #[tokio::test]
+    async fn test_round_trip() {
+        maybe_start_logging();
+
+        let metrics = Arc::new(metric::Registry::default());
+
+        let (a_socket, a_addr) = random_udp().await;
+        let (handler, _a_rx) = mpsc::channel(10);
+        let a_dispatcher = IngesterStateRx::new(Arc::new(MockEventHandler(handler)), 100);
+
+        let (b_socket, b_addr) = random_udp().await;
+        let (handler, mut b_rx) = mpsc::channel(10);
+        let b_dispatcher = IngesterStateRx::new(Arc::new(MockEventHandler(handler)), 100);
+
+        // Initialise both gossip reactors
+        let addrs = vec![a_addr.to_string(), b_addr.to_string()];
+        let a = Builder::new(addrs.clone(), a_dispatcher, Arc::clone(&metrics)).build(a_socket);
+        let b = Builder::new(addrs, b_dispatcher, Arc::clone(&metrics)).build(b_socket);
+
+        // Wait for peer discovery to occur
+        async {
+            loop {
+                if a.get_peers().await.len() == 1 && b.get_peers().await.len() == 1 {
+                    break;
+                }
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        let a = IngesterStateTx::new(Arc::new(a));
+
+        let want = IngesterStateChange {
+            address: "http://ingester-0:8083".to_string(),
+            state: IngesterState::Draining.into(),
+        };
+
+        // Broadcast the event from A
+        a.broadcast(want.clone());
+
+        // Receive it from B
+        let got = b_rx
+            .recv()
+            .with_timeout_panic(Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        // Ensuring the content is identical
+        assert_eq!(got, want);
+    }
+}
@@ -0,0 +1,104 @@
+//! A deserialiser and dispatcher of [gossip] messages for the
+//! [`Topic::IngesterState`] topic.
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use generated_types::influxdata::iox::gossip::{v1::IngesterStateChange, Topic};
+use generated_types::prost::Message;
+use gossip::Identity;
+use observability_deps::tracing::{info, warn};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// An [`IngesterStateChange`] notification handler received via gossip.
+#[async_trait]
+pub trait IngesterStateEventHandler: Send + Sync + Debug {
+    /// Process `event`.
+    async fn handle(&self, event: IngesterStateChange);
+}
+
+#[async_trait]
+impl<T> IngesterStateEventHandler for Arc<T>
+where
+    T: IngesterStateEventHandler,
+{
+    async fn handle(&self, event: IngesterStateChange) {
+        T::handle(self, event).await
+    }
+}
+
+/// An async gossip message dispatcher.
+///
+/// This type is responsible for deserialising incoming gossip
+/// [`Topic::IngesterState`] payloads and passing them off to the provided
+/// [`IngesterStateEventHandler`] implementation.
+///
+/// This type also provides a buffer between incoming events, and processing,
+/// preventing processing time from blocking the gossip reactor. Once the buffer
+/// is full, incoming events are dropped until space is made through processing
+/// of outstanding events. Dropping the [`IngesterStateRx`] stops the background
+/// event loop.
+#[derive(Debug)]
+pub struct IngesterStateRx {
+    tx: mpsc::Sender<Bytes>,
+    task: JoinHandle<()>,
+}
+
+impl IngesterStateRx {
+    /// Initialise a new dispatcher, buffering up to `buffer` number of events.
+    ///
+    /// The provided `handler` does not block the gossip reactor during
+    /// execution.
+    pub fn new<T>(handler: T, buffer: usize) -> Self
+    where
+        T: IngesterStateEventHandler + 'static,
+    {
+        // Initialise a buffered channel to decouple the two halves.
+        let (tx, rx) = mpsc::channel(buffer);
+
+        // And run a receiver loop to pull the events from the channel.
+        let task = tokio::spawn(dispatch_loop(rx, handler));
+
+        Self { tx, task }
+    }
+}
+
+#[async_trait]
+impl gossip::Dispatcher<Topic> for IngesterStateRx {
+    async fn dispatch(&self, topic: Topic, payload: Bytes, _sender: Identity) {
+        if topic != Topic::IngesterState {
+            return;
+        }
+        if let Err(e) = self.tx.try_send(payload) {
+            warn!(error=%e, "failed to buffer gossip event");
+        }
+    }
+}
+
+impl Drop for IngesterStateRx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn dispatch_loop<T>(mut rx: mpsc::Receiver<Bytes>, handler: T)
+where
+    T: IngesterStateEventHandler,
+{
+    while let Some(payload) = rx.recv().await {
+        // Deserialise the payload into the appropriate proto type.
+        let event = match IngesterStateChange::decode(payload) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "failed to deserialise gossip message");
+                continue;
+            }
+        };
+
+        // Pass this message off to the handler to process.
+        handler.handle(event).await;
+    }
+
+    info!("stopping gossip dispatcher");
+}
@@ -0,0 +1,78 @@
+//! A serialiser and broadcaster of [`gossip`] messages for the
+//! [`Topic::IngesterState`] topic.
+
+use std::sync::Arc;
+
+use generated_types::{
+    influxdata::iox::gossip::{v1::IngesterStateChange, Topic},
+    prost::Message,
+};
+use observability_deps::tracing::{debug, error, warn};
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+};
+
+/// A gossip broadcast primitive specialised for ingester state change
+/// notifications.
+///
+/// Serialisation and processing of the [`IngesterStateChange`] given to the
+/// [`IngesterStateTx::broadcast()`] method happens in a background actor task,
+/// decoupling the caller from the latency of processing each frame. Dropping
+/// the [`IngesterStateTx`] stops this background actor task.
+#[derive(Debug)]
+pub struct IngesterStateTx {
+    tx: mpsc::Sender<IngesterStateChange>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for IngesterStateTx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl IngesterStateTx {
+    /// Construct a new [`IngesterStateTx`] that publishes gossip messages over
+    /// `gossip`.
+    pub fn new(gossip: Arc<gossip::GossipHandle<Topic>>) -> Self {
+        let (tx, rx) = mpsc::channel(10);
+
+        let task = tokio::spawn(actor_loop(rx, gossip));
+
+        Self { tx, task }
+    }
+
+    /// Asynchronously broadcast `event` to all interested peers.
+    ///
+    /// This method enqueues `event` into the serialisation queue, and processed
+    /// & transmitted asynchronously.
+    pub fn broadcast(&self, event: IngesterStateChange) {
+        debug!(?event, "sending ingester state notification");
+        match self.tx.try_send(event) {
+            Ok(_) => {}
+            Err(TrySendError::Closed(_)) => {
+                panic!("ingester state serialisation actor not running")
+            }
+            Err(TrySendError::Full(_)) => {
+                warn!("ingester state serialisation queue full, dropping message")
+            }
+        }
+    }
+}
+
+async fn actor_loop(
+    mut rx: mpsc::Receiver<IngesterStateChange>,
+    gossip: Arc<gossip::GossipHandle<Topic>>,
+) {
+    while let Some(event) = rx.recv().await {
+        if let Err(e) = gossip
+            .broadcast(event.encode_to_vec(), Topic::IngesterState)
+            .await
+        {
+            error!(error=%e, "failed to broadcast payload");
+        }
+    }
+
+    debug!("stopping ingester state gossip serialisation actor");
+}
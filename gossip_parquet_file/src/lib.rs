@@ -166,12 +166,12 @@ mod tests {
         .await;
 
         let a = Peer {
-            tx: ParquetFileTx::new(a),
+            tx: ParquetFileTx::new(Arc::new(a)),
             rx: a_rx,
         };
 
         let b = Peer {
-            tx: ParquetFileTx::new(b),
+            tx: ParquetFileTx::new(Arc::new(b)),
             rx: b_rx,
         };
 
@@ -206,6 +206,7 @@ mod tests {
             created_at: 12344321,
             column_set: vec![1, 2, 3, 4, 5],
             max_l0_created_at: 123455555,
+            storage_tier: 1,
         };
 
         // Broadcast the event from A
@@ -1,7 +1,7 @@
 //! A serialiser and broadcaster of [`gossip`] messages for the
 //! [`Topic::NewParquetFiles`] topic.
 
-use std::fmt::Debug;
+use std::{fmt::Debug, sync::Arc};
 
 use generated_types::{
     influxdata::iox::{
@@ -46,7 +46,7 @@ where
 {
     /// Construct a new [`ParquetFileTx`] that publishes gossip messages over
     /// `gossip`.
-    pub fn new(gossip: gossip::GossipHandle<Topic>) -> Self {
+    pub fn new(gossip: Arc<gossip::GossipHandle<Topic>>) -> Self {
         let (tx, rx) = mpsc::channel(100);
 
         let task = tokio::spawn(actor_loop(rx, gossip));
@@ -72,7 +72,7 @@ where
     }
 }
 
-async fn actor_loop<T>(mut rx: mpsc::Receiver<T>, gossip: gossip::GossipHandle<Topic>)
+async fn actor_loop<T>(mut rx: mpsc::Receiver<T>, gossip: Arc<gossip::GossipHandle<Topic>>)
 where
     T: Into<ParquetFile> + Send + Sync,
 {
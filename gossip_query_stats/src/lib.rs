@@ -0,0 +1,167 @@
+//! Querier read cost notifications over [gossip].
+//!
+//! This sub-system is composed of the following primary components:
+//!
+//! * [`gossip`] crate: provides the gossip transport, the [`GossipHandle`], and
+//!   the [`Dispatcher`]. This crate operates on raw bytes.
+//!
+//! * The outgoing [`QueryStatsTx`]: a query statistics focussed wrapper over
+//!   the underlying [`GossipHandle`]. This type translates the protobuf
+//!   [`QueryStats`] from the application layer into raw serialised bytes,
+//!   sending them over the underlying [`gossip`] impl.
+//!
+//! * The incoming [`QueryStatsRx`]: deserialises the incoming bytes from the
+//!   gossip [`Dispatcher`] into [`QueryStats`] and passes them off to the
+//!   [`QueryStatsEventHandler`] implementation for processing.
+//!
+//! Queriers periodically broadcast the per-partition read costs they observe
+//! with the [`QueryStatsTx`], and compactors implement the
+//! [`QueryStatsEventHandler`] to prioritise compacting the partitions that are
+//! most expensive to query.
+//!
+//! # Best Effort
+//!
+//! This underlying gossip subsystem is designed to provide best effort delivery
+//! of messages, and therefore best-effort delivery of query statistics, without
+//! any ordering or delivery guarantees. The statistics are only used as a
+//! scheduling hint, and lost messages merely reduce their accuracy.
+//!
+//! # Message Size Restrictions
+//!
+//! The underlying gossip protocol has an upper bound on message size
+//! ([`MAX_USER_PAYLOAD_BYTES`]). As the statistics are additive, the
+//! [`QueryStatsTx`] splits a [`QueryStats`] with many partitions into multiple
+//! messages.
+//!
+//! [`QueryStatsTx`]: tx::QueryStatsTx
+//! [`QueryStatsRx`]: rx::QueryStatsRx
+//! [`QueryStatsEventHandler`]: rx::QueryStatsEventHandler
+//! [`GossipHandle`]: gossip::GossipHandle
+//! [`Dispatcher`]: gossip::Dispatcher
+//! [`MAX_USER_PAYLOAD_BYTES`]: gossip::MAX_USER_PAYLOAD_BYTES
+//! [`QueryStats`]: generated_types::influxdata::iox::gossip::v1::QueryStats
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_copy_implementations,
+    missing_debug_implementations,
+    unused_crate_dependencies,
+    missing_docs
+)]
+#![allow(clippy::default_constructed_unit_structs)]
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+pub mod rx;
+pub mod tx;
+
+#[cfg(test)]
+mod tests {
+    use std::{net::SocketAddr, sync::Arc, time::Duration};
+
+    use async_trait::async_trait;
+    use generated_types::influxdata::iox::gossip::v1::{PartitionReadCost, QueryStats};
+    use gossip::Builder;
+    use test_helpers::{maybe_start_logging, timeout::FutureTimeout};
+    use tokio::{net::UdpSocket, sync::mpsc};
+
+    use crate::{
+        rx::{QueryStatsEventHandler, QueryStatsRx},
+        tx::QueryStatsTx,
+    };
+
+    /// Bind a UDP socket on a random port and return it alongside the socket
+    /// address.
+    async fn random_udp() -> (UdpSocket, SocketAddr) {
+        // Bind a UDP socket to a random port
+        let socket = UdpSocket::bind("127.0.0.1:0")
+            .await
+            .expect("failed to bind UDP socket");
+        let addr = socket.local_addr().expect("failed to read local addr");
+
+        (socket, addr)
+    }
+
+    #[derive(Debug)]
+    struct MockEventHandler(mpsc::Sender<QueryStats>);
+
+    #[async_trait]
+    impl QueryStatsEventHandler for Arc<MockEventHandler> {
+        async fn handle(&self, event: QueryStats) {
+            self.0.send(event).await.unwrap();
+        }
+    }
+
+    /// Ensure a QueryStats that exceeds the maximum message size is split and
+    /// round-tripped through the gossip layer.
+    #[tokio::test]
+    async fn test_round_trip() {
+        maybe_start_logging();
+
+        let metrics = Arc::new(metric::Registry::default());
+
+        let (a_socket, a_addr) = random_udp().await;
+        let (handler, _a_rx) = mpsc::channel(10);
+        let a_dispatcher = QueryStatsRx::new(Arc::new(MockEventHandler(handler)), 100);
+
+        let (b_socket, b_addr) = random_udp().await;
+        let (handler, mut b_rx) = mpsc::channel(10);
+        let b_dispatcher = QueryStatsRx::new(Arc::new(MockEventHandler(handler)), 100);
+
+        // Initialise both gossip reactors
+        let addrs = vec![a_addr.to_string(), b_addr.to_string()];
+        let a = Builder::new(addrs.clone(), a_dispatcher, Arc::clone(&metrics)).build(a_socket);
+        let b = Builder::new(addrs, b_dispatcher, Arc::clone(&metrics)).build(b_socket);
+
+        // Wait for peer discovery to occur
+        async {
+            loop {
+                if a.get_peers().await.len() == 1 && b.get_peers().await.len() == 1 {
+                    break;
+                }
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        let a = QueryStatsTx::new(Arc::new(a));
+
+        let want = (0..1_000)
+            .map(|i| PartitionReadCost {
+                partition_id: i64::MAX - i,
+                queries: u64::MAX,
+                files_scanned: u64::MAX,
+                dedup_rows: u64::MAX,
+            })
+            .collect::<Vec<_>>();
+
+        // Broadcast the event from A
+        a.broadcast(QueryStats {
+            partitions: want.clone(),
+        });
+
+        // Receive all the partitions from B, split over multiple messages
+        let mut got = vec![];
+        async {
+            while got.len() < want.len() {
+                let event = b_rx.recv().await.unwrap();
+                assert!(event.partitions.len() < want.len());
+                got.extend(event.partitions);
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        // Ensuring the content is identical
+        got.sort_by_key(|p| std::cmp::Reverse(p.partition_id));
+        assert_eq!(got, want);
+    }
+}
@@ -0,0 +1,104 @@
+//! A deserialiser and dispatcher of [gossip] messages for the
+//! [`Topic::QueryStats`] topic.
+
+use std::{fmt::Debug, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use generated_types::influxdata::iox::gossip::{v1::QueryStats, Topic};
+use generated_types::prost::Message;
+use gossip::Identity;
+use observability_deps::tracing::{info, warn};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+/// A [`QueryStats`] notification handler received via gossip.
+#[async_trait]
+pub trait QueryStatsEventHandler: Send + Sync + Debug {
+    /// Process `event`.
+    async fn handle(&self, event: QueryStats);
+}
+
+#[async_trait]
+impl<T> QueryStatsEventHandler for Arc<T>
+where
+    T: QueryStatsEventHandler,
+{
+    async fn handle(&self, event: QueryStats) {
+        T::handle(self, event).await
+    }
+}
+
+/// An async gossip message dispatcher.
+///
+/// This type is responsible for deserialising incoming gossip
+/// [`Topic::QueryStats`] payloads and passing them off to the provided
+/// [`QueryStatsEventHandler`] implementation.
+///
+/// This type also provides a buffer between incoming events, and processing,
+/// preventing processing time from blocking the gossip reactor. Once the buffer
+/// is full, incoming events are dropped until space is made through processing
+/// of outstanding events. Dropping the [`QueryStatsRx`] stops the background
+/// event loop.
+#[derive(Debug)]
+pub struct QueryStatsRx {
+    tx: mpsc::Sender<Bytes>,
+    task: JoinHandle<()>,
+}
+
+impl QueryStatsRx {
+    /// Initialise a new dispatcher, buffering up to `buffer` number of events.
+    ///
+    /// The provided `handler` does not block the gossip reactor during
+    /// execution.
+    pub fn new<T>(handler: T, buffer: usize) -> Self
+    where
+        T: QueryStatsEventHandler + 'static,
+    {
+        // Initialise a buffered channel to decouple the two halves.
+        let (tx, rx) = mpsc::channel(buffer);
+
+        // And run a receiver loop to pull the events from the channel.
+        let task = tokio::spawn(dispatch_loop(rx, handler));
+
+        Self { tx, task }
+    }
+}
+
+#[async_trait]
+impl gossip::Dispatcher<Topic> for QueryStatsRx {
+    async fn dispatch(&self, topic: Topic, payload: Bytes, _sender: Identity) {
+        if topic != Topic::QueryStats {
+            return;
+        }
+        if let Err(e) = self.tx.try_send(payload) {
+            warn!(error=%e, "failed to buffer gossip event");
+        }
+    }
+}
+
+impl Drop for QueryStatsRx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn dispatch_loop<T>(mut rx: mpsc::Receiver<Bytes>, handler: T)
+where
+    T: QueryStatsEventHandler,
+{
+    while let Some(payload) = rx.recv().await {
+        // Deserialise the payload into the appropriate proto type.
+        let event = match QueryStats::decode(payload) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, "failed to deserialise gossip message");
+                continue;
+            }
+        };
+
+        // Pass this message off to the handler to process.
+        handler.handle(event).await;
+    }
+
+    info!("stopping gossip dispatcher");
+}
@@ -0,0 +1,115 @@
+//! A serialiser and broadcaster of [`gossip`] messages for the
+//! [`Topic::QueryStats`] topic.
+
+use std::sync::Arc;
+
+use generated_types::{
+    influxdata::iox::gossip::{v1::QueryStats, Topic},
+    prost::Message,
+};
+use gossip::MAX_USER_PAYLOAD_BYTES;
+use observability_deps::tracing::{debug, error, warn};
+use tokio::{
+    sync::mpsc::{self, error::TrySendError},
+    task::JoinHandle,
+};
+
+/// The maximum encoded size of a single
+/// [`PartitionReadCost`](generated_types::influxdata::iox::gossip::v1::PartitionReadCost)
+/// within a [`QueryStats`] message: a tag and length prefix, and four
+/// maximum-length varint fields, each with a tag.
+const MAX_ENCODED_PARTITION_BYTES: usize = 2 + 4 * (1 + 10);
+
+/// The maximum number of partitions sent in a single gossip message.
+const MAX_PARTITIONS_PER_MESSAGE: usize = MAX_USER_PAYLOAD_BYTES / MAX_ENCODED_PARTITION_BYTES;
+
+/// A gossip broadcast primitive specialised for query statistics.
+///
+/// Serialisation and processing of the [`QueryStats`] given to the
+/// [`QueryStatsTx::broadcast()`] method happens in a background actor task,
+/// decoupling the caller from the latency of processing each frame. Dropping
+/// the [`QueryStatsTx`] stops this background actor task.
+#[derive(Debug)]
+pub struct QueryStatsTx {
+    tx: mpsc::Sender<QueryStats>,
+    task: JoinHandle<()>,
+}
+
+impl Drop for QueryStatsTx {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl QueryStatsTx {
+    /// Construct a new [`QueryStatsTx`] that publishes gossip messages over
+    /// `gossip`.
+    pub fn new(gossip: Arc<gossip::GossipHandle<Topic>>) -> Self {
+        let (tx, rx) = mpsc::channel(10);
+
+        let task = tokio::spawn(actor_loop(rx, gossip));
+
+        Self { tx, task }
+    }
+
+    /// Asynchronously broadcast `event` to all interested peers.
+    ///
+    /// This method enqueues `event` into the serialisation queue, and processed
+    /// & transmitted asynchronously. An `event` too large to fit in a single
+    /// gossip message is split into multiple messages.
+    pub fn broadcast(&self, event: QueryStats) {
+        debug!(n_partitions = event.partitions.len(), "sending query stats");
+        match self.tx.try_send(event) {
+            Ok(_) => {}
+            Err(TrySendError::Closed(_)) => {
+                panic!("query stats serialisation actor not running")
+            }
+            Err(TrySendError::Full(_)) => {
+                warn!("query stats serialisation queue full, dropping message")
+            }
+        }
+    }
+}
+
+async fn actor_loop(mut rx: mpsc::Receiver<QueryStats>, gossip: Arc<gossip::GossipHandle<Topic>>) {
+    while let Some(event) = rx.recv().await {
+        for partitions in event.partitions.chunks(MAX_PARTITIONS_PER_MESSAGE) {
+            let frame = QueryStats {
+                partitions: partitions.to_vec(),
+            };
+
+            if let Err(e) = gossip
+                .broadcast(frame.encode_to_vec(), Topic::QueryStats)
+                .await
+            {
+                error!(error=%e, "failed to broadcast payload");
+            }
+        }
+    }
+
+    debug!("stopping query stats gossip serialisation actor");
+}
+
+#[cfg(test)]
+mod tests {
+    use generated_types::influxdata::iox::gossip::v1::PartitionReadCost;
+
+    use super::*;
+
+    #[test]
+    fn test_max_message_size() {
+        let frame = QueryStats {
+            partitions: vec![
+                PartitionReadCost {
+                    partition_id: i64::MIN,
+                    queries: u64::MAX,
+                    files_scanned: u64::MAX,
+                    dedup_rows: u64::MAX,
+                };
+                MAX_PARTITIONS_PER_MESSAGE
+            ],
+        };
+
+        assert!(frame.encoded_len() <= MAX_USER_PAYLOAD_BYTES);
+    }
+}
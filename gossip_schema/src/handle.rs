@@ -71,7 +71,7 @@ impl SchemaTx {
 async fn actor_loop(mut rx: mpsc::Receiver<Event>, gossip: Arc<gossip::GossipHandle<Topic>>) {
     while let Some(event) = rx.recv().await {
         let frames = match event {
-            v @ Event::NamespaceCreated(_) => vec![v],
+            v @ (Event::NamespaceCreated(_) | Event::NamespaceUpdated(_)) => vec![v],
             Event::TableCreated(v) => serialise_table_create_frames(v),
             Event::TableUpdated(v) => {
                 // Split the frame up into N frames, sized as big as the gossip
@@ -347,6 +347,8 @@ mod tests {
                         "b".repeat(partition_template_size).to_string()
                     ))
                 }]}),
+                partition_template_version: 0,
+                retention_period_ns: None,
             };
 
             let frames = serialise_table_create_frames(msg.clone());
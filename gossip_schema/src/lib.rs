@@ -212,6 +212,10 @@ mod tests {
             max_tables: 2,
             max_columns_per_table: 1,
             retention_period_ns: Some(1234),
+            write_rules: None,
+            schema_mode: 0,
+            properties_version: 0,
+            field_coercion: None,
         });
 
         // Broadcast the event from A
@@ -243,6 +247,10 @@ mod tests {
             max_tables: 2,
             max_columns_per_table: 1,
             retention_period_ns: Some(1234),
+            write_rules: None,
+            schema_mode: 0,
+            properties_version: 0,
+            field_coercion: None,
         });
 
         // Broadcast the event from A
@@ -282,6 +290,8 @@ mod tests {
             ])
             .as_proto()
             .cloned(),
+            partition_template_version: 0,
+            retention_period_ns: Some(42),
         });
 
         // Broadcast the event from A
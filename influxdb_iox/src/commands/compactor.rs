@@ -0,0 +1,111 @@
+//! This module implements the `compactor` CLI command
+
+use std::{num::NonZeroUsize, sync::Arc};
+
+use clap_blocks::{catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig};
+use compactor::explain::explain_partition;
+use data_types::PartitionId;
+use iox_query::exec::{Executor, ExecutorConfig};
+use iox_time::{SystemProvider, TimeProvider};
+use ioxd_compactor::create_compactor_config;
+use parquet_file::storage::{ParquetStorage, StorageId};
+use thiserror::Error;
+
+use crate::process_info::setup_metric_registry;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Explain error: {0}")]
+    Explain(Box<dyn std::error::Error + Send + Sync>),
+
+    #[error("JSON serialization error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Various commands for compactor debugging
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Print, as JSON, what the compactor would do with a partition in its next
+/// round: the compaction ranges, the file classification and split/compact
+/// decision of each branch, and the resulting plans.
+///
+/// Only the catalog is read; no plan is executed and nothing is written.
+#[derive(Debug, clap::Parser)]
+struct Explain {
+    /// The ID of the partition to explain
+    #[clap(long)]
+    partition_id: i64,
+
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    compactor_config: CompactorConfig,
+}
+
+/// All possible subcommands for compactor
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Explain the compaction of a partition without compacting it
+    Explain(Explain),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Explain(command) => {
+            let metric_registry = setup_metric_registry();
+            let catalog = command
+                .catalog_dsn
+                .get_catalog("cli", Arc::clone(&metric_registry))
+                .await?;
+
+            // The explanation never reads or writes parquet files.
+            let parquet_store_real = ParquetStorage::new(
+                Arc::new(object_store::memory::InMemory::new()),
+                StorageId::from("iox"),
+            );
+            let parquet_store_scratchpad = ParquetStorage::new(
+                Arc::new(object_store::memory::InMemory::new()),
+                StorageId::from("iox_scratchpad"),
+            );
+            let exec = Arc::new(Executor::new_with_config(ExecutorConfig {
+                num_threads: NonZeroUsize::new(1).unwrap(),
+                target_query_partitions: NonZeroUsize::new(1).unwrap(),
+                object_stores: [&parquet_store_real, &parquet_store_scratchpad]
+                    .into_iter()
+                    .map(|store| (store.id(), Arc::clone(store.object_store())))
+                    .collect(),
+                metric_registry: Arc::clone(&metric_registry),
+                mem_pool_size: command.compactor_config.exec_mem_pool_bytes.bytes(),
+            }));
+            let time_provider = Arc::new(SystemProvider::new()) as Arc<dyn TimeProvider>;
+
+            let compactor_config = create_compactor_config(
+                metric_registry,
+                None,
+                catalog,
+                parquet_store_real,
+                parquet_store_scratchpad,
+                exec,
+                time_provider,
+                command.compactor_config,
+            );
+
+            let explanation =
+                explain_partition(&compactor_config, PartitionId::new(command.partition_id))
+                    .await
+                    .map_err(Error::Explain)?;
+            println!("{}", serde_json::to_string_pretty(&explanation)?);
+        }
+    }
+
+    Ok(())
+}
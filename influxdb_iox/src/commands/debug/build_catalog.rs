@@ -81,7 +81,7 @@ pub async fn command(config: Config) -> Result<(), Error> {
     Ok(importer.import().await?)
 }
 
-async fn get_catalog(data_dir: &Path) -> Result<Arc<dyn Catalog>> {
+pub(super) async fn get_catalog(data_dir: &Path) -> Result<Arc<dyn Catalog>> {
     std::fs::create_dir_all(data_dir).context(FileSnafu {
         operation: "create data directory",
         path: data_dir,
@@ -98,7 +98,7 @@ async fn get_catalog(data_dir: &Path) -> Result<Arc<dyn Catalog>> {
     Ok(Arc::new(catalog))
 }
 
-fn get_object_store(data_dir: &Path) -> Result<Arc<dyn ObjectStore>> {
+pub(super) fn get_object_store(data_dir: &Path) -> Result<Arc<dyn ObjectStore>> {
     let os_dir = data_dir.join("object_store");
     std::fs::create_dir_all(&os_dir).context(FileSnafu {
         operation: "create object_store directory",
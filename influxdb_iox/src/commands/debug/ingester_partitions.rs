@@ -0,0 +1,153 @@
+//! This module implements the `ingester-partitions` CLI command
+
+use comfy_table::{Cell, Table};
+use influxdb_iox_client::{
+    connection::Connection,
+    ingester::{
+        self,
+        generated_types::{BufferedPartition, ListPartitionsRequest, PartitionOrder},
+    },
+};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// The order in which buffered partitions are listed.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum SortBy {
+    /// Order by namespace, table and partition key
+    Id,
+
+    /// Order by estimated buffered size, largest first
+    Size,
+
+    /// Order by the age of the oldest buffered write, oldest first
+    Age,
+}
+
+impl From<SortBy> for PartitionOrder {
+    fn from(v: SortBy) -> Self {
+        match v {
+            SortBy::Id => Self::Unspecified,
+            SortBy::Size => Self::SizeDesc,
+            SortBy::Age => Self::AgeDesc,
+        }
+    }
+}
+
+/// List the partitions buffered in memory by an ingester.
+///
+/// The connection must be made to the gRPC address of an ingester.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// Only list partitions for the namespace with this catalog ID
+    #[clap(long, action)]
+    namespace_id: Option<i64>,
+
+    /// Only list partitions for the table with this catalog ID
+    #[clap(long, action)]
+    table_id: Option<i64>,
+
+    /// The order in which partitions are listed
+    #[clap(long, value_enum, default_value = "id", action)]
+    sort_by: SortBy,
+
+    /// The number of partitions to skip
+    #[clap(long, default_value = "0", action)]
+    offset: u64,
+
+    /// The maximum number of partitions to list (0 lists all partitions)
+    #[clap(long, default_value = "100", action)]
+    limit: u64,
+
+    /// Output the response as JSON instead of a table
+    #[clap(long, action)]
+    json: bool,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = ingester::Client::new(connection);
+
+    let response = client
+        .list_buffered_partitions(ListPartitionsRequest {
+            namespace_id: config.namespace_id,
+            table_id: config.table_id,
+            order: PartitionOrder::from(config.sort_by).into(),
+            offset: config.offset,
+            limit: config.limit,
+        })
+        .await?;
+
+    if config.json {
+        println!("{}", serde_json::to_string_pretty(&response)?);
+        return Ok(());
+    }
+
+    println!("{}", create_table(&response.partitions));
+    println!(
+        "Showing {} of {} buffered partitions (hot partition threshold: {} bytes)",
+        response.partitions.len(),
+        response.total,
+        response.hot_partition_threshold_bytes
+    );
+
+    Ok(())
+}
+
+/// Turn buffered partition records into a table
+fn create_table(partitions: &[BufferedPartition]) -> Table {
+    let mut table = Table::new();
+    table.load_preset("||--+-++|    ++++++");
+
+    let headers: Vec<_> = [
+        "namespace",
+        "table",
+        "partition_key",
+        "partition_id",
+        "rows",
+        "estimated_bytes",
+        "min_time",
+        "max_time",
+        "age_ms",
+        "persisting",
+        "queue_position",
+        "persisted",
+        "hot_persists",
+        "series",
+    ]
+    .into_iter()
+    .map(Cell::new)
+    .collect();
+    table.set_header(headers);
+
+    let or_id = |name: &Option<String>, id: i64| name.clone().unwrap_or_else(|| id.to_string());
+    let or_empty = |v: Option<String>| v.unwrap_or_default();
+
+    for p in partitions {
+        table.add_row(vec![
+            Cell::new(or_id(&p.namespace_name, p.namespace_id)),
+            Cell::new(or_id(&p.table_name, p.table_id)),
+            Cell::new(&p.partition_key),
+            Cell::new(&p.partition_id),
+            Cell::new(p.row_count.to_string()),
+            Cell::new(p.estimated_size_bytes.to_string()),
+            Cell::new(or_empty(p.min_time.map(|v| v.to_string()))),
+            Cell::new(or_empty(p.max_time.map(|v| v.to_string()))),
+            Cell::new(or_empty(p.buffer_age_ms.map(|v| v.to_string()))),
+            Cell::new(p.persisting_batches.to_string()),
+            Cell::new(or_empty(p.persist_queue_position.map(|v| v.to_string()))),
+            Cell::new(p.completed_persist_count.to_string()),
+            Cell::new(p.hot_persist_count.to_string()),
+            Cell::new(or_empty(p.series_count.map(|v| v.to_string()))),
+        ]);
+    }
+
+    table
+}
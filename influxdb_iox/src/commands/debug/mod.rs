@@ -3,8 +3,10 @@ use influxdb_iox_client::connection::Connection;
 use snafu::prelude::*;
 
 mod build_catalog;
+mod ingester_partitions;
 mod parquet_to_lp;
 mod print_cpu;
+mod rewrite_sort_key;
 mod schema;
 mod skipped_compactions;
 mod wal;
@@ -19,10 +21,18 @@ pub enum Error {
     #[snafu(display("Error in build_catalog subcommand: {}", source))]
     BuildCatalog { source: build_catalog::Error },
 
+    #[snafu(context(false))]
+    #[snafu(display("Error in ingester-partitions subcommand: {}", source))]
+    IngesterPartitions { source: ingester_partitions::Error },
+
     #[snafu(context(false))]
     #[snafu(display("Error in parquet_to_lp subcommand: {}", source))]
     ParquetToLp { source: parquet_to_lp::Error },
 
+    #[snafu(context(false))]
+    #[snafu(display("Error in rewrite-sort-key subcommand: {}", source))]
+    RewriteSortKey { source: rewrite_sort_key::Error },
+
     #[snafu(context(false))]
     #[snafu(display("Error in skipped-compactions subcommand: {}", source))]
     SkippedCompactions { source: skipped_compactions::Error },
@@ -72,8 +82,14 @@ enum Command {
     /// Interrogate skipped compactions
     SkippedCompactions(skipped_compactions::Config),
 
+    /// Rewrite the partitions of a table with a cardinality-ordered sort key
+    RewriteSortKey(rewrite_sort_key::Config),
+
     /// Subcommands for debugging the WAL
     Wal(wal::Config),
+
+    /// List the partitions buffered in memory by an ingester
+    IngesterPartitions(ingester_partitions::Config),
 }
 
 pub async fn command<C, CFut>(connection: C, config: Config) -> Result<()>
@@ -93,7 +109,15 @@ where
             let connection = connection().await;
             skipped_compactions::command(connection, config).await?
         }
+        Command::RewriteSortKey(config) => {
+            let connection = connection().await;
+            rewrite_sort_key::command(connection, config).await?
+        }
         Command::Wal(config) => wal::command(connection, config).await?,
+        Command::IngesterPartitions(config) => {
+            let connection = connection().await;
+            ingester_partitions::command(connection, config).await?
+        }
     }
 
     Ok(())
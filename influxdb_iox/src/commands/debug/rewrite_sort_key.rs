@@ -0,0 +1,40 @@
+//! This module implements the `rewrite-sort-key` CLI command
+
+use influxdb_iox_client::{compactor, connection::Connection};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Client error: {0}")]
+    Client(#[from] influxdb_iox_client::error::Error),
+}
+
+/// Rewrite the partitions of a table with a sort key ordered by tag
+/// cardinality.
+///
+/// The connection must be made to the gRPC address of a compactor. The
+/// rewrite happens asynchronously in the compactor.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace of the table
+    #[clap(action)]
+    namespace: String,
+
+    /// The table whose partitions should be rewritten
+    #[clap(action)]
+    table: String,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<(), Error> {
+    let mut client = compactor::Client::new(connection);
+    let table_id = client
+        .rewrite_table_sort_key(&config.namespace, &config.table)
+        .await?;
+
+    println!(
+        "Requested sort key rewrite of table {} (table ID {})",
+        config.table, table_id
+    );
+
+    Ok(())
+}
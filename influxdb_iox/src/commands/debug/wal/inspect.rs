@@ -1,10 +1,11 @@
 //! A module providing a CLI command to inspect the contents of a WAL file.
 use std::{io::Write, ops::RangeInclusive, path::PathBuf};
 
+use clap_blocks::wal_encryption::WalEncryptionConfig;
 use itertools::Itertools;
 use wal::SequencedWalOp;
 
-use super::Error;
+use super::{keyring, Error};
 
 #[derive(Debug, clap::Parser)]
 pub struct Config {
@@ -17,6 +18,10 @@ pub struct Config {
     /// within the range (inclusive) will be displayed
     #[clap(long, short, value_parser = parse_sequence_number_range)]
     sequence_number_range: Option<RangeInclusive<u64>>,
+
+    /// The keys used to decrypt an encrypted WAL file.
+    #[clap(flatten)]
+    encryption_config: WalEncryptionConfig,
 }
 
 fn parse_sequence_number_range(s: &str) -> Result<RangeInclusive<u64>, String> {
@@ -41,7 +46,8 @@ fn parse_sequence_number_range(s: &str) -> Result<RangeInclusive<u64>, String> {
 }
 
 pub fn command(config: Config) -> Result<(), Error> {
-    let reader = wal::ClosedSegmentFileReader::from_path(&config.input)
+    let keyring = keyring(&config.encryption_config)?;
+    let reader = wal::ClosedSegmentFileReader::from_path_with_keyring(&config.input, &keyring)
         .map_err(Error::UnableToReadWalFile)?;
 
     inspect(config.sequence_number_range, &mut std::io::stdout(), reader)
@@ -1,12 +1,15 @@
 //! This module implements CLI commands for debugging the ingester WAL.
 
+use clap_blocks::wal_encryption::WalEncryptionConfig;
 use futures::Future;
 
 use influxdb_iox_client::connection::Connection;
 use thiserror::Error;
+use wal::Keyring;
 
 mod inspect;
 mod regenerate_lp;
+mod replay_archive;
 
 /// A command level error type to decorate WAL errors with some extra
 /// "human" context for the user
@@ -15,6 +18,9 @@ pub enum Error {
     #[error("could not read WAL file: {0}")]
     UnableToReadWalFile(#[from] wal::Error),
 
+    #[error("invalid WAL encryption keys: {0}")]
+    InvalidEncryptionKeys(#[from] wal::KeyringError),
+
     #[error("failed to decode write entries from the WAL file: {0}")]
     FailedToDecodeWriteOpEntry(#[from] wal::DecodeError),
 
@@ -31,6 +37,30 @@ pub enum Error {
 
     #[error("errors occurred during inspection of the WAL file: {sources:?}")]
     IncompleteInspection { sources: Vec<wal::Error> },
+
+    #[error("invalid object store configuration: {0}")]
+    ObjectStoreConfig(clap_blocks::object_store::ParseError),
+
+    #[error("invalid WAL archive prefix: {0}")]
+    InvalidArchivePrefix(object_store::path::Error),
+
+    #[error("object store error: {0}")]
+    ObjectStore(#[from] object_store::Error),
+
+    #[error("failed to create scratch catalog: {0}")]
+    BuildCatalog(#[from] super::build_catalog::Error),
+
+    #[error("scratch catalog error: {0}")]
+    ScratchCatalog(#[from] iox_catalog::interface::Error),
+
+    #[error("failed to create scratch catalog schema: {0}")]
+    ScratchSchema(iox_catalog::TableScopedError),
+
+    #[error("failed to decode write batch from the WAL file: {0}")]
+    FailedToDecodeWriteBatch(mutable_batch_pb::decode::Error),
+
+    #[error("failed to persist replayed writes: {0}")]
+    Ingester(#[from] ingester::InitError),
 }
 
 /// A set of non-fatal errors which can occur during the regeneration of write
@@ -58,6 +88,11 @@ enum Command {
     /// looking up measurement names from IOx, the target host must implement
     /// the namespace and schema APIs
     RegenerateLp(regenerate_lp::Config),
+    /// Rebuild parquet files in a scratch catalog from the writes in WAL
+    /// segment files archived to object storage by an ingester, within a time
+    /// window. The target host must implement the namespace and schema APIs
+    /// to look up the namespace and table names of the writes
+    ReplayArchive(replay_archive::Config),
 }
 
 /// Executes a WAL debugging subcommand as directed by the config
@@ -69,5 +104,16 @@ where
     match config.command {
         Command::Inspect(config) => inspect::command(config),
         Command::RegenerateLp(config) => regenerate_lp::command(connection, config).await,
+        Command::ReplayArchive(config) => replay_archive::command(connection, config).await,
     }
 }
+
+/// Load the WAL decryption keys specified by `config`, if any.
+fn keyring(config: &WalEncryptionConfig) -> Result<Keyring, Error> {
+    let keyring = match (&config.wal_encryption_keys, &config.wal_encryption_key_file) {
+        (Some(keys), _) => Keyring::parse(keys)?,
+        (None, Some(path)) => Keyring::from_file(path)?,
+        (None, None) => Keyring::default(),
+    };
+    Ok(keyring)
+}
@@ -4,6 +4,7 @@ use std::future::Future;
 use std::path::PathBuf;
 use std::sync::Arc;
 
+use clap_blocks::wal_encryption::WalEncryptionConfig;
 use data_types::{NamespaceId, TableId};
 use hashbrown::HashMap;
 use influxdb_iox_client::connection::Connection;
@@ -12,7 +13,7 @@ use observability_deps::tracing::{debug, error, info};
 use wal::{ClosedSegmentFileReader, WriteOpEntry, WriteOpEntryDecoder};
 use wal_inspect::{LineProtoWriter, NamespaceDemultiplexer, TableBatchWriter, WriteError};
 
-use super::{Error, RegenerateError};
+use super::{keyring, Error, RegenerateError};
 
 /// The set of errors which may occur when trying to look up a table name
 /// index for a namespace.
@@ -27,13 +28,13 @@ pub enum TableIndexLookupError {
 
 // This type provides a convenience wrapper around the namespace and schema APIs
 // to enable the fetching of table name indexes from namespace and table IDs.
-struct TableIndexFetcher {
+pub(super) struct TableIndexFetcher {
     namespace_index: HashMap<NamespaceId, String>,
     schema_client: SchemaClient,
 }
 
 impl TableIndexFetcher {
-    async fn new(connection: Connection) -> Result<Self, TableIndexLookupError> {
+    pub(super) async fn new(connection: Connection) -> Result<Self, TableIndexLookupError> {
         let mut namespace_client = influxdb_iox_client::namespace::Client::new(connection.clone());
         Ok(Self {
             namespace_index: namespace_client
@@ -47,7 +48,13 @@ impl TableIndexFetcher {
         })
     }
 
-    async fn get_table_name_index(
+    /// Return the name of the namespace with the given `namespace_id`, if
+    /// known.
+    pub(super) fn namespace_name(&self, namespace_id: NamespaceId) -> Option<&str> {
+        self.namespace_index.get(&namespace_id).map(String::as_str)
+    }
+
+    pub(super) async fn get_table_name_index(
         &self,
         namespace_id: NamespaceId,
     ) -> Result<HashMap<TableId, String>, TableIndexLookupError> {
@@ -110,6 +117,10 @@ pub struct Config {
     /// ID for each measurement, rather than the original name
     #[clap(long, short)]
     skip_measurement_lookup: bool,
+
+    /// The keys used to decrypt an encrypted WAL file.
+    #[clap(flatten)]
+    encryption_config: WalEncryptionConfig,
 }
 
 /// Executes the `regenerate-lp` command with the provided configuration, reading
@@ -119,8 +130,10 @@ where
     C: Send + FnOnce() -> CFut,
     CFut: Send + Future<Output = Connection>,
 {
+    let keyring = keyring(&config.encryption_config)?;
     let decoder = WriteOpEntryDecoder::from(
-        ClosedSegmentFileReader::from_path(&config.input).map_err(Error::UnableToReadWalFile)?,
+        ClosedSegmentFileReader::from_path_with_keyring(&config.input, &keyring)
+            .map_err(Error::UnableToReadWalFile)?,
     );
 
     let table_name_indexer = if config.skip_measurement_lookup {
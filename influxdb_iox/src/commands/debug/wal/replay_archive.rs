@@ -0,0 +1,377 @@
+//! A module providing a CLI command to rebuild parquet files from archived WAL
+//! segment files.
+use std::{
+    future::Future,
+    num::NonZeroUsize,
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+
+use clap_blocks::{
+    object_store::{make_object_store, ObjectStoreConfig},
+    wal_encryption::WalEncryptionConfig,
+};
+use data_types::{NamespaceId, NamespaceName, NamespaceSchema, TableId};
+use futures::TryStreamExt;
+use hashbrown::HashMap;
+use influxdb_iox_client::connection::Connection;
+use ingester::{
+    DurabilityMode, GossipConfig, HandoffConfig, Keyring, SpillConfig, WalArchiveConfig,
+};
+use iox_catalog::interface::{get_schema_by_name, Catalog, SoftDeletedRows};
+use iox_query::exec::Executor;
+use metric::Registry;
+use object_store::path::Path;
+use observability_deps::tracing::{debug, info, warn};
+use parquet_file::storage::{ParquetStorage, StorageId};
+use tokio::io::AsyncWriteExt;
+use tokio_util::sync::CancellationToken;
+use wal::{ArchivedSegmentName, ClosedSegmentFileReader, SequencedWalOp, Wal, WalOp};
+
+use super::{
+    keyring,
+    regenerate_lp::{TableIndexFetcher, TableIndexLookupError},
+    Error,
+};
+use crate::commands::debug::build_catalog::{get_catalog, get_object_store};
+
+/// A container for the possible arguments & flags of a `replay-archive`
+/// command.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The object store path prefix the WAL segment files were archived under
+    /// (the `--wal-archive-prefix` of the ingester).
+    #[clap(long)]
+    prefix: String,
+
+    /// Only replay segments closed at or after this time (RFC3339).
+    ///
+    /// A segment contains the writes received by the ingester in the WAL
+    /// rotation period before it was closed.
+    #[clap(long, value_parser = humantime::parse_rfc3339_weak)]
+    start: Option<SystemTime>,
+
+    /// Only replay segments closed at or before this time (RFC3339).
+    #[clap(long, value_parser = humantime::parse_rfc3339_weak)]
+    end: Option<SystemTime>,
+
+    /// Target data directory to create the scratch sqlite catalog and file
+    /// object_store in.
+    ///
+    /// After a successful replay, you can examine the rebuilt data locally
+    /// using `influxdb_iox --data-dir <dir>`.
+    #[clap(value_parser)]
+    output_dir: PathBuf,
+
+    /// The object store the WAL segment files were archived to.
+    #[clap(flatten)]
+    object_store_config: ObjectStoreConfig,
+
+    /// The keys used to decrypt encrypted WAL segment files.
+    #[clap(flatten)]
+    encryption_config: WalEncryptionConfig,
+}
+
+/// Executes the `replay-archive` command with the provided configuration,
+/// replaying the writes in the archived WAL segment files closed within the
+/// configured time window and persisting them as parquet files in a scratch
+/// catalog.
+///
+/// The namespace and table names for the writes are looked up from the IOx
+/// host the writes were originally made to, and the namespaces, tables and
+/// columns created in the scratch catalog. The writes are then replayed by an
+/// in-process ingester, which persists the data to the scratch object store.
+pub async fn command<C, CFut>(connection: C, config: Config) -> Result<(), Error>
+where
+    C: Send + FnOnce() -> CFut,
+    CFut: Send + Future<Output = Connection>,
+{
+    let keyring = keyring(&config.encryption_config)?;
+    let archive_store =
+        make_object_store(&config.object_store_config).map_err(Error::ObjectStoreConfig)?;
+    let prefix = Path::parse(&config.prefix).map_err(Error::InvalidArchivePrefix)?;
+
+    // Find the archived segments closed within the time window, in the order
+    // they were closed.
+    //
+    // The close time and segment ID are recorded by the archiver in the name
+    // of each file, and are unaffected by the object metadata (which changes
+    // should the archive be copied or restored).
+    let start = config.start.unwrap_or(SystemTime::UNIX_EPOCH);
+    let end = config.end.unwrap_or_else(SystemTime::now);
+    let mut segments = archive_store
+        .list(Some(&prefix))
+        .await?
+        .try_filter_map(|meta| async move {
+            Ok(meta
+                .location
+                .filename()
+                .and_then(ArchivedSegmentName::parse)
+                .filter(|name| (start..=end).contains(&name.closed_at()))
+                .map(|name| (name, meta.location)))
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+    segments.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+
+    if segments.is_empty() {
+        warn!(%prefix, "no archived wal segments found within the time window");
+        return Ok(());
+    }
+    info!(n_segments = segments.len(), %prefix, "replaying archived wal segments");
+
+    let catalog = get_catalog(&config.output_dir).await?;
+    catalog.setup().await?;
+    let object_store = get_object_store(&config.output_dir)?;
+
+    // Download the archived segments, rewriting the catalog IDs of the writes
+    // they contain to those of the scratch catalog, and write them to a new
+    // WAL.
+    let mut rewriter = IdRewriter::new(
+        Arc::clone(&catalog),
+        TableIndexFetcher::new(connection().await)
+            .await
+            .map_err(Error::UnableToInitTableNameFetcher)?,
+    );
+
+    let wal_dir = config.output_dir.join("wal");
+    let wal = Wal::new(&wal_dir).await?;
+    let download_dir = tempfile::tempdir()?;
+    let mut replayed_ops = 0_usize;
+    let mut skipped_ops = 0_usize;
+
+    for (name, location) in segments {
+        info!(%location, closed_at=?name.closed_at(), "downloading archived wal segment");
+
+        // Stream the segment to a local file, rather than buffering it in
+        // memory.
+        let path = download_dir.path().join(name.to_string());
+        let mut file = tokio::fs::File::create(&path).await?;
+        let mut stream = archive_store.get(&location).await?.into_stream();
+        while let Some(chunk) = stream.try_next().await? {
+            file.write_all(&chunk).await?;
+        }
+        file.flush().await?;
+        drop(file);
+
+        let reader = ClosedSegmentFileReader::from_path_with_keyring(&path, &keyring)?;
+        let mut last_write = None;
+        for batch in reader {
+            for op in batch? {
+                let Some(op) = rewriter.rewrite(op).await? else {
+                    skipped_ops += 1;
+                    continue;
+                };
+                last_write = Some(wal.write_op(op));
+                replayed_ops += 1;
+            }
+        }
+
+        // Wait for the writes to be committed to the WAL, so that they are
+        // rotated into a closed segment below.
+        if let Some(mut done) = last_write {
+            done.changed().await.expect("wal flusher stopped");
+        }
+
+        tokio::fs::remove_file(&path).await?;
+    }
+
+    wal.rotate()?;
+    drop(wal);
+
+    info!(replayed_ops, skipped_ops, "persisting replayed writes");
+
+    // Replay the new WAL with an in-process ingester, which persists all the
+    // replayed data to the scratch object store before it returns.
+    let metrics = Arc::new(Registry::default());
+    let exec = Arc::new(Executor::new(
+        NonZeroUsize::new(num_cpus::get()).unwrap_or(NonZeroUsize::MIN),
+        usize::MAX,
+        Arc::clone(&metrics),
+    ));
+    let ingester = ingester::new(
+        catalog,
+        metrics,
+        Duration::from_secs(1),
+        wal_dir,
+        Duration::from_secs(60 * 60),
+        DurabilityMode::default(),
+        Keyring::default(),
+        exec,
+        5,
+        250,
+        20_000_000,
+        ParquetStorage::new(object_store, StorageId::from("iox")),
+        GossipConfig::Disabled,
+        HandoffConfig::Disabled,
+        SpillConfig::Disabled,
+        WalArchiveConfig::Disabled,
+        Default::default(),
+        NonZeroUsize::new(num_cpus::get()).unwrap_or(NonZeroUsize::MIN),
+        NonZeroUsize::new(usize::MAX).unwrap(),
+        None,
+        futures::future::pending::<CancellationToken>(),
+    )
+    .await?;
+    drop(ingester);
+
+    info!(output_dir=?config.output_dir, "archived wal segments replayed");
+
+    Ok(())
+}
+
+/// The scratch catalog state of a namespace in the source catalog.
+struct ScratchNamespace {
+    schema: NamespaceSchema,
+    /// The table names in the source catalog, by source table ID.
+    table_names: HashMap<TableId, String>,
+}
+
+/// Rewrites the catalog IDs in [`SequencedWalOp`] from those of the source
+/// catalog, to those of the namespaces, tables and columns with the same name
+/// in the scratch catalog (creating them as needed).
+struct IdRewriter {
+    catalog: Arc<dyn Catalog>,
+    table_index: TableIndexFetcher,
+    /// The scratch namespaces, by source namespace ID, or [`None`] if the
+    /// source namespace no longer exists.
+    namespaces: HashMap<NamespaceId, Option<ScratchNamespace>>,
+}
+
+impl IdRewriter {
+    fn new(catalog: Arc<dyn Catalog>, table_index: TableIndexFetcher) -> Self {
+        Self {
+            catalog,
+            table_index,
+            namespaces: Default::default(),
+        }
+    }
+
+    /// Rewrite the catalog IDs in `op`, returning [`None`] if it cannot be
+    /// replayed.
+    ///
+    /// Only write operations are replayed.
+    async fn rewrite(&mut self, op: SequencedWalOp) -> Result<Option<SequencedWalOp>, Error> {
+        let SequencedWalOp {
+            table_write_sequence_numbers,
+            op: WalOp::Write(mut batch),
+        } = op
+        else {
+            return Ok(None);
+        };
+
+        let catalog = Arc::clone(&self.catalog);
+        let namespace_id = NamespaceId::new(batch.database_id);
+        let Some(namespace) = self.namespace(namespace_id).await? else {
+            return Ok(None);
+        };
+
+        // Ensure the tables and columns exist in the scratch catalog.
+        let tables = mutable_batch_pb::decode::decode_database_batch(&batch)
+            .map_err(Error::FailedToDecodeWriteBatch)?
+            .into_iter()
+            .map(|(id, mb)| {
+                let id = TableId::new(id);
+                match namespace.table_names.get(&id) {
+                    Some(name) => Ok((name.as_str(), mb)),
+                    None => Err(id),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>();
+        let tables = match tables {
+            Ok(v) => v,
+            Err(table_id) => {
+                warn!(%namespace_id, %table_id, "skipping write to unknown table");
+                return Ok(None);
+            }
+        };
+
+        let mut repos = catalog.repositories().await;
+        let updated = iox_catalog::validate_or_insert_schema(
+            tables.iter().map(|(name, mb)| (*name, mb)),
+            &namespace.schema,
+            repos.as_mut(),
+        )
+        .await
+        .map_err(Error::ScratchSchema)?;
+        if let Some(schema) = updated {
+            namespace.schema = schema;
+        }
+
+        // Rewrite the IDs to those of the scratch catalog.
+        let scratch_table_id = |id: i64| {
+            let name = &namespace.table_names[&TableId::new(id)];
+            namespace.schema.tables[name].id
+        };
+
+        batch.database_id = namespace.schema.id.get();
+        for table in &mut batch.table_batches {
+            table.table_id = scratch_table_id(table.table_id).get();
+        }
+        let table_write_sequence_numbers = table_write_sequence_numbers
+            .into_iter()
+            .map(|(id, seq)| (scratch_table_id(id.get()), seq))
+            .collect();
+
+        Ok(Some(SequencedWalOp {
+            table_write_sequence_numbers,
+            op: WalOp::Write(batch),
+        }))
+    }
+
+    /// Return the scratch namespace for the source namespace `id`, creating it
+    /// if necessary, or [`None`] if the source namespace does not exist.
+    async fn namespace(&mut self, id: NamespaceId) -> Result<Option<&mut ScratchNamespace>, Error> {
+        if !self.namespaces.contains_key(&id) {
+            let ns = self.create_namespace(id).await?;
+            self.namespaces.insert(id, ns);
+        }
+
+        Ok(self.namespaces.get_mut(&id).and_then(Option::as_mut))
+    }
+
+    async fn create_namespace(&self, id: NamespaceId) -> Result<Option<ScratchNamespace>, Error> {
+        let table_names = match self.table_index.get_table_name_index(id).await {
+            Ok(v) => v,
+            Err(TableIndexLookupError::NamespaceNotKnown(_)) => {
+                warn!(namespace_id=%id, "skipping writes to unknown namespace");
+                return Ok(None);
+            }
+            Err(e) => return Err(Error::UnableToInitTableNameFetcher(e)),
+        };
+        let name = self
+            .table_index
+            .namespace_name(id)
+            .expect("namespace name resolved above");
+
+        let mut repos = self.catalog.repositories().await;
+        let schema = match get_schema_by_name(name, repos.as_mut(), SoftDeletedRows::ExcludeDeleted)
+            .await
+        {
+            Ok(v) => v,
+            Err(iox_catalog::interface::Error::NamespaceNotFoundByName { .. }) => {
+                let namespace_name = NamespaceName::try_from(name)
+                    .expect("source catalog namespace name must be valid");
+                repos
+                    .namespaces()
+                    .create(&namespace_name, None, None, None)
+                    .await?;
+                get_schema_by_name(name, repos.as_mut(), SoftDeletedRows::ExcludeDeleted).await?
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        debug!(
+            namespace_id=%id,
+            namespace_name=%name,
+            scratch_namespace_id=%schema.id,
+            "resolved scratch namespace"
+        );
+
+        Ok(Some(ScratchNamespace {
+            schema,
+            table_names,
+        }))
+    }
+}
@@ -0,0 +1,104 @@
+//! This module implements the `garbage-collector` CLI command
+
+use std::{path::PathBuf, sync::Arc, time::Duration};
+
+use clap_blocks::{
+    catalog_dsn::CatalogDsnConfig,
+    object_store::{make_cold_object_store, make_object_store, ObjectStoreConfig},
+};
+use garbage_collector::report::{build_report, ReportFormat};
+use humantime::parse_duration;
+use thiserror::Error;
+
+use crate::process_info::setup_metric_registry;
+
+#[allow(clippy::enum_variant_names)]
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("Catalog DSN error: {0}")]
+    CatalogDsn(#[from] clap_blocks::catalog_dsn::Error),
+
+    #[error("Object store config error: {0}")]
+    ObjectStoreConfig(#[from] clap_blocks::object_store::ParseError),
+
+    #[error("Report error: {0}")]
+    Report(#[from] garbage_collector::report::Error),
+}
+
+/// Various commands for garbage collector auditing
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    #[clap(subcommand)]
+    command: Command,
+}
+
+/// Write an inventory of every object in the object store, and of the cold tier if configured.
+///
+/// Each object is classified as referenced by the catalog, soft-deleted in the catalog, orphaned
+/// (and deleted by the garbage collector) or too new to judge. `objects.<format>` lists every
+/// object and `summary.<format>` sums the objects and bytes per namespace, table and
+/// classification.
+///
+/// Only the catalog and the object store listings are read; nothing is deleted or modified.
+#[derive(Debug, clap::Parser)]
+struct Report {
+    /// The directory to write the report to
+    #[clap(long)]
+    output_dir: PathBuf,
+
+    /// The file format of the report
+    #[clap(long, value_enum, default_value = "csv")]
+    format: ReportFormat,
+
+    /// Objects not in the catalog that are newer than this duration are too new to judge. Should
+    /// match the `--objectstore-cutoff` of the garbage collector.
+    /// Parsed with <https://docs.rs/humantime/latest/humantime/fn.parse_duration.html>
+    #[clap(
+        long,
+        default_value = "14d",
+        value_parser = parse_duration,
+        env = "INFLUXDB_IOX_GC_OBJECTSTORE_CUTOFF"
+    )]
+    objectstore_cutoff: Duration,
+
+    #[clap(flatten)]
+    catalog_dsn: CatalogDsnConfig,
+
+    #[clap(flatten)]
+    object_store_config: ObjectStoreConfig,
+}
+
+/// All possible subcommands for garbage collector
+#[derive(Debug, clap::Parser)]
+enum Command {
+    /// Report what the garbage collector makes of every object, without deleting anything
+    Report(Report),
+}
+
+pub async fn command(config: Config) -> Result<(), Error> {
+    match config.command {
+        Command::Report(command) => {
+            let metric_registry = setup_metric_registry();
+            let catalog = command
+                .catalog_dsn
+                .get_catalog("cli", Arc::clone(&metric_registry))
+                .await?;
+
+            let mut stores = vec![("hot", make_object_store(&command.object_store_config)?)];
+            if let Some(cold_store) = make_cold_object_store(&command.object_store_config)? {
+                stores.push(("cold", cold_store));
+            }
+
+            let report = build_report(catalog, &stores, command.objectstore_cutoff).await?;
+            report.write(&command.output_dir, command.format)?;
+
+            println!(
+                "listed {} objects, wrote report to {}",
+                report.objects.len(),
+                command.output_dir.display()
+            );
+        }
+    }
+
+    Ok(())
+}
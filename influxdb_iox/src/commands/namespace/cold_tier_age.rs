@@ -0,0 +1,40 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+/// Update the age after which the specified namespace's parquet files are moved to the cold
+/// storage tier
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the cold tier age for
+    #[clap(action)]
+    namespace: String,
+
+    /// Num of days after the max time of its data a parquet file is moved to the cold tier.
+    /// Default is 0 representing never moving files
+    #[clap(action, long = "age-days", short = 'd', default_value = "0")]
+    age_days: u32,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        namespace,
+        age_days,
+    } = config;
+
+    // age_days = 0 means never moving files. Make it None/Null in the request.
+    let cold_tier_age: Option<i64> = if age_days == 0 {
+        None
+    } else {
+        // we take the age from the user in days, for ease of use, but it's stored as nanoseconds
+        // internally
+        Some(age_days as i64 * 24 * 60 * 60 * 1_000_000_000)
+    };
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+    let namespace = client
+        .update_namespace_cold_tier_age(&namespace, cold_tier_age)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
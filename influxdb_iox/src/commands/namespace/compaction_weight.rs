@@ -0,0 +1,29 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::namespace::Result;
+
+/// Update the weight of the share of the compactors given to the specified namespace when
+/// compacting with fair sharing
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the compaction weight for
+    #[clap(action)]
+    namespace: String,
+
+    /// The weight of the namespace, relative to the other namespaces. Must be at least 1, the
+    /// default of every namespace
+    #[clap(action, value_parser = clap::value_parser!(i32).range(1..))]
+    weight: i32,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config { namespace, weight } = config;
+
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+    let namespace = client
+        .update_namespace_compaction_weight(&namespace, weight)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::FieldCoercionRules;
+
+use crate::commands::namespace::Result;
+
+/// Replace the field type coercion rules of an existing namespace.
+///
+/// Any rule not specified is disabled - running this command with no rule
+/// flags disables all coercion for the namespace.
+///
+/// Routers with schema gossip enabled apply the change without a restart.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the field coercion rules for
+    #[clap(action)]
+    namespace: String,
+
+    /// Convert integer values written to a float column
+    #[clap(long, action)]
+    int_to_float: bool,
+
+    /// Convert unsigned integer values written to an integer column, if the
+    /// value is within range
+    #[clap(long, action)]
+    uint_to_int: bool,
+
+    /// Convert boolean values written to a string column
+    #[clap(long, action)]
+    bool_to_string: bool,
+
+    /// Convert integer, unsigned integer and float values written to a string
+    /// column
+    #[clap(long, action)]
+    number_to_string: bool,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let rules = FieldCoercionRules {
+        int_to_float: config.int_to_float,
+        uint_to_int: config.uint_to_int,
+        bool_to_string: config.bool_to_string,
+        number_to_string: config.number_to_string,
+    };
+
+    let namespace = client
+        .update_namespace_field_coercion(&config.namespace, rules)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -3,10 +3,17 @@
 use influxdb_iox_client::{connection::Connection, namespace};
 use thiserror::Error;
 
+mod cold_tier_age;
+mod compaction_weight;
 mod create;
 mod delete;
+mod field_coercion;
+mod partition_template;
 mod retention;
+mod rollup_policies;
+mod schema_mode;
 mod update_limit;
+mod write_rules;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -42,6 +49,29 @@ enum Command {
     /// Update one of the service protection limits for an existing namespace
     UpdateLimit(update_limit::Config),
 
+    /// Update the schema mode of an existing namespace
+    SchemaMode(schema_mode::Config),
+
+    /// Update the field type coercion rules of an existing namespace
+    FieldCoercion(field_coercion::Config),
+
+    /// Replace the write transformation rules of an existing namespace
+    WriteRules(write_rules::Config),
+
+    /// Replace the partition template of an existing namespace
+    PartitionTemplate(partition_template::Config),
+
+    /// Replace the rollup policies of an existing namespace
+    RollupPolicies(rollup_policies::Config),
+
+    /// Update the age after which the parquet files of an existing namespace are moved to the
+    /// cold storage tier
+    ColdTierAge(cold_tier_age::Config),
+
+    /// Update the weight of the share of the compactors given to an existing namespace when
+    /// compacting with fair sharing
+    CompactionWeight(compaction_weight::Config),
+
     /// Delete a namespace
     Delete(delete::Config),
 }
@@ -62,6 +92,27 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         Command::UpdateLimit(config) => {
             update_limit::command(connection, config).await?;
         }
+        Command::SchemaMode(config) => {
+            schema_mode::command(connection, config).await?;
+        }
+        Command::FieldCoercion(config) => {
+            field_coercion::command(connection, config).await?;
+        }
+        Command::WriteRules(config) => {
+            write_rules::command(connection, config).await?;
+        }
+        Command::PartitionTemplate(config) => {
+            partition_template::command(connection, config).await?;
+        }
+        Command::RollupPolicies(config) => {
+            rollup_policies::command(connection, config).await?;
+        }
+        Command::ColdTierAge(config) => {
+            cold_tier_age::command(connection, config).await?;
+        }
+        Command::CompactionWeight(config) => {
+            compaction_weight::command(connection, config).await?;
+        }
         Command::Delete(config) => {
             delete::command(connection, config).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
@@ -0,0 +1,35 @@
+use influxdb_iox_client::connection::Connection;
+
+use crate::commands::{namespace::Result, partition_template::PartitionTemplateConfig};
+
+/// Replace the partition template of an existing namespace. Only tables
+/// created after the change use the new template.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the partition template for
+    #[clap(action)]
+    namespace: String,
+
+    /// Partition template. If not specified, the default partition template
+    /// applies
+    #[clap(flatten)]
+    partition_template_config: PartitionTemplateConfig,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        namespace,
+        partition_template_config,
+    } = config;
+
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+    let namespace = client
+        .update_namespace_partition_template(
+            &namespace,
+            partition_template_config.partition_template,
+        )
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -0,0 +1,43 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::RollupPolicies;
+
+use crate::commands::namespace::Result;
+
+/// Replace the rollup policies of an existing namespace.
+///
+/// Each policy instructs the compactor to materialise a downsampled copy of a
+/// table, named "<table>_rollup_<interval>s", once its partitions are older
+/// than the configured age threshold.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the rollup policies for
+    #[clap(action)]
+    namespace: String,
+
+    /// The set of rollup policies, as JSON
+    ///
+    /// e.g. {"policies": [{"sourceTable": "cpu", "intervalSeconds": 300,
+    /// "ageThresholdSeconds": 86400, "aggregates": [{"field": "usage",
+    /// "aggregate": "AGGREGATE_MEAN"}]}]}
+    ///
+    /// Supported aggregates are AGGREGATE_MIN, AGGREGATE_MAX, AGGREGATE_SUM,
+    /// AGGREGATE_COUNT and AGGREGATE_MEAN. An empty set of policies ({})
+    /// removes all policies from the namespace.
+    #[clap(action, value_parser = parse_rollup_policies)]
+    rollup_policies: RollupPolicies,
+}
+
+fn parse_rollup_policies(s: &str) -> Result<RollupPolicies, serde_json::Error> {
+    serde_json::from_str(s)
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_rollup_policies(&config.namespace, config.rollup_policies)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -0,0 +1,45 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::SchemaMode;
+
+use crate::commands::namespace::Result;
+
+/// Update the schema mode of an existing namespace
+///
+/// Routers with schema gossip enabled apply the change without a restart.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the schema mode for
+    #[clap(action)]
+    namespace: String,
+
+    /// The new schema mode. In "explicit" mode writes referencing tables or
+    /// columns that do not exist are rejected.
+    #[clap(value_enum, action)]
+    mode: Mode,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum Mode {
+    Implicit,
+    Explicit,
+}
+
+impl From<Mode> for SchemaMode {
+    fn from(mode: Mode) -> Self {
+        match mode {
+            Mode::Implicit => Self::Implicit,
+            Mode::Explicit => Self::Explicit,
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_schema_mode(&config.namespace, config.mode.into())
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -0,0 +1,41 @@
+use influxdb_iox_client::connection::Connection;
+use influxdb_iox_client::namespace::generated_types::WriteRules;
+
+use crate::commands::namespace::Result;
+
+/// Replace the write transformation rules of an existing namespace.
+///
+/// The rules are applied by the router, in order, to every write to the
+/// namespace before it is validated against the namespace schema.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The namespace to update the write rules for
+    #[clap(action)]
+    namespace: String,
+
+    /// The ordered set of write rules, as JSON
+    ///
+    /// e.g. {"rules": [{"dropMeasurement": {"measurementRegex": "debug_.*"}},
+    /// {"renameTag": {"from": "host", "to": "hostname"}}]}
+    ///
+    /// Supported rules are dropMeasurement, dropField, renameTag, addTag and
+    /// dropLongTagValues. An empty set of rules ({}) removes all rules from
+    /// the namespace.
+    #[clap(action, value_parser = parse_write_rules)]
+    write_rules: WriteRules,
+}
+
+fn parse_write_rules(s: &str) -> Result<WriteRules, serde_json::Error> {
+    serde_json::from_str(s)
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::namespace::Client::new(connection);
+
+    let namespace = client
+        .update_namespace_write_rules(&config.namespace, config.write_rules)
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&namespace)?);
+
+    Ok(())
+}
@@ -0,0 +1,58 @@
+use influxdb_iox_client::connection::Connection;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("JSON Serialization error: {0}")]
+    Serde(#[from] serde_json::Error),
+
+    #[error("Client error: {0}")]
+    ClientError(#[from] influxdb_iox_client::error::Error),
+}
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Instruct a particular ingester to immediately persist the data it has
+/// buffered for a namespace, optionally restricted to a single table and/or
+/// partition key.
+///
+/// Prints the IDs of the resulting parquet files once persistence completes.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The IOx namespace to persist
+    #[clap(action)]
+    namespace: String,
+
+    /// Only persist the data buffered for this table
+    #[clap(long, action)]
+    table: Option<String>,
+
+    /// Only persist the data buffered for partitions with this partition key
+    #[clap(long, action)]
+    partition_key: Option<String>,
+
+    /// Return once the persist jobs are enqueued, without waiting for them
+    /// to complete
+    #[clap(long, action)]
+    no_wait: bool,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let mut client = influxdb_iox_client::ingester::Client::new(connection);
+    let Config {
+        namespace,
+        table,
+        partition_key,
+        no_wait,
+    } = config;
+
+    let parquet_file_ids = client
+        .persist_filtered(namespace, table, partition_key, !no_wait)
+        .await?;
+
+    if !no_wait {
+        println!("{}", serde_json::to_string_pretty(&parquet_file_ids)?);
+    }
+
+    Ok(())
+}
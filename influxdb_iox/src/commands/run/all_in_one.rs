@@ -19,6 +19,7 @@ use clap_blocks::{
         CONFIG_AUTHZ_ENV_NAME, CONFIG_AUTHZ_FLAG, CONFIG_CST_ENV_NAME, CONFIG_CST_FLAG,
     },
     socket_addr::SocketAddr,
+    wal_encryption::WalEncryptionConfig,
 };
 use compactor::object_store::metrics::MetricsStore;
 use iox_query::exec::{Executor, ExecutorConfig};
@@ -479,13 +480,27 @@ impl Config {
         let ingester_config = IngesterConfig {
             wal_directory,
             wal_rotation_period_seconds,
+            wal_durability_mode: Default::default(),
+            wal_group_commit_max_latency_ms: 10,
+            wal_group_commit_max_batch_bytes: 4 * 1024 * 1024, // 4MiB
+            wal_encryption_config: WalEncryptionConfig::disabled(),
+            wal_archive_prefix: None,
+            wal_archive_retention_hours: 168,
+            wal_replay_concurrency: None,
             concurrent_query_limit,
             persist_max_parallelism,
             persist_queue_depth,
             persist_hot_partition_cost,
+            spill_threshold_bytes: None,
+            spill_directory: None,
+            spill_max_disk_bytes: 0,
             rpc_write_max_incoming_bytes: 1024 * 1024 * 1024, // 1GiB
             gossip_config: GossipConfig::disabled(),
             max_partitions_per_namespace: None,
+            max_series_per_table: None,
+            handoff_peer_address: None,
+            handoff_advertise_address: None,
+            handoff_timeout_seconds: 120,
         };
 
         let router_config = RouterConfig {
@@ -497,8 +512,11 @@ impl Config {
             namespace_autocreation_enabled: true,
             rpc_write_timeout_seconds: Duration::new(3, 0),
             rpc_write_replicas: 1.try_into().unwrap(),
+            rpc_write_balancer: Default::default(),
             rpc_write_max_outgoing_bytes: ingester_config.rpc_write_max_incoming_bytes,
             rpc_write_health_num_probes: 10,
+            rpc_write_spool_directory: None,
+            rpc_write_spool_max_bytes: 1024 * 1024 * 1024, // 1GiB
             gossip_config: GossipConfig::disabled(),
         };
 
@@ -524,6 +542,8 @@ impl Config {
             max_num_columns_per_table: 200,
             max_num_files_per_plan: 200,
             max_partition_fetch_queries_per_second: Some(500),
+            rollup_poll_interval: None,
+            sort_key_rewrite_poll_interval: None,
             gossip_config: GossipConfig::disabled(),
         };
 
@@ -691,9 +711,12 @@ pub async fn command(config: Config) -> Result<()> {
         metric_registry: Arc::clone(&metrics),
         catalog,
         object_store,
+        // the garbage collector relocating files to the cold tier does not run in all-in-one mode
+        cold_object_store: None,
         exec,
         time_provider,
         querier_config,
+        gossip_config: &GossipConfig::disabled(),
         trace_context_header_name: querier_run_config
             .tracing_config()
             .traces_jaeger_trace_context_header_name
@@ -3,7 +3,9 @@
 use super::main;
 use crate::process_info::setup_metric_registry;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, compactor::CompactorConfig, object_store::make_object_store,
+    catalog_dsn::CatalogDsnConfig,
+    compactor::CompactorConfig,
+    object_store::{make_cold_object_store, make_object_store},
     run_config::RunConfig,
 };
 use compactor::object_store::metrics::MetricsStore;
@@ -17,7 +19,10 @@ use ioxd_compactor::{create_compactor_server_type, derive_mem_pool_size};
 use object_store::DynObjectStore;
 use object_store_metrics::ObjectStoreMetrics;
 use observability_deps::tracing::*;
-use parquet_file::storage::{ParquetStorage, StorageId};
+use parquet_file::{
+    storage::{ParquetStorage, StorageId},
+    tiered::ColdTierFallbackStore,
+};
 use std::num::NonZeroUsize;
 use std::sync::Arc;
 use thiserror::Error;
@@ -86,6 +91,17 @@ pub async fn command(config: Config) -> Result<(), Error> {
         &metric_registry,
     ));
 
+    // Files relocated to the cold tier by the garbage collector are still read by the compactor.
+    let object_store = match make_cold_object_store(config.run_config.object_store_config())
+        .map_err(Error::ObjectStoreParsing)?
+    {
+        Some(cold_object_store) => {
+            Arc::new(ColdTierFallbackStore::new(object_store, cold_object_store))
+                as Arc<DynObjectStore>
+        }
+        None => object_store,
+    };
+
     let parquet_store_real = ParquetStorage::new(object_store, StorageId::from("iox"));
     let parquet_store_scratchpad = ParquetStorage::new(
         Arc::new(MetricsStore::new(
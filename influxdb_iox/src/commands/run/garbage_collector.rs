@@ -1,6 +1,8 @@
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, garbage_collector::GarbageCollectorConfig,
-    object_store::make_object_store, run_config::RunConfig,
+    catalog_dsn::CatalogDsnConfig,
+    garbage_collector::GarbageCollectorConfig,
+    object_store::{make_cold_object_store, make_object_store},
+    run_config::RunConfig,
 };
 use iox_time::SystemProvider;
 use ioxd_common::{
@@ -48,6 +50,8 @@ pub async fn command(config: Config) -> Result<()> {
         &metric_registry,
     ));
 
+    let cold_object_store = make_cold_object_store(config.run_config.object_store_config())?;
+
     let sub_config = config.sub_config;
 
     info!("starting garbage-collector");
@@ -55,6 +59,7 @@ pub async fn command(config: Config) -> Result<()> {
     let server_type = Arc::new({
         let config = gc::Config {
             object_store,
+            cold_object_store,
             catalog,
             sub_config,
         };
@@ -4,7 +4,10 @@ use crate::process_info::setup_metric_registry;
 
 use super::main;
 use clap_blocks::{
-    catalog_dsn::CatalogDsnConfig, object_store::make_object_store, querier::QuerierConfig,
+    catalog_dsn::CatalogDsnConfig,
+    gossip::GossipConfig,
+    object_store::{make_cold_object_store, make_object_store},
+    querier::QuerierConfig,
     run_config::RunConfig,
 };
 use iox_query::exec::Executor;
@@ -68,6 +71,9 @@ pub struct Config {
 
     #[clap(flatten)]
     pub(crate) querier_config: QuerierConfig,
+
+    #[clap(flatten)]
+    pub(crate) gossip_config: GossipConfig,
 }
 
 pub async fn command(config: Config) -> Result<(), Error> {
@@ -89,6 +95,8 @@ pub async fn command(config: Config) -> Result<(), Error> {
         Arc::clone(&time_provider),
         &metric_registry,
     ));
+    let cold_object_store = make_cold_object_store(config.run_config.object_store_config())
+        .map_err(Error::ObjectStoreParsing)?;
 
     let time_provider = Arc::new(SystemProvider::new());
 
@@ -112,9 +120,11 @@ pub async fn command(config: Config) -> Result<(), Error> {
         metric_registry: Arc::clone(&metric_registry),
         catalog,
         object_store,
+        cold_object_store,
         exec,
         time_provider,
         querier_config: config.querier_config,
+        gossip_config: &config.gossip_config,
         trace_context_header_name: config
             .run_config
             .tracing_config()
@@ -0,0 +1,85 @@
+use crate::commands::table::Result;
+use influxdb_iox_client::{connection::Connection, table::generated_types::ColumnType};
+
+/// Create a new column in an existing table
+#[derive(Debug, clap::Parser, Clone)]
+pub struct Config {
+    /// The database containing the table
+    #[clap(action)]
+    database: String,
+
+    /// The table to create the column in
+    #[clap(action)]
+    table: String,
+
+    /// The column to be created
+    #[clap(action)]
+    column: String,
+
+    /// The data type of the column
+    #[clap(value_enum, action)]
+    column_type: Type,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum Type {
+    Tag,
+    I64,
+    U64,
+    F64,
+    Bool,
+    String,
+}
+
+impl From<Type> for ColumnType {
+    fn from(t: Type) -> Self {
+        match t {
+            Type::Tag => Self::Tag,
+            Type::I64 => Self::I64,
+            Type::U64 => Self::U64,
+            Type::F64 => Self::F64,
+            Type::Bool => Self::Bool,
+            Type::String => Self::String,
+        }
+    }
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        database,
+        table,
+        column,
+        column_type,
+    } = config;
+
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+
+    let column = client
+        .create_column(&database, &table, &column, column_type.into())
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&column)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::Parser;
+
+    #[test]
+    fn valid_config() {
+        let config =
+            Config::try_parse_from(["server", "database", "table", "column", "tag"]).unwrap();
+
+        assert_eq!(config.database, "database");
+        assert_eq!(config.table, "table");
+        assert_eq!(config.column, "column");
+        assert_eq!(config.column_type, Type::Tag);
+    }
+
+    #[test]
+    fn invalid_column_type() {
+        Config::try_parse_from(["server", "database", "table", "column", "time"]).unwrap_err();
+    }
+}
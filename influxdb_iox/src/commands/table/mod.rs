@@ -5,7 +5,10 @@ use observability_deps::tracing::info;
 use thiserror::Error;
 
 mod create;
+mod create_column;
 mod list;
+mod partition_template;
+mod retention;
 
 #[allow(clippy::enum_variant_names)]
 #[derive(Debug, Error)]
@@ -33,6 +36,12 @@ enum Command {
     List(list::Config),
     /// Create a new table
     Create(create::Config),
+    /// Create a new column in an existing table
+    CreateColumn(create_column::Config),
+    /// Update the retention period override of an existing table
+    Retention(retention::Config),
+    /// Replace the partition template of an existing table
+    PartitionTemplate(partition_template::Config),
 }
 
 pub async fn command(connection: Connection, config: Config) -> Result<()> {
@@ -41,6 +50,15 @@ pub async fn command(connection: Connection, config: Config) -> Result<()> {
         Command::Create(config) => {
             info!("Creating table with config: {:?}", config);
             create::command(connection, config).await?;
+        }
+        Command::CreateColumn(config) => {
+            create_column::command(connection, config).await?;
+        }
+        Command::Retention(config) => {
+            retention::command(connection, config).await?;
+        }
+        Command::PartitionTemplate(config) => {
+            partition_template::command(connection, config).await?;
         } // Deliberately not adding _ => so the compiler will direct people here to impl new
           // commands
     }
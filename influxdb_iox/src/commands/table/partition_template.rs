@@ -0,0 +1,40 @@
+use crate::commands::{partition_template::PartitionTemplateConfig, table::Result};
+use influxdb_iox_client::connection::Connection;
+
+/// Replace the partition template of the specified table. Only partitions
+/// created after the change use the new template.
+#[derive(Debug, clap::Parser)]
+pub struct Config {
+    /// The database containing the table
+    #[clap(action)]
+    database: String,
+
+    /// The table to update the partition template for
+    #[clap(action)]
+    table: String,
+
+    /// Partition template. If not specified, the partition template of the
+    /// database applies
+    #[clap(flatten)]
+    partition_template_config: PartitionTemplateConfig,
+}
+
+pub async fn command(connection: Connection, config: Config) -> Result<()> {
+    let Config {
+        database,
+        table,
+        partition_template_config,
+    } = config;
+
+    let mut client = influxdb_iox_client::table::Client::new(connection);
+    let table = client
+        .update_table_partition_template(
+            &database,
+            &table,
+            partition_template_config.partition_template,
+        )
+        .await?;
+    println!("{}", serde_json::to_string_pretty(&table)?);
+
+    Ok(())
+}
@@ -38,9 +38,12 @@ use trace_exporters::{
 
 mod commands {
     pub mod catalog;
+    pub mod compactor;
     pub mod debug;
+    pub mod garbage_collector;
     pub mod namespace;
     pub mod partition_template;
+    pub mod persist_ingester;
     pub mod query;
     pub mod query_ingester;
     pub mod remote;
@@ -207,9 +210,15 @@ enum Command {
     /// Various commands for catalog manipulation
     Catalog(commands::catalog::Config),
 
+    /// Various commands for compactor debugging
+    Compactor(commands::compactor::Config),
+
     /// Interrogate internal data
     Debug(commands::debug::Config),
 
+    /// Various commands for garbage collector auditing
+    GarbageCollector(commands::garbage_collector::Config),
+
     /// Initiate a read request to the gRPC storage service.
     Storage(commands::storage::Config),
 
@@ -222,6 +231,9 @@ enum Command {
     /// Query the ingester only
     QueryIngester(commands::query_ingester::Config),
 
+    /// Persist the data buffered by an ingester
+    PersistIngester(commands::persist_ingester::Config),
+
     /// Various commands for namespace manipulation
     Namespace(commands::namespace::Config),
 
@@ -344,6 +356,20 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::Compactor(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::compactor::command(config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
+            Some(Command::GarbageCollector(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                if let Err(e) = commands::garbage_collector::command(config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Debug(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 if let Err(e) = commands::debug::command(|| connection(grpc_host), config).await {
@@ -375,6 +401,14 @@ fn main() -> Result<(), std::io::Error> {
                     std::process::exit(ReturnCode::Failure as _)
                 }
             }
+            Some(Command::PersistIngester(config)) => {
+                let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
+                let connection = connection(grpc_host).await;
+                if let Err(e) = commands::persist_ingester::command(connection, config).await {
+                    eprintln!("{e}");
+                    std::process::exit(ReturnCode::Failure as _)
+                }
+            }
             Some(Command::Namespace(config)) => {
                 let _tracing_guard = handle_init_logs(init_simple_logs(log_verbose_count));
                 let connection = connection(grpc_host).await;
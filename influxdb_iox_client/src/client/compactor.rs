@@ -43,4 +43,22 @@ impl Client {
 
         Ok(response.into_inner().skipped_compaction)
     }
+
+    /// Request the rewrite of all partitions of a table with a sort key
+    /// ordered by tag cardinality, returning the ID of the table.
+    pub async fn rewrite_table_sort_key(
+        &mut self,
+        namespace_name: impl Into<String> + Send,
+        table_name: impl Into<String> + Send,
+    ) -> Result<i64, Error> {
+        let response = self
+            .inner
+            .rewrite_table_sort_key(RewriteTableSortKeyRequest {
+                namespace_name: namespace_name.into(),
+                table_name: table_name.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().table_id)
+    }
 }
@@ -1,4 +1,6 @@
-use self::generated_types::{persist_service_client::PersistServiceClient, *};
+use self::generated_types::{
+    buffer_service_client::BufferServiceClient, persist_service_client::PersistServiceClient, *,
+};
 use crate::{connection::Connection, error::Error};
 use client_util::connection::GrpcConnection;
 
@@ -7,17 +9,21 @@ pub mod generated_types {
     pub use generated_types::influxdata::iox::ingester::v1::*;
 }
 
-/// A basic client for interacting with the ingester persist service.
+/// A basic client for interacting with the ingester persist and buffer
+/// services.
 #[derive(Debug, Clone)]
 pub struct Client {
     inner: PersistServiceClient<GrpcConnection>,
+    buffer: BufferServiceClient<GrpcConnection>,
 }
 
 impl Client {
     /// Creates a new client with the provided connection
     pub fn new(connection: Connection) -> Self {
+        let connection = connection.into_grpc_connection();
         Self {
-            inner: PersistServiceClient::new(connection.into_grpc_connection()),
+            inner: PersistServiceClient::new(connection.clone()),
+            buffer: BufferServiceClient::new(connection),
         }
     }
 
@@ -25,8 +31,50 @@ impl Client {
     /// tests asserting on persisted data. May behave in unexpected ways if used concurrently with
     /// writes and ingester WAL rotations.
     pub async fn persist(&mut self, namespace: String) -> Result<(), Error> {
-        self.inner.persist(PersistRequest { namespace }).await?;
+        self.inner
+            .persist(PersistRequest {
+                namespace,
+                ..Default::default()
+            })
+            .await?;
 
         Ok(())
     }
+
+    /// Instruct the ingester to persist its data for the specified namespace to Parquet,
+    /// optionally restricted to a single `table` and/or `partition_key` within it.
+    ///
+    /// If `wait_for_completion` is true, returns the IDs of the resulting parquet files once
+    /// persistence is complete, otherwise returns an empty list once the persist jobs are
+    /// enqueued.
+    pub async fn persist_filtered(
+        &mut self,
+        namespace: String,
+        table: Option<String>,
+        partition_key: Option<String>,
+        wait_for_completion: bool,
+    ) -> Result<Vec<i64>, Error> {
+        let response = self
+            .inner
+            .persist(PersistRequest {
+                namespace,
+                table,
+                partition_key,
+                wait_for_completion: Some(wait_for_completion),
+            })
+            .await?;
+
+        Ok(response.into_inner().parquet_file_ids)
+    }
+
+    /// List the partitions buffered in the ingester, filtered, ordered and
+    /// paginated as specified by `request`.
+    pub async fn list_buffered_partitions(
+        &mut self,
+        request: ListPartitionsRequest,
+    ) -> Result<ListPartitionsResponse, Error> {
+        let response = self.buffer.list_partitions(request).await?;
+
+        Ok(response.into_inner())
+    }
 }
@@ -10,6 +10,8 @@ pub mod generated_types {
     pub use generated_types::influxdata::iox::{
         namespace::v1::{update_namespace_service_protection_limit_request::LimitUpdate, *},
         partition_template::v1::{template_part::*, *},
+        rollup::v1::*,
+        write_rules::v1::*,
     };
 }
 
@@ -108,6 +110,139 @@ impl Client {
         Ok(response.into_inner().namespace.unwrap_field("namespace")?)
     }
 
+    /// Update the schema mode of a namespace
+    pub async fn update_namespace_schema_mode(
+        &mut self,
+        namespace: &str,
+        schema_mode: SchemaMode,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_schema_mode(UpdateNamespaceSchemaModeRequest {
+                name: namespace.to_string(),
+                schema_mode: schema_mode.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Replace the field type coercion rules of a namespace
+    pub async fn update_namespace_field_coercion(
+        &mut self,
+        namespace: &str,
+        field_coercion: FieldCoercionRules,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_field_coercion(UpdateNamespaceFieldCoercionRequest {
+                name: namespace.to_string(),
+                field_coercion: Some(field_coercion),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Replace the write transformation rules of a namespace
+    pub async fn update_namespace_write_rules(
+        &mut self,
+        namespace: &str,
+        write_rules: WriteRules,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_write_rules(UpdateNamespaceWriteRulesRequest {
+                name: namespace.to_string(),
+                write_rules: Some(write_rules),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Replace the rollup policies of a namespace. An empty set of policies
+    /// stops the compactor materialising rollups for the namespace.
+    pub async fn update_namespace_rollup_policies(
+        &mut self,
+        namespace: &str,
+        rollup_policies: RollupPolicies,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_rollup_policies(UpdateNamespaceRollupPoliciesRequest {
+                name: namespace.to_string(),
+                rollup_policies: Some(rollup_policies),
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update the age after which the parquet files of a namespace are moved
+    /// to the cold storage tier.
+    ///
+    /// `cold_tier_age_ns` is measured against the max time of the data in a
+    /// file. `None` represents never moving files, and 0 is also mapped to
+    /// `None` on the server side.
+    ///
+    /// Negative ages are rejected, returning an error.
+    pub async fn update_namespace_cold_tier_age(
+        &mut self,
+        namespace: &str,
+        cold_tier_age_ns: Option<i64>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_cold_tier_age(UpdateNamespaceColdTierAgeRequest {
+                name: namespace.to_string(),
+                cold_tier_age_ns,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Update the weight of the share of the compactors given to a namespace
+    /// when compacting with fair sharing.
+    ///
+    /// Weights below 1 are rejected, returning an error.
+    pub async fn update_namespace_compaction_weight(
+        &mut self,
+        namespace: &str,
+        compaction_weight: i32,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_compaction_weight(UpdateNamespaceCompactionWeightRequest {
+                name: namespace.to_string(),
+                compaction_weight,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
+    /// Replace the partition template of a namespace. `None` resets the
+    /// namespace to the default partition template.
+    ///
+    /// Only tables created after the change use the new template.
+    pub async fn update_namespace_partition_template(
+        &mut self,
+        namespace: &str,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<Namespace, Error> {
+        let response = self
+            .inner
+            .update_namespace_partition_template(UpdateNamespacePartitionTemplateRequest {
+                name: namespace.to_string(),
+                partition_template,
+            })
+            .await?;
+
+        Ok(response.into_inner().namespace.unwrap_field("namespace")?)
+    }
+
     /// Delete a namespace
     pub async fn delete_namespace(&mut self, namespace: &str) -> Result<(), Error> {
         self.inner
@@ -9,6 +9,7 @@ use ::generated_types::google::OptionalField;
 pub mod generated_types {
     pub use generated_types::influxdata::iox::{
         partition_template::v1::{template_part::*, *},
+        schema::v1::column_schema::ColumnType,
         table::v1::*,
     };
 }
@@ -57,4 +58,67 @@ impl Client {
 
         Ok(response.into_inner().table.unwrap_field("table")?)
     }
+
+    /// Create a column in a table
+    pub async fn create_column(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        column: &str,
+        column_type: ColumnType,
+    ) -> Result<Column, Error> {
+        let response = self
+            .inner
+            .create_column(CreateColumnRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                name: column.to_string(),
+                column_type: column_type.into(),
+            })
+            .await?;
+
+        Ok(response.into_inner().column.unwrap_field("column")?)
+    }
+
+    /// Update the retention period override of a table. `None` removes the
+    /// override, so the namespace retention period applies.
+    pub async fn update_table_retention(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .update_table_retention(UpdateTableRetentionRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                retention_period_ns,
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
+
+    /// Replace the partition template of a table. `None` applies the
+    /// namespace's partition template.
+    ///
+    /// Only partitions created after the change use the new template.
+    pub async fn update_table_partition_template(
+        &mut self,
+        namespace: &str,
+        table: &str,
+        partition_template: Option<PartitionTemplate>,
+    ) -> Result<Table, Error> {
+        let response = self
+            .inner
+            .update_table_partition_template(UpdateTablePartitionTemplateRequest {
+                namespace: namespace.to_string(),
+                table: table.to_string(),
+                partition_template,
+            })
+            .await?;
+
+        Ok(response.into_inner().table.unwrap_field("table")?)
+    }
 }
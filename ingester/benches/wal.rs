@@ -1,4 +1,4 @@
-use std::{iter, sync::Arc};
+use std::{iter, num::NonZeroUsize, sync::Arc};
 
 use async_trait::async_trait;
 use criterion::{criterion_group, criterion_main, BatchSize, Criterion, Throughput};
@@ -61,7 +61,7 @@ fn wal_replay_bench(c: &mut Criterion) {
 
                 // Pass all writes into a NOP that discards them with no
                 // overhead.
-                let sink = NopSink;
+                let sink = Arc::new(NopSink);
 
                 let persist = MockPersistQueue::default();
 
@@ -71,6 +71,8 @@ fn wal_replay_bench(c: &mut Criterion) {
                     &sink,
                     Arc::new(persist),
                     Arc::new(IngestState::default()),
+                    NonZeroUsize::new(1).unwrap(),
+                    Default::default(),
                     &metric::Registry::default(),
                 )
                 .await
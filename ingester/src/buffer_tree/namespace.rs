@@ -2,7 +2,7 @@
 
 pub(crate) mod name_resolver;
 
-use std::sync::Arc;
+use std::{num::NonZeroUsize, sync::Arc};
 
 use async_trait::async_trait;
 use data_types::{NamespaceId, TableId};
@@ -13,7 +13,7 @@ use trace::span::Span;
 use super::{
     partition::{counter::PartitionCounter, resolver::PartitionProvider},
     post_write::PostWriteObserver,
-    table::{metadata_resolver::TableProvider, TableData},
+    table::{metadata_resolver::TableProvider, series::SeriesTracker, TableData},
     BufferWriteError,
 };
 use crate::{
@@ -88,6 +88,10 @@ pub(crate) struct NamespaceData<O> {
     /// consistent when enforced.
     partition_count: Arc<PartitionCounter>,
 
+    /// The maximum number of distinct series that may be buffered for each
+    /// table in this namespace, if limited.
+    max_series_per_table: Option<NonZeroUsize>,
+
     post_write_observer: Arc<O>,
 }
 
@@ -99,6 +103,7 @@ impl<O> NamespaceData<O> {
         catalog_table_resolver: Arc<dyn TableProvider>,
         partition_provider: Arc<dyn PartitionProvider>,
         partition_counter: PartitionCounter,
+        max_series_per_table: Option<NonZeroUsize>,
         post_write_observer: Arc<O>,
         metrics: &metric::Registry,
     ) -> Self {
@@ -118,6 +123,7 @@ impl<O> NamespaceData<O> {
             partition_provider,
             post_write_observer,
             partition_count: Arc::new(partition_counter),
+            max_series_per_table,
         }
     }
 
@@ -171,17 +177,19 @@ where
                             Arc::clone(&self.namespace_name),
                             Arc::clone(&self.partition_provider),
                             Arc::clone(&self.partition_count),
+                            self.max_series_per_table.map(SeriesTracker::new),
                             Arc::clone(&self.post_write_observer),
                         ))
                     });
 
-                    let partitioned_data = b.into_partitioned_data();
+                    let (partitioned_data, partition_template) = b.into_parts();
 
                     table_data
                         .buffer_table_write(
                             partitioned_data.sequence_number(),
                             partitioned_data.into_data(),
                             partition_key.clone(),
+                            partition_template,
                         )
                         .await?;
                 }
@@ -262,6 +270,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             PartitionCounter::new(NonZeroUsize::new(usize::MAX).unwrap()),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             &metrics,
         );
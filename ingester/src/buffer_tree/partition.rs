@@ -1,6 +1,6 @@
 //! Partition level data buffer structures.
 
-use std::sync::Arc;
+use std::{sync::Arc, time::Instant};
 
 use data_types::{
     sequence_number_set::SequenceNumberSet, NamespaceId, PartitionKey, SequenceNumber,
@@ -16,9 +16,14 @@ use self::{
     persisting::{BatchIdent, PersistingData},
     persisting_list::PersistingList,
 };
-use super::{namespace::NamespaceName, table::metadata::TableMetadata, BufferWriteError};
+use super::{
+    namespace::NamespaceName,
+    table::{metadata::TableMetadata, series::SeriesTracker},
+    BufferWriteError,
+};
 use crate::{
-    deferred_load::DeferredLoad, query::projection::OwnedProjection, query_adaptor::QueryAdaptor,
+    deferred_load::DeferredLoad, query::projection::OwnedProjection,
+    query_adaptor::PendingQueryAdaptor, spill::SpillableBatches,
 };
 
 mod buffer;
@@ -121,6 +126,24 @@ pub struct PartitionData {
     /// [`PartitionData`].
     completed_persistence_count: u64,
 
+    /// The number of persist operations started over the lifetime of this
+    /// [`PartitionData`] because the buffer exceeded the hot partition
+    /// persist cost threshold.
+    hot_persistence_count: u64,
+
+    /// The instant the oldest write currently in the `buffer` was received, or
+    /// [`None`] if the `buffer` is empty.
+    ///
+    /// Reset each time the `buffer` is marked as persisting.
+    buffered_since: Option<Instant>,
+
+    /// The instant the most recent write was buffered, or [`None`] if no
+    /// writes have been buffered.
+    ///
+    /// Used to identify cold partitions, the buffered data of which can be
+    /// spilled to disk.
+    last_write_at: Option<Instant>,
+
     /// A counter tracking the number of non-empty partitions per namespace.
     ///
     /// This value is incremented when this [`PartitionData`] transitions from
@@ -129,6 +152,12 @@ pub struct PartitionData {
     /// data is dropped, transitioning the [`PartitionData`] from non-empty to
     /// empty.
     partition_counter: Arc<PartitionCounter>,
+
+    /// The series tracker of the table, if a series limit is configured.
+    ///
+    /// The series recorded for this partition are reset when the partition
+    /// transitions from non-empty to empty after persistence.
+    series: Option<Arc<SeriesTracker>>,
 }
 
 impl PartitionData {
@@ -156,11 +185,30 @@ impl PartitionData {
             persisting: PersistingList::default(),
             started_persistence_count: BatchIdent::default(),
             completed_persistence_count: 0,
+            hot_persistence_count: 0,
+            buffered_since: None,
+            last_write_at: None,
             partition_counter,
+            series: None,
             is_empty: true,
         }
     }
 
+    /// Set the [`SeriesTracker`] of the table this partition belongs to,
+    /// resetting the series recorded for this partition once all its data is
+    /// persisted.
+    pub(crate) fn set_series_tracker(&mut self, series: Arc<SeriesTracker>) {
+        self.series = Some(series);
+    }
+
+    /// Return the approximate number of distinct series buffered in this
+    /// partition, if a series limit is configured.
+    pub(crate) fn series_count(&self) -> Option<usize> {
+        self.series
+            .as_ref()
+            .map(|v| v.partition_count(&self.partition_key))
+    }
+
     /// Buffer the given [`MutableBatch`] in memory.
     pub(crate) fn buffer_write(
         &mut self,
@@ -183,6 +231,9 @@ impl PartitionData {
 
         // Buffer the write.
         self.buffer.buffer_write(mb, sequence_number)?;
+        let now = Instant::now();
+        self.buffered_since.get_or_insert(now);
+        self.last_write_at = Some(now);
 
         // Invariant: if the partition contains a buffered write, it must report
         // non-empty.
@@ -288,7 +339,14 @@ impl PartitionData {
 
     /// Return all data for this partition, ordered by the calls to
     /// [`PartitionData::buffer_write()`].
-    pub(crate) fn get_query_data(&mut self, projection: &OwnedProjection) -> Option<QueryAdaptor> {
+    ///
+    /// No I/O is performed by this call - data that has been spilled to disk is
+    /// read by [`PendingQueryAdaptor::read()`], which SHOULD be called once the
+    /// partition lock has been released.
+    pub(crate) fn get_query_data(
+        &mut self,
+        projection: &OwnedProjection,
+    ) -> Option<PendingQueryAdaptor> {
         // Extract the buffered data, if any.
         let buffered_data = self.buffer.get_query_data(projection);
 
@@ -297,11 +355,8 @@ impl PartitionData {
         // The persisting RecordBatch instances MUST be ordered before the
         // buffered data to preserve the ordering of writes such that updates to
         // existing rows materialise to the correct output.
-        let data = self
-            .persisting
-            .get_query_data(projection)
-            .chain(buffered_data)
-            .collect::<Vec<_>>();
+        let mut data = self.persisting.get_query_data(projection);
+        data.extend(buffered_data);
 
         trace!(
             namespace_id = %self.namespace_id,
@@ -309,7 +364,6 @@ impl PartitionData {
             table = %self.table,
             partition_id = %self.partition_id,
             partition_key = %self.partition_key,
-            n_batches = data.len(),
             "read partition data"
         );
 
@@ -325,38 +379,28 @@ impl PartitionData {
         // point because this partition is non-empty.
         debug_assert_ne!(self.partition_counter.read(), 0);
 
-        // Construct the query adaptor over the partition data.
-        //
-        // `data` MUST contain at least one row, or the constructor panics. This
-        // is upheld by the FSM, which ensures only non-empty snapshots /
-        // RecordBatch are generated. Because `data` contains at least one
-        // RecordBatch, this invariant holds.
-        let q = QueryAdaptor::new(self.partition_id.clone(), data);
-
-        // Invariant: the number of rows returned in a query MUST always match
-        // the row count reported by the rows() method.
-        //
-        // The row count is never affected by projection.
-        debug_assert_eq!(q.num_rows(), self.rows());
-
-        // Invariant: the timestamp min/max MUST match the values reported by
-        // timestamp_stats(), iff the projection contains the "time" column.
-        #[cfg(debug_assertions)]
-        {
-            if projection
-                .columns()
-                .map(|v| v.iter().any(|v| v == schema::TIME_COLUMN_NAME))
-                .unwrap_or(true)
-            {
-                assert_eq!(q.ts_min_max(), self.timestamp_stats());
-            } else {
-                // Otherwise the timestamp summary in the query response MUST be
-                // empty.
-                assert_eq!(q.ts_min_max(), None);
-            }
-        }
+        // The timestamp summary of the data is only present if the projection
+        // contains the "time" column.
+        let ts_min_max = projection
+            .columns()
+            .map(|v| v.iter().any(|v| v == schema::TIME_COLUMN_NAME))
+            .unwrap_or(true)
+            .then(|| self.timestamp_stats())
+            .flatten();
 
-        Some(q)
+        // Construct the pending query adaptor over the partition data.
+        //
+        // `data` MUST contain at least one row, or the QueryAdaptor constructor
+        // panics. This is upheld by the FSM, which ensures only non-empty
+        // snapshots / RecordBatch are generated. Because `data` contains at
+        // least one RecordBatch, this invariant holds.
+        Some(PendingQueryAdaptor::new(
+            self.partition_id.clone(),
+            data,
+            projection.clone(),
+            self.rows(),
+            ts_min_max,
+        ))
     }
 
     /// Snapshot and mark all buffered data as persisting.
@@ -372,6 +416,7 @@ impl PartitionData {
     /// necessary for a given persistence).
     pub(crate) fn mark_persisting(&mut self) -> Option<PersistingData> {
         let fsm = std::mem::take(&mut self.buffer).into_persisting()?;
+        self.buffered_since = None;
 
         // From this point on, all code MUST be infallible or the buffered data
         // contained within persisting may be dropped.
@@ -398,10 +443,9 @@ impl PartitionData {
 
         // Wrap the persisting data in the type wrapper
         let data = PersistingData::new(
-            QueryAdaptor::new(
-                self.partition_id.clone(),
-                fsm.get_query_data(&OwnedProjection::default()),
-            ),
+            self.partition_id.clone(),
+            fsm.spillable_data().cloned().collect(),
+            fsm.schema().expect("persisting batch must have schema"),
             batch_ident,
         );
 
@@ -462,6 +506,11 @@ impl PartitionData {
             // This partitioning from non-empty to empty.
             self.partition_counter.dec();
             self.is_empty = true;
+
+            // None of the series written to this partition remain buffered.
+            if let Some(series) = &self.series {
+                series.reset(&self.partition_key);
+            }
         }
 
         // Return the set of IDs this buffer contained.
@@ -477,6 +526,52 @@ impl PartitionData {
         self.completed_persistence_count
     }
 
+    /// Return the number of persist operations that have been started, but
+    /// not yet completed, for this [`PartitionData`] instance.
+    pub(crate) fn persisting_batches(&self) -> usize {
+        self.persisting.len()
+    }
+
+    /// Return the (possibly spilled) data of each persisting batch, and each
+    /// snapshot of buffered data in this partition.
+    pub(crate) fn spillable_data(&self) -> impl Iterator<Item = &SpillableBatches> + '_ {
+        self.persisting
+            .spillable_data()
+            .chain(self.buffer.spillable_data())
+    }
+
+    /// Move the data in the mutable buffer (if any) into an immutable
+    /// snapshot that may be spilled to disk, returning a reference to it.
+    ///
+    /// The snapshot remains buffered in this partition, and is persisted by
+    /// the next call to [`Self::mark_persisting()`].
+    pub(crate) fn snapshot_buffer(&mut self) -> Option<SpillableBatches> {
+        self.buffer.snapshot()
+    }
+
+    /// Record that the buffered data is about to be marked as persisting
+    /// because it exceeded the hot partition persist cost threshold.
+    pub(crate) fn record_hot_persist(&mut self) {
+        self.hot_persistence_count += 1;
+    }
+
+    /// Return the number of hot partition persist operations started for this
+    /// [`PartitionData`] instance.
+    pub(crate) fn hot_persistence_count(&self) -> u64 {
+        self.hot_persistence_count
+    }
+
+    /// Return the instant the oldest write that is buffered, and not yet
+    /// persisting, was received - or [`None`] if there is no such data.
+    pub(crate) fn buffered_since(&self) -> Option<Instant> {
+        self.buffered_since
+    }
+
+    /// Return the instant the most recent write was buffered, if any.
+    pub(crate) fn last_write_at(&self) -> Option<Instant> {
+        self.last_write_at
+    }
+
     /// Return the metadata of the table this [`PartitionData`] is buffering writes
     /// for.
     pub(crate) fn table(&self) -> &Arc<DeferredLoad<TableMetadata>> {
@@ -548,6 +643,8 @@ mod tests {
     use super::*;
     use crate::{
         buffer_tree::partition::resolver::SortKeyResolver,
+        query_adaptor::QueryAdaptor,
+        spill::SpillDir,
         test_util::{populate_catalog, PartitionDataBuilder, ARBITRARY_TRANSITION_PARTITION_ID},
     };
 
@@ -571,7 +668,10 @@ mod tests {
         {
             let data = p
                 .get_query_data(&OwnedProjection::default())
-                .expect("should return data");
+                .expect("should return data")
+                .read()
+                .await
+                .expect("read should succeed");
             assert_eq!(data.partition_id(), &*ARBITRARY_TRANSITION_PARTITION_ID);
 
             let expected = [
@@ -594,7 +694,10 @@ mod tests {
         {
             let data = p
                 .get_query_data(&OwnedProjection::default())
-                .expect("should contain data");
+                .expect("should contain data")
+                .read()
+                .await
+                .expect("read should succeed");
             assert_eq!(data.partition_id(), &*ARBITRARY_TRANSITION_PARTITION_ID);
 
             let expected = [
@@ -625,15 +728,29 @@ mod tests {
         // Ensure the batch ident hasn't been increased yet.
         assert_eq!(p.started_persistence_count.get(), 0);
         assert_eq!(p.completed_persistence_count, 0);
+        assert_eq!(p.persisting_batches(), 0);
+        assert!(p.buffered_since().is_some());
 
         // Begin persisting the partition.
         let persisting_data = p.mark_persisting().expect("must contain existing data");
+
+        // The buffer is now empty, with the data moved to the persisting list.
+        assert_eq!(p.persisting_batches(), 1);
+        assert!(p.buffered_since().is_none());
         // And validate the data being persisted.
         assert_eq!(
             persisting_data.partition_id(),
             &*ARBITRARY_TRANSITION_PARTITION_ID
         );
-        assert_eq!(persisting_data.record_batches().len(), 1);
+        assert_eq!(
+            persisting_data
+                .query_adaptor()
+                .await
+                .unwrap()
+                .record_batches()
+                .len(),
+            1
+        );
         let expected = [
             "+--------+--------+----------+--------------------------------+",
             "| city   | people | pigeons  | time                           |",
@@ -641,7 +758,14 @@ mod tests {
             "| London | 2.0    | millions | 1970-01-01T00:00:00.000000010Z |",
             "+--------+--------+----------+--------------------------------+",
         ];
-        assert_batches_eq!(expected, persisting_data.record_batches());
+        assert_batches_eq!(
+            expected,
+            persisting_data
+                .query_adaptor()
+                .await
+                .unwrap()
+                .record_batches()
+        );
 
         // Ensure the started batch ident is increased after a persist call, but not the completed
         // batch ident.
@@ -654,12 +778,16 @@ mod tests {
         let mb = lp_to_mutable_batch(r#"bananas,city=Madrid people=4,pigeons="none" 20"#).1;
         p.buffer_write(mb, SequenceNumber::new(2))
             .expect("write should succeed");
+        assert!(p.buffered_since().is_some());
 
         // Which must be readable, alongside the ongoing persist data.
         {
             let data = p
                 .get_query_data(&OwnedProjection::default())
-                .expect("must have data");
+                .expect("must have data")
+                .read()
+                .await
+                .expect("read should succeed");
             assert_eq!(data.partition_id(), &*ARBITRARY_TRANSITION_PARTITION_ID);
             assert_eq!(data.record_batches().len(), 2);
             let expected = [
@@ -682,12 +810,16 @@ mod tests {
         // completed count is increased.
         assert_eq!(p.started_persistence_count.get(), 1);
         assert_eq!(p.completed_persistence_count, 1);
+        assert_eq!(p.persisting_batches(), 0);
 
         // Querying the buffer should now return only the second write.
         {
             let data = p
                 .get_query_data(&OwnedProjection::default())
-                .expect("must have data");
+                .expect("must have data")
+                .read()
+                .await
+                .expect("read should succeed");
             assert_eq!(data.partition_id(), &*ARBITRARY_TRANSITION_PARTITION_ID);
             assert_eq!(data.record_batches().len(), 1);
             let expected = [
@@ -761,6 +893,9 @@ mod tests {
 
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -774,7 +909,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 1.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -785,6 +924,9 @@ mod tests {
 
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -798,7 +940,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 2.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -833,6 +979,9 @@ mod tests {
 
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -846,7 +995,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 3.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -880,6 +1033,9 @@ mod tests {
 
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -893,7 +1049,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 4.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -906,6 +1066,9 @@ mod tests {
         // And assert the correct value remains.
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -919,7 +1082,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 4.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -931,6 +1098,9 @@ mod tests {
         // And assert the correct value remains.
         assert_eq!(
             p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
                 .unwrap()
                 .record_batches()
                 .len(),
@@ -944,7 +1114,11 @@ mod tests {
                 "| 1970-01-01T00:00:00.000000042Z | 4.0 |",
                 "+--------------------------------+-----+",
             ],
-            p.get_query_data(&OwnedProjection::default()).unwrap(),
+            p.get_query_data(&OwnedProjection::default())
+                .unwrap()
+                .read()
+                .await
+                .unwrap(),
         )
         .await;
 
@@ -984,7 +1158,12 @@ mod tests {
         p.buffer_write(mb, SequenceNumber::new(3))
             .expect("write should succeed");
 
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1007,7 +1186,12 @@ mod tests {
         p.buffer_write(mb, SequenceNumber::new(4))
             .expect("write should succeed");
 
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1031,7 +1215,12 @@ mod tests {
         p.buffer_write(mb, SequenceNumber::new(5))
             .expect("write should succeed");
 
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1052,7 +1241,12 @@ mod tests {
         assert_eq!(set.len(), 1);
         assert!(set.contains(SequenceNumber::new(3)));
 
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1071,7 +1265,12 @@ mod tests {
         assert_eq!(set.len(), 1);
         assert!(set.contains(SequenceNumber::new(4)));
 
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1090,7 +1289,12 @@ mod tests {
         assert!(set.contains(SequenceNumber::new(1)));
 
         // Assert only the buffered data remains
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------------------------------+-----+",
@@ -1217,7 +1421,12 @@ mod tests {
         );
 
         // Nothing should explode, data should be readable.
-        let data = p.get_query_data(&OwnedProjection::default()).unwrap();
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .unwrap()
+            .read()
+            .await
+            .unwrap();
         assert_batches_eq!(
             [
                 "+--------+--------+----------+--------------------------------+",
@@ -1231,6 +1440,72 @@ mod tests {
         );
     }
 
+    /// Snapshots of the buffer taken to spill cold data remain queryable, and
+    /// are persisted along with the rest of the buffered data.
+    #[tokio::test]
+    async fn test_snapshot_buffer() {
+        let mut p = PartitionDataBuilder::new().build();
+
+        // An empty buffer has nothing to snapshot.
+        assert!(p.snapshot_buffer().is_none());
+        assert_eq!(p.spillable_data().count(), 0);
+
+        let mb = lp_to_mutable_batch(r#"bananas,city=London people=2 10"#).1;
+        p.buffer_write(mb, SequenceNumber::new(1))
+            .expect("write should succeed");
+
+        let snapshot = p.snapshot_buffer().expect("must snapshot buffered data");
+        assert_eq!(p.spillable_data().count(), 1);
+        assert!(p.snapshot_buffer().is_none());
+        assert!(p.buffered_since().is_some());
+        assert_eq!(p.rows(), 1);
+
+        // Spill the snapshot and continue writing to the buffer.
+        let tmp = tempfile::tempdir().unwrap();
+        snapshot
+            .spill(&Arc::new(SpillDir::new(tmp.path().to_path_buf(), u64::MAX)))
+            .expect("spill should succeed");
+
+        let mb = lp_to_mutable_batch(r#"bananas,city=Madrid people=4 20"#).1;
+        p.buffer_write(mb, SequenceNumber::new(2))
+            .expect("write should succeed");
+        assert_eq!(p.rows(), 2);
+        assert_eq!(
+            p.timestamp_stats(),
+            Some(TimestampMinMax { min: 10, max: 20 })
+        );
+
+        let expected = [
+            "+--------+--------+--------------------------------+",
+            "| city   | people | time                           |",
+            "+--------+--------+--------------------------------+",
+            "| London | 2.0    | 1970-01-01T00:00:00.000000010Z |",
+            "| Madrid | 4.0    | 1970-01-01T00:00:00.000000020Z |",
+            "+--------+--------+--------------------------------+",
+        ];
+        let data = p
+            .get_query_data(&OwnedProjection::default())
+            .expect("must have data")
+            .read()
+            .await
+            .expect("read should succeed");
+        assert_batches_eq!(expected, data.record_batches());
+
+        // Both the snapshot and the buffer are persisted together.
+        let persisting_data = p.mark_persisting().expect("must contain existing data");
+        assert!(p.buffered_since().is_none());
+        let data = persisting_data
+            .query_adaptor()
+            .await
+            .expect("read should succeed");
+        assert_batches_eq!(expected, data.record_batches());
+
+        let set = p.mark_persisted(persisting_data);
+        assert!(set.contains(SequenceNumber::new(1)));
+        assert!(set.contains(SequenceNumber::new(2)));
+        assert!(p.is_empty());
+    }
+
     #[tokio::test]
     async fn test_mark_persisting_no_data() {
         let mut p = PartitionDataBuilder::new().build();
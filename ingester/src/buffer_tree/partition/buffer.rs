@@ -1,4 +1,3 @@
-use arrow::record_batch::RecordBatch;
 use data_types::{SequenceNumber, TimestampMinMax};
 use mutable_batch::MutableBatch;
 
@@ -7,10 +6,13 @@ mod mutable_buffer;
 mod state_machine;
 pub(crate) mod traits;
 
-use schema::Schema;
+use schema::{merge::SchemaMerger, Schema};
 pub(crate) use state_machine::*;
 
-use crate::query::projection::OwnedProjection;
+use crate::{
+    query::projection::OwnedProjection,
+    spill::{PendingBatches, SpillableBatches},
+};
 
 use self::{always_some::AlwaysSome, traits::Queryable};
 
@@ -36,7 +38,16 @@ impl Default for FsmState {
 /// state transitions during reads and writes from the underlying buffer.
 #[derive(Debug, Default)]
 #[must_use = "DataBuffer should not be dropped unused"]
-pub(crate) struct DataBuffer(AlwaysSome<FsmState>);
+pub(crate) struct DataBuffer {
+    /// Immutable snapshots of previously buffered data, ordered oldest first.
+    ///
+    /// Snapshots are taken of cold buffers so that the data within them can be
+    /// spilled to disk, and are persisted along with the mutable buffer.
+    snapshots: Vec<BufferState<Snapshot>>,
+
+    /// The mutable buffer accepting writes.
+    fsm: AlwaysSome<FsmState>,
+}
 
 impl DataBuffer {
     /// Buffer the given [`MutableBatch`] in memory, ordered by the specified
@@ -47,7 +58,7 @@ impl DataBuffer {
         sequence_number: SequenceNumber,
     ) -> Result<(), mutable_batch::Error> {
         // Take ownership of the FSM and apply the write.
-        self.0.mutate(|fsm| match fsm {
+        self.fsm.mutate(|fsm| match fsm {
             // Mutable stats simply have the write applied.
             FsmState::Buffering(mut b) => {
                 let ret = b.write(mb, sequence_number);
@@ -56,62 +67,129 @@ impl DataBuffer {
         })
     }
 
+    /// Return the estimated cost of persisting the mutable buffer.
+    ///
+    /// Snapshots are not included, as they may have been spilled to disk.
     pub(crate) fn persist_cost_estimate(&self) -> usize {
-        match self.0.get() {
+        match self.fsm.get() {
             FsmState::Buffering(b) => b.persist_cost_estimate(),
         }
     }
 
     /// Return all data for this buffer, ordered by the [`SequenceNumber`] from
     /// which it was buffered with.
-    pub(crate) fn get_query_data(&mut self, projection: &OwnedProjection) -> Vec<RecordBatch> {
+    ///
+    /// Snapshots that have been spilled to disk are read when the returned
+    /// [`PendingBatches`] is read.
+    pub(crate) fn get_query_data(&mut self, projection: &OwnedProjection) -> PendingBatches {
+        let mut ret = PendingBatches::default();
+        for v in &self.snapshots {
+            ret.extend(v.get_query_data(projection));
+        }
+
         // Take ownership of the FSM and return the data within it.
-        self.0.mutate(|fsm| match fsm {
+        let data = self.fsm.mutate(|fsm| match fsm {
             // The buffering state can return data.
             FsmState::Buffering(b) => {
                 let ret = b.get_query_data(projection);
                 (FsmState::Buffering(b), ret)
             }
-        })
+        });
+        ret.extend(PendingBatches::from(data));
+
+        ret
+    }
+
+    /// Move the data in the mutable buffer (if any) into an immutable snapshot
+    /// that may be spilled to disk, returning a reference to the snapshot
+    /// data.
+    pub(crate) fn snapshot(&mut self) -> Option<SpillableBatches> {
+        let snapshot = self.fsm.mutate(|fsm| match fsm {
+            FsmState::Buffering(b) => match b.snapshot() {
+                Transition::Ok(v) => (FsmState::default(), Some(v)),
+                Transition::Unchanged(b) => (FsmState::Buffering(b), None),
+            },
+        })?;
+
+        let data = snapshot.spillable_data().clone();
+        self.snapshots.push(snapshot);
+
+        Some(data)
+    }
+
+    /// Return the (possibly spilled) snapshots of buffered data.
+    pub(crate) fn spillable_data(&self) -> impl Iterator<Item = &SpillableBatches> {
+        self.snapshots.iter().map(|v| v.spillable_data())
     }
 
     /// Return the row count for this buffer.
     pub(crate) fn rows(&self) -> usize {
-        match self.0.get() {
+        let rows = match self.fsm.get() {
             FsmState::Buffering(v) => v.rows(),
-        }
+        };
+        self.snapshots.iter().map(|v| v.rows()).sum::<usize>() + rows
     }
 
     /// Return the timestamp min/max values, if this buffer contains data.
     pub(crate) fn timestamp_stats(&self) -> Option<TimestampMinMax> {
-        match self.0.get() {
+        let buffer = match self.fsm.get() {
             FsmState::Buffering(v) => v.timestamp_stats(),
-        }
+        };
+
+        self.snapshots
+            .iter()
+            .filter_map(|v| v.timestamp_stats())
+            .chain(buffer)
+            .reduce(|a, b| TimestampMinMax {
+                min: a.min.min(b.min),
+                max: a.max.max(b.max),
+            })
     }
 
     /// Returns the [`Schema`] for the buffered data.
     pub(crate) fn schema(&self) -> Option<Schema> {
-        match self.0.get() {
+        let buffer = match self.fsm.get() {
             FsmState::Buffering(v) => v.schema(),
+        };
+
+        let mut schemas = self
+            .snapshots
+            .iter()
+            .filter_map(|v| v.schema())
+            .chain(buffer)
+            .peekable();
+        schemas.peek()?;
+
+        let mut merger = SchemaMerger::new();
+        for s in schemas {
+            merger = merger
+                .merge(&s)
+                .expect("schemas of buffered data must be compatible");
         }
+        Some(merger.build())
     }
 
     // Deconstruct the [`DataBuffer`] into the underlying FSM in a
     // [`Persisting`] state, if the buffer contains any data.
     pub(crate) fn into_persisting(self) -> Option<BufferState<Persisting>> {
-        let p = match self.0.into_inner() {
+        let Self { mut snapshots, fsm } = self;
+
+        match fsm.into_inner() {
             FsmState::Buffering(b) => {
                 // Attempt to snapshot the buffer to an immutable state.
                 match b.snapshot() {
-                    Transition::Ok(b) => b.into_persisting(),
+                    Transition::Ok(b) => snapshots.push(b),
                     Transition::Unchanged(_) => {
                         // The buffer contains no data.
-                        return None;
                     }
                 }
             }
         };
 
-        Some(p)
+        if snapshots.is_empty() {
+            return None;
+        }
+
+        Some(BufferState::<Persisting>::from_snapshots(snapshots))
     }
 }
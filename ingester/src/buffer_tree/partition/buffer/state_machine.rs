@@ -1,5 +1,4 @@
 #![allow(dead_code)]
-use arrow::record_batch::RecordBatch;
 use data_types::{sequence_number_set::SequenceNumberSet, SequenceNumber, TimestampMinMax};
 use mutable_batch::MutableBatch;
 
@@ -9,8 +8,7 @@ mod snapshot;
 
 pub(in crate::buffer_tree::partition::buffer) use buffering::*;
 pub(crate) use persisting::*;
-
-use crate::query::projection::OwnedProjection;
+pub(in crate::buffer_tree::partition::buffer) use snapshot::Snapshot;
 
 use super::traits::{Queryable, Writeable};
 
@@ -67,6 +65,8 @@ impl<A, B> Transition<A, B> {
 ///
 /// A [`BufferState`] tracks the bounding [`SequenceNumber`] values it has
 /// observed, and enforces monotonic writes (w.r.t their [`SequenceNumber`]).
+///
+/// [`RecordBatch`]: arrow::record_batch::RecordBatch
 #[derive(Debug)]
 pub(crate) struct BufferState<T> {
     state: T,
@@ -119,13 +119,6 @@ impl<T> Queryable for BufferState<T>
 where
     T: Queryable,
 {
-    /// Returns the current buffer data.
-    ///
-    /// This is always a cheap method call.
-    fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
-        self.state.get_query_data(projection)
-    }
-
     fn rows(&self) -> usize {
         self.state.rows()
     }
@@ -143,6 +136,7 @@ where
 mod tests {
     use std::sync::Arc;
 
+    use arrow::record_batch::RecordBatch;
     use arrow_util::assert_batches_eq;
     use assert_matches::assert_matches;
     use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
@@ -150,11 +144,19 @@ mod tests {
     use snapshot::*;
 
     use super::*;
+    use crate::spill::PendingBatches;
 
-    #[test]
+    /// Read the unprojected `data`, which is never spilled in these tests.
+    async fn read(data: PendingBatches) -> Vec<RecordBatch> {
+        data.read(&OwnedProjection::default())
+            .await
+            .expect("read should succeed")
+    }
+
+    #[tokio::test]
     // comparing dyn Array always has same vtable, so is accurate to use Arc::ptr_eq
     #[allow(clippy::vtable_address_comparisons)]
-    fn test_buffer_lifecycle() {
+    async fn test_buffer_lifecycle() {
         // Initialise a buffer in the base state.
         let mut buffer: BufferState<Buffering> = BufferState::new();
 
@@ -210,7 +212,7 @@ mod tests {
         };
 
         // Verify the writes are still queryable.
-        let w2_data = buffer.get_query_data(&OwnedProjection::default());
+        let w2_data = read(buffer.get_query_data(&OwnedProjection::default())).await;
         let expected = vec![
             "+-------+----------+----------+--------------------------------+",
             "| great | how_much | tag      | time                           |",
@@ -224,7 +226,7 @@ mod tests {
 
         // Ensure the same data is returned for a second read.
         {
-            let second_read = buffer.get_query_data(&OwnedProjection::default());
+            let second_read = read(buffer.get_query_data(&OwnedProjection::default())).await;
             assert_eq!(w2_data, second_read);
 
             // And that no data was actually copied.
@@ -239,7 +241,7 @@ mod tests {
         let buffer: BufferState<Persisting> = buffer.into_persisting();
 
         // Extract the final buffered result
-        let final_data = buffer.get_query_data(&OwnedProjection::default());
+        let final_data = read(buffer.get_query_data(&OwnedProjection::default())).await;
 
         // And once again verify no data was changed, copied or re-ordered.
         assert_eq!(w2_data, final_data);
@@ -257,10 +259,10 @@ mod tests {
     }
 
     /// Assert projection is correct across all the queryable FSM states.
-    #[test]
+    #[tokio::test]
     // comparing dyn Array always has same vtable, so is accurate to use Arc::ptr_eq
     #[allow(clippy::vtable_address_comparisons)]
-    fn test_buffer_projection() {
+    async fn test_buffer_projection() {
         let projection = OwnedProjection::from(vec![
             "tag".to_string(),
             "great".to_string(),
@@ -316,7 +318,11 @@ mod tests {
         };
 
         // Verify the writes are still queryable.
-        let w2_data = buffer.get_query_data(&projection);
+        let w2_data = buffer
+            .get_query_data(&projection)
+            .read(&projection)
+            .await
+            .expect("read should succeed");
         let expected = vec![
             "+----------+-------+--------------------------------+",
             "| tag      | great | time                           |",
@@ -330,7 +336,11 @@ mod tests {
 
         // Ensure the same data is returned for a second read.
         {
-            let second_read = buffer.get_query_data(&projection);
+            let second_read = buffer
+                .get_query_data(&projection)
+                .read(&projection)
+                .await
+                .expect("read should succeed");
             assert_eq!(w2_data, second_read);
 
             // And that no data was actually copied.
@@ -345,7 +355,11 @@ mod tests {
         let buffer: BufferState<Persisting> = buffer.into_persisting();
 
         // Extract the final buffered result
-        let final_data = buffer.get_query_data(&projection);
+        let final_data = buffer
+            .get_query_data(&projection)
+            .read(&projection)
+            .await
+            .expect("read should succeed");
 
         // And once again verify no data was changed, copied or re-ordered.
         assert_eq!(w2_data, final_data);
@@ -362,8 +376,8 @@ mod tests {
         assert_eq!(set.len(), 2);
     }
 
-    #[test]
-    fn test_snapshot_buffer_different_but_compatible_schemas() {
+    #[tokio::test]
+    async fn test_snapshot_buffer_different_but_compatible_schemas() {
         let mut buffer = BufferState::new();
 
         // Missing tag `t1`
@@ -379,9 +393,10 @@ mod tests {
             Transition::Unchanged(_) => panic!("failed to transition"),
         };
 
-        assert_eq!(buffer.get_query_data(&OwnedProjection::default()).len(), 1);
+        let data = read(buffer.get_query_data(&OwnedProjection::default())).await;
+        assert_eq!(data.len(), 1);
 
-        let snapshot = buffer.get_query_data(&OwnedProjection::default())[0].clone();
+        let snapshot = data[0].clone();
 
         // Generate the combined buffer from the original inputs to compare
         // against.
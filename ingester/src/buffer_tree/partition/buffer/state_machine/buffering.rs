@@ -24,8 +24,7 @@ pub(crate) struct Buffering {
     buffer: Buffer,
 }
 
-/// Implement on-demand querying of the buffered contents without storing the
-/// generated snapshot.
+/// Implement on-demand summary statistics of the buffered contents.
 ///
 /// In the future this [`Queryable`] should NOT be implemented for
 /// [`Buffering`], and instead snapshots should be incrementally generated and
@@ -34,16 +33,9 @@ pub(crate) struct Buffering {
 ///
 /// # Panics
 ///
-/// This method panics if converting the buffered data (if any) into an Arrow
-/// [`RecordBatch`] fails (a non-transient error).
+/// The [`Queryable::schema()`] method panics if converting the buffered data
+/// schema fails (a non-transient error).
 impl Queryable for Buffering {
-    fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
-        self.buffer
-            .buffer()
-            .map(|v| vec![projection.project_mutable_batches(v)])
-            .unwrap_or_default()
-    }
-
     fn rows(&self) -> usize {
         self.buffer.buffer().map(|v| v.rows()).unwrap_or_default()
     }
@@ -75,6 +67,23 @@ impl Writeable for Buffering {
 }
 
 impl BufferState<Buffering> {
+    /// Return the buffered data with `projection` applied.
+    ///
+    /// This converts the buffered data into an Arrow [`RecordBatch`] on
+    /// demand, without storing the generated snapshot.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if converting the buffered data (if any) into an
+    /// Arrow [`RecordBatch`] fails (a non-transient error).
+    pub(crate) fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
+        self.state
+            .buffer
+            .buffer()
+            .map(|v| vec![projection.project_mutable_batches(v)])
+            .unwrap_or_default()
+    }
+
     /// Attempt to generate a snapshot from the data in this buffer.
     ///
     /// This returns [`Transition::Unchanged`] if this buffer contains no data.
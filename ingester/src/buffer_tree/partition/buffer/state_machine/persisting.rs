@@ -1,22 +1,27 @@
 //! A writfield1 buffer, with one or more snapshots.
 
-use arrow::record_batch::RecordBatch;
 use data_types::{sequence_number_set::SequenceNumberSet, TimestampMinMax};
-use iox_query::util::compute_timenanosecond_min_max;
-use schema::{merge::merge_record_batch_schemas, Schema};
+use schema::{merge::SchemaMerger, Schema};
 
-use super::BufferState;
+use super::{snapshot::Snapshot, BufferState};
 use crate::{
-    buffer_tree::partition::buffer::traits::Queryable, query::projection::OwnedProjection,
+    buffer_tree::partition::buffer::traits::Queryable,
+    query::projection::OwnedProjection,
+    spill::{PendingBatches, SpillableBatches},
 };
 
 /// An immutable set of [`RecordBatch`] in the process of being persisted.
+///
+/// [`RecordBatch`]: arrow::record_batch::RecordBatch
 #[derive(Debug)]
 pub(crate) struct Persisting {
-    /// Snapshots generated from previous buffer contents to be persisted.
+    /// Snapshots generated from previous buffer contents to be persisted,
+    /// ordered oldest first.
+    ///
+    /// These may be spilled to local disk, and are read back when queried.
     ///
     /// INVARIANT: this array is always non-empty.
-    snapshots: Vec<RecordBatch>,
+    snapshots: Vec<SpillableBatches>,
 
     /// Statistics describing the data in snapshots.
     row_count: usize,
@@ -24,39 +29,7 @@ pub(crate) struct Persisting {
     schema: Schema,
 }
 
-impl Persisting {
-    pub(super) fn new(
-        snapshots: Vec<RecordBatch>,
-        row_count: usize,
-        timestamp_stats: TimestampMinMax,
-        schema: Schema,
-    ) -> Self {
-        // Invariant: the summary statistics provided must match the actual
-        // data.
-        debug_assert_eq!(
-            row_count,
-            snapshots.iter().map(|v| v.num_rows()).sum::<usize>()
-        );
-        debug_assert_eq!(
-            timestamp_stats,
-            compute_timenanosecond_min_max(snapshots.iter()).unwrap()
-        );
-        debug_assert_eq!(schema, merge_record_batch_schemas(&snapshots));
-
-        Self {
-            snapshots,
-            row_count,
-            timestamp_stats,
-            schema,
-        }
-    }
-}
-
 impl Queryable for Persisting {
-    fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
-        projection.project_record_batch(&self.snapshots)
-    }
-
     fn rows(&self) -> usize {
         self.row_count
     }
@@ -71,6 +44,63 @@ impl Queryable for Persisting {
 }
 
 impl BufferState<Persisting> {
+    /// Combine the (non-empty) set of `snapshots`, ordered oldest first, into
+    /// a single [`Persisting`] state.
+    pub(in crate::buffer_tree::partition::buffer) fn from_snapshots(
+        snapshots: Vec<BufferState<Snapshot>>,
+    ) -> Self {
+        assert!(!snapshots.is_empty());
+
+        let mut sequence_numbers = SequenceNumberSet::default();
+        let mut data = Vec::with_capacity(snapshots.len());
+        let mut row_count = 0;
+        let mut timestamp_stats: Option<TimestampMinMax> = None;
+        let mut schema = SchemaMerger::new();
+
+        for snapshot in snapshots {
+            sequence_numbers.add_set(&snapshot.sequence_numbers);
+
+            let (batches, rows, ts, s) = snapshot.state.into_parts();
+            data.push(batches);
+            row_count += rows;
+            timestamp_stats = Some(match timestamp_stats {
+                Some(v) => TimestampMinMax {
+                    min: v.min.min(ts.min),
+                    max: v.max.max(ts.max),
+                },
+                None => ts,
+            });
+            schema = schema
+                .merge(&s)
+                .expect("schemas of buffered snapshots must be compatible");
+        }
+
+        Self {
+            state: Persisting {
+                snapshots: data,
+                row_count,
+                timestamp_stats: timestamp_stats.expect("non-empty snapshots"),
+                schema: schema.build(),
+            },
+            sequence_numbers,
+        }
+    }
+
+    /// Return the data in this buffer with `projection` applied, deferring the
+    /// read of spilled data.
+    pub(crate) fn get_query_data(&self, projection: &OwnedProjection) -> PendingBatches {
+        let mut ret = PendingBatches::default();
+        for v in &self.state.snapshots {
+            ret.extend(v.get_query_data(projection));
+        }
+        ret
+    }
+
+    /// Return the (possibly spilled) snapshots in this buffer.
+    pub(crate) fn spillable_data(&self) -> impl Iterator<Item = &SpillableBatches> {
+        self.state.snapshots.iter()
+    }
+
     /// Consume `self` and all references to the buffered data, returning the owned
     /// [`SequenceNumberSet`] within it.
     pub(crate) fn into_sequence_number_set(self) -> SequenceNumberSet {
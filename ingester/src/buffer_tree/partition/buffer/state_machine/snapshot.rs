@@ -9,6 +9,7 @@ use super::BufferState;
 use crate::{
     buffer_tree::partition::buffer::{state_machine::persisting::Persisting, traits::Queryable},
     query::projection::OwnedProjection,
+    spill::{PendingBatches, SpillableBatches},
 };
 
 /// An immutable, queryable FSM state containing at least one buffer snapshot.
@@ -16,8 +17,10 @@ use crate::{
 pub(crate) struct Snapshot {
     /// Snapshots generated from previous buffer contents.
     ///
+    /// These may be spilled to local disk, and are read back when queried.
+    ///
     /// INVARIANT: this array is always non-empty.
-    snapshots: Vec<RecordBatch>,
+    snapshots: SpillableBatches,
 
     /// Statistics describing the data in snapshots.
     row_count: usize,
@@ -37,19 +40,26 @@ impl Snapshot {
         let schema = merge_record_batch_schemas(&snapshots);
 
         Self {
-            snapshots,
+            snapshots: SpillableBatches::new(snapshots),
             row_count,
             timestamp_stats,
             schema,
         }
     }
-}
 
-impl Queryable for Snapshot {
-    fn get_query_data(&self, projection: &OwnedProjection) -> Vec<RecordBatch> {
-        projection.project_record_batch(&self.snapshots)
+    /// Deconstruct this [`Snapshot`] into the (possibly spilled) data, and
+    /// the row count, timestamp range and schema describing it.
+    pub(super) fn into_parts(self) -> (SpillableBatches, usize, TimestampMinMax, Schema) {
+        (
+            self.snapshots,
+            self.row_count,
+            self.timestamp_stats,
+            self.schema,
+        )
     }
+}
 
+impl Queryable for Snapshot {
     fn rows(&self) -> usize {
         self.row_count
     }
@@ -64,16 +74,18 @@ impl Queryable for Snapshot {
 }
 
 impl BufferState<Snapshot> {
+    /// Return the snapshot data with `projection` applied, deferring the read
+    /// of spilled data.
+    pub(crate) fn get_query_data(&self, projection: &OwnedProjection) -> PendingBatches {
+        self.state.snapshots.get_query_data(projection)
+    }
+
+    /// Return the (possibly spilled) snapshots in this buffer.
+    pub(crate) fn spillable_data(&self) -> &SpillableBatches {
+        &self.state.snapshots
+    }
+
     pub(crate) fn into_persisting(self) -> BufferState<Persisting> {
-        assert!(!self.state.snapshots.is_empty());
-        BufferState {
-            state: Persisting::new(
-                self.state.snapshots,
-                self.state.row_count,
-                self.state.timestamp_stats,
-                self.state.schema,
-            ),
-            sequence_numbers: self.sequence_numbers,
-        }
+        BufferState::<Persisting>::from_snapshots(vec![self])
     }
 }
@@ -1,6 +1,13 @@
-use std::fmt::Display;
+use std::{fmt::Display, sync::Arc};
 
-use crate::query_adaptor::QueryAdaptor;
+use data_types::TransitionPartitionId;
+use schema::Schema;
+use tokio::sync::OwnedSemaphorePermit;
+
+use crate::{
+    query_adaptor::QueryAdaptor,
+    spill::{QueuePermit, SpillError, SpillableBatches},
+};
 
 /// An opaque, monotonic generational identifier of a buffer in a
 /// [`PartitionData`].
@@ -33,39 +40,68 @@ impl Display for BatchIdent {
     }
 }
 
-/// A type wrapper over [`QueryAdaptor`] that statically ensures only batches of
-/// data from [`PartitionData::mark_persisting()`] are given to
+/// A handle to the data from [`PartitionData::mark_persisting()`] that
+/// statically ensures only such data is given to
 /// [`PartitionData::mark_persisted()`].
 ///
+/// The data referenced by this handle may be spilled to local disk while it
+/// waits to be persisted, and is transparently read back when needed.
+///
 /// Cloning this type is relatively cheap.
 ///
 /// [`PartitionData::mark_persisting()`]: super::PartitionData::mark_persisting
 /// [`PartitionData::mark_persisted()`]: super::PartitionData::mark_persisted
 #[derive(Debug, Clone)]
 pub struct PersistingData {
-    data: QueryAdaptor,
+    partition_id: TransitionPartitionId,
+    schema: Schema,
+    data: Vec<SpillableBatches>,
     batch_ident: BatchIdent,
 }
 
 impl PersistingData {
-    pub(super) fn new(data: QueryAdaptor, batch_ident: BatchIdent) -> Self {
-        Self { data, batch_ident }
+    pub(super) fn new(
+        partition_id: TransitionPartitionId,
+        data: Vec<SpillableBatches>,
+        schema: Schema,
+        batch_ident: BatchIdent,
+    ) -> Self {
+        Self {
+            partition_id,
+            schema,
+            data,
+            batch_ident,
+        }
     }
 
     pub(super) fn batch_ident(&self) -> BatchIdent {
         self.batch_ident
     }
 
-    pub(crate) fn query_adaptor(&self) -> QueryAdaptor {
-        self.data.clone()
+    /// Return the ID of the partition this data belongs to.
+    pub(crate) fn partition_id(&self) -> &TransitionPartitionId {
+        &self.partition_id
     }
-}
 
-impl std::ops::Deref for PersistingData {
-    type Target = QueryAdaptor;
+    /// Return the merged schema of the persisting data.
+    pub(crate) fn schema(&self) -> &Schema {
+        &self.schema
+    }
+
+    /// Hold the persist queue `permit` until all of this data has been
+    /// spilled to disk, or the returned [`QueuePermit`] is dropped.
+    pub(crate) fn queue_permit(&self, permit: OwnedSemaphorePermit) -> Arc<QueuePermit> {
+        QueuePermit::new(permit, &self.data)
+    }
 
-    fn deref(&self) -> &Self::Target {
-        &self.data
+    /// Return the persisting data, reading it from disk if it has been
+    /// spilled.
+    pub(crate) async fn query_adaptor(&self) -> Result<QueryAdaptor, SpillError> {
+        let mut batches = Vec::new();
+        for v in &self.data {
+            batches.extend(v.read().await?);
+        }
+        Ok(QueryAdaptor::new(self.partition_id.clone(), batches))
     }
 }
 
@@ -1,10 +1,12 @@
 use std::collections::VecDeque;
 
-use arrow::record_batch::RecordBatch;
 use data_types::TimestampMinMax;
 use schema::{merge::SchemaMerger, Schema};
 
-use crate::query::projection::OwnedProjection;
+use crate::{
+    query::projection::OwnedProjection,
+    spill::{PendingBatches, SpillableBatches},
+};
 
 use super::{
     buffer::{traits::Queryable, BufferState, Persisting},
@@ -116,6 +118,11 @@ impl PersistingList {
         self.persisting.is_empty()
     }
 
+    /// Returns the number of persisting batches in this list.
+    pub(crate) fn len(&self) -> usize {
+        self.persisting.len()
+    }
+
     /// Returns the row count sum across all batches in this list.
     ///
     /// This is an `O(1)` operation.
@@ -137,17 +144,21 @@ impl PersistingList {
         self.cached.as_ref().map(|v| &v.schema)
     }
 
-    /// Returns the [`RecordBatch`] in this list, optionally applying the given
-    /// projection.
+    /// Returns the (possibly spilled) data of each batch in this list.
+    pub(crate) fn spillable_data(&self) -> impl Iterator<Item = &SpillableBatches> + '_ {
+        self.persisting.iter().flat_map(|(_, b)| b.spillable_data())
+    }
+
+    /// Returns the data in this list with the given projection applied,
+    /// deferring the read of spilled data.
     ///
     /// This is an `O(n)` operation.
-    pub(crate) fn get_query_data<'a, 'b: 'a>(
-        &'a self,
-        projection: &'b OwnedProjection,
-    ) -> impl Iterator<Item = RecordBatch> + 'a {
-        self.persisting
-            .iter()
-            .flat_map(move |(_, b)| b.get_query_data(projection))
+    pub(crate) fn get_query_data(&self, projection: &OwnedProjection) -> PendingBatches {
+        let mut ret = PendingBatches::default();
+        for (_, b) in &self.persisting {
+            ret.extend(b.get_query_data(projection));
+        }
+        ret
     }
 }
 
@@ -251,8 +262,8 @@ mod tests {
 
     /// Ensure the ordering of yielded batches matches that of the calls to
     /// push(), preserving batch ordering, and in turn, causal row ordering.
-    #[test]
-    fn test_batch_ordering() {
+    #[tokio::test]
+    async fn test_batch_ordering() {
         let mut list = PersistingList::default();
         let mut ident_oracle = BatchIdent::default();
 
@@ -276,7 +287,9 @@ mod tests {
         // Assert the row content
         let data = list
             .get_query_data(&OwnedProjection::default())
-            .collect::<Vec<_>>();
+            .read(&OwnedProjection::default())
+            .await
+            .expect("read should succeed");
         let expected = vec![
             "+-------+----------+--------------------------------+",
             "| great | tag      | time                           |",
@@ -303,7 +316,9 @@ mod tests {
         // Assert the row content
         let data = list
             .get_query_data(&OwnedProjection::default())
-            .collect::<Vec<_>>();
+            .read(&OwnedProjection::default())
+            .await
+            .expect("read should succeed");
         let expected = vec![
             "+------------+----------+--------------------------------+",
             "| great      | tag      | time                           |",
@@ -318,8 +333,8 @@ mod tests {
 
     /// Assert projection across batches works, and does not panic when given a
     /// missing column.
-    #[test]
-    fn test_projection() {
+    #[tokio::test]
+    async fn test_projection() {
         let mut list = PersistingList::default();
         let mut ident_oracle = BatchIdent::default();
 
@@ -347,9 +362,12 @@ mod tests {
         );
 
         // Assert the row content
+        let projection = OwnedProjection::from(vec!["time", "tag", "missing"]);
         let data = list
-            .get_query_data(&OwnedProjection::from(vec!["time", "tag", "missing"]))
-            .collect::<Vec<_>>();
+            .get_query_data(&projection)
+            .read(&projection)
+            .await
+            .expect("read should succeed");
         let expected = vec![
             "+--------------------------------+----------+",
             "| time                           | tag      |",
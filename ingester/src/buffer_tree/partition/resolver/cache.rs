@@ -2,7 +2,10 @@ use std::{collections::HashMap, sync::Arc, time::Duration};
 
 use async_trait::async_trait;
 use backoff::BackoffConfig;
-use data_types::{NamespaceId, Partition, PartitionKey, TableId, TransitionPartitionId};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, NamespaceId, Partition, PartitionKey,
+    TableId, TransitionPartitionId,
+};
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::debug;
 use parking_lot::Mutex;
@@ -171,6 +174,7 @@ where
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         // Use the cached PartitionKey instead of the caller's partition_key,
         // instead preferring to reuse the already-shared Arc<str> in the cache.
@@ -217,6 +221,7 @@ where
                 table_id,
                 table,
                 partition_counter,
+                partition_template,
             )
             .await
     }
@@ -273,6 +278,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 defer_table_metadata_1_sec(),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
@@ -317,6 +323,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 defer_table_metadata_1_sec(),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
@@ -375,6 +382,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 defer_table_metadata_1_sec(),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
@@ -411,6 +419,7 @@ mod tests {
                 other_table,
                 defer_table_metadata_1_sec(),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
@@ -5,7 +5,10 @@ use std::sync::Arc;
 
 use async_trait::async_trait;
 use backoff::{Backoff, BackoffConfig};
-use data_types::{Column, NamespaceId, Partition, PartitionKey, TableId};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, Column, NamespaceId, Partition,
+    PartitionKey, TableId,
+};
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::debug;
 use parking_lot::Mutex;
@@ -46,13 +49,23 @@ impl CatalogPartitionResolver {
         &self,
         partition_key: PartitionKey,
         table_id: TableId,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Result<Partition, iox_catalog::interface::Error> {
-        self.catalog
-            .repositories()
-            .await
-            .partitions()
-            .create_or_get(partition_key, table_id)
-            .await
+        let mut repos = self.catalog.repositories().await;
+        match partition_template {
+            Some(t) => {
+                repos
+                    .partitions()
+                    .create_or_get_with_template(partition_key, table_id, t)
+                    .await
+            }
+            None => {
+                repos
+                    .partitions()
+                    .create_or_get(partition_key, table_id)
+                    .await
+            }
+        }
     }
 
     async fn get_columns(
@@ -78,6 +91,7 @@ impl PartitionProvider for CatalogPartitionResolver {
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         debug!(
             %partition_key,
@@ -87,7 +101,7 @@ impl PartitionProvider for CatalogPartitionResolver {
         );
         let p = Backoff::new(&self.backoff_config)
             .retry_all_errors("resolve partition", || {
-                self.get(partition_key.clone(), table_id)
+                self.get(partition_key.clone(), table_id, partition_template.clone())
             })
             .await
             .expect("retry forever");
@@ -134,6 +148,7 @@ mod tests {
     use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
     use assert_matches::assert_matches;
+    use data_types::partition_template::{test_table_partition_override, TemplatePart};
     use iox_catalog::{
         partition_lookup,
         test_helpers::{arbitrary_namespace, arbitrary_table},
@@ -185,6 +200,7 @@ mod tests {
                     &metrics,
                 )),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
@@ -208,4 +224,67 @@ mod tests {
         assert_eq!(got.table_id, table_id);
         assert_eq!(got.partition_key, PartitionKey::from(PARTITION_KEY));
     }
+
+    /// A new partition records the template its key was derived from, rather
+    /// than the current template of the table.
+    #[tokio::test]
+    async fn test_resolver_records_write_template() {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(iox_catalog::mem::MemCatalog::new(Arc::clone(&metrics)));
+
+        let write_template = test_table_partition_override(vec![TemplatePart::TimeFormat("%Y")]);
+
+        let (namespace_id, table_id) = {
+            let mut repos = catalog.repositories().await;
+            let ns = arbitrary_namespace(&mut *repos, NAMESPACE_NAME).await;
+            let table = arbitrary_table(&mut *repos, TABLE_NAME, &ns).await;
+
+            // The table template changes after the write was partitioned.
+            repos
+                .tables()
+                .update_partition_template(
+                    table.id,
+                    test_table_partition_override(vec![TemplatePart::TimeFormat("%Y-%m")]),
+                )
+                .await
+                .unwrap();
+
+            (ns.id, table.id)
+        };
+
+        let resolver = CatalogPartitionResolver::new(Arc::clone(&catalog));
+        let got = resolver
+            .get_partition(
+                PartitionKey::from("2023"),
+                namespace_id,
+                Arc::new(DeferredLoad::new(
+                    Duration::from_secs(1),
+                    async { NamespaceName::from(NAMESPACE_NAME) },
+                    &metrics,
+                )),
+                table_id,
+                Arc::new(DeferredLoad::new(
+                    Duration::from_secs(1),
+                    async {
+                        TableMetadata::new_for_testing(
+                            TableName::from(TABLE_NAME),
+                            Default::default(),
+                        )
+                    },
+                    &metrics,
+                )),
+                Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                Some(write_template.clone()),
+            )
+            .await;
+
+        let mut repos = catalog.repositories().await;
+        let id = got.lock().partition_id.clone();
+        let got = partition_lookup(repos.as_mut(), &id)
+            .await
+            .unwrap()
+            .expect("partition not created");
+        assert_eq!(got.partition_template, write_template);
+    }
 }
@@ -7,7 +7,9 @@ use std::{
 };
 
 use async_trait::async_trait;
-use data_types::{NamespaceId, PartitionKey, TableId};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, NamespaceId, PartitionKey, TableId,
+};
 use futures::{future::Shared, FutureExt};
 use hashbrown::{hash_map::Entry, HashMap};
 use parking_lot::Mutex;
@@ -150,6 +152,7 @@ where
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         let key = Key {
             table_id,
@@ -174,6 +177,7 @@ where
                     table_id,
                     table,
                     partition_counter,
+                    partition_template,
                 ));
 
                 // Make the future poll-able by many callers, all of which
@@ -238,6 +242,7 @@ async fn do_fetch<T>(
     table_id: TableId,
     table: Arc<DeferredLoad<TableMetadata>>,
     partition_counter: Arc<PartitionCounter>,
+    partition_template: Option<TablePartitionTemplateOverride>,
 ) -> Arc<Mutex<PartitionData>>
 where
     T: PartitionProvider + 'static,
@@ -259,6 +264,7 @@ where
                 table_id,
                 table,
                 partition_counter,
+                partition_template,
             )
             .await
     })
@@ -314,6 +320,7 @@ mod tests {
                     ARBITRARY_TABLE_ID,
                     defer_table_metadata_1_sec(),
                     Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                    None,
                 )
             })
             .collect::<FuturesUnordered<_>>()
@@ -392,6 +399,7 @@ mod tests {
             ARBITRARY_TABLE_ID,
             Arc::clone(&table_loader),
             Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+            None,
         );
         let pa_2 = layer.get_partition(
             ARBITRARY_PARTITION_KEY.clone(),
@@ -400,6 +408,7 @@ mod tests {
             ARBITRARY_TABLE_ID,
             Arc::clone(&table_loader),
             Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+            None,
         );
 
         let waker = futures::task::noop_waker();
@@ -421,6 +430,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 table_loader,
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .with_timeout_panic(Duration::from_secs(5))
             .await;
@@ -452,6 +462,7 @@ mod tests {
             _table_id: TableId,
             _table: Arc<DeferredLoad<TableMetadata>>,
             _partition_counter: Arc<PartitionCounter>,
+            _partition_template: Option<TablePartitionTemplateOverride>,
         ) -> Arc<Mutex<PartitionData>> {
             let waker = self.wait.notified();
             let permit = self.sem.acquire().await.unwrap();
@@ -493,6 +504,7 @@ mod tests {
             ARBITRARY_TABLE_ID,
             defer_table_metadata_1_sec(),
             Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+            None,
         );
 
         let waker = futures::task::noop_waker();
@@ -3,7 +3,9 @@
 use std::{collections::HashMap, sync::Arc};
 
 use async_trait::async_trait;
-use data_types::{NamespaceId, PartitionKey, TableId};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, NamespaceId, PartitionKey, TableId,
+};
 use parking_lot::Mutex;
 
 use super::r#trait::PartitionProvider;
@@ -60,6 +62,7 @@ impl PartitionProvider for MockPartitionProvider {
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        _partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         let p = self
             .partitions
@@ -4,7 +4,8 @@ use async_trait::async_trait;
 use backoff::BackoffConfig;
 use bloom2::CompressedBitmap;
 use data_types::{
-    NamespaceId, Partition, PartitionHashId, PartitionKey, TableId, TransitionPartitionId,
+    partition_template::TablePartitionTemplateOverride, NamespaceId, Partition, PartitionHashId,
+    PartitionKey, TableId, TransitionPartitionId,
 };
 use iox_catalog::interface::Catalog;
 use observability_deps::tracing::{debug, info};
@@ -151,6 +152,7 @@ where
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         let hash_id = PartitionHashId::new(table_id, &partition_key);
 
@@ -213,6 +215,7 @@ where
                 table_id,
                 table,
                 partition_counter,
+                partition_template,
             )
             .await
     }
@@ -260,6 +263,7 @@ mod tests {
             table_id: TableId,
             _table: Arc<DeferredLoad<TableMetadata>>,
             _partition_counter: Arc<PartitionCounter>,
+            _partition_template: Option<TablePartitionTemplateOverride>,
         ) -> Arc<Mutex<PartitionData>> {
             let mut builder = PartitionDataBuilder::default();
 
@@ -319,6 +323,7 @@ mod tests {
                 Some(vec![]),
                 Default::default(),
                 None,
+                Default::default(),
             )
         }
     }
@@ -380,6 +385,7 @@ mod tests {
                     p.table_id,
                     defer_table_metadata_1_sec(),
                     Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                    None,
                 ));
 
                 let got_id = got.lock().partition_id().clone();
@@ -404,6 +410,7 @@ mod tests {
             Some(vec![]),
             Default::default(),
             None,
+            Default::default(),
         );
         let want_id = p.transition_partition_id().clone();
 
@@ -431,6 +438,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 defer_table_metadata_1_sec(),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
 
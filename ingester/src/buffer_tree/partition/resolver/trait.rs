@@ -1,7 +1,9 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
-use data_types::{NamespaceId, PartitionKey, TableId};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, NamespaceId, PartitionKey, TableId,
+};
 use parking_lot::Mutex;
 
 use crate::{
@@ -20,6 +22,10 @@ pub(crate) trait PartitionProvider: Send + Sync + Debug {
     /// Return an initialised [`PartitionData`] for a given `(partition_key,
     /// table_id)` tuple.
     ///
+    /// If the partition does not exist, it is created recording
+    /// `partition_template` as the template its key was derived from, or the
+    /// current template of the table if [`None`].
+    ///
     /// NOTE: the constructor for [`PartitionData`] is NOT `pub` and SHOULD NOT
     /// be `pub` so this trait is effectively sealed.
     async fn get_partition(
@@ -30,6 +36,7 @@ pub(crate) trait PartitionProvider: Send + Sync + Debug {
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>>;
 }
 
@@ -46,6 +53,7 @@ where
         table_id: TableId,
         table: Arc<DeferredLoad<TableMetadata>>,
         partition_counter: Arc<PartitionCounter>,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Arc<Mutex<PartitionData>> {
         (**self)
             .get_partition(
@@ -55,6 +63,7 @@ where
                 table_id,
                 table,
                 partition_counter,
+                partition_template,
             )
             .await
     }
@@ -93,6 +102,7 @@ mod tests {
                 ARBITRARY_TABLE_ID,
                 Arc::clone(&table_loader),
                 Arc::new(PartitionCounter::new(NonZeroUsize::new(1).unwrap())),
+                None,
             )
             .await;
         assert_eq!(
@@ -31,6 +31,9 @@ pub enum BufferWriteError {
     #[error("namespace reached buffered partition limit ({count} partitions at once)")]
     PartitionLimit { count: usize },
 
+    #[error("table reached series limit ({count} of {limit} series)")]
+    SeriesLimit { count: usize, limit: usize },
+
     #[error(transparent)]
     Write(#[from] mutable_batch::Error),
 }
@@ -98,6 +101,9 @@ pub(crate) struct BufferTree<O> {
     /// The maximum number of partitions that may be buffered for a single
     /// namespace at any time.
     max_partitions_per_namespace: NonZeroUsize,
+    /// The maximum number of distinct series that may be buffered for a single
+    /// table, if limited.
+    max_series_per_table: Option<NonZeroUsize>,
 
     /// A set of namespaces this [`BufferTree`] instance has processed
     /// [`IngestOp`]'s for.
@@ -133,6 +139,7 @@ where
         table_resolver: Arc<dyn TableProvider>,
         partition_provider: Arc<dyn PartitionProvider>,
         max_partitions_per_namespace: NonZeroUsize,
+        max_series_per_table: Option<NonZeroUsize>,
         post_write_observer: Arc<O>,
         metrics: Arc<metric::Registry>,
     ) -> Self {
@@ -150,6 +157,7 @@ where
             metrics,
             partition_provider,
             max_partitions_per_namespace,
+            max_series_per_table,
             post_write_observer,
             namespace_count,
         }
@@ -201,6 +209,7 @@ where
                 Arc::clone(&self.table_resolver),
                 Arc::clone(&self.partition_provider),
                 PartitionCounter::new(self.max_partitions_per_namespace),
+                self.max_series_per_table,
                 Arc::clone(&self.post_write_observer),
                 &self.metrics,
             ))
@@ -262,7 +271,7 @@ mod tests {
         prelude::{col, lit},
         scalar::ScalarValue,
     };
-    use futures::StreamExt;
+    use futures::{StreamExt, TryStreamExt};
     use lazy_static::lazy_static;
     use metric::{Attributes, Metric};
     use predicate::Predicate;
@@ -320,6 +329,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             PartitionCounter::new(NonZeroUsize::new(42).unwrap()),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             &metrics,
         );
@@ -418,6 +428,7 @@ mod tests {
                         table_provider,
                         partition_provider,
                         NonZeroUsize::new(partition_count_limit).unwrap(),
+                        None,
                         Arc::new(MockPostWriteObserver::default()),
                         Arc::new(metric::Registry::default()),
                     );
@@ -447,7 +458,12 @@ mod tests {
                         .await
                         .expect("query should succeed")
                         .into_partition_stream()
-                        .flat_map(|ps| futures::stream::iter(ps.into_record_batches()))
+                        .flat_map(|ps| {
+                            futures::stream::iter(
+                                ps.expect("partition read should succeed")
+                                    .into_record_batches(),
+                            )
+                        })
                         .collect::<Vec<_>>()
                         .await;
 
@@ -843,6 +859,7 @@ mod tests {
             table_provider,
             partition_provider,
             NonZeroUsize::new(1).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -958,6 +975,7 @@ mod tests {
             table_provider,
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -1045,6 +1063,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::clone(&metrics),
         );
@@ -1135,6 +1154,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::clone(&Arc::new(metric::Registry::default())),
         );
@@ -1222,6 +1242,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -1320,6 +1341,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -1393,7 +1415,10 @@ mod tests {
         // Under the specified query consistency guarantees, both the first and
         // third writes (both to the arbitrary partition) should be visible. The second write to
         // partition2 should not be visible.
-        let mut partitions: Vec<PartitionResponse> = stream.collect().await;
+        let mut partitions: Vec<PartitionResponse> = stream
+            .try_collect()
+            .await
+            .expect("partition read should succeed");
         assert_eq!(partitions.len(), 1); // only p1, not p2
         let partition = partitions.pop().unwrap();
 
@@ -1435,6 +1460,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             partition_provider,
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -1466,7 +1492,10 @@ mod tests {
             .expect("query should succeed")
             .into_partition_stream();
 
-        let mut partitions: Vec<PartitionResponse> = stream.collect().await;
+        let mut partitions: Vec<PartitionResponse> = stream
+            .try_collect()
+            .await
+            .expect("partition read should succeed");
         let partition = partitions.pop().unwrap();
 
         // Ensure the partition hash ID is NOT sent.
@@ -2,6 +2,7 @@
 
 pub(crate) mod metadata;
 pub(crate) mod metadata_resolver;
+pub(crate) mod series;
 
 use std::{collections::HashMap, fmt::Debug, sync::Arc};
 
@@ -11,6 +12,7 @@ use data_types::{
     NamespaceId, PartitionKey, SequenceNumber, TableId,
 };
 use datafusion::{prelude::Expr, scalar::ScalarValue};
+use futures::StreamExt;
 use iox_query::{
     chunk_statistics::{create_chunk_statistics, ColumnRange},
     pruning::prune_summaries,
@@ -21,7 +23,7 @@ use parking_lot::Mutex;
 use predicate::Predicate;
 use trace::span::{Span, SpanRecorder};
 
-use self::metadata::TableMetadata;
+use self::{metadata::TableMetadata, series::SeriesTracker};
 
 use super::{
     namespace::NamespaceName,
@@ -63,6 +65,10 @@ pub(crate) struct TableData<O> {
     /// consistent when enforced.
     partition_count: Arc<PartitionCounter>,
 
+    /// An approximate count of the distinct series buffered for this table,
+    /// enforcing the series limit, if one is configured.
+    series: Option<Arc<SeriesTracker>>,
+
     post_write_observer: Arc<O>,
 }
 
@@ -79,6 +85,7 @@ impl<O> TableData<O> {
         namespace_name: Arc<DeferredLoad<NamespaceName>>,
         partition_provider: Arc<dyn PartitionProvider>,
         partition_count: Arc<PartitionCounter>,
+        series: Option<SeriesTracker>,
         post_write_observer: Arc<O>,
     ) -> Self {
         Self {
@@ -89,6 +96,7 @@ impl<O> TableData<O> {
             partition_data: Default::default(),
             partition_provider,
             partition_count,
+            series: series.map(Arc::new),
             post_write_observer,
         }
     }
@@ -124,6 +132,13 @@ impl<O> TableData<O> {
     pub(crate) fn namespace_id(&self) -> NamespaceId {
         self.namespace_id
     }
+
+    /// Return the approximate number of distinct series buffered for this
+    /// table, if a series limit is configured.
+    #[cfg(test)]
+    pub(crate) fn series_count(&self) -> Option<usize> {
+        self.series.as_ref().map(|v| v.count())
+    }
 }
 
 impl<O> TableData<O>
@@ -132,12 +147,20 @@ where
 {
     // buffers the table write and returns true if the lifecycle manager indicates that
     // ingest should be paused.
+    //
+    // `partition_template` is the template `partition_key` was derived from, if
+    // known, and is recorded against the partition should it be created.
     pub(super) async fn buffer_table_write(
         &self,
         sequence_number: SequenceNumber,
         batch: MutableBatch,
         partition_key: PartitionKey,
+        partition_template: Option<TablePartitionTemplateOverride>,
     ) -> Result<(), BufferWriteError> {
+        // Hash the series outside of the partition lock, only if they are
+        // needed to enforce a series limit.
+        let series_hashes = self.series.as_ref().map(|_| batch.series_hashes());
+
         let p = self.partition_data.get(&partition_key);
         let partition_data = match p {
             Some(p) => p,
@@ -159,22 +182,48 @@ where
                         self.table_id,
                         Arc::clone(&self.catalog_table),
                         Arc::clone(&self.partition_count),
+                        partition_template,
                     )
                     .await;
 
-                // Add the partition to the map.
+                // Add the partition to the map, attaching the series tracker
+                // before it becomes visible to other writers.
                 //
                 // This MAY return a different instance than `p` if another
                 // thread has already initialised the partition.
-                self.partition_data.get_or_insert_with(&partition_key, || p)
+                self.partition_data.get_or_insert_with(&partition_key, || {
+                    if let Some(series) = &self.series {
+                        p.lock().set_series_tracker(Arc::clone(series));
+                    }
+                    p
+                })
             }
         };
 
         // Obtain the partition lock.
         let mut p = partition_data.lock();
 
+        // Reject the write if it would create series beyond the configured
+        // limit for this table.
+        //
+        // This is done while holding the partition lock so the series cannot
+        // be reset by a concurrent persist completing before the write is
+        // buffered.
+        if let (Some(series), Some(hashes)) = (&self.series, series_hashes) {
+            series.observe(&partition_key, &hashes)?;
+        }
+
         // Enqueue the write, returning any error.
-        p.buffer_write(batch, sequence_number)?;
+        if let Err(e) = p.buffer_write(batch, sequence_number) {
+            // The series of a rejected write must not be retained by a
+            // partition that will never be persisted to reset them.
+            if p.is_empty() {
+                if let Some(series) = &self.series {
+                    series.reset(&partition_key);
+                }
+            }
+            return Err(e);
+        }
 
         // If successful, allow the observer to inspect the partition.
         self.post_write_observer
@@ -205,16 +254,22 @@ where
             "buffer tree index inconsistency"
         );
 
-        let table_partition_template = self.catalog_table.get().await.partition_template().clone();
-        let filters = predicate
-            .map(|p| p.filter_expr().into_iter().collect::<Vec<_>>())
-            .unwrap_or_default();
+        let table_partition_template =
+            Arc::new(self.catalog_table.get().await.partition_template().clone());
+        let filters = Arc::new(
+            predicate
+                .map(|p| p.filter_expr().into_iter().collect::<Vec<_>>())
+                .unwrap_or_default(),
+        );
 
         // Gather the partition data from all of the partitions in this table.
         let span = SpanRecorder::new(span);
-        let partitions = self.partitions().into_iter().filter_map(move |p| {
+        let partitions = futures::stream::iter(self.partitions()).filter_map(move |p| {
             let mut span = span.child("partition read");
 
+            // Obtain the partition data while holding the partition lock,
+            // deferring the read of any data spilled to disk until the lock
+            // is released.
             let (id, completed_persistence_count, data, partition_key) = {
                 let mut p = p.lock();
                 (
@@ -225,51 +280,68 @@ where
                 )
             };
 
-            let ret = match data {
-                Some(data) => {
-                    assert_eq!(&id, data.partition_id());
-
-                    // Potentially prune out this partition if the partition
-                    // template & derived partition key can be used to match
-                    // against the filters.
-                    if !keep_after_pruning_partition_key(
-                        &table_partition_template,
-                        &partition_key,
-                        &filters,
-                        &data,
-                    ) {
-                        // This partition will never contain any data that would
-                        // form part of the query response.
-                        //
-                        // Because this is true of buffered data, it is also
-                        // true of the persisted data, and therefore sending the
-                        // persisted file count metadata is useless because the
-                        // querier would never utilise the persisted files as
-                        // part of this query.
-                        //
-                        // This avoids sending O(n) metadata frames for queries
-                        // that may only touch one or two actual frames. The N
-                        // partition count grows over the lifetime of the
-                        // ingester as more partitions are created, and while
-                        // fast to serialise individually, the sequentially-sent
-                        // N metadata frames add up.
-                        return None;
+            let table_partition_template = Arc::clone(&table_partition_template);
+            let filters = Arc::clone(&filters);
+
+            async move {
+                let data = match data {
+                    Some(data) => {
+                        assert_eq!(&id, data.partition_id());
+                        match data.read().await {
+                            Ok(v) => v,
+                            Err(e) => {
+                                span.error("failed to read spilled partition data");
+                                return Some(Err(e));
+                            }
+                        }
                     }
+                    None => {
+                        span.ok("read partition data");
+                        return Some(Ok(PartitionResponse::new(
+                            vec![],
+                            id,
+                            completed_persistence_count,
+                        )));
+                    }
+                };
 
-                    PartitionResponse::new(
-                        data.into_record_batches(),
-                        id,
-                        completed_persistence_count,
-                    )
+                // Potentially prune out this partition if the partition
+                // template & derived partition key can be used to match
+                // against the filters.
+                if !keep_after_pruning_partition_key(
+                    &table_partition_template,
+                    &partition_key,
+                    &filters,
+                    &data,
+                ) {
+                    // This partition will never contain any data that would
+                    // form part of the query response.
+                    //
+                    // Because this is true of buffered data, it is also
+                    // true of the persisted data, and therefore sending the
+                    // persisted file count metadata is useless because the
+                    // querier would never utilise the persisted files as
+                    // part of this query.
+                    //
+                    // This avoids sending O(n) metadata frames for queries
+                    // that may only touch one or two actual frames. The N
+                    // partition count grows over the lifetime of the
+                    // ingester as more partitions are created, and while
+                    // fast to serialise individually, the sequentially-sent
+                    // N metadata frames add up.
+                    return None;
                 }
-                None => PartitionResponse::new(vec![], id, completed_persistence_count),
-            };
 
-            span.ok("read partition data");
-            Some(ret)
+                span.ok("read partition data");
+                Some(Ok(PartitionResponse::new(
+                    data.into_record_batches(),
+                    id,
+                    completed_persistence_count,
+                )))
+            }
         });
 
-        Ok(PartitionStream::new(futures::stream::iter(partitions)))
+        Ok(PartitionStream::new(partitions))
     }
 }
 
@@ -341,7 +413,7 @@ fn keep_after_pruning_partition_key(
                             max_value,
                         }
                     }
-                    ColumnValue::Datetime { .. } => {
+                    ColumnValue::Datetime { .. } | ColumnValue::Bucket { .. } => {
                         // not yet supported
                         return None;
                     }
@@ -412,6 +484,7 @@ mod tests {
             defer_namespace_name_1_sec(),
             partition_provider,
             Arc::clone(&partition_counter),
+            None,
             Arc::new(MockPostWriteObserver::default()),
         );
 
@@ -432,6 +505,7 @@ mod tests {
                 SequenceNumber::new(42),
                 batch,
                 ARBITRARY_PARTITION_KEY.clone(),
+                None,
             )
             .await
             .expect("buffer op should succeed");
@@ -464,6 +538,7 @@ mod tests {
             defer_namespace_name_1_sec(),
             partition_provider,
             Arc::clone(&partition_counter),
+            None,
             Arc::new(MockPostWriteObserver::default()),
         );
 
@@ -481,6 +556,7 @@ mod tests {
                 SequenceNumber::new(42),
                 batch,
                 ARBITRARY_PARTITION_KEY.clone(),
+                None,
             )
             .await
             .expect_err("buffer op should hit partition limit");
@@ -493,4 +569,85 @@ mod tests {
         // The partition counter should be unchanged
         assert_eq!(partition_counter.read(), N);
     }
+
+    /// Ensure the series limit is respected, and that writes to existing
+    /// series are accepted once the limit is reached.
+    #[tokio::test]
+    async fn test_series_limit() {
+        let partition_provider =
+            Arc::new(MockPartitionProvider::default().with_partition(PartitionDataBuilder::new()));
+
+        let table = TableData::new(
+            ARBITRARY_TABLE_ID,
+            defer_table_metadata_1_sec(),
+            ARBITRARY_NAMESPACE_ID,
+            defer_namespace_name_1_sec(),
+            partition_provider,
+            Arc::new(PartitionCounter::new(NonZeroUsize::new(42).unwrap())),
+            Some(SeriesTracker::new(NonZeroUsize::new(2).unwrap())),
+            Arc::new(MockPostWriteObserver::default()),
+        );
+
+        let batch = |lp: &str| {
+            lines_to_batches(&format!("{}{}", &*ARBITRARY_TABLE_NAME, lp), 0)
+                .unwrap()
+                .remove(&***ARBITRARY_TABLE_NAME)
+                .unwrap()
+        };
+
+        table
+            .buffer_table_write(
+                SequenceNumber::new(1),
+                batch(",bat=man value=24 42\n,bat=woman value=24 42"),
+                ARBITRARY_PARTITION_KEY.clone(),
+                None,
+            )
+            .await
+            .expect("buffer op should succeed");
+
+        let err = table
+            .buffer_table_write(
+                SequenceNumber::new(2),
+                batch(",bat=man value=24 42\n,bat=child value=24 42"),
+                ARBITRARY_PARTITION_KEY.clone(),
+                None,
+            )
+            .await
+            .expect_err("buffer op should hit series limit");
+
+        assert_matches!(err, BufferWriteError::SeriesLimit { count: 2, limit: 2 });
+
+        // Writes to existing series are still accepted.
+        table
+            .buffer_table_write(
+                SequenceNumber::new(3),
+                batch(",bat=woman value=42 43"),
+                ARBITRARY_PARTITION_KEY.clone(),
+                None,
+            )
+            .await
+            .expect("buffer op should succeed");
+
+        assert_eq!(table.series_count(), Some(2));
+
+        // Once the buffered data is persisted, its series no longer count
+        // towards the limit.
+        let partition = table.partitions().pop().unwrap();
+        let data = partition.lock().mark_persisting().unwrap();
+        partition.lock().mark_persisted(data);
+        assert_eq!(table.series_count(), Some(0));
+
+        table
+            .buffer_table_write(
+                SequenceNumber::new(4),
+                batch(",bat=child value=42 44"),
+                ARBITRARY_PARTITION_KEY.clone(),
+                None,
+            )
+            .await
+            .expect("buffer op should succeed");
+
+        assert_eq!(table.series_count(), Some(1));
+        assert_eq!(partition.lock().series_count(), Some(1));
+    }
 }
@@ -0,0 +1,182 @@
+use std::num::NonZeroUsize;
+
+use data_types::PartitionKey;
+use hashbrown::{HashMap, HashSet};
+use parking_lot::Mutex;
+
+use crate::buffer_tree::BufferWriteError;
+
+#[derive(Debug, Default)]
+struct State {
+    /// The series hashes buffered for each partition of the table.
+    partitions: HashMap<PartitionKey, HashSet<u64>>,
+    /// The sum of the lengths of the sets in `partitions`.
+    count: usize,
+}
+
+/// An approximate count of the distinct series (tag sets) buffered for a
+/// table, applying a configured limit.
+///
+/// Series are identified by the hash returned by
+/// [`MutableBatch::series_hashes()`], so the count is approximate in the
+/// presence of hash collisions. Series are tracked per partition, and a series
+/// written to more than one partition of the table is counted once for each.
+///
+/// The series of a partition are forgotten once all of its data has been
+/// persisted (see [`SeriesTracker::reset()`]).
+///
+/// [`MutableBatch::series_hashes()`]: mutable_batch::MutableBatch::series_hashes
+#[derive(Debug)]
+pub(crate) struct SeriesTracker {
+    state: Mutex<State>,
+    max: usize,
+}
+
+impl SeriesTracker {
+    pub(crate) fn new(max: NonZeroUsize) -> Self {
+        Self {
+            state: Default::default(),
+            max: max.get(),
+        }
+    }
+
+    /// Record the series identified by `hashes` as buffered in the partition
+    /// identified by `partition_key`.
+    ///
+    /// Returns [`BufferWriteError::SeriesLimit`] without recording any of
+    /// `hashes` if doing so would cause the number of distinct series to
+    /// exceed the configured limit.
+    pub(crate) fn observe(
+        &self,
+        partition_key: &PartitionKey,
+        hashes: &[u64],
+    ) -> Result<(), BufferWriteError> {
+        let mut state = self.state.lock();
+
+        let seen = state.partitions.get(partition_key);
+        let new = hashes
+            .iter()
+            .filter(|h| !seen.map(|s| s.contains(*h)).unwrap_or_default())
+            .copied()
+            .collect::<HashSet<_>>();
+
+        if new.is_empty() {
+            return Ok(());
+        }
+
+        if state.count.saturating_add(new.len()) > self.max {
+            return Err(BufferWriteError::SeriesLimit {
+                count: state.count,
+                limit: self.max,
+            });
+        }
+
+        state.count += new.len();
+        state
+            .partitions
+            .entry(partition_key.clone())
+            .or_default()
+            .extend(new);
+
+        Ok(())
+    }
+
+    /// Forget the series recorded for the partition identified by
+    /// `partition_key`.
+    ///
+    /// Called once all the data buffered for the partition has been persisted.
+    pub(crate) fn reset(&self, partition_key: &PartitionKey) {
+        let mut state = self.state.lock();
+        if let Some(seen) = state.partitions.remove(partition_key) {
+            state.count -= seen.len();
+        }
+    }
+
+    /// Read the approximate number of distinct series buffered for the table.
+    #[cfg(test)]
+    pub(crate) fn count(&self) -> usize {
+        self.state.lock().count
+    }
+
+    /// Read the approximate number of distinct series buffered for the
+    /// partition identified by `partition_key`.
+    pub(crate) fn partition_count(&self, partition_key: &PartitionKey) -> usize {
+        self.state
+            .lock()
+            .partitions
+            .get(partition_key)
+            .map(|s| s.len())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use assert_matches::assert_matches;
+
+    #[test]
+    fn test_series_tracker() {
+        let s = SeriesTracker::new(NonZeroUsize::new(3).unwrap());
+        let key = PartitionKey::from("platanos");
+
+        assert_matches!(s.observe(&key, &[1, 2, 1]), Ok(()));
+        assert_eq!(s.count(), 2);
+
+        // Existing series are always accepted.
+        assert_matches!(s.observe(&key, &[1, 2]), Ok(()));
+        assert_eq!(s.count(), 2);
+
+        // Adding two new series would exceed the limit, and neither is
+        // recorded.
+        assert_matches!(
+            s.observe(&key, &[1, 3, 4]),
+            Err(BufferWriteError::SeriesLimit { count: 2, limit: 3 })
+        );
+        assert_eq!(s.count(), 2);
+
+        assert_matches!(s.observe(&key, &[3, 3]), Ok(()));
+        assert_eq!(s.count(), 3);
+
+        assert_matches!(
+            s.observe(&key, &[4]),
+            Err(BufferWriteError::SeriesLimit { count: 3, limit: 3 })
+        );
+        assert_matches!(s.observe(&key, &[1, 2, 3]), Ok(()));
+        assert_eq!(s.count(), 3);
+        assert_eq!(s.partition_count(&key), 3);
+    }
+
+    #[test]
+    fn test_series_tracker_reset() {
+        let s = SeriesTracker::new(NonZeroUsize::new(3).unwrap());
+        let a = PartitionKey::from("platanos");
+        let b = PartitionKey::from("bananas");
+
+        // The same series in two partitions is counted for each.
+        assert_matches!(s.observe(&a, &[1, 2]), Ok(()));
+        assert_matches!(s.observe(&b, &[1]), Ok(()));
+        assert_eq!(s.count(), 3);
+        assert_eq!(s.partition_count(&a), 2);
+        assert_eq!(s.partition_count(&b), 1);
+
+        assert_matches!(
+            s.observe(&b, &[3]),
+            Err(BufferWriteError::SeriesLimit { count: 3, limit: 3 })
+        );
+
+        // Once the data in partition "a" is persisted, its series no longer
+        // count towards the limit.
+        s.reset(&a);
+        assert_eq!(s.count(), 1);
+        assert_eq!(s.partition_count(&a), 0);
+
+        assert_matches!(s.observe(&b, &[3, 4]), Ok(()));
+        assert_eq!(s.count(), 3);
+
+        // Resetting an unknown partition is a no-op.
+        s.reset(&a);
+        assert_eq!(s.count(), 3);
+    }
+}
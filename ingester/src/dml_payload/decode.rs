@@ -0,0 +1,122 @@
+//! This module houses decode helpers for the ingester internal DML types
+use data_types::{
+    partition_template::{
+        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, ValidationError,
+    },
+    TableId,
+};
+use generated_types::influxdata::pbdata::v1::DatabaseBatch;
+use hashbrown::HashMap;
+
+/// Decodes the partition templates the table batches in `batch` were
+/// partitioned with, keyed by [`TableId`].
+///
+/// Table batches that do not carry a partition template (those from writers
+/// predating the field) have no entry in the returned map.
+pub fn decode_partition_templates(
+    batch: &DatabaseBatch,
+) -> Result<HashMap<TableId, TablePartitionTemplateOverride>, ValidationError> {
+    batch
+        .table_batches
+        .iter()
+        .filter_map(|t| {
+            t.partition_template.as_ref().map(|v| {
+                TablePartitionTemplateOverride::try_new(
+                    Some(v.clone()),
+                    &NamespacePartitionTemplateOverride::default(),
+                )
+                .map(|template| (TableId::new(t.table_id), template))
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::{
+        partition_template::{test_table_partition_override, TemplatePart, PARTITION_BY_DAY_PROTO},
+        SequenceNumber,
+    };
+    use generated_types::influxdata::{
+        iox::partition_template::v1::PartitionTemplate, pbdata::v1::TableBatch,
+    };
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+    use crate::{
+        dml_payload::{
+            encode::encode_write_op,
+            write::{PartitionedData, TableData, WriteOperation},
+        },
+        test_util::{ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID},
+    };
+
+    /// The partition template carried by a write survives encoding (as it is
+    /// for the WAL) and decoding.
+    #[test]
+    fn test_encode_decode_round_trip() {
+        let template = test_table_partition_override(vec![TemplatePart::TimeFormat("%Y")]);
+
+        let (_, batch) = lp_to_mutable_batch("bananas val=42i 1");
+        let op = WriteOperation::new(
+            ARBITRARY_NAMESPACE_ID,
+            [(
+                ARBITRARY_TABLE_ID,
+                TableData::new(
+                    ARBITRARY_TABLE_ID,
+                    PartitionedData::new(SequenceNumber::new(1), batch),
+                )
+                .with_partition_template(Some(template.clone())),
+            )]
+            .into_iter()
+            .collect(),
+            ARBITRARY_PARTITION_KEY.clone(),
+            None,
+        );
+
+        let encoded = encode_write_op(ARBITRARY_NAMESPACE_ID, &op);
+        let got = decode_partition_templates(&encoded).expect("valid templates");
+        assert_eq!(got.get(&ARBITRARY_TABLE_ID), Some(&template));
+    }
+
+    #[test]
+    fn test_decode_partition_templates() {
+        let batch = DatabaseBatch {
+            database_id: 42,
+            partition_key: "2023-10-21".to_string(),
+            table_batches: vec![
+                TableBatch {
+                    table_id: 1,
+                    partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+                    ..Default::default()
+                },
+                TableBatch {
+                    table_id: 2,
+                    ..Default::default()
+                },
+            ],
+        };
+
+        let got = decode_partition_templates(&batch).expect("valid templates");
+        assert_eq!(got.len(), 1);
+        assert_eq!(
+            got.get(&TableId::new(1)).and_then(|v| v.as_proto()),
+            Some(&**PARTITION_BY_DAY_PROTO)
+        );
+
+        // An invalid template is rejected.
+        let batch = DatabaseBatch {
+            table_batches: vec![TableBatch {
+                table_id: 1,
+                partition_template: Some(PartitionTemplate { parts: vec![] }),
+                ..Default::default()
+            }],
+            ..batch
+        };
+        assert_matches!(
+            decode_partition_templates(&batch),
+            Err(ValidationError::NoParts)
+        );
+    }
+}
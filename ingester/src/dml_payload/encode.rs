@@ -6,14 +6,22 @@ use mutable_batch_pb::encode::encode_batch;
 use super::write::WriteOperation;
 
 /// Encodes a [`WriteOperation`] for `namespace` into the [`DatabaseBatch`]
-/// wire format.
+/// wire format, including the partition template of each table batch (if
+/// known).
 pub fn encode_write_op(namespace: NamespaceId, op: &WriteOperation) -> DatabaseBatch {
     DatabaseBatch {
         database_id: namespace.get(),
         partition_key: op.partition_key().to_string(),
         table_batches: op
             .tables()
-            .map(|(table_id, batch)| encode_batch(table_id.get(), batch.partitioned_data().data()))
+            .map(|(table_id, batch)| {
+                let mut table_batch = encode_batch(table_id.get(), batch.partitioned_data().data());
+                table_batch.partition_template = batch
+                    .partition_template()
+                    .and_then(|v| v.as_proto())
+                    .cloned();
+                table_batch
+            })
             .collect(),
     }
 }
@@ -1,4 +1,4 @@
-use data_types::TableId;
+use data_types::{partition_template::TablePartitionTemplateOverride, TableId};
 
 use super::partitioned_data::PartitionedData;
 
@@ -11,6 +11,9 @@ pub struct TableData {
     // partitioned in a way that each table has a single partition of
     // data associated with it per write
     partitioned_data: PartitionedData,
+    // The partition template the partition key of the write was derived from,
+    // if known.
+    partition_template: Option<TablePartitionTemplateOverride>,
 }
 
 impl TableData {
@@ -19,9 +22,20 @@ impl TableData {
         Self {
             table,
             partitioned_data,
+            partition_template: None,
         }
     }
 
+    /// Record the partition template the partition key of the write was
+    /// derived from, if known.
+    pub fn with_partition_template(
+        mut self,
+        partition_template: Option<TablePartitionTemplateOverride>,
+    ) -> Self {
+        self.partition_template = partition_template;
+        self
+    }
+
     /// Returns the [`TableId`] which the data is for
     pub fn table(&self) -> TableId {
         self.table
@@ -32,8 +46,15 @@ impl TableData {
         &self.partitioned_data
     }
 
-    /// Consumes `self`, returning the [`PartitionedData`] for the table
-    pub fn into_partitioned_data(self) -> PartitionedData {
-        self.partitioned_data
+    /// Returns the partition template the partition key of the write was
+    /// derived from, if known.
+    pub fn partition_template(&self) -> Option<&TablePartitionTemplateOverride> {
+        self.partition_template.as_ref()
+    }
+
+    /// Consumes `self`, returning the [`PartitionedData`] for the table and
+    /// the partition template it was partitioned with, if known.
+    pub fn into_parts(self) -> (PartitionedData, Option<TablePartitionTemplateOverride>) {
+        (self.partitioned_data, self.partition_template)
     }
 }
@@ -0,0 +1,31 @@
+use std::sync::Arc;
+
+use generated_types::influxdata::iox::gossip::v1::{IngesterState, IngesterStateChange};
+use gossip_ingester_state::tx::IngesterStateTx;
+use observability_deps::tracing::info;
+
+/// Announce lifecycle state changes of this ingester to listening gossip
+/// peers (routers).
+///
+/// The announced `address` MUST match the ingester address configured in the
+/// routers exactly, as it is used to identify the upstream that changed state.
+#[derive(Debug, Clone)]
+pub(crate) struct IngesterStateAnnouncer {
+    tx: Arc<IngesterStateTx>,
+    address: String,
+}
+
+impl IngesterStateAnnouncer {
+    pub(crate) fn new(tx: Arc<IngesterStateTx>, address: String) -> Self {
+        Self { tx, address }
+    }
+
+    /// Broadcast `state` for this ingester.
+    pub(crate) fn announce(&self, state: IngesterState) {
+        info!(address=%self.address, ?state, "announcing ingester state");
+        self.tx.broadcast(IngesterStateChange {
+            address: self.address.clone(),
+            state: state.into(),
+        });
+    }
+}
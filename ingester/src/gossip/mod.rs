@@ -1,3 +1,4 @@
 //! [`gossip`] integration & specialisation for ingesters.
 
+pub mod ingester_state;
 pub mod persist_parquet;
@@ -0,0 +1,299 @@
+//! Handoff of buffered, unpersisted data to a peer ingester during graceful
+//! shutdown.
+//!
+//! Instead of persisting all buffered data to object storage before stopping
+//! (which for a large buffer may take a long time, and produces many small
+//! Parquet files), an ingester may stream its closed WAL segment files to a
+//! designated peer ingester which replays them into its own buffer & WAL.
+//!
+//! The handoff proceeds as follows:
+//!
+//!   1. Ingest is blocked and (if configured) the ingester announces it is
+//!      draining over gossip, causing routers to stop sending it writes.
+//!   2. The open WAL segment is rotated, so that all buffered data is contained
+//!      in a closed segment file.
+//!   3. Each closed segment is sent to the peer, oldest first. The peer
+//!      applies the segment and persists the partitions it was applied to.
+//!      Once the peer acknowledges the segment, the local file is deleted so
+//!      it is not replayed if this ingester restarts.
+//!
+//! Queries continue to be served by this ingester while the handoff is in
+//! progress, and therefore until all the handed-off data is readable from
+//! object storage. If the handoff fails or does not complete within the
+//! configured timeout, the ingester falls back to persisting all buffered
+//! data.
+//!
+//! # Duplicates
+//!
+//! Data may be both handed off and persisted - for example a hot partition
+//! persist that was ongoing when the handoff started, or a segment that was
+//! partially applied by the peer before a failure. This duplicated data is
+//! resolved at query & compaction time in the same way as any other
+//! duplicated write.
+//!
+//! # Ordering
+//!
+//! The peer assigns new sequence numbers to the handed-off data, ordering it
+//! after any data already buffered by the peer. Overwrites of the same series
+//! & timestamp that were sent to both ingesters may therefore resolve
+//! differently to the order in which they were originally received.
+
+pub(crate) mod peer;
+
+use std::{sync::Arc, time::Duration};
+
+use generated_types::influxdata::iox::gossip::v1::IngesterState;
+use metric::U64Counter;
+use observability_deps::tracing::*;
+use thiserror::Error;
+
+use crate::gossip::ingester_state::IngesterStateAnnouncer;
+
+use self::peer::{HandoffPeer, PeerError};
+
+/// Errors that cause a handoff to be abandoned.
+#[derive(Debug, Error)]
+pub(crate) enum HandoffError {
+    /// The peer failed to accept a segment.
+    #[error(transparent)]
+    Peer(#[from] PeerError),
+
+    /// The handoff did not complete within the configured timeout.
+    #[error("handoff did not complete within {0:?}")]
+    Timeout(Duration),
+}
+
+/// Sends all closed WAL segments to a peer ingester.
+#[derive(Debug)]
+pub(crate) struct Handoff {
+    peer: Arc<dyn HandoffPeer>,
+    timeout: Duration,
+
+    /// Optionally announce the draining state to routers before handing off.
+    announcer: Option<IngesterStateAnnouncer>,
+
+    handoff_success: U64Counter,
+    handoff_error: U64Counter,
+    handoff_timeout: U64Counter,
+    segments: U64Counter,
+    bytes: U64Counter,
+}
+
+impl Handoff {
+    pub(crate) fn new(
+        peer: Arc<dyn HandoffPeer>,
+        timeout: Duration,
+        announcer: Option<IngesterStateAnnouncer>,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let handoff = metrics.register_metric::<U64Counter>(
+            "ingester_handoff",
+            "Number of shutdown handoff attempts to a peer ingester, by result",
+        );
+        let segments = metrics
+            .register_metric::<U64Counter>(
+                "ingester_handoff_segments",
+                "Number of WAL segment files handed off to a peer ingester",
+            )
+            .recorder(&[]);
+        let bytes = metrics
+            .register_metric::<U64Counter>(
+                "ingester_handoff_bytes",
+                "Number of WAL segment file bytes handed off to a peer ingester",
+            )
+            .recorder(&[]);
+
+        Self {
+            peer,
+            timeout,
+            announcer,
+            handoff_success: handoff.recorder(&[("result", "success")]),
+            handoff_error: handoff.recorder(&[("result", "error")]),
+            handoff_timeout: handoff.recorder(&[("result", "timeout")]),
+            segments,
+            bytes,
+        }
+    }
+
+    /// Hand off all the closed segments in `wal` to the peer, deleting each
+    /// segment once it has been applied by the peer.
+    ///
+    /// The caller MUST have blocked ingest and rotated the open WAL segment
+    /// before calling this method.
+    pub(crate) async fn run(&self, wal: &wal::Wal) -> Result<(), HandoffError> {
+        if let Some(announcer) = &self.announcer {
+            announcer.announce(IngesterState::Draining);
+        }
+
+        let res = match tokio::time::timeout(self.timeout, self.send_segments(wal)).await {
+            Ok(v) => v,
+            Err(_) => Err(HandoffError::Timeout(self.timeout)),
+        };
+
+        match &res {
+            Ok(()) => self.handoff_success.inc(1),
+            Err(HandoffError::Peer(_)) => self.handoff_error.inc(1),
+            Err(HandoffError::Timeout(_)) => self.handoff_timeout.inc(1),
+        }
+
+        res
+    }
+
+    async fn send_segments(&self, wal: &wal::Wal) -> Result<(), HandoffError> {
+        // Closed segments are ordered by ID, which is the order they were
+        // written in and therefore the order they should be replayed in.
+        for segment in wal.closed_segments() {
+            let segment_id = segment.id();
+
+            let ops = match self.peer.replay_segment(segment.path()).await {
+                Ok(v) => v,
+                Err(PeerError::SegmentGone) => {
+                    // All the data in this segment was persisted while the
+                    // handoff was in progress.
+                    debug!(%segment_id, "wal segment deleted before handoff");
+                    continue;
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            self.segments.inc(1);
+            self.bytes.inc(segment.size());
+
+            info!(%segment_id, ops, "handed off wal segment to peer");
+
+            // The peer has applied and persisted the segment - remove it so that it
+            // is not replayed by this ingester.
+            //
+            // This may race with the reference tracker deleting the segment
+            // as a result of a concurrent persist completing.
+            if let Err(e) = wal.delete(segment_id).await {
+                debug!(%segment_id, error=%e, "wal segment already deleted after handoff");
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use assert_matches::assert_matches;
+    use metric::{assert_counter, Attributes};
+    use wal::{SegmentId, Wal};
+
+    use super::{peer::mock::MockHandoffPeer, *};
+
+    /// Initialise a WAL in `dir` with `n` closed segments, returning the IDs
+    /// of the closed segments.
+    async fn wal_with_segments(dir: &Path, n: usize) -> (Arc<Wal>, Vec<SegmentId>) {
+        let wal = Wal::new(dir).await.expect("failed to initialise WAL");
+        let ids = (0..n)
+            .map(|_| wal.rotate().expect("failed to rotate").0.id())
+            .collect();
+        (wal, ids)
+    }
+
+    fn assert_handoff_metric(metrics: &metric::Registry, result: &'static str, want: u64) {
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_handoff",
+            labels = Attributes::from(&[("result", result)]),
+            value = want,
+        );
+    }
+
+    #[tokio::test]
+    async fn test_handoff_success() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wal, ids) = wal_with_segments(dir.path(), 3).await;
+
+        // The second segment is deleted concurrently, and is skipped.
+        let peer = Arc::new(MockHandoffPeer::default().with_results([
+            Ok(1),
+            Err(PeerError::SegmentGone),
+            Ok(2),
+        ]));
+        let metrics = metric::Registry::default();
+        let handoff = Handoff::new(
+            Arc::clone(&peer) as _,
+            Duration::from_secs(5),
+            None,
+            &metrics,
+        );
+
+        handoff.run(&wal).await.expect("handoff should succeed");
+
+        // All segments were sent in order.
+        assert_eq!(peer.calls(), ids);
+
+        // And all segments are deleted.
+        assert!(wal.closed_segments().is_empty());
+
+        assert_handoff_metric(&metrics, "success", 1);
+        assert_counter!(metrics, U64Counter, "ingester_handoff_segments", value = 2,);
+    }
+
+    #[tokio::test]
+    async fn test_handoff_peer_error() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wal, ids) = wal_with_segments(dir.path(), 3).await;
+
+        let peer = Arc::new(
+            MockHandoffPeer::default()
+                .with_results([Ok(1), Err(tonic::Status::unavailable("bananas").into())]),
+        );
+        let metrics = metric::Registry::default();
+        let handoff = Handoff::new(
+            Arc::clone(&peer) as _,
+            Duration::from_secs(5),
+            None,
+            &metrics,
+        );
+
+        let err = handoff.run(&wal).await.expect_err("handoff should fail");
+        assert_matches!(err, HandoffError::Peer(PeerError::Rpc(_)));
+
+        // The handoff stops at the first failure.
+        assert_eq!(peer.calls(), ids[..2]);
+
+        // Only the handed-off segment is deleted, leaving the rest to be
+        // persisted.
+        let remaining = wal
+            .closed_segments()
+            .into_iter()
+            .map(|v| v.id())
+            .collect::<Vec<_>>();
+        assert_eq!(remaining, ids[1..]);
+
+        assert_handoff_metric(&metrics, "error", 1);
+    }
+
+    #[tokio::test]
+    async fn test_handoff_timeout() {
+        let dir = tempfile::tempdir().unwrap();
+        let (wal, ids) = wal_with_segments(dir.path(), 2).await;
+
+        let peer = Arc::new(MockHandoffPeer::default().blocking());
+        let metrics = metric::Registry::default();
+        let handoff = Handoff::new(
+            Arc::clone(&peer) as _,
+            Duration::from_millis(10),
+            None,
+            &metrics,
+        );
+
+        let err = handoff
+            .run(&wal)
+            .await
+            .expect_err("handoff should time out");
+        assert_matches!(err, HandoffError::Timeout(_));
+
+        // No segments were deleted.
+        assert_eq!(wal.closed_segments().len(), ids.len());
+
+        assert_handoff_metric(&metrics, "timeout", 1);
+    }
+}
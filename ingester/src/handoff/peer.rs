@@ -0,0 +1,158 @@
+//! The transport used to send closed WAL segments to a peer ingester.
+
+use std::{fmt::Debug, path::Path};
+
+use async_trait::async_trait;
+use futures::stream;
+use generated_types::influxdata::iox::ingester::v1::{
+    handoff_service_client::HandoffServiceClient, ReplayWalSegmentRequest,
+};
+use observability_deps::tracing::error;
+use thiserror::Error;
+use tokio::io::AsyncReadExt;
+use tonic::transport::{Channel, Endpoint};
+
+/// The size of the WAL segment file chunks streamed to the peer.
+///
+/// Kept well below the default gRPC maximum decoding message size.
+const CHUNK_SIZE: usize = 1024 * 1024; // 1 MiB
+
+/// Errors sending a WAL segment to a peer ingester.
+#[derive(Debug, Error)]
+pub(crate) enum PeerError {
+    /// The segment file no longer exists, because all the data within it has
+    /// been persisted and the file deleted.
+    #[error("wal segment file no longer exists")]
+    SegmentGone,
+
+    /// An I/O error reading the segment file.
+    #[error("failed to read wal segment file: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// The peer failed to replay the segment.
+    #[error("peer failed to replay wal segment: {0}")]
+    Rpc(#[from] tonic::Status),
+}
+
+/// An abstract destination for closed WAL segment files.
+#[async_trait]
+pub(crate) trait HandoffPeer: Debug + Send + Sync {
+    /// Send the segment file at `path` to the peer, returning once the peer
+    /// has applied and persisted all the operations within it.
+    ///
+    /// Returns the number of operations applied by the peer.
+    async fn replay_segment(&self, path: &Path) -> Result<u64, PeerError>;
+}
+
+/// A [`HandoffPeer`] streaming segment files to the gRPC `HandoffService` of a
+/// peer ingester.
+#[derive(Debug)]
+pub(crate) struct GrpcHandoffPeer {
+    client: HandoffServiceClient<Channel>,
+}
+
+impl GrpcHandoffPeer {
+    /// Initialise a [`GrpcHandoffPeer`] that lazily connects to the ingester
+    /// at `address`.
+    pub(crate) fn new(address: &str) -> Result<Self, tonic::transport::Error> {
+        let channel = Endpoint::from_shared(address.to_string())?.connect_lazy();
+        Ok(Self {
+            client: HandoffServiceClient::new(channel),
+        })
+    }
+}
+
+#[async_trait]
+impl HandoffPeer for GrpcHandoffPeer {
+    async fn replay_segment(&self, path: &Path) -> Result<u64, PeerError> {
+        let file = match tokio::fs::File::open(path).await {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(PeerError::SegmentGone)
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Read the file into fixed size chunks, and stream them to the peer.
+        let chunks = stream::unfold(Some(file), |file| async move {
+            let mut file = file?;
+            let mut buf = Vec::with_capacity(CHUNK_SIZE);
+            match (&mut file)
+                .take(CHUNK_SIZE as u64)
+                .read_to_end(&mut buf)
+                .await
+            {
+                Ok(0) => None,
+                Ok(_) => Some((ReplayWalSegmentRequest { data: buf }, Some(file))),
+                Err(e) => {
+                    // Terminate the stream early - the peer rejects the
+                    // truncated segment.
+                    error!(
+                        error=%e,
+                        "failed to read wal segment for handoff"
+                    );
+                    None
+                }
+            }
+        });
+
+        let resp = self.client.clone().replay_wal_segment(chunks).await?;
+
+        Ok(resp.into_inner().ops_applied)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod mock {
+    use parking_lot::Mutex;
+    use wal::SegmentId;
+
+    use super::*;
+
+    /// A [`HandoffPeer`] recording the segment files it is asked to replay, and
+    /// returning the configured results in order.
+    #[derive(Debug, Default)]
+    pub(crate) struct MockHandoffPeer {
+        calls: Mutex<Vec<SegmentId>>,
+        results: Mutex<Vec<Result<u64, PeerError>>>,
+        block: bool,
+    }
+
+    impl MockHandoffPeer {
+        pub(crate) fn with_results(
+            self,
+            results: impl IntoIterator<Item = Result<u64, PeerError>>,
+        ) -> Self {
+            let mut results = results.into_iter().collect::<Vec<_>>();
+            results.reverse();
+            *self.results.lock() = results;
+            self
+        }
+
+        pub(crate) fn blocking(mut self) -> Self {
+            self.block = true;
+            self
+        }
+
+        pub(crate) fn calls(&self) -> Vec<SegmentId> {
+            self.calls.lock().clone()
+        }
+    }
+
+    #[async_trait]
+    impl HandoffPeer for MockHandoffPeer {
+        async fn replay_segment(&self, path: &Path) -> Result<u64, PeerError> {
+            let id = match wal::ClosedSegmentFileReader::from_path(path) {
+                Ok(v) => v.id(),
+                Err(_) => return Err(PeerError::SegmentGone),
+            };
+            self.calls.lock().push(id);
+
+            if self.block {
+                futures::future::pending::<()>().await;
+            }
+
+            self.results.lock().pop().expect("no mock result")
+        }
+    }
+}
@@ -1,6 +1,7 @@
 use data_types::ParquetFile;
 use gossip::{NopDispatcher, TopicInterests};
 
+use gossip_ingester_state::tx::IngesterStateTx;
 use gossip_parquet_file::tx::ParquetFileTx;
 /// This needs to be pub for the benchmarks but should not be used outside the crate.
 #[cfg(feature = "benches")]
@@ -10,6 +11,9 @@ mod graceful_shutdown;
 #[cfg(not(feature = "benches"))]
 mod wal_replay;
 
+pub use wal::{DurabilityMode, Keyring, KeyringError};
+pub use wal_replay::WalReplayProgress;
+
 use std::{net::SocketAddr, num::NonZeroUsize, path::PathBuf, sync::Arc, time::Duration};
 
 use arrow_flight::flight_service_server::FlightService;
@@ -17,8 +21,11 @@ use backoff::BackoffConfig;
 use futures::{future::Shared, Future, FutureExt};
 use generated_types::influxdata::iox::{
     catalog::v1::catalog_service_server::CatalogService,
-    gossip::Topic,
-    ingester::v1::{persist_service_server::PersistService, write_service_server::WriteService},
+    gossip::{v1::IngesterState, Topic},
+    ingester::v1::{
+        buffer_service_server::BufferService, handoff_service_server::HandoffService,
+        persist_service_server::PersistService, write_service_server::WriteService,
+    },
 };
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
@@ -28,7 +35,7 @@ use thiserror::Error;
 use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 use tracker::DiskSpaceMetrics;
-use wal::Wal;
+use wal::{Wal, WalOptions};
 
 use crate::{
     buffer_tree::{
@@ -41,7 +48,8 @@ use crate::{
         BufferTree,
     },
     dml_sink::{instrumentation::DmlSinkInstrumentation, tracing::DmlSinkTracing},
-    gossip::persist_parquet::ParquetFileNotification,
+    gossip::{ingester_state::IngesterStateAnnouncer, persist_parquet::ParquetFileNotification},
+    handoff::{peer::GrpcHandoffPeer, Handoff},
     ingest_state::IngestState,
     ingester_id::IngesterId,
     persist::{
@@ -54,8 +62,10 @@ use crate::{
         result_instrumentation::QueryResultInstrumentation, tracing::QueryExecTracing,
     },
     server::grpc::GrpcDelegate,
+    spill::{periodic_spill, SpillDir, SpillMetrics},
     timestamp_oracle::TimestampOracle,
     wal::{
+        archive::{periodic_archive_expiry, ArchiveExpiryMetrics, WalArchiver},
         disk_full_protection::{self, guard_disk_capacity},
         reference_tracker::WalReferenceHandle,
         rotate_task::periodic_rotation,
@@ -83,6 +93,10 @@ pub trait IngesterRpcInterface: Send + Sync + std::fmt::Debug {
     type PersistHandler: PersistService;
     /// The type of the [`FlightService`] implementation.
     type FlightHandler: FlightService;
+    /// The type of the [`HandoffService`] implementation.
+    type HandoffHandler: HandoffService;
+    /// The type of the [`BufferService`] implementation.
+    type BufferHandler: BufferService;
 
     /// Acquire an opaque handle to the Ingester's [`CatalogService`] RPC
     /// handler implementation.
@@ -96,6 +110,16 @@ pub trait IngesterRpcInterface: Send + Sync + std::fmt::Debug {
     /// handler implementation.
     fn persist_service(&self) -> Self::PersistHandler;
 
+    /// Acquire an opaque handle to the Ingester's [`HandoffService`] RPC
+    /// handler implementation, accepting WAL segments from a peer ingester
+    /// that is shutting down.
+    fn handoff_service(&self) -> Self::HandoffHandler;
+
+    /// Acquire an opaque handle to the Ingester's [`BufferService`] RPC
+    /// handler implementation, exposing debug information about the buffered
+    /// partitions.
+    fn buffer_service(&self) -> Self::BufferHandler;
+
     /// Acquire an opaque handle to the Ingester's Arrow Flight
     /// [`FlightService`] RPC handler implementation, allowing at most
     /// `max_simultaneous_requests` queries to be running at any one time.
@@ -118,6 +142,21 @@ pub struct IngesterGuard<T> {
     /// Aborted on drop.
     disk_metric_task: tokio::task::JoinHandle<()>,
 
+    /// The handle of the periodic spill task, if enabled.
+    ///
+    /// Aborted on drop.
+    spill_task: Option<tokio::task::JoinHandle<()>>,
+
+    /// The handle of the WAL segment archive task, if enabled.
+    ///
+    /// Aborted on drop.
+    archive_task: Option<tokio::task::JoinHandle<()>>,
+
+    /// The handle of the periodic WAL archive expiry task, if enabled.
+    ///
+    /// Aborted on drop.
+    archive_expiry_task: Option<tokio::task::JoinHandle<()>>,
+
     /// The task handle executing the graceful shutdown once triggered.
     graceful_shutdown_handler: tokio::task::JoinHandle<()>,
     shutdown_complete: Shared<oneshot::Receiver<()>>,
@@ -145,6 +184,15 @@ impl<T> Drop for IngesterGuard<T> {
     fn drop(&mut self) {
         self.rotation_task.abort();
         self.disk_metric_task.abort();
+        if let Some(t) = &self.spill_task {
+            t.abort();
+        }
+        if let Some(t) = &self.archive_task {
+            t.abort();
+        }
+        if let Some(t) = &self.archive_expiry_task {
+            t.abort();
+        }
         self.graceful_shutdown_handler.abort();
     }
 }
@@ -170,6 +218,80 @@ pub enum GossipConfig {
     },
 }
 
+/// Configuration parameters for the optional handoff of buffered data to a
+/// peer ingester during graceful shutdown.
+#[derive(Debug, Default)]
+pub enum HandoffConfig {
+    /// Persist all buffered data to object storage before shutting down.
+    #[default]
+    Disabled,
+
+    /// Hand off all buffered data to the ingester at `peer` before shutting
+    /// down, falling back to persisting it if the handoff does not complete
+    /// within `timeout`.
+    Enabled {
+        /// The gRPC address of the peer ingester, in the form of
+        /// "http://ingester-1:8083".
+        peer: String,
+        /// The address of this ingester, as configured in the routers.
+        ///
+        /// When set (and gossip is enabled) this ingester announces state
+        /// changes to the routers over gossip, causing them to stop sending
+        /// writes to this ingester while it is draining.
+        advertise_address: Option<String>,
+        /// The maximum duration of time the handoff may take before falling
+        /// back to persisting the buffered data.
+        timeout: Duration,
+    },
+}
+
+/// Configuration parameters for the optional spilling of persisting and cold
+/// buffered data to local disk.
+#[derive(Debug, Default)]
+pub enum SpillConfig {
+    /// Retain all buffered and persisting data in memory until it is
+    /// persisted.
+    #[default]
+    Disabled,
+
+    /// Spill persisting and cold buffered data to files within `directory`
+    /// once the memory it uses exceeds `max_memory_bytes`.
+    Enabled {
+        /// The maximum number of bytes of memory used by persisting and
+        /// buffered data before it is spilled to disk.
+        max_memory_bytes: usize,
+
+        /// The directory spill files are written to.
+        ///
+        /// This MUST NOT be within the WAL directory, so that spill files
+        /// do not consume the disk space required by the WAL.
+        directory: PathBuf,
+
+        /// The maximum number of bytes of disk space used by spill files.
+        max_disk_bytes: u64,
+    },
+}
+
+/// Configuration parameters for the optional archiving of closed WAL segment
+/// files to object storage.
+#[derive(Debug, Default)]
+pub enum WalArchiveConfig {
+    /// Delete WAL segment files once the data they contain is persisted.
+    #[default]
+    Disabled,
+
+    /// Upload each WAL segment file to the object store under `prefix` before
+    /// it is deleted, retaining the archived copy for `retention`.
+    Enabled {
+        /// The object store path prefix to archive segment files under, which
+        /// should be unique to this ingester.
+        prefix: String,
+        /// The minimum duration of time an archived segment is retained for
+        /// after the segment was closed.
+        retention: Duration,
+    },
+}
+
 /// Errors that occur during initialisation of an `ingester` instance.
 #[derive(Debug, Error)]
 pub enum InitError {
@@ -188,11 +310,28 @@ pub enum InitError {
 
     /// An error replaying the entries in the WAL.
     #[error(transparent)]
-    WalReplay(Box<dyn std::error::Error>),
+    WalReplay(Box<dyn std::error::Error + Send + Sync>),
 
     /// An error binding the UDP socket for gossip communication.
     #[error("failed to bind udp gossip socket: {0}")]
     GossipBind(std::io::Error),
+
+    /// The configured handoff peer address is invalid.
+    #[error("invalid handoff peer address: {0}")]
+    HandoffPeer(tonic::transport::Error),
+
+    /// The spill directory could not be created or cleaned.
+    #[error("failed to initialise spill directory: {0}")]
+    SpillDir(std::io::Error),
+
+    /// The spill directory is within the WAL directory, and would count
+    /// against the WAL disk capacity.
+    #[error("spill directory {0} must not be within the wal directory")]
+    SpillDirInWal(String),
+
+    /// The configured WAL archive prefix is not a valid object store path.
+    #[error("invalid wal archive prefix: {0}")]
+    WalArchivePrefix(object_store::path::Error),
 }
 
 /// Initialise a new `ingester` instance, returning the gRPC service handler
@@ -200,16 +339,37 @@ pub enum InitError {
 ///
 /// ## WAL Replay
 ///
-/// Writes through an `ingester` instance commit to a durable write-ahead log.
+/// Writes through an `ingester` instance commit to a durable write-ahead log,
+/// and are acknowledged once the WAL provides the guarantee described by
+/// `wal_durability`.
 ///
 /// During initialisation of an `ingester` instance, any files in
 /// `wal_directory` are read assuming they are redo log files from the
 /// write-ahead log.
 ///
 /// These files are read and replayed fully before this function returns.
+/// Segment files are decoded concurrently, and the operations within each file
+/// are applied to the buffer by up to `wal_replay_concurrency` tasks, with all
+/// operations for a given partition applied by the same task in sequence number
+/// order.
+///
+/// The state of an ongoing replay is exposed through `replay_progress`, and
+/// through the `ingester_wal_replay_*` metrics.
 ///
 /// Any error during replay is fatal.
 ///
+/// ## WAL Encryption
+///
+/// If `wal_keyring` contains keys, new WAL segments are encrypted at rest with
+/// the first (active) key, and the ID of the key is recorded in the segment
+/// header. The remaining keys are used only to read existing segments, allowing
+/// the active key to be rotated by prepending a new key to the keyring. All the
+/// keys used by existing segments must be retained until those segments are
+/// persisted and deleted.
+///
+/// Segments handed off to this ingester by a peer are decrypted with the same
+/// keyring.
+///
 /// ## Graceful Shutdown
 ///
 /// When `shutdown` completes, the ingester blocks ingest (returning an error to
@@ -223,6 +383,47 @@ pub enum InitError {
 /// The ingester will continue answering queries until the gRPC server is
 /// stopped by the caller (managed outside of this crate).
 ///
+/// ## Shutdown Handoff
+///
+/// If `handoff` is enabled, the ingester streams its WAL segments containing
+/// all buffered data to the configured peer ingester during graceful shutdown,
+/// instead of persisting the data. The peer replays the segments into its own
+/// WAL & buffer. Should the handoff fail or time out, the buffered data is
+/// persisted as normal.
+///
+/// ## Spilling Data
+///
+/// Data marked as persisting remains in memory until the persist job for it
+/// completes, and buffered data remains in memory until the partition is
+/// persisted. If the object store is degraded, or many partitions stop
+/// receiving writes, this data accumulates in memory.
+///
+/// If `spill` is enabled, persisting data and then the buffered data of the
+/// least recently written partitions is written to local disk (within the
+/// configured spill directory, up to the configured size) once the memory it
+/// uses exceeds the configured threshold. A persist job releases its persist
+/// queue capacity once all of its data is spilled, so that writes continue to
+/// be accepted while the object store is degraded. Spilled data is read back by
+/// queries, and by the persist job.
+///
+/// ## WAL Archiving
+///
+/// If `wal_archive` is enabled, each closed WAL segment file is uploaded to the
+/// object store (under the configured prefix) once the data it contains has
+/// been persisted, and before the local file is deleted. Archived segments are
+/// deleted once they exceed the configured retention period, and can be
+/// replayed into a scratch catalog with `influxdb_iox debug wal
+/// replay-archive` to recover from logical mistakes or bad compactions.
+///
+/// Segments are uploaded in the background, and a segment is only deleted once
+/// it has been uploaded - a segment that cannot be read is retained on disk and
+/// replayed after the next restart. Graceful shutdown waits for all enqueued
+/// segments to be archived.
+///
+/// Segment files handed off to a peer during graceful shutdown are not
+/// archived by this ingester - the peer archives the data it replays into its
+/// own WAL.
+///
 /// ## Deferred Loading for Persist Operations
 ///
 /// Several items within the ingester's internal state are loaded only when
@@ -266,6 +467,14 @@ pub enum InitError {
 /// Decreasing this value increases the frequency of persist operations, and
 /// usually decreases the size of the resulting parquet files.
 ///
+/// ## Series Limit
+///
+/// If `max_series_per_table` is set, each table buffer keeps an approximate
+/// count of the distinct series (tag sets) buffered for it. Writes that would
+/// cause a table to exceed `max_series_per_table` distinct series are
+/// rejected. The series of a partition no longer count towards the limit once
+/// all of its data has been persisted.
+///
 /// [`MutableBatch::size_data()`]: mutable_batch::MutableBatch::size_data
 #[allow(clippy::too_many_arguments)]
 pub async fn new<F>(
@@ -274,13 +483,21 @@ pub async fn new<F>(
     persist_background_fetch_time: Duration,
     wal_directory: PathBuf,
     wal_rotation_period: Duration,
+    wal_durability: DurabilityMode,
+    wal_keyring: Keyring,
     persist_executor: Arc<Executor>,
     persist_workers: usize,
     persist_queue_depth: usize,
     persist_hot_partition_cost: usize,
     object_store: ParquetStorage,
     gossip: GossipConfig,
+    handoff: HandoffConfig,
+    spill: SpillConfig,
+    wal_archive: WalArchiveConfig,
+    replay_progress: Arc<WalReplayProgress>,
+    wal_replay_concurrency: NonZeroUsize,
     max_partitions_per_namespace: NonZeroUsize,
+    max_series_per_table: Option<NonZeroUsize>,
     shutdown: F,
 ) -> Result<IngesterGuard<impl IngesterRpcInterface>, InitError>
 where
@@ -356,26 +573,92 @@ where
     // write path.
     let ingest_state = Arc::new(IngestState::default());
 
+    // Spilled data must not consume the disk capacity of the WAL, which is
+    // protected by the disk full guard.
+    if let SpillConfig::Enabled { directory, .. } = &spill {
+        if directory.starts_with(&wal_directory) {
+            return Err(InitError::SpillDirInWal(directory.display().to_string()));
+        }
+    }
+
     // Initialise the WAL
-    let wal = Wal::new(wal_directory.clone())
-        .await
-        .map_err(InitError::WalInit)?;
+    let wal = Wal::new_with_options(
+        wal_directory.clone(),
+        WalOptions {
+            durability: wal_durability,
+            keyring: wal_keyring.clone(),
+        },
+        &metrics,
+    )
+    .await
+    .map_err(InitError::WalInit)?;
+
+    // Segments handed off by a peer ingester are staged in a subdirectory of
+    // the WAL directory (which is ignored by the WAL) while they are received.
+    //
+    // Any staged files left over from a previous run are of no use.
+    let handoff_staging_dir = wal_directory.join("handoff");
+    if let Err(e) = tokio::fs::remove_dir_all(&handoff_staging_dir).await {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!(error=%e, "failed to remove stale handoff staging directory");
+        }
+    }
 
     // Start defining the chain of persist completion observers so it can be
     // layered in gossip handlers if needed.
     //
-    // Prepare the WAL segment reference tracker
-    let (wal_reference_handle, wal_reference_actor) =
-        WalReferenceHandle::new(Arc::clone(&wal), &metrics);
+    // Prepare the WAL segment reference tracker, optionally archiving segment
+    // files to object storage before they are deleted.
+    let (wal_reference_handle, wal_reference_actor, archive) = match wal_archive {
+        WalArchiveConfig::Disabled => {
+            let (handle, actor) = WalReferenceHandle::new(Arc::clone(&wal), &metrics);
+            (handle, actor.run().boxed(), None)
+        }
+        WalArchiveConfig::Enabled { prefix, retention } => {
+            let prefix =
+                object_store::path::Path::parse(prefix).map_err(InitError::WalArchivePrefix)?;
+            info!(%prefix, ?retention, "wal segment archiving enabled");
+
+            // Segments are uploaded and deleted by the archive task, off the
+            // reference tracker actor.
+            let (archiver, archive_task) = WalArchiver::new(
+                Arc::clone(&wal),
+                Arc::clone(object_store.object_store()),
+                prefix.clone(),
+                &metrics,
+            );
+            let archive_task = tokio::spawn(archive_task.run());
+            let (handle, actor) = WalReferenceHandle::new(archiver.clone(), &metrics);
+
+            let expiry_task = tokio::spawn(periodic_archive_expiry(
+                Arc::clone(object_store.object_store()),
+                prefix,
+                retention,
+                ArchiveExpiryMetrics::new(&metrics),
+            ));
+
+            (
+                handle,
+                actor.run().boxed(),
+                Some((archiver, archive_task, expiry_task)),
+            )
+        }
+    };
+    let (archiver, archive_task, archive_expiry_task) = match archive {
+        Some((archiver, archive_task, expiry_task)) => {
+            (Some(archiver), Some(archive_task), Some(expiry_task))
+        }
+        None => (None, None, None),
+    };
     // Add file metric instrumentation.
     let persist_observer = ParquetFileInstrumentation::new(wal_reference_handle.clone(), &metrics);
 
     // Optionally start the gossip subsystem and layer on the parquet file
     // gossip handler.
-    let persist_observer = match gossip {
+    let (persist_observer, gossip_handle) = match gossip {
         GossipConfig::Disabled => {
             info!("gossip disabled");
-            MaybeLayer::Without(persist_observer)
+            (MaybeLayer::Without(persist_observer), None)
         }
         GossipConfig::Enabled { bind_addr, peers } => {
             // Start the gossip sub-system, which logs during init.
@@ -390,13 +673,49 @@ where
             .bind(bind_addr)
             .await
             .map_err(InitError::GossipBind)?;
+            let handle = Arc::new(handle);
 
             let persist_observer = ParquetFileNotification::new(
                 persist_observer,
-                ParquetFileTx::<ParquetFile>::new(handle),
+                ParquetFileTx::<ParquetFile>::new(Arc::clone(&handle)),
             );
 
-            MaybeLayer::With(persist_observer)
+            (MaybeLayer::With(persist_observer), Some(handle))
+        }
+    };
+
+    // Optionally configure the handoff of buffered data to a peer during
+    // graceful shutdown, announcing state changes to routers over gossip if
+    // possible.
+    let (handoff, announcer) = match handoff {
+        HandoffConfig::Disabled => (None, None),
+        HandoffConfig::Enabled {
+            peer,
+            advertise_address,
+            timeout,
+        } => {
+            info!(%peer, ?timeout, "shutdown handoff enabled");
+
+            let announcer = match (advertise_address, gossip_handle) {
+                (Some(address), Some(handle)) => Some(IngesterStateAnnouncer::new(
+                    Arc::new(IngesterStateTx::new(handle)),
+                    address,
+                )),
+                (Some(_), None) => {
+                    warn!("gossip disabled, not announcing ingester state to routers");
+                    None
+                }
+                (None, _) => None,
+            };
+
+            let handoff = Handoff::new(
+                Arc::new(GrpcHandoffPeer::new(&peer).map_err(InitError::HandoffPeer)?),
+                timeout,
+                announcer.clone(),
+                &metrics,
+            );
+
+            (Some(handoff), announcer)
         }
     };
 
@@ -435,6 +754,7 @@ where
         table_provider,
         partition_provider,
         max_partitions_per_namespace,
+        max_series_per_table,
         Arc::new(hot_partition_persister),
         Arc::clone(&metrics),
     ));
@@ -442,7 +762,7 @@ where
     // Start the WAL reference actor and then replay the WAL log files, if any.
     // The tokio handle does not need retained here as the actor handle is
     // responsible for aborting the actor's run loop when dropped.
-    tokio::spawn(wal_reference_actor.run());
+    tokio::spawn(wal_reference_actor);
 
     // Initialize disk metrics to emit disk capacity / free statistics for the
     // WAL directory.
@@ -464,17 +784,57 @@ where
         ),
     ));
 
+    // Optionally spawn the task that spills data to disk, before the WAL
+    // replay enqueues persist jobs.
+    let spill_task = match spill {
+        SpillConfig::Disabled => None,
+        SpillConfig::Enabled {
+            max_memory_bytes,
+            directory,
+            max_disk_bytes,
+        } => {
+            info!(
+                max_memory_bytes,
+                max_disk_bytes,
+                directory=%directory.display(),
+                "data spill enabled"
+            );
+
+            // Spilled data is not durable across restarts (it is recovered by
+            // replaying the WAL) and any files left over from a previous run
+            // are removed.
+            let spill_dir = SpillDir::create(directory, max_disk_bytes)
+                .await
+                .map_err(InitError::SpillDir)?;
+
+            Some(tokio::spawn(periodic_spill(
+                Arc::clone(&buffer),
+                Arc::new(spill_dir),
+                max_memory_bytes,
+                SpillMetrics::new(&metrics),
+            )))
+        }
+    };
+
     // Replay the WAL log files, if any.
     let max_sequence_number = wal_replay::replay(
         &wal,
         &buffer,
         Arc::clone(&persist_handle),
         Arc::clone(&ingest_state),
+        wal_replay_concurrency,
+        replay_progress,
         &metrics,
     )
     .await
     .map_err(|e| InitError::WalReplay(e.into()))?;
 
+    // Signal to the routers that this ingester is accepting writes, undoing
+    // any draining state announced by a previous instance at this address.
+    if let Some(announcer) = &announcer {
+        announcer.announce(IngesterState::Active);
+    }
+
     // Build the chain of DmlSink that forms the write path.
     let write_path = DmlSinkInstrumentation::new(
         "write_apply",
@@ -532,8 +892,12 @@ where
         Arc::clone(&persist_handle),
         Arc::clone(&wal),
         wal_reference_handle,
+        archiver,
+        handoff,
     ));
 
+    let persist_queue_positions = persist_handle.queue_positions();
+
     Ok(IngesterGuard {
         rpc: GrpcDelegate::new(
             Arc::new(write_path),
@@ -545,9 +909,16 @@ where
             metrics,
             buffer,
             persist_handle,
+            persist_queue_positions,
+            handoff_staging_dir,
+            wal_keyring,
+            persist_hot_partition_cost,
         ),
         rotation_task,
         disk_metric_task,
+        spill_task,
+        archive_task,
+        archive_expiry_task,
         graceful_shutdown_handler: shutdown_task,
         shutdown_complete: shutdown_rx.shared(),
     })
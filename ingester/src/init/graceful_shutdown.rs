@@ -6,11 +6,12 @@ use tokio::sync::oneshot;
 use tokio_util::sync::CancellationToken;
 
 use crate::{
+    handoff::Handoff,
     ingest_state::{IngestState, IngestStateError},
     partition_iter::PartitionIter,
     persist::{drain_buffer::persist_partitions, queue::PersistQueue},
     query::projection::OwnedProjection,
-    wal::reference_tracker::WalReferenceHandle,
+    wal::{archive::WalArchiver, reference_tracker::WalReferenceHandle},
 };
 
 /// Defines how often the shutdown task polls the partition buffers for
@@ -33,6 +34,14 @@ const SHUTDOWN_POLL_INTERVAL: Duration = Duration::from_secs(1);
 /// Ingest is blocked by setting [`IngestStateError::GracefulStop`] in the
 /// [`IngestState`].
 ///
+/// If a [`Handoff`] is provided, the WAL segments containing all buffered data
+/// are first handed off to the peer ingester (which persists them) instead of
+/// being persisted locally. If the handoff fails (or times out) the buffered
+/// data is persisted as normal.
+///
+/// If a [`WalArchiver`] is provided, the fully persisted WAL segments are
+/// archived before returning.
+///
 /// [`PartitionData::mark_persisting()`]:
 ///     crate::buffer_tree::partition::PartitionData::mark_persisting()
 pub(super) async fn graceful_shutdown_handler<F, T, P>(
@@ -43,6 +52,8 @@ pub(super) async fn graceful_shutdown_handler<F, T, P>(
     persist: P,
     wal: Arc<wal::Wal>,
     wal_reference_handle: WalReferenceHandle,
+    archiver: Option<WalArchiver>,
+    handoff: Option<Handoff>,
 ) where
     F: Future<Output = CancellationToken> + Send,
     T: PartitionIter + Sync,
@@ -57,6 +68,37 @@ pub(super) async fn graceful_shutdown_handler<F, T, P>(
     // There MAY be writes ongoing that started before this state was set.
     ingest_state.set(IngestStateError::GracefulStop);
 
+    // Optionally hand the buffered data off to a peer ingester.
+    //
+    // The open WAL segment is rotated so that all buffered data is contained
+    // in closed segments, but is not yet enqueued with the reference tracker -
+    // if the handoff succeeds it is deleted by the handoff, and if the handoff
+    // fails it is enqueued below to be deleted once persisted.
+    let rotated = match handoff {
+        Some(handoff) => {
+            info!("handing off buffered data to peer before shutdown");
+
+            let rotated = wal.rotate().expect("failed to rotate wal");
+            // Queries continue to be served from the local buffer until the
+            // handoff completes, at which point the peer has persisted all
+            // the handed-off data and it is readable from object storage.
+            match handoff.run(&wal).await {
+                Ok(()) => {
+                    info!("handed off all data - stopping ingester");
+                    rpc_server_stop.cancel();
+                    let _ = complete.send(());
+                    return;
+                }
+                Err(e) => {
+                    warn!(error=%e, "handoff failed, falling back to persisting all data");
+                }
+            }
+
+            Some(rotated)
+        }
+        None => None,
+    };
+
     info!("persisting all data before shutdown");
 
     // Drain the buffer tree, persisting all data.
@@ -117,19 +159,30 @@ pub(super) async fn graceful_shutdown_handler<F, T, P>(
     //
     // We can rotate the open WAL segment and notify the reference handle
     // that the segment's file can be deleted because everything has been
-    // persisted.
-    let (closed_segment, sequence_number_set) = wal.rotate().expect("failed to rotate wal");
-    let rx = wal_reference_handle
-        .enqueue_rotated_file(closed_segment.id(), sequence_number_set)
-        .await;
-    if let Err(e) = rx.await {
-        error!(%e, "encountered failure waiting on file rotation receiver during shutdown");
-    };
+    // persisted. This includes any segment rotated out before a failed
+    // handoff attempt.
+    let closed = rotated
+        .into_iter()
+        .chain(std::iter::once(wal.rotate().expect("failed to rotate wal")));
+    for (closed_segment, sequence_number_set) in closed {
+        let rx = wal_reference_handle
+            .enqueue_rotated_file(closed_segment.id(), sequence_number_set)
+            .await;
+        if let Err(e) = rx.await {
+            error!(%e, "encountered failure waiting on file rotation receiver during shutdown");
+        };
+    }
 
     // Wait for the file rotation to be processed and the tracked set
     // to drop to empty.
     empty_waker.await;
 
+    // And for the segments to be archived and deleted, if archiving is
+    // enabled.
+    if let Some(archiver) = archiver {
+        archiver.wait_idle().await;
+    }
+
     info!("persisted all data - stopping ingester");
 
     // Stop the RPC server (and therefore stop accepting new queries)
@@ -151,6 +204,7 @@ mod tests {
 
     use crate::{
         buffer_tree::partition::PartitionData,
+        handoff::peer::mock::MockHandoffPeer,
         persist::queue::mock::MockPersistQueue,
         test_util::{PartitionDataBuilder, ARBITRARY_TABLE_NAME},
     };
@@ -212,6 +266,8 @@ mod tests {
             Arc::clone(&persist),
             Arc::clone(&wal),
             wal_reference_handle,
+            None,
+            None,
         )
         .await;
 
@@ -232,6 +288,106 @@ mod tests {
         assert!(wal.closed_segments().is_empty());
     }
 
+    /// Initialise a [`Handoff`] to `peer`.
+    fn new_handoff(peer: MockHandoffPeer) -> Handoff {
+        Handoff::new(
+            Arc::new(peer),
+            Duration::from_secs(5),
+            None,
+            &metric::Registry::default(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_handoff() {
+        let ingest_state = Arc::new(IngestState::default());
+        let (_tempdir, wal) = new_wal().await;
+        let (wal_reference_handle, wal_reference_actor) =
+            WalReferenceHandle::new(Arc::clone(&wal), &metric::Registry::default());
+        let persist = Arc::new(MockPersistQueue::new_with_observer(
+            wal_reference_handle.clone(),
+        ));
+        tokio::spawn(wal_reference_actor.run());
+
+        let partition = new_partition();
+
+        let rpc_stop = CancellationToken::new();
+        let (tx, rx) = oneshot::channel();
+        graceful_shutdown_handler(
+            ready(rpc_stop.clone()),
+            tx,
+            Arc::clone(&ingest_state),
+            vec![Arc::clone(&partition)],
+            Arc::clone(&persist),
+            Arc::clone(&wal),
+            wal_reference_handle,
+            None,
+            Some(new_handoff(
+                MockHandoffPeer::default().with_results([Ok(1), Ok(1)]),
+            )),
+        )
+        .await;
+
+        rx.with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("shutdown task panicked");
+
+        assert!(rpc_stop.is_cancelled());
+        assert_matches!(ingest_state.read(), Err(IngestStateError::GracefulStop));
+
+        // The data was handed off, not persisted.
+        assert!(persist.calls().is_empty());
+
+        // And the handed off WAL segments will not be replayed.
+        assert!(wal.closed_segments().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_graceful_shutdown_handoff_failure_persists() {
+        let ingest_state = Arc::new(IngestState::default());
+        let (_tempdir, wal) = new_wal().await;
+        let (wal_reference_handle, wal_reference_actor) =
+            WalReferenceHandle::new(Arc::clone(&wal), &metric::Registry::default());
+        let persist = Arc::new(MockPersistQueue::new_with_observer(
+            wal_reference_handle.clone(),
+        ));
+        tokio::spawn(wal_reference_actor.run());
+
+        let partition = new_partition();
+
+        let rpc_stop = CancellationToken::new();
+        let (tx, rx) = oneshot::channel();
+        graceful_shutdown_handler(
+            ready(rpc_stop.clone()),
+            tx,
+            ingest_state,
+            vec![Arc::clone(&partition)],
+            Arc::clone(&persist),
+            Arc::clone(&wal),
+            wal_reference_handle,
+            None,
+            Some(new_handoff(MockHandoffPeer::default().with_results([Err(
+                tonic::Status::unavailable("bananas").into(),
+            )]))),
+        )
+        .await;
+
+        rx.with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("shutdown task panicked");
+
+        assert!(rpc_stop.is_cancelled());
+
+        // The handoff failed, so the data was persisted instead.
+        let persist_calls = persist.calls();
+        assert_matches!(&*persist_calls, [p] => {
+            assert!(Arc::ptr_eq(p, &partition));
+        });
+
+        // Assert there are now no WAL segment files that will be replayed
+        assert!(wal.closed_segments().is_empty());
+    }
+
     #[tokio::test]
     async fn test_graceful_shutdown_concurrent_persist() {
         let ingest_state = Arc::new(IngestState::default());
@@ -269,6 +425,8 @@ mod tests {
             Arc::clone(&persist),
             Arc::clone(&wal),
             wal_reference_handle,
+            None,
+            None,
         ));
 
         // Wait a small duration of time for the first buffer emptiness check to
@@ -381,6 +539,8 @@ mod tests {
             Arc::clone(&persist),
             Arc::clone(&wal),
             wal_reference_handle.clone(),
+            None,
+            None,
         ));
 
         // Wait for the shutdown to complete.
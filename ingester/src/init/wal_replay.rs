@@ -1,17 +1,25 @@
-use std::fmt::Debug;
+use std::collections::hash_map::DefaultHasher;
+use std::fmt::{Debug, Display};
+use std::hash::{Hash, Hasher};
+use std::num::NonZeroUsize;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use async_trait::async_trait;
-use data_types::{NamespaceId, PartitionKey, SequenceNumber, TableId};
+use data_types::{
+    partition_template::ValidationError, NamespaceId, PartitionKey, SequenceNumber, TableId,
+};
+use futures::{stream, StreamExt};
 use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op;
-use metric::U64Counter;
+use metric::{U64Counter, U64Gauge};
 use mutable_batch_pb::decode::decode_database_batch;
 use observability_deps::tracing::*;
 use thiserror::Error;
 use wal::{SegmentId, SequencedWalOp};
 
 use crate::{
+    dml_payload::decode::decode_partition_templates,
     dml_payload::write::{PartitionedData, TableData, WriteOperation},
     dml_payload::IngestOp,
     dml_sink::{DmlError, DmlSink},
@@ -40,6 +48,10 @@ pub enum WalReplayError {
     #[error("failed converting wal entry to ingest operation: {0}")]
     MapToDml(#[from] mutable_batch_pb::decode::Error),
 
+    /// A partition template recorded in the WAL entry is invalid.
+    #[error("invalid partition template in wal entry: {0}")]
+    PartitionTemplate(#[from] ValidationError),
+
     /// A failure to apply a [`IngestOp`] from the WAL to the in-memory
     /// [`BufferTree`].
     ///
@@ -53,7 +65,7 @@ pub enum WalReplayError {
 #[async_trait]
 pub trait WalReader: Debug + Send + Sync + 'static {
     /// A reader for a closed WAL segment.
-    type SegmentReader: SegmentedWalOpBatchReader;
+    type SegmentReader: SegmentedWalOpBatchReader + 'static;
 
     /// Returns a reader for the closed wal segment specified.
     fn reader_for_closed_segment(&self, id: SegmentId) -> Result<Self::SegmentReader, wal::Error>;
@@ -100,22 +112,107 @@ impl SegmentedWalOpBatchReader for wal::ClosedSegmentFileReader {
     }
 }
 
+/// A shared view of the progress of a WAL replay, allowing the replay to be
+/// observed while it is ongoing (i.e. by a health check).
+#[derive(Debug, Default)]
+pub struct WalReplayProgress {
+    started: AtomicBool,
+    complete: AtomicBool,
+
+    files_total: AtomicU64,
+    files_replayed: AtomicU64,
+    bytes_total: AtomicU64,
+    bytes_replayed: AtomicU64,
+    ops_applied: AtomicU64,
+}
+
+impl WalReplayProgress {
+    /// Returns true once the WAL replay has completed.
+    pub fn is_complete(&self) -> bool {
+        self.complete.load(Ordering::Relaxed)
+    }
+
+    fn start(&self, n_files: usize, n_bytes: u64) {
+        self.files_total.store(n_files as _, Ordering::Relaxed);
+        self.bytes_total.store(n_bytes, Ordering::Relaxed);
+        self.started.store(true, Ordering::Relaxed);
+    }
+
+    fn file_replayed(&self, size: u64) {
+        self.files_replayed.fetch_add(1, Ordering::Relaxed);
+        self.bytes_replayed.fetch_add(size, Ordering::Relaxed);
+    }
+
+    fn op_applied(&self) {
+        self.ops_applied.fetch_add(1, Ordering::Relaxed);
+    }
+
+    fn mark_complete(&self) {
+        self.complete.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Display for WalReplayProgress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_complete() {
+            return write!(f, "wal replay complete");
+        }
+        if !self.started.load(Ordering::Relaxed) {
+            return write!(f, "wal replay not started");
+        }
+        write!(
+            f,
+            "replaying wal: {}/{} files ({}/{} bytes), {} ops applied",
+            self.files_replayed.load(Ordering::Relaxed),
+            self.files_total.load(Ordering::Relaxed),
+            self.bytes_replayed.load(Ordering::Relaxed),
+            self.bytes_total.load(Ordering::Relaxed),
+            self.ops_applied.load(Ordering::Relaxed),
+        )
+    }
+}
+
+/// The decoded contents of a WAL segment file.
+#[derive(Debug)]
+struct DecodedFile {
+    /// The write operations read from the file, in WAL order.
+    ops: Vec<WriteOperation>,
+
+    /// The highest sequence number observed across `ops`, if any.
+    max_sequence: Option<SequenceNumber>,
+
+    /// The error that ended the read of the file early, if any.
+    ///
+    /// All `ops` were read before the error occurred, and are valid.
+    error: Option<WalReplayError>,
+}
+
 // TODO: tolerate WAL replay errors
 //
 // https://github.com/influxdata/influxdb_iox/issues/6283
 
 /// Replay all the entries in `wal` to `sink`, returning the maximum observed
 /// [`SequenceNumber`].
+///
+/// Up to `concurrency` segment files are read & decoded in parallel ahead of
+/// the file being applied, and the operations within each file are applied to
+/// `sink` across `concurrency` tasks. All operations for a given partition are
+/// applied by the same task in WAL order, preserving the per-partition order
+/// of sequence numbers.
+///
+/// The progress of the replay is published to `progress`.
 pub async fn replay<W, T, P>(
     wal: &W,
-    sink: &T,
+    sink: &Arc<T>,
     persist: P,
     ingest_state: Arc<IngestState>,
+    concurrency: NonZeroUsize,
+    progress: Arc<WalReplayProgress>,
     metrics: &metric::Registry,
 ) -> Result<Option<SequenceNumber>, WalReplayError>
 where
     W: WalReader,
-    T: DmlSink + PartitionIter,
+    T: DmlSink + PartitionIter + Sync + 'static,
     P: PersistQueue + Clone,
 {
     // Read the set of files to replay.
@@ -125,6 +222,7 @@ where
     let files = wal.closed_segments();
     if files.is_empty() {
         info!("no wal replay files found");
+        progress.mark_complete();
         return Ok(None);
     }
 
@@ -150,6 +248,26 @@ where
     let file_count_error_truncated_metric =
         replayed_file_count_metric.recorder(&[("result", "error"), ("reason", "truncated")]);
 
+    // The total amount of work to be done, and the progress made towards it.
+    let files_total_metric = metrics
+        .register_metric::<U64Gauge>(
+            "ingester_wal_replay_files_total",
+            "Number of WAL files found for replay",
+        )
+        .recorder(&[]);
+    let bytes_total_metric = metrics
+        .register_metric::<U64Gauge>(
+            "ingester_wal_replay_bytes_total",
+            "Total size of the WAL files found for replay",
+        )
+        .recorder(&[]);
+    let bytes_finished_metric = metrics
+        .register_metric::<U64Counter>(
+            "ingester_wal_replay_bytes_finished",
+            "Total size of the WAL files that have been replayed",
+        )
+        .recorder(&[]);
+
     let op_count_metric = metrics.register_metric::<U64Counter>(
         "ingester_wal_replay_ops",
         "Number of operations replayed from the WAL",
@@ -158,24 +276,44 @@ where
     let empty_op_count_metric = op_count_metric.recorder(&[("outcome", "skipped_empty")]);
 
     let n_files = files.len();
-    info!(n_files, "found wal files for replay");
+    let n_bytes = files.iter().map(|(_, size)| *size).sum::<u64>();
+    files_total_metric.set(n_files as _);
+    bytes_total_metric.set(n_bytes);
+    progress.start(n_files, n_bytes);
+    info!(n_files, n_bytes, %concurrency, "found wal files for replay");
+
+    // Read and decode the segment files in parallel on the blocking thread
+    // pool, yielding them in WAL order.
+    //
+    // At most `concurrency` files are decoded and held in memory ahead of the
+    // file currently being applied.
+    let mut decoded = stream::iter(files.into_iter().enumerate())
+        .map(|(index, (file_id, file_size))| {
+            file_count_metric.inc(1);
+
+            let reader = wal.reader_for_closed_segment(file_id);
+            let empty_op_count_metric = empty_op_count_metric.clone();
+            async move {
+                let reader = reader.map_err(WalReplayError::OpenSegment)?;
+                let file = tokio::task::spawn_blocking(move || {
+                    decode_file(reader, &empty_op_count_metric)
+                })
+                .await
+                .expect("wal decode task panic");
+
+                // Map 0-based iter index to 1 based file count
+                Ok::<_, WalReplayError>((index + 1, file_id, file_size, file))
+            }
+        })
+        .buffered(concurrency.get());
 
     // Replay each file, keeping track of the last observed sequence number.
     //
     // Applying writes to the buffer can only happen monotonically and this is
     // enforced within the buffer.
     let mut max_sequence = None;
-    for (index, file) in files.into_iter().enumerate() {
-        // Map 0-based iter index to 1 based file count
-        let file_number = index + 1;
-        let (file_id, file_size) = (file.0, file.1);
-
-        file_count_metric.inc(1);
-
-        // Read the segment
-        let reader = wal
-            .reader_for_closed_segment(file_id)
-            .map_err(WalReplayError::OpenSegment)?;
+    while let Some(file) = decoded.next().await {
+        let (file_number, file_id, file_size, file) = file?;
 
         // Emit a log entry so progress can be tracked (and a problematic file
         // be identified should an explosion happen during replay).
@@ -184,21 +322,37 @@ where
             n_files,
             %file_id,
             size = file_size,
+            n_ops = file.ops.len(),
             "replaying wal file"
         );
 
-        // Replay this segment file, tracking successful replay in the metric
-        let replay_result = replay_file(
-            reader,
+        // Apply all the operations read from this segment file (including
+        // those preceding a read error), tracking successful replay in the
+        // metric.
+        let DecodedFile {
+            ops,
+            max_sequence: file_max_sequence,
+            error,
+        } = file;
+        let start = Instant::now();
+        apply_file(
+            ops,
             sink,
+            concurrency,
             &ok_op_count_metric,
-            &empty_op_count_metric,
             &ingest_state,
+            &progress,
         )
-        .await;
-        if replay_result.is_ok() {
-            file_count_success_metric.inc(1);
-        }
+        .await?;
+        debug!(%file_id, "wal file replayed in {:?}", start.elapsed());
+
+        let replay_result = match error {
+            None => {
+                file_count_success_metric.inc(1);
+                Ok(file_max_sequence)
+            }
+            Some(e) => Err(e),
+        };
 
         match replay_result {
             Ok(seq @ Some(_)) => max_sequence = max_sequence.max(seq),
@@ -225,6 +379,8 @@ where
                     );
                 }
 
+                bytes_finished_metric.inc(file_size);
+                progress.file_replayed(file_size);
                 continue;
             }
             // If the replay results in an underlying end of file error when
@@ -248,6 +404,9 @@ where
             Err(e) => return Err(e),
         };
 
+        bytes_finished_metric.inc(file_size);
+        progress.file_replayed(file_size);
+
         info!(
             file_number,
             n_files,
@@ -273,6 +432,7 @@ where
         );
     }
 
+    progress.mark_complete();
     info!(
         max_sequence_number = ?max_sequence,
         "wal replay complete"
@@ -281,35 +441,37 @@ where
     Ok(max_sequence)
 }
 
-/// Replay the entries in `file`, applying them to `buffer`. Returns the
-/// highest sequence number observed across the batches read from the file, or
-/// [`None`] if there were no entries read.
+/// Read and decode the entries in `file`, stopping at the first error.
+///
+/// This call performs blocking I/O.
 ///
 /// # Warnings
 ///
-/// This function relies on the [`wal::blocking::ReaderError::UnableToReadData`]
+/// The caller relies on the [`wal::blocking::ReaderError::UnableToReadData`]
 /// error sourced from an unexpected eof error to mean that there are no more
 /// valid completed writes which can be read from the provided `batches` and
 /// that it is safe to ignore them.
-async fn replay_file<T, F>(
-    file: F,
-    sink: &T,
-    ok_op_count_metric: &U64Counter,
-    empty_op_count_metric: &U64Counter,
-    ingest_state: &Arc<IngestState>,
-) -> Result<Option<SequenceNumber>, WalReplayError>
+fn decode_file<F>(file: F, empty_op_count_metric: &U64Counter) -> DecodedFile
 where
-    T: DmlSink,
     F: SegmentedWalOpBatchReader,
 {
+    let mut ops = Vec::new();
     let mut max_sequence = None;
-    let start = Instant::now();
     let segment_id = file.id();
 
     for batch in file {
-        let ops = batch.map_err(|e| WalReplayError::ReadEntry(e, max_sequence))?;
+        let batch = match batch {
+            Ok(v) => v,
+            Err(e) => {
+                return DecodedFile {
+                    ops,
+                    max_sequence,
+                    error: Some(WalReplayError::ReadEntry(e, max_sequence)),
+                }
+            }
+        };
 
-        for op in ops {
+        for op in batch {
             let SequencedWalOp {
                 table_write_sequence_numbers,
                 op,
@@ -321,11 +483,27 @@ where
                 Op::Persist(_) => unreachable!(),
             };
 
-            let mut op_min_sequence_number: Option<SequenceNumber> = None;
-            let mut op_max_sequence_number = None;
-
             // Reconstruct the ingest operation
-            let batches = decode_database_batch(&op)?;
+            let batches = match decode_database_batch(&op) {
+                Ok(v) => v,
+                Err(e) => {
+                    return DecodedFile {
+                        ops,
+                        max_sequence,
+                        error: Some(e.into()),
+                    }
+                }
+            };
+            let mut templates = match decode_partition_templates(&op) {
+                Ok(v) => v,
+                Err(e) => {
+                    return DecodedFile {
+                        ops,
+                        max_sequence,
+                        error: Some(e.into()),
+                    }
+                }
+            };
             let namespace_id = NamespaceId::new(op.database_id);
             let partition_key = PartitionKey::from(op.partition_key);
 
@@ -335,7 +513,7 @@ where
                 continue;
             }
 
-            let op = WriteOperation::new(
+            ops.push(WriteOperation::new(
                 namespace_id,
                 batches
                     .into_iter()
@@ -348,56 +526,118 @@ where
                         );
 
                         max_sequence = max_sequence.max(Some(sequence_number));
-                        op_min_sequence_number = op_min_sequence_number
-                            .map(|prev_sequence_number| prev_sequence_number.min(sequence_number))
-                            .or(Some(sequence_number));
-                        op_max_sequence_number = op_max_sequence_number.max(Some(sequence_number));
 
                         (
                             table_id,
-                            TableData::new(table_id, PartitionedData::new(sequence_number, v)),
+                            TableData::new(table_id, PartitionedData::new(sequence_number, v))
+                                .with_partition_template(templates.remove(&table_id)),
                         )
                     })
                     .collect(),
                 partition_key,
                 // TODO: A tracing context should be added for WAL replay.
                 None,
-            );
+            ));
+        }
+    }
 
-            loop {
-                match ingest_state.read_with_exceptions([IngestStateError::DiskFull]) {
-                    Ok(_) => break,
-                    Err(e) => {
-                        warn!(
-                            ingest_state_error=%e,
-                            wait_duration=?OP_REPLAY_BACKPRESSURE_WAIT_DURATION,
-                            "ingest state is unhealthy, waiting for ingest state to recover before replaying wal op",
-                        );
-                        tokio::time::sleep(OP_REPLAY_BACKPRESSURE_WAIT_DURATION).await;
-                    }
+    DecodedFile {
+        ops,
+        max_sequence,
+        error: None,
+    }
+}
+
+/// Apply `ops` to `sink` across (at most) `concurrency` tasks.
+///
+/// Operations are assigned to tasks by their namespace & partition key, so all
+/// the writes to a partition are applied by one task, in the order they appear
+/// in `ops`.
+async fn apply_file<T>(
+    ops: Vec<WriteOperation>,
+    sink: &Arc<T>,
+    concurrency: NonZeroUsize,
+    ok_op_count_metric: &U64Counter,
+    ingest_state: &Arc<IngestState>,
+    progress: &Arc<WalReplayProgress>,
+) -> Result<(), WalReplayError>
+where
+    T: DmlSink + 'static,
+{
+    let mut lanes = (0..concurrency.get())
+        .map(|_| Vec::new())
+        .collect::<Vec<_>>();
+    for op in ops {
+        let mut hasher = DefaultHasher::new();
+        (op.namespace(), op.partition_key()).hash(&mut hasher);
+        lanes[(hasher.finish() % concurrency.get() as u64) as usize].push(op);
+    }
+
+    let mut handles = lanes
+        .into_iter()
+        .filter(|ops| !ops.is_empty())
+        .map(|ops| {
+            tokio::spawn(apply_ops(
+                ops,
+                Arc::clone(sink),
+                ok_op_count_metric.clone(),
+                Arc::clone(ingest_state),
+                Arc::clone(progress),
+            ))
+        })
+        .collect::<Vec<_>>()
+        .into_iter();
+
+    while let Some(handle) = handles.next() {
+        if let Err(e) = handle.await.expect("wal replay task panic") {
+            // Stop applying any further ops.
+            handles.for_each(|h| h.abort());
+            return Err(e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Apply each op in `ops` to `sink` in order, waiting for the ingest state to
+/// be healthy before each.
+async fn apply_ops<T>(
+    ops: Vec<WriteOperation>,
+    sink: Arc<T>,
+    ok_op_count_metric: U64Counter,
+    ingest_state: Arc<IngestState>,
+    progress: Arc<WalReplayProgress>,
+) -> Result<(), WalReplayError>
+where
+    T: DmlSink,
+{
+    for op in ops {
+        loop {
+            match ingest_state.read_with_exceptions([IngestStateError::DiskFull]) {
+                Ok(_) => break,
+                Err(e) => {
+                    warn!(
+                        ingest_state_error=%e,
+                        wait_duration=?OP_REPLAY_BACKPRESSURE_WAIT_DURATION,
+                        "ingest state is unhealthy, waiting for ingest state to recover before replaying wal op",
+                    );
+                    tokio::time::sleep(OP_REPLAY_BACKPRESSURE_WAIT_DURATION).await;
                 }
             }
+        }
 
-            debug!(
-                ?op,
-                ?op_min_sequence_number,
-                ?op_max_sequence_number,
-                "apply wal op"
-            );
+        debug!(?op, "apply wal op");
 
-            // Apply the operation to the provided DML sink
-            sink.apply(IngestOp::Write(op))
-                .await
-                .map_err(Into::<DmlError>::into)?;
+        // Apply the operation to the provided DML sink
+        sink.apply(IngestOp::Write(op))
+            .await
+            .map_err(Into::<DmlError>::into)?;
 
-            ok_op_count_metric.inc(1);
-        }
+        ok_op_count_metric.inc(1);
+        progress.op_applied();
     }
 
-    // This file is complete, return the last observed sequence
-    // number.
-    debug!(?segment_id, "wal file replayed in {:?}", start.elapsed());
-    Ok(max_sequence)
+    Ok(())
 }
 
 #[cfg(test)]
@@ -408,7 +648,7 @@ mod tests {
     use async_trait::async_trait;
     use hashbrown::HashSet;
     use itertools::Itertools;
-    use metric::{assert_counter, Attributes};
+    use metric::{assert_counter, Attributes, Metric};
     use parking_lot::Mutex;
     use test_helpers::timeout::FutureTimeout;
     use wal::Wal;
@@ -596,18 +836,23 @@ mod tests {
                 SequenceNumber::new(1),
             )
             .unwrap();
-        let mock_iter = MockIter {
+        let mock_iter = Arc::new(MockIter {
             sink: mock_sink,
             partitions: vec![Arc::new(Mutex::new(partition))],
-        };
+        });
 
         let ingest_state = Arc::new(IngestState::default());
         let metrics = metric::Registry::default();
+        let progress = Arc::new(WalReplayProgress::default());
+        assert_eq!(progress.to_string(), "wal replay not started");
+
         let max_sequence_number = replay(
             &wal,
             &mock_iter,
             Arc::clone(&persist),
             Arc::clone(&ingest_state),
+            NonZeroUsize::new(2).unwrap(),
+            Arc::clone(&progress),
             &metrics,
         )
         .with_timeout_panic(Duration::from_secs(2))
@@ -615,6 +860,9 @@ mod tests {
         .expect("failed to replay WAL");
 
         assert_eq!(max_sequence_number, Some(SequenceNumber::new(43)));
+        assert!(progress.is_complete());
+        assert_eq!(progress.files_replayed.load(Ordering::Relaxed), 3);
+        assert_eq!(progress.ops_applied.load(Ordering::Relaxed), 3);
 
         // Assert the ops were pushed into the DmlSink exactly as generated,
         // barring the empty op which is skipped
@@ -686,6 +934,13 @@ mod tests {
             labels = Attributes::from(&[("outcome", "skipped_empty")]),
             value = 1,
         );
+        let files_total = metrics
+            .get_instrument::<Metric<U64Gauge>>("ingester_wal_replay_files_total")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from([]))
+            .expect("failed to get observer")
+            .fetch();
+        assert_eq!(files_total, 3);
     }
 
     #[derive(Debug)]
@@ -773,10 +1028,14 @@ mod tests {
     }
 
     fn arbitrary_sequenced_wal_op(id: SequenceNumber) -> SequencedWalOp {
+        sequenced_wal_op(id, &ARBITRARY_PARTITION_KEY)
+    }
+
+    fn sequenced_wal_op(id: SequenceNumber, partition_key: &PartitionKey) -> SequencedWalOp {
         use generated_types::influxdata::iox::wal::v1::sequenced_wal_op::Op as WalOp;
 
         let op = make_write_op(
-            &ARBITRARY_PARTITION_KEY,
+            partition_key,
             ARBITRARY_NAMESPACE_ID,
             &ARBITRARY_TABLE_NAME,
             ARBITRARY_TABLE_ID,
@@ -794,6 +1053,36 @@ mod tests {
         }
     }
 
+    /// Decode and apply the entries in `file` to `sink`, returning the highest
+    /// sequence number observed, or the error that ended the read.
+    async fn replay_file<T, F>(
+        file: F,
+        sink: &Arc<T>,
+        ingest_state: &Arc<IngestState>,
+    ) -> Result<Option<SequenceNumber>, WalReplayError>
+    where
+        T: DmlSink + 'static,
+        F: SegmentedWalOpBatchReader,
+    {
+        let metric = metric::Registry::default().register_metric::<U64Counter>("foo", "bar");
+
+        let file = decode_file(file, &metric.recorder(&[]));
+        apply_file(
+            file.ops,
+            sink,
+            NonZeroUsize::new(2).unwrap(),
+            &metric.recorder(&[]),
+            ingest_state,
+            &Default::default(),
+        )
+        .await?;
+
+        match file.error {
+            Some(e) => Err(e),
+            None => Ok(file.max_sequence),
+        }
+    }
+
     #[tokio::test]
     async fn test_replay_of_truncated_write_in_last_file() {
         let wal = MockWalReader::new(
@@ -825,10 +1114,10 @@ mod tests {
         // Replay the results into a mock to capture the DmlWrites and returns
         // some dummy partitions when iterated over.
         let mock_sink = MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(())]);
-        let mock_iter = MockIter {
+        let mock_iter = Arc::new(MockIter {
             sink: mock_sink,
             partitions: vec![],
-        };
+        });
         let metrics = metric::Registry::default();
 
         let max_sequence_number = replay(
@@ -836,6 +1125,8 @@ mod tests {
             &mock_iter,
             Arc::clone(&persist),
             Arc::new(IngestState::default()),
+            NonZeroUsize::new(2).unwrap(),
+            Default::default(),
             &metrics,
         )
         .await
@@ -891,10 +1182,10 @@ mod tests {
         // Replay the results into a mock to capture the DmlWrites and returns
         // some dummy partitions when iterated over.
         let mock_sink = MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(()), Ok(())]);
-        let mock_iter = MockIter {
+        let mock_iter = Arc::new(MockIter {
             sink: mock_sink,
             partitions: vec![],
-        };
+        });
         let metrics = metric::Registry::default();
 
         let replay_result = replay(
@@ -902,6 +1193,8 @@ mod tests {
             &mock_iter,
             Arc::clone(&persist),
             Arc::new(IngestState::default()),
+            NonZeroUsize::new(2).unwrap(),
+            Default::default(),
             &metrics,
         )
         .await;
@@ -920,10 +1213,66 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_replay_preserves_partition_order() {
+        const N_OPS: u64 = 50;
+
+        // Interleave writes to several partitions within a single file.
+        let ops = (1..=N_OPS)
+            .map(|i| {
+                let partition_key = PartitionKey::from(format!("p{}", i % 5));
+                sequenced_wal_op(SequenceNumber::new(i), &partition_key)
+            })
+            .collect::<Vec<_>>();
+        let wal = MockWalReader::new(
+            [MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([Ok(ops)])],
+            [1],
+        );
+
+        let mock_iter = Arc::new(MockIter {
+            sink: MockDmlSink::default()
+                .with_apply_return((0..N_OPS).map(|_| Ok(())).collect::<Vec<_>>()),
+            partitions: vec![],
+        });
+
+        let max_sequence_number = replay(
+            &wal,
+            &mock_iter,
+            Arc::new(MockPersistQueue::default()),
+            Arc::new(IngestState::default()),
+            NonZeroUsize::new(4).unwrap(),
+            Default::default(),
+            &metric::Registry::default(),
+        )
+        .with_timeout_panic(Duration::from_secs(5))
+        .await
+        .expect("failed to replay WAL");
+        assert_eq!(max_sequence_number, Some(SequenceNumber::new(N_OPS)));
+
+        // All the ops were applied, and the ops for each partition were
+        // applied in sequence number order.
+        let calls = mock_iter.sink.get_calls();
+        assert_eq!(calls.len(), N_OPS as usize);
+
+        let mut last = std::collections::HashMap::new();
+        for op in calls {
+            let w = assert_matches!(op, IngestOp::Write(w) => w);
+            let seq = w
+                .tables()
+                .next()
+                .unwrap()
+                .1
+                .partitioned_data()
+                .sequence_number();
+            if let Some(prev) = last.insert(w.partition_key().clone(), seq) {
+                assert!(prev < seq, "partition ops applied out of order");
+            }
+        }
+        assert_eq!(last.len(), 5);
+    }
+
     #[tokio::test]
     async fn test_replay_respects_ingest_state() {
-        let metrics = metric::Registry::default();
-        let metric = metrics.register_metric::<U64Counter>("foo", "bar");
         let reader = MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([
             Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(1))]),
             Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(2))]),
@@ -939,16 +1288,7 @@ mod tests {
             let mock_sink = Arc::clone(&mock_sink);
             let ingest_state = Arc::clone(&ingest_state);
 
-            tokio::spawn(async move {
-                replay_file(
-                    reader,
-                    &mock_sink,
-                    &metric.recorder(&[]),
-                    &metric.recorder(&[]),
-                    &ingest_state,
-                )
-                .await
-            })
+            tokio::spawn(async move { replay_file(reader, &mock_sink, &ingest_state).await })
         };
 
         // Sleep the test thread to yield to the file replay and give it a
@@ -974,26 +1314,18 @@ mod tests {
 
     #[tokio::test]
     async fn test_replay_continues_when_disk_full() {
-        let metrics = metric::Registry::default();
-        let metric = metrics.register_metric::<U64Counter>("foo", "bar");
         let reader = MockSegmentedWalOpBatchReader::new(SegmentId::new(1)).with_entry_results([
             Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(1))]),
             Ok(vec![arbitrary_sequenced_wal_op(SequenceNumber::new(2))]),
         ]);
-        let mock_sink = MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]);
+        let mock_sink = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]));
 
         // Construct an IngestState with `DiskFull` and ensure that the file is replayed.
         let ingest_state = Arc::new(IngestState::default());
         ingest_state.set(IngestStateError::DiskFull);
 
         assert_matches!(
-            replay_file(
-                reader,
-                &mock_sink,
-                &metric.recorder(&[]),
-                &metric.recorder(&[]),
-                &Arc::clone(&ingest_state),
-            )
+            replay_file(reader, &mock_sink, &ingest_state)
             .with_timeout_panic(Duration::from_secs(2))
             .await,
             Ok(Some(id))=> {
@@ -250,12 +250,14 @@ mod deferred_load;
 mod dml_payload;
 mod dml_sink;
 mod gossip;
+mod handoff;
 mod ingest_state;
 mod ingester_id;
 mod partition_iter;
 mod persist;
 mod query;
 mod query_adaptor;
+mod spill;
 pub(crate) mod server;
 mod timestamp_oracle;
 mod wal;
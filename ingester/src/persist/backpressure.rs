@@ -39,6 +39,10 @@ const EVALUATE_SATURATION_INTERVAL: Duration = Duration::from_secs(1);
 ///   * The number of outstanding persist jobs is less than 50% of
 ///     `persist_queue_depth`
 ///
+/// Persist jobs with all of their data spilled to disk release their queue
+/// permit and do not count towards the outstanding persist jobs, allowing the
+/// system to recover while the spilled jobs wait for a degraded object store.
+///
 /// These conditions are evaluated periodically, at the interval specified in
 /// [`EVALUATE_SATURATION_INTERVAL`].
 #[derive(Debug)]
@@ -189,7 +189,7 @@ mod tests {
     use crate::test_util::{
         ARBITRARY_NAMESPACE_ID, ARBITRARY_TABLE_ID, ARBITRARY_TRANSITION_PARTITION_ID,
     };
-    use data_types::{ColumnId, ColumnSet, ParquetFileId, SequenceNumber, Timestamp};
+    use data_types::{ColumnId, ColumnSet, ParquetFileId, SequenceNumber, StorageTier, Timestamp};
 
     fn arbitrary_file_meta() -> ParquetFile {
         ParquetFile {
@@ -207,6 +207,7 @@ mod tests {
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            storage_tier: StorageTier::Hot,
         }
     }
 
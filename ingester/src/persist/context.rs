@@ -1,16 +1,14 @@
 use std::sync::Arc;
 
 use data_types::{
-    NamespaceId, ParquetFile, PartitionKey, SortedColumnSet, TableId, TransitionPartitionId,
+    NamespaceId, ParquetFile, ParquetFileId, PartitionKey, SortedColumnSet, TableId,
+    TransitionPartitionId,
 };
 use observability_deps::tracing::*;
 use parking_lot::Mutex;
 use schema::sort::SortKey;
 use thiserror::Error;
-use tokio::{
-    sync::{oneshot, OwnedSemaphorePermit},
-    time::Instant,
-};
+use tokio::{sync::oneshot, time::Instant};
 
 use crate::{
     buffer_tree::{
@@ -19,7 +17,8 @@ use crate::{
         table::metadata::TableMetadata,
     },
     deferred_load::DeferredLoad,
-    persist::completion_observer::CompletedPersist,
+    persist::{completion_observer::CompletedPersist, queue_position::QueueTicket},
+    spill::QueuePermit,
 };
 
 use super::completion_observer::PersistCompletionObserver;
@@ -37,11 +36,15 @@ pub(super) enum PersistError {
 /// task.
 #[derive(Debug)]
 pub(super) struct PersistRequest {
-    complete: oneshot::Sender<()>,
+    complete: oneshot::Sender<ParquetFileId>,
     partition: Arc<Mutex<PartitionData>>,
     data: PersistingData,
     enqueued_at: Instant,
-    permit: OwnedSemaphorePermit,
+    permit: Arc<QueuePermit>,
+
+    /// The position of this request in the persist queue, released once a
+    /// worker starts executing it.
+    ticket: QueueTicket,
 }
 
 impl PersistRequest {
@@ -50,9 +53,10 @@ impl PersistRequest {
     pub(super) fn new(
         partition: Arc<Mutex<PartitionData>>,
         data: PersistingData,
-        permit: OwnedSemaphorePermit,
+        permit: Arc<QueuePermit>,
         enqueued_at: Instant,
-    ) -> (Self, oneshot::Receiver<()>) {
+        ticket: QueueTicket,
+    ) -> (Self, oneshot::Receiver<ParquetFileId>) {
         let (tx, rx) = oneshot::channel();
         (
             Self {
@@ -61,6 +65,7 @@ impl PersistRequest {
                 data,
                 enqueued_at,
                 permit,
+                ticket,
             },
             rx,
         )
@@ -110,8 +115,8 @@ pub(super) struct Context {
     sort_key: SortKeyState,
 
     /// A notification signal to indicate to the caller that this partition has
-    /// persisted.
-    complete: oneshot::Sender<()>,
+    /// persisted, carrying the ID of the resulting parquet file.
+    complete: oneshot::Sender<ParquetFileId>,
 
     /// Timing statistics tracking the timestamp this persist job was first
     /// enqueued, and the timestamp this [`Context`] was constructed (signifying
@@ -125,7 +130,10 @@ pub(super) struct Context {
     /// This permit MUST be retained for the entire duration of the persistence
     /// work, and MUST be released at the end of the persistence AFTER any
     /// references to the persisted data are released.
-    permit: OwnedSemaphorePermit,
+    ///
+    /// The underlying queue capacity is released early if all the data is
+    /// spilled to disk while the job waits in the queue.
+    permit: Arc<QueuePermit>,
 }
 
 impl Context {
@@ -145,8 +153,12 @@ impl Context {
                 data,
                 enqueued_at,
                 permit,
+                ticket,
             } = req;
 
+            // This job is no longer waiting in the queue.
+            drop(ticket);
+
             let p = Arc::clone(&partition);
             let guard = p.lock();
 
@@ -236,6 +248,7 @@ impl Context {
         O: PersistCompletionObserver,
     {
         let object_store_id = metadata.object_store_id;
+        let parquet_file_id = metadata.id;
 
         // Mark the partition as having completed persistence, causing it to
         // release the reference to the in-flight persistence data it is
@@ -276,7 +289,7 @@ impl Context {
         drop(self.permit);
 
         // Notify the observer of this persistence task, if any.
-        let _ = self.complete.send(());
+        let _ = self.complete.send(parquet_file_id);
     }
 
     pub(super) fn enqueued_at(&self) -> Instant {
@@ -1,9 +1,10 @@
 use std::{future, sync::Arc};
 
+use data_types::ParquetFileId;
 use futures::{stream, StreamExt};
 use observability_deps::tracing::debug;
 use parking_lot::Mutex;
-use tokio::time::Instant;
+use tokio::{sync::oneshot, time::Instant};
 
 use crate::buffer_tree::partition::PartitionData;
 
@@ -20,6 +21,31 @@ const PERSIST_ENQUEUE_CONCURRENCY: usize = 5;
 // Writes that landed into the partition buffer after this call, but before the
 // partition data is read will be included in the persisted data.
 pub(crate) async fn persist_partitions<T, P>(iter: T, persist: &P) -> usize
+where
+    T: Iterator<Item = Arc<Mutex<PartitionData>>> + Send,
+    P: PersistQueue + Clone,
+{
+    let notifications = enqueue_partitions(iter, persist).await;
+    let count = notifications.len();
+
+    // Wait for all the persist completion notifications.
+    for n in notifications {
+        n.await.expect("persist worker task panic");
+    }
+
+    count
+}
+
+/// Enqueue a set of [`PartitionData`] for persistence, returning the
+/// completion notification of each enqueued persist job without waiting for
+/// them to complete.
+///
+/// Each notification resolves to the ID of the parquet file created by the
+/// persist job. Partitions with no data to persist are skipped.
+pub(crate) async fn enqueue_partitions<T, P>(
+    iter: T,
+    persist: &P,
+) -> Vec<oneshot::Receiver<ParquetFileId>>
 where
     T: Iterator<Item = Arc<Mutex<PartitionData>>> + Send,
     P: PersistQueue + Clone,
@@ -68,12 +94,5 @@ where
         "queued all non-empty partitions for persist"
     );
 
-    let count = notifications.len();
-
-    // Wait for all the persist completion notifications.
-    for n in notifications {
-        n.await.expect("persist worker task panic");
-    }
-
-    count
+    notifications
 }
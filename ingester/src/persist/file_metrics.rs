@@ -157,7 +157,7 @@ mod tests {
     };
     use data_types::{
         sequence_number_set::SequenceNumberSet, ColumnId, ColumnSet, ParquetFile, ParquetFileId,
-        Timestamp,
+        StorageTier, Timestamp,
     };
     use metric::assert_histogram;
     use std::sync::Arc;
@@ -184,6 +184,7 @@ mod tests {
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            storage_tier: StorageTier::Hot,
         };
 
         decorator
@@ -1,6 +1,7 @@
 use std::{sync::Arc, time::Duration};
 
 use async_trait::async_trait;
+use data_types::ParquetFileId;
 use iox_catalog::interface::Catalog;
 use iox_query::{exec::Executor, QueryChunk};
 use metric::{DurationHistogram, DurationHistogramOptions, U64Counter, U64Gauge, DURATION_MAX};
@@ -17,7 +18,7 @@ use tokio::{
 use super::{
     backpressure::PersistState, column_map_resolver::ColumnMapResolver,
     completion_observer::PersistCompletionObserver, context::PersistRequest, queue::PersistQueue,
-    worker::SharedWorkerState,
+    queue_position::PersistQueuePositions, worker::SharedWorkerState,
 };
 use crate::{
     buffer_tree::partition::{persisting::PersistingData, PartitionData, SortKeyState},
@@ -144,6 +145,11 @@ pub(crate) struct PersistHandle {
     ///
     /// Before enqueuing an item into any of the (unbounded) worker queues, or
     /// (unbounded) global queue, the caller MUST obtain a semaphore permit.
+    ///
+    /// The permit of a job is released early if all of its data is spilled to
+    /// disk (see [`QueuePermit`]).
+    ///
+    /// [`QueuePermit`]: crate::spill::QueuePermit
     sem: Arc<Semaphore>,
 
     /// A global queue of persist tasks that may be executed on any worker in
@@ -165,6 +171,9 @@ pub(crate) struct PersistHandle {
 
     /// A counter tracking the number of enqueued into the persist system.
     enqueued_jobs: U64Counter,
+
+    /// The enqueue order of persist jobs not yet started by a worker.
+    queue_positions: Arc<PersistQueuePositions>,
 }
 
 impl PersistHandle {
@@ -311,9 +320,16 @@ impl PersistHandle {
             worker_tasks,
             persist_state,
             enqueued_jobs,
+            queue_positions: Default::default(),
         }
     }
 
+    /// Return the [`PersistQueuePositions`] tracking the persist jobs that
+    /// are waiting to be executed.
+    pub(crate) fn queue_positions(&self) -> Arc<PersistQueuePositions> {
+        Arc::clone(&self.queue_positions)
+    }
+
     fn assign_worker(&self, r: PersistRequest) {
         debug!(
             partition_id = %r.partition_id(),
@@ -342,7 +358,7 @@ impl PersistQueue for PersistHandle {
     /// task as complete.
     ///
     /// Once all persistence related tasks for `data` are complete, the returned
-    /// channel publishes a notification.
+    /// channel publishes the ID of the persisted parquet file.
     ///
     /// Persist tasks may be re-ordered w.r.t their submission order for
     /// performance reasons.
@@ -360,7 +376,7 @@ impl PersistQueue for PersistHandle {
         &self,
         partition: Arc<Mutex<PartitionData>>,
         data: PersistingData,
-    ) -> oneshot::Receiver<()> {
+    ) -> oneshot::Receiver<ParquetFileId> {
         let partition_id = data.partition_id().clone();
         debug!(%partition_id, "enqueuing persistence task");
 
@@ -370,6 +386,7 @@ impl PersistQueue for PersistHandle {
         // those blocked waiting for queue capacity).
         let enqueued_at = Instant::now();
         self.enqueued_jobs.inc(1);
+        let ticket = self.queue_positions.push(partition_id.clone());
 
         // Try and acquire the persist task permit immediately.
         let permit = match Arc::clone(&self.sem).try_acquire_owned() {
@@ -434,7 +451,9 @@ impl PersistQueue for PersistHandle {
 
         // Build the persist task request.
         let schema = data.schema().clone();
-        let (r, notify) = PersistRequest::new(Arc::clone(&partition), data, permit, enqueued_at);
+        let permit = data.queue_permit(permit);
+        let (r, notify) =
+            PersistRequest::new(Arc::clone(&partition), data, permit, enqueued_at, ticket);
 
         match sort_key {
             Some(v) => {
@@ -510,6 +529,7 @@ mod tests {
             completion_observer::{mock::MockCompletionObserver, NopObserver},
             tests::{assert_metric_counter, assert_metric_gauge},
         },
+        spill::SpillDir,
         test_util::{
             make_write_op, PartitionDataBuilder, ARBITRARY_NAMESPACE_ID, ARBITRARY_NAMESPACE_NAME,
             ARBITRARY_PARTITION_KEY, ARBITRARY_TABLE_ID, ARBITRARY_TABLE_NAME,
@@ -528,6 +548,7 @@ mod tests {
                     .with_partition(PartitionDataBuilder::new().with_sort_key_state(sort_key)),
             ),
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Default::default(),
         );
@@ -976,6 +997,99 @@ mod tests {
         assert_metric_counter(&metrics, "ingester_persist_enqueued_jobs", 2);
     }
 
+    /// A test that ensures persist jobs release their queue capacity once their
+    /// data is spilled to disk, allowing writes to be accepted while the
+    /// persist queue is blocked.
+    #[tokio::test]
+    async fn test_persist_spilled_releases_queue_capacity() {
+        let storage = ParquetStorage::new(Arc::new(InMemory::default()), StorageId::from("iox"));
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let ingest_state = Arc::new(IngestState::default());
+
+        let tmp = tempfile::tempdir().unwrap();
+        let spill_dir = Arc::new(SpillDir::new(tmp.path().to_path_buf(), u64::MAX));
+        let spill = |p: &Arc<Mutex<PartitionData>>| {
+            let data = p.lock().spillable_data().cloned().collect::<Vec<_>>();
+            for v in data {
+                v.spill(&spill_dir).expect("spill should succeed");
+            }
+        };
+
+        let mut handle = PersistHandle::new(
+            1,
+            1,
+            Arc::clone(&ingest_state),
+            Arc::new(Executor::new_testing()),
+            storage,
+            Arc::clone(&catalog),
+            NopObserver,
+            CatalogColumnMapResolver::new(catalog),
+            &metrics,
+        );
+
+        // Kill the workers, and replace the queues so that enqueued jobs are
+        // never executed, simulating a blocked object store.
+        handle.worker_tasks = vec![];
+
+        let (global_tx, _global_rx) = async_channel::unbounded();
+        handle.global_queue = global_tx;
+
+        let (worker1_tx, _worker1_rx) = mpsc::unbounded_channel();
+        let (worker2_tx, _worker2_rx) = mpsc::unbounded_channel();
+        handle.worker_queues = JumpHash::new([worker1_tx, worker2_tx]);
+
+        // Fill the persist queue.
+        let p1 = new_partition(SortKeyState::Provided(None, None)).await;
+        let data = p1.lock().mark_persisting().unwrap();
+        let _notify1 = handle.enqueue(Arc::clone(&p1), data).await;
+        assert_eq!(handle.sem.available_permits(), 0);
+
+        // Enqueue a second job, which blocks waiting for queue capacity.
+        let p2 = new_partition(SortKeyState::Provided(None, None)).await;
+        let data = p2.lock().mark_persisting().unwrap();
+        let fut = handle.enqueue(Arc::clone(&p2), data);
+
+        let waker = futures::task::noop_waker();
+        let mut cx = futures::task::Context::from_waker(&waker);
+        futures::pin_mut!(fut);
+
+        let poll = fut.as_mut().poll(&mut cx);
+        assert_matches!(poll, Poll::Pending);
+        assert_matches!(ingest_state.read(), Err(IngestStateError::PersistSaturated));
+
+        // Spilling the data of the first job releases its queue capacity,
+        // unblocking the second enqueue.
+        spill(&p1);
+        let _notify2 = fut.with_timeout_panic(Duration::from_secs(5)).await;
+
+        // Once the second job is spilled too, the queue has capacity and the
+        // system recovers, accepting writes while both jobs wait to be
+        // persisted.
+        spill(&p2);
+        assert_eq!(handle.sem.available_permits(), 1);
+
+        async {
+            loop {
+                if ingest_state.read().is_ok() {
+                    return;
+                }
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        // And more persist jobs can be enqueued without blocking.
+        let p3 = new_partition(SortKeyState::Provided(None, None)).await;
+        let data = p3.lock().mark_persisting().unwrap();
+        let _notify3 = handle
+            .enqueue(p3, data)
+            .with_timeout_panic(Duration::from_secs(5))
+            .await;
+        assert_matches!(ingest_state.read(), Ok(()));
+    }
+
     /// Export metrics showing the static config values.
     #[tokio::test]
     async fn test_static_config_metrics() {
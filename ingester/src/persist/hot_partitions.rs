@@ -53,6 +53,7 @@ where
             cost_estimate, "marking hot partition for persistence"
         );
 
+        guard.record_hot_persist();
         let data = guard
             .mark_persisting()
             .expect("failed to transition buffer fsm to persisting state");
@@ -144,6 +145,7 @@ mod tests {
         tokio::task::yield_now().await;
         // Assert no persist calls were made
         assert_eq!(persist_handle.calls().len(), 0);
+        assert_eq!(p.lock().hot_persistence_count(), 0);
 
         metric::assert_counter!(
             metrics,
@@ -167,15 +169,23 @@ mod tests {
                 .get_query_data(&OwnedProjection::default())
                 .expect("should have query adaptor")
         };
+        let want_query_data = want_query_data.read().await.expect("read should succeed");
 
         hot_partition_persister.observe(Arc::clone(&p), p.lock());
 
         tokio::task::yield_now().await;
         // Assert the partition was queued for persistence with the correct data.
-        assert_matches!(persist_handle.calls().as_slice(), [got] => {
-            let got_query_data = got.lock().get_query_data(&OwnedProjection::default(),).expect("should have query adaptor");
-            assert_eq!(got_query_data.record_batches(), want_query_data.record_batches());
-        });
+        let got = assert_matches!(persist_handle.calls().as_slice(), [got] => Arc::clone(got));
+        let got_query_data = got
+            .lock()
+            .get_query_data(&OwnedProjection::default())
+            .expect("should have query adaptor");
+        let got_query_data = got_query_data.read().await.expect("read should succeed");
+        assert_eq!(
+            got_query_data.record_batches(),
+            want_query_data.record_batches()
+        );
+        assert_eq!(p.lock().hot_persistence_count(), 1);
 
         metric::assert_counter!(
             metrics,
@@ -10,6 +10,7 @@ pub(crate) mod file_metrics;
 pub(crate) mod handle;
 pub(crate) mod hot_partitions;
 pub mod queue;
+pub(crate) mod queue_position;
 mod worker;
 
 #[cfg(test)]
@@ -33,6 +34,7 @@ mod tests {
         storage::{ParquetStorage, StorageId},
         ParquetFilePath,
     };
+    use schema::sort::SortKey;
     use test_helpers::{maybe_start_logging, timeout::FutureTimeout};
 
     use crate::{
@@ -74,6 +76,7 @@ mod tests {
             Arc::clone(&*ARBITRARY_TABLE_PROVIDER),
             Arc::new(CatalogPartitionResolver::new(Arc::clone(&catalog))),
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             Arc::new(MockPostWriteObserver::default()),
             Arc::new(metric::Registry::default()),
         );
@@ -230,7 +233,7 @@ mod tests {
         assert_metric_counter(&metrics, "ingester_persist_enqueued_jobs", 1);
 
         // Wait for the persist to complete.
-        notify
+        let parquet_file_id = notify
             .with_timeout(Duration::from_secs(10))
             .await
             .expect("timeout waiting for completion notification")
@@ -281,6 +284,7 @@ mod tests {
 
         // Validate a single file was inserted with the expected properties.
         let (object_store_id, file_size_bytes) = assert_matches!(&*files, [ParquetFile {
+                id: got_id,
                 namespace_id: got_namespace_id,
                 table_id: got_table_id,
                 partition_id: got_partition_id,
@@ -295,6 +299,9 @@ mod tests {
             {
                 assert_eq!(created_at.get(), max_l0_created_at.get());
 
+                // The completion notification carries the ID of the new file.
+                assert_eq!(got_id, &parquet_file_id);
+
                 assert_eq!(got_namespace_id, &namespace_id);
                 assert_eq!(got_table_id, &table_id);
                 assert_eq!(got_partition_id, &partition_id);
@@ -518,4 +525,127 @@ mod tests {
 
         assert_eq!(file.size, *file_size_bytes as usize);
     }
+
+    /// An integration test covering a catalog sort key rewritten by the
+    /// compactor, which a cached sort key needing no update must not ignore.
+    #[tokio::test]
+    async fn test_persist_integration_rewritten_sort_key() {
+        maybe_start_logging();
+
+        let object_storage: Arc<dyn ObjectStore> = Arc::new(InMemory::default());
+        let storage = ParquetStorage::new(Arc::clone(&object_storage), StorageId::from("iox"));
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(Arc::clone(&metrics)));
+        let column_map_resolver = CatalogColumnMapResolver::new(Arc::clone(&catalog));
+        let ingest_state = Arc::new(IngestState::default());
+        let completion_observer = Arc::new(MockCompletionObserver::default());
+
+        // Initialise the persist system.
+        let handle = PersistHandle::new(
+            1,
+            2,
+            Arc::clone(&ingest_state),
+            Arc::new(Executor::new_testing()),
+            storage,
+            Arc::clone(&catalog),
+            Arc::clone(&completion_observer),
+            column_map_resolver,
+            &metrics,
+        );
+
+        // Generate a partition with data
+        let partition = partition_with_write(Arc::clone(&catalog)).await;
+        let table_id = partition.lock().table_id();
+        let partition_id = partition.lock().partition_id().clone();
+
+        let columns = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(table_id)
+            .await
+            .expect("query for columns failed");
+        let column_id = |name: &str| {
+            columns
+                .iter()
+                .find(|c| c.name == name)
+                .expect("column not found")
+                .id
+                .get()
+        };
+        let (region, time) = (column_id("region"), column_id("time"));
+
+        // Set the sort key of the partition, and cache it.
+        catalog
+            .repositories()
+            .await
+            .partitions()
+            .cas_sort_key(
+                &partition_id,
+                None,
+                None,
+                &["region", "time"],
+                &SortedColumnSet::from([region, time]),
+            )
+            .await
+            .expect("failed to set catalog sort key");
+        partition.lock().update_sort_key(
+            Some(SortKey::from_columns(["region", "time"])),
+            SortedColumnSet::from([region, time]),
+        );
+
+        // Reorder the sort key in the catalog, as a sort key rewrite does.
+        catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .rewrite_sort_key(
+                &partition_id,
+                &SortedColumnSet::from([region, time]),
+                &["time", "region"],
+                &SortedColumnSet::from([time, region]),
+                &[],
+                &[],
+            )
+            .await
+            .expect("failed to rewrite catalog sort key");
+
+        // Persist the data, which needs no sort key update.
+        let data = partition
+            .lock()
+            .mark_persisting()
+            .expect("partition with write should transition to persisting");
+        handle
+            .enqueue(Arc::clone(&partition), data)
+            .await
+            .with_timeout(Duration::from_secs(10))
+            .await
+            .expect("timeout waiting for completion notification")
+            .expect("worker task failed");
+
+        // The persisted data is sorted by the rewritten sort key.
+        assert_matches!(partition.lock().sort_key(), SortKeyState::Provided(Some(sort_key), Some(sort_key_ids)) => {
+            let sort_key_columns = sort_key.to_columns().collect::<Vec<_>>();
+            assert_eq!(sort_key_columns, &["time", "region"]);
+            assert_eq!(sort_key_ids, &SortedColumnSet::from([time, region]));
+        });
+        let files = catalog
+            .repositories()
+            .await
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .expect("query for parquet files failed");
+        assert_eq!(files.len(), 1);
+
+        // The file sorted by the cached sort key was regenerated.
+        let uploaded = object_storage
+            .list(None)
+            .await
+            .expect("listing object storage failed")
+            .try_collect::<Vec<_>>()
+            .await
+            .expect("failed to list object store files");
+        assert_eq!(uploaded.len(), 2, "expected two uploaded files");
+    }
 }
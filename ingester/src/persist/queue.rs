@@ -3,6 +3,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
+use data_types::ParquetFileId;
 use parking_lot::Mutex;
 use tokio::sync::oneshot;
 
@@ -20,11 +21,14 @@ use crate::buffer_tree::partition::{persisting::PersistingData, PartitionData};
 pub trait PersistQueue: Send + Sync + Debug {
     /// Place `data` from `partition` into the persistence queue,
     /// (asynchronously) blocking until enqueued.
+    ///
+    /// The returned channel resolves to the ID of the persisted parquet file
+    /// once the persist job is complete.
     async fn enqueue(
         &self,
         partition: Arc<Mutex<PartitionData>>,
         data: PersistingData,
-    ) -> oneshot::Receiver<()>;
+    ) -> oneshot::Receiver<ParquetFileId>;
 }
 
 #[async_trait]
@@ -37,7 +41,7 @@ where
         &self,
         partition: Arc<Mutex<PartitionData>>,
         data: PersistingData,
-    ) -> oneshot::Receiver<()> {
+    ) -> oneshot::Receiver<ParquetFileId> {
         (**self).enqueue(partition, data).await
     }
 }
@@ -55,8 +59,8 @@ pub(crate) mod mock {
     use std::{sync::Arc, time::Duration};
 
     use data_types::{
-        ColumnId, ColumnSet, NamespaceId, ParquetFile, ParquetFileId, PartitionHashId,
-        PartitionKey, TableId, Timestamp, TransitionPartitionId,
+        ColumnId, ColumnSet, NamespaceId, ParquetFile, PartitionHashId, PartitionKey, StorageTier,
+        TableId, Timestamp, TransitionPartitionId,
     };
     use test_helpers::timeout::FutureTimeout;
     use tokio::task::JoinHandle;
@@ -139,7 +143,7 @@ pub(crate) mod mock {
             &self,
             partition: Arc<Mutex<PartitionData>>,
             data: PersistingData,
-        ) -> oneshot::Receiver<()> {
+        ) -> oneshot::Receiver<ParquetFileId> {
             let (tx, rx) = oneshot::channel();
 
             let mut guard = self.state.lock();
@@ -177,11 +181,12 @@ pub(crate) mod mock {
                             created_at: Timestamp::new(1234),
                             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
                             max_l0_created_at: Timestamp::new(42),
+                            storage_tier: StorageTier::Hot,
                         },
                         sequence_numbers,
                     )))
                     .await;
-                let _ = tx.send(());
+                let _ = tx.send(ParquetFileId::new(42));
             }));
 
             rx
@@ -0,0 +1,106 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::Arc,
+};
+
+use data_types::TransitionPartitionId;
+use parking_lot::Mutex;
+
+/// Tracks the order in which persist jobs were enqueued, for the jobs that are
+/// yet to be picked up by a persist worker.
+///
+/// Jobs waiting for capacity in the persist queue are included, as they are
+/// logically part of the persist queue.
+///
+/// Because persist jobs may be reordered w.r.t their enqueue order, the
+/// positions reported are an approximation of when a job will be executed.
+#[derive(Debug, Default)]
+pub(crate) struct PersistQueuePositions {
+    state: Mutex<State>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_seq: u64,
+    queued: BTreeMap<u64, TransitionPartitionId>,
+}
+
+impl PersistQueuePositions {
+    /// Record a persist job for `partition_id` as queued, returning a
+    /// [`QueueTicket`] that removes the job from the queue when dropped.
+    pub(crate) fn push(self: &Arc<Self>, partition_id: TransitionPartitionId) -> QueueTicket {
+        let mut state = self.state.lock();
+        let seq = state.next_seq;
+        state.next_seq += 1;
+        state.queued.insert(seq, partition_id);
+
+        QueueTicket {
+            positions: Arc::clone(self),
+            seq,
+        }
+    }
+
+    /// Return the position of the oldest queued persist job of each partition
+    /// with queued jobs, where a position of 0 is the next job to be executed.
+    pub(crate) fn positions(&self) -> HashMap<TransitionPartitionId, u64> {
+        let state = self.state.lock();
+
+        let mut positions = HashMap::with_capacity(state.queued.len());
+        for (pos, id) in state.queued.values().enumerate() {
+            positions.entry(id.clone()).or_insert(pos as u64);
+        }
+        positions
+    }
+}
+
+/// A handle to a queued persist job in [`PersistQueuePositions`].
+///
+/// The job is removed from the queue when this ticket is dropped, either
+/// because a worker started the job or the enqueue was aborted.
+#[derive(Debug)]
+pub(crate) struct QueueTicket {
+    positions: Arc<PersistQueuePositions>,
+    seq: u64,
+}
+
+impl Drop for QueueTicket {
+    fn drop(&mut self) {
+        self.positions.state.lock().queued.remove(&self.seq);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::PartitionId;
+
+    use super::*;
+
+    #[test]
+    fn test_positions() {
+        let a = TransitionPartitionId::Deprecated(PartitionId::new(1));
+        let b = TransitionPartitionId::Deprecated(PartitionId::new(2));
+
+        let positions = Arc::new(PersistQueuePositions::default());
+        assert!(positions.positions().is_empty());
+
+        let a1 = positions.push(a.clone());
+        let b1 = positions.push(b.clone());
+        let a2 = positions.push(a.clone());
+
+        // The oldest queued job of each partition is reported.
+        let got = positions.positions();
+        assert_eq!(got.len(), 2);
+        assert_eq!(got[&a], 0);
+        assert_eq!(got[&b], 1);
+
+        // Starting the first job moves the rest up the queue.
+        drop(a1);
+        let got = positions.positions();
+        assert_eq!(got[&b], 0);
+        assert_eq!(got[&a], 1);
+
+        drop(b1);
+        drop(a2);
+        assert!(positions.positions().is_empty());
+    }
+}
@@ -64,6 +64,12 @@ pub(super) struct SharedWorkerState<O, C> {
 ///               │ADD PARQUET│
 ///               │TO CATALOG │
 ///               └─────┬─────┘
+///              _______▽________     ┌────────────┐
+///             ╱                ╲    │RESTART WITH│
+///            ╱ SAW REWRITTEN    ╲___│NEW SORT KEY│
+///            ╲ SORT KEY?        ╱yes└────────────┘
+///             ╲________________╱
+///                     │no
 ///             ┌───────▽──────┐
 ///             │NOTIFY PERSIST│
 ///             │JOB COMPLETE  │
@@ -130,16 +136,33 @@ pub(super) async fn run_task<O, C>(
         // operation; if this update fails due to a concurrent sort key update,
         // the compaction must be redone with the new sort key and uploaded
         // before continuing.
-        let parquet_table_data = loop {
-            match compact_and_upload(&mut ctx, &worker_state).await {
+        //
+        // The newly uploaded parquet file is then made visible to other nodes,
+        // which only succeeds if the catalog sort key still orders the data
+        // the same - a compactor may have rewritten it with a reordered sort
+        // key in the meantime, in which case the persist is also redone.
+        let parquet_file = loop {
+            let (parquet_table_data, sort_key_ids) =
+                match compact_and_upload(&mut ctx, &worker_state).await {
+                    Ok(v) => v,
+                    Err(PersistError::ConcurrentSortKeyUpdate(_sort_key, _sort_key_ids)) => {
+                        continue
+                    }
+                };
+
+            match update_catalog_parquet(
+                &mut ctx,
+                &worker_state,
+                &parquet_table_data,
+                &sort_key_ids,
+            )
+            .await
+            {
                 Ok(v) => break v,
                 Err(PersistError::ConcurrentSortKeyUpdate(_sort_key, _sort_key_ids)) => continue,
             };
         };
 
-        // Make the newly uploaded parquet file visible to other nodes.
-        let parquet_file = update_catalog_parquet(&ctx, &worker_state, &parquet_table_data).await;
-
         // And finally mark the persist job as complete and notify any
         // observers.
         ctx.mark_complete(parquet_file, &worker_state.completion_observer)
@@ -161,6 +184,9 @@ pub(super) async fn run_task<O, C>(
 /// update the sort key in the catalog. This MAY fail because another node has
 /// concurrently done the same and the persist must be restarted.
 ///
+/// Returns the parquet file metadata along with the sort key IDs the data was
+/// sorted by.
+///
 /// See <https://github.com/influxdata/influxdb_iox/issues/6439>.
 ///
 /// [`PersistingData`]:
@@ -168,7 +194,7 @@ pub(super) async fn run_task<O, C>(
 async fn compact_and_upload<O, C>(
     ctx: &mut Context,
     worker_state: &SharedWorkerState<O, C>,
-) -> Result<ParquetFileParams, PersistError>
+) -> Result<(ParquetFileParams, SortedColumnSet), PersistError>
 where
     O: Send + Sync,
     C: ColumnMapResolver,
@@ -194,20 +220,23 @@ where
     let (sort_key_update, parquet_table_data) =
         upload(ctx, worker_state, compacted, &column_map).await;
 
-    if let Some(sort_key_update) = sort_key_update {
-        update_catalog_sort_key(
-            ctx,
-            worker_state,
-            sort_key,        // Old sort key prior to this persist job
-            sort_key_ids,    // Corresponding old sort key IDs prior to this persist job
-            sort_key_update, // New sort key updated by this persist job
-            parquet_table_data.object_store_id,
-            &column_map,
-        )
-        .await?
-    }
+    let sort_key_ids = match sort_key_update {
+        Some(sort_key_update) => {
+            update_catalog_sort_key(
+                ctx,
+                worker_state,
+                sort_key,        // Old sort key prior to this persist job
+                sort_key_ids,    // Corresponding old sort key IDs prior to this persist job
+                sort_key_update, // New sort key updated by this persist job
+                parquet_table_data.object_store_id,
+                &column_map,
+            )
+            .await?
+        }
+        None => sort_key_ids.unwrap_or_default(),
+    };
 
-    Ok(parquet_table_data)
+    Ok((parquet_table_data, sort_key_ids))
 }
 
 /// Compact the data in `ctx` using sorted by the sort key returned from
@@ -232,7 +261,15 @@ where
         "compacting partition"
     );
 
-    assert!(!ctx.data().record_batches().is_empty());
+    // Obtain the data to be persisted, reading it from local disk if it was
+    // spilled while waiting in the queue.
+    let data = Backoff::new(&Default::default())
+        .retry_all_errors("read spilled persisting data", || {
+            ctx.data().query_adaptor()
+        })
+        .await
+        .expect("retry forever");
+    assert!(!data.record_batches().is_empty());
 
     // Run a compaction sort the data and resolve any duplicate values.
     //
@@ -242,7 +279,7 @@ where
         &worker_state.exec,
         sort_key,
         ctx.table().get().await.name().clone(),
-        ctx.data().query_adaptor(),
+        data,
     )
     .await
 }
@@ -344,6 +381,8 @@ where
 /// this method updates the sort key in `ctx` to reflect the newly observed
 /// value and returns [`PersistError::ConcurrentSortKeyUpdate`] to the caller.
 ///
+/// On success the new sort key IDs are returned.
+///
 /// For now we provide both old_sort_key and old_sort_key_ids to the function.
 /// In near future, when the sort_key field is removed from the partition,
 /// we will remove old_sort_key here and only keep old_sort_key_ids.
@@ -359,7 +398,7 @@ async fn update_catalog_sort_key<O, C>(
     new_sort_key: SortKey,
     object_store_id: Uuid,
     columns: &ColumnsByName,
-) -> Result<(), PersistError>
+) -> Result<SortedColumnSet, PersistError>
 where
     O: Send + Sync,
     C: Send + Sync,
@@ -503,6 +542,8 @@ where
                 ?new_sort_key_ids,
                 "adjusted partition sort key"
             );
+
+            Ok(new_sort_key_ids)
         }
         Err(PersistError::ConcurrentSortKeyUpdate(new_sort_key, new_sort_key_ids)) => {
             // Update the cached sort key in the Context (which pushes it
@@ -511,21 +552,31 @@ where
             ctx.set_partition_sort_key(new_sort_key.clone(), new_sort_key_ids.clone())
                 .await;
 
-            return Err(PersistError::ConcurrentSortKeyUpdate(
+            Err(PersistError::ConcurrentSortKeyUpdate(
                 new_sort_key,
                 new_sort_key_ids,
-            ));
+            ))
         }
     }
-
-    Ok(())
 }
 
+/// Add the uploaded parquet file to the catalog, provided the catalog sort key
+/// of the partition still starts with the `sort_key_ids` the data was sorted
+/// by.
+///
+/// # Concurrent Updates
+///
+/// The compactor may rewrite a partition with a reordered sort key at any
+/// time, which a cached sort key that needed no extension would not observe.
+/// If the catalog sort key no longer starts with `sort_key_ids`, the file is
+/// not added, the sort key in `ctx` is updated to the newly observed value and
+/// [`PersistError::ConcurrentSortKeyUpdate`] is returned to the caller.
 async fn update_catalog_parquet<O, C>(
-    ctx: &Context,
+    ctx: &mut Context,
     worker_state: &SharedWorkerState<O, C>,
     parquet_table_data: &ParquetFileParams,
-) -> ParquetFile
+    sort_key_ids: &SortedColumnSet,
+) -> Result<ParquetFile, PersistError>
 where
     O: Send + Sync,
     C: Send + Sync,
@@ -551,14 +602,51 @@ where
     //
     // This has the effect of allowing the queriers to "discover" the
     // parquet file by polling / querying the catalog.
-    let file = Backoff::new(&Default::default())
-        .retry_all_errors("add parquet file to catalog", || async {
-            let mut repos = worker_state.catalog.repositories().await;
-            let parquet_file = repos
-                .parquet_files()
-                .create(parquet_table_data.clone())
-                .await?;
+    let result = Backoff::new(&Default::default())
+        .retry_with_backoff("add parquet file to catalog", || {
+            let catalog = Arc::clone(&worker_state.catalog);
+            let ctx = &ctx;
+            async move {
+                let mut repos = catalog.repositories().await;
+                match repos
+                    .parquet_files()
+                    .create_if_sort_key(parquet_table_data.clone(), sort_key_ids)
+                    .await
+                {
+                    Ok(parquet_file) => ControlFlow::Break(Ok(parquet_file)),
+                    Err(iox_catalog::interface::Error::ParquetFileSortKeyConflict {
+                        sort_key: observed_sort_key,
+                        sort_key_ids: observed_sort_key_ids,
+                        ..
+                    }) => {
+                        warn!(
+                            %object_store_id,
+                            namespace_id = %ctx.namespace_id(),
+                            namespace_name = %ctx.namespace_name(),
+                            table_id = %ctx.table_id(),
+                            table = %ctx.table(),
+                            partition_id = %ctx.partition_id(),
+                            partition_key = %ctx.partition_key(),
+                            ?sort_key_ids,
+                            ?observed_sort_key_ids,
+                            "detected rewritten sort key, regenerating parquet"
+                        );
+                        // Stop the retry loop with an error containing the
+                        // newly observed sort key.
+                        ControlFlow::Break(Err(PersistError::ConcurrentSortKeyUpdate(
+                            observed_sort_key.map(SortKey::from_columns),
+                            observed_sort_key_ids,
+                        )))
+                    }
+                    Err(e) => ControlFlow::Continue(e),
+                }
+            }
+        })
+        .await
+        .expect("retry forever");
 
+    match result {
+        Ok(file) => {
             debug!(
                 namespace_id = %ctx.namespace_id(),
                 namespace_name = %ctx.namespace_name(),
@@ -568,18 +656,26 @@ where
                 partition_key = %ctx.partition_key(),
                 %object_store_id,
                 ?parquet_table_data,
-                parquet_file_id=?parquet_file.id,
+                parquet_file_id=?file.id,
                 "parquet file added to catalog"
             );
 
-            // compiler insisted on getting told the type of the error :shrug:
-            Ok(parquet_file) as Result<ParquetFile, iox_catalog::interface::Error>
-        })
-        .await
-        .expect("retry forever");
+            // A newly created file should never be marked for deletion.
+            assert!(file.to_delete.is_none());
 
-    // A newly created file should never be marked for deletion.
-    assert!(file.to_delete.is_none());
+            Ok(file)
+        }
+        Err(PersistError::ConcurrentSortKeyUpdate(new_sort_key, new_sort_key_ids)) => {
+            // Update the cached sort key in the Context (which pushes it
+            // through into the PartitionData also) to reflect the newly
+            // observed value for the next attempt.
+            ctx.set_partition_sort_key(new_sort_key.clone(), new_sort_key_ids.clone())
+                .await;
 
-    file
+            Err(PersistError::ConcurrentSortKeyUpdate(
+                new_sort_key,
+                new_sort_key_ids,
+            ))
+        }
+    }
 }
@@ -3,7 +3,7 @@ use mutable_batch::MutableBatch;
 use schema::SchemaBuilder;
 
 /// The private inner type to prevent callers from constructing an empty Subset.
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 enum Projection {
     /// Return all columns.
     #[default]
@@ -21,7 +21,7 @@ enum Projection {
 /// Specify the set of columns to project during a query.
 ///
 /// Defaults to "all columns".
-#[derive(Debug, Default)]
+#[derive(Debug, Default, Clone)]
 pub(crate) struct OwnedProjection(Projection);
 
 impl From<Vec<String>> for OwnedProjection {
@@ -7,9 +7,15 @@ use std::pin::Pin;
 use futures::{Stream, StreamExt};
 
 use super::partition_response::PartitionResponse;
+use crate::spill::SpillError;
 
 /// Stream of partitions in this response.
-pub(crate) struct PartitionStream(Pin<Box<dyn Stream<Item = PartitionResponse> + Send>>);
+///
+/// Reading the data of a partition fails if the data was spilled to disk and
+/// cannot be read back.
+pub(crate) struct PartitionStream(
+    Pin<Box<dyn Stream<Item = Result<PartitionResponse, SpillError>> + Send>>,
+);
 
 impl std::fmt::Debug for PartitionStream {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
@@ -20,7 +26,7 @@ impl std::fmt::Debug for PartitionStream {
 impl PartitionStream {
     pub(crate) fn new<T>(s: T) -> Self
     where
-        T: Stream<Item = PartitionResponse> + Send + 'static,
+        T: Stream<Item = Result<PartitionResponse, SpillError>> + Send + 'static,
     {
         Self(s.boxed())
     }
@@ -43,7 +49,9 @@ impl QueryResponse {
     }
 
     /// Return the stream of [`PartitionResponse`].
-    pub(crate) fn into_partition_stream(self) -> impl Stream<Item = PartitionResponse> {
+    pub(crate) fn into_partition_stream(
+        self,
+    ) -> impl Stream<Item = Result<PartitionResponse, SpillError>> {
         self.partitions.0
     }
 }
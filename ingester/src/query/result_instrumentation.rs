@@ -61,10 +61,13 @@ use pin_project::{pin_project, pinned_drop};
 use predicate::Predicate;
 use trace::span::Span;
 
-use crate::query::{
-    partition_response::PartitionResponse,
-    response::{PartitionStream, QueryResponse},
-    QueryError, QueryExec,
+use crate::{
+    query::{
+        partition_response::PartitionResponse,
+        response::{PartitionStream, QueryResponse},
+        QueryError, QueryExec,
+    },
+    spill::SpillError,
 };
 
 use super::projection::OwnedProjection;
@@ -318,7 +321,7 @@ where
 
 impl<S, P> Stream for QueryMetricContext<S, P>
 where
-    S: Stream<Item = PartitionResponse> + Send,
+    S: Stream<Item = Result<PartitionResponse, SpillError>> + Send,
     P: TimeProvider,
 {
     type Item = S::Item;
@@ -327,7 +330,8 @@ where
         let this = self.project();
 
         match this.inner.poll_next(cx) {
-            Poll::Ready(Some(p)) => {
+            Poll::Ready(Some(Err(e))) => Poll::Ready(Some(Err(e))),
+            Poll::Ready(Some(Ok(p))) => {
                 // Instrument the RecordBatch stream in this partition.
                 *this.partition_count += 1;
 
@@ -345,7 +349,7 @@ where
                 this.record_batch_count
                     .fetch_add(data.len(), Ordering::Relaxed);
 
-                Poll::Ready(Some(PartitionResponse::new(data, id, persist_count)))
+                Poll::Ready(Some(Ok(PartitionResponse::new(data, id, persist_count))))
             }
             Poll::Ready(None) => {
                 // Record the wall clock timestamp of the stream end.
@@ -447,11 +451,11 @@ mod tests {
         let metrics = metric::Registry::default();
 
         // Construct a stream with no batches.
-        let stream = PartitionStream::new(stream::iter([PartitionResponse::new(
+        let stream = PartitionStream::new(stream::iter([Ok(PartitionResponse::new(
             vec![],
             ARBITRARY_TRANSITION_PARTITION_ID.clone(),
             42,
-        )]));
+        ))]));
 
         let mock_time = Arc::new(MockProvider::new(Time::MIN));
         let mock_inner = MockQueryExec::default().with_result(Ok(QueryResponse::new(stream)));
@@ -732,7 +736,11 @@ mod tests {
         mock_time.inc(TIME_STEP);
 
         let mut response = response.into_partition_stream();
-        let got = response.next().await.expect("should yield first batch");
+        let got = response
+            .next()
+            .await
+            .expect("should yield first batch")
+            .expect("partition read should succeed");
         drop(response);
 
         let batches = got.into_record_batches();
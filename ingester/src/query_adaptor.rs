@@ -14,6 +14,11 @@ use iox_query::{
 use once_cell::sync::OnceCell;
 use schema::{merge::merge_record_batch_schemas, sort::SortKey, Schema};
 
+use crate::{
+    query::projection::OwnedProjection,
+    spill::{PendingBatches, SpillError},
+};
+
 /// A queryable wrapper over a set of ordered [`RecordBatch`] snapshot from a
 /// single [`PartitionData`].
 ///
@@ -99,6 +104,73 @@ impl QueryAdaptor {
     }
 }
 
+/// The data of a single [`PartitionData`] obtained without performing any
+/// I/O, which may include data spilled to local disk that must be read back
+/// to construct a [`QueryAdaptor`].
+///
+/// This allows the partition data to be obtained while holding the partition
+/// lock, and read once the lock has been released.
+///
+/// [`PartitionData`]: crate::buffer_tree::partition::PartitionData
+#[derive(Debug)]
+pub(crate) struct PendingQueryAdaptor {
+    partition_id: TransitionPartitionId,
+    data: PendingBatches,
+    projection: OwnedProjection,
+
+    /// The row count and timestamp summary the data is expected to have once
+    /// read, used to validate invariants in debug builds.
+    rows: usize,
+    ts_min_max: Option<TimestampMinMax>,
+}
+
+impl PendingQueryAdaptor {
+    /// Construct a [`PendingQueryAdaptor`] over `data`, which will have
+    /// `projection` applied to any spilled data once read.
+    ///
+    /// The caller MUST ensure `data` contains at least one row.
+    pub(crate) fn new(
+        partition_id: TransitionPartitionId,
+        data: PendingBatches,
+        projection: OwnedProjection,
+        rows: usize,
+        ts_min_max: Option<TimestampMinMax>,
+    ) -> Self {
+        debug_assert!(!data.is_empty());
+
+        Self {
+            partition_id,
+            data,
+            projection,
+            rows,
+            ts_min_max,
+        }
+    }
+
+    /// Returns the partition identifier from which the data was sourced from.
+    pub(crate) fn partition_id(&self) -> &TransitionPartitionId {
+        &self.partition_id
+    }
+
+    /// Read the data, including any spilled data, into a [`QueryAdaptor`].
+    pub(crate) async fn read(self) -> Result<QueryAdaptor, SpillError> {
+        let data = self.data.read(&self.projection).await?;
+        let q = QueryAdaptor::new(self.partition_id, data);
+
+        // Invariant: the number of rows returned in a query MUST always match
+        // the row count reported by the partition.
+        //
+        // The row count is never affected by projection.
+        debug_assert_eq!(q.num_rows(), self.rows);
+
+        // Invariant: the timestamp min/max MUST match the values reported by
+        // the partition, iff the projection contains the "time" column.
+        debug_assert_eq!(q.ts_min_max(), self.ts_min_max);
+
+        Ok(q)
+    }
+}
+
 impl QueryChunk for QueryAdaptor {
     fn stats(&self) -> Arc<Statistics> {
         Arc::clone(self.stats.get_or_init(|| {
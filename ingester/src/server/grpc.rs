@@ -1,13 +1,16 @@
 //! gRPC service implementations for `ingester`.
 
+mod buffer;
+mod handoff;
 mod persist;
 mod query;
 mod rpc_write;
 
-use std::{fmt::Debug, sync::Arc};
+use std::{fmt::Debug, path::PathBuf, sync::Arc};
 
 use iox_catalog::interface::Catalog;
 use service_grpc_catalog::CatalogService;
+use wal::Keyring;
 
 use crate::{
     dml_sink::DmlSink,
@@ -15,12 +18,14 @@ use crate::{
     ingester_id::IngesterId,
     init::IngesterRpcInterface,
     partition_iter::PartitionIter,
-    persist::queue::PersistQueue,
+    persist::{queue::PersistQueue, queue_position::PersistQueuePositions},
     query::{response::QueryResponse, QueryExec},
     timestamp_oracle::TimestampOracle,
 };
 
-use self::{persist::PersistHandler, rpc_write::RpcWrite};
+use self::{
+    buffer::BufferHandler, handoff::HandoffHandler, persist::PersistHandler, rpc_write::RpcWrite,
+};
 
 /// This type is responsible for injecting internal dependencies that SHOULD NOT
 /// leak outside of the ingester crate into public gRPC handlers.
@@ -38,6 +43,10 @@ pub(crate) struct GrpcDelegate<D, Q, T, P> {
     metrics: Arc<metric::Registry>,
     buffer: Arc<T>,
     persist_handle: Arc<P>,
+    persist_queue_positions: Arc<PersistQueuePositions>,
+    handoff_staging_dir: PathBuf,
+    wal_keyring: Keyring,
+    persist_hot_partition_cost: usize,
 }
 
 impl<D, Q, T, P> GrpcDelegate<D, Q, T, P>
@@ -59,6 +68,10 @@ where
         metrics: Arc<metric::Registry>,
         buffer: Arc<T>,
         persist_handle: Arc<P>,
+        persist_queue_positions: Arc<PersistQueuePositions>,
+        handoff_staging_dir: PathBuf,
+        wal_keyring: Keyring,
+        persist_hot_partition_cost: usize,
     ) -> Self {
         Self {
             dml_sink,
@@ -70,6 +83,10 @@ where
             metrics,
             buffer,
             persist_handle,
+            persist_queue_positions,
+            handoff_staging_dir,
+            wal_keyring,
+            persist_hot_partition_cost,
         }
     }
 }
@@ -87,6 +104,8 @@ where
     type WriteHandler = RpcWrite<Arc<D>>;
     type PersistHandler = PersistHandler<Arc<T>, Arc<P>>;
     type FlightHandler = query::FlightService<Arc<Q>>;
+    type HandoffHandler = HandoffHandler<Arc<D>, Arc<T>, Arc<P>>;
+    type BufferHandler = BufferHandler<Arc<T>>;
 
     /// Acquire a [`CatalogService`] gRPC service implementation.
     ///
@@ -117,6 +136,33 @@ where
         )
     }
 
+    /// Return a [`HandoffService`] gRPC implementation.
+    ///
+    /// [`HandoffService`]: generated_types::influxdata::iox::ingester::v1::handoff_service_server::HandoffService.
+    fn handoff_service(&self) -> Self::HandoffHandler {
+        HandoffHandler::new(
+            Arc::clone(&self.dml_sink),
+            Arc::clone(&self.buffer),
+            Arc::clone(&self.persist_handle),
+            Arc::clone(&self.timestamp),
+            Arc::clone(&self.ingest_state),
+            self.handoff_staging_dir.clone(),
+            self.wal_keyring.clone(),
+            &self.metrics,
+        )
+    }
+
+    /// Return a [`BufferService`] gRPC implementation.
+    ///
+    /// [`BufferService`]: generated_types::influxdata::iox::ingester::v1::buffer_service_server::BufferService.
+    fn buffer_service(&self) -> Self::BufferHandler {
+        BufferHandler::new(
+            Arc::clone(&self.buffer),
+            Arc::clone(&self.persist_queue_positions),
+            self.persist_hot_partition_cost,
+        )
+    }
+
     /// Return an Arrow [`FlightService`] gRPC implementation.
     ///
     /// [`FlightService`]: arrow_flight::flight_service_server::FlightService
@@ -0,0 +1,298 @@
+use std::{sync::Arc, time::Instant};
+
+use generated_types::influxdata::iox::ingester::v1::{
+    self as proto, buffer_service_server::BufferService, PartitionOrder,
+};
+use tonic::{Request, Response, Status};
+
+use crate::{partition_iter::PartitionIter, persist::queue_position::PersistQueuePositions};
+
+/// A gRPC [`BufferService`] handler.
+///
+/// This handler exposes a point-in-time snapshot of the state of each
+/// partition buffered in memory, for debugging purposes.
+#[derive(Debug)]
+pub(crate) struct BufferHandler<T> {
+    buffer: T,
+
+    /// The enqueue order of persist jobs waiting to be executed.
+    queue_positions: Arc<PersistQueuePositions>,
+
+    /// The persist cost estimate at which a partition is persisted as a "hot"
+    /// partition.
+    hot_partition_threshold: usize,
+}
+
+impl<T> BufferHandler<T>
+where
+    T: PartitionIter + Sync + 'static,
+{
+    pub(crate) fn new(
+        buffer: T,
+        queue_positions: Arc<PersistQueuePositions>,
+        hot_partition_threshold: usize,
+    ) -> Self {
+        Self {
+            buffer,
+            queue_positions,
+            hot_partition_threshold,
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<T> BufferService for BufferHandler<T>
+where
+    T: PartitionIter + Sync + 'static,
+{
+    /// List the non-empty partitions in the buffer.
+    ///
+    /// Each partition is locked in turn to read its state, so the returned
+    /// set of partitions is not an atomic snapshot of the whole buffer.
+    async fn list_partitions(
+        &self,
+        request: Request<proto::ListPartitionsRequest>,
+    ) -> Result<Response<proto::ListPartitionsResponse>, Status> {
+        let request = request.into_inner();
+        let order = request.order();
+
+        let now = Instant::now();
+        let queue_positions = self.queue_positions.positions();
+        let mut partitions = self
+            .buffer
+            .partition_iter()
+            .filter_map(|p| {
+                let p = p.lock();
+
+                if p.is_empty()
+                    || request
+                        .namespace_id
+                        .map_or(false, |v| p.namespace_id().get() != v)
+                    || request.table_id.map_or(false, |v| p.table_id().get() != v)
+                {
+                    return None;
+                }
+
+                let timestamps = p.timestamp_stats();
+
+                Some(proto::BufferedPartition {
+                    namespace_id: p.namespace_id().get(),
+                    namespace_name: p.namespace_name().peek().map(|v| v.to_string()),
+                    table_id: p.table_id().get(),
+                    table_name: p.table().peek().map(|v| v.name().to_string()),
+                    partition_key: p.partition_key().to_string(),
+                    partition_id: p.partition_id().to_string(),
+                    row_count: p.rows() as _,
+                    estimated_size_bytes: p.persist_cost_estimate() as _,
+                    min_time: timestamps.map(|v| v.min),
+                    max_time: timestamps.map(|v| v.max),
+                    buffer_age_ms: p
+                        .buffered_since()
+                        .map(|v| now.duration_since(v).as_millis() as _),
+                    persisting_batches: p.persisting_batches() as _,
+                    completed_persist_count: p.completed_persistence_count(),
+                    hot_persist_count: p.hot_persistence_count(),
+                    series_count: p.series_count().map(|v| v as _),
+                    persist_queue_position: queue_positions.get(p.partition_id()).copied(),
+                })
+            })
+            .collect::<Vec<_>>();
+
+        match order {
+            PartitionOrder::Unspecified => partitions.sort_unstable_by(|a, b| {
+                (a.namespace_id, a.table_id, &a.partition_key).cmp(&(
+                    b.namespace_id,
+                    b.table_id,
+                    &b.partition_key,
+                ))
+            }),
+            PartitionOrder::SizeDesc => partitions
+                .sort_unstable_by(|a, b| b.estimated_size_bytes.cmp(&a.estimated_size_bytes)),
+            PartitionOrder::AgeDesc => {
+                partitions.sort_unstable_by(|a, b| b.buffer_age_ms.cmp(&a.buffer_age_ms))
+            }
+        }
+
+        let total = partitions.len() as u64;
+        let limit = match request.limit {
+            0 => usize::MAX,
+            v => v as usize,
+        };
+        let partitions = partitions
+            .into_iter()
+            .skip(request.offset as usize)
+            .take(limit)
+            .collect();
+
+        Ok(Response::new(proto::ListPartitionsResponse {
+            partitions,
+            total,
+            hot_partition_threshold_bytes: self.hot_partition_threshold as _,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use data_types::{NamespaceId, PartitionKey, SequenceNumber, TableId};
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use parking_lot::Mutex;
+
+    use super::*;
+    use crate::{buffer_tree::partition::PartitionData, test_util::PartitionDataBuilder};
+
+    /// Build a partition containing `n_rows` rows.
+    fn new_partition(
+        namespace_id: i64,
+        table_id: i64,
+        partition_key: &str,
+        n_rows: usize,
+    ) -> Arc<Mutex<PartitionData>> {
+        let mut p = PartitionDataBuilder::new()
+            .with_namespace_id(NamespaceId::new(namespace_id))
+            .with_table_id(TableId::new(table_id))
+            .with_partition_key(PartitionKey::from(partition_key))
+            .build();
+
+        if n_rows > 0 {
+            let lp = (0..n_rows)
+                .map(|i| format!("bananas,city=Madrid temp={i} {}", 42 + i))
+                .collect::<Vec<_>>()
+                .join("\n");
+            p.buffer_write(lp_to_mutable_batch(&lp).1, SequenceNumber::new(1))
+                .expect("write should succeed");
+        }
+
+        Arc::new(Mutex::new(p))
+    }
+
+    fn request(
+        namespace_id: Option<i64>,
+        order: PartitionOrder,
+        offset: u64,
+        limit: u64,
+    ) -> Request<proto::ListPartitionsRequest> {
+        Request::new(proto::ListPartitionsRequest {
+            namespace_id,
+            table_id: None,
+            order: order.into(),
+            offset,
+            limit,
+        })
+    }
+
+    fn keys(resp: &proto::ListPartitionsResponse) -> Vec<&str> {
+        resp.partitions
+            .iter()
+            .map(|v| v.partition_key.as_str())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_list_partitions() {
+        let buffer = vec![
+            new_partition(2, 1, "small", 1),
+            new_partition(1, 1, "large", 10),
+            new_partition(1, 1, "empty", 0),
+            new_partition(1, 2, "medium", 5),
+        ];
+        let handler = BufferHandler::new(buffer, Default::default(), 4242);
+
+        let resp = handler
+            .list_partitions(request(None, PartitionOrder::Unspecified, 0, 0))
+            .await
+            .expect("request should succeed")
+            .into_inner();
+
+        // Empty partitions are not returned, and the rest are ordered by
+        // namespace, table & partition key.
+        assert_eq!(keys(&resp), ["large", "medium", "small"]);
+        assert_eq!(resp.total, 3);
+        assert_eq!(resp.hot_partition_threshold_bytes, 4242);
+
+        let p = &resp.partitions[0];
+        assert_eq!(p.row_count, 10);
+        assert_eq!(p.min_time, Some(42));
+        assert_eq!(p.max_time, Some(51));
+        assert!(p.estimated_size_bytes > 0);
+        assert!(p.buffer_age_ms.is_some());
+        assert_eq!(p.persisting_batches, 0);
+        // No series limit is configured.
+        assert_eq!(p.series_count, None);
+        // Nothing is queued for persistence.
+        assert_eq!(p.persist_queue_position, None);
+    }
+
+    #[tokio::test]
+    async fn test_list_partitions_persisting() {
+        let p = new_partition(1, 1, "platanos", 3);
+        let persisting = p.lock().mark_persisting().expect("must have data");
+
+        // Queue a persist job for another partition ahead of this one.
+        let queue_positions = Arc::new(PersistQueuePositions::default());
+        let _other = queue_positions.push(
+            new_partition(1, 1, "other", 1)
+                .lock()
+                .partition_id()
+                .clone(),
+        );
+        let ticket = queue_positions.push(p.lock().partition_id().clone());
+
+        let handler = BufferHandler::new(vec![Arc::clone(&p)], Arc::clone(&queue_positions), 4242);
+
+        let resp = handler
+            .list_partitions(request(None, PartitionOrder::Unspecified, 0, 0))
+            .await
+            .expect("request should succeed")
+            .into_inner();
+
+        // The persisting data is still reported, but has no buffer age.
+        let got = &resp.partitions[0];
+        assert_eq!(got.row_count, 3);
+        assert_eq!(got.persisting_batches, 1);
+        assert_eq!(got.estimated_size_bytes, 0);
+        assert_eq!(got.buffer_age_ms, None);
+        assert_eq!(got.persist_queue_position, Some(1));
+
+        // Once a worker starts the persist job, it is no longer queued.
+        drop(ticket);
+        let resp = handler
+            .list_partitions(request(None, PartitionOrder::Unspecified, 0, 0))
+            .await
+            .expect("request should succeed")
+            .into_inner();
+        assert_eq!(resp.partitions[0].persisting_batches, 1);
+        assert_eq!(resp.partitions[0].persist_queue_position, None);
+
+        let _ = p.lock().mark_persisted(persisting);
+    }
+
+    #[tokio::test]
+    async fn test_list_partitions_sort_and_paginate() {
+        let buffer = vec![
+            new_partition(1, 1, "small", 1),
+            new_partition(1, 1, "large", 10),
+            new_partition(1, 2, "medium", 5),
+            new_partition(2, 1, "other", 100),
+        ];
+        let handler = BufferHandler::new(buffer, Default::default(), 4242);
+
+        let resp = handler
+            .list_partitions(request(Some(1), PartitionOrder::SizeDesc, 0, 2))
+            .await
+            .expect("request should succeed")
+            .into_inner();
+        assert_eq!(keys(&resp), ["large", "medium"]);
+        assert_eq!(resp.total, 3);
+
+        let resp = handler
+            .list_partitions(request(Some(1), PartitionOrder::SizeDesc, 2, 2))
+            .await
+            .expect("request should succeed")
+            .into_inner();
+        assert_eq!(keys(&resp), ["small"]);
+        assert_eq!(resp.total, 3);
+    }
+}
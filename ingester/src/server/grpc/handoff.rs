@@ -0,0 +1,550 @@
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+
+use data_types::{NamespaceId, PartitionKey, TableId};
+use futures::{Stream, StreamExt};
+use generated_types::influxdata::iox::{
+    ingester::v1::{
+        handoff_service_server::HandoffService, ReplayWalSegmentRequest, ReplayWalSegmentResponse,
+    },
+    wal::v1::sequenced_wal_op::Op,
+};
+use metric::U64Counter;
+use mutable_batch_pb::decode::decode_database_batch;
+use observability_deps::tracing::*;
+use tokio::io::AsyncWriteExt;
+use tonic::{Request, Response, Status, Streaming};
+use uuid::Uuid;
+use wal::Keyring;
+
+use crate::{
+    dml_payload::decode::decode_partition_templates,
+    dml_payload::write::{PartitionedData, TableData, WriteOperation},
+    dml_payload::IngestOp,
+    dml_sink::{DmlError, DmlSink},
+    ingest_state::{IngestState, IngestStateError},
+    partition_iter::PartitionIter,
+    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+    timestamp_oracle::TimestampOracle,
+};
+
+/// Defines how often the handler polls the replayed partitions for the
+/// completion of concurrent persist jobs.
+///
+/// Polls faster in tests to avoid unnecessary delay.
+#[cfg(test)]
+const PERSIST_POLL_INTERVAL: Duration = Duration::from_millis(10);
+#[cfg(not(test))]
+const PERSIST_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// A gRPC [`HandoffService`] handler.
+///
+/// This handler accepts closed WAL segment files from a peer ingester that is
+/// shutting down, and replays the operations within them through the provided
+/// [`DmlSink`] (which is expected to write them to this ingester's WAL and
+/// buffer).
+///
+/// The partitions the replayed operations were buffered into are then
+/// persisted through `persist` before the segment is acknowledged, so that the
+/// peer continues to serve queries for the handed-off data until it is
+/// readable from object storage.
+///
+/// The segment file is staged to disk in `staging_dir` while it is received,
+/// and removed once it has been replayed. Encrypted segment files are
+/// decrypted with the matching key in `keyring`, which must be shared with the
+/// peer.
+///
+/// Each replayed operation is assigned a new sequence number, ordering the
+/// handed-off data after any data already buffered by this ingester.
+#[derive(Debug)]
+pub(crate) struct HandoffHandler<T, B, P> {
+    sink: T,
+    buffer: B,
+    persist: P,
+    timestamp: Arc<TimestampOracle>,
+    ingest_state: Arc<IngestState>,
+    staging_dir: PathBuf,
+    keyring: Keyring,
+
+    ops_applied: U64Counter,
+}
+
+impl<T, B, P> HandoffHandler<T, B, P> {
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn new(
+        sink: T,
+        buffer: B,
+        persist: P,
+        timestamp: Arc<TimestampOracle>,
+        ingest_state: Arc<IngestState>,
+        staging_dir: PathBuf,
+        keyring: Keyring,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let ops_applied = metrics
+            .register_metric::<U64Counter>(
+                "ingester_handoff_received_ops",
+                "Number of operations replayed from WAL segments handed off by a peer ingester",
+            )
+            .recorder(&[]);
+
+        Self {
+            sink,
+            buffer,
+            persist,
+            timestamp,
+            ingest_state,
+            staging_dir,
+            keyring,
+            ops_applied,
+        }
+    }
+}
+
+impl<T, B, P> HandoffHandler<T, B, P>
+where
+    T: DmlSink + 'static,
+    B: PartitionIter + Sync + 'static,
+    P: PersistQueue + Clone + Sync + 'static,
+{
+    /// Receive the segment file in `stream` and replay it, returning the number
+    /// of operations applied once the replayed data has been persisted.
+    async fn replay<S>(&self, stream: S) -> Result<u64, Status>
+    where
+        S: Stream<Item = Result<ReplayWalSegmentRequest, Status>> + Send,
+    {
+        // Refuse the handoff if this ingester can't accept writes (including
+        // if it is itself shutting down).
+        self.ingest_state.read().map_err(|e| match e {
+            IngestStateError::GracefulStop => Status::failed_precondition(e.to_string()),
+            IngestStateError::PersistSaturated | IngestStateError::DiskFull => {
+                Status::resource_exhausted(e.to_string())
+            }
+        })?;
+
+        let path = self.staging_dir.join(format!("{}.dat", Uuid::new_v4()));
+        let res = self.stage_and_apply(stream, &path).await;
+
+        if let Err(e) = tokio::fs::remove_file(&path).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                warn!(error=%e, ?path, "failed to remove staged handoff segment");
+            }
+        }
+
+        res
+    }
+
+    async fn stage_and_apply<S>(&self, stream: S, path: &Path) -> Result<u64, Status>
+    where
+        S: Stream<Item = Result<ReplayWalSegmentRequest, Status>> + Send,
+    {
+        tokio::fs::create_dir_all(&self.staging_dir)
+            .await
+            .map_err(|e| Status::internal(format!("failed to create staging dir: {e}")))?;
+
+        // Write the received segment file to disk.
+        let mut file = tokio::fs::File::create(path)
+            .await
+            .map_err(|e| Status::internal(format!("failed to create staging file: {e}")))?;
+        let mut stream = std::pin::pin!(stream);
+        while let Some(chunk) = stream.next().await {
+            file.write_all(&chunk?.data)
+                .await
+                .map_err(|e| Status::internal(format!("failed to write staging file: {e}")))?;
+        }
+        file.flush()
+            .await
+            .map_err(|e| Status::internal(format!("failed to write staging file: {e}")))?;
+        drop(file);
+
+        let reader = wal::ClosedSegmentFileReader::from_path_with_keyring(path, &self.keyring)
+            .map_err(|e| Status::invalid_argument(format!("invalid wal segment: {e}")))?;
+        let segment_id = reader.id();
+
+        let mut ops_applied = 0;
+        let mut partitions = HashSet::new();
+        for batch in reader {
+            let batch =
+                batch.map_err(|e| Status::invalid_argument(format!("invalid wal segment: {e}")))?;
+
+            for op in batch {
+                let op = match op.op {
+                    Op::Write(w) => w,
+                    Op::Delete(_) | Op::Persist(_) => {
+                        return Err(Status::invalid_argument(format!(
+                            "unsupported operation in wal segment {segment_id:?}"
+                        )))
+                    }
+                };
+
+                let batches = decode_database_batch(&op)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                let mut templates = decode_partition_templates(&op)
+                    .map_err(|e| Status::invalid_argument(e.to_string()))?;
+                let namespace_id = NamespaceId::new(op.database_id);
+                let partition_key = PartitionKey::from(op.partition_key);
+
+                if batches.is_empty() {
+                    warn!(
+                        ?segment_id,
+                        %namespace_id,
+                        "handed off wal op batch contains no table data, skipping replay"
+                    );
+                    continue;
+                }
+
+                partitions.extend(
+                    batches
+                        .keys()
+                        .map(|&k| (namespace_id, TableId::new(k), partition_key.clone())),
+                );
+
+                // Sequence the data against this ingester's timestamp oracle,
+                // as the peer's sequence numbers are meaningless here.
+                let op = WriteOperation::new(
+                    namespace_id,
+                    batches
+                        .into_iter()
+                        .map(|(k, v)| {
+                            let table_id = TableId::new(k);
+                            let partition_sequence_number = self.timestamp.next();
+                            (
+                                table_id,
+                                TableData::new(
+                                    table_id,
+                                    PartitionedData::new(partition_sequence_number, v),
+                                )
+                                .with_partition_template(templates.remove(&table_id)),
+                            )
+                        })
+                        .collect(),
+                    partition_key,
+                    None,
+                );
+
+                self.sink.apply(IngestOp::Write(op)).await.map_err(|e| {
+                    error!(error=%e, "failed to apply handed off ingest operation");
+                    Status::from(Into::<DmlError>::into(e))
+                })?;
+
+                ops_applied += 1;
+                self.ops_applied.inc(1);
+            }
+        }
+
+        info!(?segment_id, ops_applied, "replayed handed off wal segment");
+
+        self.persist_replayed(&partitions).await;
+
+        info!(?segment_id, "persisted handed off wal segment");
+
+        Ok(ops_applied)
+    }
+
+    /// Persist the buffered data of the `partitions` handed-off data was
+    /// replayed into, returning once it has been persisted.
+    async fn persist_replayed(&self, partitions: &HashSet<(NamespaceId, TableId, PartitionKey)>) {
+        let replayed = || {
+            self.buffer.partition_iter().filter(|p| {
+                let p = p.lock();
+                partitions.contains(&(p.namespace_id(), p.table_id(), p.partition_key().clone()))
+            })
+        };
+
+        persist_partitions(replayed(), &self.persist).await;
+
+        // The replayed data may have been picked up by a concurrent persist
+        // job (such as a hot partition persist) that is not awaited above -
+        // wait until no replayed partition has an outstanding persist job.
+        while replayed().any(|p| p.lock().persisting_batches() > 0) {
+            debug!("waiting for concurrent persist of handed off data to complete");
+            tokio::time::sleep(PERSIST_POLL_INTERVAL).await;
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<T, B, P> HandoffService for HandoffHandler<T, B, P>
+where
+    T: DmlSink + 'static,
+    B: PartitionIter + Sync + 'static,
+    P: PersistQueue + Clone + Sync + 'static,
+{
+    /// Handle a WAL segment handed off by a peer ingester.
+    async fn replay_wal_segment(
+        &self,
+        request: Request<Streaming<ReplayWalSegmentRequest>>,
+    ) -> Result<Response<ReplayWalSegmentResponse>, Status> {
+        let ops_applied = self.replay(request.into_inner()).await?;
+        Ok(Response::new(ReplayWalSegmentResponse { ops_applied }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::SequenceNumber;
+    use futures::stream;
+    use generated_types::influxdata::iox::wal::v1::PersistOp;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use parking_lot::Mutex;
+    use tonic::Code;
+    use wal::{SequencedWalOp, Wal};
+
+    use super::*;
+    use crate::{
+        buffer_tree::partition::PartitionData,
+        dml_sink::mock_sink::MockDmlSink,
+        persist::queue::mock::MockPersistQueue,
+        test_util::{
+            make_write_op, PartitionDataBuilder, ARBITRARY_NAMESPACE_ID, ARBITRARY_PARTITION_KEY,
+            ARBITRARY_TABLE_ID, ARBITRARY_TABLE_NAME,
+        },
+        wal::wal_sink::{mock::MockUnbufferedWriteNotifier, WalSink},
+    };
+
+    /// Write two ops to a WAL in `dir` and return the closed segment file
+    /// bytes.
+    async fn segment_with_ops(dir: &std::path::Path) -> Vec<u8> {
+        let wal = Wal::new(dir).await.expect("failed to initialise WAL");
+        let wal_sink = WalSink::new(
+            Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())])),
+            Arc::clone(&wal),
+            Arc::new(MockUnbufferedWriteNotifier::default()),
+        );
+
+        for (seq, lp) in [(1, "temp=35 4242424242"), (2, "temp=25 4242424243")] {
+            let op = make_write_op(
+                &ARBITRARY_PARTITION_KEY,
+                ARBITRARY_NAMESPACE_ID,
+                &ARBITRARY_TABLE_NAME,
+                ARBITRARY_TABLE_ID,
+                seq,
+                &format!("{},region=Madrid {lp}", &*ARBITRARY_TABLE_NAME),
+                None,
+            );
+            wal_sink
+                .apply(IngestOp::Write(op))
+                .await
+                .expect("wal should not error");
+        }
+
+        let (segment, _) = wal.rotate().expect("failed to rotate WAL file");
+        std::fs::read(segment.path()).expect("failed to read segment file")
+    }
+
+    fn chunked(
+        data: Vec<u8>,
+        size: usize,
+    ) -> impl Stream<Item = Result<ReplayWalSegmentRequest, Status>> + Send {
+        stream::iter(
+            data.chunks(size)
+                .map(|c| Ok(ReplayWalSegmentRequest { data: c.to_vec() }))
+                .collect::<Vec<_>>(),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_replay_handed_off_segment() {
+        let peer_dir = tempfile::tempdir().unwrap();
+        let data = segment_with_ops(peer_dir.path()).await;
+
+        let staging = tempfile::tempdir().unwrap();
+        let mock = Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())]));
+        // This ingester has already sequenced writes.
+        let timestamp = Arc::new(TimestampOracle::new(100));
+
+        let handler = HandoffHandler::new(
+            Arc::clone(&mock),
+            Vec::new(),
+            Arc::new(MockPersistQueue::default()),
+            timestamp,
+            Arc::new(IngestState::default()),
+            staging.path().join("handoff"),
+            Keyring::default(),
+            &metric::Registry::default(),
+        );
+
+        // Stream the segment in small chunks.
+        let got = handler
+            .replay(chunked(data, 7))
+            .await
+            .expect("replay should succeed");
+        assert_eq!(got, 2);
+
+        // Both ops were applied, re-sequenced after the existing writes.
+        let calls = mock.get_calls();
+        assert_eq!(calls.len(), 2);
+        for (call, want) in calls.iter().zip([101, 102]) {
+            assert_matches!(call, IngestOp::Write(w) => {
+                let seq = w
+                    .tables()
+                    .next()
+                    .expect("must contain table data")
+                    .1
+                    .partitioned_data()
+                    .sequence_number();
+                assert_eq!(seq.get(), want);
+            });
+        }
+
+        // And the staging file was removed.
+        let staged = std::fs::read_dir(staging.path().join("handoff"))
+            .unwrap()
+            .count();
+        assert_eq!(staged, 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_invalid_segment() {
+        let staging = tempfile::tempdir().unwrap();
+        let mock = Arc::new(MockDmlSink::default());
+
+        let handler = HandoffHandler::new(
+            Arc::clone(&mock),
+            Vec::new(),
+            Arc::new(MockPersistQueue::default()),
+            Arc::new(TimestampOracle::new(0)),
+            Arc::new(IngestState::default()),
+            staging.path().to_path_buf(),
+            Keyring::default(),
+            &metric::Registry::default(),
+        );
+
+        let err = handler
+            .replay(chunked(b"bananas".to_vec(), 3))
+            .await
+            .expect_err("replay should fail");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(mock.get_calls().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replay_rejected_when_stopping() {
+        let staging = tempfile::tempdir().unwrap();
+        let mock = Arc::new(MockDmlSink::default());
+        let ingest_state = Arc::new(IngestState::default());
+        ingest_state.set(IngestStateError::GracefulStop);
+
+        let handler = HandoffHandler::new(
+            Arc::clone(&mock),
+            Vec::new(),
+            Arc::new(MockPersistQueue::default()),
+            Arc::new(TimestampOracle::new(0)),
+            ingest_state,
+            staging.path().to_path_buf(),
+            Keyring::default(),
+            &metric::Registry::default(),
+        );
+
+        let err = handler
+            .replay(chunked(vec![], 1))
+            .await
+            .expect_err("replay should be rejected");
+        assert_eq!(err.code(), Code::FailedPrecondition);
+        assert!(mock.get_calls().is_empty());
+    }
+
+    /// Initialise a partition with `partition_key` containing buffered data.
+    fn new_partition(partition_key: PartitionKey) -> Arc<Mutex<PartitionData>> {
+        let mut partition = PartitionDataBuilder::new()
+            .with_partition_key(partition_key)
+            .build();
+
+        let mb = lp_to_mutable_batch(&format!(
+            "{},region=Madrid temp=35 4242424242",
+            &*ARBITRARY_TABLE_NAME
+        ))
+        .1;
+        partition
+            .buffer_write(mb, SequenceNumber::new(1))
+            .expect("failed to write dummy data");
+
+        Arc::new(Mutex::new(partition))
+    }
+
+    #[tokio::test]
+    async fn test_replay_persists_replayed_partitions() {
+        let peer_dir = tempfile::tempdir().unwrap();
+        let data = segment_with_ops(peer_dir.path()).await;
+
+        // The mock sink does not buffer the replayed ops, so the buffer is
+        // pre-populated with a partition the ops are replayed into, and one
+        // that is unrelated to the handoff.
+        let replayed = new_partition(ARBITRARY_PARTITION_KEY.clone());
+        let unrelated = new_partition(PartitionKey::from("platanos"));
+        let persist = Arc::new(MockPersistQueue::default());
+
+        let staging = tempfile::tempdir().unwrap();
+        let handler = HandoffHandler::new(
+            Arc::new(MockDmlSink::default().with_apply_return(vec![Ok(()), Ok(())])),
+            vec![Arc::clone(&replayed), Arc::clone(&unrelated)],
+            Arc::clone(&persist),
+            Arc::new(TimestampOracle::new(0)),
+            Arc::new(IngestState::default()),
+            staging.path().to_path_buf(),
+            Keyring::default(),
+            &metric::Registry::default(),
+        );
+
+        handler
+            .replay(chunked(data, 7))
+            .await
+            .expect("replay should succeed");
+
+        // Only the partition the data was replayed into was persisted, and
+        // the persist completed before the replay returned.
+        assert_matches!(persist.calls().as_slice(), [p] => {
+            assert!(Arc::ptr_eq(p, &replayed));
+        });
+        assert_eq!(replayed.lock().persisting_batches(), 0);
+        assert_eq!(replayed.lock().completed_persistence_count(), 1);
+        assert_eq!(unrelated.lock().completed_persistence_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_replay_unsupported_op() {
+        let peer_dir = tempfile::tempdir().unwrap();
+        let wal = Wal::new(peer_dir.path())
+            .await
+            .expect("failed to initialise WAL");
+        wal.write_op(SequencedWalOp {
+            table_write_sequence_numbers: [(ARBITRARY_TABLE_ID, 1)].into_iter().collect(),
+            op: Op::Persist(PersistOp {
+                namespace_id: ARBITRARY_NAMESPACE_ID.get(),
+                parquet_file_uuid: "b4N4N4Z".into(),
+                partition_id: 43,
+                table_id: ARBITRARY_TABLE_ID.get(),
+            }),
+        })
+        .changed()
+        .await
+        .expect("wal write should complete");
+        let (segment, _) = wal.rotate().expect("failed to rotate WAL file");
+        let data = std::fs::read(segment.path()).expect("failed to read segment file");
+
+        let staging = tempfile::tempdir().unwrap();
+        let mock = Arc::new(MockDmlSink::default());
+        let handler = HandoffHandler::new(
+            Arc::clone(&mock),
+            Vec::new(),
+            Arc::new(MockPersistQueue::default()),
+            Arc::new(TimestampOracle::new(0)),
+            Arc::new(IngestState::default()),
+            staging.path().to_path_buf(),
+            Keyring::default(),
+            &metric::Registry::default(),
+        );
+
+        let err = handler
+            .replay(chunked(data, 7))
+            .await
+            .expect_err("replay should fail");
+        assert_eq!(err.code(), Code::InvalidArgument);
+        assert!(mock.get_calls().is_empty());
+    }
+}
@@ -1,11 +1,13 @@
 use crate::{
     partition_iter::PartitionIter,
-    persist::{drain_buffer::persist_partitions, queue::PersistQueue},
+    persist::{drain_buffer::enqueue_partitions, queue::PersistQueue},
 };
+use data_types::PartitionKey;
 use generated_types::influxdata::iox::ingester::v1::{
     self as proto, persist_service_server::PersistService,
 };
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::*;
 use std::sync::Arc;
 use tonic::{Request, Response};
 
@@ -36,33 +38,281 @@ where
     T: PartitionIter + Sync + 'static,
     P: PersistQueue + Clone + Sync + 'static,
 {
-    /// Handle the RPC request to persist immediately. Will block until the data has persisted,
-    /// which is useful in tests asserting on persisted data. May behave in unexpected ways if used
-    /// concurrently with writes and ingester WAL rotations.
+    /// Handle the RPC request to persist immediately. Will block until the data has persisted
+    /// (unless the request opts out of waiting), which is useful in tests asserting on persisted
+    /// data. May behave in unexpected ways if used concurrently with writes and ingester WAL
+    /// rotations.
+    ///
+    /// The persisted data may be restricted to a single table and/or partition key within the
+    /// namespace.
     async fn persist(
         &self,
         request: Request<proto::PersistRequest>,
     ) -> Result<Response<proto::PersistResponse>, tonic::Status> {
         let request = request.into_inner();
 
-        let namespace = self
-            .catalog
-            .repositories()
-            .await
+        let mut repos = self.catalog.repositories().await;
+
+        let namespace = repos
             .namespaces()
             .get_by_name(&request.namespace, SoftDeletedRows::AllRows)
             .await
             .map_err(|e| tonic::Status::internal(e.to_string()))?
             .ok_or_else(|| tonic::Status::not_found(&request.namespace))?;
 
-        persist_partitions(
-            self.buffer
-                .partition_iter()
-                .filter(|p| p.lock().namespace_id() == namespace.id),
+        let table_id = match &request.table {
+            Some(name) => Some(
+                repos
+                    .tables()
+                    .get_by_namespace_and_name(namespace.id, name)
+                    .await
+                    .map_err(|e| tonic::Status::internal(e.to_string()))?
+                    .ok_or_else(|| tonic::Status::not_found(name))?
+                    .id,
+            ),
+            None => None,
+        };
+
+        drop(repos);
+
+        let partition_key = request.partition_key.map(PartitionKey::from);
+
+        let notifications = enqueue_partitions(
+            self.buffer.partition_iter().filter(|p| {
+                let p = p.lock();
+                p.namespace_id() == namespace.id
+                    && table_id.map_or(true, |v| p.table_id() == v)
+                    && partition_key
+                        .as_ref()
+                        .map_or(true, |v| p.partition_key() == v)
+            }),
             &self.persist_handle,
         )
         .await;
 
-        Ok(Response::new(proto::PersistResponse {}))
+        info!(
+            namespace_id = %namespace.id,
+            ?table_id,
+            ?partition_key,
+            n_partitions = notifications.len(),
+            "enqueued partitions for persistence by request"
+        );
+
+        if !request.wait_for_completion.unwrap_or(true) {
+            return Ok(Response::new(proto::PersistResponse::default()));
+        }
+
+        // Wait for all the persist jobs to complete, collecting the IDs of
+        // the resulting parquet files.
+        let mut parquet_file_ids = Vec::with_capacity(notifications.len());
+        for n in notifications {
+            let id = n
+                .await
+                .map_err(|_| tonic::Status::internal("persist worker task panic"))?;
+            parquet_file_ids.push(id.get());
+        }
+
+        Ok(Response::new(proto::PersistResponse { parquet_file_ids }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::{NamespaceId, TableId};
+    use iox_catalog::mem::MemCatalog;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use parking_lot::Mutex;
+    use tonic::Code;
+
+    use super::*;
+    use crate::{
+        buffer_tree::partition::PartitionData,
+        persist::{completion_observer::NopObserver, queue::mock::MockPersistQueue},
+        test_util::{populate_catalog, PartitionDataBuilder},
+    };
+
+    const NAMESPACE: &str = "platanos";
+    const TABLE: &str = "bananas";
+    const OTHER_TABLE: &str = "plantains";
+
+    /// Initialise a catalog containing [`NAMESPACE`] with two tables, and a
+    /// buffer with data for two partition keys in each table.
+    async fn init() -> (
+        Arc<dyn Catalog>,
+        Vec<Arc<Mutex<PartitionData>>>,
+        TableId,
+        TableId,
+    ) {
+        let metrics = Arc::new(metric::Registry::default());
+        let catalog: Arc<dyn Catalog> = Arc::new(MemCatalog::new(metrics));
+
+        let (namespace_id, table_id) = populate_catalog(&*catalog, NAMESPACE, TABLE).await;
+        let other_table_id = catalog
+            .repositories()
+            .await
+            .tables()
+            .create(OTHER_TABLE, Default::default(), namespace_id)
+            .await
+            .unwrap()
+            .id;
+
+        let buffer = [
+            (table_id, TABLE, "p1"),
+            (table_id, TABLE, "p2"),
+            (other_table_id, OTHER_TABLE, "p1"),
+        ]
+        .into_iter()
+        .map(|(id, name, key)| new_partition(namespace_id, id, name, key))
+        .collect();
+
+        (catalog, buffer, table_id, other_table_id)
+    }
+
+    fn new_partition(
+        namespace_id: NamespaceId,
+        table_id: TableId,
+        table_name: &str,
+        partition_key: &str,
+    ) -> Arc<Mutex<PartitionData>> {
+        let mut p = PartitionDataBuilder::new()
+            .with_namespace_id(namespace_id)
+            .with_table_id(table_id)
+            .with_partition_key(PartitionKey::from(partition_key))
+            .build();
+
+        let mb = lp_to_mutable_batch(&format!("{table_name},city=Madrid temp=35 4242424242")).1;
+        p.buffer_write(mb, data_types::SequenceNumber::new(1))
+            .expect("write should succeed");
+
+        Arc::new(Mutex::new(p))
+    }
+
+    fn request(
+        table: Option<&str>,
+        partition_key: Option<&str>,
+        wait_for_completion: Option<bool>,
+    ) -> Request<proto::PersistRequest> {
+        Request::new(proto::PersistRequest {
+            namespace: NAMESPACE.to_string(),
+            table: table.map(ToString::to_string),
+            partition_key: partition_key.map(ToString::to_string),
+            wait_for_completion,
+        })
+    }
+
+    fn persisted(persist: &MockPersistQueue<NopObserver>) -> Vec<(TableId, PartitionKey)> {
+        let mut got = persist
+            .calls()
+            .into_iter()
+            .map(|p| {
+                let p = p.lock();
+                (p.table_id(), p.partition_key().clone())
+            })
+            .collect::<Vec<_>>();
+        got.sort_unstable();
+        got
+    }
+
+    #[tokio::test]
+    async fn test_persist_namespace() {
+        let (catalog, buffer, table_id, other_table_id) = init().await;
+        let persist = Arc::new(MockPersistQueue::default());
+        let handler = PersistHandler::new(buffer, Arc::clone(&persist), catalog);
+
+        let resp = handler
+            .persist(request(None, None, None))
+            .await
+            .expect("persist should succeed")
+            .into_inner();
+
+        // All partitions were persisted, and the (mock) file IDs returned.
+        assert_eq!(resp.parquet_file_ids, [42, 42, 42]);
+        assert_eq!(
+            persisted(&persist),
+            [
+                (table_id, PartitionKey::from("p1")),
+                (table_id, PartitionKey::from("p2")),
+                (other_table_id, PartitionKey::from("p1")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_table() {
+        let (catalog, buffer, table_id, _) = init().await;
+        let persist = Arc::new(MockPersistQueue::default());
+        let handler = PersistHandler::new(buffer, Arc::clone(&persist), catalog);
+
+        let resp = handler
+            .persist(request(Some(TABLE), None, None))
+            .await
+            .expect("persist should succeed")
+            .into_inner();
+
+        assert_eq!(resp.parquet_file_ids, [42, 42]);
+        assert_eq!(
+            persisted(&persist),
+            [
+                (table_id, PartitionKey::from("p1")),
+                (table_id, PartitionKey::from("p2")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_partition() {
+        let (catalog, buffer, _, other_table_id) = init().await;
+        let persist = Arc::new(MockPersistQueue::default());
+        let handler = PersistHandler::new(buffer, Arc::clone(&persist), catalog);
+
+        let resp = handler
+            .persist(request(Some(OTHER_TABLE), Some("p1"), None))
+            .await
+            .expect("persist should succeed")
+            .into_inner();
+
+        assert_eq!(resp.parquet_file_ids, [42]);
+        assert_eq!(
+            persisted(&persist),
+            [(other_table_id, PartitionKey::from("p1"))]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_no_wait() {
+        let (catalog, buffer, table_id, other_table_id) = init().await;
+        let persist = Arc::new(MockPersistQueue::default());
+        let handler = PersistHandler::new(buffer, Arc::clone(&persist), catalog);
+
+        let resp = handler
+            .persist(request(None, Some("p1"), Some(false)))
+            .await
+            .expect("persist should succeed")
+            .into_inner();
+
+        // The jobs were enqueued, but no file IDs are returned.
+        assert!(resp.parquet_file_ids.is_empty());
+        assert_eq!(
+            persisted(&persist),
+            [
+                (table_id, PartitionKey::from("p1")),
+                (other_table_id, PartitionKey::from("p1")),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_persist_unknown_table() {
+        let (catalog, buffer, _, _) = init().await;
+        let persist = Arc::new(MockPersistQueue::default());
+        let handler = PersistHandler::new(buffer, Arc::clone(&persist), catalog);
+
+        let err = handler
+            .persist(request(Some("bananas-and-custard"), None, None))
+            .await
+            .expect_err("persist should fail");
+
+        assert_eq!(err.code(), Code::NotFound);
+        assert!(persist.calls().is_empty());
     }
 }
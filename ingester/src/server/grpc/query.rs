@@ -362,6 +362,16 @@ fn encode_response(
     let span = SpanRecorder::new(span.clone()).span().cloned();
 
     response.into_partition_stream().flat_map(move |partition| {
+        let partition = match partition {
+            Ok(v) => v,
+            Err(e) => {
+                // The partition data could not be read back from disk.
+                error!(error=%e, "failed to read partition data for query");
+                let e = FlightError::Tonic(tonic::Status::internal(e.to_string()));
+                return futures::stream::once(async move { Err(e) }).left_stream();
+            }
+        };
+
         let partition_id = partition.id().clone();
         let completed_persistence_count = partition.completed_persistence_count();
 
@@ -394,6 +404,7 @@ fn encode_response(
         }
 
         head.chain(futures::stream::iter(output).flatten())
+            .right_stream()
     })
 }
 
@@ -406,6 +417,7 @@ mod tests {
             mock_query_exec::MockQueryExec, partition_response::PartitionResponse,
             response::PartitionStream,
         },
+        spill::SpillError,
         test_util::{ARBITRARY_PARTITION_HASH_ID, ARBITRARY_TRANSITION_PARTITION_ID},
     };
     use arrow::array::{Float64Array, Int32Array};
@@ -423,11 +435,11 @@ mod tests {
 
         let flight = FlightService::new(
             MockQueryExec::default().with_result(Ok(QueryResponse::new(PartitionStream::new(
-                futures::stream::iter([PartitionResponse::new(
+                futures::stream::iter([Ok(PartitionResponse::new(
                     vec![],
                     ARBITRARY_TRANSITION_PARTITION_ID.clone(),
                     42,
-                )]),
+                ))]),
             )))),
             ingester_id,
             100,
@@ -461,16 +473,48 @@ mod tests {
         assert_eq!(md_actual, md_expected);
     }
 
+    /// A failure to read the (spilled) data of a partition is returned to the
+    /// caller as an error.
+    #[tokio::test]
+    async fn test_partition_read_error() {
+        let flight = FlightService::new(
+            MockQueryExec::default().with_result(Ok(QueryResponse::new(PartitionStream::new(
+                futures::stream::iter([Err(SpillError::Io(std::io::Error::new(
+                    std::io::ErrorKind::NotFound,
+                    "bananas",
+                )))]),
+            )))),
+            IngesterId::new(),
+            100,
+            &metric::Registry::default(),
+        );
+
+        let req = tonic::Request::new(Ticket {
+            ticket: Bytes::new(),
+        });
+        let got = flight
+            .do_get(req)
+            .await
+            .unwrap()
+            .into_inner()
+            .try_collect::<Vec<_>>()
+            .await;
+
+        assert_matches!(got, Err(s) => {
+            assert_eq!(s.code(), Code::Internal);
+        });
+    }
+
     #[tokio::test]
     async fn doesnt_send_partition_hash_id_if_not_present() {
         let ingester_id = IngesterId::new();
         let flight = FlightService::new(
             MockQueryExec::default().with_result(Ok(QueryResponse::new(PartitionStream::new(
-                futures::stream::iter([PartitionResponse::new(
+                futures::stream::iter([Ok(PartitionResponse::new(
                     vec![],
                     TransitionPartitionId::Deprecated(PartitionId::new(2)),
                     42,
-                )]),
+                ))]),
             )))),
             ingester_id,
             100,
@@ -543,9 +587,9 @@ mod tests {
             Int32Array("int" => vec![1, 2, 3]),
         );
 
-        let query_response = QueryResponse::new(PartitionStream::new(futures::stream::iter([
+        let query_response = QueryResponse::new(PartitionStream::new(futures::stream::iter([Ok(
             PartitionResponse::new(vec![batch], ARBITRARY_TRANSITION_PARTITION_ID.clone(), 42),
-        ])));
+        )])));
 
         let histogram = Arc::new(
             metric::Registry::default()
@@ -596,7 +640,7 @@ mod tests {
 
         let flight = FlightService::new(
             MockQueryExec::default().with_result(Ok(QueryResponse::new(PartitionStream::new(
-                futures::stream::iter([PartitionResponse::new(
+                futures::stream::iter([Ok(PartitionResponse::new(
                     vec![
                         batch1.clone(),
                         batch2.clone(),
@@ -605,7 +649,7 @@ mod tests {
                     ],
                     ARBITRARY_TRANSITION_PARTITION_ID.clone(),
                     42,
-                )]),
+                ))]),
             )))),
             ingester_id,
             100,
@@ -1,8 +1,11 @@
 use std::sync::Arc;
 
-use data_types::{NamespaceId, PartitionKey, TableId};
-use generated_types::influxdata::iox::ingester::v1::{
-    self as proto, write_service_server::WriteService,
+use data_types::{partition_template::ValidationError, NamespaceId, PartitionKey, TableId};
+use generated_types::{
+    google::QuotaFailure,
+    influxdata::iox::ingester::v1::{
+        self as proto, write_service_server::WriteService, SERIES_LIMIT_QUOTA_SUBJECT,
+    },
 };
 use mutable_batch::writer;
 use mutable_batch_pb::decode::decode_database_batch;
@@ -16,6 +19,7 @@ use trace::{
 
 use crate::{
     buffer_tree::BufferWriteError,
+    dml_payload::decode::decode_partition_templates,
     dml_payload::write::{PartitionedData, TableData, WriteOperation},
     dml_payload::IngestOp,
     dml_sink::{DmlError, DmlSink},
@@ -43,6 +47,10 @@ enum RpcError {
     #[error(transparent)]
     Decode(mutable_batch_pb::decode::Error),
 
+    /// A partition template in the write payload is invalid.
+    #[error("invalid partition template: {0}")]
+    PartitionTemplate(ValidationError),
+
     /// The ingester's [`IngestState`] returns [`IngestStateError`] instances if
     /// set by a subsystem. See [`IngestState`] for documentation.
     #[error(transparent)]
@@ -52,7 +60,10 @@ enum RpcError {
 impl From<RpcError> for tonic::Status {
     fn from(e: RpcError) -> Self {
         let code = match e {
-            RpcError::Decode(_) | RpcError::NoPayload | RpcError::NoTables => Code::InvalidArgument,
+            RpcError::Decode(_)
+            | RpcError::PartitionTemplate(_)
+            | RpcError::NoPayload
+            | RpcError::NoTables => Code::InvalidArgument,
             RpcError::SystemState(IngestStateError::PersistSaturated) => Code::ResourceExhausted,
             RpcError::SystemState(IngestStateError::DiskFull) => Code::ResourceExhausted,
             RpcError::SystemState(IngestStateError::GracefulStop) => Code::FailedPrecondition,
@@ -70,6 +81,11 @@ impl From<DmlError> for tonic::Status {
             DmlError::Buffer(BufferWriteError::PartitionLimit { .. }) => {
                 Self::resource_exhausted(e.to_string())
             }
+            DmlError::Buffer(BufferWriteError::SeriesLimit { .. }) => QuotaFailure {
+                subject: SERIES_LIMIT_QUOTA_SUBJECT.to_string(),
+                description: e.to_string(),
+            }
+            .into(),
             DmlError::Buffer(BufferWriteError::Write(e)) => map_write_error(e),
             DmlError::Wal(_) => Self::internal(e.to_string()),
             DmlError::ApplyTimeout => Self::internal(e.to_string()),
@@ -89,6 +105,7 @@ fn map_write_error(e: mutable_batch::Error) -> tonic::Status {
         | mutable_batch::Error::ArrowError { .. }
         | mutable_batch::Error::InternalSchema { .. }
         | mutable_batch::Error::ColumnNotFound { .. }
+        | mutable_batch::Error::ColumnExists { .. }
         | mutable_batch::Error::WriterError {
             source: writer::Error::KeyNotFound { .. } | writer::Error::InsufficientValues { .. },
         } => Status::internal(e.to_string()),
@@ -174,6 +191,8 @@ where
         let payload = request.into_inner().payload.ok_or(RpcError::NoPayload)?;
 
         let batches = decode_database_batch(&payload).map_err(RpcError::Decode)?;
+        let mut templates =
+            decode_partition_templates(&payload).map_err(RpcError::PartitionTemplate)?;
         let num_tables = batches.len();
         let namespace_id = NamespaceId::new(payload.database_id);
         let partition_key = PartitionKey::from(payload.partition_key);
@@ -206,7 +225,8 @@ where
                         TableData::new(
                             table_id,
                             PartitionedData::new(partition_sequence_number, v),
-                        ),
+                        )
+                        .with_partition_template(templates.remove(&table_id)),
                     )
                 })
                 .collect(),
@@ -232,10 +252,16 @@ where
 #[cfg(test)]
 mod tests {
     use assert_matches::assert_matches;
-    use data_types::SequenceNumber;
-    use generated_types::influxdata::pbdata::v1::{
-        column::{SemanticType, Values},
-        Column, DatabaseBatch, TableBatch,
+    use data_types::{partition_template::PARTITION_BY_DAY_PROTO, SequenceNumber};
+    use generated_types::{
+        google::decode_quota_failure,
+        influxdata::{
+            iox::partition_template::v1::PartitionTemplate,
+            pbdata::v1::{
+                column::{SemanticType, Values},
+                Column, DatabaseBatch, TableBatch,
+            },
+        },
     };
     use std::{collections::HashSet, sync::Arc};
     use trace::RingBufferTraceCollector;
@@ -312,6 +338,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         },
@@ -326,6 +353,7 @@ mod tests {
                 w.tables().next().unwrap().1.partitioned_data().sequence_number(),
                 SequenceNumber::new(1)
             );
+            assert!(w.tables().next().unwrap().1.partition_template().is_none());
         }
     );
 
@@ -354,6 +382,7 @@ mod tests {
                             null_mask: vec![0],
                         }],
                         row_count:1 ,
+                        partition_template: None,
                     },
                     TableBatch {
                         table_id: ALTERNATIVE_TABLE_ID.get(),
@@ -373,6 +402,7 @@ mod tests {
                             null_mask: vec![0],
                         }],
                         row_count: 1,
+                        partition_template: None,
                     },
                 ],
             }),
@@ -406,6 +436,79 @@ mod tests {
         want_calls = []
     );
 
+    test_rpc_write!(
+        apply_ok_with_partition_template,
+        request = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                    partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+                }],
+            }),
+        },
+        sink_ret = Ok(()),
+        want_err = false,
+        want_calls = [IngestOp::Write(w)] => {
+            // The partition template the router derived the key from is
+            // carried with the table data.
+            assert_eq!(
+                w.tables().next().unwrap().1.partition_template().and_then(|v| v.as_proto()),
+                Some(&**PARTITION_BY_DAY_PROTO)
+            );
+        }
+    );
+
+    test_rpc_write!(
+        invalid_partition_template,
+        request = proto::WriteRequest {
+            payload: Some(DatabaseBatch {
+                database_id: ARBITRARY_NAMESPACE_ID.get(),
+                partition_key: ARBITRARY_PARTITION_KEY.to_string(),
+                table_batches: vec![TableBatch {
+                    table_id: ARBITRARY_TABLE_ID.get(),
+                    columns: vec![Column {
+                        column_name: "time".to_string(),
+                        semantic_type: SemanticType::Time.into(),
+                        values: Some(Values {
+                            i64_values: vec![4242],
+                            f64_values: vec![],
+                            u64_values: vec![],
+                            string_values: vec![],
+                            bool_values: vec![],
+                            bytes_values: vec![],
+                            packed_string_values: None,
+                            interned_string_values: None,
+                        }),
+                        null_mask: vec![0],
+                    }],
+                    row_count: 1,
+                    partition_template: Some(PartitionTemplate { parts: vec![] }),
+                }],
+            }),
+        },
+        sink_ret = Ok(()),
+        want_err = true,
+        want_calls = []
+    );
+
     test_rpc_write!(
         no_tables,
         request = proto::WriteRequest {
@@ -444,6 +547,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         },
@@ -485,6 +589,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         };
@@ -542,6 +647,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         };
@@ -598,6 +704,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         };
@@ -663,6 +770,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         };
@@ -686,6 +794,25 @@ mod tests {
         assert_matches!(*mock.get_calls(), [IngestOp::Write(_)]);
     }
 
+    /// Validate that a write rejected by the series limit is returned with a
+    /// [`QuotaFailure`] the router can identify.
+    #[test]
+    fn test_series_limit_status() {
+        let status = tonic::Status::from(DmlError::Buffer(BufferWriteError::SeriesLimit {
+            count: 42,
+            limit: 42,
+        }));
+
+        assert_eq!(status.code(), Code::ResourceExhausted);
+        assert_matches!(
+            decode_quota_failure(&status).collect::<Vec<_>>().as_slice(),
+            [q] => {
+                assert_eq!(q.subject, SERIES_LIMIT_QUOTA_SUBJECT);
+                assert!(q.description.contains("42 of 42 series"));
+            }
+        );
+    }
+
     /// Assert that the ingester propagates the SpanContext from the client
     /// request.
     #[tokio::test]
@@ -719,6 +846,7 @@ mod tests {
                         null_mask: vec![0],
                     }],
                     row_count: 1,
+                    partition_template: None,
                 }],
             }),
         });
@@ -0,0 +1,618 @@
+//! A set of immutable [`RecordBatch`] that can be moved to local disk.
+
+use std::{
+    fs::File,
+    io::{BufReader, BufWriter},
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Weak,
+    },
+};
+
+use arrow::{
+    error::ArrowError,
+    ipc::{reader::FileReader, writer::FileWriter},
+    record_batch::RecordBatch,
+};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use thiserror::Error;
+use tokio::sync::OwnedSemaphorePermit;
+use uuid::Uuid;
+
+use crate::query::projection::OwnedProjection;
+
+/// Errors writing or reading spilled data.
+#[derive(Debug, Error)]
+pub(crate) enum SpillError {
+    /// An I/O error accessing the spill files.
+    #[error("spill file i/o error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// An error encoding or decoding the Arrow IPC spill files.
+    #[error("spill file encoding error: {0}")]
+    Arrow(#[from] ArrowError),
+
+    /// Spilling the data would exceed the configured size limit of the spill
+    /// directory.
+    #[error("spill directory full ({used_bytes} of {max_bytes} bytes used)")]
+    DirectoryFull {
+        /// The number of bytes used by spill files.
+        used_bytes: u64,
+        /// The maximum number of bytes of spill files.
+        max_bytes: u64,
+    },
+}
+
+/// A directory on local disk containing spill files, bounding the total size
+/// of the spill files within it.
+#[derive(Debug)]
+pub(crate) struct SpillDir {
+    path: PathBuf,
+    max_bytes: u64,
+
+    /// The number of bytes used (or reserved) by spill files in this
+    /// directory.
+    used_bytes: Mutex<u64>,
+}
+
+impl SpillDir {
+    /// Initialise a [`SpillDir`] that writes spill files to subdirectories of
+    /// the (existing) `path`, using at most `max_bytes` of disk space.
+    pub(crate) fn new(path: PathBuf, max_bytes: u64) -> Self {
+        Self {
+            path,
+            max_bytes,
+            used_bytes: Mutex::new(0),
+        }
+    }
+
+    /// Initialise a [`SpillDir`] at `path`, creating the directory if it does
+    /// not exist and removing any spill files left over from a previous run.
+    ///
+    /// Only the subdirectories written by a [`SpillDir`] are removed.
+    pub(crate) async fn create(path: PathBuf, max_bytes: u64) -> Result<Self, std::io::Error> {
+        tokio::fs::create_dir_all(&path).await?;
+
+        let mut entries = tokio::fs::read_dir(&path).await?;
+        while let Some(entry) = entries.next_entry().await? {
+            let is_spill_files = entry
+                .file_name()
+                .to_str()
+                .map(|v| Uuid::parse_str(v).is_ok())
+                .unwrap_or_default();
+
+            if is_spill_files && entry.file_type().await?.is_dir() {
+                debug!(path=%entry.path().display(), "removing stale spill files");
+                tokio::fs::remove_dir_all(entry.path()).await?;
+            }
+        }
+
+        Ok(Self::new(path, max_bytes))
+    }
+
+    /// Return the number of bytes of disk space used by spill files.
+    pub(crate) fn used_bytes(&self) -> u64 {
+        *self.used_bytes.lock()
+    }
+
+    /// Reserve `bytes` of disk space, failing if the directory would exceed
+    /// the configured limit.
+    fn reserve(&self, bytes: u64) -> Result<(), SpillError> {
+        let mut used = self.used_bytes.lock();
+        if *used + bytes > self.max_bytes {
+            return Err(SpillError::DirectoryFull {
+                used_bytes: *used,
+                max_bytes: self.max_bytes,
+            });
+        }
+        *used += bytes;
+        Ok(())
+    }
+
+    /// Replace the `reserved` bytes of disk space with the `actual` number of
+    /// bytes used, which may exceed the configured limit.
+    fn adjust(&self, reserved: u64, actual: u64) {
+        let mut used = self.used_bytes.lock();
+        *used = used.saturating_sub(reserved) + actual;
+    }
+
+    /// Release the reservation of `bytes` of disk space.
+    fn release(&self, bytes: u64) {
+        let mut used = self.used_bytes.lock();
+        *used = used.saturating_sub(bytes);
+    }
+}
+
+/// The persist queue permit of a persist job, released once all the batches
+/// of the job have been spilled to disk.
+///
+/// Spilled data uses no memory while it waits to be persisted, so the job no
+/// longer needs to occupy the persist queue - releasing the permit allows more
+/// persist jobs to be enqueued (and writes to be accepted) while the object
+/// store is degraded. The number of spilled jobs is instead bounded by the
+/// size limit of the [`SpillDir`].
+///
+/// The permit is otherwise released when the [`QueuePermit`] is dropped by the
+/// persist job.
+#[derive(Debug)]
+pub(crate) struct QueuePermit {
+    permit: Mutex<Option<OwnedSemaphorePermit>>,
+
+    /// The number of batches of the job that remain in memory.
+    in_memory: AtomicUsize,
+}
+
+impl QueuePermit {
+    /// Hold `permit` until all of `data` has been spilled, or the returned
+    /// [`QueuePermit`] is dropped.
+    ///
+    /// If all of `data` is already spilled, `permit` is released immediately.
+    pub(crate) fn new(permit: OwnedSemaphorePermit, data: &[SpillableBatches]) -> Arc<Self> {
+        let s = Arc::new(Self {
+            permit: Mutex::new(Some(permit)),
+            // Hold a count while registering with the batches below, so that a
+            // concurrent spill does not release the permit before all the
+            // in-memory batches are registered.
+            in_memory: AtomicUsize::new(1),
+        });
+
+        for v in data {
+            if let State::Memory(_, permit) = &mut *v.0.lock() {
+                s.in_memory.fetch_add(1, Ordering::SeqCst);
+                *permit = Arc::downgrade(&s);
+            }
+        }
+
+        s.batch_spilled();
+        s
+    }
+
+    /// Record one of the batches of the job being spilled, releasing the
+    /// permit if it was the last batch in memory.
+    fn batch_spilled(&self) {
+        if self.in_memory.fetch_sub(1, Ordering::SeqCst) == 1 {
+            debug!("persist job data spilled, releasing persist queue permit");
+            drop(self.permit.lock().take());
+        }
+    }
+}
+
+/// The location of the batches in a [`SpillableBatches`].
+#[derive(Debug)]
+enum State {
+    /// The batches are held in memory, along with the [`QueuePermit`] of the
+    /// persist job they belong to (if any).
+    Memory(Vec<RecordBatch>, Weak<QueuePermit>),
+
+    /// The batches were written to local disk, and released from memory.
+    Spilled(Arc<SpillFiles>),
+}
+
+/// An immutable set of [`RecordBatch`] that may be moved out of memory and into
+/// files on local disk, and read back when needed.
+///
+/// Cloning this type is cheap, and all clones share the same state - spilling
+/// one clone spills them all. The batches are released from memory once
+/// spilled, though callers that already obtained them (i.e. an ongoing query)
+/// retain their copy until they are done with it.
+///
+/// Obtaining the batches never performs I/O, so that it is safe to do so while
+/// holding a lock - spilled data is read back by [`PendingBatches::read()`].
+#[derive(Debug, Clone)]
+pub(crate) struct SpillableBatches(Arc<Mutex<State>>);
+
+impl SpillableBatches {
+    pub(crate) fn new(batches: Vec<RecordBatch>) -> Self {
+        Self(Arc::new(Mutex::new(State::Memory(batches, Weak::new()))))
+    }
+
+    /// Return the batches with `projection` applied, deferring the read of
+    /// spilled data to [`PendingBatches::read()`].
+    pub(crate) fn get_query_data(&self, projection: &OwnedProjection) -> PendingBatches {
+        match &*self.0.lock() {
+            State::Memory(batches, _) => {
+                PendingBatches::from(projection.project_record_batch(batches))
+            }
+            State::Spilled(files) => PendingBatches(vec![Pending::Spilled(Arc::clone(files))]),
+        }
+    }
+
+    /// Return the batches, reading them from disk if they have been spilled.
+    pub(crate) async fn read(&self) -> Result<Vec<RecordBatch>, SpillError> {
+        let projection = OwnedProjection::default();
+        self.get_query_data(&projection).read(&projection).await
+    }
+
+    /// Return the number of bytes of memory held by the batches, or 0 if they
+    /// have been spilled.
+    pub(crate) fn memory_bytes(&self) -> usize {
+        match &*self.0.lock() {
+            State::Memory(batches, _) => batches.iter().map(|v| v.get_array_memory_size()).sum(),
+            State::Spilled(_) => 0,
+        }
+    }
+
+    /// Returns true if the batches have been spilled to disk.
+    #[cfg(test)]
+    pub(crate) fn is_spilled(&self) -> bool {
+        matches!(&*self.0.lock(), State::Spilled(_))
+    }
+
+    /// Write the batches to files within a new subdirectory of `dir` and
+    /// release them from memory.
+    ///
+    /// Returns the number of bytes of memory released, which is 0 if the
+    /// batches were already spilled.
+    ///
+    /// This call performs blocking I/O.
+    pub(crate) fn spill(&self, dir: &Arc<SpillDir>) -> Result<usize, SpillError> {
+        let batches = match &*self.0.lock() {
+            State::Memory(batches, _) => batches.clone(),
+            State::Spilled(_) => return Ok(0),
+        };
+
+        // Write the files without holding the lock, so that concurrent queries
+        // are not blocked.
+        let files = SpillFiles::write(dir, &batches)?;
+
+        let mut guard = self.0.lock();
+        let (released, permit) = match &*guard {
+            State::Memory(batches, permit) => (
+                batches.iter().map(|v| v.get_array_memory_size()).sum(),
+                permit.upgrade(),
+            ),
+            // Concurrently spilled by another caller - drop the files written
+            // above.
+            State::Spilled(_) => return Ok(0),
+        };
+        *guard = State::Spilled(Arc::new(files));
+        drop(guard);
+
+        // The batches no longer occupy memory, and no longer need to hold the
+        // persist queue capacity of the job they belong to.
+        if let Some(permit) = permit {
+            permit.batch_spilled();
+        }
+
+        Ok(released)
+    }
+}
+
+/// An ordered set of [`RecordBatch`] obtained without performing any I/O, some
+/// of which may have been spilled and must be read back from local disk.
+#[derive(Debug, Default)]
+pub(crate) struct PendingBatches(Vec<Pending>);
+
+#[derive(Debug)]
+enum Pending {
+    /// Batches held in memory, with the projection already applied.
+    Memory(Vec<RecordBatch>),
+
+    /// Batches spilled to disk, projected once read.
+    Spilled(Arc<SpillFiles>),
+}
+
+impl From<Vec<RecordBatch>> for PendingBatches {
+    fn from(batches: Vec<RecordBatch>) -> Self {
+        Self(vec![Pending::Memory(batches)])
+    }
+}
+
+impl PendingBatches {
+    /// Append the batches in `other`, ordering them after those in `self`.
+    pub(crate) fn extend(&mut self, other: Self) {
+        self.0.extend(other.0)
+    }
+
+    /// Returns true if there are no batches to read.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.iter().all(|v| match v {
+            Pending::Memory(batches) => batches.is_empty(),
+            Pending::Spilled(files) => files.n_files == 0,
+        })
+    }
+
+    /// Return the batches, reading any spilled batches from disk (without
+    /// blocking the async runtime) and applying `projection` to them.
+    ///
+    /// The spill files are retained until this call completes, even if the
+    /// data they contain is persisted concurrently.
+    pub(crate) async fn read(
+        self,
+        projection: &OwnedProjection,
+    ) -> Result<Vec<RecordBatch>, SpillError> {
+        let mut out = Vec::new();
+        for v in self.0 {
+            match v {
+                Pending::Memory(batches) => out.extend(batches),
+                Pending::Spilled(files) => {
+                    let batches = tokio::task::spawn_blocking(move || files.read())
+                        .await
+                        .expect("spill read task panic")?;
+                    out.extend(projection.project_record_batch(&batches));
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+/// A set of Arrow IPC files on local disk, deleted when dropped.
+///
+/// Each [`RecordBatch`] is written to its own file as the batches within a
+/// persisting buffer are not guaranteed to share a schema.
+#[derive(Debug)]
+struct SpillFiles {
+    dir: Arc<SpillDir>,
+    path: PathBuf,
+    n_files: usize,
+
+    /// The disk space reserved in `dir` by these files.
+    bytes: u64,
+}
+
+impl SpillFiles {
+    fn write(dir: &Arc<SpillDir>, batches: &[RecordBatch]) -> Result<Self, SpillError> {
+        // Reserve space for the files before writing them, using the in-memory
+        // size as an estimate of the file size.
+        let estimate = batches
+            .iter()
+            .map(|v| v.get_array_memory_size() as u64)
+            .sum();
+        dir.reserve(estimate)?;
+
+        // Construct the handle before writing, so a partially written
+        // directory is removed (and the reservation released) on error.
+        let mut files = Self {
+            dir: Arc::clone(dir),
+            path: dir.path.join(Uuid::new_v4().to_string()),
+            n_files: batches.len(),
+            bytes: estimate,
+        };
+
+        std::fs::create_dir(&files.path)?;
+        let mut written = 0;
+        for (i, batch) in batches.iter().enumerate() {
+            let path = files.path(i);
+            let file = BufWriter::new(File::create(&path)?);
+            let mut writer = FileWriter::try_new(file, &batch.schema())?;
+            writer.write(batch)?;
+            writer.finish()?;
+            drop(writer);
+
+            written += std::fs::metadata(&path)?.len();
+        }
+
+        // Account for the actual size of the files.
+        files.dir.adjust(files.bytes, written);
+        files.bytes = written;
+
+        Ok(files)
+    }
+
+    fn read(&self) -> Result<Vec<RecordBatch>, SpillError> {
+        let mut batches = Vec::with_capacity(self.n_files);
+        for i in 0..self.n_files {
+            let file = BufReader::new(File::open(self.path(i))?);
+            for batch in FileReader::try_new(file, None)? {
+                batches.push(batch?);
+            }
+        }
+        Ok(batches)
+    }
+
+    fn path(&self, i: usize) -> PathBuf {
+        self.path.join(format!("{i}.arrow"))
+    }
+}
+
+impl Drop for SpillFiles {
+    fn drop(&mut self) {
+        self.dir.release(self.bytes);
+
+        if let Err(e) = std::fs::remove_dir_all(&self.path) {
+            warn!(
+                error=%e,
+                path=%self.path.display(),
+                "failed to remove spill files"
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use arrow_util::assert_batches_eq;
+    use assert_matches::assert_matches;
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+    use schema::Projection;
+
+    use super::*;
+
+    fn batch(lp: &str) -> RecordBatch {
+        lp_to_mutable_batch(lp)
+            .1
+            .to_arrow(Projection::All)
+            .expect("must convert")
+    }
+
+    fn spill_dir(tmp: &tempfile::TempDir, max_bytes: u64) -> Arc<SpillDir> {
+        Arc::new(SpillDir::new(tmp.path().to_path_buf(), max_bytes))
+    }
+
+    /// The queue permit of a persist job is released once all of the in-memory
+    /// batches of the job are spilled.
+    #[test]
+    fn test_queue_permit_released_on_spill() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = spill_dir(&tmp, u64::MAX);
+        let sem = Arc::new(tokio::sync::Semaphore::new(1));
+
+        let a = SpillableBatches::new(vec![batch("bananas,city=Madrid temp=35 42")]);
+        let b = SpillableBatches::new(vec![batch("bananas,city=London temp=25 43")]);
+        let permit = QueuePermit::new(
+            Arc::clone(&sem).try_acquire_owned().unwrap(),
+            &[a.clone(), b.clone()],
+        );
+        assert_eq!(sem.available_permits(), 0);
+
+        // Spilling one of the batches retains the permit.
+        a.spill(&dir).expect("spill should succeed");
+        assert_eq!(sem.available_permits(), 0);
+        a.spill(&dir).expect("spill should succeed");
+        assert_eq!(sem.available_permits(), 0);
+
+        // Spilling the last batch releases it.
+        b.spill(&dir).expect("spill should succeed");
+        assert_eq!(sem.available_permits(), 1);
+
+        // Dropping the released permit has no effect.
+        drop(permit);
+        assert_eq!(sem.available_permits(), 1);
+
+        // A permit for data that is already spilled is released immediately,
+        // and an unspilled permit is released when dropped.
+        let permit = QueuePermit::new(Arc::clone(&sem).try_acquire_owned().unwrap(), &[a]);
+        assert_eq!(sem.available_permits(), 1);
+        drop(permit);
+
+        let c = SpillableBatches::new(vec![batch("bananas,city=Paris temp=30 44")]);
+        let permit = QueuePermit::new(Arc::clone(&sem).try_acquire_owned().unwrap(), &[c]);
+        assert_eq!(sem.available_permits(), 0);
+        drop(permit);
+        assert_eq!(sem.available_permits(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_spill_round_trip() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = spill_dir(&tmp, u64::MAX);
+
+        // Two batches with differing schemas.
+        let data = SpillableBatches::new(vec![
+            batch("bananas,city=Madrid temp=35 42"),
+            batch("bananas,city=London temp=25,rain=true 43"),
+        ]);
+        let want = data.read().await.expect("read should succeed");
+
+        let bytes = data.memory_bytes();
+        assert!(bytes > 0);
+        assert!(!data.is_spilled());
+        assert_eq!(dir.used_bytes(), 0);
+
+        let released = data.spill(&dir).expect("spill should succeed");
+        assert_eq!(released, bytes);
+        assert!(data.is_spilled());
+        assert_eq!(data.memory_bytes(), 0);
+        assert!(dir.used_bytes() > 0);
+
+        // A clone observes the spilled data, and spilling again is a no-op.
+        let clone = data.clone();
+        assert!(clone.is_spilled());
+        assert_eq!(clone.spill(&dir).expect("spill should succeed"), 0);
+
+        let got = data.read().await.expect("read should succeed");
+        assert_eq!(got.len(), 2);
+        assert_batches_eq!(
+            [
+                "+--------+------+--------------------------------+",
+                "| city   | temp | time                           |",
+                "+--------+------+--------------------------------+",
+                "| Madrid | 35.0 | 1970-01-01T00:00:00.000000042Z |",
+                "+--------+------+--------------------------------+",
+            ],
+            &got[..1]
+        );
+        assert_eq!(got, want);
+
+        // The projection is applied to spilled data when it is read.
+        let projection = OwnedProjection::from(vec!["temp"]);
+        let got = data
+            .get_query_data(&projection)
+            .read(&projection)
+            .await
+            .expect("read should succeed");
+        assert_batches_eq!(
+            [
+                "+------+", //
+                "| temp |", //
+                "+------+", //
+                "| 35.0 |", //
+                "+------+", //
+            ],
+            &got[..1]
+        );
+
+        // The files are removed once all references are dropped.
+        let pending = data.get_query_data(&OwnedProjection::default());
+        drop(data);
+        drop(clone);
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 1);
+
+        // Ongoing reads retain the files until complete.
+        assert_eq!(
+            pending
+                .read(&OwnedProjection::default())
+                .await
+                .expect("read should succeed"),
+            want
+        );
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+        assert_eq!(dir.used_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_spill_read_error() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = spill_dir(&tmp, u64::MAX);
+
+        let data = SpillableBatches::new(vec![batch("bananas temp=35 42")]);
+        data.spill(&dir).expect("spill should succeed");
+
+        // Remove the spill files from under the spilled data.
+        for entry in std::fs::read_dir(tmp.path()).unwrap() {
+            std::fs::remove_dir_all(entry.unwrap().path()).unwrap();
+        }
+
+        assert_matches!(data.read().await, Err(SpillError::Io(_)));
+    }
+
+    #[tokio::test]
+    async fn test_spill_dir_create_removes_stale_files() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        // Files left over from a previous run, and an unrelated file.
+        let data = SpillableBatches::new(vec![batch("bananas temp=35 42")]);
+        data.spill(&spill_dir(&tmp, u64::MAX))
+            .expect("spill should succeed");
+        std::mem::forget(data);
+        std::fs::write(tmp.path().join("platanos"), "bananas").unwrap();
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 2);
+
+        let dir = SpillDir::create(tmp.path().to_path_buf(), u64::MAX)
+            .await
+            .expect("create should succeed");
+        assert_eq!(dir.used_bytes(), 0);
+
+        let got = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|v| v.unwrap().file_name())
+            .collect::<Vec<_>>();
+        assert_eq!(got, ["platanos"]);
+    }
+
+    #[test]
+    fn test_spill_directory_full() {
+        let tmp = tempfile::tempdir().unwrap();
+
+        let data = SpillableBatches::new(vec![batch("bananas temp=35 42")]);
+        let dir = spill_dir(&tmp, data.memory_bytes() as u64 - 1);
+
+        assert_matches!(data.spill(&dir), Err(SpillError::DirectoryFull { .. }));
+        assert!(!data.is_spilled());
+        assert_eq!(dir.used_bytes(), 0);
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+}
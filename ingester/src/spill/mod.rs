@@ -0,0 +1,426 @@
+//! Spilling of persisting and cold buffered data to local disk.
+//!
+//! When the object store is degraded, persist jobs take longer to complete and
+//! the data they reference accumulates in memory. Likewise partitions that are
+//! no longer written to retain their buffered data in memory until they are
+//! persisted.
+//!
+//! When enabled, the [`periodic_spill()`] task bounds the amount of memory used
+//! by this data: once it exceeds the configured threshold, the largest
+//! persisting batches are written to Arrow IPC files on local disk and released
+//! from memory, followed by the buffered data of the least recently written
+//! partitions. Spill files are written to a dedicated directory, the total size
+//! of which is capped.
+//!
+//! A persist job releases its persist queue permit once all of its data is
+//! spilled (see [`QueuePermit`]), so a degraded object store does not saturate
+//! the persist queue and block ingest while there is spill capacity available.
+//! The spilled jobs are instead bounded by the size of the spill directory.
+//!
+//! Spilled data is read back when queried (without blocking the async runtime
+//! or holding the partition lock), and when the persist job eventually runs.
+//! The files are deleted once the data is persisted.
+//!
+//! Spilled data is not durable across restarts - the data remains in the WAL
+//! until persisted and is recovered by replaying it.
+
+mod batches;
+
+pub(crate) use batches::*;
+
+use std::{sync::Arc, time::Duration};
+
+use metric::{U64Counter, U64Gauge};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+
+use crate::{buffer_tree::partition::PartitionData, partition_iter::PartitionIter};
+
+/// The interval of time between evaluations of the memory used by spillable
+/// data.
+const SPILL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Metrics recorded by the [`periodic_spill()`] task.
+#[derive(Debug)]
+pub(crate) struct SpillMetrics {
+    memory_bytes: U64Gauge,
+    disk_bytes: U64Gauge,
+    spilled_bytes: U64Counter,
+    spilled_batches: U64Counter,
+}
+
+impl SpillMetrics {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let memory_bytes = metrics
+            .register_metric::<U64Gauge>(
+                "ingester_spill_memory_bytes",
+                "the estimated number of bytes of memory used by persisting and \
+                buffered data that has not been spilled to disk",
+            )
+            .recorder(&[]);
+        let disk_bytes = metrics
+            .register_metric::<U64Gauge>(
+                "ingester_spill_disk_bytes",
+                "the number of bytes of disk space used by spill files",
+            )
+            .recorder(&[]);
+        let spilled_bytes = metrics
+            .register_metric::<U64Counter>(
+                "ingester_spill_bytes",
+                "the estimated number of bytes of memory released by spilling \
+                data to disk",
+            )
+            .recorder(&[]);
+        let spilled_batches = metrics
+            .register_metric::<U64Counter>(
+                "ingester_spill_batch_count",
+                "the number of persisting or buffered batches spilled to disk",
+            )
+            .recorder(&[]);
+
+        Self {
+            memory_bytes,
+            disk_bytes,
+            spilled_bytes,
+            spilled_batches,
+        }
+    }
+}
+
+/// Periodically spill persisting and buffered data in `buffer` to files within
+/// `dir` when the memory it uses exceeds `max_memory_bytes`.
+pub(crate) async fn periodic_spill<T>(
+    buffer: T,
+    dir: Arc<SpillDir>,
+    max_memory_bytes: usize,
+    metrics: SpillMetrics,
+) where
+    T: PartitionIter + Sync + 'static,
+{
+    let mut interval = tokio::time::interval(SPILL_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        spill_to_threshold(&buffer, &dir, max_memory_bytes, &metrics).await;
+    }
+}
+
+/// Data that can be spilled to disk.
+#[derive(Debug)]
+enum Candidate {
+    /// Immutable persisting data (or a snapshot of buffered data).
+    Spillable(SpillableBatches),
+
+    /// The mutable buffer of a partition, which must be snapshot before it can
+    /// be spilled.
+    Buffered(Arc<Mutex<PartitionData>>),
+}
+
+/// Spill data in `buffer` until the memory it uses is no more than
+/// `max_memory_bytes`, or spilling fails.
+///
+/// The largest persisting batches are spilled first, minimising the number of
+/// files written, followed by the buffered data of the least recently written
+/// partitions.
+async fn spill_to_threshold<T>(
+    buffer: &T,
+    dir: &Arc<SpillDir>,
+    max_memory_bytes: usize,
+    metrics: &SpillMetrics,
+) where
+    T: PartitionIter + Sync + 'static,
+{
+    // Obtain a reference to all the in-memory persisting data, and the
+    // partitions with buffered data, along with the memory they use.
+    let mut spillable = Vec::new();
+    let mut buffered = Vec::new();
+    for p in buffer.partition_iter() {
+        let (buffered_bytes, last_write_at) = {
+            let guard = p.lock();
+            spillable.extend(
+                guard
+                    .spillable_data()
+                    .filter_map(|v| match v.memory_bytes() {
+                        0 => None,
+                        n => Some((n, v.clone())),
+                    }),
+            );
+            (guard.persist_cost_estimate(), guard.last_write_at())
+        };
+
+        match (buffered_bytes, last_write_at) {
+            (0, _) | (_, None) => {}
+            (n, Some(last_write_at)) => buffered.push((n, last_write_at, p)),
+        }
+    }
+
+    let mut total = spillable
+        .iter()
+        .map(|(n, _)| *n)
+        .chain(buffered.iter().map(|(n, _, _)| *n))
+        .sum::<usize>();
+    metrics.memory_bytes.set(total as _);
+
+    if total > max_memory_bytes {
+        // Spill the largest persisting batches first, followed by the coldest
+        // buffered partitions.
+        spillable.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+        buffered.sort_unstable_by_key(|(_, last_write_at, _)| *last_write_at);
+
+        let candidates = spillable
+            .into_iter()
+            .map(|(n, v)| (n, Candidate::Spillable(v)))
+            .chain(
+                buffered
+                    .into_iter()
+                    .map(|(n, _, p)| (n, Candidate::Buffered(p))),
+            );
+
+        for (n, candidate) in candidates {
+            if total <= max_memory_bytes {
+                break;
+            }
+
+            let data = match candidate {
+                Candidate::Spillable(v) => v,
+                Candidate::Buffered(p) => match p.lock().snapshot_buffer() {
+                    Some(v) => v,
+                    // The buffer was concurrently persisted.
+                    None => continue,
+                },
+            };
+
+            let spill_dir = Arc::clone(dir);
+            let res = tokio::task::spawn_blocking(move || data.spill(&spill_dir))
+                .await
+                .expect("spill task panic");
+
+            match res {
+                Ok(released) => {
+                    total = total.saturating_sub(n);
+                    metrics.spilled_bytes.inc(released as _);
+                    metrics.spilled_batches.inc(1);
+                    debug!(
+                        released_bytes = released,
+                        memory_bytes = total,
+                        disk_bytes = dir.used_bytes(),
+                        "spilled data to disk"
+                    );
+                }
+                Err(e) => {
+                    warn!(
+                        error=%e,
+                        memory_bytes = total,
+                        disk_bytes = dir.used_bytes(),
+                        "failed to spill data to disk"
+                    );
+                    break;
+                }
+            }
+        }
+    }
+
+    metrics.memory_bytes.set(total as _);
+    metrics.disk_bytes.set(dir.used_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use data_types::{PartitionKey, SequenceNumber};
+    use metric::{assert_counter, Attributes, Metric};
+    use mutable_batch_lp::test_helpers::lp_to_mutable_batch;
+
+    use super::*;
+    use crate::{query::projection::OwnedProjection, test_util::PartitionDataBuilder};
+
+    /// Build a partition containing `n_rows` rows of buffered data.
+    fn buffered_partition(partition_key: &str, n_rows: usize) -> Arc<Mutex<PartitionData>> {
+        let mut p = PartitionDataBuilder::new()
+            .with_partition_key(PartitionKey::from(partition_key))
+            .build();
+
+        let lp = (0..n_rows)
+            .map(|i| format!("bananas,city=Madrid temp={i} {}", 42 + i))
+            .collect::<Vec<_>>()
+            .join("\n");
+        p.buffer_write(lp_to_mutable_batch(&lp).1, SequenceNumber::new(1))
+            .expect("write should succeed");
+
+        Arc::new(Mutex::new(p))
+    }
+
+    /// Build a partition containing `n_rows` rows that is marked as persisting.
+    fn persisting_partition(partition_key: &str, n_rows: usize) -> Arc<Mutex<PartitionData>> {
+        let p = buffered_partition(partition_key, n_rows);
+
+        // The returned handle is not needed, as the partition retains a
+        // reference to the persisting data.
+        p.lock().mark_persisting().expect("must have data");
+
+        p
+    }
+
+    fn spilled(p: &Arc<Mutex<PartitionData>>) -> bool {
+        let p = p.lock();
+        p.spillable_data().count() > 0 && p.spillable_data().all(|v| v.is_spilled())
+    }
+
+    async fn query(p: &Arc<Mutex<PartitionData>>) -> Vec<arrow::record_batch::RecordBatch> {
+        let data = p
+            .lock()
+            .get_query_data(&OwnedProjection::default())
+            .expect("must have data");
+        data.read()
+            .await
+            .expect("read should succeed")
+            .into_record_batches()
+    }
+
+    fn gauge(registry: &metric::Registry, name: &'static str) -> u64 {
+        registry
+            .get_instrument::<Metric<U64Gauge>>(name)
+            .unwrap()
+            .get_observer(&Attributes::from([]))
+            .unwrap()
+            .fetch()
+    }
+
+    #[tokio::test]
+    async fn test_spill_to_threshold() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Arc::new(SpillDir::new(tmp.path().to_path_buf(), u64::MAX));
+        let registry = metric::Registry::default();
+        let metrics = SpillMetrics::new(&registry);
+
+        let small = persisting_partition("small", 1);
+        let large = persisting_partition("large", 100);
+        let buffer = vec![Arc::clone(&small), Arc::clone(&large)];
+
+        let want = query(&large).await;
+
+        // Below the threshold, nothing is spilled.
+        spill_to_threshold(&buffer, &dir, usize::MAX, &metrics).await;
+        assert!(!spilled(&small));
+        assert!(!spilled(&large));
+        assert_counter!(
+            registry,
+            U64Counter,
+            "ingester_spill_batch_count",
+            value = 0,
+        );
+        assert_eq!(gauge(&registry, "ingester_spill_disk_bytes"), 0);
+
+        // A threshold that can be satisfied by spilling only the largest
+        // partition.
+        let small_bytes = small
+            .lock()
+            .spillable_data()
+            .map(|v| v.memory_bytes())
+            .sum::<usize>();
+        spill_to_threshold(&buffer, &dir, small_bytes, &metrics).await;
+        assert!(!spilled(&small));
+        assert!(spilled(&large));
+        assert_counter!(
+            registry,
+            U64Counter,
+            "ingester_spill_batch_count",
+            value = 1,
+        );
+        assert_eq!(
+            gauge(&registry, "ingester_spill_memory_bytes"),
+            small_bytes as u64
+        );
+        assert_eq!(
+            gauge(&registry, "ingester_spill_disk_bytes"),
+            dir.used_bytes()
+        );
+        assert!(dir.used_bytes() > 0);
+
+        // The spilled data is read back when queried.
+        assert_eq!(query(&large).await, want);
+
+        // A zero threshold spills everything.
+        spill_to_threshold(&buffer, &dir, 0, &metrics).await;
+        assert!(spilled(&small));
+        assert_counter!(
+            registry,
+            U64Counter,
+            "ingester_spill_batch_count",
+            value = 2,
+        );
+    }
+
+    /// Once the persisting data is spilled, the buffered data of the least
+    /// recently written partitions is spilled.
+    #[tokio::test]
+    async fn test_spill_cold_buffered() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Arc::new(SpillDir::new(tmp.path().to_path_buf(), u64::MAX));
+        let registry = metric::Registry::default();
+        let metrics = SpillMetrics::new(&registry);
+
+        // Written to first, and therefore colder.
+        let cold = buffered_partition("cold", 100);
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        let hot = buffered_partition("hot", 100);
+        let buffer = vec![Arc::clone(&hot), Arc::clone(&cold)];
+
+        let want = query(&cold).await;
+        let hot_bytes = hot.lock().persist_cost_estimate();
+
+        spill_to_threshold(&buffer, &dir, hot_bytes, &metrics).await;
+        assert!(spilled(&cold));
+        assert!(!spilled(&hot));
+        assert_eq!(hot.lock().persist_cost_estimate(), hot_bytes);
+        assert_counter!(
+            registry,
+            U64Counter,
+            "ingester_spill_batch_count",
+            value = 1,
+        );
+        assert_eq!(
+            gauge(&registry, "ingester_spill_memory_bytes"),
+            hot_bytes as u64
+        );
+
+        // The spilled data remains buffered and queryable, and continues to
+        // accept writes.
+        assert_eq!(query(&cold).await, want);
+        cold.lock()
+            .buffer_write(
+                lp_to_mutable_batch("bananas,city=Madrid temp=42 1").1,
+                SequenceNumber::new(2),
+            )
+            .expect("write should succeed");
+        assert_eq!(cold.lock().rows(), 101);
+
+        // And is persisted along with the newly buffered data.
+        let data = cold.lock().mark_persisting().expect("must have data");
+        let data = data.query_adaptor().await.expect("read should succeed");
+        assert_eq!(data.num_rows(), 101);
+    }
+
+    /// Spilling stops once the spill directory reaches its size limit.
+    #[tokio::test]
+    async fn test_spill_directory_full() {
+        let tmp = tempfile::tempdir().unwrap();
+        let dir = Arc::new(SpillDir::new(tmp.path().to_path_buf(), 0));
+        let registry = metric::Registry::default();
+        let metrics = SpillMetrics::new(&registry);
+
+        let p = persisting_partition("bananas", 10);
+        let buffer = vec![Arc::clone(&p)];
+
+        spill_to_threshold(&buffer, &dir, 0, &metrics).await;
+        assert!(!spilled(&p));
+        assert_counter!(
+            registry,
+            U64Counter,
+            "ingester_spill_batch_count",
+            value = 0,
+        );
+        assert_eq!(gauge(&registry, "ingester_spill_disk_bytes"), 0);
+        assert_eq!(std::fs::read_dir(tmp.path()).unwrap().count(), 0);
+    }
+}
@@ -3,7 +3,7 @@ use std::{collections::BTreeMap, num::NonZeroUsize, sync::Arc, time::Duration};
 use data_types::{
     partition_template::TablePartitionTemplateOverride, ColumnId, ColumnSet, NamespaceId,
     ParquetFile, ParquetFileId, PartitionHashId, PartitionId, PartitionKey, SequenceNumber,
-    TableId, Timestamp, TransitionPartitionId,
+    StorageTier, TableId, Timestamp, TransitionPartitionId,
 };
 use hashbrown::HashSet;
 use iox_catalog::{interface::Catalog, test_helpers::arbitrary_namespace};
@@ -308,14 +308,14 @@ macro_rules! make_partition_stream {
                     )+
                     drop(schema);
 
-                    PartitionResponse::new(
+                    Ok(PartitionResponse::new(
                         batches,
                         TransitionPartitionId::new(
                             TableId::new($id),
                             &*ARBITRARY_PARTITION_KEY,
                         ),
                         42,
-                    )
+                    ))
                 },)+
             ]))
         }};
@@ -433,6 +433,7 @@ where
             created_at: Timestamp::new(1234),
             column_set: ColumnSet::new([1, 2, 3, 4].into_iter().map(ColumnId::new)),
             max_l0_created_at: Timestamp::new(42),
+            storage_tier: StorageTier::Hot,
         },
         sequence_numbers
             .into_iter()
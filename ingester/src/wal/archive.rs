@@ -0,0 +1,505 @@
+//! Archiving of closed WAL segment files to object storage.
+//!
+//! When enabled, the [`WalArchiver`] uploads each closed segment file to object
+//! storage before it is deleted from local disk, once the data it contains has
+//! been persisted. The archived segments are retained for a configurable period
+//! of time by the [`periodic_archive_expiry()`] task, allowing the writes they
+//! contain to be replayed from a point in time (see the `influxdb_iox debug wal
+//! replay-archive` command).
+//!
+//! Segment files are archived as they exist on disk - segments encrypted at
+//! rest remain encrypted in the archive.
+
+use std::{
+    ops::ControlFlow,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::{Duration, SystemTime},
+};
+
+use async_trait::async_trait;
+use backoff::{Backoff, BackoffConfig};
+use futures::TryStreamExt;
+use metric::U64Counter;
+use object_store::{path::Path, DynObjectStore};
+use observability_deps::tracing::*;
+use thiserror::Error;
+use tokio::{
+    io::AsyncWriteExt,
+    sync::{mpsc, Notify},
+};
+use wal::{ArchivedSegmentName, SegmentId};
+
+use super::reference_tracker::WalFileDeleter;
+
+/// The interval of time between evaluations of the archived segments for
+/// expiry.
+const EXPIRY_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// An error uploading a segment file to object storage.
+#[derive(Debug, Error)]
+enum UploadError {
+    #[error("failed to start upload: {0}")]
+    Start(object_store::Error),
+
+    #[error("failed to upload segment data: {0}")]
+    Write(std::io::Error),
+}
+
+/// A [`WalFileDeleter`] that enqueues each segment file to be uploaded to
+/// object storage and then deleted by the [`WalArchiveTask`].
+///
+/// Enqueuing never blocks, allowing the WAL reference tracker to continue
+/// processing while segments are uploaded.
+#[derive(Debug, Clone)]
+pub(crate) struct WalArchiver {
+    tx: mpsc::UnboundedSender<SegmentId>,
+    pending: Arc<Pending>,
+}
+
+/// The number of enqueued segments yet to be processed by the
+/// [`WalArchiveTask`].
+#[derive(Debug, Default)]
+struct Pending {
+    n: AtomicUsize,
+    idle: Notify,
+}
+
+impl WalArchiver {
+    /// Initialise a [`WalArchiver`] that archives segment files of `wal` to
+    /// `store` under `prefix`, and the [`WalArchiveTask`] that performs the
+    /// archiving.
+    pub(crate) fn new(
+        wal: Arc<wal::Wal>,
+        store: Arc<DynObjectStore>,
+        prefix: Path,
+        metrics: &metric::Registry,
+    ) -> (Self, WalArchiveTask) {
+        let archived_segments = metrics
+            .register_metric::<U64Counter>(
+                "ingester_wal_archive_segments",
+                "the number of closed wal segment files uploaded to object storage",
+            )
+            .recorder(&[]);
+        let archived_bytes = metrics
+            .register_metric::<U64Counter>(
+                "ingester_wal_archive_bytes",
+                "the number of bytes of closed wal segment files uploaded to object storage",
+            )
+            .recorder(&[]);
+
+        let (tx, rx) = mpsc::unbounded_channel();
+        let pending = Arc::new(Pending::default());
+
+        let task = WalArchiveTask {
+            rx,
+            pending: Arc::clone(&pending),
+            wal,
+            store,
+            prefix,
+            backoff_config: BackoffConfig::default(),
+            archived_segments,
+            archived_bytes,
+        };
+
+        (Self { tx, pending }, task)
+    }
+
+    /// Wait until all the segments enqueued so far have been processed.
+    pub(crate) async fn wait_idle(&self) {
+        loop {
+            let notified = self.pending.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if self.pending.n.load(Ordering::Acquire) == 0 {
+                return;
+            }
+            notified.await;
+        }
+    }
+}
+
+#[async_trait]
+impl WalFileDeleter for WalArchiver {
+    async fn delete_file(&self, id: SegmentId) {
+        self.pending.n.fetch_add(1, Ordering::AcqRel);
+        if self.tx.send(id).is_err() {
+            // The archive task has stopped, and the segment is retained on
+            // disk to be replayed (and archived) after the next restart.
+            error!(%id, "wal archive task stopped, retaining wal segment");
+            self.pending.n.fetch_sub(1, Ordering::AcqRel);
+        }
+    }
+}
+
+/// A background task that uploads the segment files enqueued by a
+/// [`WalArchiver`] to object storage, deleting each from local disk only once
+/// it has been uploaded.
+///
+/// Uploads are retried until they succeed. A segment that cannot be read is
+/// retained on disk, and is replayed (and archived) after the next restart.
+#[derive(Debug)]
+pub(crate) struct WalArchiveTask {
+    rx: mpsc::UnboundedReceiver<SegmentId>,
+    pending: Arc<Pending>,
+
+    wal: Arc<wal::Wal>,
+    store: Arc<DynObjectStore>,
+    prefix: Path,
+    backoff_config: BackoffConfig,
+
+    archived_segments: U64Counter,
+    archived_bytes: U64Counter,
+}
+
+impl WalArchiveTask {
+    /// Archive and delete enqueued segments until all [`WalArchiver`] handles
+    /// are dropped.
+    pub(crate) async fn run(mut self) {
+        while let Some(id) = self.rx.recv().await {
+            if self.archive(id).await {
+                self.wal.delete_file(id).await;
+            }
+
+            if self.pending.n.fetch_sub(1, Ordering::AcqRel) == 1 {
+                self.pending.idle.notify_waiters();
+            }
+        }
+    }
+
+    /// Upload the segment file with the given `id` to object storage,
+    /// returning true if it was uploaded and can be deleted.
+    async fn archive(&self, id: SegmentId) -> bool {
+        let path = self.wal.segment_path(id);
+
+        let closed_at = match tokio::fs::metadata(&path).await.and_then(|v| v.modified()) {
+            Ok(v) => v,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                // The segment may have already been removed after being
+                // handed off to a peer ingester during shutdown.
+                warn!(%id, "wal segment already deleted");
+                return false;
+            }
+            Err(e) => {
+                warn!(%id, error=%e, "unable to read wal segment for archiving, retaining segment");
+                return false;
+            }
+        };
+
+        let location = self
+            .prefix
+            .child(ArchivedSegmentName::new(closed_at, id).to_string());
+
+        // Stream the segment file to object storage, re-reading it from the
+        // start for each attempt.
+        let res = Backoff::new(&self.backoff_config)
+            .retry_with_backoff("archive wal segment", || {
+                let path = &path;
+                let location = &location;
+                async move {
+                    let file = match tokio::fs::File::open(path).await {
+                        Ok(v) => v,
+                        Err(e) => return ControlFlow::Break(Err(e)),
+                    };
+                    match upload(&*self.store, location, file).await {
+                        Ok(bytes) => ControlFlow::Break(Ok(bytes)),
+                        Err(e) => ControlFlow::Continue(e),
+                    }
+                }
+            })
+            .await
+            .expect("retry forever");
+
+        let bytes = match res {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(%id, error=%e, "unable to read wal segment for archiving, retaining segment");
+                return false;
+            }
+        };
+
+        self.archived_segments.inc(1);
+        self.archived_bytes.inc(bytes);
+
+        debug!(%id, %location, bytes, "archived wal segment");
+        true
+    }
+}
+
+/// Upload the contents of `file` to `location` in `store` as a multipart
+/// upload, returning the number of bytes uploaded.
+///
+/// The upload is aborted if it fails.
+async fn upload(
+    store: &DynObjectStore,
+    location: &Path,
+    mut file: tokio::fs::File,
+) -> Result<u64, UploadError> {
+    let (multipart_id, mut writer) = store
+        .put_multipart(location)
+        .await
+        .map_err(UploadError::Start)?;
+
+    let res = async {
+        let bytes = tokio::io::copy(&mut file, &mut writer).await?;
+        writer.shutdown().await?;
+        Ok::<_, std::io::Error>(bytes)
+    }
+    .await;
+
+    if res.is_err() {
+        if let Err(e) = store.abort_multipart(location, &multipart_id).await {
+            warn!(%location, error=%e, "failed to abort wal segment upload");
+        }
+    }
+
+    res.map_err(UploadError::Write)
+}
+
+/// Metrics recorded by the [`periodic_archive_expiry()`] task.
+#[derive(Debug)]
+pub(crate) struct ArchiveExpiryMetrics {
+    expired_segments: U64Counter,
+}
+
+impl ArchiveExpiryMetrics {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let expired_segments = metrics
+            .register_metric::<U64Counter>(
+                "ingester_wal_archive_expired_segments",
+                "the number of archived wal segment files deleted from object storage \
+                after exceeding the retention period",
+            )
+            .recorder(&[]);
+
+        Self { expired_segments }
+    }
+}
+
+/// Periodically delete segment files archived under `prefix` that were closed
+/// more than `retention` ago.
+pub(crate) async fn periodic_archive_expiry(
+    store: Arc<DynObjectStore>,
+    prefix: Path,
+    retention: Duration,
+    metrics: ArchiveExpiryMetrics,
+) {
+    let mut interval = tokio::time::interval(EXPIRY_INTERVAL);
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+
+        let Some(cutoff) = SystemTime::now().checked_sub(retention) else {
+            continue;
+        };
+
+        match expire_archived_segments(&*store, &prefix, cutoff).await {
+            Ok(n) => {
+                metrics.expired_segments.inc(n as _);
+                if n > 0 {
+                    info!(n_segments = n, %prefix, "expired archived wal segments");
+                }
+            }
+            Err(e) => warn!(error=%e, %prefix, "failed to expire archived wal segments"),
+        }
+    }
+}
+
+/// Delete the segment files archived under `prefix` that were closed before
+/// `cutoff`, returning the number of segments deleted.
+///
+/// Objects under `prefix` that are not archived segment files are ignored.
+async fn expire_archived_segments(
+    store: &DynObjectStore,
+    prefix: &Path,
+    cutoff: SystemTime,
+) -> Result<usize, object_store::Error> {
+    let expired = store
+        .list(Some(prefix))
+        .await?
+        .try_filter_map(|meta| async move {
+            Ok(meta
+                .location
+                .filename()
+                .and_then(ArchivedSegmentName::parse)
+                .filter(|name| name.closed_at() < cutoff)
+                .map(|_| meta.location))
+        })
+        .try_collect::<Vec<_>>()
+        .await?;
+
+    for location in &expired {
+        store.delete(location).await?;
+        debug!(%location, "deleted expired archived wal segment");
+    }
+
+    Ok(expired.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use bytes::Bytes;
+    use data_types::TableId;
+    use metric::assert_counter;
+    use object_store::{memory::InMemory, ObjectStore};
+    use test_helpers::timeout::FutureTimeout;
+    use wal::{SequencedWalOp, WalOp};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_archive_before_delete() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = wal::Wal::new(dir.path()).await.unwrap();
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let metrics = metric::Registry::default();
+
+        let (archiver, task) = WalArchiver::new(
+            Arc::clone(&wal),
+            Arc::clone(&store),
+            Path::from("wal_archive/ingester-1"),
+            &metrics,
+        );
+
+        wal.write_op(SequencedWalOp {
+            table_write_sequence_numbers: [(TableId::new(1), 42)].into_iter().collect(),
+            op: WalOp::Write(Default::default()),
+        })
+        .changed()
+        .await
+        .unwrap();
+        let (closed, _) = wal.rotate().unwrap();
+        let want = std::fs::read(closed.path()).unwrap();
+
+        // Enqueuing the segment does not wait for it to be archived.
+        archiver.delete_file(closed.id()).await;
+        assert!(closed.path().exists());
+
+        let task = tokio::spawn(task.run());
+        archiver
+            .wait_idle()
+            .with_timeout_panic(Duration::from_secs(5))
+            .await;
+
+        // The segment was deleted from local disk.
+        assert!(!closed.path().exists());
+
+        // And a copy uploaded to object storage.
+        let archived = store
+            .list(Some(&Path::from("wal_archive/ingester-1")))
+            .await
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        assert_eq!(archived.len(), 1);
+
+        let name = ArchivedSegmentName::parse(archived[0].location.filename().unwrap()).unwrap();
+        assert_eq!(name.id(), closed.id());
+
+        let got = store
+            .get(&archived[0].location)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        assert_eq!(got, want);
+
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_archive_segments",
+            value = 1,
+        );
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_archive_bytes",
+            value = want.len() as u64,
+        );
+
+        // Deleting a segment that no longer exists is a no-op.
+        archiver.delete_file(closed.id()).await;
+        archiver
+            .wait_idle()
+            .with_timeout_panic(Duration::from_secs(5))
+            .await;
+        assert_counter!(
+            metrics,
+            U64Counter,
+            "ingester_wal_archive_segments",
+            value = 1,
+        );
+
+        // The task stops once the archiver is dropped.
+        drop(archiver);
+        task.with_timeout_panic(Duration::from_secs(5))
+            .await
+            .expect("archive task panicked");
+    }
+
+    #[tokio::test]
+    async fn test_archive_task_stopped() {
+        let dir = tempfile::tempdir().unwrap();
+        let wal = wal::Wal::new(dir.path()).await.unwrap();
+        let store: Arc<DynObjectStore> = Arc::new(InMemory::new());
+        let metrics = metric::Registry::default();
+
+        let (archiver, task) = WalArchiver::new(
+            Arc::clone(&wal),
+            Arc::clone(&store),
+            Path::from("wal_archive"),
+            &metrics,
+        );
+        drop(task);
+
+        let (closed, _) = wal.rotate().unwrap();
+
+        // A segment that cannot be archived is retained on disk.
+        archiver.delete_file(closed.id()).await;
+        archiver
+            .wait_idle()
+            .with_timeout_panic(Duration::from_secs(5))
+            .await;
+        assert!(closed.path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_expire_archived_segments() {
+        let store = InMemory::new();
+        let prefix = Path::from("wal_archive");
+        let now = SystemTime::now();
+
+        let old = prefix.child(
+            ArchivedSegmentName::new(now - Duration::from_secs(100), SegmentId::new(1)).to_string(),
+        );
+        let new = prefix.child(
+            ArchivedSegmentName::new(now - Duration::from_secs(10), SegmentId::new(2)).to_string(),
+        );
+        let other = prefix.child("bananas");
+        for location in [&old, &new, &other] {
+            store.put(location, Bytes::from("data")).await.unwrap();
+        }
+
+        let n = expire_archived_segments(&store, &prefix, now - Duration::from_secs(50))
+            .await
+            .unwrap();
+        assert_eq!(n, 1);
+
+        let mut remaining = store
+            .list(Some(&prefix))
+            .await
+            .unwrap()
+            .map_ok(|v| v.location)
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap();
+        remaining.sort();
+        assert_eq!(remaining, [new, other]);
+    }
+}
@@ -1,6 +1,7 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
+use observability_deps::tracing::warn;
 use wal::SegmentId;
 
 /// An abstraction defining the ability of an implementer to delete WAL segment
@@ -15,6 +16,14 @@ pub(crate) trait WalFileDeleter: Debug + Send + Sync + 'static {
 #[async_trait]
 impl WalFileDeleter for Arc<wal::Wal> {
     async fn delete_file(&self, id: SegmentId) {
-        self.delete(id).await.expect("failed to drop wal segment");
+        match self.delete(id).await {
+            Ok(()) => {}
+            // The segment may have already been removed after being handed off
+            // to a peer ingester during shutdown.
+            Err(wal::Error::SegmentNotFound { .. }) => {
+                warn!(%id, "wal segment already deleted");
+            }
+            Err(e) => panic!("failed to drop wal segment: {e}"),
+        }
     }
 }
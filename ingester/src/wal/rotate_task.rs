@@ -92,6 +92,7 @@ mod tests {
 
     use assert_matches::assert_matches;
     use async_trait::async_trait;
+    use data_types::ParquetFileId;
     use parking_lot::Mutex;
     use tempfile::tempdir;
     use test_helpers::timeout::FutureTimeout;
@@ -249,7 +250,7 @@ mod tests {
         /// Observed PartitionData instances.
         calls: Mutex<Vec<Arc<Mutex<PartitionData>>>>,
         // The tx handles that callers are blocked waiting on.
-        tx: Mutex<Vec<oneshot::Sender<()>>>,
+        tx: Mutex<Vec<oneshot::Sender<ParquetFileId>>>,
     }
 
     #[async_trait]
@@ -259,7 +260,7 @@ mod tests {
             &self,
             partition: Arc<Mutex<PartitionData>>,
             _data: PersistingData,
-        ) -> oneshot::Receiver<()> {
+        ) -> oneshot::Receiver<ParquetFileId> {
             self.calls.lock().push(Arc::clone(&partition));
             let (tx, rx) = oneshot::channel();
             self.tx.lock().push(tx);
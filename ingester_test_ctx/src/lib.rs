@@ -30,7 +30,9 @@ use futures::{stream::FuturesUnordered, FutureExt, StreamExt, TryStreamExt};
 use generated_types::influxdata::iox::ingester::v1::{
     write_service_server::WriteService, WriteRequest,
 };
-use ingester::{GossipConfig, IngesterGuard, IngesterRpcInterface};
+use ingester::{
+    GossipConfig, HandoffConfig, IngesterGuard, IngesterRpcInterface, SpillConfig, WalArchiveConfig,
+};
 use ingester_query_grpc::influxdata::iox::ingester::v1::IngesterQueryRequest;
 use iox_catalog::{
     interface::{Catalog, SoftDeletedRows},
@@ -163,13 +165,21 @@ impl TestContextBuilder {
             persist_background_fetch_time,
             dir.path().to_owned(),
             wal_rotation_period,
+            Default::default(),
+            Default::default(),
             persist_executor,
             persist_workers,
             max_persist_queue_depth,
             persist_hot_partition_cost,
             storage.clone(),
             GossipConfig::default(),
+            HandoffConfig::default(),
+            SpillConfig::default(),
+            WalArchiveConfig::default(),
+            Default::default(),
+            NonZeroUsize::new(4).unwrap(),
             NonZeroUsize::new(usize::MAX).unwrap(),
+            None,
             shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
         )
         .await
@@ -240,6 +250,10 @@ where
                         max_columns_per_table: Default::default(),
                         retention_period_ns,
                         partition_template: partition_template.unwrap_or_default(),
+                        schema_mode: Default::default(),
+                        field_coercion: Default::default(),
+                        write_rules: Default::default(),
+                        properties_version: 0,
                     },
                 )
                 .is_none(),
@@ -422,7 +436,10 @@ where
         self.ingester
             .rpc()
             .persist_service()
-            .persist(Request::new(proto::PersistRequest { namespace }))
+            .persist(Request::new(proto::PersistRequest {
+                namespace,
+                ..Default::default()
+            }))
             .await
             .expect("failed to invoke persist");
     }
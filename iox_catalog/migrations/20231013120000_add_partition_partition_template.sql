@@ -0,0 +1,13 @@
+-- Record the partition template that derived each partition key, so that
+-- partition keys remain interpretable when the table template is changed.
+--
+-- NULL = the default partition template, as for the "table_name" table.
+ALTER TABLE
+    IF EXISTS partition
+    ADD COLUMN partition_template JSONB;
+
+-- Existing partitions were all created with the current table template.
+UPDATE partition
+SET partition_template = table_name.partition_template
+FROM table_name
+WHERE table_name.id = partition.table_id;
@@ -0,0 +1,8 @@
+-- Add a counter of the changes made to the namespace properties applied to
+-- writes by routers (the write rules and schema mode) to the "namespace"
+-- table.
+--
+-- Routers use it to discard stale copies of these properties.
+ALTER TABLE
+    IF EXISTS namespace
+    ADD COLUMN properties_version BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Add a counter of the changes made to the partition template of a table to
+-- the "table_name" table.
+--
+-- Routers use it to discard stale copies of the table partition template.
+ALTER TABLE
+    IF EXISTS table_name
+    ADD COLUMN partition_template_version BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Add a schema mode to the "namespace" table, controlling whether writes may
+-- implicitly create tables and columns.
+--
+-- 1 = implicit (the existing behaviour), 2 = explicit.
+ALTER TABLE
+    namespace
+ADD COLUMN schema_mode INTEGER NOT NULL DEFAULT 1;
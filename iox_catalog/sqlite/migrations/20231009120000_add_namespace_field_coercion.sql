@@ -0,0 +1,7 @@
+-- Add a bitmask of the field value type coercion rules applied to writes to
+-- the "namespace" table.
+--
+-- 0 = no coercion (the existing behaviour).
+ALTER TABLE
+    namespace
+ADD COLUMN field_coercion INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,7 @@
+-- Add the write transformation rules applied by the router to writes for
+-- the "namespace" table.
+--
+-- NULL = no rules (the existing behaviour).
+ALTER TABLE
+    namespace
+ADD COLUMN write_rules TEXT;
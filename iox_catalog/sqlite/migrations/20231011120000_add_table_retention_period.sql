@@ -0,0 +1,6 @@
+-- Add an optional retention period override to the "table_name" table.
+--
+-- NULL = use the retention period of the namespace (the existing behaviour).
+ALTER TABLE
+    table_name
+ADD COLUMN retention_period_ns INT;
@@ -0,0 +1,7 @@
+-- Add the rollup policies materialised by the compactor to the "namespace"
+-- table.
+--
+-- NULL = no rollups (the existing behaviour).
+ALTER TABLE
+    namespace
+ADD COLUMN rollup_policies TEXT;
@@ -0,0 +1,13 @@
+-- Add the object store tier holding each file to the "parquet_file" table,
+-- and the age after which the files of a namespace are moved to the cold
+-- tier to the "namespace" table.
+--
+-- 0 = hot (the existing behaviour), 1 = cold.
+ALTER TABLE
+    parquet_file
+ADD COLUMN storage_tier INTEGER NOT NULL DEFAULT 0;
+
+-- NULL = never move files to the cold tier (the existing behaviour).
+ALTER TABLE
+    namespace
+ADD COLUMN cold_tier_age_ns INT;
@@ -0,0 +1,7 @@
+-- Add the weight of the share of the compactors given to each namespace
+-- when fair-share compaction is enabled to the "namespace" table.
+--
+-- 1 = the default, equal share.
+ALTER TABLE
+    namespace
+ADD COLUMN compaction_weight INTEGER NOT NULL DEFAULT 1;
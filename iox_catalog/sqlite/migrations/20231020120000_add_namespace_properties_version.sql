@@ -0,0 +1,8 @@
+-- Add a counter of the changes made to the namespace properties applied to
+-- writes by routers (the write rules and schema mode) to the "namespace"
+-- table.
+--
+-- Routers use it to discard stale copies of these properties.
+ALTER TABLE
+    namespace
+ADD COLUMN properties_version INTEGER NOT NULL DEFAULT 0;
@@ -3,11 +3,12 @@
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, ColumnsByName, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
-    NamespaceId, NamespaceName, NamespaceSchema, NamespaceServiceProtectionLimitsOverride,
-    ParquetFile, ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId,
-    PartitionKey, SkippedCompaction, SortedColumnSet, Table, TableId, TableSchema, Timestamp,
-    TransitionPartitionId,
+    Column, ColumnType, ColumnsByName, CompactionLevel, FieldCoercionRules, MaxColumnsPerTable,
+    MaxTables, Namespace, NamespaceId, NamespaceName, NamespaceSchema, NamespaceSchemaMode,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, RollupPolicies, SkippedCompaction,
+    SortedColumnSet, StorageTier, Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
+    WriteRules,
 };
 use iox_time::TimeProvider;
 use snafu::{OptionExt, Snafu};
@@ -115,6 +116,22 @@ pub enum Error {
     #[snafu(display("parquet_file record {} not found", id))]
     ParquetRecordNotFound { id: ParquetFileId },
 
+    #[snafu(display(
+        "sort key rewrite of partition {} conflicts with a concurrent update",
+        id
+    ))]
+    SortKeyRewriteConflict { id: TransitionPartitionId },
+
+    #[snafu(display(
+        "sort key of partition {} does not start with the sort key of the parquet file",
+        id
+    ))]
+    ParquetFileSortKeyConflict {
+        id: TransitionPartitionId,
+        sort_key: Option<Vec<String>>,
+        sort_key_ids: SortedColumnSet,
+    },
+
     #[snafu(display("cannot derive valid column schema from column {}: {}", name, source))]
     InvalidColumn {
         source: Box<dyn std::error::Error + Send + Sync>,
@@ -301,6 +318,61 @@ pub trait NamespaceRepo: Send + Sync {
         name: &str,
         new_max: MaxColumnsPerTable,
     ) -> Result<Namespace>;
+
+    /// Update the schema mode of a namespace, controlling whether writes may implicitly create
+    /// tables and columns.
+    async fn update_schema_mode(
+        &mut self,
+        name: &str,
+        schema_mode: NamespaceSchemaMode,
+    ) -> Result<Namespace>;
+
+    /// Replace the field value type coercion rules of a namespace.
+    async fn update_field_coercion(
+        &mut self,
+        name: &str,
+        field_coercion: FieldCoercionRules,
+    ) -> Result<Namespace>;
+
+    /// Replace the write transformation rules of a namespace.
+    async fn update_write_rules(
+        &mut self,
+        name: &str,
+        write_rules: WriteRules,
+    ) -> Result<Namespace>;
+
+    /// Replace the partition template of a namespace.
+    ///
+    /// The namespace template is only applied to tables created after the change; existing
+    /// tables retain their partition template.
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: NamespacePartitionTemplateOverride,
+    ) -> Result<Namespace>;
+
+    /// Replace the rollup policies of a namespace.
+    async fn update_rollup_policies(
+        &mut self,
+        name: &str,
+        rollup_policies: RollupPolicies,
+    ) -> Result<Namespace>;
+
+    /// Update the age after which the parquet files of a namespace are moved
+    /// to the cold storage tier. None represents never moving files.
+    async fn update_cold_tier_age(
+        &mut self,
+        name: &str,
+        cold_tier_age_ns: Option<i64>,
+    ) -> Result<Namespace>;
+
+    /// Update the weight of the share of the compactors given to a namespace
+    /// when compacting with fair sharing.
+    async fn update_compaction_weight(
+        &mut self,
+        name: &str,
+        compaction_weight: i32,
+    ) -> Result<Namespace>;
 }
 
 /// Functions for working with tables in the catalog
@@ -330,6 +402,25 @@ pub trait TableRepo: Send + Sync {
 
     /// List all tables.
     async fn list(&mut self) -> Result<Vec<Table>>;
+
+    /// Update the retention period override of the table. `None` removes the
+    /// override, so the namespace retention period applies again.
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table>;
+
+    /// Replace the partition template of the table, creating tag columns for any tags the new
+    /// template partitions on.
+    ///
+    /// The new template applies to partitions created after the change only; existing partitions
+    /// retain the template they were created with (see [`Partition::partition_template`]).
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Table>;
 }
 
 /// Functions for working with columns in the catalog
@@ -377,6 +468,20 @@ pub trait PartitionRepo: Send + Sync {
     /// create or get a partition record for the given partition key and table
     async fn create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition>;
 
+    /// create or get a partition record for the given partition key and table, recording
+    /// `partition_template` as the template the key was derived from should the partition be
+    /// created.
+    ///
+    /// Unlike [`PartitionRepo::create_or_get()`], which records the current template of the
+    /// table, this records the correct template for keys derived before a concurrent change to the
+    /// table template.
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Partition>;
+
     /// get partition by ID
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>>;
 
@@ -483,6 +588,20 @@ pub trait ParquetFileRepo: Send + Sync {
     /// create the parquet file
     async fn create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
 
+    /// Create the parquet file if the sort key of its partition starts with
+    /// `sort_key_ids`, the sort key the file's data is sorted by, in a single
+    /// transaction.
+    ///
+    /// This fails with [`Error::ParquetFileSortKeyConflict`], without any
+    /// effect, if the partition sort key was changed such that it no longer
+    /// starts with `sort_key_ids` (for example, by a sort key rewrite). The
+    /// error contains the observed sort key of the partition.
+    async fn create_if_sort_key(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        sort_key_ids: &SortedColumnSet,
+    ) -> Result<ParquetFile>;
+
     /// List all parquet files in implementation-defined, non-deterministic order.
     ///
     /// This includes files that were marked for deletion.
@@ -542,6 +661,48 @@ pub trait ParquetFileRepo: Send + Sync {
         create: &[ParquetFileParams],
         target_level: CompactionLevel,
     ) -> Result<Vec<ParquetFileId>>;
+
+    /// Replace the sort key of a partition and all of its parquet files in a single
+    /// transaction.
+    ///
+    /// The sort key of `partition_id` is swapped from `old_sort_key_ids` to `new_sort_key`, the
+    /// `delete` files are flagged for deletion and the `create` files are created. This fails
+    /// with [`Error::SortKeyRewriteConflict`], without any effect, if the sort key no longer
+    /// matches `old_sort_key_ids` or if the partition has any file left that is not flagged for
+    /// deletion afterwards, so that no file sorted by the old sort key remains visible.
+    ///
+    /// Returns IDs of created files.
+    async fn rewrite_sort_key(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+        old_sort_key_ids: &SortedColumnSet,
+        new_sort_key: &[&str],
+        new_sort_key_ids: &SortedColumnSet,
+        delete: &[ParquetFileId],
+        create: &[ParquetFileParams],
+    ) -> Result<Vec<ParquetFileId>>;
+
+    /// List all parquet files within a given namespace that are NOT marked as
+    /// [`to_delete`](ParquetFile::to_delete), whose objects are held in the
+    /// `storage_tier` object store, and that only contain data older than
+    /// `older_than` (a [`max_time`](ParquetFile::max_time) less than it).
+    async fn list_by_namespace_tier_older_than(
+        &mut self,
+        namespace_id: NamespaceId,
+        storage_tier: StorageTier,
+        older_than: Timestamp,
+    ) -> Result<Vec<ParquetFile>>;
+
+    /// Record that the objects of the given parquet files are now held in the
+    /// `storage_tier` object store.
+    ///
+    /// Files that are marked as [`to_delete`](ParquetFile::to_delete) are not
+    /// updated. Returns the IDs of the updated files.
+    async fn update_storage_tier(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+        storage_tier: StorageTier,
+    ) -> Result<Vec<ParquetFileId>>;
 }
 
 /// Gets the namespace schema including all tables and columns.
@@ -777,7 +938,10 @@ pub(crate) mod test_helpers {
     use assert_matches::assert_matches;
     use data_types::{ColumnId, CompactionLevel, MaxColumnsPerTable, MaxTables};
     use futures::Future;
-    use generated_types::influxdata::iox::partition_template::v1 as proto;
+    use generated_types::influxdata::iox::{
+        partition_template::v1 as proto, rollup::v1 as rollup_proto,
+        write_rules::v1 as write_rules_proto,
+    };
     use metric::{Attributes, DurationHistogram, Metric};
     use std::{collections::BTreeSet, ops::DerefMut, sync::Arc, time::Duration};
 
@@ -794,6 +958,9 @@ pub(crate) mod test_helpers {
         test_parquet_file(clean_state().await).await;
         test_parquet_file_delete_broken(clean_state().await).await;
         test_update_to_compaction_level_1(clean_state().await).await;
+        test_update_storage_tier(clean_state().await).await;
+        test_rewrite_sort_key(clean_state().await).await;
+        test_create_if_sort_key(clean_state().await).await;
         test_list_by_partiton_not_to_delete(clean_state().await).await;
         test_list_schemas(clean_state().await).await;
         test_list_schemas_soft_deleted_rows(clean_state().await).await;
@@ -918,6 +1085,196 @@ pub(crate) mod test_helpers {
             .expect("namespace should be updateable");
         assert_eq!(new_column_limit, modified.max_columns_per_table);
 
+        assert_eq!(namespace.schema_mode, NamespaceSchemaMode::Implicit);
+        assert_eq!(namespace.properties_version, 0);
+        let modified = repos
+            .namespaces()
+            .update_schema_mode(namespace_name.as_str(), NamespaceSchemaMode::Explicit)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.schema_mode, NamespaceSchemaMode::Explicit);
+        assert_eq!(modified.properties_version, 1);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.schema_mode, NamespaceSchemaMode::Explicit);
+
+        assert_eq!(namespace.field_coercion, FieldCoercionRules::NONE);
+        let want_rules = FieldCoercionRules {
+            int_to_float: true,
+            bool_to_string: true,
+            ..Default::default()
+        };
+        let modified = repos
+            .namespaces()
+            .update_field_coercion(namespace_name.as_str(), want_rules)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.field_coercion, want_rules);
+        assert_eq!(modified.properties_version, 2);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.field_coercion, want_rules);
+        assert_eq!(got.properties_version, 2);
+
+        assert!(namespace.write_rules.is_empty());
+        let want_rules = WriteRules::try_from(write_rules_proto::WriteRules {
+            rules: vec![write_rules_proto::WriteRule {
+                rule: Some(write_rules_proto::write_rule::Rule::RenameTag(
+                    write_rules_proto::RenameTag {
+                        from: "host".to_string(),
+                        to: "hostname".to_string(),
+                    },
+                )),
+            }],
+        })
+        .unwrap();
+        let modified = repos
+            .namespaces()
+            .update_write_rules(namespace_name.as_str(), want_rules.clone())
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.write_rules, want_rules);
+        assert_eq!(modified.properties_version, 3);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.write_rules, want_rules);
+        assert_eq!(got.properties_version, 3);
+        let modified = repos
+            .namespaces()
+            .update_write_rules(namespace_name.as_str(), WriteRules::default())
+            .await
+            .expect("namespace should be updateable");
+        assert!(modified.write_rules.is_empty());
+        assert_eq!(modified.properties_version, 4);
+
+        let want_template =
+            NamespacePartitionTemplateOverride::try_from(proto::PartitionTemplate {
+                parts: vec![proto::TemplatePart {
+                    part: Some(proto::template_part::Part::TimeFormat("%Y-%m-%d %H".into())),
+                }],
+            })
+            .unwrap();
+        let modified = repos
+            .namespaces()
+            .update_partition_template(namespace_name.as_str(), want_template.clone())
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.partition_template, want_template);
+        assert_eq!(modified.properties_version, 5);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.partition_template, want_template);
+        let err = repos
+            .namespaces()
+            .update_partition_template("does_not_exist", want_template)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
+        assert!(namespace.rollup_policies.is_empty());
+        let want_policies = RollupPolicies::try_from(rollup_proto::RollupPolicies {
+            policies: vec![rollup_proto::RollupPolicy {
+                source_table: "cpu".to_string(),
+                interval_seconds: 60,
+                aggregates: vec![rollup_proto::FieldAggregate {
+                    field: "usage".to_string(),
+                    aggregate: rollup_proto::Aggregate::Mean.into(),
+                }],
+                age_threshold_seconds: 86_400,
+            }],
+        })
+        .unwrap();
+        let modified = repos
+            .namespaces()
+            .update_rollup_policies(namespace_name.as_str(), want_policies.clone())
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.rollup_policies, want_policies);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.rollup_policies, want_policies);
+        let modified = repos
+            .namespaces()
+            .update_rollup_policies(namespace_name.as_str(), RollupPolicies::default())
+            .await
+            .expect("namespace should be updateable");
+        assert!(modified.rollup_policies.is_empty());
+
+        assert_eq!(namespace.cold_tier_age_ns, None);
+        const COLD_TIER_AGE_NS: i64 = 30 * 24 * 60 * 60 * 1000 * 1000 * 1000;
+        let modified = repos
+            .namespaces()
+            .update_cold_tier_age(namespace_name.as_str(), Some(COLD_TIER_AGE_NS))
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.cold_tier_age_ns, Some(COLD_TIER_AGE_NS));
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.cold_tier_age_ns, Some(COLD_TIER_AGE_NS));
+        let modified = repos
+            .namespaces()
+            .update_cold_tier_age(namespace_name.as_str(), None)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.cold_tier_age_ns, None);
+        let err = repos
+            .namespaces()
+            .update_cold_tier_age("does_not_exist", None)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
+        assert_eq!(namespace.compaction_weight, 1);
+        let modified = repos
+            .namespaces()
+            .update_compaction_weight(namespace_name.as_str(), 5)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.compaction_weight, 5);
+        let got = repos
+            .namespaces()
+            .get_by_name(namespace_name.as_str(), SoftDeletedRows::ExcludeDeleted)
+            .await
+            .unwrap()
+            .expect("namespace should exist");
+        assert_eq!(got.compaction_weight, 5);
+        let modified = repos
+            .namespaces()
+            .update_compaction_weight(namespace_name.as_str(), 1)
+            .await
+            .expect("namespace should be updateable");
+        assert_eq!(modified.compaction_weight, 1);
+        let err = repos
+            .namespaces()
+            .update_compaction_weight("does_not_exist", 1)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::NamespaceNotFoundByName { .. });
+
         const NEW_RETENTION_PERIOD_NS: i64 = 5 * 60 * 60 * 1000 * 1000 * 1000;
         let modified = repos
             .namespaces()
@@ -2137,6 +2494,91 @@ pub(crate) mod test_helpers {
             .expect("should list most recent");
         assert_eq!(recent.len(), 2);
 
+        // Partitions record the table partition template they were created with, and retain it
+        // when the table template is changed.
+        assert_eq!(partition.partition_template, table.partition_template);
+        let new_template = TablePartitionTemplateOverride::try_new(
+            Some(proto::PartitionTemplate {
+                parts: vec![
+                    proto::TemplatePart {
+                        part: Some(proto::template_part::Part::TimeFormat("%Y".into())),
+                    },
+                    proto::TemplatePart {
+                        part: Some(proto::template_part::Part::Bucket(proto::Bucket {
+                            tag_name: "host".into(),
+                            num_buckets: 10,
+                        })),
+                    },
+                ],
+            }),
+            &namespace.partition_template,
+        )
+        .unwrap();
+        let updated_table = repos
+            .tables()
+            .update_partition_template(table.id, new_template.clone())
+            .await
+            .unwrap();
+        assert_eq!(updated_table.partition_template, new_template);
+        assert_eq!(
+            updated_table.partition_template_version,
+            table.partition_template_version + 1
+        );
+
+        // The tag the new template partitions on is created as a tag column.
+        let columns = repos.columns().list_by_table_id(table.id).await.unwrap();
+        assert_matches!(
+            columns.iter().find(|c| c.name == "host"),
+            Some(c) if c.column_type == ColumnType::Tag
+        );
+
+        let new_partition = repos
+            .partitions()
+            .create_or_get("2023|7".into(), table.id)
+            .await
+            .unwrap();
+        assert_eq!(new_partition.partition_template, new_template);
+        let old_partition = repos
+            .partitions()
+            .get_by_id(partition.id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(old_partition.partition_template, table.partition_template);
+
+        // A partition whose key was derived from the previous table template records that
+        // template, not the current one.
+        let stale_partition = repos
+            .partitions()
+            .create_or_get_with_template(
+                "2023-07-01".into(),
+                table.id,
+                table.partition_template.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(stale_partition.partition_template, table.partition_template);
+
+        // An existing partition is returned unchanged.
+        let existing = repos
+            .partitions()
+            .create_or_get_with_template(
+                "2023|7".into(),
+                table.id,
+                table.partition_template.clone(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(existing.id, new_partition.id);
+        assert_eq!(existing.partition_template, new_template);
+
+        let err = repos
+            .tables()
+            .update_partition_template(TableId::new(i64::MAX), new_template)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableNotFound { .. });
+
         repos
             .namespaces()
             .soft_delete("namespace_partition_test")
@@ -2500,6 +2942,45 @@ pub(crate) mod test_helpers {
             .unwrap();
         assert_eq!(ids.len(), 0); // none left
 
+        // a table retention period overrides the namespace retention period
+        let table = repos
+            .tables()
+            .update_retention_period(partition2.table_id, Some(30 * 60 * 1_000_000_000)) // 30 mins
+            .await
+            .unwrap();
+        assert_eq!(table.retention_period_ns, Some(30 * 60 * 1_000_000_000));
+        let f7_params = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            max_time: Timestamp::new(
+                // a bit over half an hour ago
+                (catalog.time_provider().now() - Duration::from_secs(60 * 45)).timestamp_nanos(),
+            ),
+            ..f1_params.clone()
+        };
+        let f7 = repos
+            .parquet_files()
+            .create(f7_params.clone())
+            .await
+            .unwrap();
+        let ids = repos
+            .parquet_files()
+            .flag_for_delete_by_retention()
+            .await
+            .unwrap();
+        assert_eq!(ids, vec![f7.id]);
+        let table = repos
+            .tables()
+            .update_retention_period(partition2.table_id, None)
+            .await
+            .unwrap();
+        assert_eq!(table.retention_period_ns, None);
+        let err = repos
+            .tables()
+            .update_retention_period(TableId::new(i64::MAX), None)
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::TableNotFound { .. });
+
         // test create_update_delete
         let f6_params = ParquetFileParams {
             object_store_id: Uuid::new_v4(),
@@ -3138,6 +3619,319 @@ pub(crate) mod test_helpers {
             .expect("delete namespace should succeed");
     }
 
+    async fn test_update_storage_tier(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_update_storage_tier").await;
+        let table = arbitrary_table(&mut *repos, "update_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("test_update_storage_tier_one".into(), table.id)
+            .await
+            .unwrap();
+
+        let parquet_file_params = arbitrary_parquet_file_params(&namespace, &table, &partition);
+        let parquet_file = repos
+            .parquet_files()
+            .create(parquet_file_params.clone())
+            .await
+            .unwrap();
+        assert_eq!(parquet_file.storage_tier, StorageTier::Hot);
+
+        // A file marked for deletion must not be moved between tiers
+        let deleted_params = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            ..parquet_file_params.clone()
+        };
+        let deleted_file = repos.parquet_files().create(deleted_params).await.unwrap();
+        repos
+            .parquet_files()
+            .create_upgrade_delete(&[deleted_file.id], &[], &[], CompactionLevel::Initial)
+            .await
+            .unwrap();
+
+        // A file that remains in the hot tier
+        let hot_params = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            ..parquet_file_params
+        };
+        let hot_file = repos.parquet_files().create(hot_params).await.unwrap();
+
+        let updated = repos
+            .parquet_files()
+            .update_storage_tier(&[parquet_file.id, deleted_file.id], StorageTier::Cold)
+            .await
+            .unwrap();
+        assert_eq!(updated, vec![parquet_file.id]);
+
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition.transition_partition_id())
+            .await
+            .unwrap();
+        let tiers = files
+            .iter()
+            .map(|f| (f.id, f.storage_tier))
+            .collect::<BTreeSet<_>>();
+        assert_eq!(
+            tiers,
+            BTreeSet::from([
+                (parquet_file.id, StorageTier::Cold),
+                (hot_file.id, StorageTier::Hot),
+            ])
+        );
+
+        // Only the files in the requested tier with data older than the cutoff
+        // are listed
+        let after = Timestamp::new(hot_file.max_time.get() + 1);
+        let hot = repos
+            .parquet_files()
+            .list_by_namespace_tier_older_than(namespace.id, StorageTier::Hot, after)
+            .await
+            .unwrap();
+        assert_eq!(hot.iter().map(|f| f.id).collect::<Vec<_>>(), [hot_file.id]);
+        let cold = repos
+            .parquet_files()
+            .list_by_namespace_tier_older_than(namespace.id, StorageTier::Cold, after)
+            .await
+            .unwrap();
+        assert_eq!(
+            cold.iter().map(|f| f.id).collect::<Vec<_>>(),
+            [parquet_file.id]
+        );
+        let hot = repos
+            .parquet_files()
+            .list_by_namespace_tier_older_than(namespace.id, StorageTier::Hot, hot_file.max_time)
+            .await
+            .unwrap();
+        assert!(hot.is_empty());
+
+        // remove namespace to avoid it from affecting later tests
+        repos
+            .namespaces()
+            .soft_delete("namespace_update_storage_tier")
+            .await
+            .expect("delete namespace should succeed");
+    }
+
+    async fn test_rewrite_sort_key(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_rewrite_sort_key").await;
+        let table = arbitrary_table(&mut *repos, "rewrite_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("test_rewrite_sort_key_one".into(), table.id)
+            .await
+            .unwrap();
+        let partition_id = partition.transition_partition_id();
+        repos
+            .partitions()
+            .cas_sort_key(
+                &partition_id,
+                None,
+                None,
+                &["tag2", "tag1", "time"],
+                &SortedColumnSet::from([2, 1, 3]),
+            )
+            .await
+            .unwrap();
+
+        let parquet_file_params = arbitrary_parquet_file_params(&namespace, &table, &partition);
+        let old_file = repos
+            .parquet_files()
+            .create(parquet_file_params.clone())
+            .await
+            .unwrap();
+        let new_params = ParquetFileParams {
+            object_store_id: Uuid::new_v4(),
+            ..parquet_file_params.clone()
+        };
+
+        // A stale sort key is rejected
+        let err = repos
+            .parquet_files()
+            .rewrite_sort_key(
+                &partition_id,
+                &SortedColumnSet::from([1, 2, 3]),
+                &["tag1", "tag2", "time"],
+                &SortedColumnSet::from([1, 2, 3]),
+                &[old_file.id],
+                &[new_params.clone()],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SortKeyRewriteConflict { .. }), "{err}");
+
+        // A file the rewrite did not read is rejected
+        let other_file = repos
+            .parquet_files()
+            .create(ParquetFileParams {
+                object_store_id: Uuid::new_v4(),
+                ..parquet_file_params
+            })
+            .await
+            .unwrap();
+        let err = repos
+            .parquet_files()
+            .rewrite_sort_key(
+                &partition_id,
+                &SortedColumnSet::from([2, 1, 3]),
+                &["tag1", "tag2", "time"],
+                &SortedColumnSet::from([1, 2, 3]),
+                &[old_file.id],
+                &[new_params.clone()],
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(err, Error::SortKeyRewriteConflict { .. }), "{err}");
+
+        // Nothing was changed by the failed attempts
+        let got = crate::partition_lookup(&mut *repos, &partition_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(got.sort_key_ids, SortedColumnSet::from([2, 1, 3]));
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .unwrap();
+        assert_eq!(files.len(), 2);
+
+        let created = repos
+            .parquet_files()
+            .rewrite_sort_key(
+                &partition_id,
+                &SortedColumnSet::from([2, 1, 3]),
+                &["tag1", "tag2", "time"],
+                &SortedColumnSet::from([1, 2, 3]),
+                &[old_file.id, other_file.id],
+                &[new_params],
+            )
+            .await
+            .unwrap();
+        assert_eq!(created.len(), 1);
+
+        let got = crate::partition_lookup(&mut *repos, &partition_id)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            got.sort_key,
+            Some(vec![
+                "tag1".to_string(),
+                "tag2".to_string(),
+                "time".to_string()
+            ])
+        );
+        assert_eq!(got.sort_key_ids, SortedColumnSet::from([1, 2, 3]));
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .unwrap();
+        assert_eq!(files.iter().map(|f| f.id).collect::<Vec<_>>(), created);
+
+        // remove namespace to avoid it from affecting later tests
+        repos
+            .namespaces()
+            .soft_delete("namespace_rewrite_sort_key")
+            .await
+            .expect("delete namespace should succeed");
+    }
+
+    async fn test_create_if_sort_key(catalog: Arc<dyn Catalog>) {
+        let mut repos = catalog.repositories().await;
+        let namespace = arbitrary_namespace(&mut *repos, "namespace_create_if_sort_key").await;
+        let table = arbitrary_table(&mut *repos, "create_if_sort_key_table", &namespace).await;
+        let partition = repos
+            .partitions()
+            .create_or_get("test_create_if_sort_key_one".into(), table.id)
+            .await
+            .unwrap();
+        let partition_id = partition.transition_partition_id();
+        repos
+            .partitions()
+            .cas_sort_key(
+                &partition_id,
+                None,
+                None,
+                &["tag2", "tag1", "time"],
+                &SortedColumnSet::from([2, 1, 3]),
+            )
+            .await
+            .unwrap();
+
+        let parquet_file_params = arbitrary_parquet_file_params(&namespace, &table, &partition);
+
+        // A file sorted by a sort key the partition sort key does not start
+        // with is rejected, returning the observed sort key.
+        let err = repos
+            .parquet_files()
+            .create_if_sort_key(
+                parquet_file_params.clone(),
+                &SortedColumnSet::from([1, 2, 3]),
+            )
+            .await
+            .unwrap_err();
+        assert_matches!(err, Error::ParquetFileSortKeyConflict { id, sort_key, sort_key_ids } => {
+            assert_eq!(id, partition_id);
+            assert_eq!(
+                sort_key,
+                Some(vec![
+                    "tag2".to_string(),
+                    "tag1".to_string(),
+                    "time".to_string()
+                ])
+            );
+            assert_eq!(sort_key_ids, SortedColumnSet::from([2, 1, 3]));
+        });
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .unwrap();
+        assert!(files.is_empty());
+
+        // A file sorted by the partition sort key, or a prefix of it (the sort
+        // key may be extended concurrently), is created.
+        let file_a = repos
+            .parquet_files()
+            .create_if_sort_key(
+                parquet_file_params.clone(),
+                &SortedColumnSet::from([2, 1, 3]),
+            )
+            .await
+            .unwrap();
+        let file_b = repos
+            .parquet_files()
+            .create_if_sort_key(
+                ParquetFileParams {
+                    object_store_id: Uuid::new_v4(),
+                    ..parquet_file_params
+                },
+                &SortedColumnSet::from([2, 1]),
+            )
+            .await
+            .unwrap();
+
+        let files = repos
+            .parquet_files()
+            .list_by_partition_not_to_delete(&partition_id)
+            .await
+            .unwrap();
+        assert_eq!(
+            files.iter().map(|f| f.id).collect::<BTreeSet<_>>(),
+            BTreeSet::from([file_a.id, file_b.id])
+        );
+
+        // remove namespace to avoid it from affecting later tests
+        repos
+            .namespaces()
+            .soft_delete("namespace_create_if_sort_key")
+            .await
+            .expect("delete namespace should succeed");
+    }
+
     /// Assert that a namespace deletion does NOT cascade to the tables/schema
     /// items/parquet files/etc.
     ///
@@ -11,13 +11,12 @@ use crate::{
 };
 use async_trait::async_trait;
 use data_types::{
-    partition_template::{
-        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
-    },
-    Column, ColumnId, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace,
-    NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
+    Column, ColumnId, ColumnType, CompactionLevel, FieldCoercionRules, MaxColumnsPerTable,
+    MaxTables, Namespace, NamespaceId, NamespaceName, NamespaceSchemaMode,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, RollupPolicies, SkippedCompaction,
+    SortedColumnSet, StorageTier, Table, TableId, Timestamp, TransitionPartitionId, WriteRules,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use snafu::ensure;
@@ -172,6 +171,13 @@ impl NamespaceRepo for MemTxn {
             retention_period_ns,
             deleted_at: None,
             partition_template: partition_template.unwrap_or_default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            rollup_policies: Default::default(),
+            cold_tier_age_ns: None,
+            compaction_weight: 1,
+            properties_version: 0,
         };
         stage.namespaces.push(namespace);
         Ok(stage.namespaces.last().unwrap().clone())
@@ -272,6 +278,129 @@ impl NamespaceRepo for MemTxn {
             }),
         }
     }
+
+    async fn update_schema_mode(
+        &mut self,
+        name: &str,
+        schema_mode: NamespaceSchemaMode,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.schema_mode = schema_mode;
+                n.properties_version += 1;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_field_coercion(
+        &mut self,
+        name: &str,
+        field_coercion: FieldCoercionRules,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.field_coercion = field_coercion;
+                n.properties_version += 1;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_write_rules(
+        &mut self,
+        name: &str,
+        write_rules: WriteRules,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.write_rules = write_rules;
+                n.properties_version += 1;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: NamespacePartitionTemplateOverride,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.partition_template = partition_template;
+                n.properties_version += 1;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_rollup_policies(
+        &mut self,
+        name: &str,
+        rollup_policies: RollupPolicies,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.rollup_policies = rollup_policies;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_cold_tier_age(
+        &mut self,
+        name: &str,
+        cold_tier_age_ns: Option<i64>,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.cold_tier_age_ns = cold_tier_age_ns;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
+
+    async fn update_compaction_weight(
+        &mut self,
+        name: &str,
+        compaction_weight: i32,
+    ) -> Result<Namespace> {
+        let stage = self.stage();
+        match stage.namespaces.iter_mut().find(|n| n.name == name) {
+            Some(n) => {
+                n.compaction_weight = compaction_weight;
+                Ok(n.clone())
+            }
+            None => Err(Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 #[async_trait]
@@ -330,6 +459,8 @@ impl TableRepo for MemTxn {
                         namespace_id,
                         name: name.to_string(),
                         partition_template,
+                        retention_period_ns: None,
+                        partition_template_version: 0,
                     };
                     stage.tables.push(table);
                     stage.tables.last().unwrap()
@@ -339,16 +470,14 @@ impl TableRepo for MemTxn {
 
         let table = table.clone();
 
-        // Partitioning is only supported for tags, so create tag columns for all `TagValue`
-        // partition template parts. It's important this happens within the table creation
+        // Partitioning is only supported for tags, so create tag columns for all `TagValue` and
+        // `Bucket` partition template parts. It's important this happens within the table creation
         // transaction so that there isn't a possibility of a concurrent write creating these
         // columns with an unsupported type.
-        for template_part in table.partition_template.parts() {
-            if let TemplatePart::TagValue(tag_name) = template_part {
-                self.columns()
-                    .create_or_get(tag_name, table.id, ColumnType::Tag)
-                    .await?;
-            }
+        for tag_name in table.partition_template.tag_columns() {
+            self.columns()
+                .create_or_get(tag_name, table.id, ColumnType::Tag)
+                .await?;
         }
 
         Ok(table)
@@ -390,6 +519,50 @@ impl TableRepo for MemTxn {
         let stage = self.stage();
         Ok(stage.tables.clone())
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let stage = self.stage();
+        match stage.tables.iter_mut().find(|t| t.id == table_id) {
+            Some(t) => {
+                t.retention_period_ns = retention_period_ns;
+                Ok(t.clone())
+            }
+            None => Err(Error::TableNotFound { id: table_id }),
+        }
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Table> {
+        if !self.stage().tables.iter().any(|t| t.id == table_id) {
+            return Err(Error::TableNotFound { id: table_id });
+        }
+
+        // Create the tag columns before applying the template, so that a
+        // column type conflict leaves the table unchanged.
+        for tag_name in partition_template.tag_columns() {
+            self.columns()
+                .create_or_get(tag_name, table_id, ColumnType::Tag)
+                .await?;
+        }
+
+        let stage = self.stage();
+        let table = stage
+            .tables
+            .iter_mut()
+            .find(|t| t.id == table_id)
+            .expect("table existence checked above");
+        table.partition_template = partition_template;
+        table.partition_template_version += 1;
+
+        Ok(table.clone())
+    }
 }
 
 #[async_trait]
@@ -560,6 +733,26 @@ impl PartitionRepo for MemTxn {
     async fn create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition> {
         let stage = self.stage();
 
+        // Record the partition template of the table at creation time.
+        let partition_template = stage
+            .tables
+            .iter()
+            .find(|t| t.id == table_id)
+            .map(|t| t.partition_template.clone())
+            .unwrap_or_default();
+
+        self.create_or_get_with_template(key, table_id, partition_template)
+            .await
+    }
+
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Partition> {
+        let stage = self.stage();
+
         let partition = match stage
             .partitions
             .iter()
@@ -567,7 +760,7 @@ impl PartitionRepo for MemTxn {
         {
             Some(p) => p,
             None => {
-                let p = Partition::new_in_memory_only(
+                let mut p = Partition::new_in_memory_only(
                     PartitionId::new(stage.partitions.len() as i64 + 1),
                     table_id,
                     key,
@@ -575,6 +768,7 @@ impl PartitionRepo for MemTxn {
                     SortedColumnSet::new(vec![]),
                     None,
                 );
+                p.partition_template = partition_template;
                 stage.partitions.push(p);
                 stage.partitions.last().unwrap()
             }
@@ -836,6 +1030,32 @@ impl ParquetFileRepo for MemTxn {
         create_parquet_file(self.stage(), parquet_file_params).await
     }
 
+    async fn create_if_sort_key(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        sort_key_ids: &SortedColumnSet,
+    ) -> Result<ParquetFile> {
+        let stage = self.stage();
+
+        let partition_id = &parquet_file_params.partition_id;
+        let partition = stage
+            .partitions
+            .iter()
+            .find(|p| p.transition_partition_id() == *partition_id)
+            .ok_or_else(|| Error::PartitionNotFound {
+                id: partition_id.clone(),
+            })?;
+        if !partition.sort_key_ids.starts_with(sort_key_ids) {
+            return Err(Error::ParquetFileSortKeyConflict {
+                id: partition_id.clone(),
+                sort_key: partition.sort_key.clone(),
+                sort_key_ids: partition.sort_key_ids.clone(),
+            });
+        }
+
+        create_parquet_file(stage, parquet_file_params).await
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         let stage = self.stage();
 
@@ -853,13 +1073,17 @@ impl ParquetFileRepo for MemTxn {
             .filter(|f| f.to_delete.is_none())
             .filter_map(|f| {
                 // table retention, if it exists, overrides namespace retention
-                // TODO - include check of table retention period once implemented
+                let table_retention = stage
+                    .tables
+                    .iter()
+                    .find(|t| t.id == f.table_id)
+                    .and_then(|t| t.retention_period_ns);
                 stage
                     .namespaces
                     .iter()
                     .find(|n| n.id == f.namespace_id)
                     .and_then(|ns| {
-                        ns.retention_period_ns.and_then(|rp| {
+                        table_retention.or(ns.retention_period_ns).and_then(|rp| {
                             if f.max_time < now - rp {
                                 f.to_delete = Some(now);
                                 Some(f.id)
@@ -1017,6 +1241,111 @@ impl ParquetFileRepo for MemTxn {
 
         Ok(ids)
     }
+
+    async fn rewrite_sort_key(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+        old_sort_key_ids: &SortedColumnSet,
+        new_sort_key: &[&str],
+        new_sort_key_ids: &SortedColumnSet,
+        delete: &[ParquetFileId],
+        create: &[ParquetFileParams],
+    ) -> Result<Vec<ParquetFileId>> {
+        assert_eq!(new_sort_key.len(), new_sort_key_ids.len());
+
+        let conflict = || Error::SortKeyRewriteConflict {
+            id: partition_id.clone(),
+        };
+        let mut stage = self.inner.clone();
+
+        let partition = stage
+            .partitions
+            .iter_mut()
+            .find(|p| match partition_id {
+                TransitionPartitionId::Deterministic(hash_id) => {
+                    p.hash_id().map_or(false, |h| h == hash_id)
+                }
+                TransitionPartitionId::Deprecated(id) => p.id == *id,
+            })
+            .ok_or_else(conflict)?;
+        if partition.sort_key_ids != *old_sort_key_ids {
+            return Err(conflict());
+        }
+        partition.sort_key = Some(new_sort_key.iter().map(|s| s.to_string()).collect());
+        partition.sort_key_ids = new_sort_key_ids.clone();
+        let partition_id = partition.transition_partition_id();
+
+        let marked_at = Timestamp::from(self.time_provider.now());
+        for id in delete {
+            match stage
+                .parquet_files
+                .iter_mut()
+                .find(|f| f.id == *id && f.to_delete.is_none())
+            {
+                Some(f) => f.to_delete = Some(marked_at),
+                None => return Err(conflict()),
+            }
+        }
+        if stage
+            .parquet_files
+            .iter()
+            .any(|f| f.partition_id == partition_id && f.to_delete.is_none())
+        {
+            return Err(conflict());
+        }
+
+        let mut ids = Vec::with_capacity(create.len());
+        for file in create {
+            let res = create_parquet_file(&mut stage, file.clone()).await?;
+            ids.push(res.id);
+        }
+
+        *self.inner = stage;
+
+        Ok(ids)
+    }
+
+    async fn list_by_namespace_tier_older_than(
+        &mut self,
+        namespace_id: NamespaceId,
+        storage_tier: StorageTier,
+        older_than: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        let stage = self.stage();
+
+        let parquet_files: Vec<_> = stage
+            .parquet_files
+            .iter()
+            .filter(|f| {
+                f.namespace_id == namespace_id
+                    && f.to_delete.is_none()
+                    && f.storage_tier == storage_tier
+                    && f.max_time < older_than
+            })
+            .cloned()
+            .collect();
+        Ok(parquet_files)
+    }
+
+    async fn update_storage_tier(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+        storage_tier: StorageTier,
+    ) -> Result<Vec<ParquetFileId>> {
+        let stage = self.stage();
+
+        let mut updated = Vec::with_capacity(parquet_file_ids.len());
+        for f in stage
+            .parquet_files
+            .iter_mut()
+            .filter(|p| parquet_file_ids.contains(&p.id) && p.to_delete.is_none())
+        {
+            f.storage_tier = storage_tier;
+            updated.push(f.id);
+        }
+
+        Ok(updated)
+    }
 }
 
 fn filter_namespace_soft_delete<'a>(
@@ -7,10 +7,11 @@ use crate::interface::{
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    Column, ColumnType, CompactionLevel, FieldCoercionRules, MaxColumnsPerTable, MaxTables,
+    Namespace, NamespaceId, NamespaceName, NamespaceSchemaMode,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, RollupPolicies, SkippedCompaction,
+    SortedColumnSet, StorageTier, Table, TableId, Timestamp, TransitionPartitionId, WriteRules,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{DurationHistogram, Metric};
@@ -141,6 +142,13 @@ decorate!(
         "namespace_soft_delete" = soft_delete(&mut self, name: &str) -> Result<()>;
         "namespace_update_table_limit" = update_table_limit(&mut self, name: &str, new_max: MaxTables) -> Result<Namespace>;
         "namespace_update_column_limit" = update_column_limit(&mut self, name: &str, new_max: MaxColumnsPerTable) -> Result<Namespace>;
+        "namespace_update_schema_mode" = update_schema_mode(&mut self, name: &str, schema_mode: NamespaceSchemaMode) -> Result<Namespace>;
+        "namespace_update_field_coercion" = update_field_coercion(&mut self, name: &str, field_coercion: FieldCoercionRules) -> Result<Namespace>;
+        "namespace_update_write_rules" = update_write_rules(&mut self, name: &str, write_rules: WriteRules) -> Result<Namespace>;
+        "namespace_update_partition_template" = update_partition_template(&mut self, name: &str, partition_template: NamespacePartitionTemplateOverride) -> Result<Namespace>;
+        "namespace_update_rollup_policies" = update_rollup_policies(&mut self, name: &str, rollup_policies: RollupPolicies) -> Result<Namespace>;
+        "namespace_update_cold_tier_age" = update_cold_tier_age(&mut self, name: &str, cold_tier_age_ns: Option<i64>) -> Result<Namespace>;
+        "namespace_update_compaction_weight" = update_compaction_weight(&mut self, name: &str, compaction_weight: i32) -> Result<Namespace>;
     ]
 );
 
@@ -152,6 +160,8 @@ decorate!(
         "table_get_by_namespace_and_name" = get_by_namespace_and_name(&mut self, namespace_id: NamespaceId, name: &str) -> Result<Option<Table>>;
         "table_list_by_namespace_id" = list_by_namespace_id(&mut self, namespace_id: NamespaceId) -> Result<Vec<Table>>;
         "table_list" = list(&mut self) -> Result<Vec<Table>>;
+        "table_update_retention_period" = update_retention_period(&mut self, table_id: TableId, retention_period_ns: Option<i64>) -> Result<Table>;
+        "table_update_partition_template" = update_partition_template(&mut self, table_id: TableId, partition_template: TablePartitionTemplateOverride) -> Result<Table>;
     ]
 );
 
@@ -170,6 +180,7 @@ decorate!(
     impl_trait = PartitionRepo,
     methods = [
         "partition_create_or_get" = create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition>;
+        "partition_create_or_get_with_template" = create_or_get_with_template(&mut self, key: PartitionKey, table_id: TableId, partition_template: TablePartitionTemplateOverride) -> Result<Partition>;
         "partition_get_by_id" = get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>>;
         "partition_get_by_id_batch" = get_by_id_batch(&mut self, partition_ids: Vec<PartitionId>) -> Result<Vec<Partition>>;
         "partition_get_by_hash_id" = get_by_hash_id(&mut self, partition_hash_id: &PartitionHashId) -> Result<Option<Partition>>;
@@ -191,6 +202,7 @@ decorate!(
     impl_trait = ParquetFileRepo,
     methods = [
         "parquet_create" = create(&mut self, parquet_file_params: ParquetFileParams) -> Result<ParquetFile>;
+        "parquet_create_if_sort_key" = create_if_sort_key(&mut self, parquet_file_params: ParquetFileParams, sort_key_ids: &SortedColumnSet) -> Result<ParquetFile>;
         "parquet_list_all" = list_all(&mut self) -> Result<Vec<ParquetFile>>;
         "parquet_flag_for_delete_by_retention" = flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>>;
         "parquet_list_by_namespace_not_to_delete" = list_by_namespace_not_to_delete(&mut self, namespace_id: NamespaceId) -> Result<Vec<ParquetFile>>;
@@ -200,5 +212,8 @@ decorate!(
         "parquet_get_by_object_store_id" = get_by_object_store_id(&mut self, object_store_id: Uuid) -> Result<Option<ParquetFile>>;
         "parquet_exists_by_object_store_id_batch" = exists_by_object_store_id_batch(&mut self, object_store_ids: Vec<Uuid>) -> Result<Vec<Uuid>>;
         "parquet_create_upgrade_delete" = create_upgrade_delete(&mut self, delete: &[ParquetFileId], upgrade: &[ParquetFileId], create: &[ParquetFileParams], target_level: CompactionLevel) -> Result<Vec<ParquetFileId>>;
+        "parquet_rewrite_sort_key" = rewrite_sort_key(&mut self, partition_id: &TransitionPartitionId, old_sort_key_ids: &SortedColumnSet, new_sort_key: &[&str], new_sort_key_ids: &SortedColumnSet, delete: &[ParquetFileId], create: &[ParquetFileParams]) -> Result<Vec<ParquetFileId>>;
+        "parquet_list_by_namespace_tier_older_than" = list_by_namespace_tier_older_than(&mut self, namespace_id: NamespaceId, storage_tier: StorageTier, older_than: Timestamp) -> Result<Vec<ParquetFile>>;
+        "parquet_update_storage_tier" = update_storage_tier(&mut self, parquet_file_ids: &[ParquetFileId], storage_tier: StorageTier) -> Result<Vec<ParquetFileId>>;
     ]
 );
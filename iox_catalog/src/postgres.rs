@@ -15,13 +15,12 @@ use crate::{
 };
 use async_trait::async_trait;
 use data_types::{
-    partition_template::{
-        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
-    },
-    Column, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceId,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId,
-    ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey, SkippedCompaction,
-    SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
+    Column, ColumnType, CompactionLevel, FieldCoercionRules, MaxColumnsPerTable, MaxTables,
+    Namespace, NamespaceId, NamespaceName, NamespaceSchemaMode,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, RollupPolicies, SkippedCompaction,
+    SortedColumnSet, StorageTier, Table, TableId, Timestamp, TransitionPartitionId, WriteRules,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::{Attributes, Instrument, MetricKind};
@@ -715,7 +714,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -746,7 +746,8 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE {v};
                 "#,
@@ -770,7 +771,8 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -800,7 +802,8 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -841,7 +844,8 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
         "#,
         )
         .bind(new_max)
@@ -870,7 +874,8 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
         "#,
         )
         .bind(new_max)
@@ -899,7 +904,8 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
         "#,
         )
         .bind(retention_period_ns) // $1
@@ -916,6 +922,216 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_schema_mode(
+        &mut self,
+        name: &str,
+        schema_mode: NamespaceSchemaMode,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET schema_mode = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(schema_mode) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_field_coercion(
+        &mut self,
+        name: &str,
+        field_coercion: FieldCoercionRules,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET field_coercion = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(field_coercion) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_write_rules(
+        &mut self,
+        name: &str,
+        write_rules: WriteRules,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET write_rules = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(write_rules) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: NamespacePartitionTemplateOverride,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET partition_template = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(partition_template) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_rollup_policies(
+        &mut self,
+        name: &str,
+        rollup_policies: RollupPolicies,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET rollup_policies = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(rollup_policies) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_cold_tier_age(
+        &mut self,
+        name: &str,
+        cold_tier_age_ns: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET cold_tier_age_ns = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(cold_tier_age_ns) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_compaction_weight(
+        &mut self,
+        name: &str,
+        compaction_weight: i32,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET compaction_weight = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(compaction_weight) // $1
+        .bind(name) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 #[async_trait]
@@ -978,15 +1194,12 @@ RETURNING *;
             }
         })?;
 
-        // Partitioning is only supported for tags, so create tag columns for all `TagValue`
-        // partition template parts. It's important this happens within the table creation
+        // Partitioning is only supported for tags, so create tag columns for all `TagValue` and
+        // `Bucket` partition template parts. It's important this happens within the table creation
         // transaction so that there isn't a possibility of a concurrent write creating these
         // columns with an unsupported type.
-        for template_part in table.partition_template.parts() {
-            if let TemplatePart::TagValue(tag_name) = template_part {
-                insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag)
-                    .await?;
-            }
+        for tag_name in table.partition_template.tag_columns() {
+            insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag).await?;
         }
 
         tx.commit()
@@ -1067,6 +1280,74 @@ WHERE namespace_id = $1;
 
         Ok(rec)
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut self.inner)
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(table)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Table> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let table = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET partition_template = $1, partition_template_version = partition_template_version + 1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(partition_template) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        // As with table creation, ensure the columns the new template partitions on exist as tags
+        // within the same transaction.
+        for tag_name in table.partition_template.tag_columns() {
+            insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(table)
+    }
 }
 
 #[async_trait]
@@ -1184,15 +1465,18 @@ impl PartitionRepo for PostgresTxn {
     async fn create_or_get(&mut self, key: PartitionKey, table_id: TableId) -> Result<Partition> {
         let hash_id = PartitionHashId::new(table_id, &key);
 
+        // The partition records the partition template of the table at creation time, so that
+        // the partition key remains interpretable should the table template change later.
         let v = sqlx::query_as::<_, Partition>(
             r#"
 INSERT INTO partition
-    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids)
+    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids, partition_template)
 VALUES
-    ( $1, $2, $3, $4, '{}', '{}')
+    ( $1, $2, $3, $4, '{}', '{}', (SELECT partition_template FROM table_name WHERE id = $3))
 ON CONFLICT ON CONSTRAINT partition_key_unique
 DO UPDATE SET partition_key = partition.partition_key
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
         )
         .bind(key) // $1
@@ -1212,10 +1496,49 @@ RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file
         Ok(v)
     }
 
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Partition> {
+        let hash_id = PartitionHashId::new(table_id, &key);
+
+        let v = sqlx::query_as::<_, Partition>(
+            r#"
+INSERT INTO partition
+    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids, partition_template)
+VALUES
+    ( $1, $2, $3, $4, '{}', '{}', $5)
+ON CONFLICT ON CONSTRAINT partition_key_unique
+DO UPDATE SET partition_key = partition.partition_key
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
+        "#,
+        )
+        .bind(key) // $1
+        .bind(TRANSITION_SHARD_ID) // $2
+        .bind(table_id) // $3
+        .bind(&hash_id) // $4
+        .bind(partition_template) // $5
+        .fetch_one(&mut self.inner)
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::ForeignKeyViolation { source: e }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(v)
+    }
+
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>> {
         let rec = sqlx::query_as::<_, Partition>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE id = $1;
         "#,
@@ -1238,7 +1561,8 @@ WHERE id = $1;
 
         sqlx::query_as::<_, Partition>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE id = ANY($1);
         "#,
@@ -1255,7 +1579,8 @@ WHERE id = ANY($1);
     ) -> Result<Option<Partition>> {
         let rec = sqlx::query_as::<_, Partition>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE hash_id = $1;
         "#,
@@ -1281,7 +1606,8 @@ WHERE hash_id = $1;
 
         sqlx::query_as::<_, Partition>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE hash_id = ANY($1);
         "#,
@@ -1295,7 +1621,8 @@ WHERE hash_id = ANY($1);
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Partition>> {
         sqlx::query_as::<_, Partition>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE table_id = $1;
             "#,
@@ -1350,7 +1677,8 @@ WHERE table_id = $1;
 UPDATE partition
 SET sort_key = $1, sort_key_ids = $4
 WHERE hash_id = $2 AND sort_key = $3 AND sort_key_ids = $5
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
             )
             .bind(new_sort_key) // $1
@@ -1363,7 +1691,8 @@ RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file
 UPDATE partition
 SET sort_key = $1, sort_key_ids = $4
 WHERE id = $2 AND sort_key = $3 AND sort_key_ids = $5
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
             )
             .bind(new_sort_key) // $1
@@ -1508,7 +1837,8 @@ RETURNING *
     // TODO: Carol has confirmed the persisted_sequence_number is not needed anywhere so let us remove it
     // but in a seperate PR to ensure we don't break anything
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, persisted_sequence_number, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, persisted_sequence_number, new_file_at,
+       partition_template
 FROM partition
 ORDER BY id DESC
 LIMIT $1;"#,
@@ -1555,7 +1885,7 @@ LIMIT $1;"#,
         sqlx::query_as(
             r#"
 SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, persisted_sequence_number,
-       new_file_at
+       new_file_at, partition_template
 FROM partition
 WHERE hash_id IS NULL
 ORDER BY id DESC;"#,
@@ -1574,6 +1904,69 @@ impl ParquetFileRepo for PostgresTxn {
         Ok(ParquetFile::from_params(parquet_file_params, id))
     }
 
+    async fn create_if_sort_key(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        sort_key_ids: &SortedColumnSet,
+    ) -> Result<ParquetFile> {
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        // Reading the sort key locks the partition row until the transaction ends. This
+        // serialises the creation of the file with any concurrent sort key update or rewrite.
+        //
+        // This `match` will go away when all partitions have hash IDs in the database.
+        let partition_id = &parquet_file_params.partition_id;
+        let query = match partition_id {
+            TransitionPartitionId::Deterministic(hash_id) => sqlx::query_as::<_, Partition>(
+                r#"
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
+FROM partition
+WHERE hash_id = $1
+FOR UPDATE;
+        "#,
+            )
+            .bind(hash_id), // $1
+            TransitionPartitionId::Deprecated(id) => sqlx::query_as::<_, Partition>(
+                r#"
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
+FROM partition
+WHERE id = $1
+FOR UPDATE;
+        "#,
+            )
+            .bind(id), // $1
+        };
+        let partition = query
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or_else(|| Error::PartitionNotFound {
+                id: partition_id.clone(),
+            })?;
+
+        if !partition.sort_key_ids.starts_with(sort_key_ids) {
+            return Err(Error::ParquetFileSortKeyConflict {
+                id: partition_id.clone(),
+                sort_key: partition.sort_key,
+                sort_key_ids: partition.sort_key_ids,
+            });
+        }
+
+        let id = create_parquet_file(&mut *tx, &parquet_file_params).await?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+        Ok(ParquetFile::from_params(parquet_file_params, id))
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         sqlx::query_as::<_, ParquetFile>(
             r#"
@@ -1581,7 +1974,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.storage_tier
 FROM parquet_file;
              "#,
         )
@@ -1592,16 +1986,17 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // table retention, if it exists, overrides namespace retention
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1630,7 +2025,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.storage_tier
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
@@ -1648,7 +2044,7 @@ WHERE table_name.namespace_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at,
-       column_set, max_l0_created_at
+       column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
@@ -1694,7 +2090,7 @@ RETURNING id;
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1707,7 +2103,7 @@ WHERE partition.hash_id = $1
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1732,7 +2128,7 @@ WHERE partition.id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, storage_tier
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1808,6 +2204,164 @@ WHERE object_store_id = ANY($1);
             .map_err(|source| Error::FailedToCommit { source })?;
         Ok(ids)
     }
+
+    async fn rewrite_sort_key(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+        old_sort_key_ids: &SortedColumnSet,
+        new_sort_key: &[&str],
+        new_sort_key_ids: &SortedColumnSet,
+        delete: &[ParquetFileId],
+        create: &[ParquetFileParams],
+    ) -> Result<Vec<ParquetFileId>> {
+        assert_eq!(new_sort_key.len(), new_sort_key_ids.len());
+
+        let mut tx = self
+            .inner
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        // Swapping the sort key locks the partition row until the transaction ends. This
+        // serialises the rewrite with the creation of any other file in the partition, which
+        // updates the `new_file_at` of the partition.
+        //
+        // This `match` will go away when all partitions have hash IDs in the database.
+        let query = match partition_id {
+            TransitionPartitionId::Deterministic(hash_id) => sqlx::query(
+                r#"
+UPDATE partition
+SET sort_key = $1, sort_key_ids = $2
+WHERE hash_id = $3 AND sort_key_ids = $4
+RETURNING id;
+        "#,
+            )
+            .bind(new_sort_key) // $1
+            .bind(new_sort_key_ids) // $2
+            .bind(hash_id) // $3
+            .bind(old_sort_key_ids), // $4
+            TransitionPartitionId::Deprecated(id) => sqlx::query(
+                r#"
+UPDATE partition
+SET sort_key = $1, sort_key_ids = $2
+WHERE id = $3 AND sort_key_ids = $4
+RETURNING id;
+        "#,
+            )
+            .bind(new_sort_key) // $1
+            .bind(new_sort_key_ids) // $2
+            .bind(id) // $3
+            .bind(old_sort_key_ids), // $4
+        };
+        let Some(partition) = query
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+        else {
+            return Err(Error::SortKeyRewriteConflict {
+                id: partition_id.clone(),
+            });
+        };
+        let id: i64 = partition.get("id");
+
+        let marked_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+UPDATE parquet_file
+SET to_delete = $1
+WHERE id = ANY($2) AND to_delete IS NULL
+RETURNING id;
+        "#,
+        )
+        .bind(marked_at) // $1
+        .bind(delete) // $2
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let remaining: i64 = sqlx::query(
+            r#"
+SELECT count(1) AS count
+FROM parquet_file
+INNER JOIN partition
+ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
+WHERE partition.id = $1
+  AND parquet_file.to_delete IS NULL;
+        "#,
+        )
+        .bind(id) // $1
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .get("count");
+
+        if flagged.len() != delete.len() || remaining != 0 {
+            return Err(Error::SortKeyRewriteConflict {
+                id: partition_id.clone(),
+            });
+        }
+
+        let mut ids = Vec::with_capacity(create.len());
+        for file in create {
+            let id = create_parquet_file(&mut *tx, file).await?;
+            ids.push(id);
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+        Ok(ids)
+    }
+
+    async fn list_by_namespace_tier_older_than(
+        &mut self,
+        namespace_id: NamespaceId,
+        storage_tier: StorageTier,
+        older_than: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        sqlx::query_as::<_, ParquetFile>(
+            r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
+       max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
+       max_l0_created_at, storage_tier
+FROM parquet_file
+WHERE namespace_id = $1
+  AND storage_tier = $2
+  AND max_time < $3
+  AND to_delete IS NULL;
+             "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(storage_tier) // $2
+        .bind(older_than) // $3
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })
+    }
+
+    async fn update_storage_tier(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+        storage_tier: StorageTier,
+    ) -> Result<Vec<ParquetFileId>> {
+        let updated = sqlx::query(
+            r#"
+UPDATE parquet_file
+SET storage_tier = $1
+WHERE id = ANY($2) AND to_delete IS NULL
+RETURNING id;
+        "#,
+        )
+        .bind(storage_tier) // $1
+        .bind(parquet_file_ids) // $2
+        .fetch_all(&mut self.inner)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let updated = updated.into_iter().map(|row| row.get("id")).collect();
+        Ok(updated)
+    }
 }
 
 // The following three functions are helpers to the create_upgrade_delete method.
@@ -2656,7 +3210,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
             "#,
         )
         .bind(namespace_name) // $1
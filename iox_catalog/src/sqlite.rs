@@ -14,13 +14,12 @@ use crate::{
 };
 use async_trait::async_trait;
 use data_types::{
-    partition_template::{
-        NamespacePartitionTemplateOverride, TablePartitionTemplateOverride, TemplatePart,
-    },
-    Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, MaxColumnsPerTable, MaxTables,
-    Namespace, NamespaceId, NamespaceName, NamespaceServiceProtectionLimitsOverride, ParquetFile,
-    ParquetFileId, ParquetFileParams, Partition, PartitionHashId, PartitionId, PartitionKey,
-    SkippedCompaction, SortedColumnSet, Table, TableId, Timestamp, TransitionPartitionId,
+    partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
+    Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, FieldCoercionRules,
+    MaxColumnsPerTable, MaxTables, Namespace, NamespaceId, NamespaceName, NamespaceSchemaMode,
+    NamespaceServiceProtectionLimitsOverride, ParquetFile, ParquetFileId, ParquetFileParams,
+    Partition, PartitionHashId, PartitionId, PartitionKey, RollupPolicies, SkippedCompaction,
+    SortedColumnSet, StorageTier, Table, TableId, Timestamp, TransitionPartitionId, WriteRules,
 };
 use iox_time::{SystemProvider, TimeProvider};
 use metric::Registry;
@@ -282,7 +281,8 @@ impl NamespaceRepo for SqliteTxn {
 INSERT INTO namespace ( name, topic_id, query_pool_id, retention_period_ns, max_tables, max_columns_per_table, partition_template )
 VALUES ( $1, $2, $3, $4, $5, $6, $7 )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
             "#,
         )
         .bind(name.as_str()) // $1
@@ -313,7 +313,8 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE {v};
                 "#,
@@ -337,7 +338,8 @@ WHERE {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE id=$1 AND {v};
                 "#,
@@ -367,7 +369,8 @@ WHERE id=$1 AND {v};
             format!(
                 r#"
 SELECT id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-       partition_template
+       partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+       cold_tier_age_ns, compaction_weight, properties_version
 FROM namespace
 WHERE name=$1 AND {v};
                 "#,
@@ -408,7 +411,8 @@ UPDATE namespace
 SET max_tables = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
         "#,
         )
         .bind(new_max)
@@ -437,7 +441,8 @@ UPDATE namespace
 SET max_columns_per_table = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
         "#,
         )
         .bind(new_max)
@@ -466,7 +471,8 @@ UPDATE namespace
 SET retention_period_ns = $1
 WHERE name = $2
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
             "#,
         )
         .bind(retention_period_ns) // $1
@@ -483,6 +489,216 @@ RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, dele
 
         Ok(namespace)
     }
+
+    async fn update_schema_mode(
+        &mut self,
+        name: &str,
+        schema_mode: NamespaceSchemaMode,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET schema_mode = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+            "#,
+        )
+        .bind(schema_mode) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_field_coercion(
+        &mut self,
+        name: &str,
+        field_coercion: FieldCoercionRules,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET field_coercion = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+            "#,
+        )
+        .bind(field_coercion) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_write_rules(
+        &mut self,
+        name: &str,
+        write_rules: WriteRules,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET write_rules = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+            "#,
+        )
+        .bind(write_rules) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        name: &str,
+        partition_template: NamespacePartitionTemplateOverride,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET partition_template = $1, properties_version = properties_version + 1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(partition_template) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_rollup_policies(
+        &mut self,
+        name: &str,
+        rollup_policies: RollupPolicies,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET rollup_policies = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(rollup_policies) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_cold_tier_age(
+        &mut self,
+        name: &str,
+        cold_tier_age_ns: Option<i64>,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET cold_tier_age_ns = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(cold_tier_age_ns) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
+
+    async fn update_compaction_weight(
+        &mut self,
+        name: &str,
+        compaction_weight: i32,
+    ) -> Result<Namespace> {
+        let rec = sqlx::query_as::<_, Namespace>(
+            r#"
+UPDATE namespace
+SET compaction_weight = $1
+WHERE name = $2
+RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
+        "#,
+        )
+        .bind(compaction_weight) // $1
+        .bind(name) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let namespace = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::NamespaceNotFoundByName {
+                name: name.to_string(),
+            },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(namespace)
+    }
 }
 
 /// [`TableRepo::create`] needs the ability to create some columns within the same transaction as
@@ -605,15 +821,12 @@ RETURNING *;
             }
         })?;
 
-        // Partitioning is only supported for tags, so create tag columns for all `TagValue`
-        // partition template parts. It's important this happens within the table creation
+        // Partitioning is only supported for tags, so create tag columns for all `TagValue` and
+        // `Bucket` partition template parts. It's important this happens within the table creation
         // transaction so that there isn't a possibility of a concurrent write creating these
         // columns with an unsupported type.
-        for template_part in table.partition_template.parts() {
-            if let TemplatePart::TagValue(tag_name) = template_part {
-                insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag)
-                    .await?;
-            }
+        for tag_name in table.partition_template.tag_columns() {
+            insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag).await?;
         }
 
         tx.commit()
@@ -694,6 +907,75 @@ WHERE namespace_id = $1;
 
         Ok(rec)
     }
+
+    async fn update_retention_period(
+        &mut self,
+        table_id: TableId,
+        retention_period_ns: Option<i64>,
+    ) -> Result<Table> {
+        let rec = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET retention_period_ns = $1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(retention_period_ns) // $1
+        .bind(table_id) // $2
+        .fetch_one(self.inner.get_mut())
+        .await;
+
+        let table = rec.map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        Ok(table)
+    }
+
+    async fn update_partition_template(
+        &mut self,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Table> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        let table = sqlx::query_as::<_, Table>(
+            r#"
+UPDATE table_name
+SET partition_template = $1, partition_template_version = partition_template_version + 1
+WHERE id = $2
+RETURNING *;
+        "#,
+        )
+        .bind(partition_template) // $1
+        .bind(table_id) // $2
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| match e {
+            sqlx::Error::RowNotFound => Error::TableNotFound { id: table_id },
+            _ => Error::SqlxError { source: e },
+        })?;
+
+        // As with table creation, ensure the columns the new template partitions on exist as tags
+        // within the same transaction.
+        for tag_name in table.partition_template.tag_columns() {
+            insert_column_with_connection(&mut *tx, tag_name, table.id, ColumnType::Tag).await?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+
+        Ok(table)
+    }
 }
 
 #[async_trait]
@@ -827,6 +1109,7 @@ struct PartitionPod {
     sort_key: Option<Json<Vec<String>>>,
     sort_key_ids: Json<Vec<i64>>,
     new_file_at: Option<Timestamp>,
+    partition_template: TablePartitionTemplateOverride,
 }
 
 impl From<PartitionPod> for Partition {
@@ -842,6 +1125,7 @@ impl From<PartitionPod> for Partition {
             sort_key,
             sort_key_ids,
             value.new_file_at,
+            value.partition_template,
         )
     }
 }
@@ -855,21 +1139,62 @@ impl PartitionRepo for SqliteTxn {
 
         let hash_id = PartitionHashId::new(table_id, &key);
 
+        // The partition records the partition template of the table at creation time, so that
+        // the partition key remains interpretable should the table template change later.
         let v = sqlx::query_as::<_, PartitionPod>(
             r#"
 INSERT INTO partition
-    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids)
+    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids, partition_template)
 VALUES
-    ($1, $2, $3, $4, '[]', '[]')
+    ($1, $2, $3, $4, '[]', '[]', (SELECT partition_template FROM table_name WHERE id = $3))
 ON CONFLICT (table_id, partition_key)
 DO UPDATE SET partition_key = partition.partition_key
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
+        "#,
+        )
+        .bind(key) // $1
+        .bind(TRANSITION_SHARD_ID) // $2
+        .bind(table_id) // $3
+        .bind(&hash_id) // $4
+        .fetch_one(self.inner.get_mut())
+        .await
+        .map_err(|e| {
+            if is_fk_violation(&e) {
+                Error::ForeignKeyViolation { source: e }
+            } else {
+                Error::SqlxError { source: e }
+            }
+        })?;
+
+        Ok(v.into())
+    }
+
+    async fn create_or_get_with_template(
+        &mut self,
+        key: PartitionKey,
+        table_id: TableId,
+        partition_template: TablePartitionTemplateOverride,
+    ) -> Result<Partition> {
+        let hash_id = PartitionHashId::new(table_id, &key);
+
+        let v = sqlx::query_as::<_, PartitionPod>(
+            r#"
+INSERT INTO partition
+    (partition_key, shard_id, table_id, hash_id, sort_key, sort_key_ids, partition_template)
+VALUES
+    ($1, $2, $3, $4, '[]', '[]', $5)
+ON CONFLICT (table_id, partition_key)
+DO UPDATE SET partition_key = partition.partition_key
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
         )
         .bind(key) // $1
         .bind(TRANSITION_SHARD_ID) // $2
         .bind(table_id) // $3
         .bind(&hash_id) // $4
+        .bind(partition_template) // $5
         .fetch_one(self.inner.get_mut())
         .await
         .map_err(|e| {
@@ -886,7 +1211,8 @@ RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file
     async fn get_by_id(&mut self, partition_id: PartitionId) -> Result<Option<Partition>> {
         let rec = sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE id = $1;
             "#,
@@ -910,7 +1236,8 @@ WHERE id = $1;
 
         sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE id IN (SELECT value FROM json_each($1));
             "#,
@@ -928,7 +1255,8 @@ WHERE id IN (SELECT value FROM json_each($1));
     ) -> Result<Option<Partition>> {
         let rec = sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE hash_id = $1;
             "#,
@@ -966,7 +1294,8 @@ WHERE hash_id = $1;
 
         sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE hex(hash_id) IN (SELECT value FROM json_each($1));
             "#,
@@ -981,7 +1310,8 @@ WHERE hex(hash_id) IN (SELECT value FROM json_each($1));
     async fn list_by_table_id(&mut self, table_id: TableId) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE table_id = $1;
             "#,
@@ -1044,7 +1374,8 @@ WHERE table_id = $1;
 UPDATE partition
 SET sort_key = $1, sort_key_ids = $4
 WHERE hash_id = $2 AND sort_key = $3 AND sort_key_ids = $5
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
             )
             .bind(Json(new_sort_key)) // $1
@@ -1057,7 +1388,8 @@ RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file
 UPDATE partition
 SET sort_key = $1, sort_key_ids = $4
 WHERE id = $2 AND sort_key = $3 AND sort_key_ids = $5
-RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at;
+RETURNING id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+          partition_template;
         "#,
             )
             .bind(Json(new_sort_key)) // $1
@@ -1197,7 +1529,8 @@ RETURNING *
     async fn most_recent_n(&mut self, n: usize) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 ORDER BY id DESC
 LIMIT $1;
@@ -1240,7 +1573,8 @@ LIMIT $1;
     async fn list_old_style(&mut self) -> Result<Vec<Partition>> {
         Ok(sqlx::query_as::<_, PartitionPod>(
             r#"
-SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
 FROM partition
 WHERE hash_id IS NULL
 ORDER BY id DESC;
@@ -1280,6 +1614,7 @@ struct ParquetFilePod {
     created_at: Timestamp,
     column_set: Json<Vec<i64>>,
     max_l0_created_at: Timestamp,
+    storage_tier: StorageTier,
 }
 
 impl From<ParquetFilePod> for ParquetFile {
@@ -1299,6 +1634,7 @@ impl From<ParquetFilePod> for ParquetFile {
             created_at: value.created_at,
             column_set: to_column_set(&value.column_set),
             max_l0_created_at: value.max_l0_created_at,
+            storage_tier: value.storage_tier,
         }
     }
 }
@@ -1310,6 +1646,66 @@ impl ParquetFileRepo for SqliteTxn {
         create_parquet_file(executor, parquet_file_params).await
     }
 
+    async fn create_if_sort_key(
+        &mut self,
+        parquet_file_params: ParquetFileParams,
+        sort_key_ids: &SortedColumnSet,
+    ) -> Result<ParquetFile> {
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        // This `match` will go away when all partitions have hash IDs in the database.
+        let partition_id = &parquet_file_params.partition_id;
+        let query = match partition_id {
+            TransitionPartitionId::Deterministic(hash_id) => sqlx::query_as::<_, PartitionPod>(
+                r#"
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
+FROM partition
+WHERE hash_id = $1;
+            "#,
+            )
+            .bind(hash_id), // $1
+            TransitionPartitionId::Deprecated(id) => sqlx::query_as::<_, PartitionPod>(
+                r#"
+SELECT id, hash_id, table_id, partition_key, sort_key, sort_key_ids, new_file_at,
+       partition_template
+FROM partition
+WHERE id = $1;
+            "#,
+            )
+            .bind(id), // $1
+        };
+        let partition: Partition = query
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+            .ok_or_else(|| Error::PartitionNotFound {
+                id: partition_id.clone(),
+            })?
+            .into();
+
+        if !partition.sort_key_ids.starts_with(sort_key_ids) {
+            return Err(Error::ParquetFileSortKeyConflict {
+                id: partition_id.clone(),
+                sort_key: partition.sort_key,
+                sort_key_ids: partition.sort_key_ids,
+            });
+        }
+
+        let parquet_file = create_parquet_file(&mut *tx, parquet_file_params).await?;
+
+        tx.commit()
+            .await
+            .map_err(|source| Error::FailedToCommit { source })?;
+        Ok(parquet_file)
+    }
+
     async fn list_all(&mut self) -> Result<Vec<ParquetFile>> {
         // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
         // `parquet_metadata` column!!
@@ -1319,7 +1715,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.storage_tier
 FROM parquet_file;
              "#,
         )
@@ -1333,16 +1730,17 @@ FROM parquet_file;
 
     async fn flag_for_delete_by_retention(&mut self) -> Result<Vec<ParquetFileId>> {
         let flagged_at = Timestamp::from(self.time_provider.now());
-        // TODO - include check of table retention period once implemented
+        // table retention, if it exists, overrides namespace retention
         let flagged = sqlx::query(
             r#"
 WITH parquet_file_ids as (
     SELECT parquet_file.id
-    FROM namespace, parquet_file
-    WHERE namespace.retention_period_ns IS NOT NULL
+    FROM namespace, table_name, parquet_file
+    WHERE COALESCE(table_name.retention_period_ns, namespace.retention_period_ns) IS NOT NULL
     AND parquet_file.to_delete IS NULL
-    AND parquet_file.max_time < $1 - namespace.retention_period_ns
+    AND parquet_file.max_time < $1 - COALESCE(table_name.retention_period_ns, namespace.retention_period_ns)
     AND namespace.id = parquet_file.namespace_id
+    AND table_name.id = parquet_file.table_id
     LIMIT $2
 )
 UPDATE parquet_file
@@ -1373,7 +1771,8 @@ SELECT parquet_file.id, parquet_file.namespace_id, parquet_file.table_id,
        parquet_file.partition_id, parquet_file.partition_hash_id, parquet_file.object_store_id,
        parquet_file.min_time, parquet_file.max_time, parquet_file.to_delete,
        parquet_file.file_size_bytes, parquet_file.row_count, parquet_file.compaction_level,
-       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at
+       parquet_file.created_at, parquet_file.column_set, parquet_file.max_l0_created_at,
+       parquet_file.storage_tier
 FROM parquet_file
 INNER JOIN table_name on table_name.id = parquet_file.table_id
 WHERE table_name.namespace_id = $1
@@ -1394,7 +1793,7 @@ WHERE table_name.namespace_id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id,
        min_time, max_time, to_delete, file_size_bytes,
-       row_count, compaction_level, created_at, column_set, max_l0_created_at
+       row_count, compaction_level, created_at, column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 WHERE table_id = $1 AND to_delete IS NULL;
              "#,
@@ -1443,7 +1842,7 @@ RETURNING id;
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1456,7 +1855,7 @@ WHERE partition.hash_id = $1
                 r#"
 SELECT parquet_file.id, namespace_id, parquet_file.table_id, partition_id, partition_hash_id,
        object_store_id, min_time, max_time, parquet_file.to_delete, file_size_bytes, row_count,
-       compaction_level, created_at, column_set, max_l0_created_at
+       compaction_level, created_at, column_set, max_l0_created_at, storage_tier
 FROM parquet_file
 INNER JOIN partition
 ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
@@ -1484,7 +1883,7 @@ WHERE partition.id = $1
             r#"
 SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
        max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
-       max_l0_created_at
+       max_l0_created_at, storage_tier
 FROM parquet_file
 WHERE object_store_id = $1;
              "#,
@@ -1568,6 +1967,173 @@ WHERE object_store_id IN ({v});",
 
         Ok(ids)
     }
+
+    async fn rewrite_sort_key(
+        &mut self,
+        partition_id: &TransitionPartitionId,
+        old_sort_key_ids: &SortedColumnSet,
+        new_sort_key: &[&str],
+        new_sort_key_ids: &SortedColumnSet,
+        delete: &[ParquetFileId],
+        create: &[ParquetFileParams],
+    ) -> Result<Vec<ParquetFileId>> {
+        assert_eq!(new_sort_key.len(), new_sort_key_ids.len());
+
+        let raw_old_sort_key_ids: Vec<_> = old_sort_key_ids.iter().map(|c| c.get()).collect();
+        let raw_new_sort_key_ids: Vec<_> = new_sort_key_ids.iter().map(|c| c.get()).collect();
+
+        let mut tx = self
+            .inner
+            .get_mut()
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::StartTransaction { source: e })?;
+
+        // This `match` will go away when all partitions have hash IDs in the database.
+        let query = match partition_id {
+            TransitionPartitionId::Deterministic(hash_id) => sqlx::query(
+                r#"
+UPDATE partition
+SET sort_key = $1, sort_key_ids = $2
+WHERE hash_id = $3 AND sort_key_ids = $4
+RETURNING id;
+        "#,
+            )
+            .bind(Json(new_sort_key)) // $1
+            .bind(Json(&raw_new_sort_key_ids)) // $2
+            .bind(hash_id) // $3
+            .bind(Json(&raw_old_sort_key_ids)), // $4
+            TransitionPartitionId::Deprecated(id) => sqlx::query(
+                r#"
+UPDATE partition
+SET sort_key = $1, sort_key_ids = $2
+WHERE id = $3 AND sort_key_ids = $4
+RETURNING id;
+        "#,
+            )
+            .bind(Json(new_sort_key)) // $1
+            .bind(Json(&raw_new_sort_key_ids)) // $2
+            .bind(id) // $3
+            .bind(Json(&raw_old_sort_key_ids)), // $4
+        };
+        let Some(partition) = query
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(|e| Error::SqlxError { source: e })?
+        else {
+            return Err(Error::SortKeyRewriteConflict {
+                id: partition_id.clone(),
+            });
+        };
+        let id: i64 = partition.get("id");
+
+        // We use a JSON-based "IS IN" check.
+        let delete_ids: Vec<_> = delete.iter().map(|p| p.get()).collect();
+        let marked_at = Timestamp::from(self.time_provider.now());
+        let flagged = sqlx::query(
+            r#"
+UPDATE parquet_file
+SET to_delete = $1
+WHERE id IN (SELECT value FROM json_each($2)) AND to_delete IS NULL
+RETURNING id;
+        "#,
+        )
+        .bind(marked_at) // $1
+        .bind(Json(&delete_ids[..])) // $2
+        .fetch_all(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let remaining: i64 = sqlx::query(
+            r#"
+SELECT count(1) AS count
+FROM parquet_file
+INNER JOIN partition
+ON partition.id = parquet_file.partition_id OR partition.hash_id = parquet_file.partition_hash_id
+WHERE partition.id = $1
+  AND parquet_file.to_delete IS NULL;
+        "#,
+        )
+        .bind(id) // $1
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .get("count");
+
+        if flagged.len() != delete.len() || remaining != 0 {
+            return Err(Error::SortKeyRewriteConflict {
+                id: partition_id.clone(),
+            });
+        }
+
+        let mut ids = Vec::with_capacity(create.len());
+        for file in create {
+            let res = create_parquet_file(&mut *tx, file.clone()).await?;
+            ids.push(res.id);
+        }
+        tx.commit()
+            .await
+            .map_err(|e| Error::FailedToCommit { source: e })?;
+
+        Ok(ids)
+    }
+
+    async fn list_by_namespace_tier_older_than(
+        &mut self,
+        namespace_id: NamespaceId,
+        storage_tier: StorageTier,
+        older_than: Timestamp,
+    ) -> Result<Vec<ParquetFile>> {
+        // Deliberately doesn't use `SELECT *` to avoid the performance hit of fetching the large
+        // `parquet_metadata` column!!
+        Ok(sqlx::query_as::<_, ParquetFilePod>(
+            r#"
+SELECT id, namespace_id, table_id, partition_id, partition_hash_id, object_store_id, min_time,
+       max_time, to_delete, file_size_bytes, row_count, compaction_level, created_at, column_set,
+       max_l0_created_at, storage_tier
+FROM parquet_file
+WHERE namespace_id = $1
+  AND storage_tier = $2
+  AND max_time < $3
+  AND to_delete IS NULL;
+             "#,
+        )
+        .bind(namespace_id) // $1
+        .bind(storage_tier) // $2
+        .bind(older_than) // $3
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?
+        .into_iter()
+        .map(Into::into)
+        .collect())
+    }
+
+    async fn update_storage_tier(
+        &mut self,
+        parquet_file_ids: &[ParquetFileId],
+        storage_tier: StorageTier,
+    ) -> Result<Vec<ParquetFileId>> {
+        // We use a JSON-based "IS IN" check.
+        let ids: Vec<_> = parquet_file_ids.iter().map(|p| p.get()).collect();
+        let updated = sqlx::query(
+            r#"
+UPDATE parquet_file
+SET storage_tier = $1
+WHERE id IN (SELECT value FROM json_each($2)) AND to_delete IS NULL
+RETURNING id;
+        "#,
+        )
+        .bind(storage_tier) // $1
+        .bind(Json(&ids[..])) // $2
+        .fetch_all(self.inner.get_mut())
+        .await
+        .map_err(|e| Error::SqlxError { source: e })?;
+
+        let updated = updated.into_iter().map(|row| row.get("id")).collect();
+        Ok(updated)
+    }
 }
 
 // The following three functions are helpers to the create_upgrade_delete method.
@@ -1608,7 +2174,7 @@ VALUES ( $1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14 )
 RETURNING
     id, table_id, partition_id, partition_hash_id, object_store_id, min_time, max_time, to_delete,
     file_size_bytes, row_count, compaction_level, created_at, namespace_id, column_set,
-    max_l0_created_at;
+    max_l0_created_at, storage_tier;
         "#,
     )
     .bind(TRANSITION_SHARD_ID) // $1
@@ -2131,7 +2697,8 @@ INSERT INTO namespace (
 )
 VALUES ( $1, $2, $3, $4, NULL )
 RETURNING id, name, retention_period_ns, max_tables, max_columns_per_table, deleted_at,
-          partition_template;
+          partition_template, schema_mode, field_coercion, write_rules, rollup_policies,
+          cold_tier_age_ns, compaction_weight, properties_version;
             "#,
         )
         .bind(namespace_name) // $1
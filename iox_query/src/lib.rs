@@ -168,7 +168,7 @@ pub trait QueryNamespace: Debug + Send + Sync {
         ctx: IOxSessionContext,
     ) -> Result<Vec<Arc<dyn QueryChunk>>, DataFusionError>;
 
-    /// Retention cutoff time.
+    /// Retention cutoff time for the given table.
     ///
     /// This gives the timestamp (NOT the duration) at which data should be cut off. This should result in an additional
     /// filter of the following form:
@@ -178,7 +178,7 @@ pub trait QueryNamespace: Debug + Send + Sync {
     /// ```
     ///
     /// Returns `None` if now retention policy was defined.
-    fn retention_time_ns(&self) -> Option<i64>;
+    fn retention_time_ns(&self, table_name: &str) -> Option<i64>;
 
     /// Record that particular type of query was run / planned
     fn record_query(
@@ -1306,7 +1306,7 @@ fn table_chunk_stream<'a>(
             let namespace = Arc::clone(&namespace);
 
             async move {
-                let predicate = match namespace.retention_time_ns() {
+                let predicate = match namespace.retention_time_ns(table_name) {
                     Some(ret) => predicate.clone().with_retention(ret),
                     None => predicate.clone(),
                 };
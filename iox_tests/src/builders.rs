@@ -1,7 +1,7 @@
 use data_types::{
     Column, ColumnId, ColumnSet, ColumnType, CompactionLevel, NamespaceId, ParquetFile,
-    ParquetFileId, Partition, PartitionId, PartitionKey, SkippedCompaction, Table, TableId,
-    Timestamp, TransitionPartitionId,
+    ParquetFileId, Partition, PartitionId, PartitionKey, SkippedCompaction, StorageTier, Table,
+    TableId, Timestamp, TransitionPartitionId,
 };
 use uuid::Uuid;
 
@@ -35,6 +35,7 @@ impl ParquetFileBuilder {
                 created_at: Timestamp::new(0),
                 column_set: ColumnSet::new(vec![]),
                 max_l0_created_at: Timestamp::new(0),
+                storage_tier: StorageTier::Hot,
             },
         }
     }
@@ -100,6 +101,16 @@ impl ParquetFileBuilder {
         }
     }
 
+    /// Set the storage tier
+    pub fn with_storage_tier(self, storage_tier: StorageTier) -> Self {
+        Self {
+            file: ParquetFile {
+                storage_tier,
+                ..self.file
+            },
+        }
+    }
+
     /// Create the [`ParquetFile`]
     pub fn build(self) -> ParquetFile {
         self.file
@@ -172,6 +183,8 @@ impl TableBuilder {
                 namespace_id: NamespaceId::new(0),
                 name: "table".to_string(),
                 partition_template: Default::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
             },
         }
     }
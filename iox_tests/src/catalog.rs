@@ -6,9 +6,10 @@ use arrow::{
 };
 use data_types::{
     partition_template::TablePartitionTemplateOverride, Column, ColumnSet, ColumnType,
-    ColumnsByName, CompactionLevel, MaxColumnsPerTable, MaxTables, Namespace, NamespaceName,
-    NamespaceSchema, ParquetFile, ParquetFileParams, Partition, PartitionId, SortedColumnSet,
-    Table, TableId, TableSchema, Timestamp, TransitionPartitionId,
+    ColumnsByName, CompactionLevel, FieldCoercionRules, MaxColumnsPerTable, MaxTables, Namespace,
+    NamespaceName, NamespaceSchema, NamespaceSchemaMode, ParquetFile, ParquetFileParams, Partition,
+    PartitionId, RollupPolicies, SortedColumnSet, StorageTier, Table, TableId, TableSchema,
+    Timestamp, TransitionPartitionId, WriteRules,
 };
 use datafusion::physical_plan::metrics::Count;
 use datafusion_util::{unbounded_memory_pool, MemoryStream};
@@ -34,6 +35,7 @@ use parquet_file::{
     chunk::ParquetChunk,
     metadata::IoxMetadata,
     storage::{ParquetStorage, StorageId},
+    ParquetFilePath,
 };
 use schema::{
     sort::{adjust_sort_key_columns, compute_sort_key, SortKey},
@@ -286,6 +288,66 @@ impl TestNamespace {
             .await
             .unwrap();
     }
+
+    /// Set the schema mode for this namespace
+    pub async fn update_schema_mode(&self, schema_mode: NamespaceSchemaMode) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_schema_mode(&self.namespace.name, schema_mode)
+            .await
+            .unwrap();
+    }
+
+    /// Set the field coercion rules for this namespace
+    pub async fn update_field_coercion(&self, field_coercion: FieldCoercionRules) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_field_coercion(&self.namespace.name, field_coercion)
+            .await
+            .unwrap();
+    }
+
+    /// Set the write rules for this namespace
+    pub async fn update_write_rules(&self, write_rules: WriteRules) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_write_rules(&self.namespace.name, write_rules)
+            .await
+            .unwrap();
+    }
+
+    /// Set the rollup policies for this namespace
+    pub async fn update_rollup_policies(&self, rollup_policies: RollupPolicies) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_rollup_policies(&self.namespace.name, rollup_policies)
+            .await
+            .unwrap();
+    }
+
+    /// Set the age after which the parquet files of this namespace are moved to the cold tier
+    pub async fn update_cold_tier_age(&self, cold_tier_age_ns: Option<i64>) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_cold_tier_age(&self.namespace.name, cold_tier_age_ns)
+            .await
+            .unwrap();
+    }
+
+    /// Set the weight of the share of the compactors given to this namespace
+    pub async fn update_compaction_weight(&self, compaction_weight: i32) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .namespaces()
+            .update_compaction_weight(&self.namespace.name, compaction_weight)
+            .await
+            .unwrap();
+    }
 }
 
 /// A test table of a namespace in the catalog
@@ -373,11 +435,23 @@ impl TestTable {
         })
     }
 
+    /// Set the retention period override of this table in the catalog.
+    pub async fn update_retention_period(&self, retention_period_ns: Option<i64>) {
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .tables()
+            .update_retention_period(self.table.id, retention_period_ns)
+            .await
+            .unwrap();
+    }
+
     /// Get the TableSchema from the catalog.
     pub async fn catalog_schema(&self) -> TableSchema {
         TableSchema {
             id: self.table.id,
             partition_template: Default::default(),
+            retention_period_ns: self.table.retention_period_ns,
+            partition_template_version: 0,
             columns: self.catalog_columns().await,
         }
     }
@@ -901,6 +975,37 @@ impl TestParquetFile {
             .unwrap();
     }
 
+    /// Move the object of the parquet file from the object store of the test catalog to
+    /// `cold_store`, and record the move in the catalog.
+    ///
+    /// Returns the updated parquet file.
+    pub async fn move_to_cold_tier(&self, cold_store: &DynObjectStore) -> ParquetFile {
+        let path = ParquetFilePath::from(&self.parquet_file).object_store_path();
+        let data = self
+            .catalog
+            .object_store
+            .get(&path)
+            .await
+            .unwrap()
+            .bytes()
+            .await
+            .unwrap();
+        cold_store.put(&path, data).await.unwrap();
+        self.catalog.object_store.delete(&path).await.unwrap();
+
+        let mut repos = self.catalog.catalog.repositories().await;
+        repos
+            .parquet_files()
+            .update_storage_tier(&[self.parquet_file.id], StorageTier::Cold)
+            .await
+            .unwrap();
+
+        ParquetFile {
+            storage_tier: StorageTier::Cold,
+            ..self.parquet_file.clone()
+        }
+    }
+
     /// Get Parquet file schema.
     pub async fn schema(&self) -> Schema {
         let table_columns = self.table.catalog_columns().await;
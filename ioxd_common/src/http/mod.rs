@@ -4,7 +4,7 @@ use authz::http::AuthorizationHeaderExtension;
 use hyper::{
     http::HeaderValue,
     server::conn::{AddrIncoming, AddrStream},
-    Body, Method, Request, Response,
+    Body, Method, Request, Response, StatusCode,
 };
 use observability_deps::tracing::{debug, error};
 use serde::Deserialize;
@@ -137,6 +137,7 @@ async fn route_request(
 
     let response = match (method.clone(), uri.path()) {
         (Method::GET, "/health") => health(),
+        (Method::GET, "/ready") => ready(server_type.as_ref()),
         (Method::GET, "/metrics") => handle_metrics(server_type.as_ref()),
         (Method::GET, "/debug/pprof") => pprof_home(req).await,
         (Method::GET, "/debug/pprof/profile") => pprof_profile(req).await,
@@ -170,6 +171,19 @@ fn health() -> Result<Response<Body>, ApplicationError> {
     Ok(Response::new(Body::from(response_body.to_string())))
 }
 
+fn ready(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
+    match server_type.ready() {
+        Ok(()) => {
+            let response_body = "OK";
+            Ok(Response::new(Body::from(response_body.to_string())))
+        }
+        Err(reason) => Ok(Response::builder()
+            .status(StatusCode::SERVICE_UNAVAILABLE)
+            .body(Body::from(reason))
+            .expect("valid response")),
+    }
+}
+
 fn handle_metrics(server_type: &dyn ServerType) -> Result<Response<Body>, ApplicationError> {
     let mut body: Vec<u8> = Default::default();
     let mut reporter = metric_exporters::PrometheusTextEncoder::new(&mut body);
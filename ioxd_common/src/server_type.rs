@@ -20,6 +20,11 @@ pub enum RpcError {
         source: tonic::transport::Error,
         details: String,
     },
+
+    #[snafu(display("server initialisation failed: {}", source))]
+    ServerInit {
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
 }
 
 // Custom impl to include underlying source (not included in tonic
@@ -47,6 +52,15 @@ pub trait ServerType: std::fmt::Debug + Send + Sync + 'static {
     /// Trace collector associated with the server, if any.
     fn trace_collector(&self) -> Option<Arc<dyn TraceCollector>>;
 
+    /// Report whether this server is ready to serve requests.
+    ///
+    /// An error causes the `/ready` route to respond with `503 Service
+    /// Unavailable`, with the error message as the response body. The
+    /// `/health` route is unaffected, and reports the process as live.
+    fn ready(&self) -> Result<(), String> {
+        Ok(())
+    }
+
     /// Route given HTTP request.
     ///
     /// Note that this is only called if none of the shared, common routes (e.g. `/health`) match.
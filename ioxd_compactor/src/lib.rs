@@ -10,6 +10,7 @@
     missing_debug_implementations,
     unused_crate_dependencies
 )]
+mod rpc;
 mod scheduler_config;
 
 // Workaround for "unused crate" lint false positives.
@@ -19,6 +20,7 @@ use async_trait::async_trait;
 use backoff::BackoffConfig;
 use clap_blocks::compactor::CompactorConfig;
 use compactor::{compactor::Compactor, config::Config};
+use compactor_scheduler::ReadAmplificationScores;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
@@ -43,10 +45,12 @@ use std::{
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
+pub use crate::scheduler_config::convert_local_scheduler_config;
 use crate::scheduler_config::convert_scheduler_config;
 
 pub struct CompactorServerType {
     compactor: Compactor,
+    catalog: Arc<dyn Catalog>,
     metric_registry: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
 }
@@ -60,11 +64,13 @@ impl std::fmt::Debug for CompactorServerType {
 impl CompactorServerType {
     pub fn new(
         compactor: Compactor,
+        catalog: Arc<dyn Catalog>,
         metric_registry: Arc<metric::Registry>,
         common_state: &CommonServerState,
     ) -> Self {
         Self {
             compactor,
+            catalog,
             metric_registry,
             trace_collector: common_state.trace_collector(),
         }
@@ -100,6 +106,14 @@ impl ServerType for CompactorServerType {
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
         let builder = setup_builder!(builder_input, self);
 
+        add_service!(
+            builder,
+            rpc::compaction_service(
+                Arc::clone(&self.catalog),
+                self.compactor.sort_key_rewrite_requests(),
+            )
+        );
+
         serve_builder!(builder);
 
         Ok(())
@@ -200,14 +214,63 @@ pub async fn create_compactor_server_type(
     time_provider: Arc<dyn TimeProvider>,
     compactor_config: CompactorConfig,
 ) -> Arc<dyn ServerType> {
+    let compactor = Compactor::start(create_compactor_config(
+        Arc::clone(&metric_registry),
+        common_state.trace_collector(),
+        Arc::clone(&catalog),
+        parquet_store_real,
+        parquet_store_scratchpad,
+        exec,
+        time_provider,
+        compactor_config,
+    ))
+    .await;
+
+    Arc::new(CompactorServerType::new(
+        compactor,
+        catalog,
+        metric_registry,
+        common_state,
+    ))
+}
+
+/// Derive the compactor [`Config`] from its command line configuration.
+#[allow(clippy::too_many_arguments)]
+pub fn create_compactor_config(
+    metric_registry: Arc<metric::Registry>,
+    trace_collector: Option<Arc<dyn TraceCollector>>,
+    catalog: Arc<dyn Catalog>,
+    parquet_store_real: ParquetStorage,
+    parquet_store_scratchpad: ParquetStorage,
+    exec: Arc<Executor>,
+    time_provider: Arc<dyn TimeProvider>,
+    compactor_config: CompactorConfig,
+) -> Config {
     let backoff_config = BackoffConfig::default();
 
-    let compactor = Compactor::start(Config {
-        metric_registry: Arc::clone(&metric_registry),
-        trace_collector: common_state.trace_collector(),
+    // Optionally prioritise partitions by the read costs gossiped by the
+    // queriers.
+    let read_amplification_scores = compactor_config
+        .compactor_scheduler_config
+        .query_stats_half_life
+        .map(|half_life| {
+            if compactor_config.gossip_config.gossip_bind_address.is_none() {
+                warn!("query stats half-life set without gossip enabled, partitions will not be prioritised");
+            }
+            Arc::new(ReadAmplificationScores::new(
+                half_life,
+                Arc::clone(&time_provider),
+            ))
+        });
+
+    Config {
+        metric_registry,
+        trace_collector,
         catalog,
         scheduler_config: convert_scheduler_config(
             compactor_config.compactor_scheduler_config.clone(),
+            compactor_config.compaction_partition_concurrency,
+            read_amplification_scores,
         ),
         parquet_store_real,
         parquet_store_scratchpad,
@@ -233,17 +296,12 @@ pub async fn create_compactor_server_type(
         max_num_files_per_plan: compactor_config.max_num_files_per_plan,
         max_partition_fetch_queries_per_second: compactor_config
             .max_partition_fetch_queries_per_second,
+        rollup_poll_interval: compactor_config.rollup_poll_interval,
+        sort_key_rewrite_poll_interval: compactor_config.sort_key_rewrite_poll_interval,
         gossip_seeds: compactor_config.gossip_config.seed_list,
         gossip_bind_address: compactor_config
             .gossip_config
             .gossip_bind_address
             .map(Into::into),
-    })
-    .await;
-
-    Arc::new(CompactorServerType::new(
-        compactor,
-        metric_registry,
-        common_state,
-    ))
+    }
 }
@@ -0,0 +1,115 @@
+//! CompactionService gRPC implementation
+
+use std::sync::Arc;
+
+use compactor::sort_key_rewrite::SortKeyRewriteRequests;
+use data_types::PartitionId;
+use generated_types::influxdata::iox::compactor::v1::{
+    self as proto,
+    compaction_service_server::{CompactionService, CompactionServiceServer},
+};
+use iox_catalog::interface::{Catalog, SoftDeletedRows};
+use observability_deps::tracing::info;
+use tonic::{Request, Response, Status};
+
+/// Acquire a [`CompactionService`] gRPC service implementation.
+pub(crate) fn compaction_service(
+    catalog: Arc<dyn Catalog>,
+    sort_key_rewrite_requests: SortKeyRewriteRequests,
+) -> CompactionServiceServer<impl CompactionService> {
+    CompactionServiceServer::new(CompactionServiceImpl {
+        catalog,
+        sort_key_rewrite_requests,
+    })
+}
+
+#[derive(Debug)]
+struct CompactionServiceImpl {
+    catalog: Arc<dyn Catalog>,
+    sort_key_rewrite_requests: SortKeyRewriteRequests,
+}
+
+#[tonic::async_trait]
+impl CompactionService for CompactionServiceImpl {
+    async fn list_skipped_compactions(
+        &self,
+        _request: Request<proto::ListSkippedCompactionsRequest>,
+    ) -> Result<Response<proto::ListSkippedCompactionsResponse>, Status> {
+        let skipped_compactions = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .list_skipped_compactions()
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .into_iter()
+            .map(Into::into)
+            .collect();
+
+        Ok(Response::new(proto::ListSkippedCompactionsResponse {
+            skipped_compactions,
+        }))
+    }
+
+    async fn delete_skipped_compactions(
+        &self,
+        request: Request<proto::DeleteSkippedCompactionsRequest>,
+    ) -> Result<Response<proto::DeleteSkippedCompactionsResponse>, Status> {
+        let partition_id = PartitionId::new(request.into_inner().partition_id);
+
+        let skipped_compaction = self
+            .catalog
+            .repositories()
+            .await
+            .partitions()
+            .delete_skipped_compactions(partition_id)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .map(Into::into);
+
+        Ok(Response::new(proto::DeleteSkippedCompactionsResponse {
+            skipped_compaction,
+        }))
+    }
+
+    async fn rewrite_table_sort_key(
+        &self,
+        request: Request<proto::RewriteTableSortKeyRequest>,
+    ) -> Result<Response<proto::RewriteTableSortKeyResponse>, Status> {
+        let proto::RewriteTableSortKeyRequest {
+            namespace_name,
+            table_name,
+        } = request.into_inner();
+
+        let mut repos = self.catalog.repositories().await;
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("namespace {namespace_name} not found")))?;
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &table_name)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| Status::not_found(format!("table {table_name} not found")))?;
+
+        if !self.sort_key_rewrite_requests.request(table.id) {
+            return Err(Status::resource_exhausted(
+                "too many sort key rewrites pending",
+            ));
+        }
+        info!(
+            namespace = %namespace_name,
+            table = %table_name,
+            table_id = %table.id,
+            "sort key rewrite requested",
+        );
+
+        Ok(Response::new(proto::RewriteTableSortKeyResponse {
+            table_id: table.id.get(),
+        }))
+    }
+}
@@ -1,11 +1,12 @@
-use std::time::Duration;
+use std::{num::NonZeroUsize, sync::Arc, time::Duration};
 
 use clap_blocks::compactor_scheduler::{
-    CompactorSchedulerConfig, CompactorSchedulerType, PartitionSourceConfigForLocalScheduler,
-    ShardConfigForLocalScheduler,
+    CompactorSchedulerConfig, CompactorSchedulerType, ConfigForRemoteScheduler,
+    PartitionSourceConfigForLocalScheduler, ShardConfigForLocalScheduler,
 };
 use compactor_scheduler::{
-    LocalSchedulerConfig, PartitionsSourceConfig, SchedulerConfig, ShardConfig,
+    CompactorId, LocalSchedulerConfig, PartitionsSourceConfig, ReadAmplificationScores,
+    RemoteSchedulerConfig, SchedulerConfig, ShardConfig,
 };
 use data_types::PartitionId;
 
@@ -64,19 +65,79 @@ fn convert_shard_config(config: ShardConfigForLocalScheduler) -> Option<ShardCon
     }
 }
 
-pub(crate) fn convert_scheduler_config(config: CompactorSchedulerConfig) -> SchedulerConfig {
+/// Create a new, non-sharded [`LocalSchedulerConfig`] from a
+/// [`PartitionSourceConfigForLocalScheduler`].
+pub fn convert_local_scheduler_config(
+    config: PartitionSourceConfigForLocalScheduler,
+) -> LocalSchedulerConfig {
+    LocalSchedulerConfig {
+        commit_wrapper: None,
+        ignore_partition_skip_marker: config.ignore_partition_skip_marker,
+        partitions_source_config: convert_partitions_source_config(config),
+        shard_config: None,
+        read_amplification_scores: None,
+        fair_share: false,
+    }
+}
+
+/// Create a new [`RemoteSchedulerConfig`] from a [`ConfigForRemoteScheduler`].
+///
+/// The compactor ID defaults to the `hostname`.
+fn convert_remote_config(
+    config: ConfigForRemoteScheduler,
+    hostname: Option<String>,
+    max_jobs_per_lease: NonZeroUsize,
+) -> RemoteSchedulerConfig {
+    let scheduler_address = config
+        .scheduler_address
+        .expect("remote scheduler requires a scheduler address")
+        .parse()
+        .expect("invalid compactor scheduler address");
+    let compactor_id = config
+        .compactor_id
+        .or(hostname)
+        .expect("remote scheduler requires either a compactor ID or hostname");
+
+    RemoteSchedulerConfig {
+        scheduler_address,
+        compactor_id: CompactorId::new(compactor_id),
+        max_jobs_per_lease: u32::try_from(max_jobs_per_lease.get()).unwrap_or(u32::MAX),
+    }
+}
+
+/// Create a new [`SchedulerConfig`] from a [`CompactorSchedulerConfig`].
+///
+/// A remote scheduler leases at most `max_jobs_per_lease` jobs at a time, and
+/// a local scheduler orders partitions by the `read_amplification_scores`, if
+/// any.
+///
+/// # Panics
+///
+/// Panics if `read_amplification_scores` are given for a remote scheduler,
+/// which hands out jobs in its own order.
+pub(crate) fn convert_scheduler_config(
+    config: CompactorSchedulerConfig,
+    max_jobs_per_lease: NonZeroUsize,
+    read_amplification_scores: Option<Arc<ReadAmplificationScores>>,
+) -> SchedulerConfig {
     match config.compactor_scheduler_type {
         CompactorSchedulerType::Local => SchedulerConfig::Local(LocalSchedulerConfig {
-            commit_wrapper: None,
-            partitions_source_config: convert_partitions_source_config(
-                config.partition_source_config.clone(),
-            ),
             shard_config: convert_shard_config(config.shard_config),
-            ignore_partition_skip_marker: config
-                .partition_source_config
-                .ignore_partition_skip_marker,
+            read_amplification_scores,
+            fair_share: config.fair_share,
+            ..convert_local_scheduler_config(config.partition_source_config)
         }),
-        CompactorSchedulerType::Remote => unimplemented!("Remote scheduler not implemented"),
+        CompactorSchedulerType::Remote => {
+            assert!(
+                read_amplification_scores.is_none(),
+                "query stats prioritisation is only supported by the local scheduler"
+            );
+            SchedulerConfig::Remote(convert_remote_config(
+                config.remote_config,
+                config.shard_config.hostname,
+                max_jobs_per_lease,
+            ))
+        }
     }
 }
 
@@ -127,6 +188,53 @@ mod tests {
         assert_eq!(partitions_source_config, PartitionsSourceConfig::CatalogAll,);
     }
 
+    #[test]
+    fn remote_compactor_id_defaults_to_hostname() {
+        let config = ConfigForRemoteScheduler {
+            scheduler_address: Some("http://scheduler:8082".into()),
+            compactor_id: None,
+        };
+        let got = convert_remote_config(
+            config.clone(),
+            Some("iox-shared-compactor-3".into()),
+            NonZeroUsize::new(10).unwrap(),
+        );
+
+        assert_eq!(got.scheduler_address.to_string(), "http://scheduler:8082/");
+        assert_eq!(got.compactor_id, CompactorId::new("iox-shared-compactor-3"));
+        assert_eq!(got.max_jobs_per_lease, 10);
+
+        let got = convert_remote_config(
+            ConfigForRemoteScheduler {
+                compactor_id: Some("bananas".into()),
+                ..config
+            },
+            Some("iox-shared-compactor-3".into()),
+            NonZeroUsize::new(10).unwrap(),
+        );
+        assert_eq!(got.compactor_id, CompactorId::new("bananas"));
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "query stats prioritisation is only supported by the local scheduler"
+    )]
+    fn remote_scheduler_rejects_prioritisation() {
+        let config = CompactorSchedulerConfig {
+            compactor_scheduler_type: CompactorSchedulerType::Remote,
+            remote_config: ConfigForRemoteScheduler {
+                scheduler_address: Some("http://scheduler:8082".into()),
+                compactor_id: Some("bananas".into()),
+            },
+            ..Default::default()
+        };
+        let scores = Arc::new(ReadAmplificationScores::new(
+            Duration::from_secs(60),
+            Arc::new(iox_time::SystemProvider::new()),
+        ));
+        convert_scheduler_config(config, NonZeroUsize::new(10).unwrap(), Some(scores));
+    }
+
     #[test]
     fn normal_compaction() {
         let config = PartitionSourceConfigForLocalScheduler {
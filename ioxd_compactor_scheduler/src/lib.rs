@@ -0,0 +1,181 @@
+//! Standalone compactor scheduler service, leasing compaction jobs to any
+//! registered compactor.
+
+#![deny(rustdoc::broken_intra_doc_links, rust_2018_idioms)]
+#![warn(
+    clippy::clone_on_ref_ptr,
+    clippy::dbg_macro,
+    clippy::explicit_iter_loop,
+    // See https://github.com/influxdata/influxdb_iox/pull/1671
+    clippy::future_not_send,
+    clippy::todo,
+    clippy::use_self,
+    missing_debug_implementations,
+    unused_crate_dependencies
+)]
+mod rpc;
+
+// Workaround for "unused crate" lint false positives.
+use workspace_hack as _;
+
+use std::{
+    fmt::{Debug, Display},
+    sync::Arc,
+};
+
+use async_trait::async_trait;
+use clap_blocks::compactor_scheduler::CompactorSchedulerServerConfig;
+use compactor_scheduler::{create_scheduler, LeaseManager, SchedulerConfig};
+use hyper::{Body, Request, Response};
+use iox_catalog::interface::Catalog;
+use iox_time::TimeProvider;
+use ioxd_common::{
+    add_service,
+    http::error::{HttpApiError, HttpApiErrorCode, HttpApiErrorSource},
+    rpc::RpcBuilderInput,
+    serve_builder,
+    server_type::{CommonServerState, RpcError, ServerType},
+    setup_builder,
+};
+use ioxd_compactor::convert_local_scheduler_config;
+use metric::Registry;
+use tokio_util::sync::CancellationToken;
+use trace::TraceCollector;
+
+pub struct CompactorSchedulerServerType {
+    leases: Arc<LeaseManager>,
+    metric_registry: Arc<Registry>,
+    trace_collector: Option<Arc<dyn TraceCollector>>,
+    shutdown: CancellationToken,
+}
+
+impl std::fmt::Debug for CompactorSchedulerServerType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "CompactorScheduler")
+    }
+}
+
+impl CompactorSchedulerServerType {
+    pub fn new(
+        leases: Arc<LeaseManager>,
+        metric_registry: Arc<metric::Registry>,
+        common_state: &CommonServerState,
+    ) -> Self {
+        Self {
+            leases,
+            metric_registry,
+            trace_collector: common_state.trace_collector(),
+            shutdown: CancellationToken::new(),
+        }
+    }
+}
+
+#[async_trait]
+impl ServerType for CompactorSchedulerServerType {
+    /// Human name for this server type
+    fn name(&self) -> &str {
+        "compactor_scheduler"
+    }
+
+    /// Return the [`metric::Registry`] used by the compactor scheduler.
+    fn metric_registry(&self) -> Arc<Registry> {
+        Arc::clone(&self.metric_registry)
+    }
+
+    /// Returns the trace collector for compactor scheduler traces.
+    fn trace_collector(&self) -> Option<Arc<dyn TraceCollector>> {
+        self.trace_collector.as_ref().map(Arc::clone)
+    }
+
+    /// Just return "not found".
+    async fn route_http_request(
+        &self,
+        _req: Request<Body>,
+    ) -> Result<Response<Body>, Box<dyn HttpApiErrorSource>> {
+        Err(Box::new(IoxHttpError::NotFound))
+    }
+
+    /// Configure the gRPC services.
+    async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
+        let builder = setup_builder!(builder_input, self);
+
+        add_service!(
+            builder,
+            rpc::compactor_scheduler_service(Arc::clone(&self.leases))
+        );
+
+        serve_builder!(builder);
+
+        Ok(())
+    }
+
+    async fn join(self: Arc<Self>) {
+        self.shutdown.cancelled().await;
+    }
+
+    fn shutdown(&self, frontend: CancellationToken) {
+        frontend.cancel();
+        self.shutdown.cancel();
+    }
+}
+
+/// Simple error struct, we're not really providing an HTTP interface for the compactor scheduler.
+#[derive(Debug)]
+pub enum IoxHttpError {
+    NotFound,
+}
+
+impl IoxHttpError {
+    fn status_code(&self) -> HttpApiErrorCode {
+        match self {
+            Self::NotFound => HttpApiErrorCode::NotFound,
+        }
+    }
+}
+
+impl Display for IoxHttpError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{self:?}")
+    }
+}
+
+impl std::error::Error for IoxHttpError {}
+
+impl HttpApiErrorSource for IoxHttpError {
+    fn to_http_api_error(&self) -> HttpApiError {
+        HttpApiError::new(self.status_code(), self.to_string())
+    }
+}
+
+/// Instantiate a compactor scheduler server
+pub fn create_compactor_scheduler_server_type(
+    common_state: &CommonServerState,
+    metric_registry: Arc<metric::Registry>,
+    catalog: Arc<dyn Catalog>,
+    time_provider: Arc<dyn TimeProvider>,
+    config: CompactorSchedulerServerConfig,
+) -> Arc<dyn ServerType> {
+    // A single, non-sharded local scheduler finds the work for all compactors.
+    let scheduler = create_scheduler(
+        SchedulerConfig::Local(convert_local_scheduler_config(
+            config.partition_source_config,
+        )),
+        catalog,
+        Arc::clone(&time_provider),
+        Arc::clone(&metric_registry),
+        false,
+    );
+
+    let leases = Arc::new(LeaseManager::new(
+        scheduler,
+        time_provider,
+        config.lease_duration,
+        &metric_registry,
+    ));
+
+    Arc::new(CompactorSchedulerServerType::new(
+        leases,
+        metric_registry,
+        common_state,
+    ))
+}
@@ -0,0 +1,287 @@
+//! CompactorSchedulerService gRPC implementation
+
+use std::sync::Arc;
+
+use compactor_scheduler::{
+    CompactionJob, CompactionJobEnd, CompactionJobEndVariant, CompactionJobStatus,
+    CompactionJobStatusResponse, CompactionJobStatusVariant, CompactorId, LeaseError, LeaseManager,
+    SkipReason,
+};
+use generated_types::influxdata::iox::compactor_scheduler::v1::{
+    self as proto,
+    compactor_scheduler_service_server::{
+        CompactorSchedulerService, CompactorSchedulerServiceServer,
+    },
+    end_job_request::EndAction,
+    update_job_status_request::Status as JobStatus,
+};
+use tonic::{Request, Response, Status};
+use uuid::Uuid;
+
+/// Acquire a [`CompactorSchedulerService`] gRPC service implementation.
+pub(crate) fn compactor_scheduler_service(
+    leases: Arc<LeaseManager>,
+) -> CompactorSchedulerServiceServer<impl CompactorSchedulerService> {
+    CompactorSchedulerServiceServer::new(CompactorSchedulerServiceImpl { leases })
+}
+
+#[derive(Debug)]
+struct CompactorSchedulerServiceImpl {
+    leases: Arc<LeaseManager>,
+}
+
+fn compactor_id(id: String) -> Result<CompactorId, Status> {
+    if id.is_empty() {
+        return Err(Status::invalid_argument("compactor_id must be specified"));
+    }
+    Ok(CompactorId::new(id))
+}
+
+fn job(job: Option<proto::CompactionJob>) -> Result<CompactionJob, Status> {
+    job.ok_or_else(|| Status::invalid_argument("job must be specified"))?
+        .try_into()
+        .map_err(|e: compactor_scheduler::ProtoError| Status::invalid_argument(e.to_string()))
+}
+
+/// Map a [`LeaseError`] to a gRPC [`Status`].
+///
+/// Compactors react to [`Status::failed_precondition`] by registering again.
+fn lease_error_to_status(e: LeaseError) -> Status {
+    match e {
+        LeaseError::UnknownCompactor(_) => Status::failed_precondition(e.to_string()),
+        LeaseError::LeaseNotHeld { .. } => Status::aborted(e.to_string()),
+        LeaseError::PartitionMismatch { .. } => Status::invalid_argument(e.to_string()),
+        LeaseError::Scheduler(_) => Status::internal(e.to_string()),
+    }
+}
+
+#[tonic::async_trait]
+impl CompactorSchedulerService for CompactorSchedulerServiceImpl {
+    async fn register_compactor(
+        &self,
+        request: Request<proto::RegisterCompactorRequest>,
+    ) -> Result<Response<proto::RegisterCompactorResponse>, Status> {
+        let id = compactor_id(request.into_inner().compactor_id)?;
+
+        self.leases.register_compactor(id);
+
+        Ok(Response::new(proto::RegisterCompactorResponse {
+            lease_duration_ms: self.leases.lease_duration().as_millis() as u64,
+        }))
+    }
+
+    async fn lease_jobs(
+        &self,
+        request: Request<proto::LeaseJobsRequest>,
+    ) -> Result<Response<proto::LeaseJobsResponse>, Status> {
+        let request = request.into_inner();
+        let id = compactor_id(request.compactor_id)?;
+
+        let jobs = self
+            .leases
+            .lease_jobs(&id, request.max_jobs as usize)
+            .await
+            .map_err(lease_error_to_status)?;
+
+        Ok(Response::new(proto::LeaseJobsResponse {
+            jobs: jobs.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<proto::HeartbeatRequest>,
+    ) -> Result<Response<proto::HeartbeatResponse>, Status> {
+        let request = request.into_inner();
+        let id = compactor_id(request.compactor_id)?;
+
+        let job_uuids = request
+            .job_uuids
+            .iter()
+            .map(|uuid| uuid.parse::<Uuid>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| Status::invalid_argument(format!("invalid job uuid: {e}")))?;
+
+        let lost = self
+            .leases
+            .heartbeat(&id, job_uuids)
+            .map_err(lease_error_to_status)?;
+
+        Ok(Response::new(proto::HeartbeatResponse {
+            lost_job_uuids: lost.iter().map(ToString::to_string).collect(),
+        }))
+    }
+
+    async fn update_job_status(
+        &self,
+        request: Request<proto::UpdateJobStatusRequest>,
+    ) -> Result<Response<proto::UpdateJobStatusResponse>, Status> {
+        let request = request.into_inner();
+        let id = compactor_id(request.compactor_id)?;
+        let job = job(request.job)?;
+
+        let status = match request
+            .status
+            .ok_or_else(|| Status::invalid_argument("status must be specified"))?
+        {
+            JobStatus::Update(update) => {
+                CompactionJobStatusVariant::Update(update.try_into().map_err(
+                    |e: compactor_scheduler::ProtoError| Status::invalid_argument(e.to_string()),
+                )?)
+            }
+            JobStatus::Error(e) => CompactionJobStatusVariant::Error(e.into()),
+        };
+
+        let response = self
+            .leases
+            .update_job_status(&id, CompactionJobStatus { job, status })
+            .await
+            .map_err(lease_error_to_status)?;
+
+        let created_file_ids = match response {
+            CompactionJobStatusResponse::CreatedParquetFiles(ids) => {
+                ids.into_iter().map(|id| id.get()).collect()
+            }
+            CompactionJobStatusResponse::Ack => vec![],
+        };
+
+        Ok(Response::new(proto::UpdateJobStatusResponse {
+            created_file_ids,
+        }))
+    }
+
+    async fn end_job(
+        &self,
+        request: Request<proto::EndJobRequest>,
+    ) -> Result<Response<proto::EndJobResponse>, Status> {
+        let request = request.into_inner();
+        let id = compactor_id(request.compactor_id)?;
+        let job = job(request.job)?;
+
+        let end_action = match request
+            .end_action
+            .ok_or_else(|| Status::invalid_argument("end_action must be specified"))?
+        {
+            EndAction::Complete(_) => CompactionJobEndVariant::Complete,
+            EndAction::SkipReason(reason) => {
+                CompactionJobEndVariant::RequestToSkip(SkipReason(reason))
+            }
+        };
+
+        self.leases
+            .end_job(&id, CompactionJobEnd { job, end_action })
+            .await
+            .map_err(lease_error_to_status)?;
+
+        Ok(Response::new(proto::EndJobResponse {}))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use assert_matches::assert_matches;
+    use compactor_scheduler::{
+        create_scheduler, CommitUpdate, LocalSchedulerConfig, PartitionsSourceConfig,
+        RemoteSchedulerConfig, Scheduler, SchedulerConfig,
+    };
+    use data_types::{CompactionLevel, PartitionId};
+    use iox_tests::{ParquetFileBuilder, TestCatalog};
+    use iox_time::{MockProvider, Time};
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_remote_scheduler_round_trip() {
+        let catalog = TestCatalog::new();
+        let time_provider = Arc::new(MockProvider::new(Time::from_timestamp_nanos(0)));
+
+        // The service leases the partitions of a fixed, shadow-mode local
+        // scheduler.
+        let inner = create_scheduler(
+            SchedulerConfig::Local(LocalSchedulerConfig {
+                partitions_source_config: PartitionsSourceConfig::Fixed(
+                    [PartitionId::new(1)].into(),
+                ),
+                ignore_partition_skip_marker: true,
+                ..Default::default()
+            }),
+            catalog.catalog(),
+            Arc::clone(&time_provider) as _,
+            Arc::new(metric::Registry::default()),
+            true,
+        );
+        let leases = Arc::new(LeaseManager::new(
+            inner,
+            time_provider as _,
+            Duration::from_secs(60),
+            &metric::Registry::default(),
+        ));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(compactor_scheduler_service(Arc::clone(&leases)))
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let scheduler = create_scheduler(
+            SchedulerConfig::Remote(RemoteSchedulerConfig {
+                scheduler_address: format!("http://{addr}").parse().unwrap(),
+                compactor_id: CompactorId::new("compactor-1"),
+                max_jobs_per_lease: 10,
+            }),
+            catalog.catalog(),
+            catalog.time_provider(),
+            Arc::new(metric::Registry::default()),
+            false,
+        );
+
+        let jobs = scheduler.get_jobs().await;
+        assert_matches!(
+            &jobs[..],
+            [CompactionJob { partition_id, .. }] if *partition_id == PartitionId::new(1)
+        );
+        let job = jobs[0].clone();
+
+        // All partitions are leased.
+        assert!(scheduler.get_jobs().await.is_empty());
+
+        let res = scheduler
+            .update_job_status(CompactionJobStatus {
+                job: job.clone(),
+                status: CompactionJobStatusVariant::Update(CommitUpdate::new(
+                    job.partition_id,
+                    vec![],
+                    vec![],
+                    vec![ParquetFileBuilder::new(1).build().into()],
+                    CompactionLevel::Final,
+                )),
+            })
+            .await;
+        assert_matches!(
+            res,
+            Ok(CompactionJobStatusResponse::CreatedParquetFiles(ids)) if ids.len() == 1
+        );
+
+        scheduler
+            .end_job(CompactionJobEnd {
+                job: job.clone(),
+                end_action: CompactionJobEndVariant::Complete,
+            })
+            .await
+            .unwrap();
+
+        // The lease was released, and the job can no longer be updated.
+        let res = scheduler
+            .update_job_status(CompactionJobStatus {
+                job,
+                status: CompactionJobStatusVariant::Error(compactor_scheduler::ErrorKind::Timeout),
+            })
+            .await;
+        assert_matches!(res, Err(e) if e.to_string().contains("is not leased to compactor"));
+    }
+}
@@ -16,16 +16,20 @@ use workspace_hack as _;
 
 use arrow_flight::flight_service_server::FlightServiceServer;
 use async_trait::async_trait;
-use clap_blocks::ingester::IngesterConfig;
+use clap_blocks::ingester::{IngesterConfig, WalDurabilityMode};
 use futures::FutureExt;
 use generated_types::influxdata::iox::{
     catalog::v1::catalog_service_server::CatalogServiceServer,
     ingester::v1::{
+        buffer_service_server::BufferServiceServer, handoff_service_server::HandoffServiceServer,
         persist_service_server::PersistServiceServer, write_service_server::WriteServiceServer,
     },
 };
 use hyper::{Body, Request, Response};
-use ingester::{GossipConfig, IngesterGuard, IngesterRpcInterface};
+use ingester::{
+    DurabilityMode, GossipConfig, HandoffConfig, IngesterGuard, IngesterRpcInterface, Keyring,
+    SpillConfig, WalArchiveConfig, WalReplayProgress,
+};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::Executor;
 use ioxd_common::{
@@ -45,7 +49,10 @@ use std::{
     time::Duration,
 };
 use thiserror::Error;
-use tokio::sync::oneshot;
+use tokio::{
+    sync::{oneshot, OnceCell},
+    task::JoinHandle,
+};
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
@@ -58,13 +65,26 @@ const MAX_OUTGOING_MSG_BYTES: usize = 1024 * 1024; // 1 MiB
 pub enum Error {
     #[error("error initializing ingester: {0}")]
     Ingester(#[from] ingester::InitError),
+
+    #[error("invalid wal encryption keys: {0}")]
+    WalEncryptionKeys(#[from] ingester::KeyringError),
+
+    #[error("ingester initialisation task failed: {0}")]
+    InitTask(tokio::task::JoinError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
 
 struct IngesterServerType<I: IngesterRpcInterface> {
-    server: IngesterGuard<I>,
+    /// The outcome of initialising the ingester, populated once `init`
+    /// completes.
+    server: OnceCell<Result<IngesterGuard<I>, Arc<Error>>>,
+    /// The ingester initialisation task, which includes replaying the WAL.
+    init: Mutex<Option<JoinHandle<Result<IngesterGuard<I>, ingester::InitError>>>>,
+    replay_progress: Arc<WalReplayProgress>,
     shutdown: Mutex<Option<oneshot::Sender<CancellationToken>>>,
+    /// Cancelled once a shutdown is requested.
+    shutdown_requested: CancellationToken,
     metrics: Arc<Registry>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     max_simultaneous_queries: usize,
@@ -73,7 +93,8 @@ struct IngesterServerType<I: IngesterRpcInterface> {
 
 impl<I: IngesterRpcInterface> IngesterServerType<I> {
     pub fn new(
-        server: IngesterGuard<I>,
+        init: JoinHandle<Result<IngesterGuard<I>, ingester::InitError>>,
+        replay_progress: Arc<WalReplayProgress>,
         metrics: Arc<Registry>,
         common_state: &CommonServerState,
         max_simultaneous_queries: usize,
@@ -81,14 +102,39 @@ impl<I: IngesterRpcInterface> IngesterServerType<I> {
         shutdown: oneshot::Sender<CancellationToken>,
     ) -> Self {
         Self {
-            server,
+            server: OnceCell::new(),
+            init: Mutex::new(Some(init)),
+            replay_progress,
             shutdown: Mutex::new(Some(shutdown)),
+            shutdown_requested: CancellationToken::new(),
             metrics,
             trace_collector: common_state.trace_collector(),
             max_simultaneous_queries,
             max_incoming_msg_bytes,
         }
     }
+
+    /// Return the ingester, waiting for initialisation to complete if
+    /// necessary, or the error that caused initialisation to fail.
+    async fn server(&self) -> Result<&IngesterGuard<I>, Arc<Error>> {
+        self.server
+            .get_or_init(|| async {
+                let init = self
+                    .init
+                    .lock()
+                    .expect("init mutex poisoned")
+                    .take()
+                    .expect("ingester initialisation awaited more than once");
+
+                match init.await {
+                    Ok(v) => v.map_err(|e| Arc::new(Error::from(e))),
+                    Err(e) => Err(Arc::new(Error::InitTask(e))),
+                }
+            })
+            .await
+            .as_ref()
+            .map_err(Arc::clone)
+    }
 }
 
 impl<I: IngesterRpcInterface> std::fmt::Debug for IngesterServerType<I> {
@@ -122,31 +168,51 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
         Err(Box::new(IoxHttpError::NotFound))
     }
 
-    /// Configure the gRPC services.
+    /// Report the ingester as not ready until it has initialised.
+    fn ready(&self) -> Result<(), String> {
+        match self.server.get() {
+            Some(Ok(_)) => Ok(()),
+            Some(Err(e)) => Err(e.to_string()),
+            None => Err(format!("ingester initialising: {}", self.replay_progress)),
+        }
+    }
+
+    /// Configure the gRPC services once the ingester has initialised.
+    ///
+    /// Returns an error if the ingester fails to initialise.
     async fn server_grpc(self: Arc<Self>, builder_input: RpcBuilderInput) -> Result<(), RpcError> {
+        let server = self.server().await.map_err(|e| RpcError::ServerInit {
+            source: Box::new(e),
+        })?;
         let builder = setup_builder!(builder_input, self);
 
         add_service!(
             builder,
-            CatalogServiceServer::new(self.server.rpc().catalog_service())
+            CatalogServiceServer::new(server.rpc().catalog_service())
         );
         add_service!(
             builder,
-            WriteServiceServer::new(self.server.rpc().write_service())
+            WriteServiceServer::new(server.rpc().write_service())
                 .max_decoding_message_size(self.max_incoming_msg_bytes)
                 .max_encoding_message_size(MAX_OUTGOING_MSG_BYTES)
         );
         add_service!(
             builder,
-            PersistServiceServer::new(self.server.rpc().persist_service())
+            PersistServiceServer::new(server.rpc().persist_service())
+        );
+        add_service!(
+            builder,
+            HandoffServiceServer::new(server.rpc().handoff_service())
+                .max_decoding_message_size(self.max_incoming_msg_bytes)
+        );
+        add_service!(
+            builder,
+            BufferServiceServer::new(server.rpc().buffer_service())
+                .max_encoding_message_size(MAX_OUTGOING_MSG_BYTES)
         );
         add_service!(
             builder,
-            FlightServiceServer::new(
-                self.server
-                    .rpc()
-                    .query_service(self.max_simultaneous_queries)
-            )
+            FlightServiceServer::new(server.rpc().query_service(self.max_simultaneous_queries))
         );
 
         serve_builder!(builder);
@@ -155,7 +221,12 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
     }
 
     async fn join(self: Arc<Self>) {
-        self.server.join().await;
+        match self.server().await {
+            Ok(server) => server.join().await,
+            // The initialisation error is returned by server_grpc(), which
+            // stops the frontend - wait for the resulting shutdown request.
+            Err(_) => self.shutdown_requested.cancelled().await,
+        }
     }
 
     fn shutdown(&self, frontend: CancellationToken) {
@@ -167,6 +238,7 @@ impl<I: IngesterRpcInterface + Sync + Send + Debug + 'static> ServerType for Ing
         {
             let _ = c.send(frontend);
         }
+        self.shutdown_requested.cancel();
     }
 }
 
@@ -219,27 +291,91 @@ pub async fn create_ingester_server_type(
         },
     };
 
-    let grpc = ingester::new(
+    let handoff = match &ingester_config.handoff_peer_address {
+        None => HandoffConfig::Disabled,
+        Some(peer) => HandoffConfig::Enabled {
+            peer: peer.clone(),
+            advertise_address: ingester_config.handoff_advertise_address.clone(),
+            timeout: Duration::from_secs(ingester_config.handoff_timeout_seconds),
+        },
+    };
+
+    let wal_durability = match ingester_config.wal_durability_mode {
+        WalDurabilityMode::PerWrite => DurabilityMode::PerWrite,
+        WalDurabilityMode::GroupCommit => DurabilityMode::GroupCommit {
+            max_latency: Duration::from_millis(ingester_config.wal_group_commit_max_latency_ms),
+            max_batch_bytes: ingester_config.wal_group_commit_max_batch_bytes,
+        },
+        WalDurabilityMode::Unsafe => DurabilityMode::Unsafe,
+    };
+
+    let encryption = &ingester_config.wal_encryption_config;
+    let wal_keyring = match (
+        &encryption.wal_encryption_keys,
+        &encryption.wal_encryption_key_file,
+    ) {
+        (Some(keys), _) => Keyring::parse(keys)?,
+        (None, Some(path)) => Keyring::from_file(path)?,
+        (None, None) => Keyring::default(),
+    };
+
+    let spill = match (
+        ingester_config.spill_threshold_bytes,
+        &ingester_config.spill_directory,
+    ) {
+        (Some(max_memory_bytes), Some(directory)) => SpillConfig::Enabled {
+            max_memory_bytes,
+            directory: directory.clone(),
+            max_disk_bytes: ingester_config.spill_max_disk_bytes,
+        },
+        _ => SpillConfig::Disabled,
+    };
+
+    let wal_archive = match &ingester_config.wal_archive_prefix {
+        None => WalArchiveConfig::Disabled,
+        Some(prefix) => WalArchiveConfig::Enabled {
+            prefix: prefix.clone(),
+            retention: Duration::from_secs(ingester_config.wal_archive_retention_hours * 60 * 60),
+        },
+    };
+
+    let replay_progress = Arc::new(WalReplayProgress::default());
+
+    // Initialise the ingester in the background, allowing the readiness
+    // endpoint to report the WAL replay progress while it is ongoing.
+    //
+    // Any initialisation error is returned when serving the gRPC services.
+    let init = tokio::spawn(ingester::new(
         catalog,
         Arc::clone(&metrics),
         PERSIST_BACKGROUND_FETCH_TIME,
         ingester_config.wal_directory.clone(),
         Duration::from_secs(ingester_config.wal_rotation_period_seconds),
+        wal_durability,
+        wal_keyring,
         exec,
         ingester_config.persist_max_parallelism,
         ingester_config.persist_queue_depth,
         ingester_config.persist_hot_partition_cost,
         object_store,
         gossip,
+        handoff,
+        spill,
+        wal_archive,
+        Arc::clone(&replay_progress),
+        ingester_config
+            .wal_replay_concurrency
+            .unwrap_or_else(|| std::thread::available_parallelism().unwrap_or(NonZeroUsize::MIN)),
         ingester_config
             .max_partitions_per_namespace
             .unwrap_or_else(|| NonZeroUsize::new(usize::MAX).unwrap()),
+        ingester_config.max_series_per_table,
         shutdown_rx.map(|v| v.expect("shutdown sender dropped without calling shutdown")),
-    )
-    .await?;
+    ));
 
     Ok(Arc::new(IngesterServerType::new(
-        grpc,
+        init,
+        replay_progress,
         metrics,
         common_state,
         ingester_config.concurrent_query_limit,
@@ -12,7 +12,7 @@
 )]
 
 use generated_types::influxdata::iox::{
-    catalog::v1::catalog_service_server::CatalogServiceServer,
+    catalog::v1::catalog_service_server::CatalogServiceServer, gossip::Topic,
     object_store::v1::object_store_service_server::ObjectStoreServiceServer,
     schema::v1::schema_service_server::SchemaServiceServer,
 };
@@ -24,8 +24,11 @@ use workspace_hack as _;
 
 use async_trait::async_trait;
 use authz::{Authorizer, IoxAuthorizer};
-use clap_blocks::querier::QuerierConfig;
+use clap_blocks::{gossip::GossipConfig, querier::QuerierConfig};
+use data_types::StorageTier;
 use datafusion_util::config::register_iox_object_store;
+use gossip::{NopDispatcher, TopicInterests};
+use gossip_query_stats::tx::QueryStatsTx;
 use hyper::{Body, Request, Response};
 use iox_catalog::interface::Catalog;
 use iox_query::exec::{Executor, ExecutorType};
@@ -40,7 +43,11 @@ use ioxd_common::{
 };
 use metric::Registry;
 use object_store::{DynObjectStore, ObjectStore};
-use querier::{create_ingester_connections, QuerierCatalogCache, QuerierDatabase, QuerierServer};
+use querier::{
+    create_ingester_connections, PartitionReadCosts, QuerierCatalogCache, QuerierDatabase,
+    QuerierServer,
+};
+use query_stats::{QueryStatsReporter, QUERY_STATS_INTERVAL};
 use std::{
     fmt::{Debug, Display},
     sync::Arc,
@@ -50,6 +57,7 @@ use tokio::runtime::Handle;
 use tokio_util::sync::CancellationToken;
 use trace::TraceCollector;
 
+mod query_stats;
 mod rpc;
 
 pub struct QuerierServerType {
@@ -60,6 +68,8 @@ pub struct QuerierServerType {
     object_store: Arc<dyn ObjectStore>,
     trace_collector: Option<Arc<dyn TraceCollector>>,
     authz: Option<Arc<dyn Authorizer>>,
+    /// Broadcasts the read costs of queries, if gossip is enabled.
+    _query_stats_reporter: Option<QueryStatsReporter>,
 }
 
 impl std::fmt::Debug for QuerierServerType {
@@ -177,9 +187,11 @@ pub struct QuerierServerTypeArgs<'a> {
     pub metric_registry: Arc<metric::Registry>,
     pub catalog: Arc<dyn Catalog>,
     pub object_store: Arc<DynObjectStore>,
+    pub cold_object_store: Option<Arc<DynObjectStore>>,
     pub exec: Arc<Executor>,
     pub time_provider: Arc<dyn TimeProvider>,
     pub querier_config: QuerierConfig,
+    pub gossip_config: &'a GossipConfig,
     pub trace_context_header_name: String,
 }
 
@@ -193,6 +205,9 @@ pub enum Error {
         source: Box<dyn std::error::Error>,
         addr: String,
     },
+
+    #[error("failed to bind udp gossip socket: {0}")]
+    GossipBind(std::io::Error),
 }
 
 /// Instantiate a querier server
@@ -204,24 +219,27 @@ pub async fn create_querier_server_type(
         args.time_provider,
         Arc::clone(&args.metric_registry),
         Arc::clone(&args.object_store),
+        args.cold_object_store.clone(),
         args.querier_config.ram_pool_metadata_bytes.bytes(),
         args.querier_config.ram_pool_data_bytes.bytes(),
         &Handle::current(),
     ));
 
-    // register cached object store with the execution context
-    let parquet_store = catalog_cache.parquet_store();
+    // register cached object stores of all tiers with the execution context
     let runtime_env = args
         .exec
         .new_context(ExecutorType::Query)
         .inner()
         .runtime_env();
-    let existing = register_iox_object_store(
-        runtime_env,
-        parquet_store.id(),
-        Arc::clone(parquet_store.object_store()),
-    );
-    assert!(existing.is_none());
+    for tier in StorageTier::all() {
+        let parquet_store = catalog_cache.parquet_store_for_tier(*tier);
+        let existing = register_iox_object_store(
+            Arc::clone(&runtime_env),
+            parquet_store.id(),
+            Arc::clone(parquet_store.object_store()),
+        );
+        assert!(existing.is_none());
+    }
 
     let authz = match &args.querier_config.authz_address {
         Some(addr) => {
@@ -256,6 +274,35 @@ pub async fn create_querier_server_type(
         ))
     };
 
+    // Optionally broadcast the read costs of the partitions scanned by
+    // queries, allowing the compactors to prioritise the partitions that are
+    // most expensive to query.
+    let (read_costs, query_stats_reporter) = match args.gossip_config.gossip_bind_address {
+        Some(bind_addr) => {
+            let handle = gossip::Builder::<_, Topic>::new(
+                args.gossip_config.seed_list.clone(),
+                NopDispatcher,
+                Arc::clone(&args.metric_registry),
+            )
+            // Configure the querier to subscribe to no topics - it currently
+            // only sends events.
+            .with_topic_filter(TopicInterests::default())
+            .bind(*bind_addr)
+            .await
+            .map_err(Error::GossipBind)?;
+
+            let read_costs = Arc::new(PartitionReadCosts::default());
+            let reporter = QueryStatsReporter::new(
+                Arc::clone(&read_costs),
+                QueryStatsTx::new(Arc::new(handle)),
+                QUERY_STATS_INTERVAL,
+            );
+
+            (Some(read_costs), Some(reporter))
+        }
+        None => (None, None),
+    };
+
     let database = Arc::new(
         QuerierDatabase::new(
             catalog_cache,
@@ -264,6 +311,7 @@ pub async fn create_querier_server_type(
             ingester_connections,
             args.querier_config.max_concurrent_queries,
             Arc::new(args.querier_config.datafusion_config),
+            read_costs,
         )
         .await?,
     );
@@ -277,5 +325,6 @@ pub async fn create_querier_server_type(
         object_store: args.object_store,
         trace_collector: args.common_state.trace_collector(),
         authz,
+        _query_stats_reporter: query_stats_reporter,
     }))
 }
@@ -0,0 +1,67 @@
+//! Periodic broadcast of the per-partition read costs observed by the querier.
+
+use std::{collections::HashMap, sync::Arc, time::Duration};
+
+use data_types::PartitionId;
+use generated_types::influxdata::iox::gossip::v1::{PartitionReadCost, QueryStats};
+use gossip_query_stats::tx::QueryStatsTx;
+use observability_deps::tracing::debug;
+use querier::PartitionReadCosts;
+use tokio::task::JoinHandle;
+
+/// The interval at which the accumulated read costs are broadcast.
+pub(crate) const QUERY_STATS_INTERVAL: Duration = Duration::from_secs(10);
+
+/// A background task draining the [`PartitionReadCosts`] at a fixed interval,
+/// and broadcasting them to the compactors over gossip.
+///
+/// Dropping the [`QueryStatsReporter`] stops the background task.
+#[derive(Debug)]
+pub(crate) struct QueryStatsReporter {
+    task: JoinHandle<()>,
+}
+
+impl QueryStatsReporter {
+    pub(crate) fn new(
+        read_costs: Arc<PartitionReadCosts>,
+        tx: QueryStatsTx,
+        interval: Duration,
+    ) -> Self {
+        let task = tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+
+                let costs = read_costs.take();
+                if costs.is_empty() {
+                    continue;
+                }
+
+                debug!(n_partitions = costs.len(), "broadcasting query stats");
+                tx.broadcast(to_proto(costs));
+            }
+        });
+
+        Self { task }
+    }
+}
+
+impl Drop for QueryStatsReporter {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+fn to_proto(costs: HashMap<PartitionId, querier::PartitionReadCost>) -> QueryStats {
+    QueryStats {
+        partitions: costs
+            .into_iter()
+            .map(|(partition_id, cost)| PartitionReadCost {
+                partition_id: partition_id.get(),
+                queries: cost.queries,
+                files_scanned: cost.files_scanned,
+                dedup_rows: cost.dedup_rows,
+            })
+            .collect(),
+    }
+}
@@ -39,6 +39,12 @@ fn namespace_to_proto(namespace: Namespace) -> proto::Namespace {
         max_tables: namespace.max_tables.get_i32(),
         max_columns_per_table: namespace.max_columns_per_table.get_i32(),
         partition_template: namespace.partition_template.as_proto().cloned(),
+        schema_mode: proto::SchemaMode::from(namespace.schema_mode).into(),
+        field_coercion: Some(namespace.field_coercion.into()),
+        write_rules: namespace.write_rules.as_proto().cloned(),
+        rollup_policies: namespace.rollup_policies.as_proto().cloned(),
+        cold_tier_age_ns: namespace.cold_tier_age_ns,
+        compaction_weight: namespace.compaction_weight,
     }
 }
 
@@ -95,6 +101,71 @@ impl proto::namespace_service_server::NamespaceService for NamespaceServiceImpl
             "use router instances to manage namespaces",
         ))
     }
+
+    async fn update_namespace_schema_mode(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceSchemaModeRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceSchemaModeResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_field_coercion(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceFieldCoercionRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceFieldCoercionResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_write_rules(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceWriteRulesRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceWriteRulesResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_partition_template(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespacePartitionTemplateRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespacePartitionTemplateResponse>, tonic::Status>
+    {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_rollup_policies(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceRollupPoliciesRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceRollupPoliciesResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_cold_tier_age(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceColdTierAgeRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceColdTierAgeResponse>, tonic::Status> {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
+
+    async fn update_namespace_compaction_weight(
+        &self,
+        _request: tonic::Request<proto::UpdateNamespaceCompactionWeightRequest>,
+    ) -> Result<tonic::Response<proto::UpdateNamespaceCompactionWeightResponse>, tonic::Status>
+    {
+        Err(tonic::Status::unimplemented(
+            "use router instances to manage namespaces",
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -130,6 +201,7 @@ mod tests {
                 Some(create_ingester_connection_for_testing()),
                 QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
                 Arc::new(HashMap::default()),
+                None,
             )
             .await
             .unwrap(),
@@ -163,6 +235,7 @@ mod tests {
                 Some(create_ingester_connection_for_testing()),
                 QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
                 Arc::new(HashMap::default()),
+                None,
             )
             .await
             .unwrap(),
@@ -184,6 +257,12 @@ mod tests {
                         max_tables: MaxTables::default().get_i32(),
                         max_columns_per_table: MaxColumnsPerTable::default().get_i32(),
                         partition_template: None,
+                        schema_mode: proto::SchemaMode::Implicit.into(),
+                        field_coercion: Some(Default::default()),
+                        write_rules: None,
+                        rollup_policies: None,
+                        cold_tier_age_ns: None,
+                        compaction_weight: 1,
                     },
                     proto::Namespace {
                         id: 2,
@@ -192,6 +271,12 @@ mod tests {
                         max_tables: MaxTables::default().get_i32(),
                         max_columns_per_table: MaxColumnsPerTable::default().get_i32(),
                         partition_template: None,
+                        schema_mode: proto::SchemaMode::Implicit.into(),
+                        field_coercion: Some(Default::default()),
+                        write_rules: None,
+                        rollup_policies: None,
+                        cold_tier_age_ns: None,
+                        compaction_weight: 1,
                     },
                 ]
             }
@@ -13,6 +13,7 @@
 #![allow(clippy::default_constructed_unit_structs)]
 
 use gossip::{Bytes, Identity, TopicInterests};
+use gossip_ingester_state::rx::{IngesterStateEventHandler, IngesterStateRx};
 use gossip_schema::{dispatcher::SchemaRx, handle::SchemaTx};
 use observability_deps::tracing::info;
 // Workaround for "unused crate" lint false positives.
@@ -26,7 +27,10 @@ use std::{
 
 use async_trait::async_trait;
 use authz::{Authorizer, AuthorizerInstrumentation, IoxAuthorizer};
-use clap_blocks::{gossip::GossipConfig, router::RouterConfig};
+use clap_blocks::{
+    gossip::GossipConfig,
+    router::{RouterConfig, RpcWriteBalancerType},
+};
 use data_types::NamespaceName;
 use hashbrown::HashMap;
 use hyper::{Body, Request, Response};
@@ -55,8 +59,10 @@ use mutable_batch::MutableBatch;
 use object_store::DynObjectStore;
 use router::{
     dml_handlers::{
-        lazy_connector::LazyConnector, DmlHandler, DmlHandlerChainExt, FanOutAdaptor,
-        InstrumentationDecorator, Partitioner, RetentionValidator, RpcWrite,
+        lazy_connector::LazyConnector,
+        spool::{SpoolError, WriteSpool},
+        BalancerStrategy, DmlHandler, DmlHandlerChainExt, FanOutAdaptor, InstrumentationDecorator,
+        Partitioner, RetentionValidator, RpcWrite, WriteRuleApplicator,
     },
     gossip::{
         anti_entropy::{
@@ -112,6 +118,10 @@ pub enum Error {
     /// An error binding the UDP socket for gossip communication.
     #[error("failed to bind udp gossip socket: {0}")]
     GossipBind(std::io::Error),
+
+    /// An error initialising the RPC write spool.
+    #[error("failed to initialise rpc write spool: {0}")]
+    WriteSpool(#[from] SpoolError),
 }
 
 pub type Result<T, E = Error> = std::result::Result<T, E>;
@@ -278,9 +288,30 @@ pub async fn create_router_server_type(
     let rpc_writer = RpcWrite::new(
         ingester_connections,
         router_config.rpc_write_replicas,
+        match router_config.rpc_write_balancer {
+            RpcWriteBalancerType::RoundRobin => BalancerStrategy::RoundRobin,
+            RpcWriteBalancerType::ConsistentHash => BalancerStrategy::ConsistentHash,
+        },
         &metrics,
         router_config.rpc_write_health_num_probes,
     );
+
+    // Optionally spool writes to disk when no ingester is available.
+    let rpc_writer = match &router_config.rpc_write_spool_directory {
+        Some(dir) => {
+            let spool = WriteSpool::new(
+                dir.clone(),
+                router_config.rpc_write_spool_max_bytes,
+                &metrics,
+            )
+            .await?;
+            rpc_writer.with_spool(Arc::new(spool))
+        }
+        None => rpc_writer,
+    };
+    // Obtain a handle to apply upstream ingester state changes (received over
+    // gossip) to the RPC writer, before it is wrapped in instrumentation.
+    let ingester_state_handler = rpc_writer.ingester_state_handler();
     let rpc_writer = InstrumentationDecorator::new("rpc_writer", &metrics, rpc_writer);
 
     // # Namespace cache
@@ -322,6 +353,7 @@ pub async fn create_router_server_type(
                 *bind_addr,
                 gossip_config.seed_list.clone(),
                 mst.clone(),
+                ingester_state_handler,
                 grpc_bind_port,
                 &metrics,
             )
@@ -347,6 +379,13 @@ pub async fn create_router_server_type(
     let schema_validator =
         InstrumentationDecorator::new("schema_validator", &metrics, schema_validator);
 
+    // # Write rules
+    //
+    // Apply the namespace write transformation rules (dropping, renaming and
+    // adding measurements, fields and tags) before the write is validated.
+    let write_rules = WriteRuleApplicator::new(&metrics);
+    let write_rules = InstrumentationDecorator::new("write_rules", &metrics, write_rules);
+
     // # Retention validator
     //
     // Add a retention validator into handler stack to reject data outside the retention period
@@ -391,7 +430,8 @@ pub async fn create_router_server_type(
     // # Handler stack
     //
     // Build the chain of DML handlers that forms the request processing pipeline
-    let handler_stack = retention_validator
+    let handler_stack = write_rules
+        .and_then(retention_validator)
         .and_then(schema_validator)
         .and_then(partitioner)
         // Once writes have been partitioned, they are processed in parallel.
@@ -531,16 +571,18 @@ where
 // drive catalog queries themselves (defeating the point of the gossiping!).
 // If a local node has to perform a catalog lookup, it gossips the result to
 // other peers, helping converge them.
-async fn init_gossip<T>(
+async fn init_gossip<T, I>(
     ns_cache: MerkleTree<T>,
     bind_addr: SocketAddr,
     seed_list: Vec<String>,
     mst: AntiEntropyHandle,
+    ingester_state_handler: I,
     local_rpc_port: u16,
     metrics: &Arc<metric::Registry>,
 ) -> Result<impl NamespaceCache<ReadError = CacheMissErr>, Error>
 where
     T: NamespaceCache<ReadError = CacheMissErr> + 'static,
+    I: IngesterStateEventHandler + 'static,
 {
     let ns_cache = Arc::new(ns_cache);
 
@@ -561,16 +603,27 @@ where
     // Initialise the consistency probe dispatcher and layer it in a demuxer to
     // route the topics to the correct dispatcher implementations.
     let (probe_dispatcher, probe_rx) = ProbeDispatcher::new();
-    let dispatcher = GossipDemuxer::new(probe_dispatcher, schema_dispatcher);
+
+    // Apply upstream ingester state changes (such as an ingester draining
+    // before shutdown) to the RPC write path.
+    let ingester_state_dispatcher = IngesterStateRx::new(ingester_state_handler, 100);
+
+    let dispatcher = GossipDemuxer::new(
+        probe_dispatcher,
+        schema_dispatcher,
+        ingester_state_dispatcher,
+    );
 
     // Initialise the gossip subsystem, delegating message processing to
     // the above dispatcher.
     let handle = gossip::Builder::<_, Topic>::new(seed_list, dispatcher, Arc::clone(metrics))
-        // Configure the router to listen to SchemaChange messages.
+        // Configure the router to listen to SchemaChange and IngesterState
+        // messages.
         .with_topic_filter(
             TopicInterests::default()
                 .with_topic(Topic::SchemaChanges)
-                .with_topic(Topic::SchemaCacheConsistency),
+                .with_topic(Topic::SchemaCacheConsistency)
+                .with_topic(Topic::IngesterState),
         )
         .bind(bind_addr)
         .await
@@ -618,13 +671,19 @@ where
 struct GossipDemuxer {
     consistency_probe: ProbeDispatcher,
     schema_update: SchemaRx,
+    ingester_state: IngesterStateRx,
 }
 
 impl GossipDemuxer {
-    fn new(consistency_probe: ProbeDispatcher, schema_update: SchemaRx) -> Self {
+    fn new(
+        consistency_probe: ProbeDispatcher,
+        schema_update: SchemaRx,
+        ingester_state: IngesterStateRx,
+    ) -> Self {
         Self {
             consistency_probe,
             schema_update,
+            ingester_state,
         }
     }
 }
@@ -639,6 +698,7 @@ impl gossip::Dispatcher<Topic> for GossipDemuxer {
                     .dispatch(topic, payload, sender)
                     .await
             }
+            Topic::IngesterState => self.ingester_state.dispatch(topic, payload, sender).await,
             _ => {}
         }
     }
@@ -10,7 +10,7 @@ use arrow::{
     error::ArrowError,
 };
 use arrow_util::{bitset::BitSet, string::PackedStringArray};
-use data_types::{StatValues, Statistics};
+use data_types::{IsNan, StatValues, Statistics};
 use schema::{InfluxColumnType, InfluxFieldType, TIME_DATA_TYPE};
 use snafu::{ResultExt, Snafu};
 use std::{fmt::Formatter, mem, sync::Arc};
@@ -45,6 +45,15 @@ pub enum Error {
 
     #[snafu(display("Internal MUB error constructing Arrow Array: {}", source))]
     CreatingArrowArray { source: ArrowError },
+
+    #[snafu(display("Cannot convert {} column to {}", from, to))]
+    UnsupportedCoercion {
+        from: InfluxColumnType,
+        to: InfluxColumnType,
+    },
+
+    #[snafu(display("Value {} cannot be represented as {}", value, to))]
+    CoercionOutOfRange { value: String, to: InfluxColumnType },
 }
 
 /// A specialized `Error` for [`Column`] errors
@@ -323,4 +332,112 @@ impl Column {
 
         Ok(data)
     }
+
+    /// Returns a copy of this field column with all values converted to `to`.
+    ///
+    /// The supported conversions are:
+    ///
+    ///   * [`InfluxFieldType::Integer`] to [`InfluxFieldType::Float`]
+    ///   * [`InfluxFieldType::UInteger`] to [`InfluxFieldType::Integer`], if
+    ///     all values fit in an i64
+    ///   * [`InfluxFieldType::Integer`], [`InfluxFieldType::UInteger`],
+    ///     [`InfluxFieldType::Float`] and [`InfluxFieldType::Boolean`] to
+    ///     [`InfluxFieldType::String`]
+    ///
+    /// NULL values remain NULL.
+    pub fn coerce(&self, to: InfluxFieldType) -> Result<Self> {
+        let valid = &self.valid;
+        let is_valid = |idx: usize| valid.get(idx);
+        let to_type = InfluxColumnType::Field(to);
+
+        let data = match (self.influx_type, &self.data, to) {
+            (
+                InfluxColumnType::Field(InfluxFieldType::Integer),
+                ColumnData::I64(v, _),
+                InfluxFieldType::Float,
+            ) => {
+                let data: Vec<_> = v.iter().map(|v| *v as f64).collect();
+                let stats = coerced_stats(data.iter().enumerate(), is_valid);
+                ColumnData::F64(data, stats)
+            }
+            (_, ColumnData::U64(v, _), InfluxFieldType::Integer) => {
+                let data = v
+                    .iter()
+                    .enumerate()
+                    .map(|(idx, v)| match i64::try_from(*v) {
+                        Ok(v) => Ok(v),
+                        // NULL rows contain an arbitrary placeholder value.
+                        Err(_) if !is_valid(idx) => Ok(0),
+                        Err(_) => Err(Error::CoercionOutOfRange {
+                            value: v.to_string(),
+                            to: to_type,
+                        }),
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                let stats = coerced_stats(data.iter().enumerate(), is_valid);
+                ColumnData::I64(data, stats)
+            }
+            (
+                InfluxColumnType::Field(
+                    InfluxFieldType::Integer
+                    | InfluxFieldType::UInteger
+                    | InfluxFieldType::Float
+                    | InfluxFieldType::Boolean,
+                ),
+                data,
+                InfluxFieldType::String,
+            ) => {
+                let value = |idx: usize| match data {
+                    ColumnData::I64(v, _) => v[idx].to_string(),
+                    ColumnData::U64(v, _) => v[idx].to_string(),
+                    ColumnData::F64(v, _) => v[idx].to_string(),
+                    ColumnData::Bool(v, _) => v.get(idx).to_string(),
+                    _ => unreachable!(),
+                };
+
+                let mut strings = PackedStringArray::new();
+                let mut stats = StatValues::new_empty();
+                for idx in 0..self.len() {
+                    if is_valid(idx) {
+                        let v = value(idx);
+                        strings.append(&v);
+                        stats.update(v.as_str());
+                    } else {
+                        strings.extend(1);
+                        stats.update_for_nulls(1);
+                    }
+                }
+                ColumnData::String(strings, stats)
+            }
+            (from, _, _) => {
+                return Err(Error::UnsupportedCoercion { from, to: to_type });
+            }
+        };
+
+        Ok(Self {
+            influx_type: to_type,
+            valid: self.valid.clone(),
+            data,
+        })
+    }
+}
+
+/// Compute the [`StatValues`] for the coerced `values`, using `is_valid` to
+/// identify NULL rows.
+fn coerced_stats<'a, T>(
+    values: impl Iterator<Item = (usize, &'a T)>,
+    is_valid: impl Fn(usize) -> bool,
+) -> StatValues<T>
+where
+    T: Clone + PartialOrd + IsNan + 'a,
+{
+    let mut stats = StatValues::new_empty();
+    for (idx, v) in values {
+        if is_valid(idx) {
+            stats.update(v);
+        } else {
+            stats.update_for_nulls(1);
+        }
+    }
+    stats
 }
@@ -24,13 +24,18 @@ use workspace_hack as _;
 
 use crate::column::{Column, ColumnData};
 use arrow::record_batch::RecordBatch;
+use arrow_util::{bitset::BitSet, dictionary::StringDictionary};
 use data_types::StatValues;
 use hashbrown::HashMap;
 use iox_time::Time;
 use schema::Projection;
-use schema::{builder::SchemaBuilder, Schema, TIME_COLUMN_NAME};
-use snafu::{OptionExt, ResultExt, Snafu};
-use std::{collections::BTreeSet, ops::Range};
+use schema::{builder::SchemaBuilder, InfluxColumnType, InfluxFieldType, Schema, TIME_COLUMN_NAME};
+use snafu::{ensure, OptionExt, ResultExt, Snafu};
+use std::{
+    collections::{hash_map::DefaultHasher, BTreeSet},
+    hash::{Hash, Hasher},
+    ops::Range,
+};
 
 pub mod column;
 pub mod payload;
@@ -56,6 +61,9 @@ pub enum Error {
     #[snafu(display("Column not found: {}", column))]
     ColumnNotFound { column: String },
 
+    #[snafu(display("Column already exists: {}", column))]
+    ColumnExists { column: String },
+
     #[snafu(context(false))]
     WriterError { source: writer::Error },
 }
@@ -205,6 +213,131 @@ impl MutableBatch {
         Ok(&self.columns[*idx])
     }
 
+    /// Convert all the values in the field column named `column` to `to`,
+    /// returning the number of non-NULL values converted.
+    ///
+    /// See [`Column::coerce`] for the supported conversions. The batch is
+    /// left unchanged if an error is returned.
+    pub fn coerce_column(&mut self, column: &str, to: InfluxFieldType) -> Result<usize> {
+        let idx = *self
+            .column_names
+            .get(column)
+            .context(ColumnNotFoundSnafu { column })?;
+
+        let col = &mut self.columns[idx];
+        *col = col.coerce(to).context(ColumnSnafu { column })?;
+
+        Ok((0..col.len()).filter(|&i| col.valid_mask().get(i)).count())
+    }
+
+    /// Remove the column named `column` from the batch, returning it.
+    pub fn drop_column(&mut self, column: &str) -> Result<Column> {
+        let idx = self
+            .column_names
+            .remove(column)
+            .context(ColumnNotFoundSnafu { column })?;
+
+        let col = self.columns.swap_remove(idx);
+
+        // The last column (if any) has been moved into the freed slot.
+        if idx < self.columns.len() {
+            let moved = self.columns.len();
+            for v in self.column_names.values_mut() {
+                if *v == moved {
+                    *v = idx;
+                    break;
+                }
+            }
+        }
+
+        Ok(col)
+    }
+
+    /// Rename the column named `from` to `to`.
+    ///
+    /// Returns an error if `from` does not exist, or a column named `to`
+    /// already exists.
+    pub fn rename_column(&mut self, from: &str, to: &str) -> Result<()> {
+        ensure!(
+            !self.column_names.contains_key(to),
+            ColumnExistsSnafu { column: to }
+        );
+
+        let idx = self
+            .column_names
+            .remove(from)
+            .context(ColumnNotFoundSnafu { column: from })?;
+        self.column_names.insert(to.to_string(), idx);
+
+        Ok(())
+    }
+
+    /// Add a tag column named `name` with `value` set for every row in the
+    /// batch.
+    ///
+    /// Returns an error if a column named `name` already exists.
+    pub fn add_tag(&mut self, name: &str, value: &str) -> Result<()> {
+        ensure!(
+            !self.column_names.contains_key(name),
+            ColumnExistsSnafu { column: name }
+        );
+
+        let mut col = Column::new(self.row_count, InfluxColumnType::Tag);
+        if self.row_count > 0 {
+            let mut dictionary = StringDictionary::new();
+            let id = dictionary.lookup_value_or_insert(value);
+
+            col.valid = BitSet::new();
+            col.valid.append_set(self.row_count);
+            col.data = ColumnData::Tag(
+                vec![id; self.row_count],
+                dictionary,
+                StatValues::new_non_null(
+                    Some(value.to_string()),
+                    Some(value.to_string()),
+                    self.row_count as u64,
+                ),
+            );
+        }
+
+        self.column_names
+            .insert(name.to_string(), self.columns.len());
+        self.columns.push(col);
+
+        Ok(())
+    }
+
+    /// Return a hash of the series (the set of tag key/value pairs) for each
+    /// row in the batch.
+    ///
+    /// Rows with identical tag sets produce identical hashes, irrespective of
+    /// the order the tag columns appear in the batch. Null tag values are not
+    /// part of the series.
+    pub fn series_hashes(&self) -> Vec<u64> {
+        let mut tags = self
+            .columns()
+            .filter_map(|(name, col)| match &col.data {
+                ColumnData::Tag(ids, dictionary, _) => Some((name, &col.valid, ids, dictionary)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        tags.sort_unstable_by(|a, b| a.0.cmp(b.0));
+
+        (0..self.row_count)
+            .map(|row| {
+                let mut hasher = DefaultHasher::new();
+                for (name, valid, ids, dictionary) in &tags {
+                    if !valid.get(row) {
+                        continue;
+                    }
+                    name.hash(&mut hasher);
+                    dictionary.lookup_id(ids[row]).hash(&mut hasher);
+                }
+                hasher.finish()
+            })
+            .collect()
+    }
+
     /// Return the approximate memory size of the batch, in bytes.
     ///
     /// This includes `Self`.
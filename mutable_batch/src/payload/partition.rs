@@ -10,9 +10,10 @@ mod strftime;
 use std::{borrow::Cow, ops::Range};
 
 use data_types::partition_template::{
-    TablePartitionTemplateOverride, TemplatePart, ENCODED_PARTITION_KEY_CHARS,
-    MAXIMUM_NUMBER_OF_TEMPLATE_PARTS, PARTITION_KEY_DELIMITER, PARTITION_KEY_MAX_PART_LEN,
-    PARTITION_KEY_PART_TRUNCATED, PARTITION_KEY_VALUE_EMPTY_STR, PARTITION_KEY_VALUE_NULL_STR,
+    bucket_for_tag_value, TablePartitionTemplateOverride, TemplatePart,
+    ENCODED_PARTITION_KEY_CHARS, MAXIMUM_NUMBER_OF_TEMPLATE_PARTS, PARTITION_KEY_DELIMITER,
+    PARTITION_KEY_MAX_PART_LEN, PARTITION_KEY_PART_TRUNCATED, PARTITION_KEY_VALUE_EMPTY_STR,
+    PARTITION_KEY_VALUE_NULL_STR,
 };
 use percent_encoding::utf8_percent_encode;
 use schema::{InfluxColumnType, TIME_COLUMN_NAME};
@@ -40,6 +41,11 @@ pub enum PartitionKeyError {
     #[error("tag value partitioner does not accept input columns of type {0:?}")]
     TagValueNotTag(InfluxColumnType),
 
+    /// The partition template defines a [`Template::Bucket`] part, but the
+    /// column type is not "tag".
+    #[error("bucket partitioner does not accept input columns of type {0:?}")]
+    BucketNotTag(InfluxColumnType),
+
     /// A "catch all" error for when a formatter returns [`std::fmt::Error`],
     /// which contains no context.
     #[error("partition key generation error")]
@@ -77,6 +83,9 @@ pub fn partition_batch<'a>(
 enum Template<'a> {
     TagValue(&'a Column, Option<i32>),
     TimeFormat(&'a [i64], StrftimeFormatter<'a>),
+    /// A tag column, the number of buckets its values are hashed into, and
+    /// the dictionary key of the last rendered value.
+    Bucket(&'a Column, u32, Option<i32>),
 
     /// This batch is missing a partitioning tag column.
     MissingTag,
@@ -105,6 +114,16 @@ impl<'a> Template<'a> {
                 _ => return Err(PartitionKeyError::TagValueNotTag(col.influx_type())),
             },
             Template::TimeFormat(t, fmt) => fmt.render(t[idx], out)?,
+            Template::Bucket(col, num_buckets, last_key) if col.valid.get(idx) => match &col.data {
+                ColumnData::Tag(col_data, dictionary, _) => {
+                    let this_key = col_data[idx];
+                    *last_key = Some(this_key);
+
+                    let value = dictionary.lookup_id(this_key).unwrap();
+                    write!(out, "{}", bucket_for_tag_value(value, *num_buckets))?
+                }
+                _ => return Err(PartitionKeyError::BucketNotTag(col.influx_type())),
+            },
             // Either a tag that has no value for this given row index, or the
             // batch does not contain this tag at all.
             Template::TagValue(_, last_key) => {
@@ -113,6 +132,12 @@ impl<'a> Template<'a> {
                 *last_key = None;
                 out.write_str(PARTITION_KEY_VALUE_NULL_STR)?
             }
+            // A bucketed tag that has no value for this row renders the NULL
+            // key part, in the same way as a missing tag value.
+            Template::Bucket(_, _, last_key) => {
+                *last_key = None;
+                out.write_str(PARTITION_KEY_VALUE_NULL_STR)?
+            }
             Template::MissingTag => out.write_str(PARTITION_KEY_VALUE_NULL_STR)?,
         }
 
@@ -146,6 +171,15 @@ impl<'a> Template<'a> {
             // it differs).
             Template::TagValue(_, Some(_)) => false,
 
+            // Identical dictionary keys always hash to the same bucket.
+            Template::Bucket(col, _, last_key) if col.valid.get(idx) => match &col.data {
+                ColumnData::Tag(col_data, _, _) => {
+                    last_key.map(|v| v == col_data[idx]).unwrap_or_default()
+                }
+                _ => false,
+            },
+            Template::Bucket(_, _, last_key) => last_key.is_none(),
+
             // The batch does not contain this tag at all - it always matches
             // with the previous row.
             Template::MissingTag => true,
@@ -223,6 +257,10 @@ fn partition_keys<'a>(
             TemplatePart::TimeFormat(fmt) => {
                 Template::TimeFormat(time, StrftimeFormatter::new(fmt))
             }
+            TemplatePart::Bucket(col_name, num_buckets) => batch.column(col_name).map_or_else(
+                |_| Template::MissingTag,
+                |v| Template::Bucket(v, num_buckets, None),
+            ),
         })
         .collect::<Vec<_>>();
 
@@ -527,6 +565,74 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_partition_bucket() {
+        let mut batch = MutableBatch::new();
+        let mut writer = Writer::new(&mut batch, 5);
+
+        writer
+            .write_time("time", vec![1, 2, 3, 4, 5].into_iter())
+            .unwrap();
+
+        writer
+            .write_tag(
+                "host",
+                Some(&[0b00011011]),
+                vec!["bananas", "platanos", "bananas", "platanos"].into_iter(),
+            )
+            .unwrap();
+
+        let template_parts = [
+            TemplatePart::TimeFormat("%Y"),
+            TemplatePart::Bucket("host", 10),
+            TemplatePart::Bucket("bananas", 10), // column not present
+        ];
+
+        writer.commit();
+
+        let keys: Vec<_> = partition_keys(&batch, template_parts.into_iter()).collect::<Vec<_>>();
+
+        assert_eq!(
+            keys,
+            vec![
+                Some(Ok("1970|9|!".to_string())),
+                Some(Ok("1970|0|!".to_string())),
+                Some(Ok("1970|!|!".to_string())),
+                Some(Ok("1970|9|!".to_string())),
+                Some(Ok("1970|0|!".to_string())),
+            ]
+        );
+
+        // The bucket number is the same as computed by the hash function.
+        assert_eq!(bucket_for_tag_value("bananas", 10), 9);
+        assert_eq!(bucket_for_tag_value("platanos", 10), 0);
+    }
+
+    #[test]
+    fn bucket_partitioning_on_fields_errors() {
+        let mut batch = MutableBatch::new();
+        let mut writer = Writer::new(&mut batch, 5);
+
+        writer
+            .write_time("time", vec![1, 2, 3, 4, 5].into_iter())
+            .unwrap();
+
+        writer
+            .write_string(
+                "host",
+                Some(&[0b00001010]),
+                vec!["west", "east"].into_iter(),
+            )
+            .unwrap();
+
+        let template_parts = [TemplatePart::Bucket("host", 10)];
+
+        writer.commit();
+
+        let got: Result<Vec<_>, _> = generate_denormalised_keys(&batch, template_parts.into_iter());
+        assert_matches::assert_matches!(got, Err(PartitionKeyError::BucketNotTag(_)));
+    }
+
     #[test]
     fn test_sparse_representation() {
         let mut batch = MutableBatch::new();
@@ -1231,6 +1337,9 @@ mod tests {
                             _ => panic!("expected datatime column value but got: {:?}", got_val)
                         }
                     },
+                    ColumnValue::Bucket{..} => {
+                        panic!("no bucket parts in test template, got: {:?}", got_val)
+                    },
                 };
             }
         }
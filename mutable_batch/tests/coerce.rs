@@ -0,0 +1,192 @@
+use arrow_util::assert_batches_eq;
+use data_types::{StatValues, Statistics};
+use mutable_batch::{column, writer::Writer, MutableBatch};
+use schema::{InfluxColumnType, InfluxFieldType, Projection};
+
+fn test_batch() -> MutableBatch {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 4);
+
+    writer
+        .write_i64("i64", Some(&[0b00001011]), vec![1, -2, 3].into_iter())
+        .unwrap();
+
+    writer
+        .write_u64("u64", Some(&[0b00001101]), vec![4, 5, 6].into_iter())
+        .unwrap();
+
+    writer
+        .write_f64("f64", None, vec![1.5, 2.0, -3.25, 4.0].into_iter())
+        .unwrap();
+
+    writer
+        .write_bool("bool", Some(&[0b00000110]), vec![true, false].into_iter())
+        .unwrap();
+
+    writer
+        .write_time("time", vec![0, 1, 2, 3].into_iter())
+        .unwrap();
+
+    writer.commit();
+    batch
+}
+
+#[test]
+fn test_coerce_numeric() {
+    let mut batch = test_batch();
+
+    assert_eq!(
+        batch.coerce_column("i64", InfluxFieldType::Float).unwrap(),
+        3
+    );
+    assert_eq!(
+        batch
+            .coerce_column("u64", InfluxFieldType::Integer)
+            .unwrap(),
+        3
+    );
+
+    let col = batch.column("i64").unwrap();
+    assert_eq!(
+        col.influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::Float)
+    );
+    assert_eq!(
+        col.stats(),
+        Statistics::F64(StatValues {
+            min: Some(-2.0),
+            max: Some(3.0),
+            total_count: 4,
+            null_count: Some(1),
+            distinct_count: None
+        })
+    );
+
+    let col = batch.column("u64").unwrap();
+    assert_eq!(
+        col.influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::Integer)
+    );
+    assert_eq!(
+        col.stats(),
+        Statistics::I64(StatValues {
+            min: Some(4),
+            max: Some(6),
+            total_count: 4,
+            null_count: Some(1),
+            distinct_count: None
+        })
+    );
+
+    assert_batches_eq!(
+        &[
+            "+-------+-------+------+--------------------------------+-----+",
+            "| bool  | f64   | i64  | time                           | u64 |",
+            "+-------+-------+------+--------------------------------+-----+",
+            "|       | 1.5   | 1.0  | 1970-01-01T00:00:00Z           | 4   |",
+            "| true  | 2.0   | -2.0 | 1970-01-01T00:00:00.000000001Z |     |",
+            "| false | -3.25 |      | 1970-01-01T00:00:00.000000002Z | 5   |",
+            "|       | 4.0   | 3.0  | 1970-01-01T00:00:00.000000003Z | 6   |",
+            "+-------+-------+------+--------------------------------+-----+",
+        ],
+        &[batch.to_arrow(Projection::All).unwrap()]
+    );
+}
+
+#[test]
+fn test_coerce_to_string() {
+    let mut batch = test_batch();
+
+    for name in ["i64", "u64", "f64", "bool"] {
+        batch.coerce_column(name, InfluxFieldType::String).unwrap();
+        assert_eq!(
+            batch.column(name).unwrap().influx_type(),
+            InfluxColumnType::Field(InfluxFieldType::String)
+        );
+    }
+
+    assert_eq!(
+        batch.column("bool").unwrap().stats(),
+        Statistics::String(StatValues {
+            min: Some("false".to_string()),
+            max: Some("true".to_string()),
+            total_count: 4,
+            null_count: Some(2),
+            distinct_count: None
+        })
+    );
+
+    assert_batches_eq!(
+        &[
+            "+-------+-------+-----+--------------------------------+-----+",
+            "| bool  | f64   | i64 | time                           | u64 |",
+            "+-------+-------+-----+--------------------------------+-----+",
+            "|       | 1.5   | 1   | 1970-01-01T00:00:00Z           | 4   |",
+            "| true  | 2     | -2  | 1970-01-01T00:00:00.000000001Z |     |",
+            "| false | -3.25 |     | 1970-01-01T00:00:00.000000002Z | 5   |",
+            "|       | 4     | 3   | 1970-01-01T00:00:00.000000003Z | 6   |",
+            "+-------+-------+-----+--------------------------------+-----+",
+        ],
+        &[batch.to_arrow(Projection::All).unwrap()]
+    );
+}
+
+#[test]
+fn test_coerce_out_of_range() {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 2);
+    writer
+        .write_u64("u64", None, vec![1, u64::MAX].into_iter())
+        .unwrap();
+    writer.write_time("time", vec![0, 1].into_iter()).unwrap();
+    writer.commit();
+
+    let err = batch
+        .coerce_column("u64", InfluxFieldType::Integer)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        mutable_batch::Error::ColumnError {
+            source: column::Error::CoercionOutOfRange { .. },
+            ..
+        }
+    ));
+
+    // The batch is unchanged.
+    assert_eq!(
+        batch.column("u64").unwrap().influx_type(),
+        InfluxColumnType::Field(InfluxFieldType::UInteger)
+    );
+}
+
+#[test]
+fn test_coerce_unsupported() {
+    let mut batch = test_batch();
+
+    let err = batch
+        .coerce_column("f64", InfluxFieldType::Integer)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        mutable_batch::Error::ColumnError {
+            source: column::Error::UnsupportedCoercion { .. },
+            ..
+        }
+    ));
+
+    let err = batch
+        .coerce_column("time", InfluxFieldType::String)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        mutable_batch::Error::ColumnError {
+            source: column::Error::UnsupportedCoercion { .. },
+            ..
+        }
+    ));
+
+    let err = batch
+        .coerce_column("missing", InfluxFieldType::Float)
+        .unwrap_err();
+    assert!(matches!(err, mutable_batch::Error::ColumnNotFound { .. }));
+}
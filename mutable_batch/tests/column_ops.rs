@@ -0,0 +1,142 @@
+use arrow_util::assert_batches_eq;
+use data_types::{StatValues, Statistics};
+use mutable_batch::{writer::Writer, MutableBatch};
+use schema::{InfluxColumnType, Projection};
+
+fn test_batch() -> MutableBatch {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 3);
+
+    writer
+        .write_tag("host", None, vec!["a", "b", "c"].into_iter())
+        .unwrap();
+
+    writer
+        .write_f64("f64", None, vec![1.0, 2.0, 3.0].into_iter())
+        .unwrap();
+
+    writer
+        .write_i64("i64", None, vec![4, 5, 6].into_iter())
+        .unwrap();
+
+    writer
+        .write_time("time", vec![0, 1, 2].into_iter())
+        .unwrap();
+
+    writer.commit();
+    batch
+}
+
+#[test]
+fn test_drop_column() {
+    let mut batch = test_batch();
+
+    let col = batch.drop_column("host").unwrap();
+    assert_eq!(col.influx_type(), InfluxColumnType::Tag);
+
+    assert!(matches!(
+        batch.drop_column("host"),
+        Err(mutable_batch::Error::ColumnNotFound { .. })
+    ));
+
+    // All the remaining columns must still resolve to the right data after
+    // the internal reindexing.
+    assert_batches_eq!(
+        &[
+            "+-----+-----+--------------------------------+",
+            "| f64 | i64 | time                           |",
+            "+-----+-----+--------------------------------+",
+            "| 1.0 | 4   | 1970-01-01T00:00:00Z           |",
+            "| 2.0 | 5   | 1970-01-01T00:00:00.000000001Z |",
+            "| 3.0 | 6   | 1970-01-01T00:00:00.000000002Z |",
+            "+-----+-----+--------------------------------+",
+        ],
+        &[batch.to_arrow(Projection::All).unwrap()]
+    );
+
+    batch.drop_column("time").unwrap();
+    batch.drop_column("f64").unwrap();
+    assert_eq!(
+        batch.column_names().into_iter().collect::<Vec<_>>(),
+        ["i64"]
+    );
+    assert_eq!(
+        batch.column("i64").unwrap().influx_type(),
+        InfluxColumnType::Field(schema::InfluxFieldType::Integer)
+    );
+}
+
+#[test]
+fn test_rename_column() {
+    let mut batch = test_batch();
+
+    batch.rename_column("host", "hostname").unwrap();
+
+    assert!(matches!(
+        batch.rename_column("host", "bananas"),
+        Err(mutable_batch::Error::ColumnNotFound { .. })
+    ));
+    assert!(matches!(
+        batch.rename_column("hostname", "f64"),
+        Err(mutable_batch::Error::ColumnExists { .. })
+    ));
+
+    assert_batches_eq!(
+        &[
+            "+-----+----------+-----+--------------------------------+",
+            "| f64 | hostname | i64 | time                           |",
+            "+-----+----------+-----+--------------------------------+",
+            "| 1.0 | a        | 4   | 1970-01-01T00:00:00Z           |",
+            "| 2.0 | b        | 5   | 1970-01-01T00:00:00.000000001Z |",
+            "| 3.0 | c        | 6   | 1970-01-01T00:00:00.000000002Z |",
+            "+-----+----------+-----+--------------------------------+",
+        ],
+        &[batch.to_arrow(Projection::All).unwrap()]
+    );
+}
+
+#[test]
+fn test_add_tag() {
+    let mut batch = test_batch();
+
+    batch.add_tag("region", "eu").unwrap();
+
+    assert!(matches!(
+        batch.add_tag("host", "bananas"),
+        Err(mutable_batch::Error::ColumnExists { .. })
+    ));
+
+    let col = batch.column("region").unwrap();
+    assert_eq!(col.influx_type(), InfluxColumnType::Tag);
+    assert_eq!(
+        col.stats(),
+        Statistics::String(StatValues {
+            min: Some("eu".to_string()),
+            max: Some("eu".to_string()),
+            total_count: 3,
+            null_count: Some(0),
+            distinct_count: std::num::NonZeroU64::new(1),
+        })
+    );
+
+    assert_batches_eq!(
+        &[
+            "+-----+------+-----+--------+--------------------------------+",
+            "| f64 | host | i64 | region | time                           |",
+            "+-----+------+-----+--------+--------------------------------+",
+            "| 1.0 | a    | 4   | eu     | 1970-01-01T00:00:00Z           |",
+            "| 2.0 | b    | 5   | eu     | 1970-01-01T00:00:00.000000001Z |",
+            "| 3.0 | c    | 6   | eu     | 1970-01-01T00:00:00.000000002Z |",
+            "+-----+------+-----+--------+--------------------------------+",
+        ],
+        &[batch.to_arrow(Projection::All).unwrap()]
+    );
+
+    // Extending a batch with the added tag works as normal.
+    let mut other = test_batch();
+    other.add_tag("region", "us").unwrap();
+    batch.extend_from(&other).unwrap();
+    assert_eq!(batch.rows(), 6);
+    assert_eq!(batch.column("region").unwrap().valid_mask().len(), 6);
+    assert!(batch.column("region").unwrap().valid_mask().is_all_set());
+}
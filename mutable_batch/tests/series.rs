@@ -0,0 +1,75 @@
+use mutable_batch::{writer::Writer, MutableBatch};
+
+fn test_batch() -> MutableBatch {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 4);
+
+    writer
+        .write_tag("host", None, vec!["a", "b", "a", "a"].into_iter())
+        .unwrap();
+
+    writer
+        .write_tag(
+            "region",
+            Some(&[0b00001011]),
+            vec!["east", "east", "west"].into_iter(),
+        )
+        .unwrap();
+
+    writer
+        .write_f64("f64", None, vec![1.0, 2.0, 3.0, 4.0].into_iter())
+        .unwrap();
+
+    writer
+        .write_time("time", vec![0, 1, 2, 3].into_iter())
+        .unwrap();
+
+    writer.commit();
+    batch
+}
+
+#[test]
+fn test_series_hashes() {
+    let batch = test_batch();
+    let hashes = batch.series_hashes();
+    assert_eq!(hashes.len(), batch.rows());
+
+    // host=a,region=east
+    // host=b,region=east
+    // host=a
+    // host=a,region=west
+    assert_ne!(hashes[0], hashes[1]);
+    assert_ne!(hashes[0], hashes[2]);
+    assert_ne!(hashes[0], hashes[3]);
+    assert_ne!(hashes[2], hashes[3]);
+
+    // Fields and timestamps are not part of the series.
+    let mut other = MutableBatch::new();
+    let mut writer = Writer::new(&mut other, 1);
+    writer
+        .write_tag("region", None, vec!["east"].into_iter())
+        .unwrap();
+    writer
+        .write_tag("host", None, vec!["a"].into_iter())
+        .unwrap();
+    writer.write_i64("i64", None, vec![42].into_iter()).unwrap();
+    writer.write_time("time", vec![42].into_iter()).unwrap();
+    writer.commit();
+
+    assert_eq!(other.series_hashes(), vec![hashes[0]]);
+}
+
+#[test]
+fn test_series_hashes_no_tags() {
+    let mut batch = MutableBatch::new();
+    let mut writer = Writer::new(&mut batch, 2);
+    writer
+        .write_f64("f64", None, vec![1.0, 2.0].into_iter())
+        .unwrap();
+    writer.write_time("time", vec![0, 1].into_iter()).unwrap();
+    writer.commit();
+
+    let hashes = batch.series_hashes();
+    assert_eq!(hashes.len(), 2);
+    assert_eq!(hashes[0], hashes[1]);
+}
@@ -622,6 +622,7 @@ mod tests {
                 ),
             ],
             row_count: 5,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -761,6 +762,7 @@ mod tests {
                 ),
             ],
             row_count: 6,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -794,6 +796,7 @@ mod tests {
                     other,
                 ],
                 row_count: 6,
+                partition_template: None,
                 table_id: 42,
             };
 
@@ -901,6 +904,7 @@ mod tests {
                 ),
             ],
             row_count: 10,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -938,6 +942,7 @@ mod tests {
                 vec![],
             )],
             row_count: 9,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -1040,6 +1045,7 @@ mod tests {
                 with_i64(column("time", SemanticType::Time), vec![1, 2, 3], vec![]),
             ],
             row_count: 9,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -1069,6 +1075,7 @@ mod tests {
         let table_batch = TableBatch {
             columns: vec![with_i64(column("time", SemanticType::Time), vec![], vec![])],
             row_count: 9,
+            partition_template: None,
             table_id: 42,
         };
 
@@ -44,6 +44,7 @@ pub fn encode_batch(table_id: i64, batch: &MutableBatch) -> TableBatch {
             })
             .collect(),
         row_count: batch.rows() as u32,
+        partition_template: None,
         table_id,
     }
 }
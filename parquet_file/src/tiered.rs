@@ -0,0 +1,144 @@
+//! Read access to parquet files that may live in either storage tier.
+//!
+//! The garbage collector relocates parquet files older than the cold tier age
+//! of their namespace from the hot object store to a cold one (see
+//! [`StorageTier`]). Components that address files by path only, like the
+//! compactor, use [`ColdTierFallbackStore`] to keep reading relocated files.
+//!
+//! [`StorageTier`]: data_types::StorageTier
+
+use std::{ops::Range, sync::Arc};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures::stream::BoxStream;
+use object_store::{
+    path::Path, DynObjectStore, Error, GetOptions, GetResult, ListResult, MultipartId, ObjectMeta,
+    ObjectStore, Result,
+};
+use tokio::io::AsyncWrite;
+
+/// An [`ObjectStore`] that reads from a cold store any object that is not
+/// found in the hot store.
+///
+/// All writes, deletes and listings only touch the hot store.
+#[derive(Debug)]
+pub struct ColdTierFallbackStore {
+    hot: Arc<DynObjectStore>,
+    cold: Arc<DynObjectStore>,
+}
+
+impl ColdTierFallbackStore {
+    /// Read objects from `hot`, falling back to `cold`.
+    pub fn new(hot: Arc<DynObjectStore>, cold: Arc<DynObjectStore>) -> Self {
+        Self { hot, cold }
+    }
+}
+
+impl std::fmt::Display for ColdTierFallbackStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ColdTierFallbackStore({}, {})", self.hot, self.cold)
+    }
+}
+
+#[async_trait]
+impl ObjectStore for ColdTierFallbackStore {
+    async fn put(&self, location: &Path, bytes: Bytes) -> Result<()> {
+        self.hot.put(location, bytes).await
+    }
+
+    async fn put_multipart(
+        &self,
+        location: &Path,
+    ) -> Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)> {
+        self.hot.put_multipart(location).await
+    }
+
+    async fn abort_multipart(&self, location: &Path, multipart_id: &MultipartId) -> Result<()> {
+        self.hot.abort_multipart(location, multipart_id).await
+    }
+
+    async fn get_opts(&self, location: &Path, options: GetOptions) -> Result<GetResult> {
+        match self.hot.get_opts(location, options.clone()).await {
+            Err(Error::NotFound { .. }) => self.cold.get_opts(location, options).await,
+            res => res,
+        }
+    }
+
+    async fn get_range(&self, location: &Path, range: Range<usize>) -> Result<Bytes> {
+        match self.hot.get_range(location, range.clone()).await {
+            Err(Error::NotFound { .. }) => self.cold.get_range(location, range).await,
+            res => res,
+        }
+    }
+
+    async fn head(&self, location: &Path) -> Result<ObjectMeta> {
+        match self.hot.head(location).await {
+            Err(Error::NotFound { .. }) => self.cold.head(location).await,
+            res => res,
+        }
+    }
+
+    async fn delete(&self, location: &Path) -> Result<()> {
+        self.hot.delete(location).await
+    }
+
+    async fn list(&self, prefix: Option<&Path>) -> Result<BoxStream<'_, Result<ObjectMeta>>> {
+        self.hot.list(prefix).await
+    }
+
+    async fn list_with_delimiter(&self, prefix: Option<&Path>) -> Result<ListResult> {
+        self.hot.list_with_delimiter(prefix).await
+    }
+
+    async fn copy(&self, from: &Path, to: &Path) -> Result<()> {
+        self.hot.copy(from, to).await
+    }
+
+    async fn copy_if_not_exists(&self, from: &Path, to: &Path) -> Result<()> {
+        self.hot.copy_if_not_exists(from, to).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use object_store::memory::InMemory;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reads_fall_back_to_cold() {
+        let hot = Arc::new(InMemory::new());
+        let cold = Arc::new(InMemory::new());
+        let store = ColdTierFallbackStore::new(Arc::clone(&hot) as _, Arc::clone(&cold) as _);
+
+        let path_hot = Path::from("hot");
+        let path_cold = Path::from("cold");
+        hot.put(&path_hot, Bytes::from_static(b"hot_data"))
+            .await
+            .unwrap();
+        cold.put(&path_cold, Bytes::from_static(b"cold_data"))
+            .await
+            .unwrap();
+
+        let got = store.get(&path_hot).await.unwrap().bytes().await.unwrap();
+        assert_eq!(got, Bytes::from_static(b"hot_data"));
+        let got = store.get(&path_cold).await.unwrap().bytes().await.unwrap();
+        assert_eq!(got, Bytes::from_static(b"cold_data"));
+        let got = store.get_range(&path_cold, 0..4).await.unwrap();
+        assert_eq!(got, Bytes::from_static(b"cold"));
+        assert_eq!(store.head(&path_cold).await.unwrap().size, 9);
+
+        let err = store.head(&Path::from("missing")).await.unwrap_err();
+        assert!(matches!(err, Error::NotFound { .. }), "{err}");
+
+        // writes only go to the hot store
+        let path_new = Path::from("new");
+        store
+            .put(&path_new, Bytes::from_static(b"new_data"))
+            .await
+            .unwrap();
+        hot.head(&path_new).await.unwrap();
+        assert!(cold.head(&path_new).await.is_err());
+    }
+}
@@ -3,6 +3,7 @@ use ::object_store::ObjectStore;
 use ::parquet_file::storage::{ParquetStorage, StorageId};
 use backoff::BackoffConfig;
 use cache_system::backend::policy::lru::ResourcePool;
+use data_types::StorageTier;
 use iox_catalog::interface::Catalog;
 use iox_time::TimeProvider;
 use std::sync::Arc;
@@ -53,11 +54,15 @@ pub struct CatalogCache {
 
 impl CatalogCache {
     /// Create empty cache.
+    ///
+    /// Files in the cold [`StorageTier`] are read from `cold_object_store`, if configured.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         catalog: Arc<dyn Catalog>,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: Arc<metric::Registry>,
         object_store: Arc<dyn ObjectStore>,
+        cold_object_store: Option<Arc<dyn ObjectStore>>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
         handle: &Handle,
@@ -67,6 +72,7 @@ impl CatalogCache {
             time_provider,
             metric_registry,
             object_store,
+            cold_object_store,
             ram_pool_metadata_bytes,
             ram_pool_data_bytes,
             handle,
@@ -89,6 +95,7 @@ impl CatalogCache {
             time_provider,
             metric_registry,
             object_store,
+            None,
             usize::MAX,
             usize::MAX,
             handle,
@@ -102,6 +109,7 @@ impl CatalogCache {
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: Arc<metric::Registry>,
         object_store: Arc<dyn ObjectStore>,
+        cold_object_store: Option<Arc<dyn ObjectStore>>,
         ram_pool_metadata_bytes: usize,
         ram_pool_data_bytes: usize,
         handle: &Handle,
@@ -156,6 +164,7 @@ impl CatalogCache {
         let object_store_cache = ObjectStoreCache::new(
             backoff_config,
             object_store,
+            cold_object_store,
             Arc::clone(&time_provider),
             &metric_registry,
             Arc::clone(&ram_pool_data),
@@ -212,9 +221,21 @@ impl CatalogCache {
 
     /// Parquet store that points to the cached object store.
     pub fn parquet_store(&self) -> ParquetStorage {
+        self.parquet_store_for_tier(StorageTier::Hot)
+    }
+
+    /// Parquet store that points to the cached object store of the given [`StorageTier`].
+    ///
+    /// Each tier uses its own [`StorageId`], so all of them must be registered with the query runtime.
+    pub fn parquet_store_for_tier(&self, tier: StorageTier) -> ParquetStorage {
+        let id = match tier {
+            StorageTier::Hot => "iox_cached",
+            StorageTier::Cold => "iox_cached_cold",
+        };
+
         ParquetStorage::new(
-            Arc::clone(self.object_store_cache.object_store()),
-            StorageId::from("iox_cached"),
+            Arc::clone(self.object_store_cache.object_store(tier)),
+            StorageId::from(id),
         )
     }
 }
@@ -15,7 +15,7 @@ use cache_system::{
 };
 use data_types::{
     partition_template::TablePartitionTemplateOverride, Column, ColumnId, Namespace, NamespaceId,
-    Table, TableId,
+    RollupPolicies, Table, TableId,
 };
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use iox_time::TimeProvider;
@@ -236,6 +236,7 @@ pub struct CachedTable {
     pub column_id_map_rev: HashMap<Arc<str>, ColumnId>,
     pub primary_key_column_ids: Box<[ColumnId]>,
     pub partition_template: TablePartitionTemplateOverride,
+    pub retention_period: Option<Duration>,
 }
 
 impl CachedTable {
@@ -280,6 +281,9 @@ impl CachedTable {
             column_id_map_rev,
             primary_key_column_ids,
             partition_template: table.partition_template,
+            retention_period: table
+                .retention_period_ns
+                .map(|retention| Duration::from_nanos(retention as u64)),
         }
     }
 
@@ -308,6 +312,7 @@ pub struct CachedNamespace {
     pub id: NamespaceId,
     pub retention_period: Option<Duration>,
     pub tables: HashMap<Arc<str>, Arc<CachedTable>>,
+    pub rollup_policies: RollupPolicies,
 }
 
 impl CachedNamespace {
@@ -340,6 +345,7 @@ impl CachedNamespace {
             id: namespace.id,
             retention_period,
             tables,
+            rollup_policies: namespace.rollup_policies,
         }
     }
 
@@ -418,6 +424,7 @@ mod tests {
         let expected_ns_1 = CachedNamespace {
             id: ns1.namespace.id,
             retention_period,
+            rollup_policies: Default::default(),
             tables: HashMap::from([
                 (
                     Arc::from("table1"),
@@ -442,6 +449,7 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col112.column.id, col113.column.id].into(),
                         partition_template: table11.table.partition_template.clone(),
+                        retention_period: None,
                     }),
                 ),
                 (
@@ -464,6 +472,7 @@ mod tests {
                         ]),
                         primary_key_column_ids: [col122.column.id].into(),
                         partition_template: TablePartitionTemplateOverride::default(),
+                        retention_period: None,
                     }),
                 ),
             ]),
@@ -482,6 +491,7 @@ mod tests {
         let expected_ns_2 = CachedNamespace {
             id: ns2.namespace.id,
             retention_period,
+            rollup_policies: Default::default(),
             tables: HashMap::from([(
                 Arc::from("table1"),
                 Arc::new(CachedTable {
@@ -497,6 +507,7 @@ mod tests {
                     )]),
                     primary_key_column_ids: [col211.column.id].into(),
                     partition_template: TablePartitionTemplateOverride::default(),
+                    retention_period: None,
                 }),
             )]),
         };
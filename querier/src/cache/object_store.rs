@@ -13,8 +13,10 @@ use cache_system::{
     loader::{metrics::MetricsLoader, FunctionLoader},
     resource_consumption::FunctionEstimator,
 };
+use data_types::StorageTier;
 use futures::{stream::BoxStream, StreamExt};
 use iox_time::TimeProvider;
+use metric::U64Counter;
 use object_store::{
     path::Path, Error as ObjectStoreError, GetOptions, GetResult, GetResultPayload, ListResult,
     MultipartId, ObjectMeta, ObjectStore,
@@ -78,12 +80,87 @@ async fn read_from_store(
     Ok(Some(CachedRead { bytes, meta }))
 }
 
+/// Per-tier read metrics of the [`ObjectStoreCache`] loader.
+#[derive(Debug)]
+struct TierMetrics {
+    found: U64Counter,
+    not_found: U64Counter,
+}
+
+impl TierMetrics {
+    fn new(metric_registry: &metric::Registry, tier: StorageTier) -> Self {
+        let metric = metric_registry.register_metric::<U64Counter>(
+            "object_store_cache_tier_reads",
+            "Number of objects loaded into the querier object store cache, by storage tier",
+        );
+
+        Self {
+            found: metric.recorder(&[("tier", tier.name()), ("result", "found")]),
+            not_found: metric.recorder(&[("tier", tier.name()), ("result", "not_found")]),
+        }
+    }
+}
+
+/// The tiered object stores backing the [`ObjectStoreCache`].
+#[derive(Debug)]
+struct TieredStores {
+    hot: Arc<dyn ObjectStore>,
+    cold: Option<Arc<dyn ObjectStore>>,
+    hot_metrics: TierMetrics,
+    cold_metrics: TierMetrics,
+}
+
+impl TieredStores {
+    /// The store and metrics of `tier`, if that tier is configured.
+    fn tier(&self, tier: StorageTier) -> Option<(&dyn ObjectStore, &TierMetrics)> {
+        match tier {
+            StorageTier::Hot => Some((self.hot.as_ref(), &self.hot_metrics)),
+            StorageTier::Cold => self
+                .cold
+                .as_ref()
+                .map(|store| (store.as_ref(), &self.cold_metrics)),
+        }
+    }
+
+    /// Read `path` from the `hint` tier, falling back to the other tier if
+    /// the object is not found there.
+    ///
+    /// The catalog view of the querier may be stale, so a file the querier
+    /// believes to be hot may already have been relocated to the cold tier.
+    async fn read(
+        &self,
+        path: &Path,
+        hint: StorageTier,
+    ) -> Result<Option<CachedRead>, ObjectStoreError> {
+        let other = match hint {
+            StorageTier::Hot => StorageTier::Cold,
+            StorageTier::Cold => StorageTier::Hot,
+        };
+
+        for tier in [hint, other] {
+            let Some((store, metrics)) = self.tier(tier) else {
+                continue;
+            };
+
+            match read_from_store(store, path).await? {
+                Some(data) => {
+                    metrics.found.inc(1);
+                    return Ok(Some(data));
+                }
+                None => metrics.not_found.inc(1),
+            }
+        }
+
+        Ok(None)
+    }
+}
+
 type CacheT = Arc<
     dyn Cache<
         K = Path,
         V = Option<CachedRead>,
-        GetExtra = ((), Option<Span>),
-        PeekExtra = ((), Option<Span>),
+        GetExtra = (StorageTier, Option<Span>),
+        PeekExtra = (StorageTier, Option<Span>),
     >,
 >;
 
@@ -94,34 +171,46 @@ type CacheT = Arc<
 ///
 /// ["Not found"](ObjectStoreError::NotFound) results are cached forever, so make sure to only retrieve objects that
 /// shall exist.
+///
+/// If a cold [`StorageTier`] store is configured, objects are read from the tier of the virtual store they are
+/// requested through first and from the other tier if they are not found there. Objects are keyed by path only, so
+/// relocating a file between tiers does not invalidate its cache entry.
 #[derive(Debug)]
 pub struct ObjectStoreCache {
-    // this is the virtual object store
-    object_store: Arc<dyn ObjectStore>,
+    // these are the virtual object stores, one per tier
+    hot_object_store: Arc<dyn ObjectStore>,
+    cold_object_store: Arc<dyn ObjectStore>,
 }
 
 impl ObjectStoreCache {
     /// Create new empty cache.
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         backoff_config: BackoffConfig,
         object_store: Arc<dyn ObjectStore>,
+        cold_object_store: Option<Arc<dyn ObjectStore>>,
         time_provider: Arc<dyn TimeProvider>,
         metric_registry: &metric::Registry,
         ram_pool: Arc<ResourcePool<RamSize>>,
         handle: &Handle,
         testing: bool,
     ) -> Self {
-        let object_store_captured = Arc::clone(&object_store);
-        let loader = FunctionLoader::new(move |key: Path, _extra: ()| {
+        let stores = Arc::new(TieredStores {
+            hot: object_store,
+            cold: cold_object_store,
+            hot_metrics: TierMetrics::new(metric_registry, StorageTier::Hot),
+            cold_metrics: TierMetrics::new(metric_registry, StorageTier::Cold),
+        });
+        let loader = FunctionLoader::new(move |key: Path, tier: StorageTier| {
             let backoff_config = backoff_config.clone();
-            let object_store = Arc::clone(&object_store_captured);
+            let stores = Arc::clone(&stores);
 
             async move {
                 Backoff::new(&backoff_config)
                     .retry_all_errors::<_, _, _, ObjectStoreError>(
                         "get object from object store",
                         || async {
-                            let data = read_from_store(object_store.as_ref(), &key).await?;
+                            let data = stores.read(&key, tier).await?;
 
                             Ok(data)
                         },
@@ -163,17 +252,29 @@ impl ObjectStoreCache {
             metric_registry,
         ));
 
-        let object_store = Arc::new(CachedObjectStore {
+        let hot_object_store = Arc::new(CachedObjectStore {
+            cache: Arc::clone(&cache),
+            handle: handle.clone(),
+            tier: StorageTier::Hot,
+        });
+        let cold_object_store = Arc::new(CachedObjectStore {
             cache,
             handle: handle.clone(),
+            tier: StorageTier::Cold,
         });
 
-        Self { object_store }
+        Self {
+            hot_object_store,
+            cold_object_store,
+        }
     }
 
-    /// Get object store.
-    pub fn object_store(&self) -> &Arc<dyn ObjectStore> {
-        &self.object_store
+    /// Get object store for files in the given [`StorageTier`].
+    pub fn object_store(&self, tier: StorageTier) -> &Arc<dyn ObjectStore> {
+        match tier {
+            StorageTier::Hot => &self.hot_object_store,
+            StorageTier::Cold => &self.cold_object_store,
+        }
     }
 }
 
@@ -181,6 +282,7 @@ impl ObjectStoreCache {
 struct CachedObjectStore {
     cache: CacheT,
     handle: Handle,
+    tier: StorageTier,
 }
 
 impl CachedObjectStore {
@@ -191,6 +293,7 @@ impl CachedObjectStore {
     async fn get_data(&self, location: &Path) -> Result<CachedRead, ObjectStoreError> {
         let cache = Arc::clone(&self.cache);
         let location = location.clone();
+        let tier = self.tier;
         let (tx, rx) = channel();
 
         // ensure that we cancel request if we no longer need them
@@ -198,7 +301,7 @@ impl CachedObjectStore {
         join_set.spawn_on(
             async move {
                 let res = cache
-                    .get(location.clone(), ((), None))
+                    .get(location.clone(), (tier, None))
                     .await
                     .ok_or_else(|| ObjectStoreError::NotFound {
                         path: location.to_string(),
@@ -220,7 +323,7 @@ impl CachedObjectStore {
 
 impl std::fmt::Display for CachedObjectStore {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "CachedObjectStore")
+        write!(f, "CachedObjectStore({})", self.tier)
     }
 }
 
@@ -397,13 +500,14 @@ mod tests {
         let cache = ObjectStoreCache::new(
             BackoffConfig::default(),
             Arc::new(instrumented_store),
+            None,
             time_provider,
             &metric_registry,
             test_ram_pool(),
             &Handle::current(),
             true,
         );
-        let cached_store = cache.object_store();
+        let cached_store = cache.object_store(StorageTier::Hot);
 
         // ensure "hits" are cached
         assert_eq!(get_count_hit(&metric_registry), 0);
@@ -464,6 +568,87 @@ mod tests {
         assert_eq!(get_count_miss(&metric_registry), 1);
     }
 
+    #[tokio::test]
+    async fn test_tiers() {
+        let hot = Arc::new(InMemory::new());
+        let cold = Arc::new(InMemory::new());
+
+        let path_hot = Path::from("hot");
+        let bytes_hot = Bytes::from(b"data_hot" as &'static [u8]);
+        hot.put(&path_hot, bytes_hot.clone()).await.unwrap();
+
+        let path_cold = Path::from("cold");
+        let bytes_cold = Bytes::from(b"data_cold" as &'static [u8]);
+        cold.put(&path_cold, bytes_cold.clone()).await.unwrap();
+
+        let metric_registry = metric::Registry::new();
+        let cache = ObjectStoreCache::new(
+            BackoffConfig::default(),
+            Arc::clone(&hot) as _,
+            Some(Arc::clone(&cold) as _),
+            Arc::new(SystemProvider::new()),
+            &metric_registry,
+            test_ram_pool(),
+            &Handle::current(),
+            true,
+        );
+
+        // read through the matching tier
+        assert_eq!(
+            cache
+                .object_store(StorageTier::Cold)
+                .get(&path_cold)
+                .await
+                .unwrap()
+                .bytes()
+                .await
+                .unwrap(),
+            bytes_cold,
+        );
+        assert_eq!(get_tier_reads(&metric_registry, "cold", "found"), 1);
+        assert_eq!(get_tier_reads(&metric_registry, "hot", "not_found"), 0);
+
+        // a stale tier falls back to the other tier
+        assert_eq!(
+            cache
+                .object_store(StorageTier::Cold)
+                .get(&path_hot)
+                .await
+                .unwrap()
+                .bytes()
+                .await
+                .unwrap(),
+            bytes_hot,
+        );
+        assert_eq!(get_tier_reads(&metric_registry, "cold", "not_found"), 1);
+        assert_eq!(get_tier_reads(&metric_registry, "hot", "found"), 1);
+
+        // unknown objects are looked up in both tiers
+        assert_matches!(
+            cache
+                .object_store(StorageTier::Hot)
+                .get(&Path::from("missing"))
+                .await
+                .unwrap_err(),
+            ObjectStoreError::NotFound { .. }
+        );
+        assert_eq!(get_tier_reads(&metric_registry, "hot", "not_found"), 1);
+        assert_eq!(get_tier_reads(&metric_registry, "cold", "not_found"), 2);
+    }
+
+    fn get_tier_reads(
+        metric_registry: &metric::Registry,
+        tier: &'static str,
+        result: &'static str,
+    ) -> u64 {
+        metric_registry
+            .get_instrument::<Metric<U64Counter>>("object_store_cache_tier_reads")
+            .unwrap()
+            .get_observer(&Attributes::from(&[("tier", tier), ("result", result)]))
+            .unwrap()
+            .fetch()
+    }
+
     fn get_count_hit(metric_registry: &metric::Registry) -> u64 {
         metric_registry
             .get_instrument::<Metric<DurationHistogram>>("object_store_op_duration")
@@ -17,8 +17,9 @@ use cache_system::{
 };
 use chrono::{DateTime, Duration, TimeZone, Utc};
 use data_types::{
-    partition_template::{build_column_values, ColumnValue},
-    ColumnId, Partition, SortedColumnSet, TransitionPartitionId, MAX_NANO_TIME, MIN_NANO_TIME,
+    partition_template::{bucket_for_tag_value, build_column_values, ColumnValue},
+    ColumnId, Partition, PartitionId, SortedColumnSet, Timestamp, TransitionPartitionId,
+    MAX_NANO_TIME, MIN_NANO_TIME,
 };
 use datafusion::scalar::ScalarValue;
 use iox_catalog::{interface::Catalog, partition_lookup_batch};
@@ -160,7 +161,8 @@ impl PartitionCache {
     ///
     /// The result only contains existing partitions. The order is undefined.
     ///
-    /// Expire partition if the cached sort key does NOT cover the given set of columns.
+    /// Expire partition if the cached sort key does NOT cover the given set of columns, or if the
+    /// partition received a file newer than the cached entry.
     pub async fn get(
         &self,
         cached_table: Arc<CachedTable>,
@@ -175,6 +177,7 @@ impl PartitionCache {
                 |PartitionRequest {
                      partition_id,
                      sort_key_should_cover,
+                     newest_file_created_at,
                  }| {
                     let cached_table = Arc::clone(&cached_table);
 
@@ -187,7 +190,7 @@ impl PartitionCache {
                         partition_id.clone(),
                         move |cached_partition| {
                             let invalidates = if let Some(sort_key) =
-                                &cached_partition.and_then(|p| p.sort_key.clone())
+                                &cached_partition.as_ref().and_then(|p| p.sort_key.clone())
                             {
                                 sort_key_should_cover
                                     .iter()
@@ -197,6 +200,12 @@ impl PartitionCache {
                                 !sort_key_should_cover.is_empty()
                             };
 
+                            // The files of the partition may have been rewritten with a
+                            // different sort key after the entry was loaded.
+                            let invalidates = invalidates
+                                || cached_partition
+                                    .map_or(false, |p| newest_file_created_at > p.new_file_at);
+
                             if invalidates {
                                 debug!(
                                     %partition_id,
@@ -243,13 +252,38 @@ impl PartitionCache {
 pub struct PartitionRequest {
     pub partition_id: TransitionPartitionId,
     pub sort_key_should_cover: Vec<ColumnId>,
+    /// Creation time of the newest file of the partition used by the query, if any.
+    pub newest_file_created_at: Option<Timestamp>,
+}
+
+/// The hash bucket all values of a tag column in a partition fall into,
+/// derived from a bucket partition template part.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ColumnBucket {
+    pub bucket: u32,
+    pub num_buckets: u32,
 }
 
+impl ColumnBucket {
+    /// Returns true if `value` hashes into this bucket.
+    pub fn contains(&self, value: &str) -> bool {
+        bucket_for_tag_value(value, self.num_buckets) == self.bucket
+    }
+}
+
+/// Hash buckets of tag columns, keyed by column name.
+pub type ColumnBuckets = Arc<HashMap<Arc<str>, ColumnBucket>>;
+
 #[derive(Debug, PartialEq, Eq)]
 pub struct CachedPartition {
     pub id: TransitionPartitionId,
+    /// The catalog-assigned ID, as used by the compactor.
+    pub catalog_id: PartitionId,
     pub sort_key: Option<Arc<PartitionSortKey>>,
     pub column_ranges: ColumnRanges,
+    pub column_buckets: ColumnBuckets,
+    /// Creation time of the newest file of the partition when it was loaded.
+    pub new_file_at: Option<Timestamp>,
 }
 
 impl CachedPartition {
@@ -269,10 +303,15 @@ impl CachedPartition {
         );
 
         let mut column_ranges = HashMap::new();
+        let mut column_buckets = HashMap::new();
         let mut ignore = HashSet::new();
-        for (col, val) in
-            build_column_values(&table.partition_template, partition.partition_key.inner())
-        {
+        // The partition key MUST be interpreted using the template that derived it, which is not
+        // necessarily the current template of the table, as the table template may have changed
+        // since the partition was created.
+        for (col, val) in build_column_values(
+            &partition.partition_template,
+            partition.partition_key.inner(),
+        ) {
             if ignore.contains(col) {
                 continue;
             }
@@ -333,6 +372,23 @@ impl CachedPartition {
                         max_value,
                     }
                 }
+                ColumnValue::Bucket {
+                    bucket,
+                    num_buckets,
+                } => {
+                    // A bucket cannot be expressed as a value range, so it is
+                    // tracked separately. Should the column be bucketed more
+                    // than once, each bucket is a necessary condition and
+                    // retaining any one of them is correct.
+                    column_buckets.insert(
+                        col,
+                        ColumnBucket {
+                            bucket,
+                            num_buckets,
+                        },
+                    );
+                    continue;
+                }
                 ColumnValue::Datetime { begin, end } => ColumnRange {
                     min_value: Arc::new(ScalarValue::TimestampNanosecond(
                         Some(
@@ -370,11 +426,15 @@ impl CachedPartition {
             }
         }
         column_ranges.shrink_to_fit();
+        column_buckets.shrink_to_fit();
 
         Self {
             id: partition.transition_partition_id(),
+            catalog_id: partition.id,
             sort_key,
             column_ranges: Arc::new(column_ranges),
+            column_buckets: Arc::new(column_buckets),
+            new_file_at: partition.new_file_at,
         }
     }
 
@@ -398,7 +458,16 @@ impl CachedPartition {
                 .map(|(col, range)| col.len() + range.min_value.size() + range.max_value.size())
                 .sum::<usize>();
 
-        std::mem::size_of_val(self) + id + sort_key + column_ranges
+        // Arc content
+        let column_buckets = std::mem::size_of::<HashMap<Arc<str>, ColumnBucket>>()
+            + (self.column_buckets.capacity() * std::mem::size_of::<(Arc<str>, ColumnBucket)>())
+            + self
+                .column_buckets
+                .keys()
+                .map(|col| col.len())
+                .sum::<usize>();
+
+        std::mem::size_of_val(self) + id + sort_key + column_ranges + column_buckets
     }
 }
 
@@ -467,9 +536,9 @@ mod tests {
     };
     use futures::StreamExt;
     use generated_types::influxdata::iox::partition_template::v1::{
-        template_part::Part, PartitionTemplate, TemplatePart,
+        template_part::Part, Bucket, PartitionTemplate, TemplatePart,
     };
-    use iox_tests::{TestCatalog, TestNamespace};
+    use iox_tests::{TestCatalog, TestNamespace, TestParquetFileBuilder};
     use schema::{Schema, SchemaBuilder, TIME_COLUMN_NAME};
     use tokio::sync::Barrier;
 
@@ -504,6 +573,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -663,6 +733,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id, c3.column.id, c4.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -914,6 +985,7 @@ mod tests {
             column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
             primary_key_column_ids: [c.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -938,6 +1010,198 @@ mod tests {
         assert_eq!(ranges.as_ref(), &HashMap::new(),);
     }
 
+    /// Partition keys are interpreted using the template the partition was created with, rather
+    /// than the current template of the table.
+    #[tokio::test]
+    async fn test_column_ranges_template_changed() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let t = ns
+            .create_table_with_partition_template(
+                "table",
+                Some(PartitionTemplate {
+                    parts: vec![
+                        TemplatePart {
+                            part: Some(Part::TimeFormat(String::from("%Y"))),
+                        },
+                        TemplatePart {
+                            part: Some(Part::TagValue(String::from("tag1"))),
+                        },
+                    ],
+                }),
+            )
+            .await;
+        let c1 = t.create_column("tag1", ColumnType::Tag).await;
+        let c2 = t.create_column(TIME_COLUMN_NAME, ColumnType::Time).await;
+        let p_old = t.create_partition("2023|v1").await.partition.clone();
+
+        // Swap the order of the template parts.
+        let new_template = TablePartitionTemplateOverride::try_new(
+            Some(PartitionTemplate {
+                parts: vec![
+                    TemplatePart {
+                        part: Some(Part::TagValue(String::from("tag1"))),
+                    },
+                    TemplatePart {
+                        part: Some(Part::TimeFormat(String::from("%Y"))),
+                    },
+                ],
+            }),
+            &Default::default(),
+        )
+        .unwrap();
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .tables()
+            .update_partition_template(t.table.id, new_template.clone())
+            .await
+            .unwrap();
+        let p_new = t.create_partition("v2|2024").await.partition.clone();
+
+        let cached_table = Arc::new(CachedTable {
+            id: t.table.id,
+            schema: schema(),
+            column_id_map: HashMap::from([
+                (c1.column.id, Arc::from(c1.column.name.clone())),
+                (c2.column.id, Arc::from(c2.column.name.clone())),
+            ]),
+            column_id_map_rev: HashMap::from([
+                (Arc::from(c1.column.name.clone()), c1.column.id),
+                (Arc::from(c2.column.name.clone()), c2.column.id),
+            ]),
+            primary_key_column_ids: [c1.column.id, c2.column.id].into(),
+            partition_template: new_template,
+            retention_period: None,
+        });
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        for (p, tag_value, year) in [(p_old, "v1", 2023), (p_new, "v2", 2024)] {
+            let ranges = &cache
+                .get_one(
+                    Arc::clone(&cached_table),
+                    &p.transition_partition_id(),
+                    &[],
+                    None,
+                )
+                .await
+                .unwrap()
+                .column_ranges;
+            assert_eq!(
+                ranges.as_ref(),
+                &HashMap::from([
+                    (Arc::from(TIME_COLUMN_NAME), year_column_range(year)),
+                    (
+                        Arc::from("tag1"),
+                        ColumnRange {
+                            min_value: Arc::new(ScalarValue::from(tag_value)),
+                            max_value: Arc::new(ScalarValue::from(tag_value))
+                        }
+                    ),
+                ]),
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_column_buckets() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let t = ns
+            .create_table_with_partition_template(
+                "table",
+                Some(PartitionTemplate {
+                    parts: vec![
+                        TemplatePart {
+                            part: Some(Part::TimeFormat(String::from("%Y"))),
+                        },
+                        TemplatePart {
+                            part: Some(Part::Bucket(Bucket {
+                                tag_name: String::from("tag1"),
+                                num_buckets: 10,
+                            })),
+                        },
+                        TemplatePart {
+                            part: Some(Part::Bucket(Bucket {
+                                tag_name: String::from("tag2"),
+                                num_buckets: 10,
+                            })),
+                        },
+                    ],
+                }),
+            )
+            .await;
+        let c1 = t.create_column("tag1", ColumnType::Tag).await;
+        let c2 = t.create_column("tag2", ColumnType::Tag).await;
+        let c3 = t.create_column(TIME_COLUMN_NAME, ColumnType::Time).await;
+        // 3rd part is NULL
+        let p = t.create_partition("2023|9|!").await.partition.clone();
+        let cached_table = Arc::new(CachedTable {
+            id: t.table.id,
+            schema: schema(),
+            column_id_map: HashMap::from([
+                (c1.column.id, Arc::from(c1.column.name.clone())),
+                (c2.column.id, Arc::from(c2.column.name.clone())),
+                (c3.column.id, Arc::from(c3.column.name.clone())),
+            ]),
+            column_id_map_rev: HashMap::from([
+                (Arc::from(c1.column.name.clone()), c1.column.id),
+                (Arc::from(c2.column.name.clone()), c2.column.id),
+                (Arc::from(c3.column.name.clone()), c3.column.id),
+            ]),
+            primary_key_column_ids: [c1.column.id, c2.column.id, c3.column.id].into(),
+            partition_template: t.table.partition_template.clone(),
+            retention_period: None,
+        });
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+
+        let cached = cache
+            .get_one(
+                Arc::clone(&cached_table),
+                &p.transition_partition_id(),
+                &[],
+                None,
+            )
+            .await
+            .unwrap();
+
+        // Buckets are not expressed as column ranges.
+        assert_eq!(
+            cached.column_ranges.as_ref(),
+            &HashMap::from([(Arc::from(TIME_COLUMN_NAME), year_column_range(2023))]),
+        );
+
+        let bucket = ColumnBucket {
+            bucket: 9,
+            num_buckets: 10,
+        };
+        assert_eq!(
+            cached.column_buckets.as_ref(),
+            &HashMap::from([(Arc::from("tag1"), bucket)]),
+        );
+        assert!(bucket.contains("bananas"));
+        assert!(!bucket.contains("platanos"));
+    }
+
     #[tokio::test]
     async fn test_column_ranges_time_edges() {
         let catalog = TestCatalog::new();
@@ -979,6 +1243,7 @@ mod tests {
             column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
             primary_key_column_ids: [c.column.id].into(),
             partition_template: t.table.partition_template.clone(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -1080,6 +1345,7 @@ mod tests {
             ]),
             primary_key_column_ids: [c1.column.id, c2.column.id].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -1213,6 +1479,103 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_expiration_newer_file() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        let t = ns.create_table("table").await;
+        let c1 = t.create_column("foo", ColumnType::Tag).await;
+        let c2 = t.create_column("time", ColumnType::Time).await;
+        let p = t.create_partition("k1").await;
+        let p_id = p.partition.transition_partition_id();
+        let cached_table = Arc::new(CachedTable {
+            id: t.table.id,
+            schema: schema(),
+            column_id_map: HashMap::from([
+                (c1.column.id, Arc::from(c1.column.name.clone())),
+                (c2.column.id, Arc::from(c2.column.name.clone())),
+            ]),
+            column_id_map_rev: HashMap::from([
+                (Arc::from(c1.column.name.clone()), c1.column.id),
+                (Arc::from(c2.column.name.clone()), c2.column.id),
+            ]),
+            primary_key_column_ids: [c1.column.id, c2.column.id].into(),
+            partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
+        });
+
+        let cache = PartitionCache::new(
+            catalog.catalog(),
+            BackoffConfig::default(),
+            catalog.time_provider(),
+            &catalog.metric_registry(),
+            test_ram_pool(),
+            true,
+        );
+        let get = |newest_file_created_at| {
+            let cached_table = Arc::clone(&cached_table);
+            let partition_id = p_id.clone();
+            let cache = &cache;
+            async move {
+                cache
+                    .get(
+                        cached_table,
+                        vec![PartitionRequest {
+                            partition_id,
+                            sort_key_should_cover: vec![],
+                            newest_file_created_at,
+                        }],
+                        None,
+                    )
+                    .await
+                    .into_iter()
+                    .next()
+                    .unwrap()
+            }
+        };
+
+        let f1 = p
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default()
+                    .with_creation_time(iox_time::Time::from_timestamp_nanos(10)),
+            )
+            .await;
+        let cached = get(Some(f1.parquet_file.created_at)).await;
+        assert_eq!(cached.new_file_at, Some(f1.parquet_file.created_at));
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "partition_get_by_hash_id_batch",
+            1,
+        );
+
+        // files known when the partition was loaded don't expire
+        for newest in [None, Some(f1.parquet_file.created_at)] {
+            let cached_2 = get(newest).await;
+            assert!(Arc::ptr_eq(&cached, &cached_2));
+        }
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "partition_get_by_hash_id_batch",
+            1,
+        );
+
+        // newer files expire
+        let f2 = p
+            .create_parquet_file_catalog_record(
+                TestParquetFileBuilder::default()
+                    .with_creation_time(iox_time::Time::from_timestamp_nanos(20)),
+            )
+            .await;
+        let cached = get(Some(f2.parquet_file.created_at)).await;
+        assert_eq!(cached.new_file_at, Some(f2.parquet_file.created_at));
+        assert_catalog_access_metric_count(
+            &catalog.metric_registry,
+            "partition_get_by_hash_id_batch",
+            2,
+        );
+    }
+
     #[tokio::test]
     async fn test_multi_get() {
         let catalog = TestCatalog::new();
@@ -1228,6 +1591,7 @@ mod tests {
             column_id_map_rev: HashMap::default(),
             primary_key_column_ids: [].into(),
             partition_template: TablePartitionTemplateOverride::default(),
+            retention_period: None,
         });
 
         let cache = PartitionCache::new(
@@ -1249,20 +1613,24 @@ mod tests {
                     PartitionRequest {
                         partition_id: p1_id.clone(),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     },
                     PartitionRequest {
                         partition_id: p2_id.clone(),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     },
                     PartitionRequest {
                         partition_id: p1_id.clone(),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     },
                     // requesting non-existing partitions is fine, they just don't appear in
                     // the output
                     PartitionRequest {
                         partition_id: TransitionPartitionId::Deprecated(PartitionId::new(i64::MAX)),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     },
                     PartitionRequest {
                         partition_id: TransitionPartitionId::new(
@@ -1270,6 +1638,7 @@ mod tests {
                             &PartitionKey::from("bananas_not_found"),
                         ),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     },
                 ],
                 None,
@@ -1459,6 +1828,7 @@ mod tests {
                 column_id_map_rev: HashMap::from([(Arc::from(c.column.name.clone()), c.column.id)]),
                 primary_key_column_ids: [c.column.id].into(),
                 partition_template: TablePartitionTemplateOverride::default(),
+                retention_period: None,
             });
             const N_PARTITIONS: usize = 20;
             let c_id = c.column.id.get();
@@ -1500,6 +1870,7 @@ mod tests {
                         .map(|p| PartitionRequest {
                             partition_id: p.clone(),
                             sort_key_should_cover: vec![],
+                            newest_file_created_at: None,
                         })
                         .collect(),
                     None,
@@ -1544,6 +1915,7 @@ mod tests {
                 vec![PartitionRequest {
                     partition_id: partition_id.clone(),
                     sort_key_should_cover: sort_key_should_cover.to_vec(),
+                    newest_file_created_at: None,
                 }],
                 span,
             )
@@ -6,6 +6,7 @@ use crate::{
     namespace::{QuerierNamespace, QuerierNamespaceArgs},
     parquet::ChunkAdapter,
     query_log::QueryLog,
+    read_costs::PartitionReadCosts,
     table::PruneMetrics,
     QueryLogEntry,
 };
@@ -106,6 +107,9 @@ impl QuerierDatabase {
     pub const MAX_CONCURRENT_QUERIES_MAX: usize = u16::MAX as usize;
 
     /// Create new database.
+    ///
+    /// If `read_costs` is provided, the parquet files scanned by each query
+    /// are recorded in it.
     pub async fn new(
         catalog_cache: Arc<CatalogCache>,
         metric_registry: Arc<metric::Registry>,
@@ -113,6 +117,7 @@ impl QuerierDatabase {
         ingester_connection: Option<Arc<dyn IngesterConnection>>,
         max_concurrent_queries: usize,
         datafusion_config: Arc<HashMap<String, String>>,
+        read_costs: Option<Arc<PartitionReadCosts>>,
     ) -> Result<Self, Error> {
         assert!(
             max_concurrent_queries <= Self::MAX_CONCURRENT_QUERIES_MAX,
@@ -124,10 +129,12 @@ impl QuerierDatabase {
         let backoff_config = BackoffConfig::default();
 
         let prune_metrics = Arc::new(PruneMetrics::new(&metric_registry));
-        let chunk_adapter = Arc::new(ChunkAdapter::new(
-            Arc::clone(&catalog_cache),
-            Arc::clone(&prune_metrics),
-        ));
+        let mut chunk_adapter =
+            ChunkAdapter::new(Arc::clone(&catalog_cache), Arc::clone(&prune_metrics));
+        if let Some(read_costs) = read_costs {
+            chunk_adapter = chunk_adapter.with_read_costs(read_costs);
+        }
+        let chunk_adapter = Arc::new(chunk_adapter);
         let query_log = Arc::new(QueryLog::new(QUERY_LOG_SIZE, catalog_cache.time_provider()));
         let semaphore_metrics = Arc::new(AsyncSemaphoreMetrics::new(
             &metric_registry,
@@ -239,6 +246,7 @@ mod tests {
             Some(create_ingester_connection_for_testing()),
             QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX.saturating_add(1),
             Arc::new(HashMap::default()),
+            None,
         )
         .await
         .unwrap();
@@ -285,6 +293,7 @@ mod tests {
             Some(create_ingester_connection_for_testing()),
             QuerierDatabase::MAX_CONCURRENT_QUERIES_MAX,
             Arc::new(HashMap::default()),
+            None,
         )
         .await
         .unwrap()
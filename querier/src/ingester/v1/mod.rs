@@ -1770,6 +1770,7 @@ mod tests {
             column_id_map_rev: Default::default(),
             primary_key_column_ids: Default::default(),
             partition_template: Default::default(),
+            retention_period: None,
         })
     }
 }
@@ -22,6 +22,7 @@ mod ingester;
 mod namespace;
 mod parquet;
 mod query_log;
+mod read_costs;
 mod server;
 mod system_tables;
 mod table;
@@ -36,4 +37,5 @@ pub use database::{Error as QuerierDatabaseError, QuerierDatabase};
 pub use ingester::{create_ingester_connection_for_testing, create_ingester_connections};
 pub use namespace::QuerierNamespace;
 pub use query_log::QueryLogEntry;
+pub use read_costs::{PartitionReadCost, PartitionReadCosts};
 pub use server::QuerierServer;
@@ -7,7 +7,7 @@ use crate::{
     query_log::QueryLog,
     table::{PruneMetrics, QuerierTable, QuerierTableArgs},
 };
-use data_types::NamespaceId;
+use data_types::{NamespaceId, RollupPolicies};
 use iox_query::exec::Executor;
 use std::{collections::HashMap, sync::Arc, time::Duration};
 
@@ -67,6 +67,9 @@ pub struct QuerierNamespace {
 
     /// Retention period.
     retention_period: Option<Duration>,
+
+    /// Rollup policies, used to route queries to rollup tables.
+    rollup_policies: RollupPolicies,
 }
 
 impl QuerierNamespace {
@@ -91,7 +94,7 @@ impl QuerierNamespace {
                 let table = Arc::new(QuerierTable::new(QuerierTableArgs {
                     namespace_id: ns.id,
                     namespace_name: Arc::clone(&name),
-                    namespace_retention_period: ns.retention_period,
+                    retention_period: cached_table.retention_period.or(ns.retention_period),
                     table_id: cached_table.id,
                     table_name: Arc::clone(table_name),
                     schema: cached_table.schema.clone(),
@@ -116,6 +119,7 @@ impl QuerierNamespace {
             datafusion_config,
             include_debug_info_tables,
             retention_period: ns.retention_period,
+            rollup_policies: ns.rollup_policies.clone(),
         }
     }
 
@@ -239,6 +243,30 @@ mod tests {
         assert_eq!(actual_schema, &expected_schema);
     }
 
+    #[tokio::test]
+    async fn test_table_retention_period() {
+        let catalog = TestCatalog::new();
+
+        let ns = catalog.create_namespace_1hr_retention("ns").await;
+        ns.create_table("table1").await;
+        let table2 = ns.create_table("table2").await;
+        table2
+            .update_retention_period(Some(2 * 3_600 * 1_000_000_000))
+            .await;
+
+        let qns = querier_namespace(&ns).await;
+
+        // table1 inherits the namespace retention, table2 overrides it
+        assert_eq!(
+            qns.tables.get("table1").unwrap().retention_period(),
+            Some(Duration::from_secs(3_600))
+        );
+        assert_eq!(
+            qns.tables.get("table2").unwrap().retention_period(),
+            Some(Duration::from_secs(2 * 3_600))
+        );
+    }
+
     fn sorted<T>(mut v: Vec<T>) -> Vec<T>
     where
         T: Ord,
@@ -7,7 +7,7 @@ use crate::{
     table::QuerierTable,
 };
 use async_trait::async_trait;
-use data_types::NamespaceId;
+use data_types::{NamespaceId, RollupPolicies};
 use datafusion::{
     catalog::{schema::SchemaProvider, CatalogProvider},
     datasource::TableProvider,
@@ -54,8 +54,12 @@ impl QueryNamespace for QuerierNamespace {
         Ok(chunks)
     }
 
-    fn retention_time_ns(&self) -> Option<i64> {
-        self.retention_period.map(|d| {
+    fn retention_time_ns(&self, table_name: &str) -> Option<i64> {
+        let retention_period = match self.tables.get(table_name) {
+            Some(table) => table.retention_period(),
+            None => self.retention_period,
+        };
+        retention_period.map(|d| {
             self.catalog_cache.time_provider().now().timestamp_nanos() - d.as_nanos() as i64
         })
     }
@@ -101,6 +105,9 @@ pub struct QuerierCatalogProvider {
 
     /// Include debug info tables.
     include_debug_info_tables: bool,
+
+    /// Rollup policies, used to route queries to rollup tables.
+    rollup_policies: RollupPolicies,
 }
 
 impl QuerierCatalogProvider {
@@ -110,6 +117,7 @@ impl QuerierCatalogProvider {
             tables: Arc::clone(&namespace.tables),
             query_log: Arc::clone(&namespace.query_log),
             include_debug_info_tables: namespace.include_debug_info_tables,
+            rollup_policies: namespace.rollup_policies.clone(),
         }
     }
 }
@@ -127,6 +135,7 @@ impl CatalogProvider for QuerierCatalogProvider {
         match name {
             DEFAULT_SCHEMA => Some(Arc::new(UserSchemaProvider {
                 tables: Arc::clone(&self.tables),
+                rollup_policies: self.rollup_policies.clone(),
             })),
             SYSTEM_SCHEMA => Some(Arc::new(SystemSchemaProvider::new(
                 Arc::clone(&self.query_log),
@@ -139,9 +148,39 @@ impl CatalogProvider for QuerierCatalogProvider {
 }
 
 /// Provider for user-provided tables in [`DEFAULT_SCHEMA`].
+///
+/// Besides the tables of the namespace, a name of the form
+/// `<table>@<interval>` (e.g. `cpu@5m`) resolves to the rollup table of
+/// `<table>` with the largest interval evenly dividing `<interval>`, as
+/// described by the [`RollupPolicies`] of the namespace.
 struct UserSchemaProvider {
     /// A snapshot of all tables.
     tables: Arc<HashMap<Arc<str>, Arc<QuerierTable>>>,
+
+    /// Rollup policies of the namespace.
+    rollup_policies: RollupPolicies,
+}
+
+impl UserSchemaProvider {
+    /// Resolve `name` to a table, routing `<table>@<interval>` names to the
+    /// matching rollup table.
+    fn resolve(&self, name: &str) -> Option<&Arc<QuerierTable>> {
+        if let Some(table) = self.tables.get(name) {
+            return Some(table);
+        }
+
+        let (source_table, interval) = name.rsplit_once('@')?;
+        let interval = humantime::parse_duration(interval).ok()?;
+        let policy = self.rollup_policies.route(source_table, interval)?;
+        let table = self.tables.get(policy.target_table().as_str());
+        debug!(
+            %name,
+            target_table = %policy.target_table(),
+            found = table.is_some(),
+            "routed query to rollup table",
+        );
+        table
+    }
 }
 
 #[async_trait]
@@ -157,11 +196,11 @@ impl SchemaProvider for UserSchemaProvider {
     }
 
     async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
-        self.tables.get(name).map(|t| Arc::clone(t) as _)
+        self.resolve(name).map(|t| Arc::clone(t) as _)
     }
 
     fn table_exist(&self, name: &str) -> bool {
-        self.tables.contains_key(name)
+        self.resolve(name).is_some()
     }
 }
 
@@ -171,10 +210,11 @@ mod tests {
     use crate::namespace::test_util::{clear_parquet_cache, querier_namespace};
     use arrow::record_batch::RecordBatch;
     use arrow_util::test_util::{batches_to_sorted_lines, Normalizer};
-    use data_types::ColumnType;
+    use data_types::{ColumnType, Namespace};
     use datafusion::common::DataFusionError;
+    use generated_types::influxdata::iox::rollup::v1 as rollup_proto;
     use iox_query::frontend::sql::SqlQueryPlanner;
-    use iox_tests::{TestCatalog, TestParquetFileBuilder};
+    use iox_tests::{TestCatalog, TestNamespace, TestParquetFileBuilder};
     use iox_time::Time;
     use metric::{Observation, RawReporter};
     use snafu::{ResultExt, Snafu};
@@ -529,6 +569,79 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_query_rollup() {
+        test_helpers::maybe_start_logging();
+
+        let catalog = TestCatalog::new();
+        let ns = catalog.create_namespace_with_retention("ns", None).await;
+
+        let table_cpu = ns.create_table("cpu").await;
+        table_cpu.create_column("host", ColumnType::Tag).await;
+        table_cpu.create_column("time", ColumnType::Time).await;
+        table_cpu.create_column("usage", ColumnType::F64).await;
+
+        let table_rollup = ns.create_table("cpu_rollup_60s").await;
+        table_rollup.create_column("host", ColumnType::Tag).await;
+        table_rollup.create_column("time", ColumnType::Time).await;
+        table_rollup
+            .create_column("usage_mean", ColumnType::F64)
+            .await;
+
+        let builder = TestParquetFileBuilder::default()
+            .with_max_l0_created_at(Time::from_timestamp_nanos(1))
+            .with_line_protocol("cpu_rollup_60s,host=a usage_mean=1.5 60000000000")
+            .with_min_time(60_000_000_000)
+            .with_max_time(60_000_000_000);
+        table_rollup
+            .create_partition("a")
+            .await
+            .create_parquet_file(builder)
+            .await;
+
+        let policies = RollupPolicies::try_from(rollup_proto::RollupPolicies {
+            policies: vec![rollup_proto::RollupPolicy {
+                source_table: "cpu".to_string(),
+                interval_seconds: 60,
+                aggregates: vec![rollup_proto::FieldAggregate {
+                    field: "usage".to_string(),
+                    aggregate: rollup_proto::Aggregate::Mean.into(),
+                }],
+                age_threshold_seconds: 86_400,
+            }],
+        })
+        .unwrap();
+        ns.update_rollup_policies(policies.clone()).await;
+        let ns = Arc::new(TestNamespace {
+            catalog: Arc::clone(&ns.catalog),
+            namespace: Namespace {
+                rollup_policies: policies,
+                ..ns.namespace.clone()
+            },
+        });
+
+        let querier_namespace = Arc::new(querier_namespace(&ns).await);
+
+        // A 5 minute interval is served by the 1 minute rollup.
+        insta::assert_yaml_snapshot!(
+            format_query(&querier_namespace, r#"SELECT * FROM "cpu@5m""#).await,
+            @r###"
+        ---
+        - +------+----------------------+------------+
+        - "| host | time                 | usage_mean |"
+        - +------+----------------------+------------+
+        - "| a    | 1970-01-01T00:01:00Z | 1.5        |"
+        - +------+----------------------+------------+
+        "###
+        );
+
+        // Intervals without a matching rollup do not resolve.
+        let err = run_res(&querier_namespace, r#"SELECT * FROM "cpu@90s""#, None)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not found"), "{err}");
+    }
+
     async fn format_query(querier_namespace: &Arc<QuerierNamespace>, sql: &str) -> Vec<String> {
         format_query_with_span_ctx(querier_namespace, sql, None).await
     }
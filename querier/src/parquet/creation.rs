@@ -15,6 +15,7 @@ use uuid::Uuid;
 use crate::{
     cache::{namespace::CachedTable, partition::CachedPartition, CatalogCache},
     parquet::QuerierParquetChunkMeta,
+    read_costs::PartitionReadCosts,
     table::PruneMetrics,
     CONCURRENT_CHUNK_CREATION_JOBS,
 };
@@ -29,6 +30,9 @@ pub struct ChunkAdapter {
 
     /// Prune metrics.
     prune_metrics: Arc<PruneMetrics>,
+
+    /// Optional accounting of the files scanned per partition.
+    read_costs: Option<Arc<PartitionReadCosts>>,
 }
 
 impl ChunkAdapter {
@@ -37,6 +41,16 @@ impl ChunkAdapter {
         Self {
             catalog_cache,
             prune_metrics,
+            read_costs: None,
+        }
+    }
+
+    /// Record the read costs of the chunks created by this adapter in
+    /// `read_costs`.
+    pub fn with_read_costs(self, read_costs: Arc<PartitionReadCosts>) -> Self {
+        Self {
+            read_costs: Some(read_costs),
+            ..self
         }
     }
 
@@ -130,6 +144,14 @@ impl ChunkAdapter {
             span_recorder.child_span("prune chunks"),
         );
 
+        if let Some(read_costs) = &self.read_costs {
+            read_costs.record_files(
+                files
+                    .iter()
+                    .map(|f| (f.cached_partition.catalog_id, f.file.as_ref())),
+            );
+        }
+
         {
             let _span_recorder = span_recorder.child("finalize chunks");
 
@@ -249,11 +271,8 @@ impl ChunkAdapter {
             partition_id: file.partition_id.clone(),
         });
 
-        let parquet_chunk = Arc::new(ParquetChunk::new(
-            file,
-            schema,
-            self.catalog_cache.parquet_store(),
-        ));
+        let store = self.catalog_cache.parquet_store_for_tier(file.storage_tier);
+        let parquet_chunk = Arc::new(ParquetChunk::new(file, schema, store));
 
         QuerierParquetChunk::new(parquet_chunk, meta, stats)
     }
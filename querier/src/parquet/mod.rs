@@ -233,6 +233,7 @@ pub mod tests {
                     vec![PartitionRequest {
                         partition_id: self.parquet_file.partition_id.clone(),
                         sort_key_should_cover: vec![],
+                        newest_file_created_at: None,
                     }],
                     None,
                 )
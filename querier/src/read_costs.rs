@@ -0,0 +1,143 @@
+//! Accounting of the per-partition read costs of queries.
+
+use std::collections::HashMap;
+
+use data_types::{CompactionLevel, ParquetFile, PartitionId};
+use parking_lot::Mutex;
+
+/// The read cost of a single partition, accumulated over all queries planned
+/// since the last [`PartitionReadCosts::take()`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct PartitionReadCost {
+    /// The number of queries that scanned the partition.
+    pub queries: u64,
+
+    /// The number of parquet files scanned after pruning.
+    pub files_scanned: u64,
+
+    /// An upper-bound estimate of the number of rows deduplicated at query
+    /// time.
+    ///
+    /// Files of a partition only overlap (and need deduplicating) if at least
+    /// one of them is a [`CompactionLevel::Initial`] file, in which case all
+    /// rows of all the scanned files of the partition are counted.
+    pub dedup_rows: u64,
+}
+
+/// Accumulates the [`PartitionReadCost`] of every partition scanned by the
+/// queries planned by this querier.
+///
+/// The accumulated costs are drained by [`PartitionReadCosts::take()`], which
+/// should be called periodically to bound the memory used.
+#[derive(Debug, Default)]
+pub struct PartitionReadCosts {
+    costs: Mutex<HashMap<PartitionId, PartitionReadCost>>,
+}
+
+impl PartitionReadCosts {
+    /// Record the read cost of scanning `files` for a single query, each
+    /// paired with the catalog ID of its partition.
+    pub(crate) fn record_files<'a>(
+        &self,
+        files: impl IntoIterator<Item = (PartitionId, &'a ParquetFile)>,
+    ) {
+        // Group the files by partition, tracking if any of them may overlap.
+        let mut partitions: HashMap<PartitionId, (PartitionReadCost, bool)> = HashMap::new();
+        for (partition_id, file) in files {
+            let (cost, has_overlaps) = partitions.entry(partition_id).or_default();
+            cost.files_scanned += 1;
+            cost.dedup_rows += file.row_count as u64;
+            *has_overlaps |= file.compaction_level == CompactionLevel::Initial;
+        }
+
+        let mut costs = self.costs.lock();
+        for (partition_id, (cost, has_overlaps)) in partitions {
+            let total = costs.entry(partition_id).or_default();
+            total.queries += 1;
+            total.files_scanned += cost.files_scanned;
+            if has_overlaps && cost.files_scanned > 1 {
+                total.dedup_rows += cost.dedup_rows;
+            }
+        }
+    }
+
+    /// Return the read costs accumulated since the last call, resetting them.
+    pub fn take(&self) -> HashMap<PartitionId, PartitionReadCost> {
+        std::mem::take(&mut *self.costs.lock())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use iox_tests::ParquetFileBuilder;
+
+    use super::*;
+
+    fn file(
+        id: i64,
+        partition_id: i64,
+        level: CompactionLevel,
+        rows: i64,
+    ) -> (PartitionId, ParquetFile) {
+        let file = ParquetFileBuilder::new(id)
+            .with_compaction_level(level)
+            .with_row_count(rows)
+            .build();
+        (PartitionId::new(partition_id), file)
+    }
+
+    #[test]
+    fn test_record_and_take() {
+        let costs = PartitionReadCosts::default();
+
+        let files = [
+            // Overlapping files in partition 1.
+            file(1, 1, CompactionLevel::Initial, 10),
+            file(2, 1, CompactionLevel::FileNonOverlapped, 20),
+            // Non-overlapping files in partition 2.
+            file(3, 2, CompactionLevel::FileNonOverlapped, 30),
+            file(4, 2, CompactionLevel::Final, 40),
+            // A single L0 file in partition 3 needs no deduplication across
+            // files.
+            file(5, 3, CompactionLevel::Initial, 50),
+        ];
+        costs.record_files(files.iter().map(|(p, f)| (*p, f)));
+        costs.record_files([(files[0].0, &files[0].1)]);
+
+        let got = costs.take();
+        assert_eq!(
+            got,
+            [
+                (
+                    PartitionId::new(1),
+                    PartitionReadCost {
+                        queries: 2,
+                        files_scanned: 3,
+                        dedup_rows: 30,
+                    }
+                ),
+                (
+                    PartitionId::new(2),
+                    PartitionReadCost {
+                        queries: 1,
+                        files_scanned: 2,
+                        dedup_rows: 0,
+                    }
+                ),
+                (
+                    PartitionId::new(3),
+                    PartitionReadCost {
+                        queries: 1,
+                        files_scanned: 1,
+                        dedup_rows: 0,
+                    }
+                ),
+            ]
+            .into_iter()
+            .collect()
+        );
+
+        // The costs are reset.
+        assert!(costs.take().is_empty());
+    }
+}
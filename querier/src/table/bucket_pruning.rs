@@ -0,0 +1,168 @@
+//! Partition pruning based on hash-bucketed tag values.
+//!
+//! A bucket partition template part hashes a tag value into one of a fixed
+//! number of buckets, so the partition key only retains the bucket and not
+//! the value itself. Such partitions cannot be described by a value range, but
+//! an equality predicate on the tag can still exclude a partition if the
+//! literal does not hash into the partition's bucket.
+
+use datafusion::{
+    logical_expr::{expr::InList, BinaryExpr, Operator},
+    optimizer::utils::split_conjunction,
+    prelude::Expr,
+    scalar::ScalarValue,
+};
+
+use crate::cache::partition::ColumnBuckets;
+
+/// Returns false if the conjunction of `filters` cannot match any row in a
+/// partition with the given `column_buckets`.
+///
+/// Only `col = 'lit'`, `'lit' = col` and `col IN ('lit', ...)` predicates are
+/// considered - all other expressions are conservatively assumed to match.
+pub(crate) fn may_match(column_buckets: &ColumnBuckets, filters: &[Expr]) -> bool {
+    if column_buckets.is_empty() {
+        return true;
+    }
+
+    filters
+        .iter()
+        .flat_map(split_conjunction)
+        .all(|expr| match tag_literals(expr) {
+            Some((col, values)) => match column_buckets.get(col) {
+                Some(bucket) => values.into_iter().any(|v| bucket.contains(v)),
+                None => true,
+            },
+            None => true,
+        })
+}
+
+/// Returns the column name and the set of string literals it is required to
+/// equal by `expr`, if `expr` is a supported predicate.
+fn tag_literals(expr: &Expr) -> Option<(&str, Vec<&str>)> {
+    match expr {
+        Expr::BinaryExpr(BinaryExpr {
+            left,
+            op: Operator::Eq,
+            right,
+        }) => match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(c), Expr::Literal(v)) | (Expr::Literal(v), Expr::Column(c)) => {
+                Some((c.name.as_str(), vec![string_literal(v)?]))
+            }
+            _ => None,
+        },
+        Expr::InList(InList {
+            expr,
+            list,
+            negated: false,
+        }) => match expr.as_ref() {
+            Expr::Column(c) => {
+                let values = list
+                    .iter()
+                    .map(|v| match v {
+                        Expr::Literal(v) => string_literal(v),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                Some((c.name.as_str(), values))
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn string_literal(v: &ScalarValue) -> Option<&str> {
+    match v {
+        ScalarValue::Utf8(Some(s)) => Some(s.as_str()),
+        ScalarValue::Dictionary(_, v) => string_literal(v),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, sync::Arc};
+
+    use datafusion::prelude::{col, lit};
+
+    use super::*;
+    use crate::cache::partition::ColumnBucket;
+
+    fn buckets() -> ColumnBuckets {
+        // "bananas" hashes into bucket 9, "platanos" into bucket 0.
+        Arc::new(HashMap::from([(
+            Arc::from("tag"),
+            ColumnBucket {
+                bucket: 9,
+                num_buckets: 10,
+            },
+        )]))
+    }
+
+    #[test]
+    fn test_no_filters() {
+        assert!(may_match(&buckets(), &[]));
+        assert!(may_match(&Arc::new(HashMap::new()), &[]));
+    }
+
+    #[test]
+    fn test_equality() {
+        assert!(may_match(&buckets(), &[col("tag").eq(lit("bananas"))]));
+        assert!(may_match(&buckets(), &[lit("bananas").eq(col("tag"))]));
+        assert!(!may_match(&buckets(), &[col("tag").eq(lit("platanos"))]));
+        assert!(!may_match(&buckets(), &[lit("platanos").eq(col("tag"))]));
+
+        // Dictionary literals are unwrapped.
+        let dict = Expr::Literal(ScalarValue::Dictionary(
+            Box::new(arrow::datatypes::DataType::Int32),
+            Box::new(ScalarValue::from("platanos")),
+        ));
+        assert!(!may_match(&buckets(), &[col("tag").eq(dict)]));
+    }
+
+    #[test]
+    fn test_in_list() {
+        assert!(may_match(
+            &buckets(),
+            &[col("tag").in_list(vec![lit("platanos"), lit("bananas")], false)]
+        ));
+        assert!(!may_match(
+            &buckets(),
+            &[col("tag").in_list(vec![lit("platanos")], false)]
+        ));
+
+        // Negated lists are not considered.
+        assert!(may_match(
+            &buckets(),
+            &[col("tag").in_list(vec![lit("bananas")], true)]
+        ));
+    }
+
+    #[test]
+    fn test_conjunction() {
+        assert!(!may_match(
+            &buckets(),
+            &[col("other")
+                .eq(lit("x"))
+                .and(col("tag").eq(lit("platanos")))]
+        ));
+        assert!(!may_match(
+            &buckets(),
+            &[col("other").eq(lit("x")), col("tag").eq(lit("platanos"))]
+        ));
+    }
+
+    #[test]
+    fn test_unsupported_predicates() {
+        // Disjunctions, other operators and other columns always match.
+        assert!(may_match(
+            &buckets(),
+            &[col("tag")
+                .eq(lit("platanos"))
+                .or(col("tag").eq(lit("platanos")))]
+        ));
+        assert!(may_match(&buckets(), &[col("tag").not_eq(lit("bananas"))]));
+        assert!(may_match(&buckets(), &[col("other").eq(lit("platanos"))]));
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     CONCURRENT_CHUNK_CREATION_JOBS,
 };
 use data_types::{
-    ColumnId, NamespaceId, ParquetFile, TableId, TimestampMinMax, TransitionPartitionId,
+    ColumnId, NamespaceId, ParquetFile, TableId, Timestamp, TimestampMinMax, TransitionPartitionId,
     MAX_NANO_TIME, MIN_NANO_TIME,
 };
 use datafusion::{error::DataFusionError, prelude::Expr};
@@ -31,6 +31,7 @@ use uuid::Uuid;
 
 pub use self::metrics::PruneMetrics;
 
+mod bucket_pruning;
 mod metrics;
 mod query_access;
 
@@ -59,7 +60,7 @@ impl From<Error> for DataFusionError {
 pub struct QuerierTableArgs {
     pub namespace_id: NamespaceId,
     pub namespace_name: Arc<str>,
-    pub namespace_retention_period: Option<Duration>,
+    pub retention_period: Option<Duration>,
     pub table_id: TableId,
     pub table_name: Arc<str>,
     pub schema: Schema,
@@ -77,8 +78,9 @@ pub struct QuerierTable {
     /// Namespace ID for this table.
     namespace_id: NamespaceId,
 
-    /// Namespace retenion
-    namespace_retention_period: Option<Duration>,
+    /// Retention period of the table, or of the namespace if the table does
+    /// not override it.
+    retention_period: Option<Duration>,
 
     /// Table name.
     table_name: Arc<str>,
@@ -105,7 +107,7 @@ impl QuerierTable {
         let QuerierTableArgs {
             namespace_id,
             namespace_name,
-            namespace_retention_period,
+            retention_period,
             table_id,
             table_name,
             schema,
@@ -117,7 +119,7 @@ impl QuerierTable {
         Self {
             namespace_name,
             namespace_id,
-            namespace_retention_period,
+            retention_period,
             table_name,
             table_id,
             schema,
@@ -142,6 +144,11 @@ impl QuerierTable {
         &self.schema
     }
 
+    /// Retention period that applies to this table.
+    pub fn retention_period(&self) -> Option<Duration> {
+        self.retention_period
+    }
+
     /// Query all chunks within this table.
     pub async fn chunks(
         &self,
@@ -357,6 +364,7 @@ impl QuerierTable {
 
         let mut should_cover: HashMap<TransitionPartitionId, HashSet<ColumnId>> =
             HashMap::with_capacity(ingester_partitions.len());
+        let mut newest_file: HashMap<TransitionPartitionId, Timestamp> = HashMap::new();
 
         // Be optimistic and assume that all partitions cover the entire columns set. This avoids resizing the HashSets.
         let n_table_cols = cached_table.column_id_map.len();
@@ -382,12 +390,20 @@ impl QuerierTable {
                 .entry(f.partition_id.clone())
                 .or_insert_with(default_hash_set)
                 .extend(f.column_set.iter().copied().filter(|id| pk.contains(id)));
+
+            // A sort key rewrite replaces all files of a partition with newer
+            // ones, sorted by a different sort key.
+            newest_file
+                .entry(f.partition_id.clone())
+                .and_modify(|t| *t = (*t).max(f.created_at))
+                .or_insert(f.created_at);
         }
 
         // batch request all partitions
         let requests = should_cover
             .into_iter()
             .map(|(id, cover)| PartitionRequest {
+                newest_file_created_at: newest_file.get(&id).copied(),
                 partition_id: id,
                 sort_key_should_cover: cover.into_iter().collect(),
             })
@@ -412,6 +428,14 @@ impl QuerierTable {
     ) -> HashMap<TransitionPartitionId, Arc<CachedPartition>> {
         let span_recorder = SpanRecorder::new(span);
 
+        // Partitions with hash-bucketed tags are pruned by mapping tag
+        // equality predicates onto their bucket, as this cannot be expressed
+        // by the column ranges used for statistics-based pruning below.
+        let partitions = partitions
+            .into_iter()
+            .filter(|p| bucket_pruning::may_match(&p.column_buckets, filters))
+            .collect::<Vec<_>>();
+
         let projections = partitions
             .iter()
             .map(|p| {
@@ -41,7 +41,7 @@ impl TableProvider for QuerierTable {
         let mut builder =
             ProviderBuilder::new(Arc::clone(self.table_name()), self.schema().clone());
 
-        let filters = match self.namespace_retention_period {
+        let filters = match self.retention_period {
             Some(d) => {
                 let ts = self
                     .chunk_adapter
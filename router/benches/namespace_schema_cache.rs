@@ -135,6 +135,8 @@ fn generate_namespace_schema(tables: usize, columns_per_table: usize) -> Namespa
             .map(|i| {
                 let schema = TableSchema {
                     id: TableId::new(i as _),
+                    retention_period_ns: None,
+                    partition_template_version: 0,
                     columns: (0..columns_per_table)
                         .map(|j| {
                             (
@@ -160,6 +162,10 @@ fn generate_namespace_schema(tables: usize, columns_per_table: usize) -> Namespa
         max_columns_per_table: MaxColumnsPerTable::try_from(i32::MAX).unwrap(),
         retention_period_ns: None,
         partition_template,
+        schema_mode: Default::default(),
+        field_coercion: Default::default(),
+        write_rules: Default::default(),
+        properties_version: 0,
     }
 }
 
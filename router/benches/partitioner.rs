@@ -170,6 +170,10 @@ fn bench(
         max_columns_per_table: MaxColumnsPerTable::try_from(1000).unwrap(),
         retention_period_ns: None,
         partition_template: partition_template.clone(),
+        schema_mode: Default::default(),
+        field_coercion: Default::default(),
+        write_rules: Default::default(),
+        properties_version: 0,
     });
 
     // Read the benchmark data
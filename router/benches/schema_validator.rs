@@ -56,6 +56,10 @@ fn bench(group: &mut BenchmarkGroup<WallTime>, tables: usize, columns_per_table:
         max_columns_per_table: MaxColumnsPerTable::try_from(42).unwrap(),
         retention_period_ns: None,
         partition_template: Default::default(),
+        schema_mode: Default::default(),
+        field_coercion: Default::default(),
+        write_rules: Default::default(),
+        properties_version: 0,
     };
     ns_cache.put_schema(NAMESPACE.clone(), namespace_schema);
 
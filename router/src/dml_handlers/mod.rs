@@ -53,6 +53,9 @@ pub mod nop;
 mod retention_validation;
 pub use retention_validation::*;
 
+mod write_rules;
+pub use write_rules::*;
+
 mod partitioner;
 pub use partitioner::*;
 
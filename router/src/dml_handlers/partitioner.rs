@@ -52,6 +52,10 @@ impl<T> Partitioned<T> {
 /// partitioned per-table [`MutableBatch`] instances according to the tables' partition templates.
 /// Deletes pass through unmodified.
 ///
+/// Each partitioned table batch carries the partition template its partition
+/// key was derived from, so that downstream ingesters can record it against
+/// the partition regardless of any concurrent change to the table template.
+///
 /// A vector of partitions are returned to the caller, or the first error that
 /// occurs during partitioning.
 #[derive(Debug, Default)]
@@ -62,7 +66,8 @@ impl DmlHandler for Partitioner {
     type WriteError = PartitionError;
 
     type WriteInput = HashMap<TableId, (String, TablePartitionTemplateOverride, MutableBatch)>;
-    type WriteOutput = Vec<Partitioned<HashMap<TableId, (String, MutableBatch)>>>;
+    type WriteOutput =
+        Vec<Partitioned<HashMap<TableId, (String, TablePartitionTemplateOverride, MutableBatch)>>>;
 
     /// Partition the per-table [`MutableBatch`].
     async fn write(
@@ -73,8 +78,10 @@ impl DmlHandler for Partitioner {
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
         // A collection of partition-keyed, per-table MutableBatch instances.
-        let mut partitions: HashMap<PartitionKey, HashMap<_, (String, MutableBatch)>> =
-            HashMap::default();
+        let mut partitions: HashMap<
+            PartitionKey,
+            HashMap<_, (String, TablePartitionTemplateOverride, MutableBatch)>,
+        > = HashMap::default();
 
         for (table_id, (table_name, table_partition_template, batch)) in batch {
             // Partition the table batch according to the configured partition
@@ -87,10 +94,17 @@ impl DmlHandler for Partitioner {
                     .raw_entry_mut()
                     .from_key(&table_id)
                     .or_insert_with(|| {
-                        (table_id, (table_name.to_owned(), MutableBatch::default()))
+                        (
+                            table_id,
+                            (
+                                table_name.to_owned(),
+                                table_partition_template.clone(),
+                                MutableBatch::default(),
+                            ),
+                        )
                     });
 
-                partition_payload.write_to_batch(&mut table_batch.1 .1)?;
+                partition_payload.write_to_batch(&mut table_batch.1 .2)?;
             }
         }
 
@@ -337,6 +351,15 @@ mod tests {
             .unwrap_or_default()
             .into_iter()
             .map(|partition| {
+                // Each table batch carries the template its key was derived
+                // from.
+                for (name, template, _) in partition.payload.values() {
+                    match name.as_str() {
+                        "bananas" => assert_eq!(*template, bananas_table_template),
+                        _ => assert_eq!(*template, TablePartitionTemplateOverride::default()),
+                    }
+                }
+
                 // Extract the table names in this partition
                 let mut tables = partition
                     .payload
@@ -426,7 +449,7 @@ mod tests {
                 // formatter output as the partition key.
                 let (key, data) = p.into_parts();
 
-                let partitioned_data = data.into_iter().map(|(_t_id, (_t_name, v))| v);
+                let partitioned_data = data.into_iter().map(|(_t_id, (_t_name, _template, v))| v);
                 for batch in partitioned_data {
                     // Validate the min/max of the batch - all other rows fall
                     // within these values.
@@ -35,7 +35,8 @@ pub enum RetentionError {
 ///
 /// Each row of data being wrote is inspected, and if any "time" column
 /// timestamp lays outside of the configured namespace retention period, the
-/// entire write is rejected.
+/// entire write is rejected. A retention period set on a table overrides that
+/// of the namespace for writes to that table.
 #[derive(Debug, Default)]
 pub struct RetentionValidator<P = SystemProvider> {
     time_provider: P,
@@ -66,11 +67,20 @@ where
         batch: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
-        // retention is not infinte, validate all lines of a write are within the retention period
-        if let Some(retention_period_ns) = namespace_schema.retention_period_ns {
-            let min_retention = self.time_provider.now().timestamp_nanos() - retention_period_ns;
-            // batch is a HashMap<tring, MutableBatch>
-            for (table_name, batch) in &batch {
+        let now = self.time_provider.now().timestamp_nanos();
+
+        // batch is a HashMap<tring, MutableBatch>
+        for (table_name, batch) in &batch {
+            // a table retention period, if set, overrides that of the namespace
+            let retention_period_ns = namespace_schema
+                .tables
+                .get(table_name)
+                .and_then(|t| t.retention_period_ns)
+                .or(namespace_schema.retention_period_ns);
+
+            // retention is not infinte, validate all lines of a write are within the retention period
+            if let Some(retention_period_ns) = retention_period_ns {
+                let min_retention = now - retention_period_ns;
                 if let Some(min) = batch.timestamp_summary().and_then(|v| v.stats.min) {
                     if min < min_retention {
                         return Err(RetentionError::OutsideRetention {
@@ -81,7 +91,7 @@ where
                     }
                 }
             }
-        };
+        }
 
         Ok(batch)
     }
@@ -238,6 +248,51 @@ mod tests {
         });
     }
 
+    #[tokio::test]
+    async fn test_table_retention_period_overrides_namespace() {
+        let namespace = test_setup().await;
+
+        // Give "bananas" a 3 hour retention period, and "apple" 30 minutes,
+        // overriding the 1 hour retention period of the namespace.
+        let bananas = namespace.create_table("bananas").await;
+        bananas
+            .update_retention_period(Some(3 * 3_600 * 1_000_000_000))
+            .await;
+        let apple = namespace.create_table("apple").await;
+        apple
+            .update_retention_period(Some(1_800 * 1_000_000_000))
+            .await;
+
+        let mock_now = iox_time::Time::from_rfc3339("2023-05-23T09:59:06+00:00").unwrap();
+        let handler = RetentionValidator {
+            time_provider: MockProvider::new(mock_now),
+        };
+
+        // Two hours ago is outside the namespace retention period, but inside
+        // that of the table.
+        let two_hours_ago = (mock_now.timestamp_nanos() - 2 * 3_600 * 1_000_000_000).to_string();
+        let writes = lp_to_writes(&("bananas,tag1=A val=42i ".to_string() + &two_hours_ago));
+        handler
+            .write(&NAMESPACE, namespace.schema().await.into(), writes, None)
+            .await
+            .expect("write within table retention period should succeed");
+
+        // Forty five minutes ago is inside the namespace retention period, but
+        // outside that of the table.
+        let forty_five_mins_ago = (mock_now.timestamp_nanos() - 2_700 * 1_000_000_000).to_string();
+        let writes = lp_to_writes(&("apple,tag1=A val=42i ".to_string() + &forty_five_mins_ago));
+        let result = handler
+            .write(&NAMESPACE, namespace.schema().await.into(), writes, None)
+            .await;
+        assert_matches!(result, Err(e) => {
+            assert_eq!(
+                e.to_string(),
+                "data in table apple is outside of the retention period: minimum \
+                 acceptable timestamp is 2023-05-23T09:29:06+00:00, but observed \
+                 timestamp 2023-05-23T09:14:06+00:00 is older.")
+        });
+    }
+
     // Parse `lp` into a table-keyed MutableBatch map.
     fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
         let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
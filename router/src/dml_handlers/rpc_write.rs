@@ -2,7 +2,9 @@ mod balancer;
 mod circuit_breaker;
 mod circuit_breaking_client;
 pub mod client;
+mod hash_ring;
 pub mod lazy_connector;
+pub mod spool;
 mod upstream_snapshot;
 
 use std::fmt::Debug;
@@ -11,15 +13,23 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use async_trait::async_trait;
-use data_types::{NamespaceName, NamespaceSchema, TableId};
+use data_types::{
+    partition_template::{TablePartitionTemplateOverride, PARTITION_BY_DAY_PROTO},
+    NamespaceName, NamespaceSchema, PartitionKey, TableId,
+};
 use dml::{DmlMeta, DmlWrite};
-use futures::{stream::FuturesUnordered, StreamExt};
-use generated_types::influxdata::iox::ingester::v1::WriteRequest;
+use futures::{stream::FuturesUnordered, StreamExt, TryStreamExt};
+use generated_types::influxdata::iox::{
+    gossip::v1::{IngesterState, IngesterStateChange},
+    ingester::v1::WriteRequest,
+};
+use gossip_ingester_state::rx::IngesterStateEventHandler;
 use hashbrown::HashMap;
 use mutable_batch::MutableBatch;
 use mutable_batch_pb::encode::encode_write;
 use observability_deps::tracing::*;
 use thiserror::Error;
+use tokio::task::JoinHandle;
 use trace::ctx::SpanContext;
 
 use self::{
@@ -27,6 +37,7 @@ use self::{
     circuit_breaker::CircuitBreaker,
     circuit_breaking_client::{CircuitBreakerState, CircuitBreakingClient},
     client::RpcWriteClientError,
+    spool::{ReplayOutcome, SpoolError, WriteSpool},
     upstream_snapshot::UpstreamSnapshot,
 };
 use super::{DmlHandler, Partitioned};
@@ -37,6 +48,9 @@ use crate::dml_handlers::rpc_write::client::WriteClient;
 /// This includes the time taken to send the request, and wait for the response.
 pub const RPC_TIMEOUT: Duration = Duration::from_secs(5);
 
+/// How often the [`WriteSpool`] (if any) is checked for writes to replay.
+const SPOOL_REPLAY_INTERVAL: Duration = Duration::from_secs(1);
+
 /// Errors experienced when submitting an RPC write request to an Ingester.
 #[derive(Debug, Error)]
 pub enum RpcWriteError {
@@ -71,14 +85,77 @@ pub enum RpcWriteError {
         /// The number of successful upstream ingester writes.
         acks: usize,
     },
+
+    /// The upstream ingester rejected the write because it would create new
+    /// series beyond the configured per-table series limit.
+    #[error("table series limit exceeded: {0}")]
+    SeriesLimit(String),
+
+    /// No upstream ingesters were available, and the write could not be
+    /// added to the [`WriteSpool`].
+    #[error(transparent)]
+    Spool(#[from] SpoolError),
+}
+
+impl RpcWriteError {
+    /// Returns `false` if this error was caused by the content of the write,
+    /// and sending the same write again will fail in the same way.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            Self::SeriesLimit(_) => false,
+            Self::Client(e) => e.is_retryable(),
+            Self::Timeout(_)
+            | Self::NoHealthyUpstreams
+            | Self::NotEnoughReplicas
+            | Self::PartialWrite { .. }
+            | Self::Spool(_) => true,
+        }
+    }
+}
+
+/// The strategy used by an [`RpcWrite`] handler to select the upstream
+/// ingesters a write is sent to.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum BalancerStrategy {
+    /// Distribute writes approximately uniformly across all healthy upstream
+    /// ingesters, with no data locality.
+    #[default]
+    RoundRobin,
+
+    /// Consistently route all writes for a given (namespace, table, partition
+    /// key) to the same upstream ingester(s), failing over to the next
+    /// ingester on the hash ring when unavailable.
+    ConsistentHash,
+}
+
+/// The key used to map a table write onto an upstream ingester when using
+/// [`BalancerStrategy::ConsistentHash`].
+///
+/// The derived hash impl is hardened against prefix collisions when combining
+/// the fields.
+#[derive(Debug, Hash)]
+struct RoutingKey<'a> {
+    namespace: &'a str,
+    table: &'a str,
+    partition_key: &'a str,
 }
 
 /// An [`RpcWrite`] handler submits a write directly to an Ingester via the
 /// [gRPC write service].
 ///
-/// Requests are sent to an arbitrary downstream Ingester, and request load is
-/// distributed approximately uniformly across all downstream Ingesters. There
-/// is no effort made to enforce or attempt data locality.
+/// # Balancing
+///
+/// With [`BalancerStrategy::RoundRobin`], requests are sent to an arbitrary
+/// downstream Ingester, and request load is distributed approximately uniformly
+/// across all downstream Ingesters. There is no effort made to enforce or
+/// attempt data locality.
+///
+/// With [`BalancerStrategy::ConsistentHash`], each table write is mapped to an
+/// upstream using a consistent hash of its (namespace, table, partition key).
+/// A write spanning tables that map to different upstreams is split into one
+/// request per upstream, and the write fails if any of these requests fail
+/// (potentially after some have succeeded). The replicas of a write are the
+/// next healthy ingesters on the hash ring.
 ///
 /// # Replication
 ///
@@ -90,6 +167,17 @@ pub enum RpcWriteError {
 /// satisfy the desired replication factor will be considered a partial failure,
 /// and a [`RpcWriteError::PartialWrite`] error is returned.
 ///
+/// # Spooling
+///
+/// If configured with a [`WriteSpool`] (see [`RpcWrite::with_spool()`]), writes
+/// are durably spooled to local disk instead of being rejected when no upstream
+/// ingester is available (all circuit breakers are open). Spooled writes are
+/// replayed in order by a background task once an upstream recovers.
+///
+/// To maintain write ordering, new writes are also spooled while the spool
+/// contains writes waiting to be replayed. Replayed writes are distributed
+/// without regard for the [`BalancerStrategy`].
+///
 /// # Deletes
 ///
 /// This handler drops delete requests, logging the attempt and returning an
@@ -98,7 +186,7 @@ pub enum RpcWriteError {
 /// [gRPC write service]: client::WriteClient
 #[derive(Debug)]
 pub struct RpcWrite<T, C = CircuitBreaker> {
-    endpoints: Balancer<T, C>,
+    endpoints: Arc<Balancer<T, C>>,
 
     /// The total number of distinct upstream ingesters to write a single
     /// request to.
@@ -112,13 +200,20 @@ pub struct RpcWrite<T, C = CircuitBreaker> {
     /// may NACK a write, having already buffered the data. When this request is
     /// retried, the data will be duplicated.
     n_copies: usize,
+
+    /// An optional durable spool for writes that cannot be sent to any
+    /// upstream.
+    spool: Option<Arc<WriteSpool>>,
+
+    /// The handle to the background task replaying writes from `spool`.
+    replay_task: Option<JoinHandle<()>>,
 }
 
 impl<T> RpcWrite<T> {
-    /// Initialise a new [`RpcWrite`] that sends requests to an arbitrary
-    /// downstream Ingester, using a round-robin strategy. Health checks are
-    /// configured by `error_window` and `num_probes` as laid out by the
-    /// documentation for [`CircuitBreaker`].
+    /// Initialise a new [`RpcWrite`] that sends requests to downstream
+    /// Ingesters selected by `strategy`. Health checks are configured by
+    /// `error_window` and `num_probes` as laid out by the documentation for
+    /// [`CircuitBreaker`].
     ///
     /// If [`Some`], `replica_copies` specifies the number of additional
     /// upstream ingesters that must receive and acknowledge the write for it to
@@ -131,6 +226,7 @@ impl<T> RpcWrite<T> {
     pub fn new<N>(
         endpoints: impl IntoIterator<Item = (T, N)>,
         n_copies: NonZeroUsize,
+        strategy: BalancerStrategy,
         metrics: &metric::Registry,
         num_probes: u64,
     ) -> Self
@@ -149,7 +245,7 @@ impl<T> RpcWrite<T> {
         // write a success.
         let n_copies = n_copies.get();
 
-        debug!(n_copies, "write replication factor");
+        debug!(n_copies, ?strategy, "write replication factor");
 
         // Assert this configuration is not impossible to satisfy.
         assert!(
@@ -159,9 +255,93 @@ impl<T> RpcWrite<T> {
             count = endpoints.len(),
         );
 
+        let endpoints = match strategy {
+            BalancerStrategy::RoundRobin => endpoints,
+            BalancerStrategy::ConsistentHash => endpoints.with_consistent_hashing(metrics),
+        };
+
         Self {
-            endpoints,
+            endpoints: Arc::new(endpoints),
             n_copies,
+            spool: None,
+            replay_task: None,
+        }
+    }
+}
+
+impl<T, C> RpcWrite<T, C>
+where
+    T: WriteClient + 'static,
+    C: CircuitBreakerState + 'static,
+{
+    /// Spool writes to `spool` when no upstream ingesters are available,
+    /// replaying them in the background once an upstream recovers.
+    pub fn with_spool(mut self, spool: Arc<WriteSpool>) -> Self {
+        if let Some(t) = self.replay_task.take() {
+            t.abort();
+        }
+        self.replay_task = Some(tokio::spawn(replay_task(
+            Arc::clone(&spool),
+            Arc::clone(&self.endpoints),
+            self.n_copies,
+        )));
+        self.spool = Some(spool);
+        self
+    }
+}
+
+impl<T, C> RpcWrite<T, C> {
+    /// Return a handle that applies upstream ingester state changes received
+    /// over gossip to this [`RpcWrite`].
+    pub fn ingester_state_handler(&self) -> IngesterStateHandler<T, C> {
+        IngesterStateHandler {
+            endpoints: Arc::clone(&self.endpoints),
+        }
+    }
+}
+
+/// An [`IngesterStateEventHandler`] that stops writes being sent to upstream
+/// ingesters that announce they are draining (handing off their buffered data
+/// to a peer before shutting down), until they announce they are active again.
+///
+/// Upstreams are identified by the address announced by the ingester, which
+/// must match the configured upstream address exactly.
+#[derive(Debug)]
+pub struct IngesterStateHandler<T, C = CircuitBreaker> {
+    endpoints: Arc<Balancer<T, C>>,
+}
+
+#[async_trait]
+impl<T, C> IngesterStateEventHandler for IngesterStateHandler<T, C>
+where
+    T: Send + Sync + Debug + 'static,
+    C: CircuitBreakerState + 'static,
+{
+    async fn handle(&self, event: IngesterStateChange) {
+        let draining = match event.state() {
+            IngesterState::Draining => true,
+            IngesterState::Active => false,
+            IngesterState::Unspecified => {
+                warn!(address=%event.address, "ignoring unspecified ingester state");
+                return;
+            }
+        };
+
+        if self.endpoints.set_draining(&event.address, draining) {
+            info!(address=%event.address, draining, "upstream ingester state changed");
+        } else {
+            debug!(
+                address=%event.address,
+                "ignoring state change for unknown upstream ingester"
+            );
+        }
+    }
+}
+
+impl<T, C> Drop for RpcWrite<T, C> {
+    fn drop(&mut self) {
+        if let Some(t) = self.replay_task.take() {
+            t.abort();
         }
     }
 }
@@ -172,7 +352,8 @@ where
     T: WriteClient + 'static,
     C: CircuitBreakerState + 'static,
 {
-    type WriteInput = Partitioned<HashMap<TableId, (String, MutableBatch)>>;
+    type WriteInput =
+        Partitioned<HashMap<TableId, (String, TablePartitionTemplateOverride, MutableBatch)>>;
     type WriteOutput = Vec<DmlMeta>;
 
     type WriteError = RpcWriteError;
@@ -184,15 +365,71 @@ where
         writes: Self::WriteInput,
         span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, RpcWriteError> {
-        let namespace_id = namespace_schema.id;
         // Extract the partition key & DML writes.
         let (partition_key, writes) = writes.into_parts();
 
-        // Drop the table names from the value tuple.
-        let writes = writes
+        // Group the table writes by the hash ring position of the upstream
+        // they are routed to, dropping the table names from the value tuple.
+        //
+        // When not using consistent hashing, all writes are placed in a single
+        // group and sent in one request.
+        let mut groups: HashMap<
+            Option<usize>,
+            HashMap<TableId, (TablePartitionTemplateOverride, MutableBatch)>,
+        > = HashMap::new();
+        for (id, (name, template, data)) in writes {
+            let position = self.endpoints.ring_position(RoutingKey {
+                namespace: namespace.as_str(),
+                table: &name,
+                partition_key: partition_key.inner(),
+            });
+            groups
+                .entry(position)
+                .or_default()
+                .insert(id, (template, data));
+        }
+
+        groups
+            .into_iter()
+            .map(|(position, writes)| {
+                self.write_group(
+                    namespace,
+                    &namespace_schema,
+                    partition_key.clone(),
+                    position,
+                    writes,
+                    span_ctx.clone(),
+                )
+            })
+            .collect::<FuturesUnordered<_>>()
+            .try_collect::<Vec<_>>()
+            .await
+    }
+}
+
+impl<T, C> RpcWrite<T, C>
+where
+    T: WriteClient + 'static,
+    C: CircuitBreakerState + 'static,
+{
+    /// Replicate the table `writes` to `n_copies` upstreams, starting at the
+    /// hash ring `position` if [`Some`], or at an arbitrary upstream
+    /// otherwise.
+    async fn write_group(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: &NamespaceSchema,
+        partition_key: PartitionKey,
+        position: Option<usize>,
+        writes: HashMap<TableId, (TablePartitionTemplateOverride, MutableBatch)>,
+        span_ctx: Option<SpanContext>,
+    ) -> Result<DmlMeta, RpcWriteError> {
+        let namespace_id = namespace_schema.id;
+
+        let (templates, writes): (HashMap<_, _>, HashMap<_, _>) = writes
             .into_iter()
-            .map(|(id, (_name, data))| (id, data))
-            .collect();
+            .map(|(id, (template, data))| ((id, template), (id, data)))
+            .unzip();
 
         // Build the DmlWrite
         let op = DmlWrite::new(
@@ -205,88 +442,57 @@ where
         );
 
         // Serialise this write into the wire format.
+        //
+        // Each table batch carries the partition template its partition key
+        // was derived from, allowing the ingester to record it against the
+        // partition even if the table template has since been changed.
+        let mut payload = encode_write(namespace_id.get(), &op);
+        for batch in &mut payload.table_batches {
+            let template = templates
+                .get(&TableId::new(batch.table_id))
+                .expect("encoded table batch must have a partition template");
+            batch.partition_template = Some(
+                template
+                    .as_proto()
+                    .cloned()
+                    .unwrap_or_else(|| (**PARTITION_BY_DAY_PROTO).clone()),
+            );
+        }
         let req = WriteRequest {
-            payload: Some(encode_write(namespace_id.get(), &op)),
+            payload: Some(payload),
         };
 
-        // Obtain a snapshot of currently-healthy upstreams (and potentially
-        // some that need probing).
-        let snap = self
-            .endpoints
-            .endpoints()
-            .ok_or(RpcWriteError::NoHealthyUpstreams)?;
-
-        // It's possible the set of endpoints may contain fewer upstreams than
-        // necessary for the write request to succeed (N < replication factor).
-        //
-        // If this occurs, the write can be aborted early, placing no load on
-        // the upstreams that appear in the snapshot iff the snapshot contains
-        // no probe requests.
-        //
-        // If the snapshot contains upstreams selected for health probe
-        // requests, the probes MUST be sent to drive recovery of the upstream,
-        // even if this write request is doomed to fail due to insufficient
-        // upstreams.
-        if snap.initial_len() < self.n_copies && !snap.contains_probe() {
-            return Err(RpcWriteError::NotEnoughReplicas);
+        // Preserve write ordering by spooling this write if there are already
+        // writes waiting to be replayed.
+        if let Some(spool) = self.spool.as_ref().filter(|s| s.has_pending()) {
+            spool.append(req.payload.expect("payload is set")).await?;
+            debug!(%namespace, %namespace_id, "spooled write behind pending writes");
+            return Ok(op.meta().clone());
         }
 
-        // Concurrently write to the required number of replicas to reach the
-        // desired replication factor.
-        //
-        // These write attempts are coupled to the client - if the client
-        // disconnects, the writes are aborted (potentially after already
-        // succeeding).
-        //
-        // While probe requests MUST be sent to drive recovery detection, and an
-        // early disconnect aborts the writes and prevents this process from
-        // completing, the probability game saves us from having to optimise
-        // this further - for a meaningful write workload, eventually enough
-        // client will perform probe writes to completion and drive health
-        // discovery.
-        let mut result_stream = (0..self.n_copies)
-            .map(|_| {
-                // Acquire a request-scoped snapshot that synchronises with
-                // other clone instances to uphold the disjoint replica hosts
-                // invariant.
-                let mut snap = snap.clone();
-                let req = req.clone();
-                let span_ctx = span_ctx.clone();
-                async move { write_loop(&mut snap, &req, span_ctx).await }
-            })
-            .collect::<FuturesUnordered<_>>()
-            .enumerate();
+        // Obtain a snapshot of currently-healthy upstreams (and potentially
+        // some that need probing).
+        let snap = match position {
+            // Each table in the group is a write mapped to `position`.
+            Some(position) => self
+                .endpoints
+                .endpoints_from(position, op.table_count() as u64),
+            None => self.endpoints.endpoints(),
+        };
 
-        // Consume the result stream, eagerly returning if an error is observed.
-        //
-        // Because partial writes have different semantics to outright failures
-        // (principally that you may expect your write to turn up in queries,
-        // even though the overall request failed), return a PartialWrite error
-        // if at least one write success has been observed.
-        //
-        // This is best-effort! It's always possible that PartialWrite is not
-        // returned, even though a partial write has occurred (for example, the
-        // next result in the stream is an already-completed write ACK).
-        while let Some((i, res)) = result_stream.next().await {
-            match res {
-                Ok(_) => {}
-                Err(_e) if i > 0 => {
-                    // In all cases, if at least one write succeeded, then this
-                    // becomes a partial write error.
-                    return Err(RpcWriteError::PartialWrite {
-                        want_n_copies: self.n_copies,
-                        acks: i,
-                    });
-                }
-                Err(RpcWriteError::Client(_)) => {
-                    // This error is an internal implementation detail - the
-                    // meaningful error for the user is "there's no healthy
-                    // upstreams".
-                    return Err(RpcWriteError::NoHealthyUpstreams);
-                }
-                Err(e) => return Err(e),
+        let snap = match (snap, &self.spool) {
+            (Some(v), _) => v,
+            (None, Some(spool)) => {
+                // There are no upstreams available to accept this write - spool
+                // it for replay once they recover.
+                spool.append(req.payload.expect("payload is set")).await?;
+                warn!(%namespace, %namespace_id, "no healthy upstreams, spooled write");
+                return Ok(op.meta().clone());
             }
-        }
+            (None, None) => return Err(RpcWriteError::NoHealthyUpstreams),
+        };
+
+        replicate(snap, self.n_copies, &req, span_ctx).await?;
 
         debug!(
             %partition_key,
@@ -297,7 +503,140 @@ where
             "dispatched write to ingester"
         );
 
-        Ok(vec![op.meta().clone()])
+        Ok(op.meta().clone())
+    }
+}
+
+/// Replicate `req` to `n_copies` distinct upstreams from `snap`.
+async fn replicate<T>(
+    snap: UpstreamSnapshot<T>,
+    n_copies: usize,
+    req: &WriteRequest,
+    span_ctx: Option<SpanContext>,
+) -> Result<(), RpcWriteError>
+where
+    T: WriteClient,
+{
+    // It's possible the set of endpoints may contain fewer upstreams than
+    // necessary for the write request to succeed (N < replication factor).
+    //
+    // If this occurs, the write can be aborted early, placing no load on
+    // the upstreams that appear in the snapshot iff the snapshot contains
+    // no probe requests.
+    //
+    // If the snapshot contains upstreams selected for health probe
+    // requests, the probes MUST be sent to drive recovery of the upstream,
+    // even if this write request is doomed to fail due to insufficient
+    // upstreams.
+    if snap.initial_len() < n_copies && !snap.contains_probe() {
+        return Err(RpcWriteError::NotEnoughReplicas);
+    }
+
+    // Concurrently write to the required number of replicas to reach the
+    // desired replication factor.
+    //
+    // These write attempts are coupled to the client - if the client
+    // disconnects, the writes are aborted (potentially after already
+    // succeeding).
+    //
+    // While probe requests MUST be sent to drive recovery detection, and an
+    // early disconnect aborts the writes and prevents this process from
+    // completing, the probability game saves us from having to optimise
+    // this further - for a meaningful write workload, eventually enough
+    // client will perform probe writes to completion and drive health
+    // discovery.
+    let mut result_stream = (0..n_copies)
+        .map(|_| {
+            // Acquire a request-scoped snapshot that synchronises with
+            // other clone instances to uphold the disjoint replica hosts
+            // invariant.
+            let mut snap = snap.clone();
+            let req = req.clone();
+            let span_ctx = span_ctx.clone();
+            async move { write_loop(&mut snap, &req, span_ctx).await }
+        })
+        .collect::<FuturesUnordered<_>>()
+        .enumerate();
+
+    // Consume the result stream, eagerly returning if an error is observed.
+    //
+    // Because partial writes have different semantics to outright failures
+    // (principally that you may expect your write to turn up in queries,
+    // even though the overall request failed), return a PartialWrite error
+    // if at least one write success has been observed.
+    //
+    // This is best-effort! It's always possible that PartialWrite is not
+    // returned, even though a partial write has occurred (for example, the
+    // next result in the stream is an already-completed write ACK).
+    while let Some((i, res)) = result_stream.next().await {
+        match res {
+            Ok(_) => {}
+            Err(_e) if i > 0 => {
+                // In all cases, if at least one write succeeded, then this
+                // becomes a partial write error.
+                return Err(RpcWriteError::PartialWrite {
+                    want_n_copies: n_copies,
+                    acks: i,
+                });
+            }
+            Err(RpcWriteError::Client(_)) => {
+                // This error is an internal implementation detail - the
+                // meaningful error for the user is "there's no healthy
+                // upstreams".
+                return Err(RpcWriteError::NoHealthyUpstreams);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+
+    Ok(())
+}
+
+/// Periodically replay any writes in `spool` to the upstreams in
+/// `endpoints`, in the order they were spooled.
+///
+/// Replay of the spool stops at the first write that cannot be replicated to
+/// `n_copies` upstreams, and is retried after [`SPOOL_REPLAY_INTERVAL`].
+/// Writes rejected with a non-retryable error are dropped instead.
+async fn replay_task<T, C>(spool: Arc<WriteSpool>, endpoints: Arc<Balancer<T, C>>, n_copies: usize)
+where
+    T: WriteClient + 'static,
+    C: CircuitBreakerState + 'static,
+{
+    let mut tick = tokio::time::interval(SPOOL_REPLAY_INTERVAL);
+    tick.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tick.tick().await;
+
+        if !spool.has_pending() {
+            continue;
+        }
+
+        let res = spool
+            .replay(|req| {
+                let snap = endpoints.endpoints();
+                async move {
+                    let snap = snap.ok_or(RpcWriteError::NoHealthyUpstreams)?;
+                    match replicate(snap, n_copies, &req, None).await {
+                        Ok(()) => Ok(ReplayOutcome::Written),
+                        Err(e) if !e.is_retryable() => {
+                            // This write will never be accepted - drop it
+                            // rather than blocking replay of the writes behind
+                            // it.
+                            warn!(error=%e, "dropping rejected spooled write");
+                            Ok(ReplayOutcome::Dropped)
+                        }
+                        Err(e) => Err(e),
+                    }
+                }
+            })
+            .await;
+
+        match res {
+            Ok(()) => info!("replayed all spooled writes"),
+            Err(e) => debug!(error=%e, "failed to replay spooled writes"),
+        }
     }
 }
 
@@ -307,7 +646,8 @@ where
 /// This write attempt is bounded in time to at most [`RPC_TIMEOUT`].
 ///
 /// If at least one upstream request has failed (returning an error), the most
-/// recent error is returned.
+/// recent error is returned. A write rejected with a non-retryable error (such
+/// as the upstream series limit) is not retried.
 ///
 /// # Panics
 ///
@@ -337,6 +677,16 @@ where
                     endpoints.remove(client);
                     return Ok(());
                 }
+                Err(RpcWriteClientError::SeriesLimit(msg)) => {
+                    // The write will be rejected by every upstream, so do not
+                    // retry it.
+                    return Err(RpcWriteError::SeriesLimit(msg));
+                }
+                Err(e) if !e.is_retryable() => {
+                    // As above, every upstream would reject this write.
+                    warn!(error=%e, "ingester rejected rpc write");
+                    return Err(RpcWriteError::Client(e));
+                }
                 Err(e) => {
                     warn!(error=%e, "failed ingester rpc write");
                     last_err = Some(e);
@@ -380,6 +730,7 @@ mod tests {
     use data_types::{NamespaceId, PartitionKey};
     use proptest::{prelude::*, prop_compose, proptest};
     use rand::seq::SliceRandom;
+    use test_helpers::timeout::FutureTimeout;
     use tokio::runtime;
 
     use crate::{
@@ -390,14 +741,16 @@ mod tests {
     use super::{client::mock::MockWriteClient, *};
 
     // Parse `lp` into a table-keyed MutableBatch map.
-    pub(crate) fn lp_to_writes(lp: &str) -> HashMap<TableId, (String, MutableBatch)> {
+    pub(crate) fn lp_to_writes(
+        lp: &str,
+    ) -> HashMap<TableId, (String, TablePartitionTemplateOverride, MutableBatch)> {
         let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
             .expect("failed to build test writes from LP");
 
         writes
             .into_iter()
             .enumerate()
-            .map(|(i, (name, data))| (TableId::new(i as _), (name, data)))
+            .map(|(i, (name, data))| (TableId::new(i as _), (name, Default::default(), data)))
             .collect()
     }
 
@@ -416,8 +769,10 @@ mod tests {
         C: CircuitBreakerState + 'static,
     {
         let handler = RpcWrite {
-            endpoints: Balancer::new(endpoints, None),
+            endpoints: Arc::new(Balancer::new(endpoints, None)),
             n_copies,
+            spool: None,
+            replay_task: None,
         };
 
         assert!(
@@ -468,6 +823,7 @@ mod tests {
         let handler = RpcWrite::new(
             [(Arc::clone(&client), "mock client")],
             1.try_into().unwrap(),
+            BalancerStrategy::RoundRobin,
             &metric::Registry::default(),
             ARBITRARY_TEST_NUM_PROBES,
         );
@@ -495,6 +851,15 @@ mod tests {
         assert_eq!(payload.partition_key, "2022-01-01");
         assert_eq!(payload.table_batches.len(), 4);
 
+        // The tables have no partition template override, so their keys are
+        // derived from the default template.
+        for batch in &payload.table_batches {
+            assert_eq!(
+                batch.partition_template.as_ref(),
+                Some(&**PARTITION_BY_DAY_PROTO)
+            );
+        }
+
         let got_tables = payload
             .table_batches
             .into_iter()
@@ -503,12 +868,80 @@ mod tests {
 
         let want_tables = batches
             .into_iter()
-            .map(|(id, (_name, _data))| id.get())
+            .map(|(id, (_name, _template, _data))| id.get())
             .collect::<HashSet<_>>();
 
         assert_eq!(got_tables, want_tables);
     }
 
+    /// With consistent hashing, writes for the same table and partition are
+    /// always routed to the same upstream, and multi-table writes are split
+    /// across upstreams.
+    #[tokio::test]
+    async fn test_write_consistent_hash() {
+        let lp = "\
+            bananas,tag1=A,tag2=B val=42i 1\n\
+            platanos,tag1=A,tag2=B value=42i 2\n\
+            another,tag1=A,tag2=B value=42i 3\n\
+            table,tag1=A,tag2=B val=42i 1\n\
+        ";
+
+        let clients = (0..3)
+            .map(|_| Arc::new(MockWriteClient::default()))
+            .collect::<Vec<_>>();
+        let handler = RpcWrite::new(
+            clients
+                .iter()
+                .enumerate()
+                .map(|(i, c)| (Arc::clone(c), format!("client{i}"))),
+            1.try_into().unwrap(),
+            BalancerStrategy::ConsistentHash,
+            &metric::Registry::default(),
+            ARBITRARY_TEST_NUM_PROBES,
+        );
+
+        // Returns the set of table IDs observed by each client.
+        let tables_per_client = || {
+            clients
+                .iter()
+                .map(|c| {
+                    c.calls()
+                        .into_iter()
+                        .flat_map(|call| call.payload.unwrap().table_batches)
+                        .map(|t| t.table_id)
+                        .collect::<HashSet<_>>()
+                })
+                .collect::<Vec<_>>()
+        };
+
+        for _ in 0..5 {
+            let input = Partitioned::new(PartitionKey::from("2022-01-01"), lp_to_writes(lp));
+            let got = handler
+                .write(
+                    &NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                    Arc::new(new_empty_namespace_schema(NAMESPACE_ID.get())),
+                    input,
+                    None,
+                )
+                .await
+                .expect("write should succeed");
+
+            // One op per upstream written to.
+            let want_ops = tables_per_client().iter().filter(|t| !t.is_empty()).count();
+            assert_eq!(got.len(), want_ops);
+        }
+
+        // Each table was only ever sent to exactly one upstream.
+        let tables = tables_per_client();
+        assert_eq!(tables.iter().map(|t| t.len()).sum::<usize>(), 4);
+
+        // And every request contained all the data for that upstream.
+        for c in &clients {
+            let calls = c.calls();
+            assert!(calls.is_empty() || calls.len() == 5);
+        }
+    }
+
     /// Ensure all candidates returned by the balancer are tried, aborting after
     /// the first successful request.
     #[tokio::test]
@@ -531,6 +964,7 @@ mod tests {
                 (Arc::clone(&client3), "client3"),
             ],
             1.try_into().unwrap(),
+            BalancerStrategy::RoundRobin,
             &metric::Registry::default(),
             ARBITRARY_TEST_NUM_PROBES,
         );
@@ -569,7 +1003,7 @@ mod tests {
 
         let want_tables = batches
             .into_iter()
-            .map(|(id, (_name, _data))| id.get())
+            .map(|(id, (_name, _template, _data))| id.get())
             .collect::<HashSet<_>>();
 
         assert_eq!(got_tables, want_tables);
@@ -600,6 +1034,7 @@ mod tests {
                 (Arc::clone(&client2), "client2"),
             ],
             1.try_into().unwrap(),
+            BalancerStrategy::RoundRobin,
             &metric::Registry::default(),
             ARBITRARY_TEST_NUM_PROBES,
         );
@@ -635,7 +1070,7 @@ mod tests {
 
         let want_tables = batches
             .into_iter()
-            .map(|(id, (_name, _data))| id.get())
+            .map(|(id, (_name, _template, _data))| id.get())
             .collect::<HashSet<_>>();
 
         assert_eq!(got_tables, want_tables);
@@ -663,6 +1098,133 @@ mod tests {
         assert_matches!(got, Err(RpcWriteError::NoHealthyUpstreams));
     }
 
+    /// When no upstreams are healthy and a spool is configured, writes are
+    /// spooled and replayed once the upstream recovers.
+    #[tokio::test]
+    async fn test_write_spooled_when_no_healthy_upstreams() {
+        let client = Arc::new(MockWriteClient::default());
+        let circuit = Arc::new(MockCircuitBreaker::default());
+        circuit.set_healthy(false);
+        circuit.set_should_probe(false);
+
+        let dir = test_helpers::tmp_dir().unwrap();
+        let spool = Arc::new(
+            WriteSpool::new(dir.path(), u64::MAX, &metric::Registry::default())
+                .await
+                .unwrap(),
+        );
+
+        let handler = RpcWrite {
+            endpoints: Arc::new(Balancer::new(
+                [CircuitBreakingClient::new(
+                    Arc::clone(&client),
+                    "client",
+                    ARBITRARY_TEST_NUM_PROBES,
+                )
+                .with_circuit_breaker(Arc::clone(&circuit))],
+                None,
+            )),
+            n_copies: 1,
+            spool: None,
+            replay_task: None,
+        }
+        .with_spool(Arc::clone(&spool));
+
+        let input = Partitioned::new(
+            PartitionKey::from("2022-01-01"),
+            lp_to_writes("bananas,tag1=A,tag2=B val=42i 1"),
+        );
+        handler
+            .write(
+                &NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                Arc::new(new_empty_namespace_schema(NAMESPACE_ID.get())),
+                input,
+                None,
+            )
+            .await
+            .expect("write should be spooled");
+
+        assert!(spool.has_pending());
+        assert!(client.calls().is_empty());
+
+        // Once the upstream recovers, the spooled write is replayed.
+        circuit.set_healthy(true);
+        async {
+            while spool.has_pending() {
+                tokio::time::sleep(Duration::from_millis(50)).await;
+            }
+        }
+        .with_timeout_panic(Duration::from_secs(5))
+        .await;
+
+        let calls = client.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(
+            calls[0].payload.as_ref().unwrap().partition_key,
+            "2022-01-01"
+        );
+    }
+
+    /// Upstreams announcing they are draining receive no writes until they
+    /// announce they are active again.
+    #[tokio::test]
+    async fn test_write_skips_draining_upstream() {
+        let client_1 = Arc::new(MockWriteClient::default());
+        let client_2 = Arc::new(MockWriteClient::default());
+        let handler = RpcWrite::new(
+            [
+                (Arc::clone(&client_1), "http://ingester-1:8083"),
+                (Arc::clone(&client_2), "http://ingester-2:8083"),
+            ],
+            1.try_into().unwrap(),
+            BalancerStrategy::RoundRobin,
+            &metric::Registry::default(),
+            ARBITRARY_TEST_NUM_PROBES,
+        );
+        let state = handler.ingester_state_handler();
+
+        async fn write(handler: &RpcWrite<Arc<MockWriteClient>>) -> Result<(), RpcWriteError> {
+            let input = Partitioned::new(
+                PartitionKey::from("2022-01-01"),
+                lp_to_writes("bananas,tag1=A,tag2=B val=42i 1"),
+            );
+            handler
+                .write(
+                    &NamespaceName::new(NAMESPACE_NAME).unwrap(),
+                    Arc::new(new_empty_namespace_schema(NAMESPACE_ID.get())),
+                    input,
+                    None,
+                )
+                .await
+                .map(|_| ())
+        }
+
+        state
+            .handle(IngesterStateChange {
+                address: "http://ingester-1:8083".to_string(),
+                state: IngesterState::Draining.into(),
+            })
+            .await;
+
+        for _ in 0..10 {
+            write(&handler).await.expect("write should succeed");
+        }
+        assert!(client_1.calls().is_empty());
+        assert_eq!(client_2.calls().len(), 10);
+
+        state
+            .handle(IngesterStateChange {
+                address: "http://ingester-1:8083".to_string(),
+                state: IngesterState::Active.into(),
+            })
+            .await;
+
+        for _ in 0..10 {
+            write(&handler).await.expect("write should succeed");
+        }
+        assert!(!client_1.calls().is_empty());
+    }
+
     /// Assert the error response when the only upstream continuously returns an
     /// error.
     #[tokio::test]
@@ -687,6 +1249,68 @@ mod tests {
         assert_matches!(got, Err(RpcWriteError::NoHealthyUpstreams));
     }
 
+    /// Assert that a write rejected by the upstream series limit is returned
+    /// to the caller without being retried.
+    #[tokio::test]
+    async fn test_write_series_limit_not_retried() {
+        let client_1 = Arc::new(MockWriteClient::default().with_ret(iter::repeat_with(|| {
+            Err(RpcWriteClientError::SeriesLimit("bananas".to_string()))
+        })));
+        let circuit_1 = Arc::new(MockCircuitBreaker::default());
+        circuit_1.set_healthy(true);
+
+        let got = make_request(
+            [CircuitBreakingClient::new(
+                Arc::clone(&client_1),
+                "client_1",
+                ARBITRARY_TEST_NUM_PROBES,
+            )
+            .with_circuit_breaker(circuit_1)],
+            1,
+        )
+        .await;
+
+        assert_matches!(got, Err(RpcWriteError::SeriesLimit(msg)) => {
+            assert_eq!(msg, "bananas");
+        });
+        assert_eq!(client_1.calls().len(), 1);
+    }
+
+    /// Assert that a write the upstream rejects as invalid is returned to the
+    /// caller without being retried, and is classified as non-retryable.
+    #[tokio::test]
+    async fn test_write_invalid_not_retried() {
+        let client_1 = Arc::new(MockWriteClient::default().with_ret(iter::repeat_with(|| {
+            Err(RpcWriteClientError::Upstream(
+                tonic::Status::invalid_argument("bananas"),
+            ))
+        })));
+        let circuit_1 = Arc::new(MockCircuitBreaker::default());
+        circuit_1.set_healthy(true);
+
+        let got = make_request(
+            [CircuitBreakingClient::new(
+                Arc::clone(&client_1),
+                "client_1",
+                ARBITRARY_TEST_NUM_PROBES,
+            )
+            .with_circuit_breaker(circuit_1)],
+            1,
+        )
+        .await;
+
+        assert_matches!(got, Err(e) => {
+            assert!(!e.is_retryable());
+        });
+        assert_eq!(client_1.calls().len(), 1);
+        assert!(
+            RpcWriteError::Client(RpcWriteClientError::Upstream(tonic::Status::internal(
+                "bananas"
+            )))
+            .is_retryable()
+        );
+    }
+
     /// Assert that an [`RpcWriteClientError::UpstreamNotConnected`] error is mapped
     /// to a user-friendly [`RpcWriteError::NoUpstreams`] for consistency.
     #[tokio::test]
@@ -1,4 +1,6 @@
-use std::{borrow::Cow, cell::RefCell, cmp::max, fmt::Debug, sync::Arc, time::Duration};
+use std::{
+    borrow::Cow, cell::RefCell, cmp::max, fmt::Debug, hash::Hash, sync::Arc, time::Duration,
+};
 
 use futures::Future;
 use metric::U64Gauge;
@@ -8,6 +10,7 @@ use tokio::task::JoinHandle;
 use super::{
     circuit_breaker::CircuitBreaker,
     circuit_breaking_client::{CircuitBreakerState, CircuitBreakingClient},
+    hash_ring::HashRing,
     upstream_snapshot::UpstreamSnapshot,
 };
 
@@ -22,7 +25,8 @@ thread_local! {
 const METRIC_EVAL_INTERVAL: Duration = Duration::from_secs(3);
 
 /// A set of health-checked gRPC endpoints, with an approximate round-robin
-/// distribution of load over healthy nodes.
+/// distribution of load over healthy nodes, or optionally a consistent hash
+/// mapping of routing keys to nodes.
 ///
 /// # Health Checking
 ///
@@ -34,6 +38,12 @@ const METRIC_EVAL_INTERVAL: Duration = Duration::from_secs(3);
 /// Requests are distributed uniformly across all endpoints **per thread**. Given
 /// enough requests (where `N` is significantly larger than the number of
 /// threads) an approximately uniform distribution is achieved.
+///
+/// When configured with [`Balancer::with_consistent_hashing()`], callers may
+/// instead map a routing key to a [`HashRing`] position with
+/// [`Balancer::ring_position()`], and obtain the endpoints for that key in ring
+/// order with [`Balancer::endpoints_from()`]. This places all requests for the
+/// same key on the same node(s) while they remain healthy.
 #[derive(Debug)]
 pub(super) struct Balancer<T, C = CircuitBreaker> {
     endpoints: Arc<[Arc<CircuitBreakingClient<T, C>>]>,
@@ -41,6 +51,10 @@ pub(super) struct Balancer<T, C = CircuitBreaker> {
     /// An optional metric exporter task that evaluates the state of this
     /// [`Balancer`] every [`METRIC_EVAL_INTERVAL`].
     metric_task: Option<JoinHandle<()>>,
+
+    /// The consistent hash ring used to map routing keys to endpoints, if
+    /// configured.
+    ring: Option<HashRing>,
 }
 
 impl<T, C> Balancer<T, C>
@@ -58,14 +72,46 @@ where
         Self {
             metric_task: metrics.map(|m| tokio::spawn(metric_task(m, Arc::clone(&endpoints)))),
             endpoints,
+            ring: None,
         }
     }
 
+    /// Configure this [`Balancer`] to distribute requests using a consistent
+    /// hash of a routing key, instead of round-robin.
+    ///
+    /// See [`Balancer::ring_position()`] and [`Balancer::endpoints_from()`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if this [`Balancer`] has no endpoints.
+    pub(super) fn with_consistent_hashing(mut self, metrics: &metric::Registry) -> Self {
+        self.ring = Some(HashRing::new(
+            self.endpoints.iter().map(|e| e.endpoint_name()),
+            metrics,
+        ));
+        self
+    }
+
     /// Returns the number of configured upstream endpoints.
     pub(super) fn len(&self) -> usize {
         self.endpoints.len()
     }
 
+    /// Mark the upstream with the endpoint name `endpoint` as draining (or
+    /// not), returning `false` if no such upstream is configured.
+    pub(super) fn set_draining(&self, endpoint: &str, draining: bool) -> bool {
+        let mut found = false;
+        for e in self
+            .endpoints
+            .iter()
+            .filter(|e| &*e.endpoint_name() == endpoint)
+        {
+            e.set_draining(draining);
+            found = true;
+        }
+        found
+    }
+
     /// Return an (infinite) iterator of healthy [`CircuitBreakingClient`], and
     /// at most one client needing a health probe.
     ///
@@ -82,31 +128,7 @@ where
             new_value
         });
 
-        // Build a set of only healthy nodes, and at most one node needing a
-        // health probe.
-        //
-        // By doing this evaluation before returning the iterator, the health is
-        // evaluated only once per request.
-        //
-        // At most one node needing a health probe is returned to avoid one
-        // request having to make multiple RPC calls that are likely to fail -
-        // this smooths out the P99. The probe node is always requested first to
-        // drive recovery.
-        let mut probe = None;
-        let mut healthy = Vec::with_capacity(self.endpoints.len());
-        for e in &*self.endpoints {
-            if e.is_healthy() {
-                healthy.push(Arc::clone(e));
-                continue;
-            }
-
-            // NOTE: if should_probe() returns true, the caller SHOULD issue a
-            // probe request - therefore it is added to the front of the
-            // iter/request queue.
-            if probe.is_none() && e.should_probe() {
-                probe = Some(Arc::clone(e));
-            }
-        }
+        let (probe, healthy) = partition_by_health(self.endpoints.iter());
 
         // If there is a node to probe, ensure it is the first node to be tried
         // (otherwise it might not get a request sent to it).
@@ -123,6 +145,99 @@ where
         let contains_probe = probe.is_some();
         UpstreamSnapshot::new(probe.into_iter().chain(healthy), idx, contains_probe)
     }
+
+    /// Return the consistent hash ring position of the primary upstream for
+    /// `key`, or [`None`] if this [`Balancer`] is not configured for
+    /// consistent hashing.
+    pub(super) fn ring_position<K>(&self, key: K) -> Option<usize>
+    where
+        K: Hash,
+    {
+        self.ring.as_ref().map(|r| r.position(key))
+    }
+
+    /// Return an (infinite) iterator of healthy [`CircuitBreakingClient`] in
+    /// ring order, starting at the upstream at `position`, and at most one
+    /// client needing a health probe.
+    ///
+    /// `table_writes` is the number of table writes mapped to `position` that
+    /// the caller is routing, used to record failovers when the primary is not
+    /// healthy.
+    ///
+    /// Healthy nodes are yielded in ring order, so that the first node yielded
+    /// is the primary for `position` when it is healthy, and the next healthy
+    /// node on the ring otherwise. As with [`Balancer::endpoints()`], a node
+    /// needing a health probe is always yielded first.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `position` is not a valid ring position for this
+    /// [`Balancer`].
+    pub(super) fn endpoints_from(
+        &self,
+        position: usize,
+        table_writes: u64,
+    ) -> Option<UpstreamSnapshot<Arc<CircuitBreakingClient<T, C>>>> {
+        assert!(position < self.endpoints.len(), "invalid ring position");
+
+        let (probe, healthy) = partition_by_health(
+            self.endpoints
+                .iter()
+                .cycle()
+                .skip(position)
+                .take(self.endpoints.len()),
+        );
+
+        // Record when the key owner is unavailable and the writes fail over to
+        // the next node on the ring.
+        if let Some(ring) = &self.ring {
+            let primary = &self.endpoints[position];
+            if !healthy.first().is_some_and(|v| Arc::ptr_eq(v, primary)) {
+                ring.record_failover(position, table_writes);
+            }
+        }
+
+        let contains_probe = probe.is_some();
+        UpstreamSnapshot::new(probe.into_iter().chain(healthy), 0, contains_probe)
+    }
+}
+
+/// Split `candidates` into at most one client needing a health probe, and the
+/// ordered set of healthy clients.
+///
+/// By doing this evaluation before returning the iterator, the health is
+/// evaluated only once per request.
+///
+/// At most one node needing a health probe is returned to avoid one request
+/// having to make multiple RPC calls that are likely to fail - this smooths out
+/// the P99. The probe node is always requested first to drive recovery.
+fn partition_by_health<'a, T, C>(
+    candidates: impl Iterator<Item = &'a Arc<CircuitBreakingClient<T, C>>>,
+) -> (
+    Option<Arc<CircuitBreakingClient<T, C>>>,
+    Vec<Arc<CircuitBreakingClient<T, C>>>,
+)
+where
+    T: 'a,
+    C: CircuitBreakerState + 'a,
+{
+    let mut probe = None;
+    let mut healthy = Vec::with_capacity(candidates.size_hint().0);
+    for e in candidates {
+        if e.is_healthy() {
+            healthy.push(Arc::clone(e));
+            continue;
+        }
+
+        // NOTE: if should_probe() returns true, the caller SHOULD issue a
+        // probe request - therefore it is added to the front of the
+        // iter/request queue.
+        if probe.is_none() && e.should_probe() {
+            probe = Some(Arc::clone(e));
+        }
+    }
+
+    (probe, healthy)
 }
 
 /// Initialise the health metric exported by the RPC balancer, and return the
@@ -210,7 +325,7 @@ mod tests {
 
     use assert_matches::assert_matches;
     use generated_types::influxdata::iox::ingester::v1::WriteRequest;
-    use metric::{Attributes, Metric};
+    use metric::{Attributes, Metric, U64Counter};
     use test_helpers::timeout::FutureTimeout;
 
     use crate::dml_handlers::rpc_write::{
@@ -628,4 +743,76 @@ mod tests {
         circuit_err.set_healthy(true);
         assert!(balancer.endpoints().is_some());
     }
+
+    /// Healthy nodes are yielded in ring order starting at the requested
+    /// position, failing over to the next healthy node on the ring.
+    #[tokio::test]
+    async fn test_consistent_hash_ring_order() {
+        let circuits = (0..3)
+            .map(|_| {
+                let c = Arc::new(MockCircuitBreaker::default());
+                c.set_healthy(true);
+                c
+            })
+            .collect::<Vec<_>>();
+        let clients = circuits.iter().enumerate().map(|(i, c)| {
+            CircuitBreakingClient::new(
+                Arc::new(MockWriteClient::default()),
+                format!("client-{i}"),
+                ARBITRARY_TEST_NUM_PROBES,
+            )
+            .with_circuit_breaker(Arc::clone(c))
+        });
+
+        let metrics = metric::Registry::default();
+        let balancer = Balancer::new(clients, None).with_consistent_hashing(&metrics);
+
+        let names = |position| {
+            let mut snap = balancer.endpoints_from(position, 2).unwrap();
+            (0..3)
+                .map(|_| snap.next().unwrap().endpoint_name().to_string())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(names(0), ["client-0", "client-1", "client-2"]);
+        assert_eq!(names(1), ["client-1", "client-2", "client-0"]);
+        assert_eq!(names(2), ["client-2", "client-0", "client-1"]);
+
+        // Mark the owner of position 1 as unhealthy - requests for it fail
+        // over to the next node on the ring.
+        circuits[1].set_healthy(false);
+        circuits[1].set_should_probe(false);
+        assert_eq!(names(1), ["client-2", "client-0", "client-2"]);
+
+        let failovers = metrics
+            .get_instrument::<Metric<U64Counter>>("rpc_balancer_consistent_hash_failovers")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("endpoint", "client-1")]))
+            .expect("failed to get observer")
+            .fetch();
+        assert_eq!(failovers, 2);
+    }
+
+    /// Keys are consistently mapped to the same ring position.
+    #[tokio::test]
+    async fn test_consistent_hash_ring_position() {
+        let clients = (0..3).map(|i| {
+            CircuitBreakingClient::new(
+                Arc::new(MockWriteClient::default()),
+                format!("client-{i}"),
+                ARBITRARY_TEST_NUM_PROBES,
+            )
+        });
+
+        let round_robin = Balancer::new(clients.clone(), None);
+        assert_eq!(round_robin.ring_position("bananas"), None);
+
+        let balancer =
+            Balancer::new(clients, None).with_consistent_hashing(&metric::Registry::default());
+        let want = balancer.ring_position("bananas").unwrap();
+        assert!(want < 3);
+        for _ in 0..10 {
+            assert_eq!(balancer.ring_position("bananas"), Some(want));
+        }
+    }
 }
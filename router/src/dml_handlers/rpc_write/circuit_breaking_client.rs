@@ -1,7 +1,12 @@
-use std::{fmt::Debug, sync::Arc};
+use std::{
+    fmt::Debug,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
 use generated_types::influxdata::iox::ingester::v1::WriteRequest;
+use parking_lot::Mutex;
 use trace::ctx::SpanContext;
 
 use super::{
@@ -9,6 +14,13 @@ use super::{
     client::{RpcWriteClientError, WriteClient},
 };
 
+/// The maximum duration of time an upstream is considered draining after it
+/// announced it is draining.
+///
+/// Bounds the effect of a missed announcement that the upstream is active
+/// again (delivery of which is best-effort).
+const DRAINING_EXPIRY: Duration = Duration::from_secs(10 * 60);
+
 /// An internal abstraction over the health probing & result recording
 /// functionality of a circuit breaker.
 pub(super) trait CircuitBreakerState: Send + Sync + Debug {
@@ -46,8 +58,15 @@ pub(super) struct CircuitBreakingClient<T, C = CircuitBreaker> {
     /// A string description of the endpoint this [`CircuitBreakingClient`]
     /// dispatches requests to.
     ///
-    /// Used for metric context only.
+    /// Used for metric context, and to identify the upstream in state change
+    /// announcements.
     endpoint_name: Arc<str>,
+
+    /// The time at which the upstream announced it is draining (handing off
+    /// its data before shutting down), if it has.
+    ///
+    /// A draining upstream is never considered healthy or probed.
+    draining: Mutex<Option<Instant>>,
 }
 
 impl<T> CircuitBreakingClient<T> {
@@ -63,6 +82,7 @@ impl<T> CircuitBreakingClient<T> {
             inner,
             state,
             endpoint_name,
+            draining: Mutex::default(),
         }
     }
 }
@@ -72,6 +92,20 @@ impl<T, C> CircuitBreakingClient<T, C> {
     pub(crate) fn endpoint_name(&self) -> Arc<str> {
         Arc::clone(&self.endpoint_name)
     }
+
+    /// Mark the upstream as draining (or not), removing it from (or returning
+    /// it to) the set of upstreams writes are sent to.
+    pub(super) fn set_draining(&self, draining: bool) {
+        *self.draining.lock() = draining.then(Instant::now);
+    }
+
+    /// Returns `true` if the upstream announced it is draining within the
+    /// last [`DRAINING_EXPIRY`].
+    fn is_draining(&self) -> bool {
+        self.draining
+            .lock()
+            .is_some_and(|t| t.elapsed() < DRAINING_EXPIRY)
+    }
 }
 
 impl<T, C> CircuitBreakingClient<T, C>
@@ -81,10 +115,10 @@ where
     /// Returns `true` if this client can be used to make a request with an
     /// expectation of success.
     pub(super) fn is_healthy(&self) -> bool {
-        self.state.is_healthy()
+        !self.is_draining() && self.state.is_healthy()
     }
     pub(super) fn should_probe(&self) -> bool {
-        self.state.should_probe()
+        !self.is_draining() && self.state.should_probe()
     }
 
     #[cfg(test)]
@@ -93,6 +127,7 @@ where
             inner: self.inner,
             endpoint_name: self.endpoint_name,
             state: breaker,
+            draining: self.draining,
         }
     }
 }
@@ -109,7 +144,12 @@ where
         span_ctx: Option<SpanContext>,
     ) -> Result<(), RpcWriteClientError> {
         let res = self.inner.write(op, span_ctx).await;
-        self.state.observe(&res);
+        match &res {
+            // A write rejected by the upstream's series limit was handled by
+            // a healthy upstream, and does not count towards the error ratio.
+            Err(RpcWriteClientError::SeriesLimit(_)) => self.state.observe(&Ok::<_, ()>(())),
+            v => self.state.observe(v),
+        }
         res
     }
 }
@@ -203,6 +243,34 @@ mod tests {
         assert_eq!(wrapper.should_probe(), circuit_breaker.should_probe());
     }
 
+    #[tokio::test]
+    async fn test_draining() {
+        let circuit_breaker = Arc::new(MockCircuitBreaker::default());
+        let wrapper = CircuitBreakingClient::new(MockWriteClient::default(), "bananas", 10)
+            .with_circuit_breaker(Arc::clone(&circuit_breaker));
+
+        circuit_breaker.set_healthy(true);
+        circuit_breaker.set_should_probe(true);
+
+        // A draining upstream is neither healthy nor probed, regardless of the
+        // circuit breaker state.
+        wrapper.set_draining(true);
+        assert!(!wrapper.is_healthy());
+        assert!(!wrapper.should_probe());
+
+        // And returns to the circuit breaker state once active again.
+        wrapper.set_draining(false);
+        assert!(wrapper.is_healthy());
+        assert!(wrapper.should_probe());
+
+        // The draining state expires (if the monotonic clock allows a time
+        // that long ago to be represented).
+        if let Some(t) = Instant::now().checked_sub(DRAINING_EXPIRY) {
+            *wrapper.draining.lock() = Some(t);
+            assert!(wrapper.is_healthy());
+        }
+    }
+
     #[tokio::test]
     async fn test_observe() {
         let circuit_breaker = Arc::new(MockCircuitBreaker::default());
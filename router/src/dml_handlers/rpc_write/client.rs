@@ -3,10 +3,14 @@
 use std::{fmt::Debug, sync::Arc};
 
 use async_trait::async_trait;
-use generated_types::influxdata::iox::ingester::v1::{
-    write_service_client::WriteServiceClient, WriteRequest,
+use generated_types::{
+    google::decode_quota_failure,
+    influxdata::iox::ingester::v1::{
+        write_service_client::WriteServiceClient, WriteRequest, SERIES_LIMIT_QUOTA_SUBJECT,
+    },
 };
 use thiserror::Error;
+use tonic::Code;
 use trace::ctx::SpanContext;
 use trace_http::ctx::format_jaeger_trace_context;
 
@@ -20,7 +24,14 @@ pub enum RpcWriteClientError {
 
     /// The upstream ingester returned an error response.
     #[error("upstream ingester error: {0}")]
-    Upstream(#[from] tonic::Status),
+    Upstream(tonic::Status),
+
+    /// The upstream ingester rejected the write because it would exceed the
+    /// per-table series limit.
+    ///
+    /// Retrying the write will not succeed.
+    #[error("upstream ingester rejected write: {0}")]
+    SeriesLimit(String),
 
     /// The client is misconfigured and has produced an invalid request metadata key.
     #[error("misconfigured client producing invalid metadata key: {0}")]
@@ -31,6 +42,36 @@ pub enum RpcWriteClientError {
     MisconfiguredMetadataValue(#[from] tonic::metadata::errors::InvalidMetadataValue),
 }
 
+impl RpcWriteClientError {
+    /// Returns `false` if the upstream rejected the write because of its
+    /// content, and every subsequent attempt to send it will fail the same
+    /// way.
+    pub(crate) fn is_retryable(&self) -> bool {
+        match self {
+            Self::SeriesLimit(_) => false,
+            Self::Upstream(status) => !matches!(
+                status.code(),
+                Code::InvalidArgument
+                    | Code::NotFound
+                    | Code::FailedPrecondition
+                    | Code::OutOfRange
+            ),
+            Self::UpstreamNotConnected(_)
+            | Self::MisconfiguredMetadataKey(_)
+            | Self::MisconfiguredMetadataValue(_) => true,
+        }
+    }
+}
+
+impl From<tonic::Status> for RpcWriteClientError {
+    fn from(status: tonic::Status) -> Self {
+        if decode_quota_failure(&status).any(|q| q.subject == SERIES_LIMIT_QUOTA_SUBJECT) {
+            return Self::SeriesLimit(status.message().to_string());
+        }
+        Self::Upstream(status)
+    }
+}
+
 /// An abstract RPC client that pushes `op` to an opaque receiver.
 #[async_trait]
 pub(super) trait WriteClient: Send + Sync + std::fmt::Debug {
@@ -146,6 +187,28 @@ mod test {
             assert_eq!(got_ctx.parent_span_id, Some(req_ctx.span_id));
         });
     }
+
+    #[test]
+    fn series_limit_status_is_identified() {
+        let status = tonic::Status::from(generated_types::google::QuotaFailure {
+            subject: SERIES_LIMIT_QUOTA_SUBJECT.to_string(),
+            description: "too many series".to_string(),
+        });
+        assert_matches!(
+            RpcWriteClientError::from(status),
+            RpcWriteClientError::SeriesLimit(msg) => {
+                assert!(msg.contains("too many series"));
+            }
+        );
+
+        // Other resource exhaustion errors are not mistaken for a series limit
+        // rejection.
+        let status = tonic::Status::resource_exhausted("partition limit");
+        assert_matches!(
+            RpcWriteClientError::from(status),
+            RpcWriteClientError::Upstream(_)
+        );
+    }
 }
 
 /// Mocks for testing
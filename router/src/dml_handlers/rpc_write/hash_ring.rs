@@ -0,0 +1,138 @@
+use std::{borrow::Cow, hash::Hash, sync::Arc};
+
+use metric::U64Counter;
+use sharder::JumpHash;
+
+/// A consistent hash ring mapping routing keys onto a fixed, ordered set of
+/// upstream endpoints.
+///
+/// Each key is mapped to a "primary" endpoint position using a [`JumpHash`].
+/// The endpoints that follow the primary (wrapping around the end of the set)
+/// form the ordered list of replica / failover candidates for that key.
+///
+/// Because the mapping is a pure function of the key and the (ordered) set of
+/// endpoints, all router instances configured with the same ingester addresses
+/// route the same key to the same ingester.
+#[derive(Debug)]
+pub(super) struct HashRing {
+    hasher: JumpHash<usize>,
+
+    /// The number of table writes mapped to each endpoint position as their
+    /// primary, indexed by ring position.
+    ///
+    /// A routing key is counted once for each write that carries it.
+    assigned: Box<[U64Counter]>,
+
+    /// The number of table writes that could not be routed to their primary
+    /// endpoint because it was unhealthy, indexed by ring position.
+    failovers: Box<[U64Counter]>,
+}
+
+impl HashRing {
+    /// Initialise a [`HashRing`] over the endpoints identified by `names`, in
+    /// order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `names` is empty.
+    pub(super) fn new(
+        names: impl IntoIterator<Item = Arc<str>>,
+        metrics: &metric::Registry,
+    ) -> Self {
+        let assigned_metric = metrics.register_metric::<U64Counter>(
+            "rpc_balancer_consistent_hash_table_writes",
+            "number of table writes mapped to the endpoint as their primary upstream",
+        );
+        let failover_metric = metrics.register_metric::<U64Counter>(
+            "rpc_balancer_consistent_hash_failovers",
+            "number of table writes routed away from the endpoint because it was unhealthy",
+        );
+
+        let (assigned, failovers): (Vec<_>, Vec<_>) = names
+            .into_iter()
+            .map(|name| {
+                let name = Cow::from(name.to_string());
+                (
+                    assigned_metric.recorder([("endpoint", name.clone())]),
+                    failover_metric.recorder([("endpoint", name)]),
+                )
+            })
+            .unzip();
+
+        Self {
+            hasher: JumpHash::new(0..assigned.len()),
+            assigned: assigned.into_boxed_slice(),
+            failovers: failovers.into_boxed_slice(),
+        }
+    }
+
+    /// Return the ring position of the primary endpoint for `key`, recording a
+    /// table write routed to it.
+    pub(super) fn position<K>(&self, key: K) -> usize
+    where
+        K: Hash,
+    {
+        let position = *self.hasher.hash(key);
+        self.assigned[position].inc(1);
+        position
+    }
+
+    /// Record that `table_writes` table writes mapped to the endpoint at
+    /// `position` were routed to another endpoint, as the primary was
+    /// unavailable.
+    pub(super) fn record_failover(&self, position: usize, table_writes: u64) {
+        self.failovers[position].inc(table_writes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn names(n: usize) -> impl Iterator<Item = Arc<str>> {
+        (0..n).map(|i| Arc::from(format!("ingester-{i}")))
+    }
+
+    fn assigned_count(metrics: &metric::Registry, endpoint: &'static str) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Counter>>("rpc_balancer_consistent_hash_table_writes")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[("endpoint", endpoint)]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    #[test]
+    fn test_position_is_stable() {
+        let a = HashRing::new(names(5), &metric::Registry::default());
+        let b = HashRing::new(names(5), &metric::Registry::default());
+
+        for i in 0..1_000 {
+            let key = format!("bananas-{i}");
+            let got = a.position(&key);
+            assert!(got < 5);
+            assert_eq!(got, b.position(&key));
+        }
+    }
+
+    #[test]
+    fn test_key_distribution_metrics() {
+        const N: usize = 10_000;
+
+        let metrics = metric::Registry::default();
+        let ring = HashRing::new(names(2), &metrics);
+
+        for i in 0..N {
+            ring.position(i);
+        }
+
+        let a = assigned_count(&metrics, "ingester-0");
+        let b = assigned_count(&metrics, "ingester-1");
+        assert_eq!(a + b, N as u64);
+
+        // The distribution should be approximately uniform.
+        assert!(a.abs_diff(b) < (N / 10) as u64, "a={a}, b={b}");
+    }
+}
@@ -157,6 +157,7 @@ fn is_envoy_unavailable_error(e: &RpcWriteClientError) -> bool {
             .map(|v| v == AsciiMetadataValue::from_static("envoy"))
             .unwrap_or(false),
         RpcWriteClientError::Upstream(_) => false,
+        RpcWriteClientError::SeriesLimit(_) => false,
         RpcWriteClientError::MisconfiguredMetadataKey(_) => false,
         RpcWriteClientError::MisconfiguredMetadataValue(_) => false,
         RpcWriteClientError::UpstreamNotConnected(_) => unreachable!(),
@@ -0,0 +1,562 @@
+//! A durable, on-disk spool of RPC writes that could not be sent to any
+//! upstream ingester.
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use data_types::TableId;
+use generated_types::influxdata::{
+    iox::{ingester::v1::WriteRequest, wal::v1::sequenced_wal_op::Op},
+    pbdata::v1::DatabaseBatch,
+};
+use metric::{U64Counter, U64Gauge};
+use observability_deps::tracing::*;
+use parking_lot::Mutex;
+use thiserror::Error;
+use wal::{SegmentId, SequencedWalOp, Wal, WriteResult};
+
+/// Errors returned by the [`WriteSpool`].
+#[derive(Debug, Error)]
+pub enum SpoolError {
+    /// The spool has reached its configured maximum size.
+    #[error("write spool is full (max {max_bytes} bytes)")]
+    Full {
+        /// The configured maximum size of the spool.
+        max_bytes: u64,
+    },
+
+    /// The underlying WAL returned an error.
+    #[error("write spool i/o error: {0}")]
+    Wal(String),
+}
+
+impl From<wal::Error> for SpoolError {
+    fn from(e: wal::Error) -> Self {
+        Self::Wal(e.to_string())
+    }
+}
+
+/// The outcome of replaying a single spooled write, returned by the callback
+/// passed to [`WriteSpool::replay()`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum ReplayOutcome {
+    /// The write was accepted by the upstream.
+    Written,
+    /// The write can never be accepted by the upstream, and was discarded so
+    /// that it does not block replay of the writes spooled after it.
+    Dropped,
+}
+
+/// The replay progress within the oldest spooled segment.
+#[derive(Debug, Default)]
+struct ReplayOffset {
+    /// The segment being replayed.
+    segment: Option<SegmentId>,
+    /// The number of writes in `segment` already replayed successfully.
+    replayed: usize,
+}
+
+/// A durable spool of [`WriteRequest`] payloads, persisted to local disk using
+/// the [`wal`] crate's segment format.
+///
+/// When every upstream ingester is unavailable, writes are appended to the
+/// spool (and acknowledged to the client once durable) instead of being
+/// rejected. Spooled writes are replayed in the order they were appended once
+/// an upstream becomes available again, and the replayed segments are
+/// deleted.
+///
+/// # Delivery Guarantees
+///
+/// Replay is at-least-once: a write that is replayed successfully immediately
+/// before the router crashes (but before the segment containing it is deleted)
+/// will be replayed again at startup.
+///
+/// Writes the upstream can never accept (such as a write rejected as
+/// invalid) are dropped during replay rather than retried forever, as they
+/// would otherwise block replay of every write spooled after them.
+///
+/// # Size Limit
+///
+/// The spool is bounded to approximately `max_bytes` of on-disk segment data -
+/// once this limit is reached, further writes are rejected with
+/// [`SpoolError::Full`] until replay makes space.
+#[derive(Debug)]
+pub struct WriteSpool {
+    wal: Arc<Wal>,
+    max_bytes: u64,
+
+    /// A monotonic source of sequence numbers for spooled writes.
+    next_sequence_number: AtomicU64,
+
+    /// The number of spooled writes not yet replayed.
+    pending_writes: AtomicU64,
+    /// The total size of all closed segments.
+    closed_bytes: AtomicU64,
+    /// The (approximate) size of the currently open segment.
+    ///
+    /// Only modified while holding `rotated`.
+    open_bytes: AtomicU64,
+
+    /// Replay progress, held for the duration of a replay to serialise
+    /// concurrent replay attempts.
+    replay_offset: tokio::sync::Mutex<ReplayOffset>,
+
+    /// The ID of the most recently rotated segment.
+    ///
+    /// Serialises segment rotation with updates to `open_bytes`, so that the
+    /// size of a write to a segment that has since been rotated is not
+    /// attributed to the new open segment.
+    rotated: Mutex<Option<SegmentId>>,
+
+    depth_writes: U64Gauge,
+    depth_bytes: U64Gauge,
+    spooled: U64Counter,
+    replayed: U64Counter,
+    dropped: U64Counter,
+    rejected: U64Counter,
+}
+
+impl WriteSpool {
+    /// Initialise a [`WriteSpool`] storing spooled writes in `directory`,
+    /// bounded to approximately `max_bytes` of on-disk data.
+    ///
+    /// Any writes spooled by a previous instance using the same `directory`
+    /// are loaded, and will be replayed.
+    pub async fn new(
+        directory: impl Into<PathBuf>,
+        max_bytes: u64,
+        metrics: &metric::Registry,
+    ) -> Result<Self, SpoolError> {
+        let wal = Wal::new(directory).await?;
+
+        // Count the writes and bytes left in the spool by a previous instance.
+        let mut pending_writes = 0;
+        let mut closed_bytes = 0;
+        for segment in wal.closed_segments() {
+            closed_bytes += segment.size();
+            for batch in wal.reader_for_segment(segment.id())? {
+                pending_writes += batch?
+                    .into_iter()
+                    .filter(|op| matches!(op.op, Op::Write(_)))
+                    .count() as u64;
+            }
+        }
+
+        if pending_writes > 0 {
+            info!(
+                pending_writes,
+                closed_bytes, "loaded spooled writes from previous run"
+            );
+        }
+
+        let depth_writes = metrics
+            .register_metric::<U64Gauge>(
+                "rpc_write_spool_depth_writes",
+                "number of writes in the router write spool waiting to be replayed",
+            )
+            .recorder(&[]);
+        let depth_bytes = metrics
+            .register_metric::<U64Gauge>(
+                "rpc_write_spool_depth_bytes",
+                "approximate on-disk size of the router write spool",
+            )
+            .recorder(&[]);
+        let writes = metrics.register_metric::<U64Counter>(
+            "rpc_write_spool_writes",
+            "number of writes spooled, replayed, dropped and rejected by the router write spool",
+        );
+
+        let spool = Self {
+            wal,
+            max_bytes,
+            next_sequence_number: AtomicU64::new(0),
+            pending_writes: AtomicU64::new(pending_writes),
+            closed_bytes: AtomicU64::new(closed_bytes),
+            open_bytes: AtomicU64::new(0),
+            replay_offset: Default::default(),
+            rotated: Default::default(),
+            depth_writes,
+            depth_bytes,
+            spooled: writes.recorder(&[("result", "spooled")]),
+            replayed: writes.recorder(&[("result", "replayed")]),
+            dropped: writes.recorder(&[("result", "dropped")]),
+            rejected: writes.recorder(&[("result", "rejected_full")]),
+        };
+        spool.update_depth();
+
+        Ok(spool)
+    }
+
+    /// Returns true if there are spooled writes waiting to be replayed.
+    pub(crate) fn has_pending(&self) -> bool {
+        self.pending_writes.load(Ordering::Relaxed) > 0
+    }
+
+    fn size_bytes(&self) -> u64 {
+        self.closed_bytes.load(Ordering::Relaxed) + self.open_bytes.load(Ordering::Relaxed)
+    }
+
+    fn update_depth(&self) {
+        self.depth_writes
+            .set(self.pending_writes.load(Ordering::Relaxed));
+        self.depth_bytes.set(self.size_bytes());
+    }
+
+    /// Durably append `batch` to the spool, returning once it has been
+    /// persisted to disk.
+    pub(crate) async fn append(&self, batch: DatabaseBatch) -> Result<(), SpoolError> {
+        if self.size_bytes() >= self.max_bytes {
+            self.rejected.inc(1);
+            return Err(SpoolError::Full {
+                max_bytes: self.max_bytes,
+            });
+        }
+
+        let sequence_number = self.next_sequence_number.fetch_add(1, Ordering::Relaxed);
+        let table_write_sequence_numbers = batch
+            .table_batches
+            .iter()
+            .map(|t| (TableId::new(t.table_id), sequence_number))
+            .collect::<HashMap<_, _>>();
+
+        let mut result = self.wal.write_op(SequencedWalOp {
+            table_write_sequence_numbers,
+            op: Op::Write(batch),
+        });
+
+        // Wait for the write to be durable before returning.
+        result
+            .changed()
+            .await
+            .map_err(|e| SpoolError::Wal(e.to_string()))?;
+
+        let summary = match result.borrow().as_ref() {
+            Some(WriteResult::Ok(summary)) => *summary,
+            Some(WriteResult::Err(e)) => return Err(SpoolError::Wal(e.clone())),
+            None => unreachable!("wal write notification without result"),
+        };
+
+        self.record_open_bytes(summary.segment_id, summary.total_bytes as u64);
+        self.pending_writes.fetch_add(1, Ordering::Relaxed);
+        self.spooled.inc(1);
+        self.update_depth();
+
+        Ok(())
+    }
+
+    /// Record that the segment `segment_id` is `total_bytes` in size after a
+    /// write to it.
+    ///
+    /// Writes to a segment that has already been rotated are accounted for in
+    /// the closed segment size, and are ignored.
+    fn record_open_bytes(&self, segment_id: SegmentId, total_bytes: u64) {
+        let rotated = self.rotated.lock();
+        if rotated.is_some_and(|id| segment_id <= id) {
+            return;
+        }
+        self.open_bytes.fetch_max(total_bytes, Ordering::Relaxed);
+    }
+
+    /// Close the open segment if it contains any spooled writes, making them
+    /// available for replay.
+    fn rotate(&self) -> Result<(), SpoolError> {
+        let mut rotated = self.rotated.lock();
+        if self.open_bytes.load(Ordering::Relaxed) == 0 {
+            return Ok(());
+        }
+
+        let (closed, _) = self.wal.rotate()?;
+        self.closed_bytes
+            .fetch_add(closed.size(), Ordering::Relaxed);
+        self.open_bytes.store(0, Ordering::Relaxed);
+        *rotated = Some(closed.id());
+
+        Ok(())
+    }
+
+    /// Replay all spooled writes in the order they were appended, passing
+    /// each to `f`.
+    ///
+    /// Replay stops at the first write `f` fails to accept, returning the
+    /// error. A subsequent call resumes replay from this write. Writes for
+    /// which `f` returns [`ReplayOutcome::Dropped`] are removed from the spool
+    /// as if replayed, and counted separately.
+    pub(crate) async fn replay<F, Fut, E>(&self, mut f: F) -> Result<(), E>
+    where
+        F: FnMut(WriteRequest) -> Fut + Send,
+        Fut: Future<Output = Result<ReplayOutcome, E>> + Send,
+        E: From<SpoolError>,
+    {
+        let mut offset = self.replay_offset.lock().await;
+
+        // Writes may be spooled concurrently with replay - continue replaying
+        // until no further writes remain.
+        loop {
+            self.rotate()?;
+
+            let segments = self.wal.closed_segments();
+            if segments.is_empty() {
+                return Ok(());
+            }
+
+            for segment in segments {
+                if offset.segment != Some(segment.id()) {
+                    *offset = ReplayOffset {
+                        segment: Some(segment.id()),
+                        replayed: 0,
+                    };
+                }
+
+                let reader = self
+                    .wal
+                    .reader_for_segment(segment.id())
+                    .map_err(SpoolError::from)?;
+
+                let mut n = 0;
+                for batch in reader {
+                    for op in batch.map_err(SpoolError::from)? {
+                        let payload = match op.op {
+                            Op::Write(w) => w,
+                            Op::Delete(_) | Op::Persist(_) => continue,
+                        };
+
+                        // Skip writes already replayed by a previous attempt.
+                        n += 1;
+                        if n <= offset.replayed {
+                            continue;
+                        }
+
+                        let outcome = f(WriteRequest {
+                            payload: Some(payload),
+                        })
+                        .await?;
+
+                        offset.replayed = n;
+                        self.pending_writes.fetch_sub(1, Ordering::Relaxed);
+                        match outcome {
+                            ReplayOutcome::Written => self.replayed.inc(1),
+                            ReplayOutcome::Dropped => self.dropped.inc(1),
+                        }
+                        self.update_depth();
+                    }
+                }
+
+                // The segment has been fully replayed and can be removed.
+                self.wal
+                    .delete(segment.id())
+                    .await
+                    .map_err(SpoolError::from)?;
+                self.closed_bytes
+                    .fetch_sub(segment.size(), Ordering::Relaxed);
+                self.update_depth();
+
+                debug!(segment_id = %segment.id(), writes = n, "replayed spooled segment");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use generated_types::influxdata::pbdata::v1::TableBatch;
+    use metric::{Attributes, Metric};
+
+    use super::*;
+
+    fn batch(table_id: i64) -> DatabaseBatch {
+        DatabaseBatch {
+            database_id: 42,
+            partition_key: "platanos".to_string(),
+            table_batches: vec![TableBatch {
+                table_id,
+                ..Default::default()
+            }],
+        }
+    }
+
+    fn depth(metrics: &metric::Registry) -> u64 {
+        metrics
+            .get_instrument::<Metric<U64Gauge>>("rpc_write_spool_depth_writes")
+            .expect("failed to read metric")
+            .get_observer(&Attributes::from(&[]))
+            .expect("failed to get observer")
+            .fetch()
+    }
+
+    fn table_id(req: &WriteRequest) -> i64 {
+        req.payload.as_ref().unwrap().table_batches[0].table_id
+    }
+
+    #[tokio::test]
+    async fn test_append_replay_in_order() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metrics = metric::Registry::default();
+        let spool = WriteSpool::new(dir.path(), u64::MAX, &metrics)
+            .await
+            .unwrap();
+
+        assert!(!spool.has_pending());
+        for i in 0..3 {
+            spool.append(batch(i)).await.unwrap();
+        }
+        assert!(spool.has_pending());
+        assert_eq!(depth(&metrics), 3);
+
+        // Fail the replay of the second write.
+        let mut got = vec![];
+        let res = spool
+            .replay(|req| {
+                let id = table_id(&req);
+                got.push(id);
+                async move {
+                    match id {
+                        1 => Err(SpoolError::Wal("bananas".to_string())),
+                        _ => Ok(ReplayOutcome::Written),
+                    }
+                }
+            })
+            .await;
+        assert_matches!(res, Err(SpoolError::Wal(_)));
+        assert_eq!(got, [0, 1]);
+        assert_eq!(depth(&metrics), 2);
+
+        // Resuming replay starts at the failed write.
+        let mut got = vec![];
+        spool
+            .replay(|req| {
+                got.push(table_id(&req));
+                async { Ok::<_, SpoolError>(ReplayOutcome::Written) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(got, [1, 2]);
+
+        assert!(!spool.has_pending());
+        assert_eq!(depth(&metrics), 0);
+        assert!(spool.wal.closed_segments().is_empty());
+    }
+
+    /// Dropped writes are removed from the spool without blocking the writes
+    /// behind them.
+    #[tokio::test]
+    async fn test_replay_dropped() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metrics = metric::Registry::default();
+        let spool = WriteSpool::new(dir.path(), u64::MAX, &metrics)
+            .await
+            .unwrap();
+
+        for i in 0..3 {
+            spool.append(batch(i)).await.unwrap();
+        }
+
+        let mut got = vec![];
+        spool
+            .replay(|req| {
+                let id = table_id(&req);
+                got.push(id);
+                async move {
+                    match id {
+                        1 => Ok::<_, SpoolError>(ReplayOutcome::Dropped),
+                        _ => Ok(ReplayOutcome::Written),
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(got, [0, 1, 2]);
+        assert!(!spool.has_pending());
+
+        let writes = |result: &'static str| {
+            metrics
+                .get_instrument::<Metric<U64Counter>>("rpc_write_spool_writes")
+                .expect("failed to read metric")
+                .get_observer(&Attributes::from(&[("result", result)]))
+                .expect("failed to get observer")
+                .fetch()
+        };
+        assert_eq!(writes("replayed"), 2);
+        assert_eq!(writes("dropped"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reload_pending() {
+        let dir = test_helpers::tmp_dir().unwrap();
+
+        {
+            let spool = WriteSpool::new(dir.path(), u64::MAX, &metric::Registry::default())
+                .await
+                .unwrap();
+            spool.append(batch(1)).await.unwrap();
+            spool.append(batch(2)).await.unwrap();
+        }
+
+        let metrics = metric::Registry::default();
+        let spool = WriteSpool::new(dir.path(), u64::MAX, &metrics)
+            .await
+            .unwrap();
+        assert!(spool.has_pending());
+        assert_eq!(depth(&metrics), 2);
+
+        let mut got = vec![];
+        spool
+            .replay(|req| {
+                got.push(table_id(&req));
+                async { Ok::<_, SpoolError>(ReplayOutcome::Written) }
+            })
+            .await
+            .unwrap();
+        assert_eq!(got, [1, 2]);
+    }
+
+    /// The size reported by a write completing after its segment was rotated
+    /// must not be attributed to the new open segment.
+    #[tokio::test]
+    async fn test_stale_write_size_after_rotate() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let spool = WriteSpool::new(dir.path(), u64::MAX, &metric::Registry::default())
+            .await
+            .unwrap();
+
+        spool.append(batch(1)).await.unwrap();
+        assert!(spool.wal.closed_segments().is_empty());
+
+        spool.rotate().unwrap();
+        let closed = spool.wal.closed_segments();
+        assert_eq!(closed.len(), 1);
+        let closed_bytes = spool.closed_bytes.load(Ordering::Relaxed);
+        assert_eq!(spool.open_bytes.load(Ordering::Relaxed), 0);
+
+        // A stale size for the rotated segment is ignored.
+        spool.record_open_bytes(closed[0].id(), 42);
+        assert_eq!(spool.open_bytes.load(Ordering::Relaxed), 0);
+        assert_eq!(spool.size_bytes(), closed_bytes);
+
+        // Writes to the new open segment are counted.
+        spool.append(batch(2)).await.unwrap();
+        assert!(spool.open_bytes.load(Ordering::Relaxed) > 0);
+    }
+
+    #[tokio::test]
+    async fn test_full() {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let spool = WriteSpool::new(dir.path(), 1, &metric::Registry::default())
+            .await
+            .unwrap();
+
+        // The first write fits, as the spool is empty.
+        spool.append(batch(1)).await.unwrap();
+        assert_matches!(
+            spool.append(batch(2)).await,
+            Err(SpoolError::Full { max_bytes: 1 })
+        );
+    }
+}
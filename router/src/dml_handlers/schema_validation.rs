@@ -5,7 +5,10 @@ use data_types::{
     partition_template::TablePartitionTemplateOverride, NamespaceName, NamespaceSchema, TableId,
 };
 use hashbrown::HashMap;
-use iox_catalog::{interface::Error as CatalogError, validate_or_insert_schema};
+use iox_catalog::{
+    interface::{get_schema_by_id, Error as CatalogError, SoftDeletedRows},
+    validate_or_insert_schema,
+};
 use mutable_batch::MutableBatch;
 use observability_deps::tracing::*;
 use trace::ctx::SpanContext;
@@ -13,7 +16,7 @@ use trace::ctx::SpanContext;
 use super::DmlHandler;
 use crate::{
     namespace_cache::NamespaceCache,
-    schema_validator::{SchemaError, SchemaValidator},
+    schema_validator::{find_undeclared, SchemaError, SchemaValidator},
 };
 
 #[async_trait]
@@ -38,84 +41,151 @@ where
     /// If the schema validation fails due to a service limit being reached,
     /// [`SchemaError::ServiceLimit`] is returned.
     ///
+    /// If the namespace is in explicit schema mode and the request references
+    /// a table or column that does not exist, [`SchemaError::Undeclared`] is
+    /// returned.
+    ///
+    /// If a field value cannot be converted to the type of the existing column
+    /// under the namespace's field coercion rules, [`SchemaError::Coercion`]
+    /// is returned.
+    ///
     /// A request that fails validation on one or more tables fails the request
     /// as a whole - calling this method has "all or nothing" semantics.
     async fn write(
         &self,
         namespace: &NamespaceName<'static>,
         namespace_schema: Arc<NamespaceSchema>,
-        batches: Self::WriteInput,
+        mut batches: Self::WriteInput,
         _span_ctx: Option<SpanContext>,
     ) -> Result<Self::WriteOutput, Self::WriteError> {
         let namespace_id = namespace_schema.id;
 
-        let column_names_by_table = batches
-            .iter()
-            .map(|(table_name, batch)| (table_name.as_str(), batch.column_names()));
-
-        self.validate_service_limits(namespace, &namespace_schema, column_names_by_table)?;
+        let column_names_by_table = || {
+            batches
+                .iter()
+                .map(|(table_name, batch)| (table_name.as_str(), batch.column_names()))
+        };
 
         let mut repos = self.catalog.repositories().await;
 
-        let maybe_new_schema = validate_or_insert_schema(
-            batches.iter().map(|(k, v)| (k.as_str(), v)),
-            &namespace_schema,
-            repos.deref_mut(),
-        )
-        .await
-        .map_err(|e| {
-            match e.err() {
-                // Schema conflicts
-                CatalogError::ColumnTypeMismatch {
-                    ref name,
-                    ref existing,
-                    ref new,
-                } => {
-                    warn!(
-                        %namespace,
-                        %namespace_id,
-                        column_name=%name,
-                        existing_column_type=%existing,
-                        request_column_type=%new,
-                        table_name=%e.table(),
-                        "schema conflict"
-                    );
-                    self.schema_conflict.inc(1);
-                    SchemaError::Conflict(e)
-                }
-                // Service limits
-                CatalogError::ColumnCreateLimitError { table_id, .. } => {
+        // Namespaces in explicit schema mode must not have any tables or
+        // columns created by this write.
+        //
+        // The tables & columns are created out-of-band, so the cached schema
+        // may be stale - refresh it from the catalog before rejecting the
+        // write.
+        let namespace_schema = match find_undeclared(column_names_by_table(), &namespace_schema) {
+            None => namespace_schema,
+            Some(_) => {
+                let latest = get_schema_by_id(
+                    namespace_id,
+                    repos.deref_mut(),
+                    SoftDeletedRows::ExcludeDeleted,
+                )
+                .await
+                .map_err(SchemaError::UnexpectedCatalogError)?;
+
+                if let Some(e) = find_undeclared(column_names_by_table(), &latest) {
                     warn!(
                         %namespace,
                         %namespace_id,
-                        %table_id,
                         error=%e,
-                        "service protection limit reached (columns)"
+                        "write references undeclared schema"
                     );
-                    self.service_limit_hit_columns.inc(1);
-                    SchemaError::ServiceLimit(Box::new(e.into_err()))
+                    self.schema_undeclared.inc(1);
+                    return Err(SchemaError::Undeclared(e));
                 }
-                CatalogError::TableCreateLimitError { .. } => {
-                    warn!(
-                        %namespace,
-                        %namespace_id,
-                        error=%e,
-                        "service protection limit reached (tables)"
-                    );
-                    self.service_limit_hit_tables.inc(1);
-                    SchemaError::ServiceLimit(Box::new(e.into_err()))
-                }
-                _ => {
-                    error!(
-                        %namespace,
-                        %namespace_id,
-                        error=%e,
-                        "schema validation failed"
-                    );
-                    SchemaError::UnexpectedCatalogError(e.into_err())
+
+                let (latest, _) = self.cache.put_schema(namespace.clone(), latest);
+                latest
+            }
+        };
+
+        self.validate_service_limits(namespace, &namespace_schema, column_names_by_table())?;
+
+        self.coerce_fields(namespace, &namespace_schema, &mut batches)?;
+
+        let maybe_new_schema = loop {
+            let res = validate_or_insert_schema(
+                batches.iter().map(|(k, v)| (k.as_str(), v)),
+                &namespace_schema,
+                repos.deref_mut(),
+            )
+            .await;
+
+            // A column missing from the cached schema may exist in the catalog
+            // with a different type - if the namespace permits it, coerce the
+            // field to the catalog type and try again.
+            if let Err(e) = &res {
+                if let CatalogError::ColumnTypeMismatch {
+                    name,
+                    existing,
+                    new,
+                } = e.err()
+                {
+                    if namespace_schema.field_coercion.permits(*new, *existing) {
+                        if let Some(batch) = batches.get_mut(e.table()) {
+                            self.coerce_column(namespace, e.table(), batch, name, *new, *existing)?;
+                            continue;
+                        }
+                    }
                 }
             }
-        })?;
+
+            break res.map_err(|e| {
+                match e.err() {
+                    // Schema conflicts
+                    CatalogError::ColumnTypeMismatch {
+                        ref name,
+                        ref existing,
+                        ref new,
+                    } => {
+                        warn!(
+                            %namespace,
+                            %namespace_id,
+                            column_name=%name,
+                            existing_column_type=%existing,
+                            request_column_type=%new,
+                            table_name=%e.table(),
+                            "schema conflict"
+                        );
+                        self.schema_conflict.inc(1);
+                        SchemaError::Conflict(e)
+                    }
+                    // Service limits
+                    CatalogError::ColumnCreateLimitError { table_id, .. } => {
+                        warn!(
+                            %namespace,
+                            %namespace_id,
+                            %table_id,
+                            error=%e,
+                            "service protection limit reached (columns)"
+                        );
+                        self.service_limit_hit_columns.inc(1);
+                        SchemaError::ServiceLimit(Box::new(e.into_err()))
+                    }
+                    CatalogError::TableCreateLimitError { .. } => {
+                        warn!(
+                            %namespace,
+                            %namespace_id,
+                            error=%e,
+                            "service protection limit reached (tables)"
+                        );
+                        self.service_limit_hit_tables.inc(1);
+                        SchemaError::ServiceLimit(Box::new(e.into_err()))
+                    }
+                    _ => {
+                        error!(
+                            %namespace,
+                            %namespace_id,
+                            error=%e,
+                            "schema validation failed"
+                        );
+                        SchemaError::UnexpectedCatalogError(e.into_err())
+                    }
+                }
+            })?;
+        };
 
         trace!(%namespace, "schema validation complete");
 
@@ -157,12 +227,19 @@ mod tests {
     use std::{collections::BTreeSet, sync::Arc};
 
     use assert_matches::assert_matches;
-    use data_types::{ColumnType, MaxColumnsPerTable, MaxTables};
+    use data_types::{
+        ColumnType, FieldCoercionRules, MaxColumnsPerTable, MaxTables, NamespaceSchemaMode,
+    };
     use iox_tests::{TestCatalog, TestNamespace};
+    use metric::{Attributes, Metric, U64Counter};
     use once_cell::sync::Lazy;
+    use schema::{InfluxColumnType, InfluxFieldType};
 
     use super::*;
-    use crate::namespace_cache::{MemoryNamespaceCache, ReadThroughCache};
+    use crate::{
+        namespace_cache::{MemoryNamespaceCache, ReadThroughCache},
+        schema_validator::UndeclaredSchema,
+    };
 
     static NAMESPACE: Lazy<NamespaceName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
 
@@ -369,6 +446,187 @@ mod tests {
         assert_eq!(1, handler.schema_conflict.fetch());
     }
 
+    #[tokio::test]
+    async fn test_write_explicit_schema_mode() {
+        let (catalog, namespace) = test_setup().await;
+        namespace
+            .update_schema_mode(NamespaceSchemaMode::Explicit)
+            .await;
+
+        let metrics = Arc::new(metric::Registry::default());
+        let cache = Arc::new(setup_test_cache(&catalog));
+        let handler = SchemaValidator::new(catalog.catalog(), Arc::clone(&cache), &metrics);
+
+        // Writing to a table that has not been declared is rejected.
+        let err = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas,tag1=A val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Undeclared(UndeclaredSchema::Table { table_name }) => {
+            assert_eq!(table_name, "bananas");
+        });
+
+        // Declare the table and one of the columns, bypassing the (now stale)
+        // cache.
+        let table = namespace.create_table("bananas").await;
+        table.create_column("val", ColumnType::I64).await;
+
+        // The undeclared tag column is still rejected.
+        let err = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas,tag1=A val=42i 123456"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Undeclared(UndeclaredSchema::Column { table_name, column_name }) => {
+            assert_eq!(table_name, "bananas");
+            assert_eq!(column_name, "tag1");
+        });
+        assert_eq!(2, handler.schema_undeclared.fetch());
+
+        // A write using only declared columns (and the implicit timestamp)
+        // succeeds.
+        let got = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas val=42i 123456"),
+                None,
+            )
+            .await
+            .expect("request should succeed");
+        assert!(got.contains_key(&table.table.id));
+
+        // The refreshed schema is cached, including the timestamp column.
+        assert_cache(&handler, "bananas", "val", ColumnType::I64).await;
+        assert_cache(&handler, "bananas", "time", ColumnType::Time).await;
+        assert_eq!(2, handler.schema_undeclared.fetch());
+    }
+
+    #[tokio::test]
+    async fn test_write_field_coercion() {
+        let (catalog, namespace) = test_setup().await;
+        namespace
+            .update_field_coercion(FieldCoercionRules {
+                int_to_float: true,
+                uint_to_int: true,
+                ..Default::default()
+            })
+            .await;
+
+        let metrics = Arc::new(metric::Registry::default());
+        let cache = Arc::new(setup_test_cache(&catalog));
+        let handler = SchemaValidator::new(catalog.catalog(), Arc::clone(&cache), &metrics);
+
+        // First write sets the schema
+        handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas val=4.2 123456"),
+                None,
+            )
+            .await
+            .expect("request should succeed");
+
+        // An integer written to the float column is converted.
+        let got = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas val=42i 123456"),
+                None,
+            )
+            .await
+            .expect("request should succeed");
+        let (_, _, data) = got.values().next().unwrap();
+        assert_eq!(
+            data.column("val").unwrap().influx_type(),
+            InfluxColumnType::Field(InfluxFieldType::Float)
+        );
+        assert_cache(&handler, "bananas", "val", ColumnType::F64).await;
+
+        // Conversions not enabled for the namespace remain a schema conflict.
+        let err = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas val=true 123456"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Conflict(_));
+
+        // Create a column the cache does not know about, forcing the type
+        // mismatch to be discovered by the catalog.
+        let table = catalog
+            .catalog()
+            .repositories()
+            .await
+            .tables()
+            .get_by_namespace_and_name(namespace.namespace.id, "bananas")
+            .await
+            .unwrap()
+            .unwrap();
+        catalog
+            .catalog()
+            .repositories()
+            .await
+            .columns()
+            .create_or_get("count", table.id, ColumnType::I64)
+            .await
+            .unwrap();
+
+        let got = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas count=3u,val=1i 123456"),
+                None,
+            )
+            .await
+            .expect("request should succeed");
+        let (_, _, data) = got.values().next().unwrap();
+        assert_eq!(
+            data.column("count").unwrap().influx_type(),
+            InfluxColumnType::Field(InfluxFieldType::Integer)
+        );
+        assert_cache(&handler, "bananas", "count", ColumnType::I64).await;
+
+        // A u64 that does not fit in an i64 cannot be converted.
+        let err = handler
+            .write(
+                &NAMESPACE,
+                cache.get_schema(&NAMESPACE).await.unwrap(),
+                lp_to_writes("bananas count=18446744073709551615u 123456"),
+                None,
+            )
+            .await
+            .expect_err("request should fail");
+        assert_matches!(err, SchemaError::Coercion(_));
+
+        let coerced = |from: &'static str, to: &'static str| {
+            metrics
+                .get_instrument::<Metric<U64Counter>>("schema_validation_field_values_coerced")
+                .expect("failed to read metric")
+                .get_observer(&Attributes::from(&[("from", from), ("to", to)]))
+                .expect("failed to get observer")
+                .fetch()
+        };
+        assert_eq!(coerced("i64", "f64"), 2);
+        assert_eq!(coerced("u64", "i64"), 1);
+        assert_eq!(1, handler.schema_conflict.fetch());
+    }
+
     #[tokio::test]
     async fn test_write_table_service_limit() {
         let (catalog, namespace) = test_setup().await;
@@ -1,4 +1,7 @@
-use super::{partitioner::PartitionError, retention_validation::RetentionError, RpcWriteError};
+use super::{
+    partitioner::PartitionError, retention_validation::RetentionError,
+    write_rules::WriteRulesError, RpcWriteError,
+};
 use crate::schema_validator::SchemaError;
 use async_trait::async_trait;
 use data_types::{NamespaceName, NamespaceSchema};
@@ -31,6 +34,10 @@ pub enum DmlError {
     #[error(transparent)]
     Retention(#[from] RetentionError),
 
+    /// An error applying the namespace write rules.
+    #[error(transparent)]
+    WriteRules(#[from] WriteRulesError),
+
     /// An unknown error occured while processing the DML request.
     #[error("internal dml handler error: {0}")]
     Internal(Box<dyn Error + Send + Sync>),
@@ -0,0 +1,546 @@
+use std::{ops::Range, sync::Arc};
+
+use async_trait::async_trait;
+use data_types::{NamespaceName, NamespaceSchema, WriteRule};
+use hashbrown::HashMap;
+use metric::{Metric, U64Counter};
+use mutable_batch::{column::ColumnData, MutableBatch};
+use observability_deps::tracing::*;
+use schema::InfluxColumnType;
+use thiserror::Error;
+use trace::ctx::SpanContext;
+
+use super::DmlHandler;
+
+/// Errors emitted when applying the namespace write rules.
+#[derive(Debug, Error)]
+pub enum WriteRulesError {
+    /// The rule could not be applied to the batch for `table_name`.
+    #[error("failed to apply {rule} write rule to table {table_name}: {source}")]
+    Batch {
+        /// The kind of rule being applied.
+        rule: &'static str,
+        /// The table the rule was applied to.
+        table_name: String,
+        /// The underlying batch error.
+        source: mutable_batch::Error,
+    },
+}
+
+/// A [`DmlHandler`] implementation that applies the ordered set of
+/// [`WriteRule`] configured for the namespace to each write.
+///
+/// Rules are applied in order, each operating on the output of the previous
+/// rule:
+///
+///   * [`WriteRule::DropMeasurement`] removes matching tables from the write.
+///   * [`WriteRule::DropField`] removes matching field columns. A table left
+///     with no fields is removed from the write.
+///   * [`WriteRule::RenameTag`] renames a tag column, unless the target name
+///     is already in use by another column.
+///   * [`WriteRule::AddTag`] adds a tag column with a static value, unless a
+///     column of the same name already exists.
+///   * [`WriteRule::DropLongTagValues`] removes all rows containing a tag
+///     value that exceeds the configured length. A table left with no rows is
+///     removed from the write.
+///
+/// A write may be reduced to nothing by the configured rules, in which case
+/// an empty set of batches is passed to the next handler.
+///
+/// The number of times each type of rule modifies a write is recorded in the
+/// `write_rules_applied` metric.
+#[derive(Debug)]
+pub struct WriteRuleApplicator {
+    rules_applied: Metric<U64Counter>,
+}
+
+impl WriteRuleApplicator {
+    /// Initialise a new [`WriteRuleApplicator`], registering metrics in
+    /// `metrics`.
+    pub fn new(metrics: &metric::Registry) -> Self {
+        let rules_applied = metrics.register_metric::<U64Counter>(
+            "write_rules_applied",
+            "number of times a namespace write rule modified a write, by rule type",
+        );
+
+        Self { rules_applied }
+    }
+
+    fn record(&self, rule: &WriteRule) {
+        self.rules_applied.recorder(&[("rule", rule.kind())]).inc(1);
+    }
+}
+
+#[async_trait]
+impl DmlHandler for WriteRuleApplicator {
+    type WriteError = WriteRulesError;
+
+    type WriteInput = HashMap<String, MutableBatch>;
+    type WriteOutput = Self::WriteInput;
+
+    /// Apply the namespace write rules to the per-table [`MutableBatch`].
+    async fn write(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: Arc<NamespaceSchema>,
+        mut batches: Self::WriteInput,
+        _span_ctx: Option<SpanContext>,
+    ) -> Result<Self::WriteOutput, Self::WriteError> {
+        for rule in namespace_schema.write_rules.iter() {
+            if apply_rule(rule, &mut batches)? {
+                debug!(%namespace, rule = rule.kind(), "applied write rule");
+                self.record(rule);
+            }
+        }
+
+        Ok(batches)
+    }
+}
+
+/// Apply `rule` to `batches`, returning true if the write was modified.
+fn apply_rule(
+    rule: &WriteRule,
+    batches: &mut HashMap<String, MutableBatch>,
+) -> Result<bool, WriteRulesError> {
+    let n_tables = batches.len();
+
+    match rule {
+        WriteRule::DropMeasurement { measurement } => {
+            batches.retain(|table_name, _| !measurement.is_match(table_name));
+            Ok(batches.len() != n_tables)
+        }
+        WriteRule::DropField { measurement, field } => {
+            let mut modified = false;
+            for (table_name, batch) in batches.iter_mut() {
+                if matches!(measurement, Some(m) if !m.is_match(table_name)) {
+                    continue;
+                }
+
+                let drop = batch
+                    .columns()
+                    .filter(|(name, col)| {
+                        matches!(col.influx_type(), InfluxColumnType::Field(_))
+                            && field.is_match(name)
+                    })
+                    .map(|(name, _)| name.clone())
+                    .collect::<Vec<_>>();
+
+                for name in drop {
+                    batch
+                        .drop_column(&name)
+                        .map_err(|source| WriteRulesError::Batch {
+                            rule: rule.kind(),
+                            table_name: table_name.clone(),
+                            source,
+                        })?;
+                    modified = true;
+                }
+            }
+
+            // A line must contain at least one field.
+            batches.retain(|_, batch| {
+                batch
+                    .columns()
+                    .any(|(_, col)| matches!(col.influx_type(), InfluxColumnType::Field(_)))
+            });
+
+            Ok(modified || batches.len() != n_tables)
+        }
+        WriteRule::RenameTag { from, to } => {
+            let mut modified = false;
+            for (table_name, batch) in batches.iter_mut() {
+                let is_tag = matches!(
+                    batch.column(from).map(|c| c.influx_type()),
+                    Ok(InfluxColumnType::Tag)
+                );
+                if !is_tag || batch.column(to).is_ok() {
+                    continue;
+                }
+
+                batch
+                    .rename_column(from, to)
+                    .map_err(|source| WriteRulesError::Batch {
+                        rule: rule.kind(),
+                        table_name: table_name.clone(),
+                        source,
+                    })?;
+                modified = true;
+            }
+            Ok(modified)
+        }
+        WriteRule::AddTag { key, value } => {
+            let mut modified = false;
+            for (table_name, batch) in batches.iter_mut() {
+                if batch.column(key).is_ok() {
+                    continue;
+                }
+
+                batch
+                    .add_tag(key, value)
+                    .map_err(|source| WriteRulesError::Batch {
+                        rule: rule.kind(),
+                        table_name: table_name.clone(),
+                        source,
+                    })?;
+                modified = true;
+            }
+            Ok(modified)
+        }
+        WriteRule::DropLongTagValues { max_length } => {
+            let mut modified = false;
+            for (table_name, batch) in batches.iter_mut() {
+                let keep = rows_within_tag_length(batch, *max_length);
+                if keep.len() == 1 && keep[0] == (0..batch.rows()) {
+                    continue;
+                }
+
+                let mut filtered = MutableBatch::new();
+                filtered
+                    .extend_from_ranges(batch, &keep)
+                    .map_err(|source| WriteRulesError::Batch {
+                        rule: rule.kind(),
+                        table_name: table_name.clone(),
+                        source,
+                    })?;
+                *batch = filtered;
+                modified = true;
+            }
+
+            batches.retain(|_, batch| batch.rows() > 0);
+
+            Ok(modified)
+        }
+    }
+}
+
+/// Return the ranges of rows in `batch` that contain no tag values longer
+/// than `max_length` bytes.
+fn rows_within_tag_length(batch: &MutableBatch, max_length: usize) -> Vec<Range<usize>> {
+    let mut drop = vec![false; batch.rows()];
+
+    for (_, col) in batch.columns() {
+        let (ids, dictionary) = match col.data() {
+            ColumnData::Tag(ids, dictionary, _) => (ids, dictionary),
+            _ => continue,
+        };
+
+        // Resolve which dictionary entries are too long once, rather than for
+        // every row.
+        let too_long = dictionary
+            .values()
+            .iter()
+            .map(|v| v.len() > max_length)
+            .collect::<Vec<_>>();
+        if !too_long.contains(&true) {
+            continue;
+        }
+
+        for (row, id) in ids.iter().enumerate() {
+            if col.valid_mask().get(row) && too_long[*id as usize] {
+                drop[row] = true;
+            }
+        }
+    }
+
+    let mut ranges = Vec::new();
+    let mut start = None;
+    for (row, drop) in drop.iter().enumerate() {
+        match (start, drop) {
+            (None, false) => start = Some(row),
+            (Some(s), true) => {
+                ranges.push(s..row);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        ranges.push(s..batch.rows());
+    }
+
+    ranges
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use data_types::WriteRules;
+    use generated_types::influxdata::iox::write_rules::v1 as proto;
+    use metric::{Attributes, Registry};
+    use once_cell::sync::Lazy;
+
+    use super::*;
+    use crate::test_helpers::new_empty_namespace_schema;
+
+    static NAMESPACE: Lazy<NamespaceName<'static>> = Lazy::new(|| "bananas".try_into().unwrap());
+
+    fn lp_to_writes(lp: &str) -> HashMap<String, MutableBatch> {
+        let (writes, _) = mutable_batch_lp::lines_to_batches_stats(lp, 42)
+            .expect("failed to build test writes from LP");
+        writes
+    }
+
+    fn schema_with_rules(
+        rules: impl IntoIterator<Item = proto::write_rule::Rule>,
+    ) -> Arc<NamespaceSchema> {
+        let mut schema = new_empty_namespace_schema(42);
+        schema.write_rules = WriteRules::try_from(proto::WriteRules {
+            rules: rules
+                .into_iter()
+                .map(|r| proto::WriteRule { rule: Some(r) })
+                .collect(),
+        })
+        .expect("valid rules");
+        Arc::new(schema)
+    }
+
+    /// Return the tag values of `column` in `batch`, in row order.
+    fn tag_values(batch: &MutableBatch, column: &str) -> Vec<String> {
+        assert_matches!(batch.column(column).unwrap().data(), ColumnData::Tag(ids, dict, _) => {
+            ids.iter()
+                .map(|id| dict.lookup_id(*id).unwrap().to_string())
+                .collect()
+        })
+    }
+
+    fn assert_applied(metrics: &Registry, rule: &'static str, want: u64) {
+        let got = metrics
+            .get_instrument::<Metric<U64Counter>>("write_rules_applied")
+            .expect("metric not registered")
+            .get_observer(&Attributes::from(&[("rule", rule)]))
+            .map(|v| v.fetch())
+            .unwrap_or_default();
+        assert_eq!(got, want, "unexpected count for rule {rule}");
+    }
+
+    #[tokio::test]
+    async fn test_no_rules() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let writes = lp_to_writes("bananas,tag1=A val=42i 1\nplatanos val=1i 2");
+        let got = handler
+            .write(&NAMESPACE, schema_with_rules([]), writes, None)
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(got.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_drop_measurement() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::DropMeasurement(
+            proto::DropMeasurement {
+                measurement_regex: "plat.*".to_string(),
+            },
+        )]);
+
+        let writes = lp_to_writes("bananas val=42i 1\nplatanos val=1i 2\nxplatanos val=1i 2");
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        // The regex is anchored, so "xplatanos" is not dropped.
+        let mut tables = got.keys().map(String::as_str).collect::<Vec<_>>();
+        tables.sort_unstable();
+        assert_eq!(tables, ["bananas", "xplatanos"]);
+
+        assert_applied(&metrics, "drop_measurement", 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_field() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::DropField(proto::DropField {
+            measurement_regex: "bananas".to_string(),
+            field_regex: "debug_.*".to_string(),
+        })]);
+
+        let writes = lp_to_writes(
+            "\
+            bananas,debug_tag=A val=42i,debug_count=1i 1\n\
+            platanos val=1i,debug_count=2i 2\n\
+        ",
+        );
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        // Only fields in the matching measurement are dropped, and tags are
+        // never dropped.
+        assert_eq!(
+            got["bananas"].column_names(),
+            ["debug_tag", "time", "val"].into_iter().collect()
+        );
+        assert_eq!(
+            got["platanos"].column_names(),
+            ["debug_count", "time", "val"].into_iter().collect()
+        );
+
+        assert_applied(&metrics, "drop_field", 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_all_fields_drops_table() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::DropField(proto::DropField {
+            measurement_regex: String::new(),
+            field_regex: "val".to_string(),
+        })]);
+
+        let writes = lp_to_writes("bananas,tag=A val=42i 1\nplatanos val=1i,other=2i 2");
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(got.keys().collect::<Vec<_>>(), ["platanos"]);
+    }
+
+    #[tokio::test]
+    async fn test_rename_tag() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::RenameTag(proto::RenameTag {
+            from: "host".to_string(),
+            to: "hostname".to_string(),
+        })]);
+
+        let writes = lp_to_writes(
+            "\
+            bananas,host=A val=42i 1\n\
+            platanos,host=A,hostname=B val=1i 2\n\
+            fields host=\"A\" 3\n\
+        ",
+        );
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        // Renamed
+        assert_eq!(
+            got["bananas"].column_names(),
+            ["hostname", "time", "val"].into_iter().collect()
+        );
+        // Target already exists
+        assert_eq!(
+            got["platanos"].column_names(),
+            ["host", "hostname", "time", "val"].into_iter().collect()
+        );
+        // Not a tag
+        assert_eq!(
+            got["fields"].column_names(),
+            ["host", "time"].into_iter().collect()
+        );
+
+        assert_applied(&metrics, "rename_tag", 1);
+    }
+
+    #[tokio::test]
+    async fn test_add_tag() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::AddTag(proto::AddTag {
+            key: "region".to_string(),
+            value: "eu".to_string(),
+        })]);
+
+        let writes = lp_to_writes("bananas val=42i 1\nplatanos,region=us val=1i 2");
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(tag_values(&got["bananas"], "region"), ["eu"]);
+        assert_eq!(tag_values(&got["platanos"], "region"), ["us"]);
+
+        assert_applied(&metrics, "add_tag", 1);
+    }
+
+    #[tokio::test]
+    async fn test_drop_long_tag_values() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        let schema = schema_with_rules([proto::write_rule::Rule::DropLongTagValues(
+            proto::DropLongTagValues { max_length: 3 },
+        )]);
+
+        let writes = lp_to_writes(
+            "\
+            bananas,a=1 val=1i 1\n\
+            bananas,a=1234 val=2i 2\n\
+            bananas,b=123 val=3i 3\n\
+            bananas,a=12,b=12345 val=4i 4\n\
+            bananas val=5i 5\n\
+            platanos,a=1234 val=1i 1\n\
+            short,a=1 val=1i 1\n\
+        ",
+        );
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        // All rows of "platanos" are dropped, so is the table.
+        assert_matches!(got.get("platanos"), None);
+        assert_eq!(got["short"].rows(), 1);
+
+        assert_matches!(got["bananas"].column("val").unwrap().data(), ColumnData::I64(v, _) => {
+            assert_eq!(v, &[1, 3, 5]);
+        });
+
+        assert_applied(&metrics, "drop_long_tag_values", 1);
+    }
+
+    #[tokio::test]
+    async fn test_rules_applied_in_order() {
+        let metrics = Registry::default();
+        let handler = WriteRuleApplicator::new(&metrics);
+
+        // The rename makes the added tag a no-op for "bananas", and the
+        // "platanos" measurement the tag is added to is then dropped.
+        let schema = schema_with_rules([
+            proto::write_rule::Rule::RenameTag(proto::RenameTag {
+                from: "dc".to_string(),
+                to: "region".to_string(),
+            }),
+            proto::write_rule::Rule::AddTag(proto::AddTag {
+                key: "region".to_string(),
+                value: "default".to_string(),
+            }),
+            proto::write_rule::Rule::DropMeasurement(proto::DropMeasurement {
+                measurement_regex: "platanos".to_string(),
+            }),
+        ]);
+
+        let writes = lp_to_writes("bananas,dc=eu val=42i 1\nplatanos val=1i 2");
+        let got = handler
+            .write(&NAMESPACE, schema, writes, None)
+            .await
+            .expect("write should succeed");
+
+        assert_eq!(got.keys().collect::<Vec<_>>(), ["bananas"]);
+        assert_eq!(
+            got["bananas"].column_names(),
+            ["region", "time", "val"].into_iter().collect()
+        );
+        assert_eq!(tag_values(&got["bananas"], "region"), ["eu"]);
+
+        assert_applied(&metrics, "rename_tag", 1);
+        assert_applied(&metrics, "add_tag", 1);
+        assert_applied(&metrics, "drop_measurement", 1);
+    }
+}
@@ -118,6 +118,8 @@ pub mod prop_gen {
             (name.to_string(), TableSchema {
                 id: deterministic_id_for_table_name(name),
                 partition_template: Default::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns,
             })
         }
@@ -147,6 +149,10 @@ pub mod prop_gen {
                 max_columns_per_table: MaxColumnsPerTable::try_from(max_columns_per_table).unwrap(),
                 retention_period_ns,
                 partition_template: Default::default(),
+                schema_mode: Default::default(),
+                field_coercion: Default::default(),
+                write_rules: Default::default(),
+                properties_version: 0,
             }
         }
     }
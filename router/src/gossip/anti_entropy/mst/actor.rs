@@ -335,6 +335,8 @@ mod tests {
                 "bananas".to_string(),
                 TableSchema {
                     id: TableId::new(24),
+                    retention_period_ns: None,
+                    partition_template_version: 0,
                     columns: ColumnsByName::new([data_types::Column {
                         name: "platanos".to_string(),
                         column_type: data_types::ColumnType::String,
@@ -69,6 +69,11 @@ impl<T> AntiEntropyService<T> {
     pub fn new(mst: AntiEntropyHandle, cache: T) -> Self {
         Self { mst, cache }
     }
+
+    /// Return the [`NamespaceCache`] this service reads schemas from.
+    pub(crate) fn cache(&self) -> &T {
+        &self.cache
+    }
 }
 
 #[async_trait]
@@ -186,9 +191,12 @@ mod tests {
     use std::sync::Arc;
 
     use data_types::NamespaceId;
-    use generated_types::influxdata::iox::gossip::v1::{
-        anti_entropy_service_server::AntiEntropyService as _, DiffRange, NamespaceCreated,
-        PageRange,
+    use generated_types::influxdata::iox::{
+        gossip::v1::{
+            anti_entropy_service_server::AntiEntropyService as _, DiffRange, NamespaceCreated,
+            PageRange,
+        },
+        namespace::v1::SchemaMode,
     };
 
     use crate::{
@@ -205,6 +213,10 @@ mod tests {
             max_columns_per_table: Default::default(),
             retention_period_ns: Default::default(),
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         }
     }
 
@@ -294,6 +306,10 @@ mod tests {
                             max_columns_per_table: 200,
                             max_tables: 500,
                             retention_period_ns: None,
+                            write_rules: None,
+                            schema_mode: SchemaMode::Implicit.into(),
+                            properties_version: 0,
+                            field_coercion: Some(Default::default()),
                         }),
                         tables: vec![],
                     },
@@ -305,6 +321,10 @@ mod tests {
                             max_columns_per_table: 200,
                             max_tables: 500,
                             retention_period_ns: None,
+                            write_rules: None,
+                            schema_mode: SchemaMode::Implicit.into(),
+                            properties_version: 0,
+                            field_coercion: Some(Default::default()),
                         }),
                         tables: vec![],
                     }
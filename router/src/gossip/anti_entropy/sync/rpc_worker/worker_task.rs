@@ -148,11 +148,13 @@ mod tests {
             NamespacePartitionTemplateOverride, TablePartitionTemplateOverride,
             PARTITION_BY_DAY_PROTO,
         },
-        Column, ColumnId, ColumnType, ColumnsByName, MaxColumnsPerTable, MaxTables, NamespaceId,
-        NamespaceName, NamespaceSchema, TableId, TableSchema,
+        Column, ColumnId, ColumnType, ColumnsByName, FieldCoercionRules, MaxColumnsPerTable,
+        MaxTables, NamespaceId, NamespaceName, NamespaceSchema, NamespaceSchemaMode, TableId,
+        TableSchema, WriteRules,
     };
-    use generated_types::influxdata::iox::gossip::v1::{
-        NamespaceCreated, NamespaceSchemaEntry, TableCreated, TableUpdated,
+    use generated_types::influxdata::iox::{
+        gossip::v1::{NamespaceCreated, NamespaceSchemaEntry, TableCreated, TableUpdated},
+        namespace::v1::SchemaMode,
     };
 
     use crate::{
@@ -179,6 +181,10 @@ mod tests {
         max_tables: MaxTables::const_default(),
         retention_period_ns: None,
         partition_template: DEFAULT_NAMESPACE_PARTITION_TEMPLATE,
+        schema_mode: NamespaceSchemaMode::Implicit,
+        field_coercion: FieldCoercionRules::NONE,
+        write_rules: WriteRules::const_default(),
+        properties_version: 0,
     };
 
     /// Assert that a sync worker will request the appropriate gossip events
@@ -220,6 +226,10 @@ mod tests {
                                 as _,
                             max_tables: DEFAULT_NAMESPACE.max_tables.get() as _,
                             retention_period_ns: DEFAULT_NAMESPACE.retention_period_ns,
+                            write_rules: None,
+                            schema_mode: SchemaMode::Implicit.into(),
+                            properties_version: 0,
+                            field_coercion: None,
                         }),
                         tables: vec![
                             TableCreated {
@@ -230,6 +240,8 @@ mod tests {
                                     columns: vec![],
                                 }),
                                 partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+                                partition_template_version: 0,
+                                retention_period_ns: None,
                             },
                             TableCreated {
                                 table: Some(TableUpdated {
@@ -239,6 +251,8 @@ mod tests {
                                     columns: vec![],
                                 }),
                                 partition_template: None,
+                                partition_template_version: 0,
+                                retention_period_ns: None,
                             },
                         ],
                     }]),
@@ -251,6 +265,10 @@ mod tests {
                             max_columns_per_table: 1234,
                             max_tables: 666,
                             retention_period_ns: Some(4321),
+                            write_rules: None,
+                            schema_mode: SchemaMode::Implicit.into(),
+                            properties_version: 0,
+                            field_coercion: None,
                         }),
                         tables: vec![TableCreated {
                             table: Some(TableUpdated {
@@ -266,6 +284,8 @@ mod tests {
                                 ],
                             }),
                             partition_template: None,
+                            partition_template_version: 0,
+                            retention_period_ns: None,
                         }],
                     }]),
                 ]),
@@ -297,6 +317,8 @@ mod tests {
                         "bananas1",
                         TableSchema {
                             id: TableId::new(421),
+                            retention_period_ns: None,
+                            partition_template_version: 0,
                             partition_template: TablePartitionTemplateOverride::try_new(
                                 Some((**PARTITION_BY_DAY_PROTO).clone()),
                                 &DEFAULT_NAMESPACE_PARTITION_TEMPLATE,
@@ -309,6 +331,8 @@ mod tests {
                         "bananas2",
                         TableSchema {
                             id: TableId::new(422),
+                            retention_period_ns: None,
+                            partition_template_version: 0,
                             partition_template: TablePartitionTemplateOverride::try_new(
                                 None,
                                 &NamespacePartitionTemplateOverride::try_from(
@@ -351,6 +375,8 @@ mod tests {
                     TableSchema {
                         id: TableId::new(423),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
+                        partition_template_version: 0,
                         columns: ColumnsByName::new([Column {
                             id: ColumnId::new(101),
                             table_id: TableId::new(423),
@@ -362,6 +388,10 @@ mod tests {
                 .map(|(a, b)| (a.to_string(), b))
                 .into_iter()
                 .collect(),
+                schema_mode: Default::default(),
+                field_coercion: Default::default(),
+                write_rules: Default::default(),
+                properties_version: 0,
             }
         );
     }
@@ -53,8 +53,9 @@ pub mod schema_change_observer;
 pub mod traits;
 
 use data_types::{NamespaceName, NamespaceSchema};
-use generated_types::influxdata::iox::gossip::v1::{
-    Column, NamespaceCreated, TableCreated, TableUpdated,
+use generated_types::influxdata::iox::{
+    gossip::v1::{Column, NamespaceCreated, NamespaceUpdated, TableCreated, TableUpdated},
+    namespace::v1::SchemaMode,
 };
 
 /// Make a `NamespaceCreated` protobuf instance from the specified name and schema.
@@ -69,6 +70,27 @@ pub(crate) fn namespace_created(
         max_tables: schema.max_tables.get() as u64,
         max_columns_per_table: schema.max_columns_per_table.get() as u64,
         retention_period_ns: schema.retention_period_ns,
+        write_rules: schema.write_rules.as_proto().cloned(),
+        schema_mode: SchemaMode::from(schema.schema_mode).into(),
+        properties_version: schema.properties_version,
+        field_coercion: Some(schema.field_coercion.into()),
+    }
+}
+
+/// Make a [`NamespaceUpdated`] protobuf instance carrying the mutable
+/// properties of `schema`.
+pub(crate) fn namespace_updated(
+    namespace_name: impl Into<String>,
+    schema: &NamespaceSchema,
+) -> NamespaceUpdated {
+    NamespaceUpdated {
+        namespace_name: namespace_name.into(),
+        namespace_id: schema.id.get(),
+        write_rules: schema.write_rules.as_proto().cloned(),
+        schema_mode: SchemaMode::from(schema.schema_mode).into(),
+        properties_version: schema.properties_version,
+        partition_template: schema.partition_template.as_proto().cloned(),
+        field_coercion: Some(schema.field_coercion.into()),
     }
 }
 
@@ -94,6 +116,8 @@ pub(crate) fn table_created(
                 .collect(),
         }),
         partition_template: schema.partition_template.as_proto().cloned(),
+        partition_template_version: schema.partition_template_version,
+        retention_period_ns: schema.retention_period_ns,
     }
 }
 
@@ -181,6 +205,8 @@ mod tests {
             "platanos".to_string(),
             TableSchema {
                 id: TableId::new(4242),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 partition_template: test_table_partition_override(vec![
                     data_types::partition_template::TemplatePart::TagValue("bananatastic"),
                 ]),
@@ -227,6 +253,8 @@ mod tests {
             "platanos".to_string(),
             TableSchema {
                 id: TableId::new(4242),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 partition_template: TablePartitionTemplateOverride::try_new(
                     None,
                     &NamespacePartitionTemplateOverride::default(),
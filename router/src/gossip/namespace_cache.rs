@@ -5,11 +5,12 @@ use std::{borrow::Cow, collections::BTreeMap, fmt::Debug};
 use async_trait::async_trait;
 use data_types::{
     partition_template::{NamespacePartitionTemplateOverride, TablePartitionTemplateOverride},
-    ColumnSchema, ColumnsByName, MaxColumnsPerTable, MaxTables, NamespaceId, NamespaceName,
-    NamespaceNameError, NamespaceSchema, TableId, TableSchema,
+    ColumnSchema, ColumnsByName, FieldCoercionRules, MaxColumnsPerTable, MaxTables, NamespaceId,
+    NamespaceName, NamespaceNameError, NamespaceSchema, NamespaceSchemaMode, TableId, TableSchema,
+    WriteRules,
 };
 use generated_types::influxdata::iox::gossip::v1::{
-    schema_message::Event, NamespaceCreated, TableCreated, TableUpdated,
+    schema_message::Event, NamespaceCreated, NamespaceUpdated, TableCreated, TableUpdated,
 };
 use gossip_schema::dispatcher::SchemaEventHandler;
 use observability_deps::tracing::{debug, error, trace, warn};
@@ -29,6 +30,9 @@ enum Error {
     #[error("invalid partition template: {0}")]
     PartitionTemplate(#[from] data_types::partition_template::ValidationError),
 
+    #[error("invalid write rules: {0}")]
+    WriteRules(#[from] data_types::write_rules::ValidationError),
+
     #[error("invalid column schema: {0}")]
     ColumnSchema(Box<dyn std::error::Error>),
 
@@ -100,6 +104,7 @@ where
             Event::NamespaceCreated(v) => self.handle_namespace_created(v).await,
             Event::TableCreated(v) => self.handle_table_created(v).await,
             Event::TableUpdated(v) => self.handle_updated_table(v).await,
+            Event::NamespaceUpdated(v) => self.handle_namespace_updated(v).await,
         };
 
         if let Err(error) = res {
@@ -147,15 +152,29 @@ where
             .transpose()?
             .unwrap_or_default();
 
+        let write_rules = note
+            .write_rules
+            .map(WriteRules::try_from)
+            .transpose()?
+            .unwrap_or_default();
+        let schema_mode = NamespaceSchemaMode::from(note.schema_mode());
+        let field_coercion = note
+            .field_coercion
+            .map(FieldCoercionRules::from)
+            .unwrap_or_default();
+
         // Insert the namespace or do nothing if it exists.
         match self.inner.get_schema(&namespace_name).await {
             Ok(v) => {
                 // It does! This is a no-op.
 
-                // Invariant: name -> ID mappings & partition templates MUST be
-                // immutable and consistent across the cluster.
+                // Invariant: name -> ID mappings MUST be immutable and
+                // consistent across the cluster.
+                //
+                // The partition template may have been changed since this
+                // message was sent - changes are applied by the
+                // NamespaceUpdated handler.
                 assert_eq!(v.id.get(), note.namespace_id);
-                assert_eq!(v.partition_template, partition_template);
 
                 return Ok(());
             }
@@ -187,6 +206,10 @@ where
                         .unwrap_or_default(),
                         retention_period_ns: note.retention_period_ns,
                         partition_template,
+                        schema_mode,
+                        field_coercion,
+                        write_rules,
+                        properties_version: note.properties_version,
                     },
                 );
             }
@@ -195,6 +218,72 @@ where
         Ok(())
     }
 
+    /// Handle a gossip event replacing the mutable properties of a namespace.
+    ///
+    /// If the local peer does not know of this namespace, this is a no-op -
+    /// the current properties are loaded from the catalog with the rest of the
+    /// namespace schema when it is first used.
+    ///
+    /// # Panics
+    ///
+    /// This method panics if the gossiped namespace ID does not match the
+    /// local state.
+    async fn handle_namespace_updated(&self, note: NamespaceUpdated) -> Result<(), Error> {
+        let namespace_name = NamespaceName::try_from(note.namespace_name.clone())?;
+        let schema_mode = NamespaceSchemaMode::from(note.schema_mode());
+        let field_coercion = note
+            .field_coercion
+            .map(FieldCoercionRules::from)
+            .unwrap_or_default();
+        let write_rules = note
+            .write_rules
+            .map(WriteRules::try_from)
+            .transpose()?
+            .unwrap_or_default();
+        let partition_template = note
+            .partition_template
+            .map(NamespacePartitionTemplateOverride::try_from)
+            .transpose()?
+            .unwrap_or_default();
+
+        let ns = match self.inner.get_schema(&namespace_name).await {
+            Ok(v) => v,
+            Err(CacheMissErr { .. }) => {
+                trace!(%namespace_name, "ignoring properties for unknown namespace");
+                return Ok(());
+            }
+        };
+
+        // Invariant: name -> ID mappings MUST be immutable and consistent
+        // across the cluster.
+        assert_eq!(ns.id.get(), note.namespace_id);
+
+        // Ignore properties that are not newer than those already applied,
+        // which may be received out of order.
+        if note.properties_version <= ns.properties_version {
+            return Ok(());
+        }
+
+        debug!(
+            %namespace_name,
+            n_rules = write_rules.iter().count(),
+            %schema_mode,
+            ?field_coercion,
+            ?partition_template,
+            "applying namespace properties received via gossip"
+        );
+
+        let mut ns = NamespaceSchema::clone(&ns);
+        ns.write_rules = write_rules;
+        ns.schema_mode = schema_mode;
+        ns.field_coercion = field_coercion;
+        ns.partition_template = partition_template;
+        ns.properties_version = note.properties_version;
+        self.inner.put_schema(namespace_name, ns);
+
+        Ok(())
+    }
+
     /// Handle a gossip event for a table schema update.
     ///
     /// The local peer MAY or MAY NOT already know about this table and
@@ -243,7 +332,9 @@ where
     /// If the local peer does not know of this namespace, this is a no-op.
     ///
     /// If the local peer already knows of this table, the contents are merged,
-    /// and the immutable fields are verified to be identical.
+    /// and the immutable fields are verified to be identical. The partition
+    /// template is replaced if the gossiped version is newer than the local
+    /// one.
     ///
     /// # Panics
     ///
@@ -265,6 +356,7 @@ where
 
         let partition_template =
             TablePartitionTemplateOverride::try_new(v.partition_template, &ns.partition_template)?;
+        let partition_template_version = v.partition_template_version;
 
         // Attempt to apply this update.
         //
@@ -276,12 +368,27 @@ where
         // the NamespaceCache for later reuse.
         let table = match ns.tables.get(&update.table_name) {
             Some(v) => {
-                // Invariant: name -> ID mappings & partition templates MUST be
-                // immutable and consistent across the cluster.
+                // Invariant: name -> ID mappings MUST be immutable and
+                // consistent across the cluster.
                 assert_eq!(v.id, table_id);
-                assert_eq!(v.partition_template, partition_template);
 
-                update_table(v, update)?
+                // Replace the partition template if the peer has observed a
+                // newer version of it.
+                if v.partition_template_version < partition_template_version {
+                    debug!(
+                        table_name=%update.table_name,
+                        %table_id,
+                        ?partition_template,
+                        partition_template_version,
+                        "applying table partition template received via gossip"
+                    );
+                    let mut v = v.clone();
+                    v.partition_template = partition_template;
+                    v.partition_template_version = partition_template_version;
+                    Some(update_table(&v, update)?.unwrap_or(v))
+                } else {
+                    update_table(v, update)?
+                }
             }
             None => {
                 // Decode the columns within this update
@@ -305,6 +412,8 @@ where
                 Some(TableSchema {
                     id: table_id,
                     partition_template,
+                    retention_period_ns: v.retention_period_ns,
+                    partition_template_version,
                     columns: ColumnsByName::from(columns),
                 })
             }
@@ -394,12 +503,19 @@ mod tests {
 
     use assert_matches::assert_matches;
     use data_types::{
-        partition_template::{NamespacePartitionTemplateOverride, PARTITION_BY_DAY_PROTO},
+        partition_template::{
+            test_table_partition_override, NamespacePartitionTemplateOverride, TemplatePart,
+            PARTITION_BY_DAY_PROTO,
+        },
         ColumnId, ColumnType,
     };
+    use generated_types::influxdata::iox::{
+        namespace::v1::{self as namespace_proto, SchemaMode},
+        write_rules::v1 as write_rules_proto,
+    };
 
     use crate::{
-        gossip::namespace_created,
+        gossip::{namespace_created, namespace_updated},
         namespace_cache::{CacheMissErr, MemoryNamespaceCache},
         test_helpers::{
             new_empty_namespace_schema, DEFAULT_NAMESPACE, DEFAULT_NAMESPACE_PARTITION_TEMPLATE,
@@ -417,6 +533,9 @@ mod tests {
         assert_eq!(left.max_columns_per_table, right.max_columns_per_table);
         assert_eq!(left.retention_period_ns, right.retention_period_ns);
         assert_eq!(left.partition_template, right.partition_template);
+        assert_eq!(left.schema_mode, right.schema_mode);
+        assert_eq!(left.write_rules, right.write_rules);
+        assert_eq!(left.properties_version, right.properties_version);
     }
 
     /// Generate a test that processes the provided gossip message, and asserts
@@ -471,6 +590,8 @@ mod tests {
                 columns: vec![],
             }),
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Err(CacheMissErr { .. })
     );
@@ -489,12 +610,14 @@ mod tests {
                 columns: vec![],
             }),
             partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_namespace_attributes_eq(&ns, &new_empty_namespace_schema(4242));
             assert_eq!(ns.tables.len(), 1);
 
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns, .. }) => {
                 assert_eq!(id.get(), 42);
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     Some((**PARTITION_BY_DAY_PROTO).clone()),
@@ -524,13 +647,16 @@ mod tests {
                 ],
             }),
             partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+            partition_template_version: 0,
+            retention_period_ns: Some(1234),
         }),
         want = Ok(ns) => {
             assert_namespace_attributes_eq(&ns, &DEFAULT_NAMESPACE);
             assert_eq!(ns.tables.len(), 1);
 
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, retention_period_ns, columns, .. }) => {
                 assert_eq!(id.get(), 42);
+                assert_eq!(*retention_period_ns, Some(1234));
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     Some((**PARTITION_BY_DAY_PROTO).clone()),
                     &DEFAULT_NAMESPACE_PARTITION_TEMPLATE,
@@ -557,12 +683,14 @@ mod tests {
                 columns: vec![],
             }),
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_namespace_attributes_eq(&ns, &DEFAULT_NAMESPACE);
             assert_eq!(ns.tables.len(), 1);
 
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns, .. }) => {
                 assert_eq!(id.get(), 42);
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     None,
@@ -584,6 +712,8 @@ mod tests {
             let table = TableSchema{
                 id: TableId::new(1234), // Same table ID
                 partition_template: TablePartitionTemplateOverride::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns: ColumnsByName::new(vec![]),
             };
 
@@ -606,6 +736,8 @@ mod tests {
                 ],
             }),
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_namespace_attributes_eq(&ns, &DEFAULT_NAMESPACE);
@@ -617,7 +749,7 @@ mod tests {
             });
 
             // The new table was merged in
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns, .. }) => {
                 assert_eq!(id.get(), 42);
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     None,
@@ -641,6 +773,8 @@ mod tests {
             let mut table = TableSchema{
                 id: TableId::new(42), // Same table ID
                 partition_template: TablePartitionTemplateOverride::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns: ColumnsByName::new(vec![]),
             };
 
@@ -668,12 +802,14 @@ mod tests {
                 ],
             }),
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_namespace_attributes_eq(&ns, &DEFAULT_NAMESPACE);
             assert_eq!(ns.tables.len(), 1);
 
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns, .. }) => {
                 assert_eq!(id.get(), 42);
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     None,
@@ -701,6 +837,8 @@ mod tests {
         message = Event::TableCreated(TableCreated {
             table: None, // No inner content!
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_eq!(*ns, DEFAULT_NAMESPACE); // Unmodified
@@ -719,6 +857,8 @@ mod tests {
                 columns: vec![],
             }),
             partition_template: None,
+            partition_template_version: 0,
+            retention_period_ns: None,
         }),
         want = Ok(ns) => {
             assert_eq!(*ns, DEFAULT_NAMESPACE); // Unmodified
@@ -781,6 +921,8 @@ mod tests {
             let mut table = TableSchema{
                 id: TableId::new(42), // Same table ID
                 partition_template: TablePartitionTemplateOverride::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns: ColumnsByName::new(vec![]),
             };
 
@@ -814,7 +956,7 @@ mod tests {
             assert_namespace_attributes_eq(&ns, &DEFAULT_NAMESPACE);
             assert_eq!(ns.tables.len(), 1);
 
-            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns }) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { id, partition_template, columns, .. }) => {
                 assert_eq!(id.get(), 42);
                 assert_eq!(*partition_template, TablePartitionTemplateOverride::try_new(
                     None,
@@ -883,12 +1025,12 @@ mod tests {
         namespace_created_existing,
         existing = Some(DEFAULT_NAMESPACE),
         message = Event::NamespaceCreated(NamespaceCreated {
-            // The partition template is not allowed to change over the lifetime
-            // of the namespace.
-            partition_template: DEFAULT_NAMESPACE.partition_template.as_proto().cloned(),
-            // But these fields can change.
+            // The partition template and the fields below may differ from the
+            // local state (partition template changes are applied by
+            // NamespaceUpdated messages only).
             //
             // They will be ignored, and the local values used instead.
+            partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
             max_tables: 123456,
             max_columns_per_table: 123456,
             retention_period_ns: Some(123456),
@@ -899,4 +1041,266 @@ mod tests {
             assert_eq!(*v, DEFAULT_NAMESPACE);
         }
     );
+
+    fn test_write_rules() -> write_rules_proto::WriteRules {
+        write_rules_proto::WriteRules {
+            rules: vec![write_rules_proto::WriteRule {
+                rule: Some(write_rules_proto::write_rule::Rule::AddTag(
+                    write_rules_proto::AddTag {
+                        key: "region".to_string(),
+                        value: "eu".to_string(),
+                    },
+                )),
+            }],
+        }
+    }
+
+    // A create message arrives for an unknown namespace with write rules.
+    test_handle_gossip_message_!(
+        namespace_created_write_rules,
+        existing = None,
+        message = Event::NamespaceCreated(NamespaceCreated {
+            write_rules: Some(test_write_rules()),
+            ..namespace_created(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.write_rules = WriteRules::try_from(test_write_rules()).unwrap();
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A create message arrives for an unknown namespace in explicit schema
+    // mode.
+    test_handle_gossip_message_!(
+        namespace_created_schema_mode,
+        existing = None,
+        message = Event::NamespaceCreated(NamespaceCreated {
+            schema_mode: SchemaMode::Explicit.into(),
+            ..namespace_created(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.schema_mode = NamespaceSchemaMode::Explicit;
+            assert_eq!(*v, want);
+        }
+    );
+
+    fn test_field_coercion() -> namespace_proto::FieldCoercionRules {
+        namespace_proto::FieldCoercionRules {
+            int_to_float: true,
+            ..Default::default()
+        }
+    }
+
+    // A create message arrives for an unknown namespace with field coercion
+    // rules.
+    test_handle_gossip_message_!(
+        namespace_created_field_coercion,
+        existing = None,
+        message = Event::NamespaceCreated(NamespaceCreated {
+            field_coercion: Some(test_field_coercion()),
+            ..namespace_created(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.field_coercion = FieldCoercionRules::from(test_field_coercion());
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A field coercion update arrives for a known namespace.
+    test_handle_gossip_message_!(
+        field_coercion_updated,
+        existing = Some(DEFAULT_NAMESPACE),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            field_coercion: Some(test_field_coercion()),
+            properties_version: 1,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.field_coercion = FieldCoercionRules::from(test_field_coercion());
+            want.properties_version = 1;
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A write rules update arrives for a known namespace.
+    test_handle_gossip_message_!(
+        write_rules_updated,
+        existing = Some(DEFAULT_NAMESPACE),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            write_rules: Some(test_write_rules()),
+            properties_version: 1,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.write_rules = WriteRules::try_from(test_write_rules()).unwrap();
+            want.properties_version = 1;
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A write rules update removing all rules from a known namespace.
+    test_handle_gossip_message_!(
+        write_rules_updated_removed,
+        existing = Some({
+            let mut ns = DEFAULT_NAMESPACE.clone();
+            ns.write_rules = WriteRules::try_from(test_write_rules()).unwrap();
+            ns.properties_version = 1;
+            ns
+        }),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            write_rules: None,
+            properties_version: 2,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.properties_version = 2;
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A properties update older than the cached properties is ignored.
+    test_handle_gossip_message_!(
+        namespace_updated_stale,
+        existing = Some({
+            let mut ns = DEFAULT_NAMESPACE.clone();
+            ns.write_rules = WriteRules::try_from(test_write_rules()).unwrap();
+            ns.properties_version = 2;
+            ns
+        }),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            write_rules: None,
+            schema_mode: SchemaMode::Explicit.into(),
+            properties_version: 1,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.write_rules = WriteRules::try_from(test_write_rules()).unwrap();
+            want.properties_version = 2;
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A schema mode change arrives for a known namespace.
+    test_handle_gossip_message_!(
+        schema_mode_updated,
+        existing = Some(DEFAULT_NAMESPACE),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            schema_mode: SchemaMode::Explicit.into(),
+            properties_version: 1,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.schema_mode = NamespaceSchemaMode::Explicit;
+            want.properties_version = 1;
+            assert_eq!(*v, want);
+        }
+    );
+
+    // A partition template update arrives for a known namespace.
+    test_handle_gossip_message_!(
+        namespace_updated_partition_template,
+        existing = Some(DEFAULT_NAMESPACE),
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+            properties_version: 1,
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Ok(v) => {
+            let mut want = DEFAULT_NAMESPACE.clone();
+            want.partition_template = NamespacePartitionTemplateOverride::try_from((**PARTITION_BY_DAY_PROTO).clone()).unwrap();
+            want.properties_version = 1;
+            assert_eq!(*v, want);
+        }
+    );
+
+    fn test_table_template() -> TablePartitionTemplateOverride {
+        test_table_partition_override(vec![TemplatePart::TagValue("region")])
+    }
+
+    // A table create arrives for a known table carrying a newer partition
+    // template, which replaces the local template.
+    test_handle_gossip_message_!(
+        table_created_newer_partition_template,
+        existing = Some({
+            let mut ns = DEFAULT_NAMESPACE.clone();
+            ns.tables.insert("bananas".to_string(), TableSchema {
+                id: TableId::new(42),
+                partition_template: TablePartitionTemplateOverride::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
+                columns: ColumnsByName::new(vec![]),
+            });
+            ns
+        }),
+        message = Event::TableCreated(TableCreated {
+            table: Some(TableUpdated {
+                table_name: "bananas".to_string(),
+                namespace_name: NAMESPACE_NAME.to_string(),
+                table_id: 42,
+                columns: vec![],
+            }),
+            partition_template: test_table_template().as_proto().cloned(),
+            partition_template_version: 1,
+            retention_period_ns: None,
+        }),
+        want = Ok(ns) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { partition_template, partition_template_version, .. }) => {
+                assert_eq!(*partition_template, test_table_template());
+                assert_eq!(*partition_template_version, 1);
+            });
+        }
+    );
+
+    // A table create arrives for a known table carrying an older partition
+    // template, which is ignored.
+    test_handle_gossip_message_!(
+        table_created_stale_partition_template,
+        existing = Some({
+            let mut ns = DEFAULT_NAMESPACE.clone();
+            ns.tables.insert("bananas".to_string(), TableSchema {
+                id: TableId::new(42),
+                partition_template: test_table_template(),
+                retention_period_ns: None,
+                partition_template_version: 2,
+                columns: ColumnsByName::new(vec![]),
+            });
+            ns
+        }),
+        message = Event::TableCreated(TableCreated {
+            table: Some(TableUpdated {
+                table_name: "bananas".to_string(),
+                namespace_name: NAMESPACE_NAME.to_string(),
+                table_id: 42,
+                columns: vec![],
+            }),
+            partition_template: Some((**PARTITION_BY_DAY_PROTO).clone()),
+            partition_template_version: 1,
+            retention_period_ns: None,
+        }),
+        want = Ok(ns) => {
+            assert_matches!(ns.tables.get("bananas"), Some(TableSchema { partition_template, partition_template_version, .. }) => {
+                assert_eq!(*partition_template, test_table_template());
+                assert_eq!(*partition_template_version, 2);
+            });
+        }
+    );
+
+    // A properties update arrives for an unknown namespace.
+    test_handle_gossip_message_!(
+        namespace_updated_unknown_namespace,
+        existing = None,
+        message = Event::NamespaceUpdated(NamespaceUpdated {
+            write_rules: Some(test_write_rules()),
+            ..namespace_updated(NAMESPACE_NAME, &DEFAULT_NAMESPACE)
+        }),
+        want = Err(CacheMissErr { .. })
+    );
 }
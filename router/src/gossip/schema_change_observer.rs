@@ -8,7 +8,7 @@ use generated_types::influxdata::iox::gossip::v1::{schema_message::Event, Column
 
 use crate::namespace_cache::{ChangeStats, NamespaceCache};
 
-use super::{namespace_created, table_created, traits::SchemaBroadcast};
+use super::{namespace_created, namespace_updated, table_created, traits::SchemaBroadcast};
 
 /// A [`NamespaceCache`] decorator implementing cluster-wide, best-effort
 /// propagation of local schema changes via the gossip subsystem.
@@ -22,7 +22,11 @@ use super::{namespace_created, table_created, traits::SchemaBroadcast};
 /// [`NamespaceCache::put_schema()`] method are broadcast to gossip peers.
 ///
 /// Instead of gossiping the entire schema, the new schema elements described by
-/// the [`ChangeStats`] are transmitted on a best-effort basis.
+/// the [`ChangeStats`] are transmitted on a best-effort basis. Changes to the
+/// mutable namespace properties (the write rules, schema mode and partition
+/// template) are gossiped as the complete, replacement set of properties, and
+/// a changed table partition template is gossiped as a replacement table
+/// creation.
 ///
 /// Gossip [`Event`] are populated within the call to
 /// [`NamespaceCache::put_schema()`] but packed & serialised into gossip frames
@@ -105,6 +109,18 @@ where
         if !c.new_columns_per_table.is_empty() {
             self.handle_table_update(name, schema, &c.new_columns_per_table);
         }
+
+        // Dispatch "new table" messages carrying the newer partition template
+        // of existing tables, replacing the template held by peers
+        if !c.updated_partition_templates.is_empty() {
+            self.handle_new_tables(name, &c.updated_partition_templates);
+        }
+
+        // Dispatch the replacement properties of an existing namespace
+        if c.properties_updated {
+            let msg = namespace_updated(name, schema);
+            self.tx.broadcast(Event::NamespaceUpdated(msg));
+        }
     }
 
     /// Gossip that a new namespace named `namespace_name` exists and is
@@ -189,7 +205,11 @@ mod tests {
     use data_types::{
         partition_template::test_table_partition_override, ColumnId, TableId, TableSchema,
     };
-    use generated_types::influxdata::iox::gossip::v1::{column::ColumnType, TableCreated};
+    use generated_types::influxdata::iox::{
+        gossip::v1::{column::ColumnType, TableCreated},
+        namespace::v1::SchemaMode,
+        write_rules::v1 as write_rules_proto,
+    };
 
     macro_rules! test_observe {
         (
@@ -233,6 +253,8 @@ mod tests {
             new_columns_per_table: Default::default(),
             num_new_columns: Default::default(),
             did_update: false,
+            properties_updated: false,
+            updated_partition_templates: Default::default(),
         },
         schema = new_empty_namespace_schema(4242),
         want_count = 1,
@@ -249,6 +271,8 @@ mod tests {
             new_tables: new_map(&[
                 (TABLE_NAME, TableSchema {
                     id: TableId::new(TABLE_ID),
+                    retention_period_ns: None,
+                    partition_template_version: 0,
                     columns: ColumnsByName::new([
                         data_types::Column {
                             name: "platanos".to_string(),
@@ -265,11 +289,13 @@ mod tests {
             new_columns_per_table: Default::default(),
             num_new_columns: Default::default(),
             did_update: false,
+            properties_updated: false,
+            updated_partition_templates: Default::default(),
         },
         schema = DEFAULT_NAMESPACE,
         want_count = 1,
         want = [Event::NamespaceCreated(created),
-        Event::TableCreated(TableCreated { table, partition_template: table_template })] => {
+        Event::TableCreated(TableCreated { table, partition_template: table_template, .. })] => {
             // Validate the namespace create message
             assert_eq!(created, &namespace_created(NAMESPACE_NAME, &DEFAULT_NAMESPACE));
 
@@ -298,6 +324,8 @@ mod tests {
             new_tables: new_map(&[
                 (TABLE_NAME, TableSchema {
                     id: TableId::new(TABLE_ID),
+                    retention_period_ns: Some(4242),
+                    partition_template_version: 0,
                     columns: ColumnsByName::new([
                         data_types::Column {
                             name: "platanos".to_string(),
@@ -314,15 +342,18 @@ mod tests {
             new_columns_per_table: Default::default(),
             num_new_columns: Default::default(),
             did_update: true,
+            properties_updated: false,
+            updated_partition_templates: Default::default(),
         },
         schema = DEFAULT_NAMESPACE,
         want_count = 1,
-        want = [Event::TableCreated(TableCreated { table, partition_template: table_template })] => {
+        want = [Event::TableCreated(TableCreated { table, partition_template: table_template, retention_period_ns, .. })] => {
             let meta = table.as_ref().expect("must have metadata");
 
             assert_eq!(meta.table_name, TABLE_NAME);
             assert_eq!(meta.namespace_name, NAMESPACE_NAME);
             assert_eq!(meta.table_id, TABLE_ID);
+            assert_eq!(*retention_period_ns, Some(4242));
             assert_matches!(meta.columns.as_slice(), [c] => {
                 assert_eq!(c.name, "platanos");
                 assert_eq!(c.column_id, 2442);
@@ -344,6 +375,8 @@ mod tests {
             new_tables: new_map(&[
                 (TABLE_NAME, TableSchema {
                     id: TableId::new(TABLE_ID),
+                    retention_period_ns: None,
+                    partition_template_version: 0,
                     columns: ColumnsByName::new([
                         data_types::Column {
                             name: "platanos".to_string(),
@@ -369,6 +402,8 @@ mod tests {
             ]),
             num_new_columns: Default::default(),
             did_update: true,
+            properties_updated: false,
+            updated_partition_templates: Default::default(),
         },
         schema = {
             // insert the existing table & column that was added in this diff
@@ -376,6 +411,8 @@ mod tests {
             ns.tables.insert("more-bananas".to_string(), TableSchema {
                 id: TableId::new(4321),
                 partition_template:  test_table_partition_override(vec![]),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns: ColumnsByName::new([
                     data_types::Column {
                         name: "platanos".to_string(),
@@ -421,6 +458,80 @@ mod tests {
         }
     );
 
+    // The write rules and schema mode of an existing namespace were replaced.
+    test_observe!(
+        existing_namespace_properties,
+        diff = ChangeStats {
+            new_tables: Default::default(),
+            new_columns_per_table: Default::default(),
+            num_new_columns: Default::default(),
+            did_update: true,
+            properties_updated: true,
+            updated_partition_templates: Default::default(),
+        },
+        schema = {
+            let mut ns = DEFAULT_NAMESPACE.clone();
+            ns.write_rules = data_types::WriteRules::try_from(write_rules_proto::WriteRules {
+                rules: vec![write_rules_proto::WriteRule {
+                    rule: Some(write_rules_proto::write_rule::Rule::AddTag(
+                        write_rules_proto::AddTag {
+                            key: "region".to_string(),
+                            value: "eu".to_string(),
+                        },
+                    )),
+                }],
+            })
+            .unwrap();
+            ns.schema_mode = data_types::NamespaceSchemaMode::Explicit;
+            ns
+        },
+        want_count = 1,
+        want = [Event::NamespaceUpdated(updated)] => {
+            assert_eq!(updated.namespace_name, NAMESPACE_NAME);
+            assert_eq!(updated.namespace_id, DEFAULT_NAMESPACE.id.get());
+            assert_eq!(updated.schema_mode(), SchemaMode::Explicit);
+            assert_matches!(updated.write_rules.as_ref(), Some(rules) => {
+                assert_eq!(rules.rules.len(), 1);
+            });
+        }
+    );
+
+    // The partition template of an existing table was replaced.
+    test_observe!(
+        existing_table_partition_template,
+        diff = ChangeStats {
+            new_tables: Default::default(),
+            new_columns_per_table: Default::default(),
+            num_new_columns: Default::default(),
+            did_update: true,
+            properties_updated: false,
+            updated_partition_templates: new_map(&[
+                (TABLE_NAME, TableSchema {
+                    id: TableId::new(TABLE_ID),
+                    retention_period_ns: None,
+                    partition_template_version: 3,
+                    columns: ColumnsByName::new([]),
+                    partition_template: test_table_partition_override(vec![
+                        data_types::partition_template::TemplatePart::TagValue("bananatastic"),
+                    ]),
+                })
+            ]),
+        },
+        schema = DEFAULT_NAMESPACE,
+        want_count = 1,
+        want = [Event::TableCreated(TableCreated { table, partition_template, partition_template_version, .. })] => {
+            let meta = table.as_ref().expect("must have metadata");
+            assert_eq!(meta.table_name, TABLE_NAME);
+            assert_eq!(meta.table_id, TABLE_ID);
+            assert_eq!(*partition_template_version, 3);
+
+            let want = test_table_partition_override(vec![
+                data_types::partition_template::TemplatePart::TagValue("bananatastic"),
+            ]);
+            assert_eq!(partition_template.as_ref(), want.as_proto());
+        }
+    );
+
     fn new_map<T>(v: &[(&str, T)]) -> BTreeMap<String, T>
     where
         T: Clone,
@@ -146,8 +146,9 @@ pub mod server;
 #[cfg(test)]
 pub(crate) mod test_helpers {
     use data_types::{
-        partition_template::NamespacePartitionTemplateOverride, MaxColumnsPerTable, MaxTables,
-        NamespaceId, NamespaceSchema,
+        partition_template::NamespacePartitionTemplateOverride, FieldCoercionRules,
+        MaxColumnsPerTable, MaxTables, NamespaceId, NamespaceSchema, NamespaceSchemaMode,
+        WriteRules,
     };
     use std::collections::BTreeMap;
 
@@ -168,6 +169,10 @@ pub(crate) mod test_helpers {
             max_columns_per_table: MaxColumnsPerTable::const_default(),
             retention_period_ns: None,
             partition_template: DEFAULT_NAMESPACE_PARTITION_TEMPLATE,
+            schema_mode: NamespaceSchemaMode::Implicit,
+            field_coercion: FieldCoercionRules::NONE,
+            write_rules: WriteRules::const_default(),
+            properties_version: 0,
         }
     }
 }
@@ -83,6 +83,14 @@ pub struct ChangeStats {
     /// Indicates whether the change took place when an entry already
     /// existed.
     pub(crate) did_update: bool,
+
+    /// Indicates whether any of the mutable properties of an existing entry
+    /// (the write rules, schema mode and partition template) were changed.
+    pub(crate) properties_updated: bool,
+
+    /// The pre-existing tables whose partition template was replaced by a
+    /// newer version, keyed by table name.
+    pub(crate) updated_partition_templates: BTreeMap<String, TableSchema>,
 }
 
 /// An optional [`NamespaceCache`] decorator layer.
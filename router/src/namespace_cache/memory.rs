@@ -1,4 +1,4 @@
-use std::{collections::BTreeMap, sync::Arc};
+use std::{cmp::Ordering, collections::BTreeMap, sync::Arc};
 
 use async_trait::async_trait;
 use data_types::{ColumnsByName, NamespaceName, NamespaceSchema};
@@ -62,6 +62,8 @@ impl NamespaceCache for MemoryNamespaceCache {
                     new_columns_per_table: Default::default(),
                     num_new_columns: schema.tables.values().map(|v| v.column_count()).sum(),
                     did_update: false,
+                    properties_updated: false,
+                    updated_partition_templates: Default::default(),
                 };
                 (schema, change_stats)
             }
@@ -82,10 +84,20 @@ fn merge_schema_additive(
 ) -> (NamespaceSchema, ChangeStats) {
     // invariant: Namespace ID should never change for a given name
     assert_eq!(old_ns.id, new_ns.id);
-    // invariant: Namespace partition template override should never change for a given name
-    assert_eq!(old_ns.partition_template, new_ns.partition_template);
+
+    // A schema loaded from the catalog before the most recent properties
+    // update (i.e. on the write path, racing with a gossiped or RPC update)
+    // must not revert the newer properties already in the cache.
+    if new_ns.properties_version < old_ns.properties_version {
+        new_ns.schema_mode = old_ns.schema_mode;
+        new_ns.field_coercion = old_ns.field_coercion;
+        new_ns.write_rules = old_ns.write_rules.clone();
+        new_ns.partition_template = old_ns.partition_template.clone();
+        new_ns.properties_version = old_ns.properties_version;
+    }
 
     let mut new_columns_per_table: BTreeMap<String, ColumnsByName> = Default::default();
+    let mut updated_partition_templates = Vec::new();
     let mut num_new_columns = 0;
 
     // Table schema missing from the new schema are added from the old. If the
@@ -106,6 +118,20 @@ fn merge_schema_additive(
     for (old_table_name, old_table) in &old_ns.tables {
         match new_ns.tables.get_mut(old_table_name) {
             Some(new_table) => {
+                // Retain the newest version of the table partition template,
+                // noting if the new table schema carries a newer version.
+                match new_table
+                    .partition_template_version
+                    .cmp(&old_table.partition_template_version)
+                {
+                    Ordering::Less => {
+                        new_table.partition_template = old_table.partition_template.clone();
+                        new_table.partition_template_version = old_table.partition_template_version;
+                    }
+                    Ordering::Greater => updated_partition_templates.push(old_table_name),
+                    Ordering::Equal => {}
+                }
+
                 // Insert old columns missing from the new table schema
                 for (old_column_name, old_column) in old_table.columns.iter() {
                     if !new_table.contains_column_name(old_column_name) {
@@ -154,6 +180,12 @@ fn merge_schema_additive(
         })
         .collect();
 
+    // Capture the merged schema of the tables with a newer partition template.
+    let updated_partition_templates = updated_partition_templates
+        .into_iter()
+        .map(|name| (name.clone(), new_ns.tables[name].clone()))
+        .collect();
+
     // To compute the change stats for the merge it is still necessary to iterate
     // over the tables present in the new schema. The new schema may have
     // introduced additional tables that won't be visited by the merge logic's logic.
@@ -162,6 +194,11 @@ fn merge_schema_additive(
         new_columns_per_table,
         num_new_columns,
         did_update: true,
+        properties_updated: old_ns.write_rules != new_ns.write_rules
+            || old_ns.schema_mode != new_ns.schema_mode
+            || old_ns.field_coercion != new_ns.field_coercion
+            || old_ns.partition_template != new_ns.partition_template,
+        updated_partition_templates,
     };
     (new_ns, change_stats)
 }
@@ -172,8 +209,15 @@ mod tests {
 
     use assert_matches::assert_matches;
     use data_types::{
-        Column, ColumnId, ColumnSchema, ColumnType, ColumnsByName, MaxColumnsPerTable, MaxTables,
-        NamespaceId, TableId, TableSchema,
+        partition_template::{
+            test_table_partition_override, NamespacePartitionTemplateOverride, TemplatePart,
+        },
+        Column, ColumnId, ColumnSchema, ColumnType, ColumnsByName, FieldCoercionRules,
+        MaxColumnsPerTable, MaxTables, NamespaceId, NamespaceSchemaMode, TableId, TableSchema,
+        WriteRules,
+    };
+    use generated_types::influxdata::iox::{
+        partition_template::v1 as partition_template_proto, write_rules::v1 as write_rules_proto,
     };
     use proptest::{prelude::*, prop_compose, proptest};
 
@@ -190,6 +234,10 @@ mod tests {
             max_columns_per_table: MaxColumnsPerTable::try_from(50).unwrap(),
             retention_period_ns: Some(876),
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         }
     }
 
@@ -202,6 +250,10 @@ mod tests {
             max_columns_per_table: MaxColumnsPerTable::try_from(10).unwrap(),
             retention_period_ns: Some(876),
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         }
     }
 
@@ -244,6 +296,8 @@ mod tests {
         TableSchema {
             id,
             partition_template: Default::default(),
+            retention_period_ns: None,
+            partition_template_version: 0,
             columns: ColumnsByName::new([]),
         }
     }
@@ -312,7 +366,7 @@ mod tests {
                 assert_eq!(*new_schema, schema_update_1);
                 assert_eq!(
                     new_stats,
-                    ChangeStats { new_tables: schema_update_1.tables.clone(), new_columns_per_table: Default::default(), num_new_columns: schema_update_1.tables.values().map(|v| v.column_count()).sum(), did_update: false }
+                    ChangeStats { new_tables: schema_update_1.tables.clone(), new_columns_per_table: Default::default(), num_new_columns: schema_update_1.tables.values().map(|v| v.column_count()).sum(), did_update: false, properties_updated: false, updated_partition_templates: Default::default() }
                 );
             }
         );
@@ -326,7 +380,7 @@ mod tests {
                 )].into_iter().collect::<BTreeMap<_,_>>().into(),
             )].into_iter().collect::<BTreeMap<_,_>>();
 
-            assert_eq!(new_stats, ChangeStats{ new_tables: Default::default(), new_columns_per_table: want_new_columns.clone(), num_new_columns: want_new_columns.values().map(|v| v.column_count()).sum(), did_update: true});
+            assert_eq!(new_stats, ChangeStats{ new_tables: Default::default(), new_columns_per_table: want_new_columns.clone(), num_new_columns: want_new_columns.values().map(|v| v.column_count()).sum(), did_update: true, properties_updated: false, updated_partition_templates: Default::default() });
         });
 
         let got_namespace_schema = cache
@@ -419,6 +473,8 @@ mod tests {
                         new_columns_per_table: Default::default(),
                         num_new_columns: schema_update_1.tables.values().map(|v| v.column_count()).sum(),
                         did_update: false,
+                        properties_updated: false,
+                        updated_partition_templates: Default::default(),
                          }
                 );
             }
@@ -431,6 +487,8 @@ mod tests {
                 new_columns_per_table: Default::default(),
                 num_new_columns: want_new_tables.values().map(|v| v.column_count()).sum(),
                 did_update: true,
+                properties_updated: false,
+                updated_partition_templates: Default::default(),
             });
         });
 
@@ -445,6 +503,147 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_put_properties_updated() {
+        let ns = NamespaceName::new("test").expect("namespace name is valid");
+        let cache = MemoryNamespaceCache::default();
+
+        let (_, stats) = cache.put_schema(ns.clone(), schema1());
+        assert!(!stats.properties_updated);
+
+        // Re-inserting the same rules is not a change.
+        let (_, stats) = cache.put_schema(ns.clone(), schema1());
+        assert!(stats.did_update);
+        assert!(!stats.properties_updated);
+
+        let mut with_rules = schema1();
+        with_rules.write_rules = WriteRules::try_from(write_rules_proto::WriteRules {
+            rules: vec![write_rules_proto::WriteRule {
+                rule: Some(write_rules_proto::write_rule::Rule::DropMeasurement(
+                    write_rules_proto::DropMeasurement {
+                        measurement_regex: "bananas".to_string(),
+                    },
+                )),
+            }],
+        })
+        .unwrap();
+
+        let (got, stats) = cache.put_schema(ns.clone(), with_rules.clone());
+        assert!(stats.properties_updated);
+        assert_eq!(got.write_rules, with_rules.write_rules);
+
+        // And removing them is a change too.
+        let (got, stats) = cache.put_schema(ns.clone(), schema1());
+        assert!(stats.properties_updated);
+        assert!(got.write_rules.is_empty());
+
+        // As is changing the schema mode.
+        let mut explicit = schema1();
+        explicit.schema_mode = NamespaceSchemaMode::Explicit;
+        let (got, stats) = cache.put_schema(ns.clone(), explicit);
+        assert!(stats.properties_updated);
+        assert_eq!(got.schema_mode, NamespaceSchemaMode::Explicit);
+
+        // And the field coercion rules.
+        let mut coerced = schema1();
+        coerced.field_coercion = FieldCoercionRules {
+            int_to_float: true,
+            ..Default::default()
+        };
+        let (got, stats) = cache.put_schema(ns, coerced.clone());
+        assert!(stats.properties_updated);
+        assert_eq!(got.field_coercion, coerced.field_coercion);
+    }
+
+    #[tokio::test]
+    async fn test_put_stale_properties_ignored() {
+        let ns = NamespaceName::new("test").expect("namespace name is valid");
+        let cache = MemoryNamespaceCache::default();
+
+        let mut updated = schema1();
+        updated.schema_mode = NamespaceSchemaMode::Explicit;
+        updated.field_coercion = FieldCoercionRules {
+            bool_to_string: true,
+            ..Default::default()
+        };
+        updated.properties_version = 1;
+        cache.put_schema(ns.clone(), updated.clone());
+
+        // A schema read from the catalog before the update was applied must
+        // not revert it.
+        let (got, stats) = cache.put_schema(ns, schema1());
+        assert!(!stats.properties_updated);
+        assert_eq!(got.schema_mode, NamespaceSchemaMode::Explicit);
+        assert_eq!(got.field_coercion, updated.field_coercion);
+        assert_eq!(got.properties_version, 1);
+    }
+
+    #[tokio::test]
+    async fn test_put_namespace_partition_template_updated() {
+        let ns = NamespaceName::new("test").expect("namespace name is valid");
+        let cache = MemoryNamespaceCache::default();
+
+        cache.put_schema(ns.clone(), schema1());
+
+        let template = NamespacePartitionTemplateOverride::try_from(
+            partition_template_proto::PartitionTemplate {
+                parts: vec![partition_template_proto::TemplatePart {
+                    part: Some(partition_template_proto::template_part::Part::TagValue(
+                        "region".into(),
+                    )),
+                }],
+            },
+        )
+        .unwrap();
+
+        let mut updated = schema1();
+        updated.partition_template = template.clone();
+        updated.properties_version = 1;
+        let (got, stats) = cache.put_schema(ns.clone(), updated);
+        assert!(stats.properties_updated);
+        assert_eq!(got.partition_template, template);
+
+        // A stale schema must not revert the template.
+        let (got, stats) = cache.put_schema(ns, schema1());
+        assert!(!stats.properties_updated);
+        assert_eq!(got.partition_template, template);
+    }
+
+    #[tokio::test]
+    async fn test_put_table_partition_template_version_merge() {
+        let ns = NamespaceName::new("test").expect("namespace name is valid");
+        let cache = MemoryNamespaceCache::default();
+
+        let mut schema = schema1();
+        schema
+            .tables
+            .insert("bananas".to_string(), empty_table_schema(TableId::new(1)));
+        cache.put_schema(ns.clone(), schema.clone());
+
+        let template = test_table_partition_override(vec![TemplatePart::TagValue("region")]);
+
+        // A newer template replaces the cached one and is reported.
+        let mut updated = schema.clone();
+        {
+            let table = updated.tables.get_mut("bananas").unwrap();
+            table.partition_template = template.clone();
+            table.partition_template_version = 1;
+        }
+        let (got, stats) = cache.put_schema(ns.clone(), updated);
+        assert_eq!(got.tables["bananas"].partition_template, template);
+        assert_eq!(got.tables["bananas"].partition_template_version, 1);
+        assert_matches!(stats.updated_partition_templates.get("bananas"), Some(t) => {
+            assert_eq!(t.partition_template, template);
+            assert_eq!(t.partition_template_version, 1);
+        });
+
+        // An older table schema does not revert it.
+        let (got, stats) = cache.put_schema(ns, schema);
+        assert_eq!(got.tables["bananas"].partition_template, template);
+        assert_eq!(got.tables["bananas"].partition_template_version, 1);
+        assert!(stats.updated_partition_templates.is_empty());
+    }
+
     /// A set of table and column names from which arbitrary names are selected
     /// in prop tests, instead of using random values that have a low
     /// probability of overlap.
@@ -472,6 +671,8 @@ mod tests {
             TableSchema {
                 id: TableId::new(id),
                 partition_template: Default::default(),
+                retention_period_ns: None,
+                partition_template_version: 0,
                 columns,
             }
         }
@@ -497,6 +698,10 @@ mod tests {
                     .unwrap(),
                 retention_period_ns,
                 partition_template: Default::default(),
+                schema_mode: Default::default(),
+                field_coercion: Default::default(),
+                write_rules: Default::default(),
+                properties_version: 0,
             }
         }
     }
@@ -155,6 +155,8 @@ mod tests {
                     TableSchema {
                         id: TableId::new(i as _),
                         partition_template: Default::default(),
+                        retention_period_ns: None,
+                        partition_template_version: 0,
                         columns: ColumnsByName::new(columns),
                     },
                 )
@@ -168,6 +170,10 @@ mod tests {
             max_columns_per_table: MaxColumnsPerTable::try_from(100).unwrap(),
             retention_period_ns: None,
             partition_template: Default::default(),
+            schema_mode: Default::default(),
+            field_coercion: Default::default(),
+            write_rules: Default::default(),
+            properties_version: 0,
         }
     }
 
@@ -233,6 +233,13 @@ mod tests {
                 retention_period_ns: TEST_RETENTION_PERIOD_NS,
                 deleted_at: None,
                 partition_template: Default::default(),
+                schema_mode: Default::default(),
+                field_coercion: Default::default(),
+                write_rules: Default::default(),
+                rollup_policies: Default::default(),
+                cold_tier_age_ns: None,
+                compaction_weight: 1,
+                properties_version: 0,
             }
         );
     }
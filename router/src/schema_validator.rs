@@ -3,10 +3,16 @@
 
 use std::{collections::BTreeSet, sync::Arc};
 
-use data_types::{MaxColumnsPerTable, MaxTables, NamespaceId, NamespaceName, NamespaceSchema};
+use data_types::{
+    ColumnType, MaxColumnsPerTable, MaxTables, NamespaceId, NamespaceName, NamespaceSchema,
+    NamespaceSchemaMode,
+};
+use hashbrown::HashMap;
 use iox_catalog::interface::Catalog;
-use metric::U64Counter;
+use metric::{Metric, U64Counter};
+use mutable_batch::MutableBatch;
 use observability_deps::tracing::*;
+use schema::InfluxColumnType;
 use thiserror::Error;
 
 /// Errors emitted during schema validation.
@@ -20,6 +26,16 @@ pub enum SchemaError {
     #[error("schema conflict: {0}")]
     Conflict(iox_catalog::TableScopedError),
 
+    /// The request references a table or column that does not exist in a
+    /// namespace that does not permit implicit schema creation.
+    #[error("undeclared schema: {0}")]
+    Undeclared(UndeclaredSchema),
+
+    /// The request contains field values that could not be converted to the
+    /// type of the existing column.
+    #[error("field coercion failed: {0}")]
+    Coercion(mutable_batch::Error),
+
     /// A catalog error during schema validation.
     ///
     /// NOTE: this may be due to transient I/O errors while interrogating the
@@ -89,6 +105,36 @@ pub enum SchemaError {
 /// columns in the request, racing multiple requests for the same table can
 /// produce incorrect schemas ([#3573]).
 ///
+/// # Explicit Schema Mode
+///
+/// Namespaces configured with [`NamespaceSchemaMode::Explicit`] never have
+/// tables or columns created by writes - any write referencing a table or
+/// column that does not exist is rejected with [`SchemaError::Undeclared`].
+/// The mandatory timestamp column is the only exception, as its name and type
+/// are fixed.
+///
+/// Because tables and columns are created out-of-band, a cached schema that
+/// does not contain the referenced table/column is refreshed from the catalog
+/// before the write is rejected.
+///
+/// The schema mode itself is cached. A mode change is applied to the cache of
+/// the router that served the update, and gossiped to all other routers with
+/// schema gossip enabled, which apply it without a restart.
+///
+/// # Field Coercion
+///
+/// By default a field value with a type that differs from the existing column
+/// is a schema conflict. Namespaces may enable [`FieldCoercionRules`] that
+/// instead convert the written values to the type of the existing column (for
+/// example, writing `val=42i` to a float column stores `42.0`).
+///
+/// Fields are coerced against the cached schema before the catalog is
+/// consulted, and again if the catalog reports a type mismatch for a column
+/// not yet in the cache. A value that cannot be represented in the column type
+/// (such as a u64 greater than [`i64::MAX`]) fails the write with
+/// [`SchemaError::Coercion`].
+///
+/// [`FieldCoercionRules`]: data_types::FieldCoercionRules
 /// [#3573]: https://github.com/influxdata/influxdb_iox/issues/3573
 #[derive(Debug)]
 pub struct SchemaValidator<C> {
@@ -98,6 +144,8 @@ pub struct SchemaValidator<C> {
     pub(crate) service_limit_hit_tables: U64Counter,
     pub(crate) service_limit_hit_columns: U64Counter,
     pub(crate) schema_conflict: U64Counter,
+    pub(crate) schema_undeclared: U64Counter,
+    pub(crate) field_values_coerced: Metric<U64Counter>,
 }
 
 impl<C> SchemaValidator<C> {
@@ -118,13 +166,120 @@ impl<C> SchemaValidator<C> {
             )
             .recorder(&[]);
 
+        let schema_undeclared = metrics
+            .register_metric::<U64Counter>(
+                "schema_validation_schema_undeclared",
+                "number of requests rejected for referencing an undeclared table/column in an \
+                explicit schema mode namespace",
+            )
+            .recorder(&[]);
+
+        let field_values_coerced = metrics.register_metric::<U64Counter>(
+            "schema_validation_field_values_coerced",
+            "number of non-null field values converted to the type of an existing column",
+        );
+
         Self {
             catalog,
             cache: ns_cache,
             service_limit_hit_tables,
             service_limit_hit_columns,
             schema_conflict,
+            schema_undeclared,
+            field_values_coerced,
+        }
+    }
+
+    /// Convert any field in `batches` that has a different type to the
+    /// existing column in `namespace_schema`, if permitted by the namespace's
+    /// [`FieldCoercionRules`](data_types::FieldCoercionRules).
+    ///
+    /// # Errors
+    ///
+    /// If the values of a field cannot be converted, [`SchemaError::Coercion`]
+    /// is returned.
+    pub(crate) fn coerce_fields(
+        &self,
+        namespace: &NamespaceName<'static>,
+        namespace_schema: &NamespaceSchema,
+        batches: &mut HashMap<String, MutableBatch>,
+    ) -> Result<(), SchemaError> {
+        let rules = namespace_schema.field_coercion;
+        if !rules.is_enabled() {
+            return Ok(());
         }
+
+        for (table_name, batch) in batches.iter_mut() {
+            let table = match namespace_schema.tables.get(table_name) {
+                Some(v) => v,
+                None => continue,
+            };
+
+            let to_coerce = batch
+                .columns()
+                .filter_map(|(column_name, column)| {
+                    let existing = table.columns.get(column_name)?.column_type;
+                    let new = ColumnType::from(column.influx_type());
+                    (new != existing && rules.permits(new, existing))
+                        .then(|| (column_name.clone(), new, existing))
+                })
+                .collect::<Vec<_>>();
+
+            for (column_name, from, to) in to_coerce {
+                self.coerce_column(namespace, table_name, batch, &column_name, from, to)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Convert the values of the field `column_name` in `batch` from `from` to
+    /// `to`, recording the number of values converted.
+    ///
+    /// The caller must ensure the conversion is permitted by the namespace's
+    /// [`FieldCoercionRules`](data_types::FieldCoercionRules).
+    pub(crate) fn coerce_column(
+        &self,
+        namespace: &NamespaceName<'static>,
+        table_name: &str,
+        batch: &mut MutableBatch,
+        column_name: &str,
+        from: ColumnType,
+        to: ColumnType,
+    ) -> Result<(), SchemaError> {
+        let to_field = match InfluxColumnType::from(to) {
+            InfluxColumnType::Field(v) => v,
+            // Coercion rules only ever target field columns.
+            v => unreachable!("coercion to non-field column type {v:?}"),
+        };
+
+        let n = batch.coerce_column(column_name, to_field).map_err(|e| {
+            warn!(
+                %namespace,
+                %table_name,
+                %column_name,
+                from=%from,
+                to=%to,
+                error=%e,
+                "field coercion failed"
+            );
+            SchemaError::Coercion(e)
+        })?;
+
+        debug!(
+            %namespace,
+            %table_name,
+            %column_name,
+            from=%from,
+            to=%to,
+            values=n,
+            "coerced field values"
+        );
+        self.field_values_coerced
+            .recorder(&[("from", from.as_str()), ("to", to.as_str())])
+            .inc(n as _);
+
+        Ok(())
     }
 
     /// Validate the schema changes specified are within the system's service limits.
@@ -192,6 +347,71 @@ impl<C> SchemaValidator<C> {
     }
 }
 
+/// A table or column referenced by a write that does not exist in a namespace
+/// configured with [`NamespaceSchemaMode::Explicit`].
+#[derive(Debug, Error)]
+pub enum UndeclaredSchema {
+    /// The write references a table that does not exist.
+    #[error(
+        "table `{table_name}` does not exist and namespace is in explicit \
+        schema mode; the table must be created before writing to it"
+    )]
+    Table {
+        /// The undeclared table.
+        table_name: String,
+    },
+
+    /// The write references a column that does not exist.
+    #[error(
+        "column `{column_name}` does not exist in table `{table_name}` and \
+        namespace is in explicit schema mode; the column must be created \
+        before writing to it"
+    )]
+    Column {
+        /// The table the column was written to.
+        table_name: String,
+        /// The undeclared column.
+        column_name: String,
+    },
+}
+
+/// Return the first table or column in `column_names_by_table` that does not
+/// exist in `schema`, if `schema` is in [`NamespaceSchemaMode::Explicit`].
+///
+/// The timestamp column is always permitted.
+pub(crate) fn find_undeclared<'a>(
+    column_names_by_table: impl Iterator<Item = (&'a str, BTreeSet<&'a str>)>,
+    schema: &NamespaceSchema,
+) -> Option<UndeclaredSchema> {
+    if schema.schema_mode == NamespaceSchemaMode::Implicit {
+        return None;
+    }
+
+    for (table_name, column_names) in column_names_by_table {
+        let table = match schema.tables.get(table_name) {
+            Some(v) => v,
+            None => {
+                return Some(UndeclaredSchema::Table {
+                    table_name: table_name.to_string(),
+                })
+            }
+        };
+
+        if let Some(column_name) = column_names
+            .into_iter()
+            .filter(|&c| c != schema::TIME_COLUMN_NAME)
+            .find(|&c| !table.contains_column_name(c))
+        {
+            return Some(UndeclaredSchema::Column {
+                table_name: table_name.to_string(),
+                column_name: column_name.to_string(),
+            });
+        }
+    }
+
+    None
+}
+
 /// An error returned by schema limit evaluation against a cached
 /// [`NamespaceSchema`].
 #[derive(Debug, Error)]
@@ -1,16 +1,19 @@
 //! gRPC service implementations for `router`.
 
+use async_trait::async_trait;
+use data_types::{Namespace as CatalogNamespace, NamespaceName, NamespaceSchema, Table};
 use generated_types::influxdata::iox::{
     catalog::v1::*, gossip::v1::anti_entropy_service_server, namespace::v1::*, object_store::v1::*,
     table::v1::*,
 };
 use iox_catalog::interface::Catalog;
 use object_store::DynObjectStore;
+use observability_deps::tracing::*;
 use service_grpc_catalog::CatalogService;
-use service_grpc_namespace::NamespaceService;
+use service_grpc_namespace::{NamespaceService, NamespaceUpdateObserver};
 use service_grpc_object_store::ObjectStoreService;
 use service_grpc_schema::SchemaService;
-use service_grpc_table::TableService;
+use service_grpc_table::{TableService, TableUpdateObserver};
 use std::sync::Arc;
 
 use crate::{
@@ -63,16 +66,39 @@ impl<T> RpcWriteGrpcDelegate<T> {
 
     /// Acquire a [`NamespaceService`] gRPC service implementation.
     ///
+    /// Namespace write rule, schema mode and partition template changes made
+    /// through the service are applied to the local namespace cache (and
+    /// therefore gossiped to peers, if enabled) without waiting for the cache
+    /// entry to be reloaded.
+    ///
     /// [`NamespaceService`]: generated_types::influxdata::iox::namespace::v1::namespace_service_server::NamespaceService.
-    pub fn namespace_service(&self) -> impl namespace_service_server::NamespaceService {
-        NamespaceService::new(Arc::clone(&self.catalog))
+    pub fn namespace_service(&self) -> impl namespace_service_server::NamespaceService
+    where
+        T: NamespaceCache<ReadError = CacheMissErr> + Clone + 'static,
+    {
+        NamespaceService::new(Arc::clone(&self.catalog)).with_update_observer(Arc::new(
+            CachedNamespaceProperties {
+                cache: self.anti_entropy.cache().clone(),
+            },
+        ))
     }
 
     /// Acquire a [`TableService`] gRPC service implementation.
     ///
+    /// Table partition template changes made through the service are applied
+    /// to the local namespace cache (and therefore gossiped to peers, if
+    /// enabled) without waiting for the cache entry to be reloaded.
+    ///
     /// [`TableService`]: generated_types::influxdata::iox::table::v1::table_service_server::TableService
-    pub fn table_service(&self) -> impl table_service_server::TableService {
-        TableService::new(Arc::clone(&self.catalog))
+    pub fn table_service(&self) -> impl table_service_server::TableService
+    where
+        T: NamespaceCache<ReadError = CacheMissErr> + Clone + 'static,
+    {
+        TableService::new(Arc::clone(&self.catalog)).with_update_observer(Arc::new(
+            CachedTablePartitionTemplate {
+                cache: self.anti_entropy.cache().clone(),
+            },
+        ))
     }
 
     /// Acquire a [`AntiEntropyService`] gRPC service implementation.
@@ -86,3 +112,77 @@ impl<T> RpcWriteGrpcDelegate<T> {
         self.anti_entropy.clone()
     }
 }
+
+/// A [`NamespaceUpdateObserver`] that replaces the write rules, schema mode,
+/// field coercion rules and partition template of the cached
+/// [`NamespaceSchema`] (if any) with those updated in the catalog.
+#[derive(Debug)]
+struct CachedNamespaceProperties<T> {
+    cache: T,
+}
+
+#[async_trait]
+impl<T> NamespaceUpdateObserver for CachedNamespaceProperties<T>
+where
+    T: NamespaceCache<ReadError = CacheMissErr>,
+{
+    async fn observe_update(&self, namespace: &CatalogNamespace) {
+        let name = match NamespaceName::try_from(namespace.name.clone()) {
+            Ok(v) => v,
+            Err(e) => {
+                warn!(error=%e, namespace_name=%namespace.name, "invalid namespace name");
+                return;
+            }
+        };
+
+        // A namespace that is not cached will load the new properties from
+        // the catalog when it is next used.
+        let mut schema = match self.cache.get_schema(&name).await {
+            Ok(v) => NamespaceSchema::clone(&v),
+            Err(_) => return,
+        };
+        schema.write_rules = namespace.write_rules.clone();
+        schema.schema_mode = namespace.schema_mode;
+        schema.field_coercion = namespace.field_coercion;
+        schema.partition_template = namespace.partition_template.clone();
+        schema.properties_version = namespace.properties_version;
+
+        debug!(namespace_name=%name, "applying updated namespace properties to namespace cache");
+        self.cache.put_schema(name, schema);
+    }
+}
+
+/// A [`TableUpdateObserver`] that replaces the partition template of the
+/// cached table (if any) with the one updated in the catalog.
+#[derive(Debug)]
+struct CachedTablePartitionTemplate<T> {
+    cache: T,
+}
+
+#[async_trait]
+impl<T> TableUpdateObserver for CachedTablePartitionTemplate<T>
+where
+    T: NamespaceCache<ReadError = CacheMissErr>,
+{
+    async fn observe_update(&self, namespace: &NamespaceName<'static>, table: &Table) {
+        // A namespace or table that is not cached will load the new template
+        // from the catalog when it is next used.
+        let mut schema = match self.cache.get_schema(namespace).await {
+            Ok(v) => NamespaceSchema::clone(&v),
+            Err(_) => return,
+        };
+        let cached = match schema.tables.get_mut(&table.name) {
+            Some(v) => v,
+            None => return,
+        };
+        cached.partition_template = table.partition_template.clone();
+        cached.partition_template_version = table.partition_template_version;
+
+        debug!(
+            namespace_name=%namespace,
+            table_name=%table.name,
+            "applying updated table partition template to namespace cache"
+        );
+        self.cache.put_schema(namespace.clone(), schema);
+    }
+}
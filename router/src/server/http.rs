@@ -23,8 +23,8 @@ use self::write::{
 };
 use crate::{
     dml_handlers::{
-        client::RpcWriteClientError, DmlError, DmlHandler, PartitionError, RetentionError,
-        RpcWriteError,
+        client::RpcWriteClientError, spool::SpoolError, DmlError, DmlHandler, PartitionError,
+        RetentionError, RpcWriteError, WriteRulesError,
     },
     namespace_resolver::NamespaceResolver,
     schema_validator::SchemaError,
@@ -165,6 +165,8 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::BAD_REQUEST
             }
             DmlError::Schema(SchemaError::Conflict(_)) => StatusCode::BAD_REQUEST,
+            DmlError::Schema(SchemaError::Undeclared(_)) => StatusCode::BAD_REQUEST,
+            DmlError::Schema(SchemaError::Coercion(_)) => StatusCode::BAD_REQUEST,
             DmlError::Schema(SchemaError::UnexpectedCatalogError(_)) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -175,6 +177,9 @@ impl From<&DmlError> for StatusCode {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
             DmlError::Retention(RetentionError::OutsideRetention { .. }) => StatusCode::FORBIDDEN,
+            DmlError::WriteRules(WriteRulesError::Batch { .. }) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
             DmlError::RpcWrite(RpcWriteError::Client(RpcWriteClientError::Upstream(_))) => {
                 StatusCode::INTERNAL_SERVER_ERROR
             }
@@ -187,12 +192,20 @@ impl From<&DmlError> for StatusCode {
             DmlError::RpcWrite(RpcWriteError::Client(
                 RpcWriteClientError::UpstreamNotConnected(_),
             )) => StatusCode::SERVICE_UNAVAILABLE,
+            DmlError::RpcWrite(RpcWriteError::Client(RpcWriteClientError::SeriesLimit(_)))
+            | DmlError::RpcWrite(RpcWriteError::SeriesLimit(_)) => StatusCode::UNPROCESSABLE_ENTITY,
             DmlError::RpcWrite(RpcWriteError::Timeout(_)) => StatusCode::GATEWAY_TIMEOUT,
             DmlError::RpcWrite(
                 RpcWriteError::NoHealthyUpstreams
                 | RpcWriteError::NotEnoughReplicas
                 | RpcWriteError::PartialWrite { .. },
             ) => StatusCode::SERVICE_UNAVAILABLE,
+            DmlError::RpcWrite(RpcWriteError::Spool(SpoolError::Full { .. })) => {
+                StatusCode::SERVICE_UNAVAILABLE
+            }
+            DmlError::RpcWrite(RpcWriteError::Spool(SpoolError::Wal(_))) => {
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
         }
     }
 }
@@ -487,7 +500,7 @@ mod tests {
     use crate::{
         dml_handlers::mock::{MockDmlHandler, MockDmlHandlerCall},
         namespace_resolver::{mock::MockNamespaceResolver, NamespaceCreationError},
-        schema_validator::CachedServiceProtectionLimit,
+        schema_validator::{CachedServiceProtectionLimit, UndeclaredSchema},
         server::http::write::{
             mock::{MockUnifyingParseCall, MockWriteRequestUnifier},
             multi_tenant::MultiTenantRequestUnifier,
@@ -1565,6 +1578,36 @@ mod tests {
             "dml handler error: service limit reached: couldn't create table bananas; limit reached on namespace 42",
         ),
 
+        (
+            DmlHandler(DmlError::Schema(SchemaError::Undeclared(UndeclaredSchema::Table {
+                table_name: "bananas".to_string(),
+            }))),
+            "dml handler error: undeclared schema: table `bananas` does not exist and namespace \
+            is in explicit schema mode; the table must be created before writing to it",
+        ),
+
+        (
+            DmlHandler(DmlError::Schema(SchemaError::Undeclared(UndeclaredSchema::Column {
+                table_name: "bananas".to_string(),
+                column_name: "platanos".to_string(),
+            }))),
+            "dml handler error: undeclared schema: column `platanos` does not exist in table \
+            `bananas` and namespace is in explicit schema mode; the column must be created \
+            before writing to it",
+        ),
+
+        (
+            DmlHandler(DmlError::Schema(SchemaError::Coercion(mutable_batch::Error::ColumnError {
+                column: "platanos".to_string(),
+                source: mutable_batch::column::Error::CoercionOutOfRange {
+                    value: "18446744073709551615".to_string(),
+                    to: schema::InfluxColumnType::Field(schema::InfluxFieldType::Integer),
+                },
+            }))),
+            "dml handler error: field coercion failed: Column error on column platanos: Value \
+            18446744073709551615 cannot be represented as iox::column_type::field::integer",
+        ),
+
         // A single-tenant namespace parsing error
         (
             SingleTenantError(SingleTenantExtractError::InvalidNamespace(NamespaceNameError::LengthConstraint{name: "bananas".to_string()})),
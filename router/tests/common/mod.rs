@@ -1,6 +1,6 @@
 use std::{iter, string::String, sync::Arc, time::Duration};
 
-use data_types::TableId;
+use data_types::{partition_template::TablePartitionTemplateOverride, TableId};
 use generated_types::influxdata::iox::ingester::v1::WriteRequest;
 use hashbrown::HashMap;
 use hyper::{Body, Request, Response};
@@ -12,8 +12,9 @@ use mutable_batch::MutableBatch;
 use object_store::memory::InMemory;
 use router::{
     dml_handlers::{
-        client::mock::MockWriteClient, Chain, DmlHandlerChainExt, FanOutAdaptor,
+        client::mock::MockWriteClient, BalancerStrategy, Chain, DmlHandlerChainExt, FanOutAdaptor,
         InstrumentationDecorator, Partitioned, Partitioner, RetentionValidator, RpcWrite,
+        WriteRuleApplicator,
     },
     gossip::anti_entropy::{mst::actor::AntiEntropyActor, sync::rpc_server::AntiEntropyService},
     namespace_cache::{MemoryNamespaceCache, ReadThroughCache, ShardedCache},
@@ -115,12 +116,19 @@ type HttpDelegateStack = HttpDelegate<
     InstrumentationDecorator<
         Chain<
             Chain<
-                Chain<RetentionValidator, SchemaValidator<Arc<ReadThroughCache<CacheImpl>>>>,
+                Chain<
+                    Chain<WriteRuleApplicator, RetentionValidator>,
+                    SchemaValidator<Arc<ReadThroughCache<CacheImpl>>>,
+                >,
                 Partitioner,
             >,
             FanOutAdaptor<
                 RpcWrite<Arc<MockWriteClient>>,
-                Vec<Partitioned<HashMap<TableId, (String, MutableBatch)>>>,
+                Vec<
+                    Partitioned<
+                        HashMap<TableId, (String, TablePartitionTemplateOverride, MutableBatch)>,
+                    >,
+                >,
             >,
         >,
     >,
@@ -144,6 +152,7 @@ impl TestContext {
         let rpc_writer = RpcWrite::new(
             [(Arc::clone(&client), "mock client")],
             1.try_into().unwrap(),
+            BalancerStrategy::RoundRobin,
             &metrics,
             rpc_write_num_probes,
         );
@@ -162,6 +171,8 @@ impl TestContext {
         let schema_validator =
             SchemaValidator::new(Arc::clone(&catalog), Arc::clone(&ns_cache), &metrics);
 
+        let write_rules = WriteRuleApplicator::new(&metrics);
+
         let retention_validator = RetentionValidator::new();
 
         let partitioner = Partitioner::default();
@@ -176,7 +187,8 @@ impl TestContext {
 
         let parallel_write = FanOutAdaptor::new(rpc_writer);
 
-        let handler_stack = retention_validator
+        let handler_stack = write_rules
+            .and_then(retention_validator)
             .and_then(schema_validator)
             .and_then(partitioner)
             .and_then(parallel_write);
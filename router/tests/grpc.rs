@@ -8,6 +8,7 @@ use generated_types::influxdata::{
         namespace::v1::{namespace_service_server::NamespaceService, *},
         partition_template::v1::*,
         table::v1::{table_service_server::TableService, *},
+        write_rules::v1 as write_rules_proto,
     },
     pbdata::v1::DatabaseBatch,
 };
@@ -1252,3 +1253,190 @@ async fn test_namespace_without_partition_template_table_with_partition_template
         assert_eq!(partition_key, "B");
     });
 }
+
+/// Ensure namespace write rules updated through the gRPC NamespaceService are
+/// applied to subsequent writes to a namespace that is already cached.
+#[tokio::test]
+async fn test_namespace_write_rules() {
+    // Initialise a TestContext with namespace autocreation.
+    let ctx = TestContextBuilder::default()
+        .with_autocreate_namespace(None)
+        .build()
+        .await;
+
+    // Populate the namespace cache.
+    ctx.write_lp("bananas", "test", "bananas,tag1=A val=42i 42424242")
+        .await
+        .expect("write should succeed");
+
+    let got = ctx
+        .grpc_delegate()
+        .namespace_service()
+        .update_namespace_write_rules(Request::new(UpdateNamespaceWriteRulesRequest {
+            name: "bananas_test".to_string(),
+            write_rules: Some(write_rules_proto::WriteRules {
+                rules: vec![write_rules_proto::WriteRule {
+                    rule: Some(write_rules_proto::write_rule::Rule::DropMeasurement(
+                        write_rules_proto::DropMeasurement {
+                            measurement_regex: "platanos".to_string(),
+                        },
+                    )),
+                }],
+            }),
+        }))
+        .await
+        .expect("failed to update namespace write rules")
+        .into_inner()
+        .namespace
+        .expect("no namespace in response");
+    assert_matches!(got.write_rules, Some(rules) => {
+        assert_eq!(rules.rules.len(), 1);
+    });
+
+    // The dropped measurement must not reach the ingester, nor be created in
+    // the catalog.
+    ctx.write_lp(
+        "bananas",
+        "test",
+        "bananas,tag1=A val=43i 42424243\nplatanos,tag1=A val=1i 42424243",
+    )
+    .await
+    .expect("write should succeed");
+
+    let writes = ctx.write_calls();
+    assert_eq!(writes.len(), 2);
+    assert_matches!(
+        &writes[1],
+        WriteRequest {
+            payload: Some(DatabaseBatch {
+                table_batches,
+                ..
+            }),
+        } => {
+        let table_id = ctx.table_id("bananas_test", "bananas").await.get();
+        assert_eq!(table_batches.len(), 1);
+        assert_eq!(table_batches[0].table_id, table_id);
+    });
+
+    let mut repos = ctx.catalog().repositories().await;
+    let namespace_id = repos
+        .namespaces()
+        .get_by_name("bananas_test", SoftDeletedRows::ExcludeDeleted)
+        .await
+        .unwrap()
+        .expect("namespace should exist")
+        .id;
+    assert_matches!(
+        repos
+            .tables()
+            .get_by_namespace_and_name(namespace_id, "platanos")
+            .await,
+        Ok(None)
+    );
+}
+
+/// Ensure a table partition template updated through the gRPC TableService is
+/// applied to, and sent with, subsequent writes to a table that is already
+/// cached.
+#[tokio::test]
+async fn test_table_partition_template_update() {
+    // Initialise a TestContext with namespace autocreation.
+    let ctx = TestContextBuilder::default()
+        .with_autocreate_namespace(None)
+        .build()
+        .await;
+
+    // Populate the namespace cache, creating the table with the default
+    // template.
+    ctx.write_lp("bananas", "test", "plantains,tag1=A val=42i 42424242")
+        .await
+        .expect("write should succeed");
+
+    let template = PartitionTemplate {
+        parts: vec![TemplatePart {
+            part: Some(template_part::Part::TagValue("tag1".into())),
+        }],
+    };
+    ctx.grpc_delegate()
+        .table_service()
+        .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+            namespace: "bananas_test".to_string(),
+            table: "plantains".to_string(),
+            partition_template: Some(template.clone()),
+        }))
+        .await
+        .expect("failed to update table partition template");
+
+    ctx.write_lp("bananas", "test", "plantains,tag1=A val=43i 42424243")
+        .await
+        .expect("write should succeed");
+
+    // The second write is partitioned by, and carries, the new template.
+    let writes = ctx.write_calls();
+    assert_eq!(writes.len(), 2);
+    assert_matches!(
+        &writes[1],
+        WriteRequest {
+            payload: Some(DatabaseBatch {
+                table_batches,
+                partition_key,
+                ..
+            }),
+        } => {
+        assert_eq!(partition_key, "A");
+        assert_matches!(table_batches.as_slice(), [batch] => {
+            assert_eq!(batch.partition_template.as_ref(), Some(&template));
+        });
+    });
+}
+
+/// Ensure a namespace partition template updated through the gRPC
+/// NamespaceService is applied to tables created by subsequent writes to a
+/// namespace that is already cached.
+#[tokio::test]
+async fn test_namespace_partition_template_update() {
+    // Initialise a TestContext with namespace autocreation.
+    let ctx = TestContextBuilder::default()
+        .with_autocreate_namespace(None)
+        .build()
+        .await;
+
+    // Populate the namespace cache.
+    ctx.write_lp("bananas", "test", "plantains,tag1=A val=42i 42424242")
+        .await
+        .expect("write should succeed");
+
+    let template = PartitionTemplate {
+        parts: vec![TemplatePart {
+            part: Some(template_part::Part::TagValue("tag1".into())),
+        }],
+    };
+    ctx.grpc_delegate()
+        .namespace_service()
+        .update_namespace_partition_template(Request::new(
+            UpdateNamespacePartitionTemplateRequest {
+                name: "bananas_test".to_string(),
+                partition_template: Some(template),
+            },
+        ))
+        .await
+        .expect("failed to update namespace partition template");
+
+    // A new table inherits the new namespace template.
+    ctx.write_lp("bananas", "test", "platanos,tag1=B val=43i 42424243")
+        .await
+        .expect("write should succeed");
+
+    let writes = ctx.write_calls();
+    assert_eq!(writes.len(), 2);
+    assert_matches!(
+        &writes[1],
+        WriteRequest {
+            payload: Some(DatabaseBatch {
+                partition_key,
+                ..
+            }),
+        } => {
+        assert_eq!(partition_key, "B");
+    });
+}
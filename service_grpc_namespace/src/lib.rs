@@ -19,24 +19,54 @@ use workspace_hack as _;
 use std::sync::Arc;
 
 use data_types::{
-    partition_template::NamespacePartitionTemplateOverride, Namespace as CatalogNamespace,
-    NamespaceName, NamespaceServiceProtectionLimitsOverride, ServiceLimitUpdate,
+    partition_template::NamespacePartitionTemplateOverride,
+    FieldCoercionRules as CatalogFieldCoercionRules, Namespace as CatalogNamespace, NamespaceName,
+    NamespaceSchemaMode, NamespaceServiceProtectionLimitsOverride,
+    RollupPolicies as CatalogRollupPolicies, ServiceLimitUpdate, WriteRules as CatalogWriteRules,
 };
 use generated_types::influxdata::iox::namespace::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, info, warn};
 use tonic::{Request, Response, Status};
 
+/// An observer of namespace property changes applied through the
+/// [`NamespaceService`].
+///
+/// Allows the process hosting the service to propagate the new write rules,
+/// schema mode, field coercion rules and partition template (for example,
+/// into a local schema cache) without waiting for them to be loaded from the
+/// catalog.
+#[tonic::async_trait]
+pub trait NamespaceUpdateObserver: std::fmt::Debug + Send + Sync {
+    /// Called after the write rules, schema mode, field coercion rules or
+    /// partition template of `namespace` have been successfully updated in the
+    /// catalog.
+    async fn observe_update(&self, namespace: &CatalogNamespace);
+}
+
 /// Implementation of the gRPC namespace service
 #[derive(Debug)]
 pub struct NamespaceService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// An optional observer notified of namespace property changes.
+    update_observer: Option<Arc<dyn NamespaceUpdateObserver>>,
 }
 
 impl NamespaceService {
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            update_observer: None,
+        }
+    }
+
+    /// Notify `observer` of every successful namespace write rule, schema mode,
+    /// field coercion rule or partition template update.
+    pub fn with_update_observer(mut self, observer: Arc<dyn NamespaceUpdateObserver>) -> Self {
+        self.update_observer = Some(observer);
+        self
     }
 }
 
@@ -249,6 +279,325 @@ impl namespace_service_server::NamespaceService for NamespaceService {
             },
         ))
     }
+
+    async fn update_namespace_schema_mode(
+        &self,
+        request: Request<UpdateNamespaceSchemaModeRequest>,
+    ) -> Result<Response<UpdateNamespaceSchemaModeResponse>, Status> {
+        let request = request.into_inner();
+        let schema_mode = NamespaceSchemaMode::from(request.schema_mode());
+        let namespace_name = request.name;
+
+        debug!(%namespace_name, %schema_mode, "updating namespace schema mode");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_schema_mode(&namespace_name, schema_mode)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    %schema_mode,
+                    "failed to update namespace schema mode",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            schema_mode = %namespace.schema_mode,
+            "updated namespace schema mode",
+        );
+
+        if let Some(observer) = &self.update_observer {
+            observer.observe_update(&namespace).await;
+        }
+
+        Ok(Response::new(UpdateNamespaceSchemaModeResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_field_coercion(
+        &self,
+        request: Request<UpdateNamespaceFieldCoercionRequest>,
+    ) -> Result<Response<UpdateNamespaceFieldCoercionResponse>, Status> {
+        let UpdateNamespaceFieldCoercionRequest {
+            name: namespace_name,
+            field_coercion,
+        } = request.into_inner();
+
+        let field_coercion = CatalogFieldCoercionRules::from(field_coercion.unwrap_or_default());
+
+        debug!(%namespace_name, ?field_coercion, "updating namespace field coercion rules");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_field_coercion(&namespace_name, field_coercion)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    ?field_coercion,
+                    "failed to update namespace field coercion rules",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            field_coercion = ?namespace.field_coercion,
+            "updated namespace field coercion rules",
+        );
+
+        if let Some(observer) = &self.update_observer {
+            observer.observe_update(&namespace).await;
+        }
+
+        Ok(Response::new(UpdateNamespaceFieldCoercionResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_write_rules(
+        &self,
+        request: Request<UpdateNamespaceWriteRulesRequest>,
+    ) -> Result<Response<UpdateNamespaceWriteRulesResponse>, Status> {
+        let UpdateNamespaceWriteRulesRequest {
+            name: namespace_name,
+            write_rules,
+        } = request.into_inner();
+
+        // An absent set of rules removes all rules from the namespace.
+        let write_rules = CatalogWriteRules::try_from(write_rules.unwrap_or_default())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%namespace_name, ?write_rules, "updating namespace write rules");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_write_rules(&namespace_name, write_rules)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    "failed to update namespace write rules",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            n_rules = namespace.write_rules.iter().count(),
+            "updated namespace write rules",
+        );
+
+        if let Some(observer) = &self.update_observer {
+            observer.observe_update(&namespace).await;
+        }
+
+        Ok(Response::new(UpdateNamespaceWriteRulesResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_partition_template(
+        &self,
+        request: Request<UpdateNamespacePartitionTemplateRequest>,
+    ) -> Result<Response<UpdateNamespacePartitionTemplateResponse>, Status> {
+        let UpdateNamespacePartitionTemplateRequest {
+            name: namespace_name,
+            partition_template,
+        } = request.into_inner();
+
+        // An absent template resets the namespace to the default template.
+        let partition_template = partition_template
+            .map(NamespacePartitionTemplateOverride::try_from)
+            .transpose()
+            .map_err(|e| Status::invalid_argument(e.to_string()))?
+            .unwrap_or_default();
+
+        debug!(
+            %namespace_name,
+            ?partition_template,
+            "updating namespace partition template"
+        );
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_partition_template(&namespace_name, partition_template)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    "failed to update namespace partition template",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            partition_template = ?namespace.partition_template,
+            "updated namespace partition template",
+        );
+
+        if let Some(observer) = &self.update_observer {
+            observer.observe_update(&namespace).await;
+        }
+
+        Ok(Response::new(UpdateNamespacePartitionTemplateResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_rollup_policies(
+        &self,
+        request: Request<UpdateNamespaceRollupPoliciesRequest>,
+    ) -> Result<Response<UpdateNamespaceRollupPoliciesResponse>, Status> {
+        let UpdateNamespaceRollupPoliciesRequest {
+            name: namespace_name,
+            rollup_policies,
+        } = request.into_inner();
+
+        // An absent set of policies removes all policies from the namespace.
+        let rollup_policies = CatalogRollupPolicies::try_from(rollup_policies.unwrap_or_default())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%namespace_name, ?rollup_policies, "updating namespace rollup policies");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_rollup_policies(&namespace_name, rollup_policies)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    "failed to update namespace rollup policies",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            namespace_id = %namespace.id,
+            n_policies = namespace.rollup_policies.iter().count(),
+            "updated namespace rollup policies",
+        );
+
+        Ok(Response::new(UpdateNamespaceRollupPoliciesResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_cold_tier_age(
+        &self,
+        request: Request<UpdateNamespaceColdTierAgeRequest>,
+    ) -> Result<Response<UpdateNamespaceColdTierAgeResponse>, Status> {
+        let UpdateNamespaceColdTierAgeRequest {
+            name: namespace_name,
+            cold_tier_age_ns,
+        } = request.into_inner();
+
+        let cold_tier_age_ns = map_cold_tier_age(cold_tier_age_ns)?;
+
+        debug!(%namespace_name, ?cold_tier_age_ns, "updating namespace cold tier age");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_cold_tier_age(&namespace_name, cold_tier_age_ns)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    "failed to update namespace cold tier age",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            cold_tier_age_ns,
+            namespace_id = %namespace.id,
+            "updated namespace cold tier age",
+        );
+
+        Ok(Response::new(UpdateNamespaceColdTierAgeResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
+
+    async fn update_namespace_compaction_weight(
+        &self,
+        request: Request<UpdateNamespaceCompactionWeightRequest>,
+    ) -> Result<Response<UpdateNamespaceCompactionWeightResponse>, Status> {
+        let UpdateNamespaceCompactionWeightRequest {
+            name: namespace_name,
+            compaction_weight,
+        } = request.into_inner();
+
+        if compaction_weight < 1 {
+            return Err(Status::invalid_argument(
+                "compaction weight must be at least 1",
+            ));
+        }
+
+        debug!(%namespace_name, compaction_weight, "updating namespace compaction weight");
+
+        let namespace = self
+            .catalog
+            .repositories()
+            .await
+            .namespaces()
+            .update_compaction_weight(&namespace_name, compaction_weight)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error = %e,
+                    %namespace_name,
+                    "failed to update namespace compaction weight",
+                );
+                status_from_catalog_namespace_error(e)
+            })?;
+
+        info!(
+            %namespace_name,
+            compaction_weight,
+            namespace_id = %namespace.id,
+            "updated namespace compaction weight",
+        );
+
+        Ok(Response::new(UpdateNamespaceCompactionWeightResponse {
+            namespace: Some(namespace_to_proto(&namespace)),
+        }))
+    }
 }
 
 /// Convert the namespace record from the catalog into its protobuf representation.
@@ -260,6 +609,12 @@ pub fn namespace_to_proto(namespace: &CatalogNamespace) -> Namespace {
         max_tables: namespace.max_tables.get_i32(),
         max_columns_per_table: namespace.max_columns_per_table.get_i32(),
         partition_template: namespace.partition_template.as_proto().cloned(),
+        schema_mode: SchemaMode::from(namespace.schema_mode).into(),
+        field_coercion: Some(namespace.field_coercion.into()),
+        write_rules: namespace.write_rules.as_proto().cloned(),
+        rollup_policies: namespace.rollup_policies.as_proto().cloned(),
+        cold_tier_age_ns: namespace.cold_tier_age_ns,
+        compaction_weight: namespace.compaction_weight,
     }
 }
 
@@ -280,6 +635,20 @@ fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
     }
 }
 
+/// Map a user-submitted cold tier age to the correct internal encoding.
+///
+/// 0 is always mapped to [`None`], indicating files are never moved to the
+/// cold tier.
+///
+/// Negative ages are rejected with an error.
+fn map_cold_tier_age(v: Option<i64>) -> Result<Option<i64>, Status> {
+    match v {
+        Some(0) | None => Ok(None),
+        Some(v @ 1..) => Ok(Some(v)),
+        Some(_) => Err(Status::invalid_argument("invalid negative cold tier age")),
+    }
+}
+
 fn status_from_catalog_namespace_error(err: iox_catalog::interface::Error) -> Status {
     match err {
         iox_catalog::interface::Error::NamespaceNotFoundByName { .. } => {
@@ -301,6 +670,8 @@ mod tests {
             update_namespace_service_protection_limit_request::LimitUpdate,
         },
         partition_template::v1::PartitionTemplate,
+        rollup::v1 as rollup_proto,
+        write_rules::v1 as write_rules_proto,
     };
     use iox_catalog::mem::MemCatalog;
     use tonic::Code;
@@ -469,6 +840,248 @@ mod tests {
         assert_eq!(updated_ns.max_tables, want_max_tables);
         assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
 
+        // Namespaces start out in implicit schema mode
+        assert_eq!(created_ns.schema_mode(), SchemaMode::Implicit);
+
+        // Switch the namespace to explicit schema mode
+        let updated_ns = handler
+            .update_namespace_schema_mode(Request::new(UpdateNamespaceSchemaModeRequest {
+                name: NS_NAME.to_string(),
+                schema_mode: SchemaMode::Explicit.into(),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.name, created_ns.name);
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.schema_mode(), SchemaMode::Explicit);
+        assert_eq!(updated_ns.max_columns_per_table, want_max_columns_per_table);
+
+        // Namespaces start out with no field coercion
+        assert_eq!(
+            created_ns.field_coercion,
+            Some(FieldCoercionRules::default())
+        );
+
+        // Enable some of the coercion rules
+        let want_rules = FieldCoercionRules {
+            int_to_float: true,
+            number_to_string: true,
+            ..Default::default()
+        };
+        let updated_ns = handler
+            .update_namespace_field_coercion(Request::new(UpdateNamespaceFieldCoercionRequest {
+                name: NS_NAME.to_string(),
+                field_coercion: Some(want_rules.clone()),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.field_coercion, Some(want_rules));
+        assert_eq!(updated_ns.schema_mode(), SchemaMode::Explicit);
+
+        // Namespaces start out with no write rules
+        assert_eq!(created_ns.write_rules, None);
+
+        // Invalid rules are rejected
+        let err = handler
+            .update_namespace_write_rules(Request::new(UpdateNamespaceWriteRulesRequest {
+                name: NS_NAME.to_string(),
+                write_rules: Some(write_rules_proto::WriteRules {
+                    rules: vec![write_rules_proto::WriteRule {
+                        rule: Some(write_rules_proto::write_rule::Rule::DropMeasurement(
+                            write_rules_proto::DropMeasurement {
+                                measurement_regex: "(".to_string(),
+                            },
+                        )),
+                    }],
+                }),
+            }))
+            .await
+            .expect_err("invalid regex should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Add a rule
+        let want_rules = write_rules_proto::WriteRules {
+            rules: vec![write_rules_proto::WriteRule {
+                rule: Some(write_rules_proto::write_rule::Rule::AddTag(
+                    write_rules_proto::AddTag {
+                        key: "region".to_string(),
+                        value: "eu-west".to_string(),
+                    },
+                )),
+            }],
+        };
+        let updated_ns = handler
+            .update_namespace_write_rules(Request::new(UpdateNamespaceWriteRulesRequest {
+                name: NS_NAME.to_string(),
+                write_rules: Some(want_rules.clone()),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.write_rules, Some(want_rules));
+
+        // And remove it again
+        let updated_ns = handler
+            .update_namespace_write_rules(Request::new(UpdateNamespaceWriteRulesRequest {
+                name: NS_NAME.to_string(),
+                write_rules: None,
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.write_rules, None);
+
+        // Namespaces start out with no rollup policies
+        assert_eq!(created_ns.rollup_policies, None);
+
+        // Invalid policies are rejected
+        let mut want_policies = rollup_proto::RollupPolicies {
+            policies: vec![rollup_proto::RollupPolicy {
+                source_table: "cpu".to_string(),
+                interval_seconds: 7,
+                aggregates: vec![rollup_proto::FieldAggregate {
+                    field: "usage".to_string(),
+                    aggregate: rollup_proto::Aggregate::Mean.into(),
+                }],
+                age_threshold_seconds: 86_400,
+            }],
+        };
+        let err = handler
+            .update_namespace_rollup_policies(Request::new(UpdateNamespaceRollupPoliciesRequest {
+                name: NS_NAME.to_string(),
+                rollup_policies: Some(want_policies.clone()),
+            }))
+            .await
+            .expect_err("invalid interval should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Add a policy
+        want_policies.policies[0].interval_seconds = 60;
+        let updated_ns = handler
+            .update_namespace_rollup_policies(Request::new(UpdateNamespaceRollupPoliciesRequest {
+                name: NS_NAME.to_string(),
+                rollup_policies: Some(want_policies.clone()),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.rollup_policies, Some(want_policies));
+
+        // And remove it again
+        let updated_ns = handler
+            .update_namespace_rollup_policies(Request::new(UpdateNamespaceRollupPoliciesRequest {
+                name: NS_NAME.to_string(),
+                rollup_policies: None,
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.rollup_policies, None);
+
+        // Namespaces start out never moving files to the cold tier
+        assert_eq!(created_ns.cold_tier_age_ns, None);
+
+        // Negative ages are rejected
+        let err = handler
+            .update_namespace_cold_tier_age(Request::new(UpdateNamespaceColdTierAgeRequest {
+                name: NS_NAME.to_string(),
+                cold_tier_age_ns: Some(-1),
+            }))
+            .await
+            .expect_err("negative age should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Set an age
+        let want_age = Duration::from_secs(30 * 24 * 60 * 60).as_nanos() as i64;
+        let updated_ns = handler
+            .update_namespace_cold_tier_age(Request::new(UpdateNamespaceColdTierAgeRequest {
+                name: NS_NAME.to_string(),
+                cold_tier_age_ns: Some(want_age),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.cold_tier_age_ns, Some(want_age));
+
+        // And unset it again, 0 meaning "never"
+        let updated_ns = handler
+            .update_namespace_cold_tier_age(Request::new(UpdateNamespaceColdTierAgeRequest {
+                name: NS_NAME.to_string(),
+                cold_tier_age_ns: Some(0),
+            }))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.cold_tier_age_ns, None);
+
+        // Namespaces start out with an equal share of the compactors
+        assert_eq!(created_ns.compaction_weight, 1);
+
+        // Weights below 1 are rejected
+        let err = handler
+            .update_namespace_compaction_weight(Request::new(
+                UpdateNamespaceCompactionWeightRequest {
+                    name: NS_NAME.to_string(),
+                    compaction_weight: 0,
+                },
+            ))
+            .await
+            .expect_err("zero weight should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Set a weight
+        let updated_ns = handler
+            .update_namespace_compaction_weight(Request::new(
+                UpdateNamespaceCompactionWeightRequest {
+                    name: NS_NAME.to_string(),
+                    compaction_weight: 5,
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(updated_ns.compaction_weight, 5);
+
+        // And reset it
+        let updated_ns = handler
+            .update_namespace_compaction_weight(Request::new(
+                UpdateNamespaceCompactionWeightRequest {
+                    name: NS_NAME.to_string(),
+                    compaction_weight: 1,
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.compaction_weight, 1);
+
         // Deleting the namespace should cause it to disappear
         handler
             .delete_namespace(Request::new(DeleteNamespaceRequest {
@@ -575,6 +1188,85 @@ mod tests {
         })
     }
 
+    #[tokio::test]
+    async fn test_update_namespace_partition_template() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = NamespaceService::new(Arc::clone(&catalog));
+
+        let created_ns = handler
+            .create_namespace(Request::new(CreateNamespaceRequest {
+                name: NS_NAME.to_string(),
+                retention_period_ns: None,
+                partition_template: None,
+                service_protection_limits: None,
+            }))
+            .await
+            .expect("failed to create namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(created_ns.partition_template, None);
+
+        // Invalid templates are rejected
+        let err = handler
+            .update_namespace_partition_template(Request::new(
+                UpdateNamespacePartitionTemplateRequest {
+                    name: NS_NAME.to_string(),
+                    partition_template: Some(PartitionTemplate { parts: vec![] }),
+                },
+            ))
+            .await
+            .expect_err("empty template should be rejected");
+        assert_eq!(err.code(), Code::InvalidArgument);
+
+        // Set a custom template
+        let updated_ns = handler
+            .update_namespace_partition_template(Request::new(
+                UpdateNamespacePartitionTemplateRequest {
+                    name: NS_NAME.to_string(),
+                    partition_template: Some(PARTITION_BY_DAY_PROTO.as_ref().clone()),
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.id, created_ns.id);
+        assert_eq!(
+            updated_ns.partition_template,
+            Some(PARTITION_BY_DAY_PROTO.as_ref().clone())
+        );
+
+        // And reset it to the default
+        let updated_ns = handler
+            .update_namespace_partition_template(Request::new(
+                UpdateNamespacePartitionTemplateRequest {
+                    name: NS_NAME.to_string(),
+                    partition_template: None,
+                },
+            ))
+            .await
+            .expect("failed to update namespace")
+            .into_inner()
+            .namespace
+            .expect("no namespace in response");
+        assert_eq!(updated_ns.partition_template, None);
+
+        // Updating a non-existent namespace is an error
+        let err = handler
+            .update_namespace_partition_template(Request::new(
+                UpdateNamespacePartitionTemplateRequest {
+                    name: "platanos".to_string(),
+                    partition_template: None,
+                },
+            ))
+            .await
+            .expect_err("missing namespace should be rejected");
+        assert_eq!(err.code(), Code::NotFound);
+    }
+
     #[tokio::test]
     async fn invalid_custom_namespace_template_returns_error() {
         let catalog: Arc<dyn Catalog> =
@@ -24,23 +24,51 @@ use workspace_hack as _;
 
 use std::sync::Arc;
 
-use data_types::{partition_template::TablePartitionTemplateOverride, NamespaceName};
+use data_types::{
+    partition_template::TablePartitionTemplateOverride, ColumnType, NamespaceName, Table,
+};
 use generated_types::influxdata::iox::table::v1::*;
 use iox_catalog::interface::{Catalog, SoftDeletedRows};
 use observability_deps::tracing::{debug, error, info, warn};
+use schema::TIME_COLUMN_NAME;
 use tonic::{Request, Response, Status};
 
+/// An observer of table partition template changes applied through the
+/// [`TableService`].
+///
+/// Allows the process hosting the service to propagate the new partition
+/// template (for example, into a local schema cache) without waiting for it to
+/// be loaded from the catalog.
+#[tonic::async_trait]
+pub trait TableUpdateObserver: std::fmt::Debug + Send + Sync {
+    /// Called after the partition template of `table` in `namespace` has been
+    /// successfully updated in the catalog.
+    async fn observe_update(&self, namespace: &NamespaceName<'static>, table: &Table);
+}
+
 /// Implementation of the table gRPC service
 #[derive(Debug)]
 pub struct TableService {
     /// Catalog.
     catalog: Arc<dyn Catalog>,
+
+    /// An optional observer notified of table partition template changes.
+    update_observer: Option<Arc<dyn TableUpdateObserver>>,
 }
 
 impl TableService {
     /// Create a new `TableService` instance
     pub fn new(catalog: Arc<dyn Catalog>) -> Self {
-        Self { catalog }
+        Self {
+            catalog,
+            update_observer: None,
+        }
+    }
+
+    /// Notify `observer` of every successful table partition template update.
+    pub fn with_update_observer(mut self, observer: Arc<dyn TableUpdateObserver>) -> Self {
+        self.update_observer = Some(observer);
+        self
     }
 }
 
@@ -143,6 +171,251 @@ impl table_service_server::TableService for TableService {
             table: Some(table.into()),
         }))
     }
+
+    // create a column
+    async fn create_column(
+        &self,
+        request: Request<CreateColumnRequest>,
+    ) -> Result<Response<CreateColumnResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let request = request.into_inner();
+        let column_type = ColumnType::try_from(request.column_type())
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let CreateColumnRequest {
+            namespace,
+            table,
+            name,
+            ..
+        } = request;
+
+        if column_type == ColumnType::Time || name == TIME_COLUMN_NAME {
+            return Err(Status::invalid_argument(format!(
+                "the `{TIME_COLUMN_NAME}` column is created automatically and cannot be declared"
+            )));
+        }
+
+        let namespace_name = NamespaceName::try_from(namespace)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%name, %table, %namespace_name, %column_type, "Creating column");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a namespace with name {namespace_name}"
+                ))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &table)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {table} in namespace {namespace_name}"
+                ))
+            })?;
+
+        let column = repos
+            .columns()
+            .create_or_get(&name, table.id, column_type)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, %name, table_id=%table.id, "failed to create column");
+                match e {
+                    iox_catalog::interface::Error::ColumnTypeMismatch { .. } => {
+                        Status::already_exists(e.to_string())
+                    }
+                    iox_catalog::interface::Error::ColumnCreateLimitError { .. } => {
+                        Status::resource_exhausted(e.to_string())
+                    }
+                    other => Status::internal(other.to_string()),
+                }
+            })?;
+
+        info!(
+            %name,
+            column_id = column.id.get(),
+            table_id = %table.id,
+            column_type = %column.column_type,
+            "created column"
+        );
+
+        Ok(Response::new(CreateColumnResponse {
+            column: Some(column.into()),
+        }))
+    }
+
+    // update the retention period override of a table
+    async fn update_table_retention(
+        &self,
+        request: Request<UpdateTableRetentionRequest>,
+    ) -> Result<Response<UpdateTableRetentionResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateTableRetentionRequest {
+            namespace,
+            table,
+            retention_period_ns,
+        } = request.into_inner();
+
+        let retention_period_ns = map_retention_period(retention_period_ns)?;
+
+        let namespace_name = NamespaceName::try_from(namespace)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%table, %namespace_name, ?retention_period_ns, "Updating table retention");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a namespace with name {namespace_name}"
+                ))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &table)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {table} in namespace {namespace_name}"
+                ))
+            })?;
+
+        let table = repos
+            .tables()
+            .update_retention_period(table.id, retention_period_ns)
+            .await
+            .map_err(|e| {
+                warn!(error=%e, table_id=%table.id, "failed to update table retention");
+                match e {
+                    iox_catalog::interface::Error::TableNotFound { .. } => {
+                        Status::not_found(e.to_string())
+                    }
+                    other => Status::internal(other.to_string()),
+                }
+            })?;
+
+        info!(
+            name = %table.name,
+            table_id = %table.id,
+            retention_period_ns,
+            "updated table retention"
+        );
+
+        Ok(Response::new(UpdateTableRetentionResponse {
+            table: Some(table.into()),
+        }))
+    }
+
+    // replace the partition template of a table
+    async fn update_table_partition_template(
+        &self,
+        request: Request<UpdateTablePartitionTemplateRequest>,
+    ) -> Result<Response<UpdateTablePartitionTemplateResponse>, Status> {
+        let mut repos = self.catalog.repositories().await;
+
+        let UpdateTablePartitionTemplateRequest {
+            namespace,
+            table,
+            partition_template,
+        } = request.into_inner();
+
+        let namespace_name = NamespaceName::try_from(namespace)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+
+        debug!(%table, %namespace_name, "Updating table partition template");
+
+        let namespace = repos
+            .namespaces()
+            .get_by_name(&namespace_name, SoftDeletedRows::ExcludeDeleted)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a namespace with name {namespace_name}"
+                ))
+            })?;
+
+        let table = repos
+            .tables()
+            .get_by_namespace_and_name(namespace.id, &table)
+            .await
+            .map_err(|e| Status::internal(e.to_string()))?
+            .ok_or_else(|| {
+                Status::not_found(format!(
+                    "Could not find a table with name {table} in namespace {namespace_name}"
+                ))
+            })?;
+
+        let partition_template = TablePartitionTemplateOverride::try_new(
+            partition_template,
+            &namespace.partition_template,
+        )
+        .map_err(|v| Status::invalid_argument(v.to_string()))?;
+
+        let table = repos
+            .tables()
+            .update_partition_template(table.id, partition_template)
+            .await
+            .map_err(|e| {
+                warn!(
+                    error=%e,
+                    table_id=%table.id,
+                    "failed to update table partition template"
+                );
+                match e {
+                    iox_catalog::interface::Error::TableNotFound { .. } => {
+                        Status::not_found(e.to_string())
+                    }
+                    iox_catalog::interface::Error::ColumnTypeMismatch { .. } => {
+                        Status::failed_precondition(e.to_string())
+                    }
+                    iox_catalog::interface::Error::ColumnCreateLimitError { .. } => {
+                        Status::resource_exhausted(e.to_string())
+                    }
+                    other => Status::internal(other.to_string()),
+                }
+            })?;
+
+        info!(
+            name = %table.name,
+            table_id = %table.id,
+            partition_template = ?table.partition_template,
+            "updated table partition template"
+        );
+
+        if let Some(observer) = &self.update_observer {
+            observer.observe_update(&namespace_name, &table).await;
+        }
+
+        Ok(Response::new(UpdateTablePartitionTemplateResponse {
+            table: Some(table.into()),
+        }))
+    }
+}
+
+fn map_retention_period(v: Option<i64>) -> Result<Option<i64>, Status> {
+    match v {
+        Some(0) => Ok(None),
+        Some(v @ 1..) => Ok(Some(v)),
+        Some(_v @ ..=0) => Err(Status::invalid_argument(
+            "invalid negative retention period",
+        )),
+        None => Ok(None),
+    }
 }
 
 #[cfg(test)]
@@ -150,6 +423,7 @@ mod tests {
     use data_types::{partition_template::NamespacePartitionTemplateOverride, TableId};
     use generated_types::influxdata::iox::{
         partition_template::v1::{template_part, PartitionTemplate, TemplatePart},
+        schema::v1::column_schema::ColumnType as ColumnSchemaType,
         table::v1::table_service_server::TableService as _,
     };
     use iox_catalog::{mem::MemCatalog, test_helpers::arbitrary_namespace};
@@ -466,4 +740,361 @@ mod tests {
         let all_tables = catalog.repositories().await.tables().list().await.unwrap();
         assert!(all_tables.is_empty());
     }
+
+    #[tokio::test]
+    async fn test_create_column() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let table = handler
+            .create_table(Request::new(CreateTableRequest {
+                name: "varietals".into(),
+                namespace: namespace.name.clone(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+
+        let request = CreateColumnRequest {
+            namespace: namespace.name.clone(),
+            table: "varietals".into(),
+            name: "color".into(),
+            column_type: ColumnSchemaType::Tag.into(),
+        };
+
+        let column = handler
+            .create_column(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner()
+            .column
+            .unwrap();
+        assert!(column.id > 0);
+        assert_eq!(column.name, "color");
+        assert_eq!(column.table_id, table.id);
+        assert_eq!(column.column_type(), ColumnSchemaType::Tag);
+
+        // Creating the same column again is idempotent.
+        let again = handler
+            .create_column(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner()
+            .column
+            .unwrap();
+        assert_eq!(again, column);
+
+        // Creating the column with a different type is an error.
+        let error = handler
+            .create_column(Request::new(CreateColumnRequest {
+                column_type: ColumnSchemaType::F64.into(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::AlreadyExists);
+
+        // The time column cannot be declared.
+        let error = handler
+            .create_column(Request::new(CreateColumnRequest {
+                name: "time".into(),
+                column_type: ColumnSchemaType::Time.into(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+
+        // An unspecified column type is rejected.
+        let error = handler
+            .create_column(Request::new(CreateColumnRequest {
+                column_type: ColumnSchemaType::Unspecified.into(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+
+        // The table must exist.
+        let error = handler
+            .create_column(Request::new(CreateColumnRequest {
+                table: "does_not_exist".into(),
+                ..request
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        let table_columns = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(TableId::new(table.id))
+            .await
+            .unwrap();
+        assert_eq!(table_columns.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_update_table_retention() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let table = handler
+            .create_table(Request::new(CreateTableRequest {
+                name: "varietals".into(),
+                namespace: namespace.name.clone(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(table.retention_period_ns, None);
+
+        let request = UpdateTableRetentionRequest {
+            namespace: namespace.name.clone(),
+            table: "varietals".into(),
+            retention_period_ns: Some(42),
+        };
+
+        let updated = handler
+            .update_table_retention(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.id, table.id);
+        assert_eq!(updated.retention_period_ns, Some(42));
+
+        let catalog_table = catalog
+            .repositories()
+            .await
+            .tables()
+            .get_by_id(TableId::new(table.id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(catalog_table.retention_period_ns, Some(42));
+
+        // Negative retention periods are rejected.
+        let error = handler
+            .update_table_retention(Request::new(UpdateTableRetentionRequest {
+                retention_period_ns: Some(-1),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+
+        // The table must exist.
+        let error = handler
+            .update_table_retention(Request::new(UpdateTableRetentionRequest {
+                table: "does_not_exist".into(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        // A zero retention period removes the override.
+        let updated = handler
+            .update_table_retention(Request::new(UpdateTableRetentionRequest {
+                retention_period_ns: Some(0),
+                ..request
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.retention_period_ns, None);
+    }
+
+    #[tokio::test]
+    async fn test_update_table_partition_template() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let handler = TableService::new(Arc::clone(&catalog));
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        let table = handler
+            .create_table(Request::new(CreateTableRequest {
+                name: "varietals".into(),
+                namespace: namespace.name.clone(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(table.partition_template, None);
+
+        let partition_template = PartitionTemplate {
+            parts: vec![
+                TemplatePart {
+                    part: Some(template_part::Part::TagValue("color".into())),
+                },
+                TemplatePart {
+                    part: Some(template_part::Part::TimeFormat("%Y".into())),
+                },
+            ],
+        };
+        let request = UpdateTablePartitionTemplateRequest {
+            namespace: namespace.name.clone(),
+            table: "varietals".into(),
+            partition_template: Some(partition_template.clone()),
+        };
+
+        let updated = handler
+            .update_table_partition_template(Request::new(request.clone()))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.id, table.id);
+        assert_eq!(updated.partition_template, Some(partition_template));
+
+        // The tag column used by the template is created.
+        let table_columns = catalog
+            .repositories()
+            .await
+            .columns()
+            .list_by_table_id(TableId::new(table.id))
+            .await
+            .unwrap();
+        assert_eq!(table_columns.len(), 1);
+        assert_eq!(table_columns[0].name, "color");
+        assert!(table_columns[0].is_tag());
+
+        // Empty templates are rejected.
+        let error = handler
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                partition_template: Some(PartitionTemplate { parts: vec![] }),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::InvalidArgument);
+
+        // The table must exist.
+        let error = handler
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                table: "does_not_exist".into(),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::NotFound);
+
+        // A template using an existing non-tag column is rejected.
+        catalog
+            .repositories()
+            .await
+            .columns()
+            .create_or_get("tannins", TableId::new(table.id), ColumnType::F64)
+            .await
+            .unwrap();
+        let error = handler
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                partition_template: Some(PartitionTemplate {
+                    parts: vec![TemplatePart {
+                        part: Some(template_part::Part::TagValue("tannins".into())),
+                    }],
+                }),
+                ..request.clone()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(error.code(), Code::FailedPrecondition);
+
+        // An absent template falls back to the namespace template.
+        let updated = handler
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                partition_template: None,
+                ..request
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .table
+            .unwrap();
+        assert_eq!(updated.partition_template, None);
+    }
+
+    #[derive(Debug, Default)]
+    struct MockTableUpdateObserver {
+        calls: std::sync::Mutex<Vec<(String, Table)>>,
+    }
+
+    #[tonic::async_trait]
+    impl TableUpdateObserver for MockTableUpdateObserver {
+        async fn observe_update(&self, namespace: &NamespaceName<'static>, table: &Table) {
+            self.calls
+                .lock()
+                .unwrap()
+                .push((namespace.to_string(), table.clone()));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_update_table_partition_template_observed() {
+        let catalog: Arc<dyn Catalog> =
+            Arc::new(MemCatalog::new(Arc::new(metric::Registry::default())));
+        let observer = Arc::new(MockTableUpdateObserver::default());
+        let handler = TableService::new(Arc::clone(&catalog))
+            .with_update_observer(Arc::clone(&observer) as _);
+
+        let namespace = arbitrary_namespace(&mut *catalog.repositories().await, "grapes").await;
+        handler
+            .create_table(Request::new(CreateTableRequest {
+                name: "varietals".into(),
+                namespace: namespace.name.clone(),
+                partition_template: None,
+            }))
+            .await
+            .unwrap();
+        assert!(observer.calls.lock().unwrap().is_empty());
+
+        let request = UpdateTablePartitionTemplateRequest {
+            namespace: namespace.name.clone(),
+            table: "varietals".into(),
+            partition_template: Some(PartitionTemplate {
+                parts: vec![TemplatePart {
+                    part: Some(template_part::Part::TagValue("color".into())),
+                }],
+            }),
+        };
+        handler
+            .update_table_partition_template(Request::new(request.clone()))
+            .await
+            .unwrap();
+
+        // A rejected update is not observed.
+        handler
+            .update_table_partition_template(Request::new(UpdateTablePartitionTemplateRequest {
+                table: "does_not_exist".into(),
+                ..request
+            }))
+            .await
+            .unwrap_err();
+
+        let calls = observer.calls.lock().unwrap();
+        assert_eq!(calls.len(), 1);
+        let (ns, table) = &calls[0];
+        assert_eq!(*ns, namespace.name);
+        assert_eq!(table.name, "varietals");
+        assert_eq!(table.partition_template_version, 1);
+    }
 }
@@ -0,0 +1,97 @@
+use std::time::{Duration, SystemTime};
+
+use crate::{SegmentId, SEGMENT_FILE_EXTENSION};
+
+/// The name of an archived copy of a closed segment file, encoding the time
+/// at which the segment was closed, and its [`SegmentId`].
+///
+/// Archive file names sort in the order the segments were closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ArchivedSegmentName {
+    closed_at: Duration,
+    id: SegmentId,
+}
+
+impl ArchivedSegmentName {
+    /// Construct the archive name of the segment `id`, closed at `closed_at`.
+    ///
+    /// Times before the UNIX epoch are clamped to the epoch.
+    pub fn new(closed_at: SystemTime, id: SegmentId) -> Self {
+        let closed_at = closed_at
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default();
+        Self { closed_at, id }
+    }
+
+    /// Parse an archive file name, as produced by the [`Display`]
+    /// implementation of this type.
+    ///
+    /// Returns [`None`] if `name` is not an archived segment file name.
+    ///
+    /// [`Display`]: std::fmt::Display
+    pub fn parse(name: &str) -> Option<Self> {
+        let stem = name
+            .strip_suffix(SEGMENT_FILE_EXTENSION)?
+            .strip_suffix('.')?;
+        let (closed_at, id) = stem.split_once('-')?;
+
+        Some(Self {
+            closed_at: Duration::from_nanos(closed_at.parse().ok()?),
+            id: SegmentId::new(id.parse().ok()?),
+        })
+    }
+
+    /// The time at which the segment was closed, after which no further
+    /// writes were added to it.
+    pub fn closed_at(&self) -> SystemTime {
+        SystemTime::UNIX_EPOCH + self.closed_at
+    }
+
+    /// The ID of the archived segment.
+    pub fn id(&self) -> SegmentId {
+        self.id
+    }
+}
+
+impl std::fmt::Display for ArchivedSegmentName {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Zero-pad the timestamp so that names sort chronologically.
+        write!(
+            f,
+            "{:020}-{}.{}",
+            self.closed_at.as_nanos(),
+            self.id,
+            SEGMENT_FILE_EXTENSION
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archived_segment_name_round_trip() {
+        let closed_at = SystemTime::UNIX_EPOCH + Duration::from_nanos(1_697_000_000_123_456_789);
+        let name = ArchivedSegmentName::new(closed_at, SegmentId::new(42));
+
+        let s = name.to_string();
+        assert_eq!(s, "01697000000123456789-42.dat");
+
+        let got = ArchivedSegmentName::parse(&s).unwrap();
+        assert_eq!(got, name);
+        assert_eq!(got.closed_at(), closed_at);
+        assert_eq!(got.id(), SegmentId::new(42));
+
+        // Names sort in the order the segments were closed.
+        let earlier = ArchivedSegmentName::new(
+            closed_at - Duration::from_secs(1_000_000_000),
+            SegmentId::new(100),
+        );
+        assert!(earlier.to_string() < s);
+
+        assert!(ArchivedSegmentName::parse("42.dat").is_none());
+        assert!(ArchivedSegmentName::parse("bananas-42.dat").is_none());
+        assert!(ArchivedSegmentName::parse("1-42.json").is_none());
+    }
+}
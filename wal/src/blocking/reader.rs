@@ -1,4 +1,6 @@
-use crate::{FileTypeIdentifier, SegmentEntry, SegmentIdBytes, SequencedWalOp};
+use crate::{
+    EncryptionKey, FileTypeIdentifier, SegmentEntry, SegmentId, SegmentIdBytes, SequencedWalOp,
+};
 use byteorder::{BigEndian, ReadBytesExt};
 use crc32fast::Hasher;
 use generated_types::influxdata::iox::wal::v1::WalOpBatch as ProtoWalOpBatch;
@@ -9,10 +11,25 @@ use std::{
     fs::File,
     io::{self, BufReader, Read},
     path::{Path, PathBuf},
+    sync::Arc,
 };
 
+/// The maximum number of bytes allocated up-front to read an encrypted entry,
+/// before the entry length read from the segment file is known to be valid.
+const MAX_PREALLOCATED_ENTRY_LEN: u64 = 1024 * 1024; // 1 MiB
+
 #[derive(Debug)]
-pub struct ClosedSegmentFileReader<R>(R);
+pub struct ClosedSegmentFileReader<R> {
+    inner: R,
+
+    /// The key used to decrypt entries, and the ID of the segment they were
+    /// encrypted for, if the segment is encrypted.
+    decryption: Option<(Arc<EncryptionKey>, SegmentId)>,
+
+    /// The number of entries read from the segment, which is the index of the
+    /// next entry.
+    entries_read: u64,
+}
 
 impl ClosedSegmentFileReader<BufReader<File>> {
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
@@ -28,12 +45,16 @@ where
     R: Read,
 {
     pub fn new(f: R) -> Self {
-        Self(f)
+        Self {
+            inner: f,
+            decryption: None,
+            entries_read: 0,
+        }
     }
 
     fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
         let mut data = [0u8; N];
-        self.0
+        self.inner
             .read_exact(&mut data)
             .context(UnableToReadArraySnafu { length: N })?;
         Ok(data)
@@ -43,19 +64,46 @@ where
         Ok((self.read_array()?, self.read_array()?))
     }
 
+    /// Read the ID of the key used to encrypt the segment, which immediately
+    /// follows the header of an encrypted segment.
+    pub fn read_key_id(&mut self) -> Result<u32> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    /// Decrypt all subsequently read entries using `key`, authenticating them
+    /// as belonging to the segment `segment_id`.
+    pub fn set_decryption_key(&mut self, key: Arc<EncryptionKey>, segment_id: SegmentId) {
+        self.decryption = Some((key, segment_id));
+    }
+
     fn one_entry(&mut self) -> Result<Option<SegmentEntry>> {
-        let expected_checksum = match self.0.read_u32::<BigEndian>() {
+        let expected_checksum = match self.inner.read_u32::<BigEndian>() {
             Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
             other => other.context(UnableToReadChecksumSnafu)?,
         };
 
         let expected_len = self
-            .0
+            .inner
             .read_u32::<BigEndian>()
             .context(UnableToReadLengthSnafu)?
             .into();
 
-        let compressed_read = self.0.by_ref().take(expected_len);
+        let entry_index = self.entries_read;
+        self.entries_read += 1;
+
+        if let Some((key, segment_id)) = &self.decryption {
+            return Self::one_encrypted_entry(
+                &mut self.inner,
+                key,
+                *segment_id,
+                entry_index,
+                expected_checksum,
+                expected_len,
+            )
+            .map(Some);
+        }
+
+        let compressed_read = self.inner.by_ref().take(expected_len);
         let hashing_read = CrcReader::new(compressed_read);
         let mut decompressing_read = FrameDecoder::new(hashing_read);
 
@@ -85,6 +133,55 @@ where
         Ok(Some(SegmentEntry { data }))
     }
 
+    /// Read an entry of `expected_len` encrypted bytes from `inner`, and
+    /// decrypt and decompress it, authenticating it as the entry at
+    /// `entry_index` within `segment_id`.
+    fn one_encrypted_entry(
+        inner: &mut R,
+        key: &EncryptionKey,
+        segment_id: SegmentId,
+        entry_index: u64,
+        expected_checksum: u32,
+        expected_len: u64,
+    ) -> Result<SegmentEntry> {
+        // The entry must be read in full before it can be authenticated and
+        // decrypted.
+        //
+        // The length is read from the (unauthenticated) entry header, so the
+        // buffer grows as data is read rather than being allocated up-front,
+        // bounding the allocation by the size of the segment file.
+        let mut hashing_read = CrcReader::new(inner.by_ref().take(expected_len));
+        let mut sealed = Vec::with_capacity(expected_len.min(MAX_PREALLOCATED_ENTRY_LEN) as usize);
+        hashing_read
+            .read_to_end(&mut sealed)
+            .context(UnableToReadDataSnafu)?;
+
+        let (actual_len, actual_checksum) = hashing_read.checksum();
+        if actual_len != expected_len {
+            // A truncated entry, as would be produced by a partial write.
+            return Err(io::Error::from(io::ErrorKind::UnexpectedEof))
+                .context(UnableToReadDataSnafu);
+        }
+        ensure!(
+            expected_checksum == actual_checksum,
+            ChecksumMismatchSnafu {
+                expected: expected_checksum,
+                actual: actual_checksum
+            }
+        );
+
+        let compressed = key
+            .open(segment_id, entry_index, &mut sealed)
+            .context(UnableToDecryptDataSnafu { key_id: key.id() })?;
+
+        let mut data = Vec::with_capacity(100);
+        FrameDecoder::new(compressed)
+            .read_to_end(&mut data)
+            .context(UnableToReadDataSnafu)?;
+
+        Ok(SegmentEntry { data })
+    }
+
     pub fn next_batch(&mut self) -> Result<Option<Vec<SequencedWalOp>>> {
         if let Some(entry) = self.one_entry()? {
             let decoded =
@@ -175,6 +272,10 @@ pub enum Error {
         source: snap::Error,
     },
 
+    UnableToDecryptData {
+        key_id: u32,
+    },
+
     UnableToDeserializeData {
         source: prost::DecodeError,
     },
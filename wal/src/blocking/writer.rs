@@ -1,4 +1,7 @@
-use crate::{ClosedSegment, SegmentId, WriteSummary, FILE_TYPE_IDENTIFIER};
+use crate::{
+    ClosedSegment, EncryptionKey, SegmentId, WriteSummary, ENCRYPTED_FILE_TYPE_IDENTIFIER,
+    FILE_TYPE_IDENTIFIER,
+};
 use byteorder::{BigEndian, WriteBytesExt};
 use crc32fast::Hasher;
 use snafu::prelude::*;
@@ -32,13 +35,23 @@ pub struct OpenSegmentFileWriter {
     f: File,
     bytes_written: usize,
 
+    /// The number of entries written to the segment file, which is the index
+    /// of the next entry.
+    entries_written: u64,
+
+    /// The key used to encrypt entries, if any.
+    encryption: Option<Arc<EncryptionKey>>,
+
     buffer: Vec<u8>,
 }
 
 impl OpenSegmentFileWriter {
+    /// Create a new segment file in `dir`, encrypting the entries written to
+    /// it with `encryption` if specified.
     pub fn new_in_directory(
         dir: impl Into<PathBuf>,
         next_id_source: Arc<AtomicU64>,
+        encryption: Option<Arc<EncryptionKey>>,
     ) -> Result<Self> {
         let id = SegmentId::new(next_id_source.fetch_add(1, Ordering::Relaxed));
         let path = crate::build_segment_path(dir, id);
@@ -49,23 +62,36 @@ impl OpenSegmentFileWriter {
             .open(&path)
             .context(SegmentCreateSnafu)?;
 
-        f.write_all(FILE_TYPE_IDENTIFIER)
-            .context(SegmentWriteFileTypeSnafu)?;
-        let file_type_bytes_written = FILE_TYPE_IDENTIFIER.len();
+        let file_type = match encryption {
+            Some(_) => ENCRYPTED_FILE_TYPE_IDENTIFIER,
+            None => FILE_TYPE_IDENTIFIER,
+        };
+        f.write_all(file_type).context(SegmentWriteFileTypeSnafu)?;
+        let file_type_bytes_written = file_type.len();
 
         let id_bytes = id.as_bytes();
         f.write_all(&id_bytes).context(SegmentWriteIdSnafu)?;
         let id_bytes_written = id_bytes.len();
 
+        // Encrypted segments record the ID of the key used to encrypt them.
+        let mut key_id_bytes_written = 0;
+        if let Some(key) = &encryption {
+            let key_id_bytes = key.id().to_be_bytes();
+            f.write_all(&key_id_bytes).context(SegmentWriteKeyIdSnafu)?;
+            key_id_bytes_written = key_id_bytes.len();
+        }
+
         f.sync_all().expect("fsync failure");
 
-        let bytes_written = file_type_bytes_written + id_bytes_written;
+        let bytes_written = file_type_bytes_written + id_bytes_written + key_id_bytes_written;
 
         Ok(Self {
             id,
             path,
             f,
             bytes_written,
+            entries_written: 0,
+            encryption,
             buffer: Vec::with_capacity(8 * 1204), // 8kiB initial size
         })
     }
@@ -74,7 +100,19 @@ impl OpenSegmentFileWriter {
         self.id
     }
 
+    /// Write `data` to the segment file as a single entry, and fsync it.
     pub fn write(&mut self, data: &[u8]) -> Result<WriteSummary> {
+        let summary = self.write_unsynced(data)?;
+        self.sync();
+        Ok(summary)
+    }
+
+    /// Write `data` to the segment file as a single entry, without calling
+    /// fsync.
+    ///
+    /// The entry is not durable until a subsequent call to
+    /// [`Self::sync()`] completes.
+    pub fn write_unsynced(&mut self, data: &[u8]) -> Result<WriteSummary> {
         // Ensure the write buffer is always empty before using it.
         self.buffer.clear();
         // And shrink the buffer below the maximum permitted size should the odd
@@ -94,14 +132,33 @@ impl OpenSegmentFileWriter {
             .write_u64::<BigEndian>(0)
             .expect("cannot fail to write to buffer");
 
-        // Compress the payload into the reused buffer, recording the crc hash
-        // as it is wrote.
-        let mut encoder = snap::write::FrameEncoder::new(HasherWrapper::new(&mut self.buffer));
-        encoder.write_all(data).context(UnableToCompressDataSnafu)?;
-        let (checksum, buf) = encoder
-            .into_inner()
-            .expect("cannot fail to flush to a Vec")
-            .finalize();
+        let (checksum, buf) = match &self.encryption {
+            None => {
+                // Compress the payload into the reused buffer, recording the
+                // crc hash as it is wrote.
+                let mut encoder =
+                    snap::write::FrameEncoder::new(HasherWrapper::new(&mut self.buffer));
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                encoder
+                    .into_inner()
+                    .expect("cannot fail to flush to a Vec")
+                    .finalize()
+            }
+            Some(key) => {
+                // Compress the payload, then encrypt the compressed data
+                // (which cannot be compressed further once encrypted).
+                let mut encoder = snap::write::FrameEncoder::new(Vec::new());
+                encoder.write_all(data).context(UnableToCompressDataSnafu)?;
+                let compressed = encoder.into_inner().expect("cannot fail to flush to a Vec");
+
+                // The checksum covers the encrypted bytes, as wrote to disk.
+                let mut hasher = HasherWrapper::new(&mut self.buffer);
+                hasher
+                    .write_all(&key.seal(self.id, self.entries_written, &compressed))
+                    .expect("cannot fail to write to buffer");
+                hasher.finalize()
+            }
+        };
 
         // Adjust the compressed length to take into account the u64 padding
         // above.
@@ -124,10 +181,8 @@ impl OpenSegmentFileWriter {
         let bytes_written = buf.len();
         self.f.write_all(buf).context(SegmentWriteDataSnafu)?;
 
-        // fsync the fd
-        self.f.sync_all().expect("fsync failure");
-
         self.bytes_written += bytes_written;
+        self.entries_written += 1;
 
         Ok(WriteSummary {
             total_bytes: self.bytes_written,
@@ -136,6 +191,16 @@ impl OpenSegmentFileWriter {
         })
     }
 
+    /// fsync all entries written to the segment file.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the fsync fails - the state of the written data is unknown
+    /// and cannot be safely retried.
+    pub fn sync(&mut self) {
+        self.f.sync_all().expect("fsync failure");
+    }
+
     pub fn close(self) -> Result<ClosedSegment> {
         let Self {
             id,
@@ -167,6 +232,10 @@ pub enum Error {
         source: io::Error,
     },
 
+    SegmentWriteKeyId {
+        source: io::Error,
+    },
+
     SegmentWriteChecksum {
         source: io::Error,
     },
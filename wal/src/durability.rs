@@ -0,0 +1,64 @@
+use std::time::Duration;
+
+use crate::WAL_FLUSH_INTERVAL;
+
+/// The default maximum number of bytes of writes buffered before a
+/// [`DurabilityMode::GroupCommit`] is committed early.
+pub const DEFAULT_GROUP_COMMIT_MAX_BATCH_BYTES: usize = 4 * 1024 * 1024; // 4 MiB
+
+/// The durability guarantee made by the [`Wal`] before a write is
+/// acknowledged to the caller of [`Wal::write_op()`].
+///
+/// [`Wal`]: crate::Wal
+/// [`Wal::write_op()`]: crate::Wal::write_op
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurabilityMode {
+    /// Each write is written to the segment file as its own entry and fsync'd
+    /// individually, as soon as the I/O thread is able to service it.
+    ///
+    /// This minimises write latency under light load at the cost of
+    /// throughput, as every write incurs an fsync.
+    PerWrite,
+
+    /// Writes are buffered and committed together with a single fsync, at
+    /// most every `max_latency`, or as soon as the buffered writes exceed
+    /// `max_batch_bytes`.
+    ///
+    /// Increasing the window amortises the cost of each fsync over more
+    /// writes, trading write latency for throughput.
+    GroupCommit {
+        /// The maximum duration of time a write is buffered for before it is
+        /// committed.
+        max_latency: Duration,
+        /// The estimated size of the buffered writes that causes them to be
+        /// committed before `max_latency` elapses.
+        max_batch_bytes: usize,
+    },
+
+    /// Writes are buffered and written as they are for
+    /// [`DurabilityMode::GroupCommit`] using the default window, but are NOT
+    /// fsync'd before being acknowledged.
+    ///
+    /// Acknowledged writes may be lost if the host crashes - this mode is only
+    /// suitable for test clusters.
+    Unsafe,
+}
+
+impl Default for DurabilityMode {
+    fn default() -> Self {
+        Self::GroupCommit {
+            max_latency: WAL_FLUSH_INTERVAL,
+            max_batch_bytes: DEFAULT_GROUP_COMMIT_MAX_BATCH_BYTES,
+        }
+    }
+}
+
+impl DurabilityMode {
+    /// The maximum interval of time between flushes of the write buffer.
+    pub(crate) fn flush_interval(&self) -> Duration {
+        match self {
+            Self::GroupCommit { max_latency, .. } => *max_latency,
+            Self::PerWrite | Self::Unsafe => WAL_FLUSH_INTERVAL,
+        }
+    }
+}
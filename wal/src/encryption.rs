@@ -0,0 +1,320 @@
+use std::{collections::HashMap, fmt::Debug, io, num::ParseIntError, path::Path, sync::Arc};
+
+use ring::{
+    aead::{Aad, LessSafeKey, Nonce, UnboundKey, AES_256_GCM, NONCE_LEN},
+    rand::{SecureRandom, SystemRandom},
+};
+use snafu::prelude::*;
+
+use crate::SegmentId;
+
+/// The length of an [`EncryptionKey`] in bytes.
+pub const KEY_LEN: usize = 32;
+
+/// Errors parsing WAL encryption keys.
+///
+/// These errors never include key material.
+#[derive(Debug, Snafu)]
+#[allow(missing_docs)]
+pub enum KeyringError {
+    #[snafu(display("failed to read wal encryption key file {}: {source}", path.display()))]
+    ReadKeyFile {
+        source: io::Error,
+        path: std::path::PathBuf,
+    },
+
+    #[snafu(display(
+        "invalid wal encryption key at position {position}: \
+        expected the format \"<key id>:<64 hex characters>\""
+    ))]
+    InvalidFormat { position: usize },
+
+    #[snafu(display("invalid wal encryption key id at position {position}: {source}"))]
+    InvalidKeyId {
+        source: ParseIntError,
+        position: usize,
+    },
+
+    #[snafu(display(
+        "invalid wal encryption key {id}: expected {} hex encoded bytes",
+        KEY_LEN
+    ))]
+    InvalidKey { id: u32 },
+
+    #[snafu(display("duplicate wal encryption key id {id}"))]
+    DuplicateKeyId { id: u32 },
+
+    #[snafu(display("no wal encryption keys specified"))]
+    NoKeys,
+}
+
+/// A 256-bit key used to encrypt & authenticate WAL segment entries with
+/// AES-256-GCM, identified by a numeric key ID.
+///
+/// The key ID is recorded in the header of each segment encrypted with the
+/// key, allowing the key to be rotated while segments encrypted with the old
+/// key remain readable.
+pub struct EncryptionKey {
+    id: u32,
+    key: LessSafeKey,
+}
+
+impl Debug for EncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // Never print the key material.
+        f.debug_struct("EncryptionKey")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl EncryptionKey {
+    /// Initialise a key with the given `id` from the raw `key` bytes.
+    pub fn new(id: u32, key: &[u8]) -> Result<Self, KeyringError> {
+        ensure!(key.len() == KEY_LEN, InvalidKeySnafu { id });
+
+        let key =
+            UnboundKey::new(&AES_256_GCM, key).map_err(|_| KeyringError::InvalidKey { id })?;
+
+        Ok(Self {
+            id,
+            key: LessSafeKey::new(key),
+        })
+    }
+
+    /// The ID of this key.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
+
+    /// Encrypt `data`, binding it to the entry at `entry_index` within the
+    /// segment `segment_id`, returning the ciphertext prefixed with the
+    /// randomly generated nonce used.
+    pub(crate) fn seal(&self, segment_id: SegmentId, entry_index: u64, data: &[u8]) -> Vec<u8> {
+        let mut nonce = [0; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .expect("failed to generate wal encryption nonce");
+
+        let mut out = Vec::with_capacity(NONCE_LEN + data.len() + AES_256_GCM.tag_len());
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(data);
+
+        let tag = self
+            .key
+            .seal_in_place_separate_tag(
+                Nonce::assume_unique_for_key(nonce),
+                Aad::from(aad(segment_id, entry_index)),
+                &mut out[NONCE_LEN..],
+            )
+            .expect("wal entry too large to encrypt");
+        out.extend_from_slice(tag.as_ref());
+
+        out
+    }
+
+    /// Decrypt and authenticate the output of [`Self::seal()`] in place,
+    /// returning the plaintext.
+    ///
+    /// Returns [`None`] if `data` was not encrypted with this key for the
+    /// entry at `entry_index` within `segment_id`, or has been modified.
+    pub(crate) fn open<'a>(
+        &self,
+        segment_id: SegmentId,
+        entry_index: u64,
+        data: &'a mut [u8],
+    ) -> Option<&'a [u8]> {
+        if data.len() < NONCE_LEN {
+            return None;
+        }
+        let (nonce, ciphertext) = data.split_at_mut(NONCE_LEN);
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+
+        self.key
+            .open_in_place(nonce, Aad::from(aad(segment_id, entry_index)), ciphertext)
+            .ok()
+            .map(|v| &*v)
+    }
+}
+
+/// Build the additional authenticated data for the entry at `entry_index`
+/// within `segment_id`.
+///
+/// Binding each entry to its position prevents entries from being reordered,
+/// duplicated or removed from the middle of a segment without detection, as
+/// well as being moved between segments.
+fn aad(segment_id: SegmentId, entry_index: u64) -> [u8; 16] {
+    let mut aad = [0; 16];
+    aad[..8].copy_from_slice(&segment_id.as_bytes());
+    aad[8..].copy_from_slice(&entry_index.to_be_bytes());
+    aad
+}
+
+/// A set of [`EncryptionKey`], one of which is the active key used to encrypt
+/// new segments.
+///
+/// An empty [`Keyring`] disables encryption of new segments. Segments that
+/// were not encrypted remain readable regardless of the keys in the keyring.
+///
+/// Cloning a [`Keyring`] is cheap.
+#[derive(Debug, Clone, Default)]
+pub struct Keyring {
+    active: Option<Arc<EncryptionKey>>,
+    keys: Arc<HashMap<u32, Arc<EncryptionKey>>>,
+}
+
+impl Keyring {
+    /// Construct a [`Keyring`] containing `keys`, with the first key used to
+    /// encrypt new segments.
+    pub fn new(keys: impl IntoIterator<Item = EncryptionKey>) -> Result<Self, KeyringError> {
+        let mut active = None;
+        let mut map = HashMap::new();
+
+        for key in keys {
+            let key = Arc::new(key);
+            active.get_or_insert_with(|| Arc::clone(&key));
+            ensure!(
+                !map.contains_key(&key.id()),
+                DuplicateKeyIdSnafu { id: key.id() }
+            );
+            map.insert(key.id(), key);
+        }
+
+        ensure!(active.is_some(), NoKeysSnafu);
+
+        Ok(Self {
+            active,
+            keys: Arc::new(map),
+        })
+    }
+
+    /// Parse a set of keys from `s`, separated by newlines or commas, each in
+    /// the form "<key id>:<64 hex characters>".
+    ///
+    /// The first key is used to encrypt new segments, and the rest are used
+    /// only to read segments encrypted with them. Blank lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(s: &str) -> Result<Self, KeyringError> {
+        let keys = s
+            .split(|c| c == '\n' || c == ',')
+            .map(str::trim)
+            .filter(|v| !v.is_empty() && !v.starts_with('#'))
+            .enumerate()
+            .map(|(position, entry)| {
+                let (id, key) = entry
+                    .split_once(':')
+                    .context(InvalidFormatSnafu { position })?;
+                let id = id.trim().parse().context(InvalidKeyIdSnafu { position })?;
+                let key = decode_hex(key.trim()).context(InvalidKeySnafu { id })?;
+                EncryptionKey::new(id, &key)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Self::new(keys)
+    }
+
+    /// Read and [`parse`](Self::parse) the keys in the file at `path`.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, KeyringError> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path).context(ReadKeyFileSnafu { path })?;
+        Self::parse(&s)
+    }
+
+    /// The key used to encrypt new segments, if encryption is enabled.
+    pub fn active_key(&self) -> Option<&Arc<EncryptionKey>> {
+        self.active.as_ref()
+    }
+
+    /// Return the key with the given `id`, if any.
+    pub fn get(&self, id: u32) -> Option<Arc<EncryptionKey>> {
+        self.keys.get(&id).map(Arc::clone)
+    }
+}
+
+fn decode_hex(s: &str) -> Option<Vec<u8>> {
+    if s.len() % 2 != 0 || !s.is_ascii() {
+        return None;
+    }
+
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::*;
+
+    const KEY_A: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+    const KEY_B: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff000102030405060708090a0b0c0d0e0f";
+
+    #[test]
+    fn test_parse() {
+        let keyring = Keyring::parse(&format!("# current\n2:{KEY_B}\n\n1:{KEY_A}\n")).unwrap();
+        assert_eq!(keyring.active_key().unwrap().id(), 2);
+        assert!(keyring.get(1).is_some());
+        assert!(keyring.get(2).is_some());
+        assert!(keyring.get(3).is_none());
+
+        let keyring = Keyring::parse(&format!("1:{KEY_A},2:{KEY_B}")).unwrap();
+        assert_eq!(keyring.active_key().unwrap().id(), 1);
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_matches!(Keyring::parse(""), Err(KeyringError::NoKeys));
+        assert_matches!(
+            Keyring::parse(KEY_A),
+            Err(KeyringError::InvalidFormat { position: 0 })
+        );
+        assert_matches!(
+            Keyring::parse(&format!("bananas:{KEY_A}")),
+            Err(KeyringError::InvalidKeyId { position: 0, .. })
+        );
+        assert_matches!(
+            Keyring::parse(&format!("1:{}", &KEY_A[2..])),
+            Err(KeyringError::InvalidKey { id: 1 })
+        );
+        assert_matches!(
+            Keyring::parse("1:zz"),
+            Err(KeyringError::InvalidKey { id: 1 })
+        );
+        assert_matches!(
+            Keyring::parse(&format!("1:{KEY_A}\n1:{KEY_B}")),
+            Err(KeyringError::DuplicateKeyId { id: 1 })
+        );
+
+        // Key material is never included in the error.
+        let err = Keyring::parse(&format!("1:{}", &KEY_A[2..])).unwrap_err();
+        assert!(!err.to_string().contains(&KEY_A[2..]));
+    }
+
+    #[test]
+    fn test_seal_open() {
+        let keyring = Keyring::parse(&format!("1:{KEY_A}\n2:{KEY_B}")).unwrap();
+        let key_a = keyring.get(1).unwrap();
+        let key_b = keyring.get(2).unwrap();
+        let segment_id = SegmentId::new(42);
+
+        let mut sealed = key_a.seal(segment_id, 3, b"bananas");
+        assert!(!sealed.windows(7).any(|v| v == b"bananas"));
+
+        // The wrong key, the wrong segment, or the wrong entry position fails
+        // to decrypt.
+        assert!(key_b.open(segment_id, 3, &mut sealed.clone()).is_none());
+        assert!(key_a
+            .open(SegmentId::new(24), 3, &mut sealed.clone())
+            .is_none());
+        assert!(key_a.open(segment_id, 4, &mut sealed.clone()).is_none());
+
+        // As does modified data.
+        let mut modified = sealed.clone();
+        *modified.last_mut().unwrap() ^= 1;
+        assert!(key_a.open(segment_id, 3, &mut modified).is_none());
+
+        assert_eq!(key_a.open(segment_id, 3, &mut sealed).unwrap(), b"bananas");
+    }
+}
@@ -37,16 +37,26 @@ use mutable_batch_pb::decode::decode_database_batch;
 use observability_deps::tracing::info;
 use parking_lot::Mutex;
 use snafu::prelude::*;
-use tokio::{sync::watch, task::JoinHandle};
-use writer_thread::WriterIoThreadHandle;
+use tokio::{
+    sync::{watch, Notify},
+    task::JoinHandle,
+};
+use writer_thread::{WriterIoThreadHandle, WriterMetrics};
 
 use crate::blocking::{
     ClosedSegmentFileReader as RawClosedSegmentFileReader, OpenSegmentFileWriter,
 };
 
+mod archive;
 pub mod blocking;
+mod durability;
+mod encryption;
 mod writer_thread;
 
+pub use archive::*;
+pub use durability::*;
+pub use encryption::*;
+
 const WAL_FLUSH_INTERVAL: Duration = Duration::from_millis(10);
 
 // TODO: Should have more variants / error types to avoid reusing these
@@ -141,6 +151,11 @@ pub enum Error {
     UnableToCreateSegmentFile {
         source: blocking::WriterError,
     },
+
+    /// The segment is encrypted with a key that is not in the [`Keyring`].
+    MissingEncryptionKey {
+        key_id: u32,
+    },
 }
 
 /// Errors that occur when decoding internal types from a WAL file.
@@ -202,6 +217,9 @@ pub(crate) fn build_segment_path(dir: impl Into<PathBuf>, id: SegmentId) -> Path
 // TODO: What's the expected way of upgrading -- what happens when we need version 31?
 type FileTypeIdentifier = [u8; 8];
 const FILE_TYPE_IDENTIFIER: &FileTypeIdentifier = b"INFLUXV3";
+/// The identifier of segment files with encrypted entries, which is followed
+/// by the segment ID and the ID of the [`EncryptionKey`] used.
+const ENCRYPTED_FILE_TYPE_IDENTIFIER: &FileTypeIdentifier = b"INFLUXE3";
 /// File extension for segment files.
 const SEGMENT_FILE_EXTENSION: &str = "dat";
 
@@ -221,6 +239,14 @@ pub struct Wal {
     next_id_source: Arc<AtomicU64>,
     buffer: Mutex<WalBuffer>,
 
+    /// The durability guarantee provided before writes are acknowledged.
+    mode: DurabilityMode,
+    /// The keys used to encrypt new segments, and decrypt existing segments.
+    keyring: Keyring,
+    /// Wakes the flusher task to flush the buffer before the next flush
+    /// interval.
+    flush_notify: Notify,
+
     /// The handle to the [`Wal::flush_buffer_background_task()`] task.
     flusher_task: Mutex<Option<JoinHandle<()>>>,
 }
@@ -236,6 +262,23 @@ impl Wal {
     /// Similarly, editing or deleting files within a `Wal`'s root directory via some other
     /// mechanism is not supported.
     pub async fn new(root: impl Into<PathBuf>) -> Result<Arc<Self>> {
+        Self::new_with_options(root, WalOptions::default(), &metric::Registry::default()).await
+    }
+
+    /// Creates a `Wal` instance that manages files in the specified root directory, configured
+    /// by `options`, and recording commit metrics to `metrics`.
+    ///
+    /// The same constraints as [`Wal::new()`] apply.
+    pub async fn new_with_options(
+        root: impl Into<PathBuf>,
+        options: WalOptions,
+        metrics: &metric::Registry,
+    ) -> Result<Arc<Self>> {
+        let WalOptions {
+            durability: mode,
+            keyring,
+        } = options;
+
         let root = root.into();
         info!(wal_dir=?root, "Initalizing Write Ahead Log (WAL)");
         tokio::fs::create_dir_all(&root)
@@ -291,9 +334,12 @@ impl Wal {
             .map(|id| id.get() + 1)
             .unwrap_or(0);
         let next_id_source = Arc::new(AtomicU64::new(next_id));
-        let open_segment =
-            OpenSegmentFileWriter::new_in_directory(&root, Arc::clone(&next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let open_segment = OpenSegmentFileWriter::new_in_directory(
+            &root,
+            Arc::clone(&next_id_source),
+            keyring.active_key().cloned(),
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let buffer = WalBuffer::new(None);
 
@@ -306,15 +352,21 @@ impl Wal {
             })),
             next_id_source,
             buffer: Mutex::new(buffer),
+            mode,
+            keyring,
+            flush_notify: Notify::new(),
             flusher_task: Default::default(),
         };
 
         let wal = Arc::new(wal);
         let flush_wal = Arc::clone(&wal);
+        let metrics = WriterMetrics::new(metrics);
+
+        info!(?mode, "configured WAL durability mode");
 
         // Retain the handle to the flusher task so it can be stopped later.
         *wal.flusher_task.lock() = Some(tokio::task::spawn(async move {
-            flush_wal.flush_buffer_background_task().await
+            flush_wal.flush_buffer_background_task(metrics).await
         }));
 
         Ok(wal)
@@ -329,13 +381,38 @@ impl Wal {
     /// Open a reader to a closed segment
     pub fn reader_for_segment(&self, id: SegmentId) -> Result<ClosedSegmentFileReader> {
         let path = build_segment_path(&self.root, id);
-        ClosedSegmentFileReader::from_path(path)
+        ClosedSegmentFileReader::from_path_with_keyring(path, &self.keyring)
+    }
+
+    /// The path of the segment file with the given ID.
+    pub fn segment_path(&self, id: SegmentId) -> PathBuf {
+        build_segment_path(&self.root, id)
+    }
+
+    /// The keys used by this WAL to encrypt and decrypt segments.
+    pub fn keyring(&self) -> &Keyring {
+        &self.keyring
     }
 
     /// Writes one [`SequencedWalOp`] to the buffer and returns a watch channel
-    /// for when the buffer is flushed and fsync'd to disk.
+    /// for when the buffer is flushed to disk, and fsync'd according to the
+    /// configured [`DurabilityMode`].
     pub fn write_op(&self, op: SequencedWalOp) -> watch::Receiver<Option<WriteResult>> {
         let mut b = self.buffer.lock();
+
+        match self.mode {
+            DurabilityMode::PerWrite => self.flush_notify.notify_one(),
+            DurabilityMode::GroupCommit {
+                max_batch_bytes, ..
+            } => {
+                b.bytes += op.op.encoded_len();
+                if b.bytes >= max_batch_bytes {
+                    self.flush_notify.notify_one();
+                }
+            }
+            DurabilityMode::Unsafe => {}
+        }
+
         b.ops.push(op);
         b.flush_notification.clone()
     }
@@ -344,9 +421,12 @@ impl Wal {
     /// closed segment details, including the [`SequenceNumberSet`] containing
     /// the sequence numbers of the writes within the closed segment.
     pub fn rotate(&self) -> Result<(ClosedSegment, SequenceNumberSet)> {
-        let new_open_segment =
-            OpenSegmentFileWriter::new_in_directory(&self.root, Arc::clone(&self.next_id_source))
-                .context(UnableToCreateSegmentFileSnafu)?;
+        let new_open_segment = OpenSegmentFileWriter::new_in_directory(
+            &self.root,
+            Arc::clone(&self.next_id_source),
+            self.keyring.active_key().cloned(),
+        )
+        .context(UnableToCreateSegmentFileSnafu)?;
 
         let mut segments = self.segments.lock();
 
@@ -363,7 +443,7 @@ impl Wal {
         Ok((closed, seqnum_set))
     }
 
-    async fn flush_buffer_background_task(&self) {
+    async fn flush_buffer_background_task(&self, metrics: WriterMetrics) {
         // Start a separate I/O thread to handle the serialisation, compression,
         // and actual file I/O.
         //
@@ -371,9 +451,9 @@ impl Wal {
         // which in turn would starve it of the ability to service other tasks.
         //
         // When this handle is dropped, the I/O thread is gracefully stopped.
-        let io_thread = WriterIoThreadHandle::new(Arc::clone(&self.segments));
+        let io_thread = WriterIoThreadHandle::new(Arc::clone(&self.segments), self.mode, metrics);
 
-        let mut interval = tokio::time::interval(WAL_FLUSH_INTERVAL);
+        let mut interval = tokio::time::interval(self.mode.flush_interval());
 
         // Pre-allocate the WAL buffer outside of the exclusive lock, and track
         // the buffer utilisation to optimise pre-allocation.
@@ -381,7 +461,12 @@ impl Wal {
         let mut new_buf = WalBuffer::new(size_hint);
 
         loop {
-            interval.tick().await;
+            // Flush the buffer at the configured interval, or earlier if a
+            // writer requests it.
+            tokio::select! {
+                _ = interval.tick() => {},
+                _ = self.flush_notify.notified() => interval.reset(),
+            }
 
             // Rust's move properties ensure we never accidentally reuse a
             // buffer, but make it clear the buffer is always fresh before use.
@@ -443,6 +528,18 @@ impl std::fmt::Debug for Wal {
     }
 }
 
+/// Configuration options for a [`Wal`].
+#[derive(Debug, Clone, Default)]
+pub struct WalOptions {
+    /// The durability guarantee provided before a write is acknowledged.
+    pub durability: DurabilityMode,
+
+    /// The keys used to encrypt segment entries at rest.
+    ///
+    /// When the keyring is empty, new segments are not encrypted.
+    pub keyring: Keyring,
+}
+
 #[derive(Debug)]
 struct Segments {
     closed_segments: BTreeMap<SegmentId, ClosedSegment>,
@@ -453,6 +550,9 @@ struct Segments {
 #[derive(Debug)]
 struct WalBuffer {
     ops: Vec<SequencedWalOp>,
+    /// The estimated encoded size of `ops`, maintained only when group
+    /// committing.
+    bytes: usize,
     notify_flush: tokio::sync::watch::Sender<Option<WriteResult>>,
     flush_notification: tokio::sync::watch::Receiver<Option<WriteResult>>,
 }
@@ -463,6 +563,7 @@ impl WalBuffer {
 
         Self {
             ops: Vec::with_capacity(size_hint.unwrap_or(20)),
+            bytes: 0,
             notify_flush: tx,
             flush_notification: rx,
         }
@@ -568,20 +669,37 @@ impl ClosedSegmentFileReader {
     }
 
     /// Open the segment file and read its header, ensuring it is a segment file and reading its id.
+    ///
+    /// Encrypted segment files cannot be opened - see [`Self::from_path_with_keyring()`].
     pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_path_with_keyring(path, &Keyring::default())
+    }
+
+    /// Open the segment file and read its header, ensuring it is a segment file and reading its id.
+    ///
+    /// If the segment file is encrypted, the key it was encrypted with is obtained from
+    /// `keyring` and used to decrypt its entries.
+    pub fn from_path_with_keyring(path: impl AsRef<Path>, keyring: &Keyring) -> Result<Self> {
         let path = path.as_ref();
         let mut file =
             RawClosedSegmentFileReader::from_path(path).context(UnableToOpenFileSnafu { path })?;
 
         let (file_type, id) = file.read_header().context(UnableToReadFileHeaderSnafu)?;
-
-        ensure!(
-            &file_type == FILE_TYPE_IDENTIFIER,
-            SegmentFileIdentifierMismatchSnafu,
-        );
-
         let id = SegmentId::from_bytes(id);
 
+        if &file_type == ENCRYPTED_FILE_TYPE_IDENTIFIER {
+            let key_id = file.read_key_id().context(UnableToReadFileHeaderSnafu)?;
+            let key = keyring
+                .get(key_id)
+                .context(MissingEncryptionKeySnafu { key_id })?;
+            file.set_decryption_key(key, id);
+        } else {
+            ensure!(
+                &file_type == FILE_TYPE_IDENTIFIER,
+                SegmentFileIdentifierMismatchSnafu,
+            );
+        }
+
         Ok(Self { id, file })
     }
 }
@@ -671,6 +789,11 @@ impl ClosedSegment {
     pub fn size(&self) -> u64 {
         self.size
     }
+
+    /// The path of the segment file on disk.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
 }
 
 #[cfg(test)]
@@ -769,6 +892,257 @@ mod tests {
         );
     }
 
+    /// Write two ops with the specified durability `mode`, returning the
+    /// number of entries they were written as and the number of fsync calls
+    /// made.
+    async fn write_with_durability(mode: DurabilityMode) -> (usize, u64) {
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metrics = metric::Registry::default();
+        let options = WalOptions {
+            durability: mode,
+            ..Default::default()
+        };
+        let wal = Wal::new_with_options(dir.path(), options, &metrics)
+            .await
+            .unwrap();
+
+        let op1 = SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), 0)].into_iter().collect(),
+            op: WalOp::Write(test_data("m1,t=foo v=1i 1")),
+        };
+        let op2 = SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), 1)].into_iter().collect(),
+            op: WalOp::Write(test_data("m1,t=foo v=2i 2")),
+        };
+
+        wal.write_op(op1.clone());
+        let res = wal.write_op(op2.clone()).changed().await;
+        assert!(res.is_ok());
+
+        let (closed, _ids) = wal.rotate().unwrap();
+        let entries = wal
+            .reader_for_segment(closed.id)
+            .expect("should be able to open reader for closed WAL segment")
+            .map(|batch| batch.expect("failed to read WAL op batch"))
+            .collect::<Vec<_>>();
+
+        // All modes write the ops, in order.
+        let ops = entries.iter().flatten().cloned().collect::<Vec<_>>();
+        assert_eq!(ops, [op1, op2]);
+
+        let fsyncs = metrics
+            .get_instrument::<metric::Metric<metric::DurationHistogram>>("wal_fsync_duration")
+            .expect("failed to read metric")
+            .get_observer(&metric::Attributes::from([]))
+            .expect("failed to get observer")
+            .fetch()
+            .sample_count();
+
+        (entries.len(), fsyncs)
+    }
+
+    #[tokio::test]
+    async fn wal_durability_per_write() {
+        let (entries, fsyncs) = write_with_durability(DurabilityMode::PerWrite).await;
+
+        // Each write is its own entry, and fsync'd individually.
+        assert_eq!(entries, 2);
+        assert_eq!(fsyncs, 2);
+    }
+
+    #[tokio::test]
+    async fn wal_durability_group_commit() {
+        // A window long enough that the writes are committed together.
+        let (entries, fsyncs) = write_with_durability(DurabilityMode::GroupCommit {
+            max_latency: Duration::from_millis(100),
+            max_batch_bytes: usize::MAX,
+        })
+        .await;
+
+        assert_eq!(entries, 1);
+        assert_eq!(fsyncs, 1);
+    }
+
+    #[tokio::test]
+    async fn wal_durability_group_commit_max_batch_bytes() {
+        // A window that will not elapse during the test, so the writes are
+        // committed by exceeding the batch size.
+        let (entries, fsyncs) = write_with_durability(DurabilityMode::GroupCommit {
+            max_latency: Duration::from_secs(3600),
+            max_batch_bytes: 1,
+        })
+        .await;
+
+        assert!(entries >= 1);
+        assert_eq!(fsyncs as usize, entries);
+    }
+
+    #[tokio::test]
+    async fn wal_durability_unsafe() {
+        let (entries, fsyncs) = write_with_durability(DurabilityMode::Unsafe).await;
+
+        assert_eq!(entries, 1);
+        assert_eq!(fsyncs, 0);
+    }
+
+    #[tokio::test]
+    async fn wal_encryption_round_trip() {
+        const KEY_A: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+        const KEY_B: &str = "f0f1f2f3f4f5f6f7f8f9fafbfcfdfeff000102030405060708090a0b0c0d0e0f";
+
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metrics = metric::Registry::default();
+        let open = |keys: &str| {
+            let options = WalOptions {
+                keyring: Keyring::parse(keys).unwrap(),
+                ..Default::default()
+            };
+            Wal::new_with_options(dir.path(), options, &metrics)
+        };
+
+        let op = |lp: &str| SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), 0)].into_iter().collect(),
+            op: WalOp::Write(test_data(lp)),
+        };
+        let op1 = op("bananas,city=Madrid v=1i 1");
+        let op2 = op("bananas,city=London v=2i 2");
+
+        // Write a segment encrypted with key 1.
+        let wal = open(&format!("1:{KEY_A}")).await.unwrap();
+        wal.write_op(op1.clone()).changed().await.unwrap();
+        let (closed_a, _) = wal.rotate().unwrap();
+        drop(wal);
+
+        // The plaintext is not present in the segment file.
+        let data = std::fs::read(closed_a.path()).unwrap();
+        assert_eq!(&data[..8], ENCRYPTED_FILE_TYPE_IDENTIFIER);
+        assert!(!data.windows(6).any(|v| v == b"Madrid"));
+
+        // Rotate the active key to key 2, retaining key 1 to read the existing
+        // segment.
+        let wal = open(&format!("2:{KEY_B}\n1:{KEY_A}")).await.unwrap();
+        wal.write_op(op2.clone()).changed().await.unwrap();
+        let (closed_b, _) = wal.rotate().unwrap();
+
+        let read = |id| {
+            wal.reader_for_segment(id)
+                .unwrap()
+                .flat_map(|batch| batch.expect("failed to read WAL op batch"))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(read(closed_a.id()), [op1]);
+        assert_eq!(read(closed_b.id()), [op2]);
+
+        // Segments cannot be read without their key.
+        assert_matches!(
+            ClosedSegmentFileReader::from_path(closed_a.path()),
+            Err(Error::MissingEncryptionKey { key_id: 1 })
+        );
+        assert_matches!(
+            ClosedSegmentFileReader::from_path_with_keyring(
+                closed_b.path(),
+                &Keyring::parse(&format!("1:{KEY_A}")).unwrap()
+            ),
+            Err(Error::MissingEncryptionKey { key_id: 2 })
+        );
+
+        // Or with the wrong key material for the key ID.
+        let mut reader = ClosedSegmentFileReader::from_path_with_keyring(
+            closed_a.path(),
+            &Keyring::parse(&format!("1:{KEY_B}")).unwrap(),
+        )
+        .unwrap();
+        assert_matches!(
+            reader.next(),
+            Some(Err(Error::UnableToReadNextOps {
+                source: blocking::ReaderError::UnableToDecryptData { key_id: 1 }
+            }))
+        );
+    }
+
+    #[tokio::test]
+    async fn wal_encryption_entry_position() {
+        const KEY: &str = "000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f";
+
+        let dir = test_helpers::tmp_dir().unwrap();
+        let metrics = metric::Registry::default();
+        let keyring = Keyring::parse(&format!("1:{KEY}")).unwrap();
+        let options = WalOptions {
+            keyring: keyring.clone(),
+            ..Default::default()
+        };
+        let wal = Wal::new_with_options(dir.path(), options, &metrics)
+            .await
+            .unwrap();
+
+        let op = |lp: &str| SequencedWalOp {
+            table_write_sequence_numbers: vec![(TableId::new(0), 0)].into_iter().collect(),
+            op: WalOp::Write(test_data(lp)),
+        };
+        wal.write_op(op("bananas,city=Madrid v=1i 1"))
+            .changed()
+            .await
+            .unwrap();
+        wal.write_op(op("bananas,city=London v=2i 2"))
+            .changed()
+            .await
+            .unwrap();
+        let (closed, _) = wal.rotate().unwrap();
+        drop(wal);
+
+        // Split the segment into the header (file type, segment ID & key ID)
+        // and the individual entries.
+        let data = std::fs::read(closed.path()).unwrap();
+        let (header, mut rest) = data.split_at(8 + 8 + 4);
+        let mut entries = vec![];
+        while !rest.is_empty() {
+            let len = u32::from_be_bytes(rest[4..8].try_into().unwrap()) as usize;
+            let (entry, tail) = rest.split_at(8 + len);
+            entries.push(entry);
+            rest = tail;
+        }
+        assert_eq!(entries.len(), 2);
+
+        let read = |data: Vec<u8>| {
+            let path = dir.path().join("tampered");
+            std::fs::write(&path, data).unwrap();
+            ClosedSegmentFileReader::from_path_with_keyring(&path, &keyring)
+                .unwrap()
+                .next()
+        };
+
+        // Reordered entries fail to decrypt.
+        assert_matches!(
+            read([header, entries[1], entries[0]].concat()),
+            Some(Err(Error::UnableToReadNextOps {
+                source: blocking::ReaderError::UnableToDecryptData { key_id: 1 }
+            }))
+        );
+
+        // As does an entry with a preceding entry removed.
+        assert_matches!(
+            read([header, entries[1]].concat()),
+            Some(Err(Error::UnableToReadNextOps {
+                source: blocking::ReaderError::UnableToDecryptData { key_id: 1 }
+            }))
+        );
+
+        // An entry header specifying a huge length does not cause the length
+        // to be allocated, and is treated as a truncated entry.
+        let mut huge = header.to_vec();
+        huge.extend_from_slice(&0_u32.to_be_bytes());
+        huge.extend_from_slice(&u32::MAX.to_be_bytes());
+        huge.extend_from_slice(b"platanos");
+        assert_matches!(
+            read(huge),
+            Some(Err(Error::UnableToReadNextOps {
+                source: blocking::ReaderError::UnableToReadData { source }
+            })) => {
+                assert_eq!(source.kind(), std::io::ErrorKind::UnexpectedEof);
+            }
+        );
+    }
+
     // open wal with files that aren't segments (should log and skip)
 
     // read segment works even if last entry is truncated
@@ -1,13 +1,17 @@
-use std::{sync::Arc, thread::JoinHandle};
+use std::{sync::Arc, thread::JoinHandle, time::Instant};
 
 use data_types::{sequence_number_set::SequenceNumberSet, SequenceNumber};
 use generated_types::influxdata::iox::wal::v1 as proto;
+use metric::{DurationHistogram, U64Histogram, U64HistogramOptions};
 use observability_deps::tracing::{debug, error};
 use parking_lot::Mutex;
 use prost::Message;
 use tokio::sync::mpsc;
 
-use crate::{Segments, WalBuffer, WriteResult};
+use crate::{
+    blocking::{OpenSegmentFileWriter, WriterError},
+    DurabilityMode, Segments, WalBuffer, WriteResult, WriteSummary,
+};
 
 /// The number of [`WalBuffer`] that may be enqueued for persistence.
 const FLUSH_QUEUE_DEPTH: usize = 1;
@@ -32,8 +36,12 @@ pub(crate) struct WriterIoThreadHandle {
 
 impl WriterIoThreadHandle {
     /// Spawn an I/O writer thread, flushing batches to the open segment in
-    /// `segments`.
-    pub(crate) fn new(segments: Arc<Mutex<Segments>>) -> Self {
+    /// `segments` with the durability guarantee specified by `mode`.
+    pub(crate) fn new(
+        segments: Arc<Mutex<Segments>>,
+        mode: DurabilityMode,
+        metrics: WriterMetrics,
+    ) -> Self {
         let (batch_tx, batch_rx) = mpsc::channel(FLUSH_QUEUE_DEPTH);
 
         // Spawn the I/O thread and retain a handle to wait for shutdown when
@@ -41,7 +49,7 @@ impl WriterIoThreadHandle {
         let join_handle = std::thread::Builder::new()
             .name("WAL writer I/O thread".to_string())
             .spawn(move || {
-                let writer = WriterIoThread::new(batch_rx, segments);
+                let writer = WriterIoThread::new(batch_rx, segments, mode, metrics);
                 writer.run();
             })
             .expect("failed to spawn WAL I/O thread");
@@ -85,17 +93,82 @@ impl Drop for WriterIoThreadHandle {
     }
 }
 
+/// Metrics recorded by the I/O thread for each committed batch.
+#[derive(Debug)]
+pub(crate) struct WriterMetrics {
+    batch_ops: U64Histogram,
+    batch_bytes: U64Histogram,
+    fsync_duration: DurationHistogram,
+}
+
+impl WriterMetrics {
+    pub(crate) fn new(metrics: &metric::Registry) -> Self {
+        let batch_ops = metrics
+            .register_metric_with_options::<U64Histogram, _>(
+                "wal_commit_batch_ops",
+                "distribution of the number of write operations committed in a single batch",
+                || U64HistogramOptions::new([1, 4, 16, 64, 256, 1024, 4096, 16384, u64::MAX]),
+            )
+            .recorder(&[]);
+        let batch_bytes = metrics
+            .register_metric_with_options::<U64Histogram, _>(
+                "wal_commit_batch_bytes",
+                "distribution of the number of bytes written to the WAL in a single batch",
+                || {
+                    U64HistogramOptions::new([
+                        4_u64.pow(5),  // 1 kibibyte
+                        4_u64.pow(6),  // 4 kibibytes
+                        4_u64.pow(7),  // 16 kibibytes
+                        4_u64.pow(8),  // 64 kibibytes
+                        4_u64.pow(9),  // 256 kibibytes
+                        4_u64.pow(10), // 1 mebibyte
+                        4_u64.pow(11), // 4 mebibytes
+                        4_u64.pow(12), // 16 mebibytes
+                        4_u64.pow(13), // 64 mebibytes
+                        u64::MAX,
+                    ])
+                },
+            )
+            .recorder(&[]);
+        let fsync_duration = metrics
+            .register_metric::<DurationHistogram>(
+                "wal_fsync_duration",
+                "distribution of the duration of WAL segment fsync calls",
+            )
+            .recorder(&[]);
+
+        Self {
+            batch_ops,
+            batch_bytes,
+            fsync_duration,
+        }
+    }
+}
+
 /// The state of the I/O actor thread.
 struct WriterIoThread {
     /// A channel to receive batches to flush.
     batch_rx: mpsc::Receiver<WalBuffer>,
     /// The set of segments, used to obtain the current open segment handle.
     segments: Arc<Mutex<Segments>>,
+    /// The durability guarantee to provide before notifying writers.
+    mode: DurabilityMode,
+    metrics: WriterMetrics,
 }
 
 impl WriterIoThread {
-    fn new(batch_rx: mpsc::Receiver<WalBuffer>, segments: Arc<Mutex<Segments>>) -> Self {
-        Self { batch_rx, segments }
+    fn new(
+        batch_rx: mpsc::Receiver<WalBuffer>,
+        segments: Arc<Mutex<Segments>>,
+        mode: DurabilityMode,
+        metrics: WriterMetrics,
+    ) -> Self {
+        Self {
+            batch_rx,
+            segments,
+            mode,
+            metrics,
+        }
     }
 
     fn run(mut self) {
@@ -104,8 +177,6 @@ impl WriterIoThread {
         let mut proto_data = Vec::with_capacity(4 * 1024);
 
         loop {
-            proto_data.clear();
-
             let batch = match self.batch_rx.blocking_recv() {
                 Some(batch) => batch,
                 None => {
@@ -130,19 +201,27 @@ impl WriterIoThread {
                     (proto::SequencedWalOp::from(v), op_ids)
                 })
                 .unzip();
-            let proto_batch = proto::WalOpBatch { ops };
 
-            // Generate the binary protobuf message, storing it into proto_data
-            proto_batch
-                .encode(&mut proto_data)
-                .expect("encoding batch into vec cannot fail");
+            self.metrics.batch_ops.record(ops.len() as _);
+
+            // Split the batch into the entries to be written to the segment
+            // file - in per-write mode, each write is its own entry.
+            let entries = match self.mode {
+                DurabilityMode::PerWrite => ops
+                    .into_iter()
+                    .map(|op| proto::WalOpBatch { ops: vec![op] })
+                    .collect::<Vec<_>>(),
+                DurabilityMode::GroupCommit { .. } | DurabilityMode::Unsafe => {
+                    vec![proto::WalOpBatch { ops }]
+                }
+            };
 
             // Obtain the segments lock - this prevents concurrent rotation, but
             // has no impact on concurrent writers.
             {
                 // Write the serialised data to the current open segment file.
                 let mut segments = self.segments.lock();
-                match segments.open_segment.write(&proto_data) {
+                match self.write_entries(&mut segments.open_segment, &entries, &mut proto_data) {
                     Ok(summary) => {
                         // Broadcast the result to all writers to this batch.
                         //
@@ -166,4 +245,54 @@ impl WriterIoThread {
             };
         }
     }
+
+    /// Write `entries` to `segment`, calling fsync as required by the
+    /// configured [`DurabilityMode`].
+    ///
+    /// Returns a [`WriteSummary`] covering all the entries written.
+    fn write_entries(
+        &self,
+        segment: &mut OpenSegmentFileWriter,
+        entries: &[proto::WalOpBatch],
+        proto_data: &mut Vec<u8>,
+    ) -> Result<WriteSummary, WriterError> {
+        let mut summary: Option<WriteSummary> = None;
+
+        for entry in entries {
+            // Generate the binary protobuf message, storing it into proto_data
+            proto_data.clear();
+            entry
+                .encode(proto_data)
+                .expect("encoding batch into vec cannot fail");
+
+            let s = segment.write_unsynced(proto_data)?;
+            if self.mode == DurabilityMode::PerWrite {
+                self.sync(segment);
+            }
+
+            summary = Some(match summary {
+                None => s,
+                Some(acc) => WriteSummary {
+                    bytes_written: acc.bytes_written + s.bytes_written,
+                    ..s
+                },
+            });
+        }
+
+        if matches!(self.mode, DurabilityMode::GroupCommit { .. }) {
+            self.sync(segment);
+        }
+
+        let summary = summary.expect("flushed batch must contain at least one entry");
+        self.metrics.batch_bytes.record(summary.bytes_written as _);
+
+        Ok(summary)
+    }
+
+    /// fsync `segment`, recording the latency of the call.
+    fn sync(&self, segment: &mut OpenSegmentFileWriter) {
+        let t = Instant::now();
+        segment.sync();
+        self.metrics.fsync_duration.record(t.elapsed());
+    }
 }
@@ -232,6 +232,7 @@ fn test_data(lp: &str) -> DatabaseBatch {
                 table_id,
                 mut columns,
                 row_count,
+                partition_template,
             } = table_batch;
 
             columns.sort_by(|a, b| a.column_name.cmp(&b.column_name));
@@ -240,6 +241,7 @@ fn test_data(lp: &str) -> DatabaseBatch {
                 table_id,
                 columns,
                 row_count,
+                partition_template,
             }
         })
         .collect();